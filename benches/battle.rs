@@ -0,0 +1,137 @@
+use std::collections::HashMap;
+use std::time::Instant;
+
+use criterion::{criterion_group, criterion_main, BatchSize, Criterion};
+use gero::combat::CombatEncounter;
+use gero::frontend::tiles::terrain_sprite_id;
+use gero::frontend::Renderer;
+use gero::grid::{GridMap, TerrainType};
+use gero::models::{Faction, Position, Unit, UnitType, Weapon, WeaponTier};
+use gero::rng::GameRng;
+use gero::simulation::run_round;
+use gero::state::GameState;
+
+const SIZE: usize = 128;
+/// Units per side.
+const SQUAD: usize = 100;
+/// What a full AI round of the 200-unit battle should stay under in a
+/// release build, so large skirmishes don't stall between turns. The first
+/// round is the slowest: every unit paths across most of the map.
+const ROUND_BUDGET_MS: f64 = 75.0;
+/// Frames in each unit's idle animation.
+const ANIMATION_FRAMES: usize = 4;
+/// One unit in this many steps its animation each rendered frame.
+const ANIMATED_PER_FRAME: usize = 20;
+
+/// A 128x128 map with a scattering of ruined walls to path around.
+fn battlefield() -> GridMap {
+    let mut map = GridMap::new(SIZE, SIZE);
+    for x in (8..SIZE - 8).step_by(12) {
+        for y in (4..SIZE - 4).filter(|y| y % 16 < 10) {
+            map.set_terrain(&Position { x, y }, TerrainType::Blocked);
+        }
+    }
+    map
+}
+
+fn rifle() -> Weapon {
+    Weapon {
+        id: "rifle".into(),
+        name: "Rifle".into(),
+        tier: WeaponTier::Basic,
+        damage: 3,
+        accuracy: 0.7,
+        range: 6,
+        armor_piercing: None,
+        action_point_cost: 1,
+        critical_chance: 0.05,
+        abilities_granted: Vec::new(),
+        damage_type: Default::default(),
+        forced_move: None,
+        indirect: None,
+    }
+}
+
+/// A squad two columns deep, facing the other across the map.
+fn squad(prefix: &str, unit_type: UnitType, faction: Faction, x: impl Fn(usize) -> usize) -> Vec<Unit> {
+    (0..SQUAD)
+        .map(|i| {
+            let mut unit = Unit::new(&format!("{prefix}{i}"), prefix, unit_type.clone(), faction.clone());
+            unit.base_stats.agility = 6;
+            unit.recalculate_stats();
+            unit.equip_weapon(rifle());
+            unit.grid_position = Position { x: x(i / 50), y: 14 + (i % 50) * 2 };
+            unit
+        })
+        .collect()
+}
+
+fn large_battle() -> CombatEncounter {
+    let players = squad("guard", UnitType::Guardsman, Faction::Imperial, |row| 20 + row);
+    let enemies = squad("ork", UnitType::OrkBoy, Faction::Ork, |row| SIZE - 21 - row);
+    CombatEncounter::new(players, enemies, battlefield(), None)
+}
+
+/// A full HD view with every terrain and unit sprite loaded.
+fn battle_renderer(state: &GameState) -> Renderer<'static> {
+    let mut renderer = Renderer::new_headless(1920, 1080);
+    for terrain in [TerrainType::Normal, TerrainType::Blocked] {
+        renderer.load_sprite_from_bytes(terrain_sprite_id(&terrain), vec![vec![0; 4]]);
+    }
+    for unit in state.units.iter() {
+        renderer.load_sprite_from_bytes(&unit.sprite_id, vec![vec![0; 4]; ANIMATION_FRAMES]);
+    }
+    renderer
+}
+
+/// Step the animation of a few units, as a frame of an idle battle would.
+/// Returns the next frame number.
+fn animate(state: &mut GameState, frame: usize) -> usize {
+    for unit in state.units.iter_mut().skip(frame % ANIMATED_PER_FRAME).step_by(ANIMATED_PER_FRAME) {
+        unit.animation_state.frame_index += 1;
+    }
+    frame + 1
+}
+
+fn ai_round(c: &mut Criterion) {
+    let encounter = large_battle();
+    let mut group = c.benchmark_group("large_battle");
+    group.sample_size(10);
+    group.bench_function("ai_round_200_units", |b| {
+        b.iter_batched(
+            || encounter.clone(),
+            |mut encounter| run_round(&mut encounter, &mut GameRng::new(1), &mut HashMap::new()),
+            BatchSize::LargeInput,
+        )
+    });
+    let state = GameState::from_encounter(&encounter);
+    group.bench_function("render_200_units", |b| {
+        let (mut renderer, mut state, mut frame) = (battle_renderer(&state), state.clone(), 0);
+        b.iter(|| {
+            frame = animate(&mut state, frame);
+            renderer.render_state(&state)
+        })
+    });
+    group.bench_function("render_200_units_retained", |b| {
+        let (mut renderer, mut state, mut frame) = (battle_renderer(&state), state.clone(), 0);
+        b.iter(|| {
+            frame = animate(&mut state, frame);
+            renderer.render_state_retained(&state)
+        })
+    });
+    group.finish();
+
+    let best = (0..5)
+        .map(|_| {
+            let mut encounter = encounter.clone();
+            let start = Instant::now();
+            run_round(&mut encounter, &mut GameRng::new(1), &mut HashMap::new());
+            start.elapsed().as_secs_f64() * 1000.0
+        })
+        .fold(f64::INFINITY, f64::min);
+    let verdict = if best <= ROUND_BUDGET_MS { "within" } else { "OVER" };
+    println!("AI round: {best:.1} ms, {verdict} the {ROUND_BUDGET_MS} ms budget");
+}
+
+criterion_group!(benches, ai_round);
+criterion_main!(benches);
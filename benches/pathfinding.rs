@@ -0,0 +1,61 @@
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use gero::grid::{try_move_in, FlowFieldCache, GridMap, TerrainType};
+use gero::models::{Faction, MovementType, Position, Unit, UnitType};
+use gero::weather::Weather;
+
+const SIZE: usize = 100;
+
+/// A 100x100 map with staggered walls every tenth column, each with a gap,
+/// so paths have to weave.
+fn walled_map() -> GridMap {
+    let mut map = GridMap::new(SIZE, SIZE);
+    for x in (10..SIZE).step_by(10) {
+        let gap = if (x / 10) % 2 == 0 { 5 } else { SIZE - 5 };
+        for y in (0..SIZE).filter(|y| y.abs_diff(gap) > 1) {
+            map.set_terrain(&Position { x, y }, TerrainType::Blocked);
+        }
+    }
+    map
+}
+
+fn squad(count: usize) -> Vec<Unit> {
+    (0..count)
+        .map(|i| {
+            let mut unit = Unit::new(&format!("u{i}"), "U", UnitType::OrkBoy, Faction::Ork);
+            unit.current_stats.agility = 1000;
+            unit.grid_position = Position { x: SIZE - 1, y: (i * 7) % SIZE };
+            unit
+        })
+        .collect()
+}
+
+fn pathfinding(c: &mut Criterion) {
+    let map = walled_map();
+    let target = Position { x: 0, y: SIZE / 2 };
+    let mut group = c.benchmark_group("ai_units_to_one_target");
+    group.sample_size(10);
+    for count in [10, 50] {
+        let units = squad(count);
+        group.bench_with_input(BenchmarkId::new("astar_per_unit", count), &units, |b, units| {
+            b.iter(|| {
+                for unit in units {
+                    let mut unit = unit.clone();
+                    try_move_in(&mut unit, target.clone(), &map, 0, Weather::Clear);
+                }
+            })
+        });
+        group.bench_with_input(BenchmarkId::new("shared_flow_field", count), &units, |b, units| {
+            b.iter(|| {
+                let mut cache = FlowFieldCache::default();
+                for unit in units {
+                    let field = cache.get(&map, &target, MovementType::Ground, Weather::Clear);
+                    field.advance(&map, &unit.grid_position, u32::MAX);
+                }
+            })
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, pathfinding);
+criterion_main!(benches);
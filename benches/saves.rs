@@ -0,0 +1,47 @@
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use gero::campaign::Campaign;
+use gero::models::{Faction, Unit, UnitType};
+use gero::saves::{self, SaveFormat};
+
+/// A late campaign: a large roster with long histories of flags and items.
+fn big_campaign() -> Campaign {
+    let mut campaign = Campaign::new();
+    for i in 0..500 {
+        campaign.roster.push(Unit::new(&format!("unit_{i}"), "Trooper", UnitType::Guardsman, Faction::Imperial));
+        campaign.set_flag(&format!("mission_{i}_complete"));
+        campaign.grant_item("lasgun");
+    }
+    campaign
+}
+
+fn formats() -> Vec<SaveFormat> {
+    let mut formats = vec![SaveFormat::Json, SaveFormat::Binary];
+    if cfg!(feature = "compression") {
+        formats.push(SaveFormat::Compressed);
+    }
+    formats
+}
+
+fn saves(c: &mut Criterion) {
+    let campaign = big_campaign();
+    for format in formats() {
+        let size = saves::encode(&campaign, format).unwrap().len();
+        println!("{format:?}: {size} bytes");
+    }
+
+    let mut group = c.benchmark_group("campaign_save");
+    group.sample_size(20);
+    for format in formats() {
+        group.bench_with_input(BenchmarkId::new("encode", format!("{format:?}")), &campaign, |b, campaign| {
+            b.iter(|| saves::encode(campaign, format).unwrap())
+        });
+        let data = saves::encode(&campaign, format).unwrap();
+        group.bench_with_input(BenchmarkId::new("decode", format!("{format:?}")), &data, |b, data| {
+            b.iter(|| saves::decode::<Campaign>(data).unwrap())
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, saves);
+criterion_main!(benches);
@@ -0,0 +1,230 @@
+use std::fmt;
+
+use crate::combat::CombatEvent;
+use crate::localization::Localizer;
+use crate::models::Unit;
+use crate::registry::UnitRegistry;
+use crate::ui::{UiManager, UiTab};
+
+/// How urgently a screen reader should speak an announcement.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Priority {
+    /// Spoken after whatever is being read now, e.g. focus changes.
+    Polite,
+    /// Interrupts, e.g. a unit going down or a turn hand-off.
+    Assertive,
+}
+
+/// One line of plain text for a screen reader, already localized.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Announcement {
+    pub text: String,
+    pub priority: Priority,
+}
+
+type Listener = Box<dyn FnMut(&Announcement) + Send>;
+
+/// Turns UI focus changes and combat events into plain-text announcements
+/// so blind players can follow the battle. Each announcement goes to every
+/// registered listener, e.g. [`system_speech`] or a platform accessibility
+/// bridge, and is kept in `history`.
+pub struct Announcer {
+    pub enabled: bool,
+    /// Everything announced, oldest first. Useful in tests.
+    pub history: Vec<Announcement>,
+    listeners: Vec<Listener>,
+    /// Tab and button last announced, so only changes are spoken.
+    focus: Option<(UiTab, usize)>,
+    /// Id of the selected unit last announced.
+    selected: Option<String>,
+}
+
+impl fmt::Debug for Announcer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Announcer")
+            .field("enabled", &self.enabled)
+            .field("history", &self.history)
+            .field("listeners", &self.listeners.len())
+            .finish()
+    }
+}
+
+impl Default for Announcer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Announcer {
+    pub fn new() -> Self {
+        Self { enabled: true, history: Vec::new(), listeners: Vec::new(), focus: None, selected: None }
+    }
+
+    /// Call `listener` with every future announcement.
+    pub fn on_announce(&mut self, listener: impl FnMut(&Announcement) + Send + 'static) {
+        self.listeners.push(Box::new(listener));
+    }
+
+    pub fn announce(&mut self, text: impl Into<String>, priority: Priority) {
+        if !self.enabled {
+            return;
+        }
+        let announcement = Announcement { text: text.into(), priority };
+        for listener in &mut self.listeners {
+            listener(&announcement);
+        }
+        self.history.push(announcement);
+    }
+
+    /// Announce the focused button if the tab or selection changed since
+    /// the last call, and the selected unit if a different one was picked.
+    pub fn observe(&mut self, ui: &UiManager, units: &UnitRegistry, loc: &Localizer) {
+        let focus = (ui.current_tab, ui.selected_index);
+        if self.focus != Some(focus) {
+            self.focus = Some(focus);
+            let buttons = match ui.current_tab {
+                UiTab::Abilities => &ui.ability_buttons,
+                UiTab::Inventory => &ui.inventory_buttons,
+            };
+            let text = match buttons.get(ui.selected_index) {
+                Some(button) => loc.format(
+                    "announce.focus",
+                    &[
+                        ("tab", &ui.current_tab.label(loc)),
+                        ("item", &button.id),
+                        ("index", &(ui.selected_index + 1).to_string()),
+                        ("count", &buttons.len().to_string()),
+                    ],
+                ),
+                None => loc.format("announce.tab", &[("tab", &ui.current_tab.label(loc))]),
+            };
+            self.announce(text, Priority::Polite);
+        }
+
+        let selected = units.iter().find(|u| u.is_selected);
+        if self.selected.as_deref() != selected.map(|u| u.id.as_str()) {
+            self.selected = selected.map(|u| u.id.clone());
+            if let Some(unit) = selected {
+                self.announce_selection(unit, loc);
+            }
+        }
+    }
+
+    /// Name and health of a newly selected unit.
+    pub fn announce_selection(&mut self, unit: &Unit, loc: &Localizer) {
+        let text = loc.format(
+            "announce.selected",
+            &[
+                ("unit", &unit.name),
+                ("hp", &loc.get("ui.hp")),
+                ("current", &unit.health_points.to_string()),
+                ("max", &unit.current_stats.max_health.to_string()),
+            ],
+        );
+        self.announce(text, Priority::Polite);
+    }
+
+    /// Whose turn it is now.
+    pub fn announce_turn(&mut self, unit: &Unit, loc: &Localizer) {
+        self.announce(loc.format("announce.turn", &[("unit", &unit.name)]), Priority::Assertive);
+    }
+
+    /// Describe the result of a combat event. Units are looked up in
+    /// `units` by id for their names; a unit left with no health is also
+    /// announced as down.
+    pub fn announce_combat_event(&mut self, event: &CombatEvent, units: &UnitRegistry, loc: &Localizer) {
+        let name = |id: &str| units.by_name(id).map(|u| u.name.clone()).unwrap_or_else(|| id.to_string());
+        match event {
+            CombatEvent::UnitDamaged { unit_id, amount, critical } => {
+                let key = if *critical { "announce.critical" } else { "announce.damage" };
+                let unit = name(unit_id);
+                self.announce(loc.format(key, &[("unit", &unit), ("amount", &amount.to_string())]), Priority::Polite);
+                if units.by_name(unit_id).is_some_and(|u| u.health_points <= 0) {
+                    self.announce(loc.format("announce.down", &[("unit", &unit)]), Priority::Assertive);
+                }
+            }
+            CombatEvent::UnitHealed { unit_id, amount } => {
+                let text = loc.format("announce.heal", &[("unit", &name(unit_id)), ("amount", &amount.to_string())]);
+                self.announce(text, Priority::Polite);
+            }
+            CombatEvent::TurnHandedOff { to, .. } => {
+                let text = loc.format("announce.handoff", &[("player", &(to + 1).to_string())]);
+                self.announce(text, Priority::Assertive);
+            }
+            CombatEvent::Explosion { cells, .. } => {
+                let hit = units.iter().filter(|u| cells.contains(&u.grid_position)).count();
+                self.announce(loc.plural("announce.explosion", hit as i64, &[]), Priority::Polite);
+            }
+            CombatEvent::ShellImpact { attacker_id, cells, .. } => {
+                let hit = units.iter().filter(|u| cells.contains(&u.grid_position)).count();
+                let text = loc.plural("announce.shell", hit as i64, &[("unit", &name(attacker_id))]);
+                self.announce(text, Priority::Polite);
+            }
+            CombatEvent::ObjectDestroyed { .. } => self.announce(loc.get("announce.destroyed"), Priority::Polite),
+            CombatEvent::LootPickedUp { unit_id, loot } => {
+                let text = loc.plural("announce.loot", loot.len() as i64, &[("unit", &name(unit_id))]);
+                self.announce(text, Priority::Polite);
+            }
+            CombatEvent::WeaponJammed { unit_id } => {
+                self.announce(loc.format("announce.jammed", &[("unit", &name(unit_id))]), Priority::Polite);
+            }
+            CombatEvent::UnitSuppressed { unit_id } => {
+                self.announce(loc.format("announce.suppressed", &[("unit", &name(unit_id))]), Priority::Polite);
+            }
+            CombatEvent::UnitPinned { unit_id } => {
+                self.announce(loc.format("announce.pinned", &[("unit", &name(unit_id))]), Priority::Assertive);
+            }
+            CombatEvent::OverwatchTriggered { unit_id, target_id } => {
+                let text = loc.format("announce.overwatch", &[("unit", &name(unit_id)), ("target", &name(target_id))]);
+                self.announce(text, Priority::Assertive);
+            }
+            CombatEvent::HeldActionTriggered { unit_id, target_id } => {
+                let text = loc.format("announce.held", &[("unit", &name(unit_id)), ("target", &name(target_id))]);
+                self.announce(text, Priority::Assertive);
+            }
+            CombatEvent::ChannelInterrupted { unit_id, .. } => {
+                self.announce(loc.format("announce.interrupted", &[("unit", &name(unit_id))]), Priority::Polite);
+            }
+            CombatEvent::UnitReacted { unit_id } => {
+                self.announce(loc.format("announce.reacted", &[("unit", &name(unit_id))]), Priority::Polite);
+            }
+            CombatEvent::UnitDisplaced { unit_id, .. } => {
+                self.announce(loc.format("announce.displaced", &[("unit", &name(unit_id))]), Priority::Polite);
+            }
+            CombatEvent::RegionEntered { .. } | CombatEvent::RegionExited { .. } | CombatEvent::UnitAttacked { .. } => {}
+        }
+    }
+}
+
+/// Speak `announcement` with the platform's text-to-speech command: `say`
+/// on macOS, `System.Speech` through PowerShell on Windows and
+/// speech-dispatcher's `spd-say` elsewhere. Register it with
+/// [`Announcer::on_announce`]. Failures are ignored so a missing TTS
+/// engine never stops the game.
+#[cfg(feature = "tts")]
+pub fn system_speech(announcement: &Announcement) {
+    use std::process::Command;
+
+    let text = &announcement.text;
+    let mut command = if cfg!(target_os = "macos") {
+        let mut c = Command::new("say");
+        c.arg(text);
+        c
+    } else if cfg!(windows) {
+        let script = format!(
+            "Add-Type -AssemblyName System.Speech; (New-Object System.Speech.Synthesis.SpeechSynthesizer).Speak('{}')",
+            text.replace('\'', "''")
+        );
+        let mut c = Command::new("powershell");
+        c.args(["-NoProfile", "-Command", &script]);
+        c
+    } else {
+        let mut c = Command::new("spd-say");
+        if announcement.priority == Priority::Assertive {
+            c.args(["--priority", "important"]);
+        }
+        c.arg(text);
+        c
+    };
+    let _ = command.spawn();
+}
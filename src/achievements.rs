@@ -0,0 +1,160 @@
+use std::collections::BTreeSet;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::combat::{CombatEncounter, CombatEvent};
+use crate::models::UnitType;
+use crate::registry::Side;
+use crate::simulation::BattleOutcome;
+
+/// Achievement definitions shipped with the game.
+pub const ACHIEVEMENTS_PATH: &str = "assets/achievements.json";
+/// Where unlocked achievements are kept between sessions.
+pub const PROFILE_PATH: &str = "profile.json";
+
+/// What a player has to do to earn an achievement.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Condition {
+    /// Win a battle with every player unit still standing.
+    NoCasualties,
+    /// Finish off a `victim` unit on a `killer` unit's turn.
+    Kill { killer: UnitType, victim: UnitType },
+    /// Win a battle within this many rounds.
+    WinWithinRounds(u32),
+}
+
+/// One entry of the achievements list. `name` and `description` are
+/// locale keys.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Achievement {
+    pub id: String,
+    pub name: String,
+    pub description: String,
+    pub condition: Condition,
+}
+
+/// Read the list of achievements from a JSON array.
+pub fn load_achievements<P: AsRef<Path>>(path: P) -> io::Result<Vec<Achievement>> {
+    let data = fs::read_to_string(path)?;
+    serde_json::from_str(&data).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+}
+
+/// Achievements the player has earned, across every campaign.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AchievementProfile {
+    pub unlocked: BTreeSet<String>,
+}
+
+impl AchievementProfile {
+    pub fn load<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        let data = fs::read_to_string(path)?;
+        serde_json::from_str(&data).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+    }
+
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        let data = serde_json::to_string_pretty(self).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        fs::write(path, data)
+    }
+}
+
+/// Checks the achievements list against combat events as they are drained
+/// and against the encounter's stats when a battle ends. Newly unlocked
+/// achievements are added to the profile, which is saved straight away if
+/// it has a path.
+#[derive(Debug, Clone, Default)]
+pub struct AchievementTracker {
+    pub achievements: Vec<Achievement>,
+    pub profile: AchievementProfile,
+    pub profile_path: Option<PathBuf>,
+}
+
+impl AchievementTracker {
+    pub fn new(achievements: Vec<Achievement>, profile: AchievementProfile) -> Self {
+        Self { achievements, profile, profile_path: None }
+    }
+
+    /// Load the definitions and the player's profile. A missing profile
+    /// starts empty and is created on the first unlock.
+    pub fn load<P: AsRef<Path>, Q: AsRef<Path>>(achievements: P, profile: Q) -> io::Result<Self> {
+        let unlocked = match AchievementProfile::load(&profile) {
+            Err(e) if e.kind() == io::ErrorKind::NotFound => AchievementProfile::default(),
+            result => result?,
+        };
+        Ok(Self { profile_path: Some(profile.as_ref().to_path_buf()), ..Self::new(load_achievements(achievements)?, unlocked) })
+    }
+
+    pub fn is_unlocked(&self, id: &str) -> bool {
+        self.profile.unlocked.contains(id)
+    }
+
+    /// Check `events`, just drained from `encounter`, for kills. The unit
+    /// whose turn it is gets the credit.
+    pub fn observe(&mut self, events: &[CombatEvent], encounter: &CombatEncounter) -> Vec<Achievement> {
+        let Some(killer) = encounter.turn_order.current_unit_id.as_deref().and_then(|id| encounter.unit_by_id(id)) else {
+            return Vec::new();
+        };
+        let victims: Vec<UnitType> = events
+            .iter()
+            .filter_map(|e| match e {
+                CombatEvent::UnitDamaged { unit_id, amount, .. } if *unit_id != killer.id => {
+                    let unit = encounter.unit_by_id(unit_id)?;
+                    // Only the blow that took it from alive to down.
+                    (unit.health_points <= 0 && unit.health_points + amount > 0).then(|| unit.unit_type.clone())
+                }
+                _ => None,
+            })
+            .collect();
+        self.unlock_where(|condition| match condition {
+            Condition::Kill { killer: k, victim } => *k == killer.unit_type && victims.contains(victim),
+            _ => false,
+        })
+    }
+
+    /// Check the end-of-battle achievements for a battle that finished
+    /// with `outcome`.
+    pub fn finish_battle(&mut self, encounter: &CombatEncounter, outcome: BattleOutcome) -> Vec<Achievement> {
+        let won = outcome == BattleOutcome::PlayerVictory;
+        let no_casualties = encounter.units.on_side(Side::Player).all(|u| u.health_points > 0);
+        let rounds = encounter.stats.rounds;
+        self.unlock_where(|condition| match condition {
+            Condition::NoCasualties => won && no_casualties,
+            Condition::WinWithinRounds(limit) => won && rounds <= *limit,
+            Condition::Kill { .. } => false,
+        })
+    }
+
+    /// Unlock every locked achievement whose condition `met` accepts.
+    fn unlock_where(&mut self, met: impl Fn(&Condition) -> bool) -> Vec<Achievement> {
+        let unlocked: Vec<Achievement> = self
+            .achievements
+            .iter()
+            .filter(|a| !self.profile.unlocked.contains(&a.id) && met(&a.condition))
+            .cloned()
+            .collect();
+        if unlocked.is_empty() {
+            return unlocked;
+        }
+        self.profile.unlocked.extend(unlocked.iter().map(|a| a.id.clone()));
+        if let Some(path) = &self.profile_path {
+            // Losing an unlock to a write error beats stopping the game.
+            let _ = self.profile.save(path);
+        }
+        unlocked
+    }
+
+    /// Take the profile back to `profile`, dropping what was unlocked
+    /// since, as when a battle is retried. Saved like an unlock.
+    pub fn restore(&mut self, profile: AchievementProfile) {
+        if profile == self.profile {
+            return;
+        }
+        self.profile = profile;
+        if let Some(path) = &self.profile_path {
+            let _ = self.profile.save(path);
+        }
+    }
+}
@@ -0,0 +1,242 @@
+use std::collections::VecDeque;
+
+use crate::combat::{CombatEncounter, CombatError, CombatEvent};
+use crate::command::Command;
+use crate::grid::find_path;
+use crate::interrupts::{InterruptEvent, CAMERA_PAN_SECONDS};
+use crate::models::{AnimationType, Position};
+
+/// Seconds a unit takes to walk from one cell to the next.
+pub const WALK_STEP_SECONDS: f32 = 0.15;
+/// Seconds an attack or ability animation plays.
+pub const ACTION_ANIMATION_SECONDS: f32 = 0.5;
+/// Seconds damage and healing numbers stay up before the next action.
+pub const FLOATING_TEXT_SECONDS: f32 = 0.6;
+/// Seconds the cinematic camera takes to close in on a kill or critical.
+pub const CINEMATIC_SECONDS: f32 = 0.4;
+/// Speed the steps of a cinematic action play at, for slow motion.
+pub const SLOW_MOTION_SCALE: f32 = 0.4;
+
+/// Something shown to the player after an action has been resolved.
+#[derive(Debug, Clone, PartialEq)]
+pub enum PresentationStep {
+    /// The unit walks along `path`, one cell at a time.
+    Walk { unit_id: String, path: Vec<Position> },
+    /// The unit plays `animation` in place.
+    Animate { unit_id: String, animation: AnimationType },
+    /// Health changes shown over the units, damage negative.
+    FloatingText { changes: Vec<(String, i32)> },
+    /// A scripted event injected mid-turn; see [`crate::interrupts`].
+    Interrupt(InterruptEvent),
+    /// The cinematic camera closes in on `target`, where a unit was killed
+    /// or critically hit. The action's steps then play in slow motion.
+    Cinematic { target: Position },
+}
+
+impl PresentationStep {
+    /// How long the step holds up the next action.
+    pub fn seconds(&self) -> f32 {
+        match self {
+            PresentationStep::Walk { path, .. } => WALK_STEP_SECONDS * path.len().saturating_sub(1) as f32,
+            PresentationStep::Animate { .. } => ACTION_ANIMATION_SECONDS,
+            PresentationStep::FloatingText { .. } => FLOATING_TEXT_SECONDS,
+            PresentationStep::Cinematic { .. } => CINEMATIC_SECONDS,
+            // Dialogue waits for the player; see [`CombatEncounter::resume_actions`].
+            PresentationStep::Interrupt(InterruptEvent::Dialogue { .. }) => f32::INFINITY,
+            PresentationStep::Interrupt(InterruptEvent::CameraPan { .. }) => CAMERA_PAN_SECONDS,
+            PresentationStep::Interrupt(InterruptEvent::Reinforcement { .. }) => ACTION_ANIMATION_SECONDS,
+        }
+    }
+
+    /// For a walk, the cell reached after `progress` (0 to 1) of the step.
+    pub fn walk_position(&self, progress: f32) -> Option<&Position> {
+        let PresentationStep::Walk { path, .. } = self else { return None };
+        let last = path.len().checked_sub(1)?;
+        path.get(((progress.clamp(0.0, 1.0) * last as f32).floor() as usize).min(last))
+    }
+}
+
+/// Commands waiting to be resolved and the presentation of the last one.
+/// The encounter stays authoritative: each command is applied in full when
+/// it leaves the queue, and its steps only replay it at a human pace. The
+/// next command waits until they have played.
+#[derive(Debug, Clone, Default)]
+pub struct ActionQueue {
+    pending: VecDeque<Command>,
+    steps: VecDeque<PresentationStep>,
+    /// Seconds the front step has been playing.
+    elapsed: f32,
+    /// Whether the front step has started.
+    begun: bool,
+    /// Play kills and critical hits through the cinematic camera.
+    pub cinematic: bool,
+    /// Steps left to play in slow motion, counting the front one.
+    slow_steps: usize,
+    /// Queued commands the encounter refused, with the reason; drained by
+    /// whoever shows errors.
+    pub rejected: Vec<(Command, CombatError)>,
+}
+
+impl ActionQueue {
+    /// The step playing now and how far through it is, from 0 to 1.
+    pub fn current(&self) -> Option<(&PresentationStep, f32)> {
+        let step = self.steps.front()?;
+        let seconds = step.seconds();
+        Some((step, if seconds > 0.0 { (self.elapsed / seconds).min(1.0) } else { 1.0 }))
+    }
+
+    /// Whether the step playing now belongs to a cinematic action.
+    pub fn in_slow_motion(&self) -> bool {
+        self.slow_steps > 0
+    }
+
+    /// Whether commands or steps are still waiting.
+    pub fn is_busy(&self) -> bool {
+        !self.pending.is_empty() || !self.steps.is_empty()
+    }
+
+    /// Commands still waiting to be resolved.
+    pub fn len(&self) -> usize {
+        self.pending.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.pending.is_empty()
+    }
+
+    /// Queue `steps` after those already waiting.
+    pub(crate) fn push_steps(&mut self, steps: impl IntoIterator<Item = PresentationStep>) {
+        self.steps.extend(steps);
+    }
+}
+
+impl CombatEncounter {
+    /// Queue `command` to be resolved once everything queued before it has
+    /// been shown; see [`CombatEncounter::update_actions`].
+    pub fn queue_action(&mut self, command: Command) {
+        self.actions.pending.push_back(command);
+    }
+
+    /// Play the presentation for `dt` seconds. Whenever no step is left the
+    /// next queued command is executed, any interrupts it triggered are
+    /// shown behind its steps, and the steps begin. Steps in slow motion
+    /// take [`SLOW_MOTION_SCALE`] of `dt`. Returns the steps started during
+    /// the call, so the caller can spawn floating text, sounds or dialogue.
+    pub fn update_actions(&mut self, mut dt: f32) -> Vec<PresentationStep> {
+        let mut started = Vec::new();
+        loop {
+            let Some(step) = self.actions.steps.front().cloned() else {
+                let Some(command) = self.actions.pending.pop_front() else { break };
+                let steps = self.resolve_action(command);
+                // Interrupts the command set off were queued as it ran;
+                // its own steps play first.
+                for step in steps.into_iter().rev() {
+                    self.actions.steps.push_front(step);
+                }
+                continue;
+            };
+            if !self.actions.begun {
+                self.actions.begun = true;
+                self.actions.elapsed = 0.0;
+                started.push(step.clone());
+            }
+            let scale = if self.actions.in_slow_motion() { SLOW_MOTION_SCALE } else { 1.0 };
+            let left = (step.seconds() - self.actions.elapsed) / scale;
+            if dt < left {
+                self.actions.elapsed += dt * scale;
+                break;
+            }
+            dt -= left.max(0.0);
+            self.end_step();
+        }
+        started
+    }
+
+    /// Dismiss the dialogue interrupt holding the turn, letting queued
+    /// steps and commands carry on. Returns false when no dialogue is up.
+    pub fn resume_actions(&mut self) -> bool {
+        let holding = self.actions.begun
+            && matches!(self.actions.steps.front(), Some(PresentationStep::Interrupt(InterruptEvent::Dialogue { .. })));
+        if holding {
+            self.end_step();
+        }
+        holding
+    }
+
+    fn end_step(&mut self) {
+        self.actions.elapsed = 0.0;
+        self.actions.begun = false;
+        self.actions.slow_steps = self.actions.slow_steps.saturating_sub(1);
+        if let Some(PresentationStep::Walk { unit_id, .. } | PresentationStep::Animate { unit_id, .. }) =
+            self.actions.steps.pop_front()
+            && let Some(unit) = self.unit_by_id_mut(&unit_id)
+            && unit.animation_state.current_animation != AnimationType::Death
+        {
+            unit.animation_state.current_animation = AnimationType::Idle;
+        }
+    }
+
+    /// Execute `command`, returning the steps that show what happened.
+    fn resolve_action(&mut self, command: Command) -> Vec<PresentationStep> {
+        let path = match &command {
+            Command::Move { unit_id, destination } => self
+                .unit_by_id(unit_id)
+                .map(|u| find_path(u, destination, &self.battlefield, self.movement_bonus(u), self.weather).path)
+                .unwrap_or_default(),
+            _ => Vec::new(),
+        };
+        let events_before = self.events.len();
+        if let Err(reason) = self.execute(command.clone()) {
+            self.actions.rejected.push((command, reason));
+            return Vec::new();
+        }
+
+        let mut steps = Vec::new();
+        match &command {
+            Command::Move { unit_id, .. } => {
+                if let Some(unit) = self.unit_by_id_mut(unit_id) {
+                    unit.animation_state.current_animation = AnimationType::Move;
+                }
+                steps.push(PresentationStep::Walk { unit_id: unit_id.clone(), path });
+            }
+            Command::EndTurn | Command::Delay => {}
+            _ => {
+                if let Some(unit) = command.actor().and_then(|id| self.unit_by_id(id)) {
+                    let animation = match unit.animation_state.current_animation {
+                        AnimationType::Idle | AnimationType::Move => AnimationType::Attack,
+                        ref other => other.clone(),
+                    };
+                    steps.push(PresentationStep::Animate { unit_id: unit.id.clone(), animation });
+                }
+            }
+        }
+        let changes: Vec<(String, i32)> = self.events[events_before..]
+            .iter()
+            .filter_map(|e| match e {
+                CombatEvent::UnitDamaged { unit_id, amount, .. } => Some((unit_id.clone(), -amount)),
+                CombatEvent::UnitHealed { unit_id, amount } => Some((unit_id.clone(), *amount)),
+                _ => None,
+            })
+            .collect();
+        if !changes.is_empty() {
+            steps.push(PresentationStep::FloatingText { changes });
+        }
+        if self.actions.cinematic
+            && let Some(target) = self.cinematic_target(events_before)
+        {
+            steps.insert(0, PresentationStep::Cinematic { target });
+            self.actions.slow_steps = steps.len();
+        }
+        steps
+    }
+
+    /// Where the events since `events_before` killed a unit or critically
+    /// hit one, if they did.
+    fn cinematic_target(&self, events_before: usize) -> Option<Position> {
+        self.events[events_before..].iter().find_map(|e| {
+            let CombatEvent::UnitDamaged { unit_id, critical, .. } = e else { return None };
+            let unit = self.unit_by_id(unit_id)?;
+            (*critical || unit.health_points <= 0).then(|| unit.grid_position.clone())
+        })
+    }
+}
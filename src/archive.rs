@@ -0,0 +1,131 @@
+use std::collections::HashSet;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::campaign::Campaign;
+use crate::content::ContentDatabase;
+use crate::input::KeyBindings;
+use crate::ioutil::invalid;
+use crate::saves::{self, SaveFormat};
+use crate::state::GameState;
+use crate::ui::options::AccessibilitySettings;
+
+/// Version written into new archives. Archives from a newer version are
+/// refused; older ones are read with missing fields defaulted.
+pub const ARCHIVE_VERSION: u32 = 1;
+
+/// A named battle save stored alongside the campaign.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SaveSlot {
+    pub name: String,
+    pub state: GameState,
+}
+
+/// Everything a player's progress needs on another machine, in one file:
+/// the campaign, its battle saves, options and key bindings.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CampaignArchive {
+    /// Must stay the first field so [`CampaignArchive::import`] can read it
+    /// before the rest.
+    pub version: u32,
+    pub campaign: Campaign,
+    #[serde(default)]
+    pub saves: Vec<SaveSlot>,
+    #[serde(default)]
+    pub options: AccessibilitySettings,
+    #[serde(default)]
+    pub keybindings: KeyBindings,
+}
+
+/// Leading fields of an archive, decoded on their own to check the version.
+#[derive(Deserialize)]
+struct VersionProbe {
+    version: u32,
+}
+
+impl CampaignArchive {
+    pub fn new(campaign: Campaign, options: AccessibilitySettings, keybindings: KeyBindings) -> Self {
+        Self { version: ARCHIVE_VERSION, campaign, saves: Vec::new(), options, keybindings }
+    }
+
+    /// Add a battle save, replacing any slot with the same name.
+    pub fn add_save(&mut self, name: &str, state: GameState) {
+        self.saves.retain(|s| s.name != name);
+        self.saves.push(SaveSlot { name: name.to_string(), state });
+    }
+
+    /// The battle save named `name`, to load. Iron-man campaigns only
+    /// resume from their own autosave, so their slots can't be loaded.
+    pub fn load_save(&self, name: &str) -> Result<&GameState, &'static str> {
+        if self.campaign.ironman {
+            return Err("iron-man campaigns can't load saves");
+        }
+        self.saves.iter().find(|s| s.name == name).map(|s| &s.state).ok_or("unknown save")
+    }
+
+    /// Encode the archive as a single blob in `format`.
+    pub fn export(&self, format: SaveFormat) -> io::Result<Vec<u8>> {
+        saves::encode(self, format)
+    }
+
+    pub fn export_to<P: AsRef<Path>>(&self, path: P, format: SaveFormat) -> io::Result<()> {
+        fs::write(path, self.export(format)?)
+    }
+
+    /// Decode an archive in any save format and check it against `db`
+    /// with [`CampaignArchive::validate`].
+    pub fn import(data: &[u8], db: &ContentDatabase) -> io::Result<Self> {
+        let probe: VersionProbe = saves::decode(data)?;
+        if probe.version > ARCHIVE_VERSION {
+            return Err(invalid(format!("archive version {} is newer than supported {}", probe.version, ARCHIVE_VERSION)));
+        }
+        let mut archive: CampaignArchive = saves::decode(data)?;
+        archive.validate(db)?;
+        archive.version = ARCHIVE_VERSION;
+        Ok(archive)
+    }
+
+    pub fn import_from<P: AsRef<Path>>(path: P, db: &ContentDatabase) -> io::Result<Self> {
+        Self::import(&fs::read(path)?, db)
+    }
+
+    /// Check that every item the campaign holds or equips exists in `db`,
+    /// roster unit ids are unique, and each battle save's turn order only
+    /// names units in that save.
+    pub fn validate(&self, db: &ContentDatabase) -> io::Result<()> {
+        let campaign = &self.campaign;
+        if let Some(id) = campaign.inventory.iter().find(|id| db.get(id).is_none()) {
+            return Err(invalid(format!("unknown item '{}' in inventory", id)));
+        }
+        let mut seen = HashSet::new();
+        for unit in &campaign.roster {
+            if !seen.insert(unit.id.as_str()) {
+                return Err(invalid(format!("duplicate unit '{}' in roster", unit.id)));
+            }
+            let mut equipped = unit
+                .equipment
+                .weapon
+                .as_ref()
+                .map(|w| w.id.as_str())
+                .into_iter()
+                .chain(unit.equipment.armor.as_ref().map(|a| a.id.as_str()));
+            if let Some(id) = equipped.find(|id| db.get(id).is_none()) {
+                return Err(invalid(format!("unit '{}' equips unknown item '{}'", unit.id, id)));
+            }
+            if unit.equipment.accessory_slots.iter().any(|a| db.accessory_id(a).is_none()) {
+                return Err(invalid(format!("unit '{}' carries an unknown accessory", unit.id)));
+            }
+        }
+        for slot in &self.saves {
+            let state = &slot.state;
+            let mut queued = state.turn_queue.initiative.iter().chain(&state.turn_queue.current_unit_id);
+            if let Some(id) = queued.find(|id| state.units.id(id).is_none()) {
+                return Err(invalid(format!("save '{}' references missing unit '{}'", slot.name, id)));
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,123 @@
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+use std::sync::RwLock;
+
+pub mod streaming;
+#[cfg(target_arch = "wasm32")]
+mod web;
+#[cfg(target_arch = "wasm32")]
+pub use web::WebAssets;
+
+/// Every file under `assets/`, one path per entry. Browsers can't list
+/// directories, so the browser build fetches what this names.
+pub const ASSET_INDEX: &str = "assets/index.json";
+
+/// Where the game reads its assets and writes player files. Paths are
+/// relative and use `/` on every platform, e.g. `assets/locales/de.json`.
+///
+/// Native builds use [`FileAssets`]; the browser build has no file system
+/// and uses `WebAssets`, which fetches assets over HTTP and keeps written
+/// files in IndexedDB.
+pub trait AssetProvider: fmt::Debug + Send + Sync {
+    fn read(&self, path: &str) -> io::Result<Vec<u8>>;
+
+    fn write(&self, path: &str, data: &[u8]) -> io::Result<()>;
+
+    /// Names of the files directly inside `dir`, sorted.
+    fn list(&self, dir: &str) -> io::Result<Vec<String>>;
+
+    fn read_to_string(&self, path: &str) -> io::Result<String> {
+        String::from_utf8(self.read(path)?).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+    }
+}
+
+/// Assets on disk under `root`, the working directory by default.
+#[derive(Debug, Clone, Default)]
+pub struct FileAssets {
+    pub root: PathBuf,
+}
+
+impl FileAssets {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+}
+
+impl AssetProvider for FileAssets {
+    fn read(&self, path: &str) -> io::Result<Vec<u8>> {
+        fs::read(self.root.join(path))
+    }
+
+    /// Creates the file's directory if needed.
+    fn write(&self, path: &str, data: &[u8]) -> io::Result<()> {
+        let path = self.root.join(path);
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        fs::write(path, data)
+    }
+
+    fn list(&self, dir: &str) -> io::Result<Vec<String>> {
+        let mut names = Vec::new();
+        for entry in fs::read_dir(self.root.join(dir))? {
+            let entry = entry?;
+            if entry.file_type()?.is_file() {
+                names.extend(entry.file_name().to_str().map(str::to_string));
+            }
+        }
+        names.sort();
+        Ok(names)
+    }
+}
+
+/// Assets held in memory, keyed by path. Used for tests, and by the
+/// browser build as its cache of fetched and saved files.
+#[derive(Debug, Default)]
+pub struct MemoryAssets {
+    files: RwLock<BTreeMap<String, Vec<u8>>>,
+}
+
+impl MemoryAssets {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Paths of every file held, sorted.
+    pub fn paths(&self) -> Vec<String> {
+        self.files.read().map(|files| files.keys().cloned().collect()).unwrap_or_default()
+    }
+}
+
+fn poisoned() -> io::Error {
+    io::Error::other("asset cache lock poisoned")
+}
+
+impl AssetProvider for MemoryAssets {
+    fn read(&self, path: &str) -> io::Result<Vec<u8>> {
+        let files = self.files.read().map_err(|_| poisoned())?;
+        files.get(path).cloned().ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, format!("no asset at {}", path)))
+    }
+
+    fn write(&self, path: &str, data: &[u8]) -> io::Result<()> {
+        self.files.write().map_err(|_| poisoned())?.insert(path.to_string(), data.to_vec());
+        Ok(())
+    }
+
+    fn list(&self, dir: &str) -> io::Result<Vec<String>> {
+        let prefix = format!("{}/", dir.trim_end_matches('/'));
+        let files = self.files.read().map_err(|_| poisoned())?;
+        let names: Vec<String> = files
+            .keys()
+            .filter_map(|path| path.strip_prefix(&prefix))
+            .filter(|name| !name.contains('/'))
+            .map(str::to_string)
+            .collect();
+        if names.is_empty() {
+            return Err(io::Error::new(io::ErrorKind::NotFound, format!("no assets in {}", dir)));
+        }
+        Ok(names)
+    }
+}
@@ -0,0 +1,298 @@
+use std::collections::{HashMap, HashSet};
+use std::io;
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::Arc;
+use std::thread;
+
+use crate::audio::AudioSystem;
+use crate::frontend::tiles::terrain_sprite_id;
+use crate::frontend::Renderer;
+use crate::state::GameState;
+
+use super::AssetProvider;
+
+/// Directory streamed sprites are read from, one PNG per sprite id.
+pub const SPRITE_DIR: &str = "assets/sprites";
+/// Magenta and black 2x2 checker, as RGBA8, drawn for a sprite until it
+/// arrives, or for good if it fails to load.
+pub const PLACEHOLDER_SPRITE: [u8; 16] = [255, 0, 255, 255, 0, 0, 0, 255, 0, 0, 0, 255, 255, 0, 255, 255];
+
+/// Where a streamed sprite is read from.
+pub fn sprite_path(id: &str) -> String {
+    format!("{}/{}.png", SPRITE_DIR, id)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AssetKind {
+    /// Decoded to RGBA8 and installed as a one-frame renderer texture.
+    Sprite,
+    /// Kept as encoded bytes for the audio system.
+    Sound,
+}
+
+/// Who holds a reference to an asset. Releasing a scope drops every
+/// reference it took; assets nothing else holds are unloaded.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AssetScope {
+    Campaign,
+    Battle,
+}
+
+/// How far the requested assets have got, for a loading screen.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct LoadingProgress {
+    pub loaded: usize,
+    pub failed: usize,
+    pub total: usize,
+}
+
+impl LoadingProgress {
+    /// Share of the assets finished, loaded or failed, from 0 to 1.
+    pub fn fraction(&self) -> f32 {
+        if self.total == 0 {
+            return 1.0;
+        }
+        (self.loaded + self.failed) as f32 / self.total as f32
+    }
+
+    pub fn is_done(&self) -> bool {
+        self.loaded + self.failed == self.total
+    }
+}
+
+type AssetKey = (AssetKind, String);
+
+#[derive(Debug, Clone, PartialEq)]
+enum Status {
+    Loading,
+    Loaded,
+    Failed(String),
+}
+
+#[derive(Debug)]
+struct Entry {
+    refs: u32,
+    status: Status,
+}
+
+/// An asset read, and for sprites decoded, off the game thread.
+#[derive(Debug)]
+struct Loaded {
+    key: AssetKey,
+    data: io::Result<Vec<u8>>,
+}
+
+fn load(assets: &dyn AssetProvider, kind: AssetKind, path: &str) -> io::Result<Vec<u8>> {
+    let bytes = assets.read(path)?;
+    match kind {
+        AssetKind::Sprite => image::load_from_memory(&bytes)
+            .map(|image| image.to_rgba8().into_raw())
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, format!("{}: {}", path, e))),
+        AssetKind::Sound => Ok(bytes),
+    }
+}
+
+/// Loads sprites and sounds on a background thread and hands them to the
+/// renderer and audio system as they arrive, in [`AssetManager::poll`].
+/// Sprites show [`PLACEHOLDER_SPRITE`] until then. Assets are reference
+/// counted per [`AssetScope`], so a battle's sprites are released when
+/// the player returns to the campaign map.
+///
+/// Sprites and sounds already loaded some other way, like a mod's, win
+/// over streamed ones. Where threads aren't available, as in the browser,
+/// assets load when requested instead.
+#[derive(Debug)]
+pub struct AssetManager {
+    assets: Arc<dyn AssetProvider>,
+    /// Jobs for the loader thread, if it could be started.
+    jobs: Option<Sender<(AssetKey, String)>>,
+    done: Sender<Loaded>,
+    arrived: Receiver<Loaded>,
+    entries: HashMap<AssetKey, Entry>,
+    scopes: HashMap<AssetScope, Vec<AssetKey>>,
+    /// Sprites to cover with a placeholder on the next poll.
+    placeholders: Vec<String>,
+    /// Assets nothing holds any more, unloaded on the next poll.
+    released: Vec<AssetKey>,
+    /// Loaded assets waiting to be installed.
+    ready: Vec<(AssetKey, Vec<u8>)>,
+    /// Assets this manager installed, so the ones loaded some other way,
+    /// like a mod's sprites, are left alone.
+    owned: HashSet<AssetKey>,
+}
+
+impl AssetManager {
+    pub fn new(assets: Arc<dyn AssetProvider>) -> Self {
+        let (done, arrived) = mpsc::channel();
+        let (jobs, pending) = mpsc::channel::<(AssetKey, String)>();
+        let loader = assets.clone();
+        let finished = done.clone();
+        let worker = thread::Builder::new().name("asset-loader".into()).spawn(move || {
+            for ((kind, id), path) in pending {
+                let data = load(loader.as_ref(), kind, &path);
+                if finished.send(Loaded { key: (kind, id), data }).is_err() {
+                    break;
+                }
+            }
+        });
+        Self {
+            assets,
+            jobs: worker.is_ok().then_some(jobs),
+            done,
+            arrived,
+            entries: HashMap::new(),
+            scopes: HashMap::new(),
+            placeholders: Vec::new(),
+            released: Vec::new(),
+            ready: Vec::new(),
+            owned: HashSet::new(),
+        }
+    }
+
+    /// Take a reference to the asset `id` for `scope`, loading it from
+    /// `path` unless it is already loaded or on its way.
+    pub fn request(&mut self, kind: AssetKind, id: &str, path: &str, scope: AssetScope) {
+        let key = (kind, id.to_string());
+        self.scopes.entry(scope).or_default().push(key.clone());
+        if let Some(entry) = self.entries.get_mut(&key) {
+            entry.refs += 1;
+            return;
+        }
+        self.entries.insert(key.clone(), Entry { refs: 1, status: Status::Loading });
+        if kind == AssetKind::Sprite {
+            self.placeholders.push(id.to_string());
+        }
+        let job = (key, path.to_string());
+        let queued = match &self.jobs {
+            Some(jobs) => jobs.send(job).map_err(|e| e.0),
+            None => Err(job),
+        };
+        // Without a loader thread, load it now; it is installed on the next poll.
+        if let Err(((kind, id), path)) = queued {
+            let data = load(self.assets.as_ref(), kind, &path);
+            let _ = self.done.send(Loaded { key: (kind, id), data });
+        }
+    }
+
+    /// Request the sprites a battle in `state` draws, its units' and
+    /// terrain's, from [`SPRITE_DIR`].
+    pub fn request_battle(&mut self, state: &GameState) {
+        let mut sprites: Vec<&str> = state.units.iter().map(|u| u.sprite_id.as_str()).collect();
+        sprites.extend(state.map.tiles.iter().map(|t| terrain_sprite_id(t)));
+        sprites.sort_unstable();
+        sprites.dedup();
+        for id in sprites {
+            self.request(AssetKind::Sprite, id, &sprite_path(id), AssetScope::Battle);
+        }
+    }
+
+    /// Drop every reference `scope` took. Assets no other scope holds are
+    /// unloaded on the next poll.
+    pub fn release_scope(&mut self, scope: AssetScope) {
+        for key in self.scopes.remove(&scope).unwrap_or_default() {
+            let Some(entry) = self.entries.get_mut(&key) else { continue };
+            entry.refs -= 1;
+            if entry.refs == 0 {
+                self.entries.remove(&key);
+                self.released.push(key);
+            }
+        }
+    }
+
+    /// Unload released assets, cover newly requested sprites with the
+    /// placeholder and install whatever has arrived. Call once a frame.
+    /// Returns how many assets were installed.
+    pub fn poll(&mut self, renderer: &mut Renderer, audio: &mut AudioSystem) -> usize {
+        while let Ok(loaded) = self.arrived.try_recv() {
+            self.finished(loaded);
+        }
+        self.install(renderer, audio)
+    }
+
+    /// Block until every requested asset has loaded or failed, then
+    /// install them like [`Self::poll`].
+    pub fn finish(&mut self, renderer: &mut Renderer, audio: &mut AudioSystem) -> usize {
+        while !self.progress().is_done() {
+            let Ok(loaded) = self.arrived.recv() else { break };
+            self.finished(loaded);
+        }
+        self.poll(renderer, audio)
+    }
+
+    pub fn progress(&self) -> LoadingProgress {
+        let mut progress = LoadingProgress { total: self.entries.len(), ..LoadingProgress::default() };
+        for entry in self.entries.values() {
+            match entry.status {
+                Status::Loading => {}
+                Status::Loaded => progress.loaded += 1,
+                Status::Failed(_) => progress.failed += 1,
+            }
+        }
+        progress
+    }
+
+    /// Why an asset failed to load, if it did.
+    pub fn error(&self, kind: AssetKind, id: &str) -> Option<&str> {
+        match &self.entries.get(&(kind, id.to_string()))?.status {
+            Status::Failed(e) => Some(e),
+            _ => None,
+        }
+    }
+
+    /// References held on an asset, across all scopes.
+    pub fn references(&self, kind: AssetKind, id: &str) -> u32 {
+        self.entries.get(&(kind, id.to_string())).map_or(0, |e| e.refs)
+    }
+
+    /// Note a load coming back. One released while in flight is dropped.
+    fn finished(&mut self, loaded: Loaded) {
+        let Some(entry) = self.entries.get_mut(&loaded.key) else { return };
+        if entry.status != Status::Loading {
+            return;
+        }
+        match loaded.data {
+            Ok(data) => {
+                entry.status = Status::Loaded;
+                self.ready.push((loaded.key, data));
+            }
+            Err(e) => entry.status = Status::Failed(e.to_string()),
+        }
+    }
+
+    fn install(&mut self, renderer: &mut Renderer, audio: &mut AudioSystem) -> usize {
+        for key in self.released.drain(..) {
+            // Requested again since, or never installed.
+            if self.entries.contains_key(&key) || !self.owned.remove(&key) {
+                continue;
+            }
+            match key.0 {
+                AssetKind::Sprite => renderer.unload_sprite(&key.1),
+                AssetKind::Sound => audio.unload_sound(&key.1),
+            }
+        }
+        for id in self.placeholders.drain(..) {
+            let key = (AssetKind::Sprite, id);
+            if self.entries.contains_key(&key) && !renderer.sprite_textures.contains_key(&key.1) {
+                renderer.load_sprite_from_bytes(&key.1, vec![PLACEHOLDER_SPRITE.to_vec()]);
+                self.owned.insert(key);
+            }
+        }
+        let mut installed = 0;
+        for (key, data) in self.ready.drain(..) {
+            let present = match key.0 {
+                AssetKind::Sprite => renderer.sprite_textures.contains_key(&key.1),
+                AssetKind::Sound => audio.is_loaded(&key.1),
+            };
+            if present && !self.owned.contains(&key) {
+                continue;
+            }
+            match key.0 {
+                AssetKind::Sprite => renderer.load_sprite_from_bytes(&key.1, vec![data]),
+                AssetKind::Sound => audio.load_sound_from_bytes(&key.1, data),
+            }
+            self.owned.insert(key);
+            installed += 1;
+        }
+        installed
+    }
+}
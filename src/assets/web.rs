@@ -0,0 +1,136 @@
+use std::io;
+
+use js_sys::{Array, Promise, Uint8Array};
+use wasm_bindgen::closure::Closure;
+use wasm_bindgen::{JsCast, JsValue};
+use wasm_bindgen_futures::JsFuture;
+use web_sys::{Event, IdbDatabase, IdbOpenDbRequest, IdbRequest, IdbTransactionMode, Response};
+
+use super::{AssetProvider, MemoryAssets, ASSET_INDEX};
+
+/// Object store holding written files, keyed by path.
+const STORE: &str = "files";
+
+fn js_error(e: JsValue) -> io::Error {
+    io::Error::other(e.as_string().unwrap_or_else(|| format!("{:?}", e)))
+}
+
+fn unsupported(what: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::Unsupported, format!("{} is not available", what))
+}
+
+/// Resolve once `request` succeeds, with its result.
+async fn finished(request: &IdbRequest) -> io::Result<JsValue> {
+    let promise = Promise::new(&mut |resolve, reject| {
+        let done = request.clone();
+        let on_success = Closure::once_into_js(move || {
+            let _ = resolve.call1(&JsValue::NULL, &done.result().unwrap_or(JsValue::UNDEFINED));
+        });
+        let on_error = Closure::once_into_js(move |e: Event| {
+            let _ = reject.call1(&JsValue::NULL, &e);
+        });
+        request.set_onsuccess(Some(on_success.unchecked_ref()));
+        request.set_onerror(Some(on_error.unchecked_ref()));
+    });
+    JsFuture::from(promise).await.map_err(js_error)
+}
+
+/// Open the IndexedDB database `name`, creating its store on first use.
+async fn open(name: &str) -> io::Result<IdbDatabase> {
+    let factory = web_sys::window()
+        .and_then(|w| w.indexed_db().ok().flatten())
+        .ok_or_else(|| unsupported("IndexedDB"))?;
+    let request: IdbOpenDbRequest = factory.open_with_u32(name, 1).map_err(js_error)?;
+    let upgrading = request.clone();
+    let on_upgrade = Closure::once_into_js(move |_: Event| {
+        if let Ok(db) = upgrading.result().and_then(|db| db.dyn_into::<IdbDatabase>()) {
+            let _ = db.create_object_store(STORE);
+        }
+    });
+    request.set_onupgradeneeded(Some(on_upgrade.unchecked_ref()));
+    Ok(finished(&request).await?.unchecked_into())
+}
+
+async fn persist(database: &str, path: &str, data: &[u8]) -> io::Result<()> {
+    let db = open(database).await?;
+    let store = db
+        .transaction_with_str_and_mode(STORE, IdbTransactionMode::Readwrite)
+        .and_then(|tx| tx.object_store(STORE))
+        .map_err(js_error)?;
+    let request = store.put_with_key(&Uint8Array::from(data), &JsValue::from_str(path)).map_err(js_error)?;
+    finished(&request).await.map(|_| ())
+}
+
+/// Every file saved in `database`, as (path, bytes).
+async fn saved_files(database: &str) -> io::Result<Vec<(String, Vec<u8>)>> {
+    let db = open(database).await?;
+    let store = db.transaction_with_str(STORE).and_then(|tx| tx.object_store(STORE)).map_err(js_error)?;
+    let keys: Array = finished(&store.get_all_keys().map_err(js_error)?).await?.unchecked_into();
+    let values: Array = finished(&store.get_all().map_err(js_error)?).await?.unchecked_into();
+    Ok(keys
+        .iter()
+        .zip(values.iter())
+        .filter_map(|(key, value)| Some((key.as_string()?, Uint8Array::new(&value).to_vec())))
+        .collect())
+}
+
+async fn fetch(url: &str) -> io::Result<Vec<u8>> {
+    let window = web_sys::window().ok_or_else(|| unsupported("window"))?;
+    let response: Response = JsFuture::from(window.fetch_with_str(url)).await.map_err(js_error)?.unchecked_into();
+    if !response.ok() {
+        let kind = if response.status() == 404 { io::ErrorKind::NotFound } else { io::ErrorKind::Other };
+        return Err(io::Error::new(kind, format!("fetching {}: HTTP {}", url, response.status())));
+    }
+    let buffer = JsFuture::from(response.array_buffer().map_err(js_error)?).await.map_err(js_error)?;
+    Ok(Uint8Array::new(&buffer).to_vec())
+}
+
+/// Assets for the browser. The files named in [`ASSET_INDEX`] are fetched
+/// from the page's origin up front, so reads are synchronous like on disk.
+/// Written files are kept in memory and copied to IndexedDB in the
+/// background, and are restored from there on the next visit.
+#[derive(Debug)]
+pub struct WebAssets {
+    files: MemoryAssets,
+    database: String,
+}
+
+impl WebAssets {
+    /// Fetch the asset index and every file it names, then restore the
+    /// files saved in the IndexedDB database `database`.
+    pub async fn load(database: &str) -> io::Result<Self> {
+        let files = MemoryAssets::new();
+        let index: Vec<String> = serde_json::from_slice(&fetch(ASSET_INDEX).await?)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        for path in index {
+            files.write(&path, &fetch(&path).await?)?;
+        }
+        for (path, data) in saved_files(database).await? {
+            files.write(&path, &data)?;
+        }
+        Ok(Self { files, database: database.to_string() })
+    }
+}
+
+impl AssetProvider for WebAssets {
+    fn read(&self, path: &str) -> io::Result<Vec<u8>> {
+        self.files.read(path)
+    }
+
+    /// Succeeds once the file is cached; a failure to store it in IndexedDB
+    /// only loses it on the next visit.
+    fn write(&self, path: &str, data: &[u8]) -> io::Result<()> {
+        self.files.write(path, data)?;
+        let (database, path, data) = (self.database.clone(), path.to_string(), data.to_vec());
+        wasm_bindgen_futures::spawn_local(async move {
+            if let Err(e) = persist(&database, &path, &data).await {
+                web_sys::console::error_1(&format!("saving {}: {}", path, e).into());
+            }
+        });
+        Ok(())
+    }
+
+    fn list(&self, dir: &str) -> io::Result<Vec<String>> {
+        self.files.list(dir)
+    }
+}
@@ -1,12 +1,55 @@
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::combat::CombatEvent;
+use crate::ioutil::invalid;
+use crate::ui::options::AccessibilitySettings;
 
 #[cfg(all(feature = "audio", not(test)))]
 use rodio::{OutputStream, OutputStreamHandle, Sink, Decoder, source::Source};
+#[cfg(all(feature = "audio", not(test)))]
 use std::io::Cursor;
+#[cfg(all(feature = "audio", not(test)))]
 use std::time::Duration;
+#[cfg(all(feature = "audio", not(test)))]
 use std::thread;
 
-#[derive(Debug, Clone)]
+/// File extensions the `audio` feature knows how to decode.
+const SUPPORTED_EXTENSIONS: &[&str] = &["ogg", "wav", "mp3"];
+
+/// Seconds a caption stays on screen unless its manifest entry says
+/// otherwise.
+pub const CAPTION_DURATION: f32 = 3.0;
+
+/// Captions for the built-in combat cues played by
+/// [`AudioSystem::handle_combat_event`].
+const DEFAULT_CAPTIONS: &[(&str, &str)] = &[("explosion", "caption.explosion"), ("rubble", "caption.rubble")];
+
+/// Subtitle for a sound. `speaker` and `text` are localization keys,
+/// resolved when the caption is drawn.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Caption {
+    pub speaker: Option<String>,
+    pub text: String,
+    pub duration: f32,
+}
+
+impl Caption {
+    pub fn new(text: impl Into<String>) -> Self {
+        Self { speaker: None, text: text.into(), duration: CAPTION_DURATION }
+    }
+
+    pub fn spoken_by(mut self, speaker: impl Into<String>) -> Self {
+        self.speaker = Some(speaker.into());
+        self
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct AudioSettings {
     pub master: f32,
     pub sfx: f32,
@@ -28,6 +71,26 @@ pub enum AudioChannel {
     Voice,
 }
 
+/// A single entry in an audio manifest: either a bare path or a table that
+/// can request the file be read into memory immediately.
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum ManifestEntry {
+    Path(String),
+    Detailed {
+        path: String,
+        #[serde(default)]
+        preload: bool,
+        /// Localization key of the subtitle shown when the sound plays.
+        #[serde(default)]
+        caption: Option<String>,
+        #[serde(default)]
+        speaker: Option<String>,
+        #[serde(default)]
+        caption_seconds: Option<f32>,
+    },
+}
+
 /// Very small audio manager used for tests and demos.
 /// In production this would stream audio via `rodio`.
 pub struct AudioSystem {
@@ -36,6 +99,7 @@ pub struct AudioSystem {
     #[cfg(all(feature = "audio", not(test)))]
     handle: OutputStreamHandle,
     sounds: HashMap<String, Vec<u8>>, // key -> raw audio bytes
+    sound_paths: HashMap<String, PathBuf>, // key -> file read on first play
     #[cfg(all(feature = "audio", not(test)))]
     music_sink: Option<Sink>,
     #[cfg(any(test, not(feature = "audio")))]
@@ -43,6 +107,25 @@ pub struct AudioSystem {
     pub settings: AudioSettings,
     /// Records which sound keys were played. Useful in tests.
     pub played_log: Vec<String>,
+    /// sound key -> subtitle shown when it plays
+    pub captions: HashMap<String, Caption>,
+    /// Emit captions for played sounds; follows the subtitles option.
+    pub captions_enabled: bool,
+    /// Captions for sounds played since the last [`AudioSystem::take_captions`].
+    pending_captions: Vec<Caption>,
+    /// Manifest sounds that couldn't be read when first played, since the
+    /// last [`AudioSystem::take_load_errors`].
+    load_errors: Vec<io::Error>,
+}
+
+fn default_captions() -> HashMap<String, Caption> {
+    DEFAULT_CAPTIONS.iter().map(|(key, text)| (key.to_string(), Caption::new(*text))).collect()
+}
+
+impl Default for AudioSystem {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl AudioSystem {
@@ -54,16 +137,36 @@ impl AudioSystem {
             stream,
             handle,
             sounds: HashMap::new(),
+            sound_paths: HashMap::new(),
             music_sink: None,
             settings: AudioSettings::default(),
             played_log: Vec::new(),
+            captions: default_captions(),
+            captions_enabled: false,
+            pending_captions: Vec::new(),
+            load_errors: Vec::new(),
         }
     }
 
     /// Headless constructor used without the `audio` feature or in tests.
     #[cfg(any(test, not(feature = "audio")))]
     pub fn new() -> Self {
-        Self { sounds: HashMap::new(), current_music: None, settings: AudioSettings::default(), played_log: Vec::new() }
+        Self {
+            sounds: HashMap::new(),
+            sound_paths: HashMap::new(),
+            current_music: None,
+            settings: AudioSettings::default(),
+            played_log: Vec::new(),
+            captions: default_captions(),
+            captions_enabled: false,
+            pending_captions: Vec::new(),
+            load_errors: Vec::new(),
+        }
+    }
+
+    /// Turn captions on or off to match the subtitles option.
+    pub fn apply_accessibility(&mut self, settings: &AccessibilitySettings) {
+        self.captions_enabled = settings.subtitles;
     }
 
     /// Load a sound from raw bytes.
@@ -71,22 +174,159 @@ impl AudioSystem {
         self.sounds.insert(key.to_string(), data);
     }
 
+    /// Forget a sound loaded from bytes.
+    pub fn unload_sound(&mut self, key: &str) {
+        self.sounds.remove(key);
+    }
+
+    /// Load a JSON manifest mapping sound keys to audio files. Paths are
+    /// resolved relative to the manifest. Each entry is either a path string
+    /// or `{ "path": ..., "preload": true }`; preloaded sounds are read now and
+    /// the rest on first playback. A table may also give a `caption` key,
+    /// its `speaker` and `caption_seconds`.
+    ///
+    /// Every file is checked before anything is registered, so a missing file
+    /// or an unsupported format fails the whole manifest.
+    pub fn load_manifest<P: AsRef<Path>>(&mut self, path: P) -> io::Result<()> {
+        let path = path.as_ref();
+        let data = fs::read_to_string(path)?;
+        let entries: BTreeMap<String, ManifestEntry> = serde_json::from_str(&data).map_err(|e| invalid(e.to_string()))?;
+        let base = path.parent().unwrap_or(Path::new(""));
+
+        let mut resolved = Vec::with_capacity(entries.len());
+        for (key, entry) in entries {
+            let (file, preload, caption) = match entry {
+                ManifestEntry::Path(file) => (file, false, None),
+                ManifestEntry::Detailed { path, preload, caption, speaker, caption_seconds } => {
+                    let caption = caption.map(|text| Caption {
+                        speaker,
+                        text,
+                        duration: caption_seconds.unwrap_or(CAPTION_DURATION),
+                    });
+                    (path, preload, caption)
+                }
+            };
+            let file = base.join(file);
+            let supported = file
+                .extension()
+                .and_then(|e| e.to_str())
+                .is_some_and(|e| SUPPORTED_EXTENSIONS.contains(&e.to_ascii_lowercase().as_str()));
+            if !supported {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    format!("unsupported audio format for '{}': {}", key, file.display()),
+                ));
+            }
+            if !file.is_file() {
+                return Err(io::Error::new(
+                    io::ErrorKind::NotFound,
+                    format!("missing audio file for '{}': {}", key, file.display()),
+                ));
+            }
+            resolved.push((key, file, preload, caption));
+        }
+
+        for (key, file, preload, caption) in resolved {
+            if let Some(caption) = caption {
+                self.captions.insert(key.clone(), caption);
+            }
+            if preload {
+                let bytes = fs::read(&file)?;
+                self.sounds.insert(key, bytes);
+            } else {
+                self.sounds.remove(&key);
+                self.sound_paths.insert(key, file);
+            }
+        }
+        Ok(())
+    }
+
+    /// Whether the bytes for `key` are currently held in memory.
+    pub fn is_loaded(&self, key: &str) -> bool {
+        self.sounds.contains_key(key)
+    }
+
+    /// Read a sound registered by a manifest from disk if it isn't in
+    /// memory yet. A file that has gone missing or unreadable since the
+    /// manifest was loaded is an error; the sound stays registered so a
+    /// later call can try again.
+    pub fn load(&mut self, key: &str) -> io::Result<()> {
+        if self.sounds.contains_key(key) {
+            return Ok(());
+        }
+        if let Some(path) = self.sound_paths.get(key) {
+            let bytes = fs::read(path)
+                .map_err(|e| io::Error::new(e.kind(), format!("can't read audio file for '{key}': {}: {e}", path.display())))?;
+            self.sounds.insert(key.to_string(), bytes);
+        }
+        Ok(())
+    }
+
+    /// Load `key` before playing it, keeping any failure for
+    /// [`AudioSystem::take_load_errors`]; the sound is logged as played
+    /// either way.
+    fn ensure_loaded(&mut self, key: &str) {
+        if let Err(e) = self.load(key) {
+            self.load_errors.push(e);
+        }
+    }
+
+    /// Sounds that failed to load on playback since the last call, oldest
+    /// first.
+    pub fn take_load_errors(&mut self) -> Vec<io::Error> {
+        std::mem::take(&mut self.load_errors)
+    }
+
     /// Play a sound effect previously loaded.
     pub fn play(&mut self, key: &str) {
-        if let Some(bytes) = self.sounds.get(key) {
-            #[cfg(all(feature = "audio", not(test)))]
-            if let Ok(decoder) = Decoder::new(Cursor::new(bytes.clone())) {
-                let sink = Sink::try_new(&self.handle).expect("sink");
-                sink.set_volume(self.settings.master * self.settings.sfx);
-                sink.append(decoder.convert_samples());
-                sink.detach();
-            }
+        self.play_at(key, self.settings.sfx);
+    }
+
+    /// Play a voice bark on the voice channel.
+    pub fn play_voice(&mut self, key: &str) {
+        self.play_at(key, self.settings.voice);
+    }
+
+    /// Play `key` at `volume` (before the master volume) and queue its
+    /// caption if captions are on.
+    #[cfg_attr(any(test, not(feature = "audio")), allow(unused_variables))]
+    fn play_at(&mut self, key: &str, volume: f32) {
+        self.ensure_loaded(key);
+        #[cfg(all(feature = "audio", not(test)))]
+        if let Some(bytes) = self.sounds.get(key)
+            && let Ok(decoder) = Decoder::new(Cursor::new(bytes.clone()))
+        {
+            let sink = Sink::try_new(&self.handle).expect("sink");
+            sink.set_volume(self.settings.master * volume);
+            sink.append(decoder.convert_samples());
+            sink.detach();
+        }
+        if self.captions_enabled
+            && let Some(caption) = self.captions.get(key)
+        {
+            self.pending_captions.push(caption.clone());
         }
         self.played_log.push(key.to_string());
     }
 
+    /// Captions queued since the last call, oldest first.
+    pub fn take_captions(&mut self) -> Vec<Caption> {
+        std::mem::take(&mut self.pending_captions)
+    }
+
+    /// Play the sound cue for a combat event, if it has one.
+    pub fn handle_combat_event(&mut self, event: &CombatEvent) {
+        match event {
+            CombatEvent::Explosion { .. } => self.play("explosion"),
+            CombatEvent::ShellImpact { .. } => self.play("shell_impact"),
+            CombatEvent::ObjectDestroyed { .. } => self.play("rubble"),
+            _ => {}
+        }
+    }
+
     /// Play a background music track, crossfading if one is already playing.
     pub fn play_background_music(&mut self, key: &str) {
+        self.ensure_loaded(key);
         #[cfg(all(feature = "audio", not(test)))]
         {
             if let Some(bytes) = self.sounds.get(key) {
@@ -122,6 +362,14 @@ impl AudioSystem {
         self.played_log.push(format!("music:{}", key));
     }
 
+    /// Set every channel's volume from `settings`.
+    pub fn apply_settings(&mut self, settings: &AudioSettings) {
+        self.set_volume(AudioChannel::Sfx, settings.sfx);
+        self.set_volume(AudioChannel::Voice, settings.voice);
+        self.set_volume(AudioChannel::Music, settings.music);
+        self.set_volume(AudioChannel::Master, settings.master);
+    }
+
     /// Change the volume for a specific audio channel.
     pub fn set_volume(&mut self, channel: AudioChannel, value: f32) {
         match channel {
@@ -0,0 +1,86 @@
+use serde::{Deserialize, Serialize};
+
+use crate::combat::CombatEncounter;
+use crate::models::{Faction, Position, UnitType};
+use crate::modifiers::{Modifier, ModifierSource, Stat};
+
+/// A passive effect a unit projects onto allies within a grid radius.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Aura {
+    /// Commissar: +2 willpower and +5 accuracy within 3 cells.
+    Leadership,
+    /// Ork Nob: +1 strength and +1 toughness within 2 cells.
+    Intimidation,
+}
+
+impl Aura {
+    pub fn for_unit_type(unit_type: &UnitType) -> Option<Aura> {
+        match unit_type {
+            UnitType::Commissar => Some(Aura::Leadership),
+            UnitType::OrkNob => Some(Aura::Intimidation),
+            _ => None,
+        }
+    }
+
+    /// Manhattan distance the aura reaches.
+    pub fn radius(&self) -> u32 {
+        match self {
+            Aura::Leadership => 3,
+            Aura::Intimidation => 2,
+        }
+    }
+
+    pub fn modifiers(&self) -> Vec<Modifier> {
+        let stats: &[(Stat, i32)] = match self {
+            Aura::Leadership => &[(Stat::Willpower, 2), (Stat::Accuracy, 5)],
+            Aura::Intimidation => &[(Stat::Strength, 1), (Stat::Toughness, 1)],
+        };
+        stats.iter().map(|&(stat, amount)| Modifier::new(ModifierSource::Aura, stat, amount)).collect()
+    }
+}
+
+fn distance(a: &Position, b: &Position) -> u32 {
+    (a.x.abs_diff(b.x) + a.y.abs_diff(b.y)) as u32
+}
+
+impl CombatEncounter {
+    /// Position and radius of every living unit projecting an aura.
+    pub fn aura_sources(&self) -> Vec<(Position, u32)> {
+        self.units
+            .iter()
+            .filter(|u| u.health_points > 0)
+            .filter_map(|u| Aura::for_unit_type(&u.unit_type).map(|a| (u.grid_position.clone(), a.radius())))
+            .collect()
+    }
+
+    /// Rebuild every unit's aura modifiers from current positions. A unit
+    /// does not benefit from its own aura, and auras of the same kind do not
+    /// stack.
+    pub fn refresh_auras(&mut self) {
+        // Sources go by their index in the registry, so no ids are cloned.
+        let sources: Vec<(usize, Faction, Position, Aura)> = self
+            .units
+            .iter()
+            .enumerate()
+            .filter(|(_, u)| u.health_points > 0)
+            .filter_map(|(i, u)| Aura::for_unit_type(&u.unit_type).map(|a| (i, u.faction.clone(), u.grid_position.clone(), a)))
+            .collect();
+        for (index, unit) in self.units.iter_mut().enumerate() {
+            unit.modifiers.remove_source(ModifierSource::Aura);
+            let mut applied = Vec::new();
+            for (source, faction, pos, aura) in &sources {
+                if *source != index
+                    && *faction == unit.faction
+                    && distance(pos, &unit.grid_position) <= aura.radius()
+                    && !applied.contains(aura)
+                {
+                    applied.push(*aura);
+                    for m in aura.modifiers() {
+                        unit.modifiers.push(m);
+                    }
+                }
+            }
+            unit.recalculate_stats();
+        }
+    }
+}
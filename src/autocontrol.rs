@@ -0,0 +1,49 @@
+use crate::combat::{CombatEncounter, CombatError, Controller};
+
+impl CombatEncounter {
+    /// Let the AI play the turns of the human-controlled unit `unit_id`, or
+    /// hand it back. While on auto-control the unit counts as AI driven, so
+    /// its player can't order it about.
+    pub fn set_auto_control(&mut self, unit_id: &str, enabled: bool) -> Result<(), CombatError> {
+        let unit = self.unit_by_id(unit_id).ok_or(CombatError::UnknownUnit)?;
+        if !matches!(self.controllers.get(&unit.faction), Some(Controller::Player(_))) {
+            return Err(CombatError::NotControlled);
+        }
+        if enabled {
+            self.auto_controlled.insert(unit_id.to_string());
+        } else {
+            self.auto_controlled.remove(unit_id);
+        }
+        Ok(())
+    }
+
+    pub fn is_auto_controlled(&self, unit_id: &str) -> bool {
+        self.auto_controlled.contains(unit_id)
+    }
+
+    /// Put every unit `player` controls on auto-control, or take them all
+    /// back: the auto-battle button.
+    pub fn set_auto_battle(&mut self, player: u8, enabled: bool) {
+        let theirs: Vec<String> = self
+            .units
+            .iter()
+            .filter(|u| self.controllers.get(&u.faction) == Some(&Controller::Player(player)))
+            .map(|u| u.id.clone())
+            .collect();
+        for id in theirs {
+            let _ = self.set_auto_control(&id, enabled);
+        }
+    }
+
+    /// Run the AI for the current unit if the AI controls it, whether an
+    /// opponent or a player's unit on auto-control. Returns whether it
+    /// acted.
+    pub fn ai_action(&mut self, roll: u8) -> bool {
+        let Some(id) = self.turn_order.current_unit_id.clone() else { return false };
+        if self.controller_of(&id) != Some(Controller::Ai) {
+            return false;
+        }
+        self.ai_take_action(roll);
+        true
+    }
+}
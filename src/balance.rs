@@ -0,0 +1,177 @@
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::assets::AssetProvider;
+use crate::grid::TerrainType;
+use crate::ioutil::invalid;
+use crate::knowledge::PerfectInformation;
+use crate::facing::FacingArc;
+use crate::models::{MovementType, Position, Stats, Unit};
+
+/// Where the shipped balance file lives.
+pub const BALANCE_PATH: &str = "assets/content/balance.json";
+
+/// Movement point costs and how many points units get. Carried by the
+/// battlefield so pathfinding sees them; see [`crate::grid::GridMap::set_movement_rules`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MovementRules {
+    /// Points of agility per movement point.
+    pub agility_per_point: i32,
+    pub straight_step: u32,
+    pub diagonal_step: u32,
+    /// Added to a step onto difficult terrain.
+    pub difficult: u32,
+    /// Added to a step onto hazardous terrain.
+    pub hazardous: u32,
+}
+
+impl MovementRules {
+    pub const DEFAULT: Self = Self { agility_per_point: 2, straight_step: 1, diagonal_step: 2, difficult: 1, hazardous: 2 };
+
+    /// Movement points each AP spent moving buys: agility over
+    /// `agility_per_point`, plus any movement bonus.
+    pub fn points(&self, stats: &Stats) -> u32 {
+        (stats.agility / self.agility_per_point + stats.movement).max(0) as u32
+    }
+
+    /// Cost for a unit moving in `movement` style to step onto an adjacent
+    /// tile, or `None` if it cannot enter it.
+    pub fn step(&self, terrain: &TerrainType, diagonal: bool, movement: MovementType) -> Option<u32> {
+        let mut cost = if diagonal { self.diagonal_step } else { self.straight_step };
+        match terrain {
+            TerrainType::Difficult => cost += self.difficult,
+            TerrainType::Hazardous => cost += self.hazardous,
+            TerrainType::Blocked => cost += movement.blocked_crossing_cost()?,
+            TerrainType::Normal => {}
+        }
+        Some(cost)
+    }
+
+    /// Least cost of crossing `dx` by `dy` open cells, for A*. A diagonal
+    /// step never counts for more than the two straight ones it replaces.
+    pub fn distance(&self, dx: usize, dy: usize) -> u32 {
+        let diag = dx.min(dy) as u32;
+        let straight = (dx.max(dy) - dx.min(dy)) as u32;
+        diag * self.diagonal_step.min(self.straight_step * 2) + straight * self.straight_step
+    }
+}
+
+impl Default for MovementRules {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
+/// Bonuses for attacking a unit from outside its front arc; see
+/// [`crate::facing`]. Vehicles get none: their hull armour already
+/// depends on the arc.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FlankingRules {
+    /// Hit chance added to attacks from the side arc.
+    pub side_accuracy: i32,
+    /// Hit chance added to attacks from the rear arc.
+    pub rear_accuracy: i32,
+    /// Damage added to attacks from the rear arc.
+    pub rear_damage: i32,
+}
+
+impl FlankingRules {
+    pub const DEFAULT: Self = Self { side_accuracy: 10, rear_accuracy: 20, rear_damage: 1 };
+
+    /// Hit chance and damage added to an attack on `defender` from `from`.
+    pub fn bonus(&self, defender: &Unit, from: &Position) -> (i32, i32) {
+        if defender.vehicle.is_some() {
+            return (0, 0);
+        }
+        match defender.facing.arc(&defender.grid_position, from) {
+            FacingArc::Front => (0, 0),
+            FacingArc::Side => (self.side_accuracy, 0),
+            FacingArc::Rear => (self.rear_accuracy, self.rear_damage),
+        }
+    }
+}
+
+impl Default for FlankingRules {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
+/// The numbers combat runs on, loaded from a data file so they can be
+/// retuned without recompiling. Fields left out of the file keep their
+/// defaults.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BalanceConfig {
+    /// Hit chance gained per point of the attacker's agility, and lost per
+    /// point of the defender's.
+    pub agility_hit_chance: f32,
+    /// Hit chance per unit of weapon accuracy, which runs from 0 to 1.
+    pub weapon_accuracy_hit_chance: f32,
+    /// Hits rolling this or lower are critical.
+    pub critical_roll: u8,
+    pub critical_multiplier: i32,
+    pub movement: MovementRules,
+    /// Difficulties at which the AI sees through fog of war; see
+    /// [`crate::knowledge`].
+    pub ai_perfect_information: PerfectInformation,
+    pub flanking: FlankingRules,
+}
+
+impl BalanceConfig {
+    pub const DEFAULT: Self = Self {
+        agility_hit_chance: 10.0,
+        weapon_accuracy_hit_chance: 100.0,
+        critical_roll: 10,
+        critical_multiplier: 2,
+        movement: MovementRules::DEFAULT,
+        ai_perfect_information: PerfectInformation::DEFAULT,
+        flanking: FlankingRules::DEFAULT,
+    };
+
+    pub fn load<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        Self::parse(&fs::read_to_string(path)?)
+    }
+
+    /// [`BalanceConfig::load`] from `path` in `assets`.
+    pub fn load_from(assets: &dyn AssetProvider, path: &str) -> io::Result<Self> {
+        Self::parse(&assets.read_to_string(path)?)
+    }
+
+    /// Parse a config, rejecting values that would divide by zero or make
+    /// steps free.
+    fn parse(data: &str) -> io::Result<Self> {
+        let config: BalanceConfig = serde_json::from_str(data).map_err(invalid)?;
+        let movement = &config.movement;
+        if movement.agility_per_point <= 0 {
+            return Err(invalid("agility_per_point must be positive"));
+        }
+        if movement.straight_step == 0 || movement.diagonal_step == 0 {
+            return Err(invalid("steps must cost at least one movement point"));
+        }
+        Ok(config)
+    }
+
+    /// Chance out of 100 to hit, from the attacker's stats, its weapon's
+    /// accuracy and any `accuracy` bonus, against the defender's agility
+    /// and cover.
+    pub fn hit_chance(&self, attacker: &Stats, weapon_accuracy: f32, accuracy: i32, defender_agility: i32, cover: i32) -> f32 {
+        (attacker.agility as f32 * self.agility_hit_chance + weapon_accuracy * self.weapon_accuracy_hit_chance + accuracy as f32)
+            - (defender_agility as f32 * self.agility_hit_chance + cover as f32)
+    }
+
+    pub fn is_critical(&self, roll: u8) -> bool {
+        roll <= self.critical_roll
+    }
+}
+
+impl Default for BalanceConfig {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
@@ -0,0 +1,118 @@
+use serde::{Deserialize, Serialize};
+
+use crate::campaign::Campaign;
+use crate::events::MAX_MORALE;
+use crate::injuries::{MAX_MEDBAY_LEVEL, MEDBAY_UPGRADE_COST};
+use crate::models::WeaponTier;
+use crate::research::ActiveResearch;
+
+/// Requisition for the armory upgrade.
+pub const ARMORY_UPGRADE_COST: u32 = 150;
+/// Highest armory level; it unlocks master-crafted weapons.
+pub const MAX_ARMORY_LEVEL: u32 = 1;
+/// Requisition for the first chapel level; each further level costs as
+/// much again.
+pub const CHAPEL_UPGRADE_COST: u32 = 60;
+/// Highest chapel level.
+pub const MAX_CHAPEL_LEVEL: u32 = 3;
+/// Morale restored after each mission per chapel level.
+pub const MORALE_PER_CHAPEL_LEVEL: i32 = 1;
+
+/// A part of the squad's base that requisition can be invested in. Each
+/// is a track of levels bought in order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Facility {
+    /// Unlocks higher weapon tiers for requisition.
+    Armory,
+    /// Speeds recovery from injuries.
+    Medbay,
+    /// Restores morale after each mission.
+    Chapel,
+}
+
+impl Facility {
+    pub const ALL: [Facility; 3] = [Facility::Armory, Facility::Medbay, Facility::Chapel];
+
+    pub fn max_level(&self) -> u32 {
+        match self {
+            Facility::Armory => MAX_ARMORY_LEVEL,
+            Facility::Medbay => MAX_MEDBAY_LEVEL,
+            Facility::Chapel => MAX_CHAPEL_LEVEL,
+        }
+    }
+
+    /// Cost of the first level; later levels cost it times their level.
+    fn base_cost(&self) -> u32 {
+        match self {
+            Facility::Armory => ARMORY_UPGRADE_COST,
+            Facility::Medbay => MEDBAY_UPGRADE_COST,
+            Facility::Chapel => CHAPEL_UPGRADE_COST,
+        }
+    }
+
+    /// Localization key for the facility's display name.
+    pub fn label_key(&self) -> &'static str {
+        match self {
+            Facility::Armory => "facility.armory",
+            Facility::Medbay => "facility.medbay",
+            Facility::Chapel => "facility.chapel",
+        }
+    }
+
+    fn maxed_error(&self) -> &'static str {
+        match self {
+            Facility::Armory => "armory fully upgraded",
+            Facility::Medbay => "medbay fully upgraded",
+            Facility::Chapel => "chapel fully upgraded",
+        }
+    }
+}
+
+/// Armory level needed to requisition weapons of `tier`.
+pub fn armory_level_for(tier: &WeaponTier) -> u32 {
+    match tier {
+        WeaponTier::Basic | WeaponTier::Advanced => 0,
+        WeaponTier::MasterCrafted => 1,
+    }
+}
+
+impl Campaign {
+    pub fn facility_level(&self, facility: Facility) -> u32 {
+        match facility {
+            Facility::Armory => self.armory_level,
+            Facility::Medbay => self.medbay_level,
+            Facility::Chapel => self.chapel_level,
+        }
+    }
+
+    /// Requisition for the facility's next level, or `None` at the top.
+    pub fn facility_upgrade_cost(&self, facility: Facility) -> Option<u32> {
+        let level = self.facility_level(facility);
+        (level < facility.max_level()).then(|| facility.base_cost() * (level + 1))
+    }
+
+    /// Spend requisition on the facility's next level.
+    pub fn upgrade_facility(&mut self, facility: Facility) -> Result<(), &'static str> {
+        let cost = self.facility_upgrade_cost(facility).ok_or(facility.maxed_error())?;
+        if cost > self.currency {
+            return Err("insufficient requisition");
+        }
+        self.currency -= cost;
+        match facility {
+            Facility::Armory => self.armory_level += 1,
+            Facility::Medbay => self.medbay_level += 1,
+            Facility::Chapel => self.chapel_level += 1,
+        }
+        Ok(())
+    }
+
+    /// Run the base between missions: injuries recover at the medbay's
+    /// pace, the chapel restores morale and research moves on. Returns the
+    /// research project finished, if any.
+    pub fn tend_base(&mut self) -> Option<ActiveResearch> {
+        self.advance_recovery();
+        let boost = self.chapel_level as i32 * MORALE_PER_CHAPEL_LEVEL;
+        self.morale = (self.morale + boost).min(MAX_MORALE);
+        self.advance_research()
+    }
+}
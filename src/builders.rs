@@ -0,0 +1,398 @@
+use crate::channel::Channel;
+use crate::damage::DamageType;
+use crate::displacement::ForcedMove;
+use crate::environment::Hazard;
+use crate::facing::Facing;
+use crate::indirect::IndirectFire;
+use crate::ranks::AbilityRank;
+use crate::terraform::TerrainShift;
+use crate::vehicle::Vehicle;
+use crate::models::{
+    Ability, AbilityEffect, AbilityType, AnimationType, AreaOfEffect, Armor, EffectType, Faction, MovementType, Position,
+    Stats, StatsModifier, Trait, Unit, UnitType, Weapon, WeaponTier,
+};
+
+/// Builds a [`Weapon`]. Unless set, it is a basic kinetic melee weapon
+/// dealing 1 damage at 50% accuracy for one action point.
+#[derive(Debug, Clone)]
+pub struct WeaponBuilder {
+    weapon: Weapon,
+}
+
+impl WeaponBuilder {
+    /// Start a weapon with `id`, also used as its name.
+    pub fn new(id: &str) -> Self {
+        Self {
+            weapon: Weapon {
+                id: id.to_string(),
+                name: id.to_string(),
+                tier: WeaponTier::Basic,
+                damage: 1,
+                accuracy: 0.5,
+                range: 1,
+                armor_piercing: None,
+                action_point_cost: 1,
+                critical_chance: 0.0,
+                abilities_granted: Vec::new(),
+                damage_type: DamageType::Kinetic,
+                forced_move: None,
+                indirect: None,
+            },
+        }
+    }
+
+    pub fn name(mut self, name: &str) -> Self {
+        self.weapon.name = name.to_string();
+        self
+    }
+
+    pub fn tier(mut self, tier: WeaponTier) -> Self {
+        self.weapon.tier = tier;
+        self
+    }
+
+    pub fn damage(mut self, damage: i32) -> Self {
+        self.weapon.damage = damage;
+        self
+    }
+
+    /// Accuracy from 0 to 1.
+    pub fn accuracy(mut self, accuracy: f32) -> Self {
+        self.weapon.accuracy = accuracy;
+        self
+    }
+
+    /// Range in tiles; 1 makes a melee weapon.
+    pub fn range(mut self, range: u32) -> Self {
+        self.weapon.range = range;
+        self
+    }
+
+    pub fn armor_piercing(mut self, armor_piercing: f32) -> Self {
+        self.weapon.armor_piercing = Some(armor_piercing);
+        self
+    }
+
+    pub fn action_point_cost(mut self, cost: u32) -> Self {
+        self.weapon.action_point_cost = cost;
+        self
+    }
+
+    pub fn critical_chance(mut self, chance: f32) -> Self {
+        self.weapon.critical_chance = chance;
+        self
+    }
+
+    pub fn grants(mut self, ability: AbilityType) -> Self {
+        self.weapon.abilities_granted.push(ability);
+        self
+    }
+
+    pub fn damage_type(mut self, damage_type: DamageType) -> Self {
+        self.weapon.damage_type = damage_type;
+        self
+    }
+
+    /// Knock back, pull or swap places with whoever it hits.
+    pub fn forced_move(mut self, forced: ForcedMove) -> Self {
+        self.weapon.forced_move = Some(forced);
+        self
+    }
+
+    /// Lob shells at cells no closer than `min_range`, bursting over
+    /// `aoe_radius`; see [`crate::indirect`].
+    pub fn indirect(mut self, min_range: u32, aoe_radius: u32) -> Self {
+        self.weapon.indirect = Some(IndirectFire { min_range, aoe_radius });
+        self
+    }
+
+    pub fn build(self) -> Weapon {
+        self.weapon
+    }
+}
+
+/// Builds an [`Ability`]. Unless set, it costs one action point, has no
+/// cooldown, reaches adjacent tiles and does nothing.
+#[derive(Debug, Clone)]
+pub struct AbilityBuilder {
+    ability: Ability,
+}
+
+impl AbilityBuilder {
+    /// Start an ability with `id`, also used as its name.
+    pub fn new(id: &str, ability_type: AbilityType) -> Self {
+        Self {
+            ability: Ability {
+                id: id.to_string(),
+                name: id.to_string(),
+                ability_type,
+                description: String::new(),
+                action_point_cost: 1,
+                cooldown: 0,
+                current_cooldown: 0,
+                range: 1,
+                area_of_effect: None,
+                effect: AbilityEffect {
+                    damage: None,
+                    healing: None,
+                    buff: None,
+                    debuff: None,
+                    status_applied: None,
+                    duration: None,
+                    creates_hazard: None,
+                    alters_terrain: None,
+                    forced_move: None,
+                    damage_type: DamageType::Kinetic,
+                },
+                animation: AnimationType::AbilityCast,
+                sound_effect_key: String::new(),
+                rank: 1,
+                ranks: Vec::new(),
+                channel: None,
+            },
+        }
+    }
+
+    pub fn name(mut self, name: &str) -> Self {
+        self.ability.name = name.to_string();
+        self
+    }
+
+    pub fn description(mut self, description: &str) -> Self {
+        self.ability.description = description.to_string();
+        self
+    }
+
+    pub fn action_point_cost(mut self, cost: u32) -> Self {
+        self.ability.action_point_cost = cost;
+        self
+    }
+
+    /// Turns between uses.
+    pub fn cooldown(mut self, cooldown: u32) -> Self {
+        self.ability.cooldown = cooldown;
+        self
+    }
+
+    pub fn range(mut self, range: u32) -> Self {
+        self.ability.range = range;
+        self
+    }
+
+    pub fn area(mut self, area: AreaOfEffect) -> Self {
+        self.ability.area_of_effect = Some(area);
+        self
+    }
+
+    pub fn damage(mut self, damage: i32, damage_type: DamageType) -> Self {
+        self.ability.effect.damage = Some(damage);
+        self.ability.effect.damage_type = damage_type;
+        self
+    }
+
+    pub fn healing(mut self, healing: i32) -> Self {
+        self.ability.effect.healing = Some(healing);
+        self
+    }
+
+    pub fn buff(mut self, buff: StatsModifier) -> Self {
+        self.ability.effect.buff = Some(buff);
+        self
+    }
+
+    pub fn debuff(mut self, debuff: StatsModifier) -> Self {
+        self.ability.effect.debuff = Some(debuff);
+        self
+    }
+
+    /// Apply `status` to each target, and make buffs and debuffs last, for
+    /// `turns`.
+    pub fn status(mut self, status: EffectType, turns: u32) -> Self {
+        self.ability.effect.status_applied = Some(status);
+        self.ability.effect.duration = Some(turns);
+        self
+    }
+
+    /// How long buffs, debuffs and statuses last.
+    pub fn duration(mut self, turns: u32) -> Self {
+        self.ability.effect.duration = Some(turns);
+        self
+    }
+
+    pub fn hazard(mut self, hazard: Hazard) -> Self {
+        self.ability.effect.creates_hazard = Some(hazard);
+        self
+    }
+
+    pub fn animation(mut self, animation: AnimationType) -> Self {
+        self.ability.animation = animation;
+        self
+    }
+
+    pub fn sound(mut self, key: &str) -> Self {
+        self.ability.sound_effect_key = key.to_string();
+        self
+    }
+
+    /// Add the next rank's upgrade.
+    pub fn rank(mut self, rank: AbilityRank) -> Self {
+        self.ability.ranks.push(rank);
+        self
+    }
+
+    /// Change the terrain of the cells the ability covers.
+    pub fn alters_terrain(mut self, shift: TerrainShift) -> Self {
+        self.ability.effect.alters_terrain = Some(shift);
+        self
+    }
+
+    /// Knock back, pull or swap places with each target.
+    pub fn forced_move(mut self, forced: ForcedMove) -> Self {
+        self.ability.effect.forced_move = Some(forced);
+        self
+    }
+
+    /// Make the ability charge up or be sustained over several turns.
+    pub fn channel(mut self, channel: Channel) -> Self {
+        self.ability.channel = Some(channel);
+        self
+    }
+
+    pub fn build(self) -> Ability {
+        self.ability
+    }
+}
+
+/// Builds a [`Unit`] with stats recalculated and full health and action
+/// points. Unless set, it has the stats of [`Unit::new`]: 10 health, 2
+/// action points and nothing else.
+#[derive(Debug, Clone)]
+pub struct UnitBuilder {
+    unit: Unit,
+}
+
+impl UnitBuilder {
+    /// Start a unit with `id`, also used as its name.
+    pub fn new(id: &str, unit_type: UnitType, faction: Faction) -> Self {
+        Self { unit: Unit::new(id, id, unit_type, faction) }
+    }
+
+    pub fn name(mut self, name: &str) -> Self {
+        self.unit.name = name.to_string();
+        self
+    }
+
+    /// Replace every base stat.
+    pub fn stats(mut self, stats: Stats) -> Self {
+        self.unit.base_stats = stats;
+        self
+    }
+
+    pub fn max_health(mut self, max_health: i32) -> Self {
+        self.unit.base_stats.max_health = max_health;
+        self
+    }
+
+    pub fn max_action(mut self, max_action: u32) -> Self {
+        self.unit.base_stats.max_action = max_action;
+        self
+    }
+
+    pub fn strength(mut self, strength: i32) -> Self {
+        self.unit.base_stats.strength = strength;
+        self
+    }
+
+    pub fn toughness(mut self, toughness: i32) -> Self {
+        self.unit.base_stats.toughness = toughness;
+        self
+    }
+
+    pub fn agility(mut self, agility: i32) -> Self {
+        self.unit.base_stats.agility = agility;
+        self
+    }
+
+    pub fn willpower(mut self, willpower: i32) -> Self {
+        self.unit.base_stats.willpower = willpower;
+        self
+    }
+
+    pub fn level(mut self, level: u32) -> Self {
+        self.unit.level = level;
+        self
+    }
+
+    pub fn weapon(mut self, weapon: Weapon) -> Self {
+        self.unit.equipment.weapon = Some(weapon);
+        self
+    }
+
+    pub fn armor(mut self, armor: Armor) -> Self {
+        self.unit.equipment.armor = Some(armor);
+        self
+    }
+
+    pub fn ability(mut self, ability: Ability) -> Self {
+        self.unit.abilities.push(ability);
+        self
+    }
+
+    pub fn with_trait(mut self, t: Trait) -> Self {
+        self.unit.traits.push(t);
+        self
+    }
+
+    pub fn movement(mut self, movement_type: MovementType) -> Self {
+        self.unit.movement_type = movement_type;
+        self
+    }
+
+    pub fn at(mut self, x: usize, y: usize) -> Self {
+        self.unit.grid_position = Position { x, y };
+        self
+    }
+
+    pub fn facing(mut self, facing: Facing) -> Self {
+        self.unit.facing = facing;
+        self
+    }
+
+    pub fn vehicle(mut self, vehicle: Vehicle) -> Self {
+        self.unit.vehicle = Some(vehicle);
+        self
+    }
+
+    pub fn sprite(mut self, sprite_id: &str) -> Self {
+        self.unit.sprite_id = sprite_id.to_string();
+        self
+    }
+
+    pub fn build(mut self) -> Unit {
+        self.unit.recalculate_stats();
+        self.unit.health_points = self.unit.current_stats.max_health;
+        self.unit.action_points = self.unit.current_stats.max_action;
+        self.unit
+    }
+}
+
+impl Weapon {
+    /// Shorthand for [`WeaponBuilder::new`].
+    pub fn builder(id: &str) -> WeaponBuilder {
+        WeaponBuilder::new(id)
+    }
+}
+
+impl Ability {
+    /// Shorthand for [`AbilityBuilder::new`].
+    pub fn builder(id: &str, ability_type: AbilityType) -> AbilityBuilder {
+        AbilityBuilder::new(id, ability_type)
+    }
+}
+
+impl Unit {
+    /// Shorthand for [`UnitBuilder::new`].
+    pub fn builder(id: &str, unit_type: UnitType, faction: Faction) -> UnitBuilder {
+        UnitBuilder::new(id, unit_type, faction)
+    }
+}
@@ -0,0 +1,78 @@
+use std::collections::BTreeSet;
+
+use serde::{Deserialize, Serialize};
+
+use crate::doctrine::Doctrine;
+use crate::events::EventRecord;
+use crate::models::Unit;
+use crate::overworld::OverworldState;
+use crate::research::ResearchState;
+use crate::stats::CampaignStats;
+
+/// Progress that persists between missions.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Campaign {
+    /// Story flags set by dialogue choices and mission outcomes.
+    pub flags: BTreeSet<String>,
+    /// Item ids held by the campaign, not yet equipped on a unit.
+    pub inventory: Vec<String>,
+    /// Units available for deployment.
+    pub roster: Vec<Unit>,
+    /// Requisition points available to spend between missions.
+    pub currency: u32,
+    pub missions_completed: u32,
+    /// Doctrines adopted so far, applied to every encounter.
+    pub doctrines: Vec<Doctrine>,
+    /// Totals over every battle fought.
+    #[serde(default)]
+    pub stats: CampaignStats,
+    /// Medbay upgrades bought; each speeds recovery from injuries.
+    #[serde(default)]
+    pub medbay_level: u32,
+    /// Armory upgrades bought; each unlocks a higher weapon tier.
+    #[serde(default)]
+    pub armory_level: u32,
+    /// Chapel upgrades bought; each restores more morale between missions.
+    #[serde(default)]
+    pub chapel_level: u32,
+    /// Iron-man campaigns keep one save, written after every action, and
+    /// can't load earlier ones.
+    #[serde(default)]
+    pub ironman: bool,
+    /// Squad morale, raised and lowered by campaign events.
+    #[serde(default)]
+    pub morale: i32,
+    /// Event drawn between missions and not yet answered.
+    #[serde(default)]
+    pub pending_event: Option<String>,
+    /// Events answered so far, oldest first.
+    #[serde(default)]
+    pub event_log: Vec<EventRecord>,
+    /// The squad's whereabouts on the overworld.
+    #[serde(default)]
+    pub overworld: OverworldState,
+    /// Research finished and under way.
+    #[serde(default)]
+    pub research: ResearchState,
+    /// Keys of the codex entries unlocked so far; see [`crate::codex`].
+    #[serde(default)]
+    pub codex: BTreeSet<String>,
+}
+
+impl Campaign {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn set_flag(&mut self, flag: &str) {
+        self.flags.insert(flag.to_string());
+    }
+
+    pub fn has_flag(&self, flag: &str) -> bool {
+        self.flags.contains(flag)
+    }
+
+    pub fn grant_item(&mut self, item_id: &str) {
+        self.inventory.push(item_id.to_string());
+    }
+}
@@ -0,0 +1,263 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::combat::{tick_cooldowns, CombatEncounter, CombatError};
+use crate::command::Command;
+use crate::ioutil::invalid;
+use crate::models::{Armor, Faction, Position, Stats, Trait, Unit, UnitType, Weapon};
+use crate::registry::Side;
+use crate::rng::GameRng;
+use crate::scenario::Scenario;
+use crate::simulation::BattleOutcome;
+
+/// Where the local leaderboard is kept between sessions.
+pub const LEADERBOARD_PATH: &str = "leaderboard.json";
+/// Entries kept per challenge.
+pub const LEADERBOARD_SIZE: usize = 10;
+/// Score for each round left under par when the challenge is won.
+pub const POINTS_PER_ROUND: u32 = 100;
+/// Score for each hit point the squad has left.
+pub const POINTS_PER_HP: u32 = 10;
+
+/// A unit of a challenge's fixed squads.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChallengeUnit {
+    pub id: String,
+    pub name: String,
+    pub unit_type: UnitType,
+    pub faction: Faction,
+    pub position: Position,
+    pub base_stats: Stats,
+    #[serde(default)]
+    pub weapon: Option<Weapon>,
+    #[serde(default)]
+    pub armor: Option<Armor>,
+}
+
+impl ChallengeUnit {
+    pub fn spawn(&self) -> Unit {
+        let mut unit = Unit::new(&self.id, &self.name, self.unit_type.clone(), self.faction.clone());
+        unit.base_stats = self.base_stats.clone();
+        unit.traits = Trait::for_unit_type(&self.unit_type);
+        unit.grid_position = self.position.clone();
+        unit.equipment.weapon = self.weapon.clone();
+        unit.equipment.armor = self.armor.clone();
+        unit.recalculate_stats();
+        unit.health_points = unit.current_stats.max_health;
+        unit.action_points = unit.current_stats.max_action;
+        unit
+    }
+}
+
+/// A puzzle mission: fixed map, squads and RNG seed, so every attempt
+/// plays out the same way for the same commands. It must be won within
+/// `par_rounds`; `name` and `description` are locale keys.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChallengeMission {
+    pub id: String,
+    pub name: String,
+    pub description: String,
+    pub seed: u64,
+    pub par_rounds: u32,
+    pub scenario: Scenario,
+    pub squad: Vec<ChallengeUnit>,
+    pub enemies: Vec<ChallengeUnit>,
+}
+
+/// How well a won challenge went.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChallengeScore {
+    pub rounds: u32,
+    /// Health left across the squad's living units.
+    pub hp_remaining: i32,
+    /// [`POINTS_PER_ROUND`] per round under par, counting the par round
+    /// itself, plus [`POINTS_PER_HP`] per hit point left.
+    pub points: u32,
+}
+
+impl ChallengeMission {
+    pub fn load<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        Self::from_json(&fs::read_to_string(path)?)
+    }
+
+    /// Parse a challenge, rejecting ones without a par or without a unit
+    /// on either side.
+    pub fn from_json(data: &str) -> io::Result<Self> {
+        let challenge: ChallengeMission = serde_json::from_str(data).map_err(invalid)?;
+        if challenge.par_rounds == 0 {
+            return Err(invalid(format!("challenge '{}' has no par", challenge.id)));
+        }
+        if challenge.squad.is_empty() || challenge.enemies.is_empty() {
+            return Err(invalid(format!("challenge '{}' needs units on both sides", challenge.id)));
+        }
+        Ok(challenge)
+    }
+
+    /// Start an attempt, up to the squad's first turn.
+    pub fn start(&self) -> ChallengeRun {
+        let squad = self.squad.iter().map(ChallengeUnit::spawn).collect();
+        let enemies = self.enemies.iter().map(ChallengeUnit::spawn).collect();
+        let mut encounter = self.scenario.encounter(squad, enemies, None);
+        encounter.rng = GameRng::new(self.seed);
+        let mut run = ChallengeRun { par_rounds: self.par_rounds, encounter };
+        run.advance();
+        run
+    }
+
+    /// Replay `commands` from the start and score the result, so a
+    /// leaderboard entry can be checked against its recorded commands.
+    /// A refused command fails with its [`CombatError::key`].
+    pub fn verify(&self, commands: &[Command]) -> Result<ChallengeScore, &'static str> {
+        let mut run = self.start();
+        for cmd in commands {
+            run.execute(cmd.clone()).map_err(|e| e.key())?;
+        }
+        run.score().ok_or("challenge not won")
+    }
+}
+
+/// One attempt at a [`ChallengeMission`]. Enemy turns play out by
+/// themselves, rolling on the encounter's seeded RNG, so only the squad's
+/// commands need recording.
+#[derive(Debug, Clone)]
+pub struct ChallengeRun {
+    pub par_rounds: u32,
+    pub encounter: CombatEncounter,
+}
+
+impl ChallengeRun {
+    fn side_alive(&self, side: Side) -> bool {
+        self.encounter.units.on_side(side).any(|u| u.health_points > 0)
+    }
+
+    /// The result once the battle is decided. Going past par without a
+    /// win counts as a draw.
+    pub fn outcome(&self) -> Option<BattleOutcome> {
+        if !self.side_alive(Side::Player) {
+            Some(BattleOutcome::EnemyVictory)
+        } else if !self.side_alive(Side::Enemy) {
+            Some(BattleOutcome::PlayerVictory)
+        } else if self.encounter.turn_order.round_number > self.par_rounds {
+            Some(BattleOutcome::Draw)
+        } else {
+            None
+        }
+    }
+
+    /// Execute a squad command; ending or delaying the turn plays the
+    /// enemy turns up to the squad's next one.
+    pub fn execute(&mut self, cmd: Command) -> Result<(), CombatError> {
+        if self.outcome().is_some() {
+            return Err(CombatError::WrongPhase);
+        }
+        let end_turn = cmd == Command::EndTurn;
+        let delay = cmd == Command::Delay;
+        self.encounter.execute(cmd)?;
+        if end_turn
+            && let Some(unit) = self.encounter.turn_order.current_unit_id.clone().and_then(|id| self.encounter.unit_by_id_mut(&id))
+        {
+            tick_cooldowns(unit);
+        }
+        if end_turn || delay {
+            self.advance();
+        }
+        Ok(())
+    }
+
+    /// Start turns until a living squad unit is up or the battle is
+    /// decided, letting the AI act for enemies.
+    pub(crate) fn advance(&mut self) {
+        while self.outcome().is_none() {
+            self.encounter.start_turn();
+            let Some(id) = self.encounter.turn_order.current_unit_id.clone() else { return };
+            let Some(handle) = self.encounter.units.id(&id) else { return };
+            let side = self.encounter.units.side(handle);
+            let unit = &mut self.encounter.units[handle];
+            if unit.health_points <= 0 {
+                self.encounter.end_turn();
+                continue;
+            }
+            unit.action_points = unit.current_stats.max_action;
+            if side == Some(Side::Player) {
+                return;
+            }
+            let roll = self.encounter.rng.roll_d100();
+            self.encounter.ai_take_action(roll);
+            self.encounter.end_turn();
+            if let Some(unit) = self.encounter.unit_by_id_mut(&id) {
+                tick_cooldowns(unit);
+            }
+        }
+    }
+
+    /// The score, once the challenge is won.
+    pub fn score(&self) -> Option<ChallengeScore> {
+        if self.outcome() != Some(BattleOutcome::PlayerVictory) {
+            return None;
+        }
+        let rounds = self.encounter.turn_order.round_number;
+        let hp_remaining: i32 = self.encounter.player_units().map(|u| u.health_points.max(0)).sum();
+        let points = (self.par_rounds + 1).saturating_sub(rounds) * POINTS_PER_ROUND + hp_remaining as u32 * POINTS_PER_HP;
+        Some(ChallengeScore { rounds, hp_remaining, points })
+    }
+
+    /// A leaderboard entry for `player`, once the challenge is won.
+    pub fn entry(&self, player: &str) -> Option<LeaderboardEntry> {
+        let score = self.score()?;
+        Some(LeaderboardEntry { player: player.to_string(), score, commands: self.encounter.command_log.clone() })
+    }
+}
+
+/// A score on the leaderboard with the commands that earned it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LeaderboardEntry {
+    pub player: String,
+    pub score: ChallengeScore,
+    pub commands: Vec<Command>,
+}
+
+/// Best scores per challenge id, highest first, kept in a local file.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Leaderboard {
+    pub challenges: BTreeMap<String, Vec<LeaderboardEntry>>,
+}
+
+impl Leaderboard {
+    /// Load the leaderboard; a missing file starts it empty.
+    pub fn load<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        match fs::read_to_string(path) {
+            Ok(data) => serde_json::from_str(&data).map_err(invalid),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(e),
+        }
+    }
+
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        fs::write(path, serde_json::to_string_pretty(self).map_err(invalid)?)
+    }
+
+    pub fn entries(&self, challenge_id: &str) -> &[LeaderboardEntry] {
+        self.challenges.get(challenge_id).map_or(&[], Vec::as_slice)
+    }
+
+    /// Add `entry` for `challenge`, after replaying its commands to check
+    /// the score. Returns its rank from 0, or `None` if it didn't make the
+    /// top [`LEADERBOARD_SIZE`]. Ties go to the earlier entry.
+    pub fn submit(&mut self, challenge: &ChallengeMission, entry: LeaderboardEntry) -> Result<Option<usize>, &'static str> {
+        if challenge.verify(&entry.commands)? != entry.score {
+            return Err("score does not match replay");
+        }
+        let entries = self.challenges.entry(challenge.id.clone()).or_default();
+        let rank = entries.iter().position(|e| e.score.points < entry.score.points).unwrap_or(entries.len());
+        if rank >= LEADERBOARD_SIZE {
+            return Ok(None);
+        }
+        entries.insert(rank, entry);
+        entries.truncate(LEADERBOARD_SIZE);
+        Ok(Some(rank))
+    }
+}
@@ -0,0 +1,135 @@
+use serde::{Deserialize, Serialize};
+
+use crate::combat::{apply_ability_effect, hp_change_event, CombatEncounter, CombatEvent};
+use crate::models::{EffectType, Position, Unit};
+use crate::registry::UnitId;
+
+/// How an ability plays out over the caster's turns after it is used.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Channel {
+    /// Builds up over `turns` of the caster's turns and goes off at the
+    /// start of the last. The caster can do nothing else meanwhile.
+    Charged { turns: u32 },
+    /// Goes off when used and again at the start of each of the caster's
+    /// next `turns` turns.
+    Sustained { turns: u32 },
+}
+
+/// A channelled ability in progress on its caster. Taking damage or being
+/// stunned breaks it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum AbilityState {
+    Charging { ability_id: String, target_ids: Vec<String>, turns_left: u32 },
+    Channelling { ability_id: String, target_ids: Vec<String>, turns_left: u32 },
+}
+
+impl AbilityState {
+    /// The state a caster is left in after using an ability with
+    /// `channel` on `targets`.
+    pub(crate) fn begin(channel: Channel, ability_id: &str, targets: &[&mut Unit]) -> Self {
+        let ability_id = ability_id.to_string();
+        let target_ids = targets.iter().map(|t| t.id.clone()).collect();
+        match channel {
+            Channel::Charged { turns } => AbilityState::Charging { ability_id, target_ids, turns_left: turns },
+            Channel::Sustained { turns } => AbilityState::Channelling { ability_id, target_ids, turns_left: turns },
+        }
+    }
+
+    pub fn ability_id(&self) -> &str {
+        match self {
+            AbilityState::Charging { ability_id, .. } | AbilityState::Channelling { ability_id, .. } => ability_id,
+        }
+    }
+
+    pub fn target_ids(&self) -> &[String] {
+        match self {
+            AbilityState::Charging { target_ids, .. } | AbilityState::Channelling { target_ids, .. } => target_ids,
+        }
+    }
+}
+
+impl Unit {
+    /// Whether the unit is busy charging an ability.
+    pub fn is_charging(&self) -> bool {
+        matches!(self.ability_state, Some(AbilityState::Charging { .. }))
+    }
+}
+
+impl CombatEncounter {
+    /// Break the channels of casters hurt since event `since`, and of any
+    /// that are stunned or down.
+    pub(crate) fn interrupt_channels(&mut self, since: usize) {
+        let hurt: Vec<String> = self.events[since..]
+            .iter()
+            .filter_map(|e| match e {
+                CombatEvent::UnitDamaged { unit_id, .. } => Some(unit_id.clone()),
+                _ => None,
+            })
+            .collect();
+        let mut interrupted = Vec::new();
+        for unit in self.units.iter_mut() {
+            let stunned = unit.status_effects.iter().any(|s| s.effect_type == EffectType::Stun && s.remaining_turns > 0);
+            if unit.ability_state.is_some() && (stunned || unit.health_points <= 0 || hurt.contains(&unit.id)) {
+                let state = unit.ability_state.take().expect("checked above");
+                interrupted.push(CombatEvent::ChannelInterrupted {
+                    unit_id: unit.id.clone(),
+                    ability_id: state.ability_id().to_string(),
+                });
+            }
+        }
+        self.events.extend(interrupted);
+    }
+
+    /// Carry the active unit's channel on at the start of its turn: a
+    /// charge counts down and goes off at zero, a sustained channel goes
+    /// off again until its turns run out.
+    pub(crate) fn advance_channel(&mut self, unit_id: &str) {
+        let Some(handle) = self.units.id(unit_id) else { return };
+        let caster = &mut self.units[handle];
+        let charging = caster.is_charging();
+        let (fire, turns_left) = match &mut caster.ability_state {
+            None => return,
+            Some(AbilityState::Charging { turns_left, .. }) => {
+                *turns_left = turns_left.saturating_sub(1);
+                (*turns_left == 0, *turns_left)
+            }
+            Some(AbilityState::Channelling { turns_left, .. }) => {
+                *turns_left = turns_left.saturating_sub(1);
+                (true, *turns_left)
+            }
+        };
+        if charging {
+            caster.action_points = 0;
+        }
+        let state = if turns_left == 0 { caster.ability_state.take() } else { caster.ability_state.clone() };
+        if fire && let Some(state) = state {
+            self.resolve_channel(handle, &state);
+        }
+    }
+
+    /// Apply the channelled ability's effect to its living targets.
+    fn resolve_channel(&mut self, caster: UnitId, state: &AbilityState) {
+        let handles: Vec<UnitId> = state.target_ids().iter().filter_map(|id| self.units.id(id)).collect();
+        let Some((caster, mut targets)) = self.units.split_mut(caster, &handles) else { return };
+        let Some(ability) = caster.abilities.iter().find(|a| a.id == state.ability_id()) else { return };
+        targets.retain(|t| t.health_points > 0);
+        if ability.area_of_effect.is_none() {
+            targets.truncate(1);
+        }
+        let mut events = Vec::new();
+        let mut cells: Vec<Position> = Vec::new();
+        for target in targets {
+            let before = target.health_points;
+            apply_ability_effect(&ability.effect, target);
+            self.stats.record_damage(Some(&caster.id), &target.id, before - target.health_points);
+            self.stats.record_kill(&caster.id, before, target.health_points);
+            events.extend(hp_change_event(&target.id, before, target.health_points, false));
+            cells.push(target.grid_position.clone());
+        }
+        let hazard = ability.effect.creates_hazard.clone();
+        self.events.extend(events);
+        if let Some(hazard) = hazard {
+            self.spawn_hazard(&hazard, &cells);
+        }
+    }
+}
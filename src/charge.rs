@@ -0,0 +1,97 @@
+use crate::combat::{manhattan, AttackModifiers, AttackResult, CombatEncounter, CombatError};
+use crate::grid::{find_path, move_ap_cost};
+use crate::models::{Faction, Position, Unit};
+use crate::stance::{Stance, BRACE_ACCURACY_PENALTY};
+
+/// How far off a target must be to charge it, for a run-up of three cells;
+/// closer targets are simply walked up to and attacked.
+pub const CHARGE_MIN_DISTANCE: u32 = 4;
+/// Extra damage from the momentum of a charge.
+pub const CHARGE_DAMAGE_BONUS: i32 = 1;
+/// Further damage for Orks, who live for the charge.
+pub const ORK_CHARGE_DAMAGE_BONUS: i32 = 1;
+/// Accuracy bonus for charging Orks.
+pub const ORK_CHARGE_ACCURACY_BONUS: i32 = 10;
+
+/// How a charge that went ahead ended.
+#[derive(Debug, Clone)]
+pub enum ChargeOutcome {
+    /// The charger reached the target and struck it.
+    Struck(AttackResult),
+    /// Overwatch cut the charger down on its way in.
+    CutDown,
+}
+
+/// Bonuses the unit gets on the melee attack that ends its charge. The
+/// `Charging` condition applies on top, through the faction's stack.
+pub fn charge_bonus(unit: &Unit) -> AttackModifiers {
+    let mut bonus = AttackModifiers { damage: CHARGE_DAMAGE_BONUS, ..AttackModifiers::default() };
+    if unit.faction == Faction::Ork {
+        bonus.damage += ORK_CHARGE_DAMAGE_BONUS;
+        bonus.accuracy += ORK_CHARGE_ACCURACY_BONUS;
+    }
+    bonus
+}
+
+impl CombatEncounter {
+    /// The free cell next to the target where a charge would end: the
+    /// cheapest to reach while keeping the AP for the strike.
+    pub fn charge_destination(&self, attacker_id: &str, target_id: &str) -> Result<Position, CombatError> {
+        let attacker = self.unit_by_id(attacker_id).ok_or(CombatError::UnknownUnit)?;
+        let target = self.unit_by_id(target_id).filter(|t| t.health_points > 0 && t.embarked_in.is_none()).ok_or(CombatError::InvalidTarget)?;
+        let weapon = attacker.equipment.weapon.as_ref().filter(|w| w.is_melee()).ok_or(CombatError::NoMeleeWeapon)?;
+        if manhattan(&attacker.grid_position, &target.grid_position) < CHARGE_MIN_DISTANCE {
+            return Err(CombatError::TooCloseToCharge);
+        }
+        if attacker.action_points < weapon.action_point_cost {
+            return Err(CombatError::InsufficientAp);
+        }
+        let bonus = self.movement_bonus(attacker);
+        self.battlefield
+            .neighbours(&target.grid_position)
+            .into_iter()
+            .filter(|cell| !self.units.iter().any(|u| u.health_points > 0 && u.grid_position == *cell))
+            .filter_map(|cell| {
+                let cost = find_path(attacker, &cell, &self.battlefield, bonus, self.weather).cost?;
+                let ap = move_ap_cost(attacker, &self.battlefield, bonus, cost)?;
+                (ap + weapon.action_point_cost <= attacker.action_points).then_some((cost, cell))
+            })
+            .min_by_key(|(cost, _)| *cost)
+            .map(|(_, cell)| cell)
+            .ok_or(CombatError::OutOfRange)
+    }
+
+    /// Whether the AI should charge the target this turn: it can, the
+    /// target isn't braced for it, and no ability reaches the target from
+    /// where it stands.
+    pub fn ai_should_charge(&self, unit_id: &str, target_id: &str) -> bool {
+        let (Some(unit), Some(target)) = (self.unit_by_id(unit_id), self.unit_by_id(target_id)) else { return false };
+        let distance = manhattan(&unit.grid_position, &target.grid_position);
+        let ability_in_range = unit
+            .abilities
+            .iter()
+            .any(|a| a.current_cooldown == 0 && a.action_point_cost <= unit.action_points && distance <= a.range);
+        let braced = target.stance == Some(Stance::Brace);
+        !ability_in_range && !braced && self.charge_destination(unit_id, target_id).is_ok()
+    }
+
+    /// Charge: move next to the target and strike it with a melee attack
+    /// carrying the charge bonuses, or a penalty if the target is braced.
+    /// Nothing happens unless both can; overwatch may still cut the
+    /// charger down on its way in.
+    pub fn charge(&mut self, attacker_id: &str, target_id: &str, roll: u8) -> Result<ChargeOutcome, CombatError> {
+        let destination = self.charge_destination(attacker_id, target_id)?;
+        self.walk(attacker_id, &destination)?;
+        self.trigger_overwatch(attacker_id);
+        if self.unit_by_id(attacker_id).is_none_or(|u| u.health_points <= 0) {
+            return Ok(ChargeOutcome::CutDown);
+        }
+        let braced = self.unit_by_id(target_id).is_some_and(|t| t.stance == Some(Stance::Brace));
+        let bonus = if braced {
+            AttackModifiers { accuracy: BRACE_ACCURACY_PENALTY, ..AttackModifiers::default() }
+        } else {
+            self.unit_by_id(attacker_id).map(charge_bonus).unwrap_or_default()
+        };
+        self.attack_unit(attacker_id, target_id, roll, bonus).map(ChargeOutcome::Struck)
+    }
+}
@@ -0,0 +1,257 @@
+use std::collections::BTreeSet;
+
+use serde::{Deserialize, Serialize};
+
+use crate::campaign::Campaign;
+use crate::content::{ContentDatabase, Item};
+use crate::localization::Localizer;
+use crate::models::{Ability, Armor, EffectType, Trait, Unit, UnitType, Weapon};
+
+const UNIT_TYPES: [UnitType; 12] = [
+    UnitType::SpaceMarine,
+    UnitType::Guardsman,
+    UnitType::Commissar,
+    UnitType::TechPriest,
+    UnitType::OrkBoy,
+    UnitType::OrkNob,
+    UnitType::Weirdboy,
+    UnitType::Cultist,
+    UnitType::ChaosMarine,
+    UnitType::Daemon,
+    UnitType::Chimera,
+    UnitType::Trukk,
+];
+
+const STATUS_EFFECTS: [EffectType; 7] = [
+    EffectType::Poison,
+    EffectType::Stun,
+    EffectType::Shield,
+    EffectType::Suppression,
+    EffectType::Burning,
+    EffectType::Pinned,
+    EffectType::Taunted,
+];
+
+fn unit_type_id(unit_type: &UnitType) -> &'static str {
+    match unit_type {
+        UnitType::SpaceMarine => "space_marine",
+        UnitType::Guardsman => "guardsman",
+        UnitType::Commissar => "commissar",
+        UnitType::TechPriest => "tech_priest",
+        UnitType::OrkBoy => "ork_boy",
+        UnitType::OrkNob => "ork_nob",
+        UnitType::Weirdboy => "weirdboy",
+        UnitType::Cultist => "cultist",
+        UnitType::ChaosMarine => "chaos_marine",
+        UnitType::Daemon => "daemon",
+        UnitType::Chimera => "chimera",
+        UnitType::Trukk => "trukk",
+    }
+}
+
+fn effect_id(effect: &EffectType) -> &'static str {
+    match effect {
+        EffectType::Poison => "poison",
+        EffectType::Stun => "stun",
+        EffectType::Shield => "shield",
+        EffectType::Suppression => "suppression",
+        EffectType::Burning => "burning",
+        EffectType::Pinned => "pinned",
+        EffectType::Taunted => "taunted",
+    }
+}
+
+/// The sections of the codex, in the order they are listed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum CodexCategory {
+    UnitType,
+    Weapon,
+    Armor,
+    Ability,
+    StatusEffect,
+}
+
+impl CodexCategory {
+    pub const ALL: [CodexCategory; 5] = [
+        CodexCategory::UnitType,
+        CodexCategory::Weapon,
+        CodexCategory::Armor,
+        CodexCategory::Ability,
+        CodexCategory::StatusEffect,
+    ];
+
+    /// Localization key for the section's heading.
+    pub fn label_key(&self) -> &'static str {
+        match self {
+            CodexCategory::UnitType => "codex.units",
+            CodexCategory::Weapon => "codex.weapons",
+            CodexCategory::Armor => "codex.armor",
+            CodexCategory::Ability => "codex.abilities",
+            CodexCategory::StatusEffect => "codex.status_effects",
+        }
+    }
+
+    fn prefix(&self) -> &'static str {
+        match self {
+            CodexCategory::UnitType => "unit",
+            CodexCategory::Weapon => "weapon",
+            CodexCategory::Armor => "armor",
+            CodexCategory::Ability => "ability",
+            CodexCategory::StatusEffect => "status",
+        }
+    }
+}
+
+/// One page of the codex. Name, lore and stat values are locale keys or,
+/// for content that carries its own text, shown as they are.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CodexEntry {
+    pub category: CodexCategory,
+    pub id: String,
+    pub name: String,
+    pub lore: String,
+    /// Stat label keys with their values.
+    pub stats: Vec<(&'static str, String)>,
+}
+
+impl CodexEntry {
+    /// The key the entry is unlocked under, e.g. `weapon:lasgun`.
+    pub fn key(&self) -> String {
+        entry_key(self.category, &self.id)
+    }
+
+    fn unit_type(unit_type: &UnitType) -> Self {
+        let id = unit_type_id(unit_type);
+        let stats = Trait::for_unit_type(unit_type).iter().map(|t| ("codex.stat.trait", t.label_key().to_string())).collect();
+        Self::keyed(CodexCategory::UnitType, id, stats)
+    }
+
+    fn status_effect(effect: &EffectType) -> Self {
+        Self::keyed(CodexCategory::StatusEffect, effect_id(effect), Vec::new())
+    }
+
+    /// An entry whose name and lore live in the locale files as
+    /// `codex.<section>.<id>` and `codex.<section>.<id>.lore`.
+    fn keyed(category: CodexCategory, id: &str, stats: Vec<(&'static str, String)>) -> Self {
+        let name = format!("codex.{}.{}", category.prefix(), id);
+        Self { category, id: id.to_string(), lore: format!("{name}.lore"), name, stats }
+    }
+
+    fn weapon(weapon: &Weapon) -> Self {
+        let mut stats = vec![
+            ("codex.stat.damage", weapon.damage.to_string()),
+            ("codex.stat.damage_type", weapon.damage_type.label_key().to_string()),
+            ("codex.stat.range", weapon.range.to_string()),
+            ("codex.stat.accuracy", format!("{:.0}%", weapon.accuracy * 100.0)),
+            ("codex.stat.critical", format!("{:.0}%", weapon.critical_chance * 100.0)),
+            ("codex.stat.ap_cost", weapon.action_point_cost.to_string()),
+        ];
+        if let Some(indirect) = &weapon.indirect {
+            stats.push(("codex.stat.min_range", indirect.min_range().to_string()));
+        }
+        Self { name: weapon.name.clone(), ..Self::keyed(CodexCategory::Weapon, &weapon.id, stats) }
+    }
+
+    fn armor(armor: &Armor) -> Self {
+        let stats = vec![
+            ("codex.stat.toughness", format!("+{}", armor.toughness_bonus)),
+            ("codex.stat.agility", format!("-{}", armor.agility_penalty)),
+        ];
+        Self { name: armor.name.clone(), ..Self::keyed(CodexCategory::Armor, &armor.id, stats) }
+    }
+
+    fn ability(ability: &Ability) -> Self {
+        let stats = vec![
+            ("codex.stat.ap_cost", ability.action_point_cost.to_string()),
+            ("codex.stat.cooldown", ability.cooldown.to_string()),
+            ("codex.stat.range", ability.range.to_string()),
+        ];
+        Self {
+            category: CodexCategory::Ability,
+            id: ability.id.clone(),
+            name: ability.name.clone(),
+            lore: ability.description.clone(),
+            stats,
+        }
+    }
+}
+
+fn entry_key(category: CodexCategory, id: &str) -> String {
+    format!("{}:{}", category.prefix(), id)
+}
+
+/// What the codex lists: a section, or all, narrowed to entries whose
+/// localized name contains `query`, ignoring case.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CodexFilter {
+    pub category: Option<CodexCategory>,
+    pub query: String,
+}
+
+impl CodexFilter {
+    /// Whether `entry`, called `name` in the current language, passes.
+    /// Locked entries only match an empty query, so searching gives
+    /// nothing away.
+    pub fn matches(&self, entry: &CodexEntry, name: &str, unlocked: &BTreeSet<String>) -> bool {
+        let query = self.query.trim().to_lowercase();
+        self.category.is_none_or(|c| c == entry.category)
+            && (query.is_empty() || (unlocked.contains(&entry.key()) && name.to_lowercase().contains(&query)))
+    }
+}
+
+/// Every entry the game's content has, sorted by section and id.
+#[derive(Debug, Clone, Default)]
+pub struct Codex {
+    pub entries: Vec<CodexEntry>,
+}
+
+impl Codex {
+    /// Build the codex from every unit type and status effect, and the
+    /// weapons, armor and abilities in `db`, including those heroes have.
+    pub fn from_content(db: &ContentDatabase) -> Self {
+        let mut entries: Vec<CodexEntry> = UNIT_TYPES.iter().map(CodexEntry::unit_type).collect();
+        for entry in db.items.values() {
+            match &entry.item {
+                Item::Weapon(w) => entries.push(CodexEntry::weapon(w)),
+                Item::Armor(a) => entries.push(CodexEntry::armor(a)),
+                Item::Accessory(_) => {}
+            }
+        }
+        let abilities = db.heroes.values().flat_map(|h| h.abilities.iter().chain(h.tree.iter().flat_map(|b| b.nodes.iter().map(|n| &n.ability))));
+        entries.extend(abilities.map(CodexEntry::ability));
+        entries.extend(STATUS_EFFECTS.iter().map(CodexEntry::status_effect));
+        entries.sort_by(|a, b| (a.category, &a.id).cmp(&(b.category, &b.id)));
+        entries.dedup_by(|a, b| a.key() == b.key());
+        Self { entries }
+    }
+
+    pub fn get(&self, category: CodexCategory, id: &str) -> Option<&CodexEntry> {
+        self.entries.iter().find(|e| e.category == category && e.id == id)
+    }
+
+    /// Entries passing `filter`, with names looked up in `loc`.
+    pub fn search(&self, filter: &CodexFilter, unlocked: &BTreeSet<String>, loc: &Localizer) -> Vec<&CodexEntry> {
+        self.entries.iter().filter(|e| filter.matches(e, &loc.get(&e.name), unlocked)).collect()
+    }
+}
+
+/// Codex keys for everything `unit` shows: its type, gear, abilities and
+/// the status effects on it.
+pub fn sightings(unit: &Unit) -> Vec<String> {
+    let mut keys = vec![entry_key(CodexCategory::UnitType, unit_type_id(&unit.unit_type))];
+    keys.extend(unit.equipment.weapon.iter().map(|w| entry_key(CodexCategory::Weapon, &w.id)));
+    keys.extend(unit.equipment.armor.iter().map(|a| entry_key(CodexCategory::Armor, &a.id)));
+    keys.extend(unit.abilities.iter().map(|a| entry_key(CodexCategory::Ability, &a.id)));
+    keys.extend(unit.status_effects.iter().map(|s| entry_key(CodexCategory::StatusEffect, effect_id(&s.effect_type))));
+    keys
+}
+
+impl Campaign {
+    /// Unlock the codex entries for everything `units` show. Returns how
+    /// many were new.
+    pub fn record_sightings<'a>(&mut self, units: impl IntoIterator<Item = &'a Unit>) -> usize {
+        let before = self.codex.len();
+        self.codex.extend(units.into_iter().flat_map(sightings));
+        self.codex.len() - before
+    }
+}
@@ -1,46 +1,281 @@
-use crate::models::{AnimationType, Unit, Weapon, AbilityEffect, StatsModifier};
+use crate::actions::ActionQueue;
+use crate::balance::BalanceConfig;
+use crate::channel::{AbilityState, Channel};
+use crate::damage::{inflict, mitigate, resist, DamageType};
+use crate::debrief::Objective;
+use crate::interrupts::ScriptedInterrupt;
+use crate::loot::Loot;
+use crate::models::{AnimationType, EffectType, Unit, Weapon, AbilityEffect};
 use serde::{Serialize, Deserialize};
 
 #[derive(Debug, Clone)]
 pub struct AttackResult {
     pub hit: bool,
     pub damage: i32,
+    pub critical: bool,
+    /// The weapon jammed, wasting the attack.
+    pub jammed: bool,
 }
 
-/// Resolve a weapon attack from attacker to defender.
-pub fn resolve_attack(attacker: &mut Unit, weapon: &Weapon, defender: &mut Unit, roll: u8, cover_bonus: i32) -> AttackResult {
+/// Why a combat action was refused. [`CombatError::key`] is the locale key
+/// of a message to show the player.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CombatError {
+    InsufficientAp,
+    OnCooldown,
+    OutOfRange,
+    /// No such unit to act on, or one that can't be, like a dead one.
+    InvalidTarget,
+    /// Terrain blocks the shot.
+    NoLineOfSight,
+    /// Not the player's turn, or the battle is over.
+    WrongPhase,
+    /// The acting unit doesn't exist, or is dead.
+    UnknownUnit,
+    /// The unit belongs to another player or the AI.
+    NotControlled,
+    NoWeapon,
+    /// Pinned units can't attack or use abilities.
+    Pinned,
+    Unreachable,
+    /// The unit is charging an ability and can't act.
+    AbilityCharging,
+    /// Passengers can only disembark.
+    Embarked,
+    InvalidAbility,
+    /// The ability is aimed at units, not cells.
+    NeedsUnitTarget,
+    /// Only a unit that hasn't acted yet can delay its turn.
+    AlreadyActed,
+    NobodyToWaitFor,
+    NoMeleeWeapon,
+    TooCloseToCharge,
+    MeleeCantSuppress,
+    NoGrenade,
+    /// The weapon is too light to damage terrain.
+    WeaponTooLight,
+    NothingToPickUp,
+    NotAVehicle,
+    /// The unit is a vehicle or already aboard one.
+    CantBoard,
+    VehicleFull,
+    NotAboard,
+    NoSuchHardpoint,
+    /// Each hardpoint fires once a turn.
+    HardpointFired,
+    /// A hull mount only fires into the front arc.
+    OutsideArc,
+    NotIndirect,
+    /// The target is inside an indirect weapon's minimum range.
+    TargetTooClose,
+    /// Indirect weapons are aimed at a cell, not a unit.
+    FiresAtCells,
+    /// The tutorial is waiting on a different action.
+    TutorialStep,
+    /// No unit template goes by that id.
+    UnknownTemplate,
+}
+
+impl CombatError {
+    pub fn key(&self) -> &'static str {
+        match self {
+            CombatError::InsufficientAp => "combat_error.insufficient_ap",
+            CombatError::OnCooldown => "combat_error.on_cooldown",
+            CombatError::OutOfRange => "combat_error.out_of_range",
+            CombatError::InvalidTarget => "combat_error.invalid_target",
+            CombatError::NoLineOfSight => "combat_error.no_line_of_sight",
+            CombatError::WrongPhase => "combat_error.wrong_phase",
+            CombatError::UnknownUnit => "combat_error.unknown_unit",
+            CombatError::NotControlled => "combat_error.not_controlled",
+            CombatError::NoWeapon => "combat_error.no_weapon",
+            CombatError::Pinned => "combat_error.pinned",
+            CombatError::Unreachable => "combat_error.unreachable",
+            CombatError::AbilityCharging => "combat_error.ability_charging",
+            CombatError::Embarked => "combat_error.embarked",
+            CombatError::InvalidAbility => "combat_error.invalid_ability",
+            CombatError::NeedsUnitTarget => "combat_error.needs_unit_target",
+            CombatError::AlreadyActed => "combat_error.already_acted",
+            CombatError::NobodyToWaitFor => "combat_error.nobody_to_wait_for",
+            CombatError::NoMeleeWeapon => "combat_error.no_melee_weapon",
+            CombatError::TooCloseToCharge => "combat_error.too_close_to_charge",
+            CombatError::MeleeCantSuppress => "combat_error.melee_cant_suppress",
+            CombatError::NoGrenade => "combat_error.no_grenade",
+            CombatError::WeaponTooLight => "combat_error.weapon_too_light",
+            CombatError::NothingToPickUp => "combat_error.nothing_to_pick_up",
+            CombatError::NotAVehicle => "combat_error.not_a_vehicle",
+            CombatError::CantBoard => "combat_error.cant_board",
+            CombatError::VehicleFull => "combat_error.vehicle_full",
+            CombatError::NotAboard => "combat_error.not_aboard",
+            CombatError::NoSuchHardpoint => "combat_error.no_such_hardpoint",
+            CombatError::HardpointFired => "combat_error.hardpoint_fired",
+            CombatError::OutsideArc => "combat_error.outside_arc",
+            CombatError::NotIndirect => "combat_error.not_indirect",
+            CombatError::TargetTooClose => "combat_error.target_too_close",
+            CombatError::FiresAtCells => "combat_error.fires_at_cells",
+            CombatError::TutorialStep => "combat_error.tutorial_step",
+            CombatError::UnknownTemplate => "combat_error.unknown_template",
+        }
+    }
+}
+
+impl std::fmt::Display for CombatError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.key())
+    }
+}
+
+impl std::error::Error for CombatError {}
+
+/// Something that happened during combat, recorded for presentation layers
+/// (renderer effects, audio, UI) to react to after the simulation step.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum CombatEvent {
+    UnitDamaged { unit_id: String, amount: i32, critical: bool },
+    UnitHealed { unit_id: String, amount: i32 },
+    /// Control passed between two human players in hotseat play.
+    TurnHandedOff { from: u8, to: u8 },
+    /// A blast covering `cells` went off at `center`.
+    Explosion { center: Position, cells: Vec<Position> },
+    /// A shell `attacker_id` fired came down at `center`, its blast
+    /// covering `cells`; see [`crate::indirect`].
+    ShellImpact { attacker_id: String, center: Position, cells: Vec<Position> },
+    /// A map object at `position` was destroyed.
+    ObjectDestroyed { position: Position },
+    /// A unit moved into the trigger region named `region`.
+    RegionEntered { unit_id: String, region: String },
+    /// A unit moved out of the trigger region named `region`.
+    RegionExited { unit_id: String, region: String },
+    /// A unit picked up `loot` from its tile.
+    LootPickedUp { unit_id: String, loot: Vec<Loot> },
+    /// A unit's worn weapon jammed instead of firing.
+    WeaponJammed { unit_id: String },
+    /// A unit was caught under suppressing fire.
+    UnitSuppressed { unit_id: String },
+    /// A suppressed unit lost its nerve and is pinned for its turn.
+    UnitPinned { unit_id: String },
+    /// A unit on overwatch fired at `target_id` as it moved.
+    OverwatchTriggered { unit_id: String, target_id: String },
+    /// `target_id` set off a unit's held action; see [`crate::hold`].
+    HeldActionTriggered { unit_id: String, target_id: String },
+    /// A unit was hurt or stunned while channelling `ability_id`, which
+    /// fizzled.
+    ChannelInterrupted { unit_id: String, ability_id: String },
+    /// A weapon attack by `attacker_id` hit `target_id`. Comes before the
+    /// damage it did.
+    UnitAttacked { attacker_id: String, target_id: String, melee: bool },
+    /// A unit's reaction fired; see [`crate::reactions`].
+    UnitReacted { unit_id: String },
+    /// A unit was moved against its will from `from` to `to`; see
+    /// [`crate::displacement`].
+    UnitDisplaced { unit_id: String, from: Position, to: Position },
+}
+
+/// Situational bonuses from modifier stacks, added on top of unit stats
+/// when resolving an attack.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct AttackModifiers {
+    pub accuracy: i32,
+    pub damage: i32,
+    pub cover: i32,
+}
+
+impl AttackModifiers {
+    /// Bonuses for `attacker` hitting `defender` from the faction stacks.
+    /// Cover modifiers only count when the defender already has cover.
+    pub fn from_stacks(
+        stacks: &HashMap<Faction, ModifierStack>,
+        attacker: &Unit,
+        defender: &Unit,
+        weapon: &Weapon,
+        charging: bool,
+        cover_bonus: i32,
+    ) -> Self {
+        let empty = ModifierStack::new();
+        let attacking = Situation { charging, in_cover: false };
+        let defending = Situation { charging: false, in_cover: cover_bonus > 0 };
+        let own = stacks.get(&attacker.faction).unwrap_or(&empty);
+        let theirs = stacks.get(&defender.faction).unwrap_or(&empty);
+        let melee = if weapon.is_melee() { own.total(Stat::MeleeDamage, &attacking) } else { 0 };
+        Self {
+            accuracy: own.total(Stat::Accuracy, &attacking),
+            damage: melee,
+            cover: theirs.total(Stat::CoverBonus, &defending),
+        }
+    }
+}
+
+impl AttackModifiers {
+    /// Add the weather's ranged accuracy penalty for non-melee weapons.
+    pub fn in_weather(mut self, weather: Weather, weapon: &Weapon) -> Self {
+        if !weapon.is_melee() {
+            self.accuracy += weather.ranged_accuracy();
+        }
+        self
+    }
+}
+
+/// Resolve a weapon attack from attacker to defender. Refused if the
+/// attacker can't pay the weapon's AP.
+pub fn resolve_attack(
+    attacker: &mut Unit,
+    weapon: &Weapon,
+    defender: &mut Unit,
+    roll: u8,
+    cover_bonus: i32,
+) -> Result<AttackResult, CombatError> {
+    resolve_attack_with(attacker, weapon, defender, roll, cover_bonus, AttackModifiers::default(), &BalanceConfig::DEFAULT)
+}
+
+/// [`resolve_attack`] with extra situational bonuses, under `balance`.
+pub fn resolve_attack_with(
+    attacker: &mut Unit,
+    weapon: &Weapon,
+    defender: &mut Unit,
+    roll: u8,
+    cover_bonus: i32,
+    mods: AttackModifiers,
+    balance: &BalanceConfig,
+) -> Result<AttackResult, CombatError> {
+    let cover_bonus = cover_bonus + mods.cover + defender.stance.map_or(0, |s| s.cover_bonus());
     if attacker.action_points < weapon.action_point_cost {
-        return AttackResult { hit: false, damage: 0 };
+        return Err(CombatError::InsufficientAp);
     }
     attacker.action_points -= weapon.action_point_cost;
+    attacker.animation_state.current_animation = AnimationType::Attack;
+    let (flank_accuracy, flank_damage) = balance.flanking.bonus(defender, &attacker.grid_position);
+    attacker.face(&defender.grid_position);
+    if attacker.wear_weapon(roll) {
+        return Ok(AttackResult { hit: false, damage: 0, critical: false, jammed: true });
+    }
 
-    let hit_chance = (attacker.current_stats.agility as f32 * 10.0 + weapon.accuracy * 100.0)
-        - (defender.current_stats.agility as f32 * 10.0 + cover_bonus as f32);
+    let accuracy = attacker.current_stats.accuracy + mods.accuracy + flank_accuracy;
+    let hit_chance = balance.hit_chance(&attacker.current_stats, weapon.accuracy, accuracy, defender.current_stats.agility, cover_bonus);
 
     let mut hit = false;
     let mut damage = 0;
+    let mut critical = false;
     if (roll as f32) <= hit_chance {
         hit = true;
-        damage = (weapon.damage + attacker.current_stats.strength)
-            - defender.current_stats.toughness;
-        if damage < 0 {
-            damage = 0;
+        let melee_bonus = if weapon.is_melee() { attacker.current_stats.melee_damage } else { 0 };
+        let raw = weapon.damage + attacker.current_stats.strength + melee_bonus + mods.damage + flank_damage;
+        damage = mitigate(raw - defender.hull_armor(&attacker.grid_position, weapon), weapon.damage_type, defender);
+        if balance.is_critical(roll) {
+            damage *= balance.critical_multiplier;
+            critical = true;
         }
-        if roll <= 10 {
-            damage *= 2;
+        inflict(defender, damage, weapon.damage_type);
+        if damage > 0 {
+            defender.wear_armor();
         }
-        defender.health_points -= damage;
     }
 
-    attacker.animation_state.current_animation = AnimationType::Attack;
-
-    AttackResult { hit, damage }
+    Ok(AttackResult { hit, damage, critical, jammed: false })
 }
 
 /// Apply an ability effect to a single unit.
-fn apply_ability_effect(effect: &AbilityEffect, target: &mut Unit) {
+pub(crate) fn apply_ability_effect(effect: &AbilityEffect, target: &mut Unit) {
     if let Some(dmg) = effect.damage {
-        target.health_points -= dmg;
+        inflict(target, resist(dmg, effect.damage_type, target), effect.damage_type);
     }
     if let Some(heal) = effect.healing {
         target.health_points += heal;
@@ -48,11 +283,13 @@ fn apply_ability_effect(effect: &AbilityEffect, target: &mut Unit) {
             target.health_points = target.current_stats.max_health;
         }
     }
-    if let Some(buff) = &effect.buff {
-        modify_stats(&mut target.current_stats, buff, 1);
-    }
-    if let Some(debuff) = &effect.debuff {
-        modify_stats(&mut target.current_stats, debuff, -1);
+    let turns = effect.duration.unwrap_or(1);
+    for (modifier, sign) in [(&effect.buff, 1), (&effect.debuff, -1)] {
+        if let Some(m) = modifier {
+            for m in Modifier::from_stats_modifier(ModifierSource::Status, m, sign, turns) {
+                target.add_modifier(m);
+            }
+        }
     }
     if let Some(status) = effect.status_applied.clone() {
         target.status_effects.push(crate::models::StatusEffect {
@@ -63,54 +300,54 @@ fn apply_ability_effect(effect: &AbilityEffect, target: &mut Unit) {
     }
 }
 
-fn modify_stats(stats: &mut crate::models::Stats, modifier: &StatsModifier, sign: i32) {
-    stats.strength += modifier.strength_mod * sign;
-    stats.toughness += modifier.toughness_mod * sign;
-    stats.agility += modifier.agility_mod * sign;
-    stats.intellect += modifier.intellect_mod * sign;
-    stats.willpower += modifier.willpower_mod * sign;
-    stats.fellowship += modifier.fellowship_mod * sign;
-}
-
-/// Use an ability on one or more targets.
+/// Use an ability on one or more targets. The first, which an area ability
+/// is aimed at, must be within its range. A channelled ability leaves the
+/// user in its [`AbilityState`], and a charged one has no effect yet.
 pub fn use_ability(
     user: &mut Unit,
     ability_index: usize,
     targets: &mut [&mut Unit],
-    mut audio: Option<&mut crate::audio::AudioSystem>,
-) -> Result<(), &'static str> {
+    audio: Option<&mut crate::audio::AudioSystem>,
+) -> Result<(), CombatError> {
     let ability = user
         .abilities
         .get_mut(ability_index)
-        .ok_or("invalid ability")?;
+        .ok_or(CombatError::InvalidAbility)?;
 
     if user.action_points < ability.action_point_cost {
-        return Err("not enough AP");
+        return Err(CombatError::InsufficientAp);
     }
     if ability.current_cooldown > 0 {
-        return Err("ability on cooldown");
+        return Err(CombatError::OnCooldown);
+    }
+    if targets.first().is_some_and(|t| manhattan(&user.grid_position, &t.grid_position) > ability.range) {
+        return Err(CombatError::OutOfRange);
     }
 
     user.action_points -= ability.action_point_cost;
     ability.current_cooldown = ability.cooldown;
     user.animation_state.current_animation = ability.animation.clone();
+    let state = ability.channel.map(|c| AbilityState::begin(c, &ability.id, targets));
 
-    if ability.area_of_effect.is_some() {
+    if matches!(ability.channel, Some(Channel::Charged { .. })) {
+        // Goes off when the charge completes; see [`crate::channel`].
+    } else if ability.area_of_effect.is_some() {
         for t in targets.iter_mut() {
-            apply_ability_effect(&ability.effect, *t);
-        }
-    } else {
-        if let Some(first) = targets.get_mut(0) {
-            apply_ability_effect(&ability.effect, *first);
+            apply_ability_effect(&ability.effect, t);
         }
+    } else if let Some(first) = targets.get_mut(0) {
+        apply_ability_effect(&ability.effect, first);
     }
 
-    if let Some(sys) = audio {
-        if !ability.sound_effect_key.is_empty() {
-            sys.play(&ability.sound_effect_key);
-        }
+    if let Some(sys) = audio
+        && !ability.sound_effect_key.is_empty()
+    {
+        sys.play(&ability.sound_effect_key);
     }
 
+    if state.is_some() {
+        user.ability_state = state;
+    }
     Ok(())
 }
 
@@ -123,134 +360,470 @@ pub fn tick_cooldowns(unit: &mut Unit) {
     }
 }
 
-fn manhattan(a: &Position, b: &Position) -> u32 {
+/// Build the damage/heal event for a unit whose HP went from `before` to `after`.
+pub(crate) fn hp_change_event(unit_id: &str, before: i32, after: i32, critical: bool) -> Option<CombatEvent> {
+    if after < before {
+        Some(CombatEvent::UnitDamaged { unit_id: unit_id.to_string(), amount: before - after, critical })
+    } else if after > before {
+        Some(CombatEvent::UnitHealed { unit_id: unit_id.to_string(), amount: after - before })
+    } else {
+        None
+    }
+}
+
+pub(crate) fn manhattan(a: &Position, b: &Position) -> u32 {
     ((a.x as i32 - b.x as i32).abs() + (a.y as i32 - b.y as i32).abs()) as u32
 }
 
-fn ai_move_towards(unit: &mut Unit, dest: &Position, map: &crate::grid::GridMap) {
-    use crate::grid::TerrainType;
-    let mp = unit.current_stats.agility as u32 / 2;
-    let mut pos = unit.grid_position.clone();
-    for _ in 0..mp {
-        if pos == *dest {
-            break;
-        }
-        let mut next = pos.clone();
-        if pos.x < dest.x {
-            next.x += 1;
-        } else if pos.x > dest.x {
-            next.x -= 1;
-        } else if pos.y < dest.y {
-            next.y += 1;
-        } else if pos.y > dest.y {
-            next.y -= 1;
-        }
-        if !map.in_bounds(&next) {
-            break;
-        }
-        if matches!(map.terrain_at(&next), TerrainType::Blocked) {
-            break;
-        }
-        pos = next;
-    }
-    unit.grid_position = pos;
+/// Move `unit` along the cached flow field towards `dest` as far as the
+/// movement points it has left and one more AP's worth (plus `bonus`)
+/// allow, paying for the points spent.
+fn ai_move_towards(unit: &mut Unit, dest: &Position, map: &crate::grid::GridMap, fields: &mut FlowFieldCache, bonus: i32, weather: Weather) {
+    let one_ap = if unit.action_points > 0 { points_per_ap(unit, map, bonus) } else { 0 };
+    let field = fields.towards(map, dest, unit.movement_type, weather, &unit.grid_position);
+    if let Some(vehicle) = &unit.vehicle {
+        let search = crate::vehicle::advance(unit, vehicle.turn_cost, field, map, unit.movement_left + one_ap, weather);
+        let Some(to) = search.path.last() else { return };
+        pay_for_move(unit, map, bonus, search.cost.unwrap_or(0));
+        unit.grid_position = to.clone();
+        unit.face_along(&search.path);
+        return;
+    }
+    let to = field.advance(map, &unit.grid_position, unit.movement_left + one_ap);
+    let spent = field.cost(map, &unit.grid_position).zip(field.cost(map, &to)).map_or(0, |(from, to)| from.saturating_sub(to));
+    pay_for_move(unit, map, bonus, spent);
+    unit.face(&to);
+    unit.grid_position = to;
 }
 
 use std::collections::VecDeque;
-use crate::models::Position;
+use std::collections::{BTreeMap, HashMap, HashSet};
 
-#[derive(Debug, Clone)]
+use crate::command::{Command, MoveUndo};
+use crate::commander::Commander;
+use crate::content::UnitTemplate;
+use crate::profile::Difficulty;
+use crate::grid::{pay_for_move, points_per_ap, FlowFieldCache};
+use crate::registry::{Side, UnitId, UnitRegistry};
+use crate::models::{Faction, Position};
+use crate::modifiers::{Modifier, ModifierSource, ModifierStack, Situation, Stat};
+use crate::rng::GameRng;
+use crate::stats::BattleStats;
+use crate::terraform::TerrainChange;
+use crate::weather::Weather;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CombatEncounter {
-    pub player_units: Vec<Unit>,
-    pub enemy_units: Vec<Unit>,
+    /// Every unit in the battle, on both sides.
+    pub units: UnitRegistry,
     pub battlefield: crate::grid::GridMap,
     pub turn_order: TurnQueue,
     pub current_phase: CombatPhase,
     pub environmental_effects: Vec<EnvironmentalEffect>,
     pub camera_state: CameraState,
+    /// Events produced since the presentation layer last drained them.
+    pub events: Vec<CombatEvent>,
+    /// Source of attack rolls for player commands.
+    pub rng: GameRng,
+    /// Moves that can still be taken back; cleared when an action commits.
+    pub undo_stack: Vec<MoveUndo>,
+    /// Every command executed so far, for replays.
+    pub command_log: Vec<Command>,
+    /// Who controls each faction's units.
+    pub controllers: HashMap<Faction, Controller>,
+    /// Human player owning the current turn, if any.
+    pub active_player: Option<u8>,
+    /// Faction-wide modifiers such as doctrines.
+    pub faction_modifiers: HashMap<Faction, ModifierStack>,
+    /// Units that have moved since their turn started, for charge bonuses.
+    pub moved_this_turn: HashSet<String>,
+    #[serde(default)]
+    pub weather: Weather,
+    /// AI pathfinding fields, rebuilt each round.
+    #[serde(skip)]
+    pub flow_fields: FlowFieldCache,
+    /// Per-unit metrics for the end-of-battle summary.
+    #[serde(default)]
+    pub stats: BattleStats,
+    /// Commands waiting to be shown at a human pace; see [`crate::actions`].
+    #[serde(skip)]
+    pub actions: ActionQueue,
+    /// Scripted events waiting on their triggers; see [`crate::interrupts`].
+    #[serde(default)]
+    pub interrupts: Vec<ScriptedInterrupt>,
+    /// What reinforcements are made from, keyed by template id; usually
+    /// [`crate::content::ContentDatabase::units`].
+    #[serde(default)]
+    pub unit_templates: BTreeMap<String, UnitTemplate>,
+    /// Loot picked up by player units, handed to the campaign when the
+    /// battle ends.
+    #[serde(default)]
+    pub recovered: Vec<Loot>,
+    /// Hit, critical and movement numbers; see [`CombatEncounter::set_balance`].
+    #[serde(default)]
+    pub balance: BalanceConfig,
+    /// Terrain changed by abilities, to be put back; see [`crate::terraform`].
+    #[serde(default)]
+    pub terrain_changes: Vec<TerrainChange>,
+    /// Player units the AI is playing for; see [`crate::autocontrol`].
+    #[serde(default)]
+    pub auto_controlled: HashSet<String>,
+    /// Squad-level AI by faction; see [`crate::commander`].
+    #[serde(default)]
+    pub commanders: HashMap<Faction, Commander>,
+    /// How hard the AI plays; decides what it may know, see
+    /// [`crate::knowledge`].
+    #[serde(default)]
+    pub difficulty: Difficulty,
+    /// Goals beyond winning, judged when the battle ends; see
+    /// [`crate::debrief`].
+    #[serde(default)]
+    pub objectives: Vec<Objective>,
+}
+
+/// Sight radius, in tiles, used for hotseat fog of war.
+pub const SIGHT_RANGE: u32 = 6;
+
+/// Who issues orders for a faction.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Controller {
+    Ai,
+    /// A human player, numbered from 0.
+    Player(u8),
 }
 
 impl CombatEncounter {
-    pub fn new(player_units: Vec<Unit>, enemy_units: Vec<Unit>, battlefield: crate::grid::GridMap, mut audio: Option<&mut crate::audio::AudioSystem>) -> Self {
+    pub fn new(player_units: Vec<Unit>, enemy_units: Vec<Unit>, battlefield: crate::grid::GridMap, audio: Option<&mut crate::audio::AudioSystem>) -> Self {
         let mut turn_order = TurnQueue::new();
         for u in player_units.iter().chain(enemy_units.iter()) {
             turn_order.add_unit(u.id.clone());
         }
-        if let Some(sys) = audio.as_deref_mut() {
+        if let Some(sys) = audio {
             sys.play_background_music("combat");
         }
+        let mut controllers = HashMap::new();
+        for u in &enemy_units {
+            controllers.insert(u.faction.clone(), Controller::Ai);
+        }
+        for u in &player_units {
+            controllers.insert(u.faction.clone(), Controller::Player(0));
+        }
         Self {
-            player_units,
-            enemy_units,
+            units: UnitRegistry::with_sides(player_units, enemy_units),
             battlefield,
             turn_order,
             current_phase: CombatPhase::Movement,
             environmental_effects: Vec::new(),
-            camera_state: CameraState { x_offset: 0.0, y_offset: 0.0, zoom_level: 1.0 },
+            camera_state: CameraState::default(),
+            events: Vec::new(),
+            rng: GameRng::new(0),
+            undo_stack: Vec::new(),
+            command_log: Vec::new(),
+            controllers,
+            active_player: None,
+            faction_modifiers: HashMap::new(),
+            moved_this_turn: HashSet::new(),
+            weather: Weather::Clear,
+            flow_fields: FlowFieldCache::default(),
+            stats: BattleStats::new(),
+            actions: ActionQueue::default(),
+            interrupts: Vec::new(),
+            unit_templates: BTreeMap::new(),
+            recovered: Vec::new(),
+            balance: BalanceConfig::DEFAULT,
+            terrain_changes: Vec::new(),
+            auto_controlled: HashSet::new(),
+            commanders: HashMap::new(),
+            difficulty: Difficulty::default(),
+            objectives: Vec::new(),
         }
     }
 
-    /// Helper to find a mutable reference to a unit by id
-    fn unit_by_id_mut(&mut self, id: &str) -> Option<&mut Unit> {
-        if let Some(idx) = self.player_units.iter().position(|u| u.id == id) {
-            return Some(&mut self.player_units[idx]);
+    /// Fight under `balance`, handing its movement rules to the
+    /// battlefield.
+    pub fn set_balance(&mut self, balance: BalanceConfig) {
+        self.battlefield.set_movement_rules(balance.movement.clone());
+        self.balance = balance;
+    }
+
+    /// Hand a faction to a human player or the AI.
+    pub fn set_controller(&mut self, faction: Faction, controller: Controller) {
+        self.controllers.insert(faction, controller);
+    }
+
+    /// Controller of the unit with `unit_id`; factions without an explicit
+    /// controller and units on auto-control are AI driven.
+    pub fn controller_of(&self, unit_id: &str) -> Option<Controller> {
+        let unit = self.unit_by_id(unit_id)?;
+        if self.auto_controlled.contains(unit_id) {
+            return Some(Controller::Ai);
         }
-        if let Some(idx) = self.enemy_units.iter().position(|u| u.id == id) {
-            return Some(&mut self.enemy_units[idx]);
+        Some(self.controllers.get(&unit.faction).copied().unwrap_or(Controller::Ai))
+    }
+
+    /// Extra movement points for `unit` from its faction's modifiers.
+    pub fn movement_bonus(&self, unit: &Unit) -> i32 {
+        self.faction_modifiers
+            .get(&unit.faction)
+            .map(|m| m.total(Stat::Movement, &Situation::default()))
+            .unwrap_or(0)
+    }
+
+    /// Cells the unit with `unit_id` can move to this turn, for the
+    /// movement-range overlay.
+    pub fn movement_range(&self, unit_id: &str) -> Vec<Position> {
+        match self.unit_by_id(unit_id) {
+            Some(unit) => crate::grid::reachable_cells(unit, &self.battlefield, self.movement_bonus(unit), self.weather),
+            None => Vec::new(),
         }
-        None
+    }
+
+    /// [`CombatEncounter::movement_range`] with the AP moving to each cell
+    /// would cost; see [`crate::frontend::Renderer::set_movement_preview`].
+    pub fn movement_bands(&self, unit_id: &str) -> Vec<(Position, u32)> {
+        match self.unit_by_id(unit_id) {
+            Some(unit) => crate::grid::movement_bands(unit, &self.battlefield, self.movement_bonus(unit), self.weather),
+            None => Vec::new(),
+        }
+    }
+
+    /// Change the weather, switching to its music track.
+    pub fn set_weather(&mut self, weather: Weather, audio: Option<&mut crate::audio::AudioSystem>) {
+        self.weather = weather;
+        if let Some(sys) = audio {
+            sys.play_background_music(weather.music_key());
+        }
+    }
+
+    /// Cells within the weather's sight range (at most [`SIGHT_RANGE`]) of
+    /// any living unit controlled by `player`.
+    pub fn visible_cells(&self, player: u8) -> HashSet<Position> {
+        let mut cells = HashSet::new();
+        let map = &self.battlefield;
+        for unit in &self.units {
+            if unit.health_points <= 0 || self.controllers.get(&unit.faction) != Some(&Controller::Player(player)) {
+                continue;
+            }
+            for y in 0..map.height {
+                for x in 0..map.width {
+                    let pos = Position { x, y };
+                    if manhattan(&unit.grid_position, &pos) <= self.weather.sight_range() {
+                        cells.insert(pos);
+                    }
+                }
+            }
+        }
+        cells
+    }
+
+    /// Fire the attacker's heavy weapon at the structure on `target`.
+    pub fn attack_terrain(&mut self, attacker_id: &str, target: &Position) -> Result<(), CombatError> {
+        if !self.battlefield.structures.iter().any(|s| s.position == *target) {
+            return Err(CombatError::InvalidTarget);
+        }
+        let attacker = self.unit_by_id_mut(attacker_id).ok_or(CombatError::UnknownUnit)?;
+        let weapon = attacker.equipment.weapon.clone().ok_or(CombatError::NoWeapon)?;
+        if !weapon.is_heavy() {
+            return Err(CombatError::WeaponTooLight);
+        }
+        if manhattan(&attacker.grid_position, target) > weapon.range {
+            return Err(CombatError::OutOfRange);
+        }
+        if attacker.action_points < weapon.action_point_cost {
+            return Err(CombatError::InsufficientAp);
+        }
+        attacker.action_points -= weapon.action_point_cost;
+        attacker.animation_state.current_animation = AnimationType::Attack;
+        let damage = weapon.damage + attacker.current_stats.strength;
+        if self.battlefield.damage_structure(target, damage) {
+            self.events.push(CombatEvent::ObjectDestroyed { position: target.clone() });
+        }
+        Ok(())
+    }
+
+    /// Take all events recorded so far.
+    pub fn drain_events(&mut self) -> Vec<CombatEvent> {
+        std::mem::take(&mut self.events)
+    }
+
+    /// Find a unit on either side by id.
+    pub fn unit_by_id(&self, id: &str) -> Option<&Unit> {
+        self.units.by_name(id)
+    }
+
+    pub fn unit_by_id_mut(&mut self, id: &str) -> Option<&mut Unit> {
+        self.units.by_name_mut(id)
+    }
+
+    /// Player-side units, living or dead.
+    pub fn player_units(&self) -> impl Iterator<Item = &Unit> {
+        self.units.players()
+    }
+
+    pub fn enemy_units(&self) -> impl Iterator<Item = &Unit> {
+        self.units.enemies()
     }
 
     /// Execute a very small AI routine for the current enemy unit.
     /// The unit will attempt to move toward the nearest player and use the
     /// highest-damage ability or weapon that is in range.
     pub fn enemy_ai_action(&mut self, roll: u8) {
+        let is_enemy = match &self.turn_order.current_unit_id {
+            Some(id) => {
+                self.units.id(id).and_then(|i| self.units.side(i)) == Some(Side::Enemy)
+                    && !matches!(self.controller_of(id), Some(Controller::Player(_)))
+            }
+            None => false,
+        };
+        if is_enemy {
+            self.ai_take_action(roll);
+        }
+    }
+
+    /// Run the AI routine for the current unit, whichever side it is on. It
+    /// targets the nearest living opponent; dead and charging units take no
+    /// action.
+    pub fn ai_take_action(&mut self, roll: u8) {
+        let events_before = self.events.len();
+        self.ai_act(roll);
+        self.release_passengers();
+        self.react(events_before);
+        self.interrupt_channels(events_before);
+        self.check_interrupts();
+    }
+
+    fn ai_act(&mut self, roll: u8) {
         let id = match &self.turn_order.current_unit_id {
             Some(i) => i.clone(),
             None => return,
         };
-        let enemy_idx = match self.enemy_units.iter().position(|u| u.id == id) {
-            Some(i) => i,
-            None => return,
+        let Some(actor_id) = self.units.id(&id) else { return };
+        let actor = &self.units[actor_id];
+        // Pinned units keep their heads down.
+        if actor.health_points <= 0 || actor.is_pinned() || actor.is_charging() || actor.embarked_in.is_some() {
+            return;
+        }
+        self.spot(actor_id);
+        if let Some(objective) = self.runner_objective(actor_id) {
+            self.ai_advance(actor_id, &objective);
+            return;
+        }
+        if let Some(cell) = self.ai_suppression_target(&id) {
+            let _ = self.suppress(&id, &cell);
+            return;
+        }
+        let Some(target_id) = self.ai_target(actor_id) else {
+            if let Some(destination) = self.search_destination(actor_id) {
+                self.ai_advance(actor_id, &destination);
+            }
+            return;
         };
-
-        let enemy_pos = self.enemy_units[enemy_idx].grid_position.clone();
-        let (target_idx, _) = self
-            .player_units
-            .iter()
-            .enumerate()
-            .map(|(i, u)| (i, manhattan(&enemy_pos, &u.grid_position)))
-            .min_by_key(|(_, d)| *d)
-            .unwrap();
-
-        // Split borrows so we can mutably access both units
-        let enemy = &mut self.enemy_units[enemy_idx];
-        let target = &mut self.player_units[target_idx];
+        let destination = self.squad_destination(actor_id, target_id);
+        // Melee units with a run-up charge rather than walk in
+        let target_name = self.units[target_id].id.clone();
+        if self.ai_should_charge(&id, &target_name) {
+            let _ = self.charge(&id, &target_name, roll);
+            return;
+        }
+        if self.ai_fire_hardpoints(&id, &target_name) {
+            return;
+        }
+        let Some((actor, target)) = self.units.pair_mut(actor_id, target_id) else { return };
+        let target_hp = target.health_points;
 
         // Try abilities first
-        if let Some((idx, _)) = enemy
+        if let Some((idx, _)) = actor
             .abilities
             .iter()
             .enumerate()
-            .filter(|(_, a)| a.current_cooldown == 0 && a.action_point_cost <= enemy.action_points)
-            .filter(|(_, a)| manhattan(&enemy.grid_position, &target.grid_position) <= a.range)
-            .map(|(i, a)| (i, a.effect.damage.unwrap_or(0)))
-            .max_by_key(|&(_, dmg)| dmg)
+            .filter(|(_, a)| a.current_cooldown == 0 && a.action_point_cost <= actor.action_points)
+            .filter(|(_, a)| manhattan(&actor.grid_position, &target.grid_position) <= a.range)
+            // Of equally damaging abilities, the higher ranked.
+            .map(|(i, a)| (i, (a.effect.damage.unwrap_or(0), a.rank)))
+            .max_by_key(|&(_, score)| score)
         {
-            let _ = use_ability(enemy, idx, &mut [target], None);
+            let used = use_ability(actor, idx, &mut [&mut *target], None).is_ok();
+            let event = hp_change_event(&target.id, target_hp, target.health_points, false);
+            self.events.extend(event);
+            if used {
+                self.stats.record_ability(&actor.id);
+                self.stats.record_damage(Some(&actor.id), &target.id, target_hp - target.health_points);
+                self.stats.record_kill(&actor.id, target_hp, target.health_points);
+            }
+            let landed = used && !actor.is_charging();
+            if landed && actor.abilities[idx].effect.status_applied == Some(EffectType::Taunted) {
+                target.taunted_by = Some(actor.id.clone());
+            }
+            let forced = landed.then_some(actor.abilities[idx].effect.forced_move).flatten();
+            let (actor_name, target_name) = (actor.id.clone(), target.id.clone());
+            if landed && let Some(hazard) = actor.abilities[idx].effect.creates_hazard.clone() {
+                let cell = target.grid_position.clone();
+                self.spawn_hazard(&hazard, &[cell]);
+            }
+            if let Some(forced) = forced {
+                self.force_move(&actor_name, &target_name, forced);
+            }
             return;
         }
 
         // Fallback to weapon
-        if let Some(weapon) = enemy.equipment.weapon.clone() {
-            if manhattan(&enemy.grid_position, &target.grid_position) <= weapon.range {
-                let _ = resolve_attack(enemy, &weapon, target, roll, 0);
-                return;
+        if let Some(weapon) = actor.equipment.weapon.clone()
+            && weapon.indirect.is_none()
+            && manhattan(&actor.grid_position, &target.grid_position) <= weapon.range
+            && (weapon.is_melee() || self.battlefield.has_line_of_sight(&actor.grid_position, &target.grid_position))
+        {
+            let charging = self.moved_this_turn.contains(&actor.id);
+            let mods = AttackModifiers::from_stacks(&self.faction_modifiers, actor, target, &weapon, charging, 0)
+                .in_weather(self.weather, &weapon);
+            let Ok(result) = resolve_attack_with(actor, &weapon, target, roll, 0, mods, &self.balance) else { return };
+            if result.jammed {
+                self.events.push(CombatEvent::WeaponJammed { unit_id: actor.id.clone() });
+            }
+            if result.hit {
+                let (attacker_id, target_id) = (actor.id.clone(), target.id.clone());
+                self.events.push(CombatEvent::UnitAttacked { attacker_id, target_id, melee: weapon.is_melee() });
             }
+            let event = hp_change_event(&target.id, target_hp, target.health_points, result.critical);
+            self.events.extend(event);
+            self.stats.record_attack(&actor.id, &target.id, result.hit, target_hp - target.health_points);
+            self.stats.record_kill(&actor.id, target_hp, target.health_points);
+            if result.hit && let Some(forced) = weapon.forced_move {
+                let (actor_name, target_name) = (actor.id.clone(), target.id.clone());
+                self.force_move(&actor_name, &target_name, forced);
+            }
+            return;
+        }
+
+        // Indirect weapons shell the target's cell, in sight or not
+        let cell = target.grid_position.clone();
+        if actor.equipment.weapon.as_ref().is_some_and(|w| w.indirect.is_some()) && self.bombard(&id, &cell).is_ok() {
+            return;
         }
 
         // Move toward target if nothing was in range
-        ai_move_towards(enemy, &target.grid_position, &self.battlefield);
+        self.ai_advance(actor_id, &destination);
+    }
+
+    /// Move an AI unit toward `dest` as far as it can this turn, unless it
+    /// is suppressed, when it holds its ground rather than advance under
+    /// fire.
+    pub(crate) fn ai_advance(&mut self, actor_id: UnitId, dest: &Position) {
+        if self.units[actor_id].is_suppressed() {
+            return;
+        }
+        let bonus = self.movement_bonus(&self.units[actor_id]);
+        let actor = &mut self.units[actor_id];
+        let from = actor.grid_position.clone();
+        ai_move_towards(actor, dest, &self.battlefield, &mut self.flow_fields, bonus, self.weather);
+        if actor.grid_position != from {
+            let id = actor.id.clone();
+            self.stats.record_move(&id, &from, &actor.grid_position);
+            self.moved_this_turn.insert(id.clone());
+            let to = self.units[actor_id].grid_position.clone();
+            self.carry_passengers(&id);
+            self.resolve_move_holds(&id, &from, &[to]);
+            self.trigger_overwatch(&id);
+        }
     }
 
     /// Convenience wrapper running start_turn -> enemy_ai_action -> end_turn.
@@ -260,37 +833,87 @@ impl CombatEncounter {
         self.end_turn();
     }
 
-    /// Advance the turn queue and apply start-of-turn environmental effects to the active unit
+    /// Advance the turn queue (ticking the environment on a new round), refresh
+    /// auras and apply start-of-turn environmental effects to the active unit,
+    /// unless it is taking up a turn it delayed
     pub fn start_turn(&mut self) {
-        if let Some(id) = self.turn_order.next_turn() {
-            let effects = self.environmental_effects.clone();
-            if let Some(unit) = self.unit_by_id_mut(&id) {
-                unit.apply_equipment();
-                for effect in &effects {
+        let events_before = self.events.len();
+        self.moved_this_turn.clear();
+        let round = self.turn_order.round_number;
+        let next = self.turn_order.next_turn();
+        if self.turn_order.round_number != round {
+            self.flow_fields.clear();
+            self.tick_environment();
+            self.plan_squads();
+        }
+        self.stats.rounds = self.turn_order.round_number;
+        self.refresh_auras();
+        let resumed = next.as_ref().is_some_and(|id| self.turn_order.resume(id));
+        if let Some(id) = next {
+            if let Some(Controller::Player(to)) = self.controller_of(&id) {
+                if let Some(from) = self.active_player
+                    && from != to
+                {
+                    self.events.push(CombatEvent::TurnHandedOff { from, to });
+                }
+                self.active_player = Some(to);
+            }
+            // A resumed turn already had its start.
+            if !resumed && let Some(unit) = self.units.by_name_mut(&id) {
+                let starting_hp = unit.health_points;
+                unit.stance = None;
+                unit.held_action = None;
+                unit.movement_left = 0;
+                for hardpoint in unit.vehicle.iter_mut().flat_map(|v| &mut v.hardpoints) {
+                    hardpoint.fired = false;
+                }
+                unit.modifiers.remove_source(ModifierSource::Environment);
+                unit.recalculate_stats();
+                // Passengers sit out whatever the vehicle is standing in.
+                let exposed = if unit.embarked_in.is_none() { &self.environmental_effects[..] } else { &[] };
+                for effect in exposed {
                     match effect {
                         EnvironmentalEffect::FirePatch { grid_cells, damage_per_turn } => {
                             if grid_cells.contains(&unit.grid_position) {
-                                unit.health_points -= *damage_per_turn;
+                                unit.health_points -= resist(*damage_per_turn, DamageType::Fire, unit);
                             }
                         }
                         EnvironmentalEffect::AcidPool { grid_cells, movement_penalty } => {
                             if grid_cells.contains(&unit.grid_position) {
                                 let adjusted = (unit.current_stats.agility as f32 * movement_penalty) as i32;
-                                unit.current_stats.agility = adjusted;
+                                let delta = adjusted - unit.current_stats.agility;
+                                unit.add_modifier(Modifier::timed(ModifierSource::Environment, Stat::Agility, delta, 1));
                             }
                         }
                         EnvironmentalEffect::SmokeCloud { .. } => {}
                     }
                 }
+                unit.suffer_statuses();
+                if unit.test_nerve(&mut self.rng) {
+                    self.events.push(CombatEvent::UnitPinned { unit_id: unit.id.clone() });
+                }
+                self.events.extend(hp_change_event(&unit.id, starting_hp, unit.health_points, false));
+                self.stats.record_damage(None, &unit.id, starting_hp - unit.health_points);
             }
+            self.release_passengers();
+            self.react(events_before);
+            self.interrupt_channels(events_before);
+            self.advance_channel(&id);
+            self.check_interrupts();
         }
     }
 
-    /// Apply end-of-turn environmental logic such as expiring smoke clouds and resetting stats
+    /// Apply end-of-turn environmental logic such as expiring smoke clouds and
+    /// counting down the active unit's timed modifiers and statuses
     pub fn end_turn(&mut self) {
-        if let Some(id) = self.turn_order.current_unit_id.clone() {
-            if let Some(unit) = self.unit_by_id_mut(&id) {
-                unit.apply_equipment();
+        if let Some(id) = self.turn_order.current_unit_id.clone()
+            && let Some(unit) = self.units.by_name_mut(&id)
+        {
+            unit.modifiers.tick();
+            unit.tick_statuses();
+            unit.recalculate_stats();
+            if unit.health_points > 0 {
+                self.stats.record_turn_survived(&id);
             }
         }
 
@@ -316,19 +939,38 @@ pub struct TurnQueue {
     pub initiative: VecDeque<String>,
     pub current_unit_id: Option<String>,
     pub round_number: u32,
+    /// Turns started in the current round.
+    #[serde(default)]
+    pub turns_this_round: usize,
+    /// Units that put their turn off this round; see [`TurnQueue::delay`].
+    #[serde(default)]
+    pub delayed: Vec<String>,
+}
+
+impl Default for TurnQueue {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl TurnQueue {
     pub fn new() -> Self {
-        Self { initiative: VecDeque::new(), current_unit_id: None, round_number: 1 }
+        Self { initiative: VecDeque::new(), current_unit_id: None, round_number: 1, turns_this_round: 0, delayed: Vec::new() }
     }
 
     pub fn add_unit(&mut self, id: String) {
         self.initiative.push_back(id);
     }
 
+    /// Hand the turn to the next unit in initiative order. A new round
+    /// begins once every unit has had a turn.
     pub fn next_turn(&mut self) -> Option<String> {
         if let Some(id) = self.initiative.pop_front() {
+            if self.turns_this_round > self.initiative.len() {
+                self.round_number += 1;
+                self.turns_this_round = 0;
+            }
+            self.turns_this_round += 1;
             self.current_unit_id = Some(id.clone());
             self.initiative.push_back(id.clone());
             Some(id)
@@ -336,6 +978,31 @@ impl TurnQueue {
             None
         }
     }
+
+    /// Put the current unit back in this round's order behind the next
+    /// `places` units still to act, or last if fewer are left, and keep it
+    /// there in later rounds. Its turn then resumes rather than starting
+    /// afresh. Returns false when no unit is left to act this round.
+    pub fn delay(&mut self, places: usize) -> bool {
+        let remaining = self.initiative.len().saturating_sub(self.turns_this_round);
+        let Some(id) = self.current_unit_id.clone() else { return false };
+        if places == 0 || remaining == 0 || self.initiative.back() != Some(&id) {
+            return false;
+        }
+        self.initiative.pop_back();
+        self.initiative.insert(places.min(remaining), id.clone());
+        self.turns_this_round -= 1;
+        self.delayed.push(id);
+        true
+    }
+
+    /// Whether the turn of `unit_id` is one it put off, which it then
+    /// takes up.
+    fn resume(&mut self, unit_id: &str) -> bool {
+        let Some(index) = self.delayed.iter().position(|id| id == unit_id) else { return false };
+        self.delayed.remove(index);
+        true
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -352,11 +1019,62 @@ pub enum EnvironmentalEffect {
     AcidPool { grid_cells: Vec<Position>, movement_penalty: f32 },
 }
 
-#[derive(Debug, Clone)]
+/// Furthest the camera zooms out.
+pub const MIN_ZOOM: f32 = 0.5;
+/// Furthest the camera zooms in.
+pub const MAX_ZOOM: f32 = 3.0;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CameraState {
     pub x_offset: f32,
     pub y_offset: f32,
     pub zoom_level: f32,
+    /// Viewport width over height.
+    #[serde(default = "default_aspect_ratio")]
+    pub aspect_ratio: f32,
+}
+
+fn default_aspect_ratio() -> f32 {
+    16.0 / 9.0
+}
+
+impl Default for CameraState {
+    fn default() -> Self {
+        Self { x_offset: 0.0, y_offset: 0.0, zoom_level: 1.0, aspect_ratio: default_aspect_ratio() }
+    }
+}
+
+impl CameraState {
+    /// A camera at the origin for a `width` x `height` viewport.
+    pub fn new(width: u32, height: u32) -> Self {
+        Self { aspect_ratio: width as f32 / height.max(1) as f32, ..Self::default() }
+    }
+
+    /// Follow a viewport resized from `old` to `new` pixels: the aspect
+    /// ratio matches the new size and the point at the centre of the view
+    /// stays put.
+    pub fn resize(&mut self, old: (u32, u32), new: (u32, u32)) {
+        self.x_offset = (self.x_offset + (old.0 as f32 - new.0 as f32) / 2.0).max(0.0);
+        self.y_offset = (self.y_offset + (old.1 as f32 - new.1 as f32) / 2.0).max(0.0);
+        self.aspect_ratio = new.0 as f32 / new.1.max(1) as f32;
+    }
+
+    /// Drag the view by `dx`, `dy` screen pixels, so the map moves with
+    /// the finger. The view stops at the map's top-left corner.
+    pub fn pan(&mut self, dx: f32, dy: f32) {
+        self.x_offset = (self.x_offset - dx).max(0.0);
+        self.y_offset = (self.y_offset - dy).max(0.0);
+    }
+
+    /// Scale the zoom by `factor`, within [`MIN_ZOOM`] and [`MAX_ZOOM`],
+    /// keeping the map point under screen position `at` in place.
+    pub fn zoom_at(&mut self, at: (f32, f32), factor: f32) {
+        let old = if self.zoom_level > 0.0 { self.zoom_level } else { 1.0 };
+        let new = (old * factor).clamp(MIN_ZOOM, MAX_ZOOM);
+        self.x_offset = ((self.x_offset + at.0) * new / old - at.0).max(0.0);
+        self.y_offset = ((self.y_offset + at.1) * new / old - at.1).max(0.0);
+        self.zoom_level = new;
+    }
 }
 
 #[cfg(test)]
@@ -381,6 +1099,9 @@ mod tests {
             action_point_cost: 1,
             critical_chance: 0.1,
             abilities_granted: Vec::new(),
+            damage_type: DamageType::Kinetic,
+            forced_move: None,
+            indirect: None,
         };
         (attacker, defender, weapon)
     }
@@ -388,9 +1109,8 @@ mod tests {
     #[test]
     fn attack_hits() {
         let (mut a, mut d, w) = basic_units();
-        let result = resolve_attack(&mut a, &w, &mut d, 5, 0);
+        let result = resolve_attack(&mut a, &w, &mut d, 5, 0).unwrap();
         assert!(result.hit);
         assert!(result.damage > 0);
     }
 }
-
@@ -0,0 +1,483 @@
+use serde::{Deserialize, Serialize};
+
+use crate::combat::{
+    hp_change_event, manhattan, resolve_attack_with, use_ability, AttackModifiers, AttackResult, CombatEncounter, CombatError,
+    CombatEvent, Controller,
+};
+use crate::grenade::blast_cells;
+use crate::grid::move_along;
+use crate::facing::Facing;
+use crate::hold::HeldAction;
+use crate::models::{Accessory, EffectType, Position, Unit, Weapon};
+use crate::registry::UnitId;
+use crate::stance::Stance;
+
+/// A player-issued action. Commands are the only way the player side
+/// changes an encounter, so the command log is enough to replay a battle
+/// from its starting state and seed.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Command {
+    Move { unit_id: String, destination: Position },
+    Attack { attacker_id: String, target_id: String },
+    ThrowGrenade { thrower_id: String, accessory_index: usize, target: Position },
+    /// Fire a heavy weapon at a structure.
+    AttackTerrain { attacker_id: String, target: Position },
+    /// Lob a shell from an indirect weapon at `target`; see
+    /// [`crate::indirect`].
+    Bombard { attacker_id: String, target: Position },
+    UseAbility { user_id: String, ability_index: usize, target_ids: Vec<String> },
+    /// Use a terrain-altering ability on a cell; see [`crate::terraform`].
+    UseAbilityAt { user_id: String, ability_index: usize, target: Position },
+    /// Lay down suppressing fire on the area around `target`.
+    Suppress { attacker_id: String, target: Position },
+    /// Run at `target_id` and strike it in melee.
+    Charge { attacker_id: String, target_id: String },
+    /// Pick up the loot on the unit's tile.
+    PickUp { unit_id: String },
+    /// Break open the crate at `target`, next to the unit.
+    OpenCrate { unit_id: String, target: Position },
+    /// Spend the unit's remaining AP on a defensive stance.
+    TakeStance { unit_id: String, stance: Stance },
+    /// Spend the unit's remaining AP holding an action back to take out
+    /// of turn; see [`crate::hold`].
+    Hold { unit_id: String, action: HeldAction },
+    /// Turn the unit where it stands; vehicles pay for it in movement.
+    Turn { unit_id: String, facing: Facing },
+    /// Board a friendly vehicle next to the unit; see [`crate::vehicle`].
+    Embark { unit_id: String, vehicle_id: String },
+    /// Get out of the vehicle the unit rides in onto `destination`.
+    Disembark { unit_id: String, destination: Position },
+    /// Fire one of a vehicle's hardpoints at `target_id`.
+    FireHardpoint { vehicle_id: String, hardpoint: usize, target_id: String },
+    /// Put the current unit's turn off until the next unit has acted.
+    Delay,
+    EndTurn,
+}
+
+impl Command {
+    /// Id of the unit carrying out the command; `None` for
+    /// [`Command::EndTurn`] and [`Command::Delay`].
+    pub fn actor(&self) -> Option<&str> {
+        match self {
+            Command::Move { unit_id, .. } => Some(unit_id),
+            Command::Attack { attacker_id, .. } | Command::Charge { attacker_id, .. } => Some(attacker_id),
+            Command::ThrowGrenade { thrower_id, .. } => Some(thrower_id),
+            Command::AttackTerrain { attacker_id, .. }
+            | Command::Bombard { attacker_id, .. }
+            | Command::Suppress { attacker_id, .. } => Some(attacker_id),
+            Command::UseAbility { user_id, .. } | Command::UseAbilityAt { user_id, .. } => Some(user_id),
+            Command::PickUp { unit_id }
+            | Command::OpenCrate { unit_id, .. }
+            | Command::TakeStance { unit_id, .. }
+            | Command::Hold { unit_id, .. }
+            | Command::Turn { unit_id, .. }
+            | Command::Embark { unit_id, .. }
+            | Command::Disembark { unit_id, .. } => Some(unit_id),
+            Command::FireHardpoint { vehicle_id, .. } => Some(vehicle_id),
+            Command::EndTurn | Command::Delay => None,
+        }
+    }
+
+    /// Whether the command attacks or uses an ability, which pinned units
+    /// can't do.
+    pub fn is_offensive(&self) -> bool {
+        matches!(
+            self,
+            Command::Attack { .. }
+                | Command::ThrowGrenade { .. }
+                | Command::AttackTerrain { .. }
+                | Command::Bombard { .. }
+                | Command::UseAbility { .. }
+                | Command::UseAbilityAt { .. }
+                | Command::Suppress { .. }
+                | Command::Charge { .. }
+                | Command::FireHardpoint { .. }
+        )
+    }
+}
+
+/// State needed to revert a move.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MoveUndo {
+    pub unit_id: String,
+    pub from: Position,
+    pub health_points: i32,
+    /// AP and left-over movement points before the move paid for itself.
+    #[serde(default)]
+    pub action_points: u32,
+    #[serde(default)]
+    pub movement_left: u32,
+    #[serde(default)]
+    pub facing: Facing,
+}
+
+impl CombatEncounter {
+    /// Execute a command. Moves are pushed onto the undo stack; any other
+    /// command commits them, clearing the stack.
+    pub fn execute(&mut self, cmd: Command) -> Result<(), CombatError> {
+        let events_before = self.events.len();
+        // Only the unit whose turn it is acts, once turns have begun.
+        if let (Some(actor), Some(current)) = (cmd.actor(), &self.turn_order.current_unit_id)
+            && actor != current
+        {
+            return Err(CombatError::WrongPhase);
+        }
+        if cmd.is_offensive() && cmd.actor().and_then(|id| self.unit_by_id(id)).is_some_and(|u| u.is_pinned()) {
+            return Err(CombatError::Pinned);
+        }
+        if cmd.actor().and_then(|id| self.unit_by_id(id)).is_some_and(|u| u.is_charging()) {
+            return Err(CombatError::AbilityCharging);
+        }
+        let embarked = cmd.actor().and_then(|id| self.unit_by_id(id)).is_some_and(|u| u.embarked_in.is_some());
+        if embarked && !matches!(cmd, Command::Disembark { .. }) {
+            return Err(CombatError::Embarked);
+        }
+        match &cmd {
+            Command::Move { unit_id, destination } => {
+                let undo = self.walk(unit_id, destination)?;
+                let held = self.events[events_before..].iter().any(|e| matches!(e, CombatEvent::HeldActionTriggered { .. }));
+                // Being shot at can't be taken back.
+                if self.trigger_overwatch(unit_id) || held {
+                    self.undo_stack.clear();
+                } else {
+                    self.undo_stack.push(undo);
+                }
+            }
+            // Dice are rolled only once an attack is sure to go ahead, so a
+            // refused command leaves the RNG where replays and peers expect.
+            Command::Attack { attacker_id, target_id } => {
+                self.check_attack(attacker_id, target_id, &self.equipped_weapon(attacker_id)?)?;
+                let roll = self.rng.roll_d100();
+                self.attack_unit(attacker_id, target_id, roll, AttackModifiers::default())?;
+                self.undo_stack.clear();
+            }
+            Command::Charge { attacker_id, target_id } => {
+                self.charge_destination(attacker_id, target_id)?;
+                let roll = self.rng.roll_d100();
+                // Cut down or not, the run in happened, so the command stands.
+                self.charge(attacker_id, target_id, roll)?;
+                self.undo_stack.clear();
+            }
+            Command::ThrowGrenade { thrower_id, accessory_index, target } => {
+                self.throw_grenade(thrower_id, *accessory_index, target)?;
+                self.undo_stack.clear();
+            }
+            Command::AttackTerrain { attacker_id, target } => {
+                self.attack_terrain(attacker_id, target)?;
+                self.undo_stack.clear();
+            }
+            Command::Bombard { attacker_id, target } => {
+                self.bombard(attacker_id, target)?;
+                self.undo_stack.clear();
+            }
+            Command::Suppress { attacker_id, target } => {
+                self.suppress(attacker_id, target)?;
+                self.undo_stack.clear();
+            }
+            Command::UseAbility { user_id, ability_index, target_ids } => {
+                self.execute_ability(user_id, *ability_index, target_ids)?;
+                self.resolve_ability_holds(user_id);
+                self.undo_stack.clear();
+            }
+            Command::UseAbilityAt { user_id, ability_index, target } => {
+                self.use_ability_at(user_id, *ability_index, target)?;
+                self.resolve_ability_holds(user_id);
+                self.undo_stack.clear();
+            }
+            Command::PickUp { unit_id } => {
+                self.pick_up(unit_id)?;
+                self.undo_stack.clear();
+            }
+            Command::OpenCrate { unit_id, target } => {
+                self.open_crate(unit_id, target)?;
+                self.undo_stack.clear();
+            }
+            Command::TakeStance { unit_id, stance } => {
+                self.take_stance(unit_id, *stance)?;
+                self.undo_stack.clear();
+            }
+            Command::Hold { unit_id, action } => {
+                self.hold_action(unit_id, action.clone())?;
+                self.undo_stack.clear();
+            }
+            Command::Turn { unit_id, facing } => {
+                self.turn_to(unit_id, *facing)?;
+                self.undo_stack.clear();
+            }
+            Command::Embark { unit_id, vehicle_id } => {
+                self.embark(unit_id, vehicle_id)?;
+                self.undo_stack.clear();
+            }
+            Command::Disembark { unit_id, destination } => {
+                self.disembark(unit_id, destination)?;
+                self.undo_stack.clear();
+            }
+            Command::FireHardpoint { vehicle_id, hardpoint, target_id } => {
+                self.check_hardpoint(vehicle_id, *hardpoint, target_id)?;
+                let roll = self.rng.roll_d100();
+                self.fire_hardpoint(vehicle_id, *hardpoint, target_id, roll)?;
+                self.undo_stack.clear();
+            }
+            Command::Delay => {
+                self.delay_turn()?;
+                self.undo_stack.clear();
+            }
+            Command::EndTurn => {
+                self.end_turn();
+                self.undo_stack.clear();
+            }
+        }
+        self.settle(events_before);
+        // A scripted moment the command set off can't be taken back either.
+        if self.check_interrupts() > 0 {
+            self.undo_stack.clear();
+        }
+        self.command_log.push(cmd);
+        Ok(())
+    }
+
+    /// Deal with what the events from `since` on set off: passengers turned
+    /// out of wrecks, reactions, dropped loot and broken channels.
+    pub(crate) fn settle(&mut self, since: usize) {
+        self.release_passengers();
+        self.react(since);
+        self.drop_loot(since);
+        self.interrupt_channels(since);
+    }
+
+    /// Execute a command on behalf of a human player. Refused unless it is
+    /// that player's turn and they control the acting unit (or, for
+    /// [`Command::EndTurn`], the unit whose turn it is).
+    pub fn execute_as(&mut self, player: u8, cmd: Command) -> Result<(), CombatError> {
+        if self.active_player != Some(player) {
+            return Err(CombatError::WrongPhase);
+        }
+        let actor = cmd.actor().map(str::to_string).or_else(|| self.turn_order.current_unit_id.clone());
+        let controller = actor.and_then(|id| self.controller_of(&id));
+        if controller != Some(Controller::Player(player)) {
+            return Err(CombatError::NotControlled);
+        }
+        self.execute(cmd)
+    }
+
+    /// Move a unit to `destination`, reporting the trigger regions it
+    /// crosses and setting off the holds it walks into. Returns what's
+    /// needed to take the move back.
+    pub(crate) fn walk(&mut self, unit_id: &str, destination: &Position) -> Result<MoveUndo, CombatError> {
+        let bonus = self.unit_by_id(unit_id).map(|u| self.movement_bonus(u)).unwrap_or(0);
+        let map = &self.battlefield;
+        let unit = self.units.by_name_mut(unit_id).ok_or(CombatError::UnknownUnit)?;
+        let undo = MoveUndo {
+            unit_id: unit.id.clone(),
+            from: unit.grid_position.clone(),
+            health_points: unit.health_points,
+            action_points: unit.action_points,
+            movement_left: unit.movement_left,
+            facing: unit.facing,
+        };
+        let path = move_along(unit, destination.clone(), map, bonus, self.weather).ok_or(CombatError::Unreachable)?;
+        for (region, entered) in map.region_crossings(&path) {
+            let (unit_id, region) = (unit_id.to_string(), region.to_string());
+            let event = if entered {
+                CombatEvent::RegionEntered { unit_id, region }
+            } else {
+                CombatEvent::RegionExited { unit_id, region }
+            };
+            self.events.push(event);
+        }
+        self.carry_passengers(unit_id);
+        self.stats.record_move(unit_id, &undo.from, destination);
+        self.moved_this_turn.insert(unit_id.to_string());
+        self.resolve_move_holds(unit_id, &undo.from, &path);
+        Ok(undo)
+    }
+
+    /// Attack `target_id` with the attacker's weapon, adding `extra` to the
+    /// situational bonuses.
+    pub(crate) fn attack_unit(
+        &mut self,
+        attacker_id: &str,
+        target_id: &str,
+        roll: u8,
+        extra: AttackModifiers,
+    ) -> Result<AttackResult, CombatError> {
+        let weapon = self.equipped_weapon(attacker_id)?;
+        self.attack_with(attacker_id, target_id, &weapon, roll, extra)
+    }
+
+    fn equipped_weapon(&self, attacker_id: &str) -> Result<Weapon, CombatError> {
+        let attacker = self.unit_by_id(attacker_id).ok_or(CombatError::InvalidTarget)?;
+        attacker.equipment.weapon.clone().ok_or(CombatError::NoWeapon)
+    }
+
+    /// Why `attacker_id` can't attack `target_id` with `weapon` right now,
+    /// if it can't: checked before any dice are rolled.
+    pub(crate) fn check_attack(&self, attacker_id: &str, target_id: &str, weapon: &Weapon) -> Result<(), CombatError> {
+        let (attacker, target) = self.unit_by_id(attacker_id).zip(self.unit_by_id(target_id)).ok_or(CombatError::InvalidTarget)?;
+        if weapon.indirect.is_some() {
+            return Err(CombatError::FiresAtCells);
+        }
+        if target.health_points <= 0 || target.embarked_in.is_some() {
+            return Err(CombatError::InvalidTarget);
+        }
+        if manhattan(&attacker.grid_position, &target.grid_position) > weapon.range {
+            return Err(CombatError::OutOfRange);
+        }
+        if !weapon.is_melee() && !self.battlefield.has_line_of_sight(&attacker.grid_position, &target.grid_position) {
+            return Err(CombatError::NoLineOfSight);
+        }
+        if attacker.action_points < weapon.action_point_cost {
+            return Err(CombatError::InsufficientAp);
+        }
+        Ok(())
+    }
+
+    /// [`Self::attack_unit`] with `weapon` in place of the equipped one,
+    /// e.g. a vehicle's hardpoint.
+    pub(crate) fn attack_with(
+        &mut self,
+        attacker_id: &str,
+        target_id: &str,
+        weapon: &Weapon,
+        roll: u8,
+        extra: AttackModifiers,
+    ) -> Result<AttackResult, CombatError> {
+        self.check_attack(attacker_id, target_id, weapon)?;
+        let charging = self.moved_this_turn.contains(attacker_id);
+        let stacks = &self.faction_modifiers;
+        let ids = self.units.id(attacker_id).zip(self.units.id(target_id));
+        let (attacker, target) = ids.and_then(|(a, t)| self.units.pair_mut(a, t)).ok_or(CombatError::InvalidTarget)?;
+        let target_hp = target.health_points;
+        let mut mods = AttackModifiers::from_stacks(stacks, attacker, target, weapon, charging, 0)
+            .in_weather(self.weather, weapon);
+        mods.accuracy += extra.accuracy;
+        mods.damage += extra.damage;
+        mods.cover += extra.cover;
+        let result = resolve_attack_with(attacker, weapon, target, roll, 0, mods, &self.balance)?;
+        if result.jammed {
+            self.events.push(CombatEvent::WeaponJammed { unit_id: attacker_id.to_string() });
+        }
+        if result.hit {
+            let (attacker_id, target_id) = (attacker_id.to_string(), target_id.to_string());
+            self.events.push(CombatEvent::UnitAttacked { attacker_id, target_id, melee: weapon.is_melee() });
+        }
+        let event = hp_change_event(&target.id, target_hp, target.health_points, result.critical);
+        self.events.extend(event);
+        self.stats.record_attack(attacker_id, target_id, result.hit, target_hp - target.health_points);
+        self.stats.record_kill(attacker_id, target_hp, target.health_points);
+        if result.hit && let Some(forced) = weapon.forced_move {
+            self.force_move(attacker_id, target_id, forced);
+        }
+        Ok(result)
+    }
+
+    /// Revert the most recent uncommitted move. Returns false when there is
+    /// nothing to undo.
+    pub fn undo(&mut self) -> bool {
+        let Some(undo) = self.undo_stack.pop() else { return false };
+        if let Some(unit) = self.units.by_name_mut(&undo.unit_id) {
+            self.stats.undo_move(&undo.unit_id, &undo.from, &unit.grid_position);
+            unit.grid_position = undo.from;
+            unit.health_points = undo.health_points;
+            unit.action_points = undo.action_points;
+            unit.movement_left = undo.movement_left;
+            unit.facing = undo.facing;
+            self.carry_passengers(&undo.unit_id);
+        }
+        if !self.undo_stack.iter().any(|u| u.unit_id == undo.unit_id) {
+            self.moved_this_turn.remove(&undo.unit_id);
+        }
+        self.command_log.pop();
+        true
+    }
+
+    pub fn can_undo(&self) -> bool {
+        !self.undo_stack.is_empty()
+    }
+
+    /// Execute a recorded command sequence, stopping at the first failure.
+    pub fn replay(&mut self, commands: &[Command]) -> Result<(), CombatError> {
+        for cmd in commands {
+            self.execute(cmd.clone())?;
+        }
+        Ok(())
+    }
+
+    pub(crate) fn execute_ability(&mut self, user_id: &str, ability_index: usize, target_ids: &[String]) -> Result<(), CombatError> {
+        let user = self.units.id(user_id).ok_or(CombatError::UnknownUnit)?;
+        let target_handles = target_ids
+            .iter()
+            .map(|id| self.units.id(id).ok_or(CombatError::InvalidTarget))
+            .collect::<Result<Vec<UnitId>, _>>()?;
+        if target_handles.iter().any(|&t| self.units[t].health_points <= 0 || self.units[t].embarked_in.is_some()) {
+            return Err(CombatError::InvalidTarget);
+        }
+        let (user, mut targets) = self.units.split_mut(user, &target_handles).ok_or(CombatError::UnknownUnit)?;
+        let before: Vec<i32> = targets.iter().map(|t| t.health_points).collect();
+        let result = use_ability(user, ability_index, &mut targets, None);
+        let landed = result.is_ok() && !user.is_charging();
+        let hazard = landed.then(|| user.abilities[ability_index].effect.creates_hazard.clone()).flatten();
+        let forced = landed.then_some(user.abilities[ability_index].effect.forced_move).flatten();
+        if landed && user.abilities[ability_index].effect.status_applied == Some(EffectType::Taunted) {
+            for target in targets.iter_mut() {
+                target.taunted_by = Some(user.id.clone());
+            }
+        }
+        let cells: Vec<Position> = targets.iter().map(|t| t.grid_position.clone()).collect();
+        let events: Vec<_> = targets
+            .iter()
+            .zip(&before)
+            .filter_map(|(t, hp)| hp_change_event(&t.id, *hp, t.health_points, false))
+            .collect();
+        self.events.extend(events);
+        if result.is_ok() {
+            self.stats.record_ability(user_id);
+            for (target, hp) in targets.iter().zip(before) {
+                self.stats.record_damage(Some(user_id), &target.id, hp - target.health_points);
+                self.stats.record_kill(user_id, hp, target.health_points);
+            }
+        }
+        if let Some(hazard) = hazard {
+            self.spawn_hazard(&hazard, &cells);
+        }
+        if let Some(forced) = forced {
+            for target_id in target_ids {
+                self.force_move(user_id, target_id, forced);
+            }
+        }
+        result
+    }
+
+    /// Living units on the actor's own side that `cmd` would hurt if it
+    /// lands where aimed: an attack's target, or anyone in a grenade's
+    /// blast, the thrower included. Scatter is not predicted.
+    pub fn friendly_fire(&self, cmd: &Command) -> Vec<&Unit> {
+        let Some(side) = cmd.actor().and_then(|id| self.units.id(id)).and_then(|id| self.units.side(id)) else {
+            return Vec::new();
+        };
+        let cells = match cmd {
+            Command::Attack { target_id, .. } | Command::Charge { target_id, .. } => {
+                self.unit_by_id(target_id).map(|t| vec![t.grid_position.clone()]).unwrap_or_default()
+            }
+            Command::ThrowGrenade { thrower_id, accessory_index, target } => {
+                match self.unit_by_id(thrower_id).and_then(|u| u.equipment.accessory_slots.get(*accessory_index)) {
+                    Some(Accessory::Grenade { aoe_radius, .. }) => blast_cells(&self.battlefield, target, *aoe_radius),
+                    _ => Vec::new(),
+                }
+            }
+            _ => Vec::new(),
+        };
+        self.units
+            .ids()
+            .filter(|&id| self.units.side(id) == Some(side))
+            .map(|id| &self.units[id])
+            .filter(|u| u.health_points > 0 && cells.contains(&u.grid_position))
+            .collect()
+    }
+
+    /// Mutable references to two distinct units, on the same or opposite sides.
+    pub fn pair_mut(&mut self, a: &str, b: &str) -> Option<(&mut Unit, &mut Unit)> {
+        let (a, b) = (self.units.id(a)?, self.units.id(b)?);
+        self.units.pair_mut(a, b)
+    }
+}
@@ -0,0 +1,182 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::combat::{manhattan, CombatEncounter};
+use crate::grid::TerrainType;
+use crate::models::{Faction, Position};
+use crate::registry::UnitId;
+
+/// The part a commander gives a squad member for the round.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum SquadRole {
+    /// Work around the focus target to come at it from the far side.
+    Flanker,
+    /// Shoot at the focus target from range.
+    FireSupport,
+    /// Make for the nearest objective, ignoring the fight until there.
+    ObjectiveRunner,
+}
+
+/// Squad-level AI for one faction. Each round it hands out roles, and it
+/// pools what its units have seen so they only go after opponents one of
+/// them has spotted, all focusing fire on one of them.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Commander {
+    /// Cells worth taking; the unit nearest one is sent to run for them.
+    pub objectives: Vec<Position>,
+    pub roles: HashMap<String, SquadRole>,
+    /// Where each living opponent the squad has seen was last seen.
+    pub spotted: HashMap<String, Position>,
+    /// The opponent the squad concentrates on.
+    pub focus: Option<String>,
+}
+
+impl Commander {
+    pub fn new(objectives: Vec<Position>) -> Self {
+        Self { objectives, ..Self::default() }
+    }
+
+    pub fn role(&self, unit_id: &str) -> Option<SquadRole> {
+        self.roles.get(unit_id).copied()
+    }
+}
+
+impl CombatEncounter {
+    /// Put a commander in charge of `faction`'s AI units, planning at once.
+    pub fn set_commander(&mut self, faction: Faction, commander: Commander) {
+        self.commanders.insert(faction.clone(), commander);
+        self.plan_squad(&faction);
+    }
+
+    /// Have every commander take stock, pick a focus target and hand out
+    /// roles. Called at the start of each round.
+    pub fn plan_squads(&mut self) {
+        let factions: Vec<Faction> = self.commanders.keys().cloned().collect();
+        for faction in factions {
+            self.plan_squad(&faction);
+        }
+    }
+
+    fn plan_squad(&mut self, faction: &Faction) {
+        self.spot_for(faction);
+        let members: Vec<(String, Position, bool)> = self
+            .units
+            .iter()
+            .filter(|u| u.faction == *faction && u.health_points > 0)
+            .map(|u| (u.id.clone(), u.grid_position.clone(), u.equipment.weapon.as_ref().is_some_and(|w| !w.is_melee())))
+            .collect();
+        let Some(commander) = self.commanders.get(faction) else { return };
+        // The weakest opponent in sight, then the one closest to the squad.
+        let focus = commander
+            .spotted
+            .iter()
+            .filter_map(|(id, seen)| Some((self.unit_by_id(id)?.health_points, seen, id)))
+            .min_by_key(|&(hp, seen, id)| (hp, members.iter().map(|(_, pos, _)| manhattan(pos, seen)).sum::<u32>(), id))
+            .map(|(_, _, id)| id.clone());
+        let runner = members
+            .iter()
+            .filter_map(|(id, pos, _)| Some((commander.objectives.iter().map(|o| manhattan(pos, o)).min()?, id)))
+            .min()
+            .map(|(_, id)| id.clone());
+        let roles = members
+            .into_iter()
+            .map(|(id, _, ranged)| {
+                let role = if runner.as_ref() == Some(&id) {
+                    SquadRole::ObjectiveRunner
+                } else if ranged {
+                    SquadRole::FireSupport
+                } else {
+                    SquadRole::Flanker
+                };
+                (id, role)
+            })
+            .collect();
+        if let Some(commander) = self.commanders.get_mut(faction) {
+            commander.focus = focus;
+            commander.roles = roles;
+        }
+    }
+
+    /// Add the opponents `faction`'s side knows of to its commander's
+    /// spotted list, forgetting the fallen.
+    fn spot_for(&mut self, faction: &Faction) {
+        let Some(member) = self.units.ids().find(|&i| self.units[i].faction == *faction) else { return };
+        let Some(view) = self.knowledge_of(member) else { return };
+        let seen: Vec<(String, Position)> = view.opponents().map(|u| (u.id.clone(), u.grid_position.clone())).collect();
+        let alive: Vec<String> = self.units.iter().filter(|u| u.health_points > 0).map(|u| u.id.clone()).collect();
+        let Some(commander) = self.commanders.get_mut(faction) else { return };
+        commander.spotted.retain(|id, _| alive.contains(id));
+        commander.spotted.extend(seen);
+        if commander.focus.as_ref().is_some_and(|f| !commander.spotted.contains_key(f)) {
+            commander.focus = None;
+        }
+    }
+
+    /// Let the commander of the unit's faction, if any, see what it sees.
+    pub(crate) fn spot(&mut self, actor: UnitId) {
+        let faction = self.units[actor].faction.clone();
+        if self.commanders.contains_key(&faction) {
+            self.spot_for(&faction);
+        }
+    }
+
+    fn commander_of(&self, actor: UnitId) -> Option<&Commander> {
+        self.commanders.get(&self.units[actor].faction)
+    }
+
+    /// The objective an AI unit sent running should head for, until it
+    /// stands on one.
+    pub(crate) fn runner_objective(&self, actor: UnitId) -> Option<Position> {
+        let commander = self.commander_of(actor)?;
+        let unit = &self.units[actor];
+        if commander.role(&unit.id) != Some(SquadRole::ObjectiveRunner) || commander.objectives.contains(&unit.grid_position) {
+            return None;
+        }
+        commander.objectives.iter().min_by_key(|o| manhattan(&unit.grid_position, o)).cloned()
+    }
+
+    /// The opponent an AI unit goes for. Under a commander that is the
+    /// squad's focus target, or failing that the nearest opponent the squad
+    /// has spotted; otherwise the nearest its side knows of, see
+    /// [`KnowledgeView::target_for`]. Taunts win out either way.
+    ///
+    /// [`KnowledgeView::target_for`]: crate::knowledge::KnowledgeView::target_for
+    pub fn ai_target(&self, actor: UnitId) -> Option<UnitId> {
+        let Some(commander) = self.commander_of(actor) else { return self.knowledge_of(actor)?.target_for(actor) };
+        if self.units[actor].taunter().is_none()
+            && let Some(focus) = commander.focus.as_deref().and_then(|f| self.units.id(f))
+            && self.units[focus].health_points > 0
+        {
+            return Some(focus);
+        }
+        self.units.ai_target_where(actor, |u| commander.spotted.contains_key(&u.id))
+    }
+
+    /// Where an AI unit closing on `target` heads. Under a commander that
+    /// is where the squad last saw it, and for flankers the open cell just
+    /// beyond it from the squad's middle; otherwise the open cell behind
+    /// it, to come at its rear, or failing that its own cell.
+    pub(crate) fn squad_destination(&self, actor: UnitId, target: UnitId) -> Position {
+        let target = &self.units[target];
+        let Some(commander) = self.commander_of(actor) else {
+            let open = |p: &Position| self.battlefield.in_bounds(p) && *self.battlefield.terrain_at(p) != TerrainType::Blocked;
+            return target.behind().filter(open).unwrap_or_else(|| target.grid_position.clone());
+        };
+        let seen = commander.spotted.get(&target.id).unwrap_or(&target.grid_position).clone();
+        if commander.role(&self.units[actor].id) != Some(SquadRole::Flanker) {
+            return seen;
+        }
+        let squad: Vec<&Position> = commander.roles.keys().filter_map(|id| self.unit_by_id(id)).map(|u| &u.grid_position).collect();
+        let count = squad.len().max(1) as i64;
+        let middle_x = squad.iter().map(|p| p.x as i64).sum::<i64>() / count;
+        let middle_y = squad.iter().map(|p| p.y as i64).sum::<i64>() / count;
+        let (x, y) = (seen.x as i64 + (seen.x as i64 - middle_x).signum(), seen.y as i64 + (seen.y as i64 - middle_y).signum());
+        let beyond = Position { x: x as usize, y: y as usize };
+        let open = x >= 0
+            && y >= 0
+            && self.battlefield.in_bounds(&beyond)
+            && *self.battlefield.terrain_at(&beyond) != TerrainType::Blocked;
+        if open { beyond } else { seen }
+    }
+}
@@ -0,0 +1,145 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::assets::AssetProvider;
+use crate::heroes::HeroTemplate;
+use crate::models::{Ability, Accessory, Armor, Faction, Stats, Trait, Unit, UnitType, Weapon};
+
+/// Any piece of equipment the campaign can hold.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Item {
+    Weapon(Weapon),
+    Armor(Armor),
+    Accessory(Accessory),
+}
+
+impl Item {
+    pub fn name(&self) -> &str {
+        match self {
+            Item::Weapon(w) => &w.name,
+            Item::Armor(a) => &a.name,
+            Item::Accessory(Accessory::Grenade { .. }) => "Grenade",
+            Item::Accessory(Accessory::Stimpack { .. }) => "Stimpack",
+            Item::Accessory(Accessory::Medkit { .. }) => "Medkit",
+            Item::Accessory(Accessory::Bionics { .. }) => "Bionics",
+        }
+    }
+}
+
+/// A stocked item with its requisition price.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CatalogEntry {
+    pub price: u32,
+    /// Completed missions needed before the item can be requisitioned.
+    #[serde(default)]
+    pub unlocks_after: u32,
+    pub item: Item,
+}
+
+/// A kind of unit that arrives mid-battle, such as a scripted
+/// reinforcement.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UnitTemplate {
+    pub name: String,
+    pub unit_type: UnitType,
+    pub faction: Faction,
+    pub base_stats: Stats,
+    #[serde(default)]
+    pub weapon: Option<Weapon>,
+    #[serde(default)]
+    pub abilities: Vec<Ability>,
+    #[serde(default)]
+    pub sprite_id: String,
+}
+
+impl UnitTemplate {
+    /// A unit made from the template going by `id`, at full health and
+    /// action points.
+    pub fn build(&self, id: &str) -> Unit {
+        let mut unit = Unit::new(id, &self.name, self.unit_type.clone(), self.faction.clone());
+        unit.base_stats = self.base_stats.clone();
+        unit.equipment.weapon = self.weapon.clone();
+        unit.abilities = self.abilities.clone();
+        unit.sprite_id = self.sprite_id.clone();
+        unit.traits = Trait::for_unit_type(&self.unit_type);
+        unit.recalculate_stats();
+        unit.health_points = unit.current_stats.max_health;
+        unit.action_points = unit.current_stats.max_action;
+        unit
+    }
+}
+
+/// Static game content keyed by id, loaded from JSON.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ContentDatabase {
+    pub items: BTreeMap<String, CatalogEntry>,
+    /// Named characters keyed by hero id.
+    #[serde(default)]
+    pub heroes: BTreeMap<String, HeroTemplate>,
+    /// Units reinforcements are made from, keyed by template id.
+    #[serde(default)]
+    pub units: BTreeMap<String, UnitTemplate>,
+}
+
+impl ContentDatabase {
+    /// Load a database. Weapon and armor ids must match their keys so that
+    /// equipped items can be returned to the campaign inventory, and hero
+    /// ability trees must be well formed.
+    pub fn load<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        Self::parse(&fs::read_to_string(path)?)
+    }
+
+    /// [`ContentDatabase::load`] from `path` in `assets`.
+    pub fn load_from(assets: &dyn AssetProvider, path: &str) -> io::Result<Self> {
+        Self::parse(&assets.read_to_string(path)?)
+    }
+
+    fn parse(data: &str) -> io::Result<Self> {
+        let db: ContentDatabase =
+            serde_json::from_str(data).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        for (key, entry) in &db.items {
+            let id = match &entry.item {
+                Item::Weapon(w) => Some(&w.id),
+                Item::Armor(a) => Some(&a.id),
+                Item::Accessory(_) => None,
+            };
+            if id.is_some_and(|id| id != key) {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("item '{}' has mismatched id", key),
+                ));
+            }
+        }
+        for (id, hero) in &db.heroes {
+            hero.validate(id)?;
+        }
+        Ok(db)
+    }
+
+    pub fn get(&self, id: &str) -> Option<&CatalogEntry> {
+        self.items.get(id)
+    }
+
+    /// Catalog id of an accessory, found by value since accessories carry
+    /// no id of their own.
+    pub fn accessory_id(&self, accessory: &Accessory) -> Option<&str> {
+        self.items
+            .iter()
+            .find(|(_, e)| matches!(&e.item, Item::Accessory(a) if a == accessory))
+            .map(|(id, _)| id.as_str())
+    }
+
+    /// Ids of items unlocked after `missions_completed` missions, in id order.
+    pub fn available(&self, missions_completed: u32) -> Vec<&str> {
+        self.items
+            .iter()
+            .filter(|(_, e)| e.unlocks_after <= missions_completed)
+            .map(|(id, _)| id.as_str())
+            .collect()
+    }
+}
@@ -0,0 +1,171 @@
+use serde::{Deserialize, Serialize};
+
+use crate::combat::{hp_change_event, CombatEncounter};
+use crate::models::{ArmorProperty, EffectType, StatusEffect, Unit, UnitType};
+
+/// Turns a unit set alight by fire damage keeps burning.
+pub const BURN_TURNS: u32 = 2;
+/// Turns a unit poisoned by toxic damage stays poisoned.
+pub const POISON_TURNS: u32 = 3;
+/// Damage poison deals each turn.
+pub const POISON_DAMAGE: i32 = 1;
+
+/// What a weapon or ability hurts with. Each type is soaked by a
+/// different stat, resisted separately and may leave a status behind.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum DamageType {
+    /// Bullets, blades and shrapnel.
+    #[default]
+    Kinetic,
+    /// Las and plasma.
+    Energy,
+    /// Flames; they find gaps in armour and can set the target alight.
+    Fire,
+    /// Gas and venom; they ignore armour and can poison the target.
+    Toxic,
+    /// Psychic power, soaked by willpower rather than toughness.
+    Warp,
+}
+
+impl DamageType {
+    /// Localization key for the type's display name.
+    pub fn label_key(&self) -> &'static str {
+        match self {
+            DamageType::Kinetic => "damage.kinetic",
+            DamageType::Energy => "damage.energy",
+            DamageType::Fire => "damage.fire",
+            DamageType::Toxic => "damage.toxic",
+            DamageType::Warp => "damage.warp",
+        }
+    }
+
+    /// Damage the defender shrugs off before resistances.
+    fn soak(&self, defender: &Unit) -> i32 {
+        match self {
+            DamageType::Kinetic | DamageType::Energy => defender.current_stats.toughness,
+            DamageType::Fire => defender.current_stats.toughness / 2,
+            DamageType::Toxic => 0,
+            DamageType::Warp => defender.current_stats.willpower,
+        }
+    }
+
+    /// The status the type leaves on a unit it hurts, as
+    /// `(effect, turns, damage per turn)`.
+    fn rider(&self, damage: i32) -> Option<(EffectType, u32, i32)> {
+        match self {
+            DamageType::Fire => Some((EffectType::Burning, BURN_TURNS, (damage / 2).max(1))),
+            DamageType::Toxic => Some((EffectType::Poison, POISON_TURNS, POISON_DAMAGE)),
+            _ => None,
+        }
+    }
+}
+
+impl EffectType {
+    /// Damage type of the harm the status deals each turn, if any.
+    fn damage_type(&self) -> Option<DamageType> {
+        match self {
+            EffectType::Burning => Some(DamageType::Fire),
+            EffectType::Poison => Some(DamageType::Toxic),
+            _ => None,
+        }
+    }
+}
+
+impl UnitType {
+    /// Percent of `damage_type` the unit's body resists.
+    pub fn resistance(&self, damage_type: DamageType) -> i32 {
+        match (self, damage_type) {
+            (UnitType::Daemon | UnitType::Chimera | UnitType::Trukk, DamageType::Toxic) => 100,
+            (UnitType::Daemon, DamageType::Kinetic) => 25,
+            (UnitType::TechPriest, DamageType::Toxic) => 50,
+            (UnitType::SpaceMarine | UnitType::OrkBoy | UnitType::OrkNob, DamageType::Toxic) => 25,
+            (UnitType::ChaosMarine | UnitType::Weirdboy, DamageType::Warp) => 25,
+            _ => 0,
+        }
+    }
+}
+
+impl ArmorProperty {
+    /// Percent of `damage_type` the property resists.
+    pub fn resistance(&self, damage_type: DamageType) -> i32 {
+        match (self, damage_type) {
+            (ArmorProperty::ReactivePlating, DamageType::Kinetic) => 25,
+            (ArmorProperty::InoculatedCeramite, DamageType::Toxic) => 50,
+            _ => 0,
+        }
+    }
+}
+
+impl Unit {
+    /// Percent of `damage_type` the unit resists, from its body and its
+    /// armour, up to immunity.
+    pub fn resistance(&self, damage_type: DamageType) -> i32 {
+        let armor = self.equipment.armor.iter().flat_map(|a| &a.special_properties);
+        let total = self.unit_type.resistance(damage_type) + armor.map(|p| p.resistance(damage_type)).sum::<i32>();
+        total.min(100)
+    }
+
+    /// Take burning and poison damage at the start of the unit's turn.
+    pub(crate) fn suffer_statuses(&mut self) {
+        let ticks: Vec<(DamageType, i32)> = self
+            .status_effects
+            .iter()
+            .filter(|s| s.remaining_turns > 0)
+            .filter_map(|s| Some((s.effect_type.damage_type()?, s.magnitude)))
+            .collect();
+        for (damage_type, amount) in ticks {
+            self.health_points -= resist(amount, damage_type, self);
+        }
+    }
+
+    /// Count down statuses at the end of the unit's turn, dropping the
+    /// expired ones.
+    pub(crate) fn tick_statuses(&mut self) {
+        for status in &mut self.status_effects {
+            status.remaining_turns = status.remaining_turns.saturating_sub(1);
+        }
+        self.status_effects.retain(|s| s.remaining_turns > 0);
+        if !self.status_effects.iter().any(|s| s.effect_type == EffectType::Taunted) {
+            self.taunted_by = None;
+        }
+    }
+}
+
+/// Apply the defender's resistance to `amount` of `damage_type`.
+pub fn resist(amount: i32, damage_type: DamageType, defender: &Unit) -> i32 {
+    (amount * (100 - defender.resistance(damage_type)) / 100).max(0)
+}
+
+/// Run `raw` damage through the defender's mitigation: first the stat
+/// that soaks `damage_type`, then resistance. Never negative.
+pub fn mitigate(raw: i32, damage_type: DamageType, defender: &Unit) -> i32 {
+    resist((raw - damage_type.soak(defender)).max(0), damage_type, defender)
+}
+
+/// Take mitigated `damage` off the target and, if it hurt, leave the
+/// type's status behind. A fresh status replaces one already running.
+pub fn inflict(target: &mut Unit, damage: i32, damage_type: DamageType) {
+    target.health_points -= damage;
+    if damage <= 0 {
+        return;
+    }
+    let Some((effect_type, remaining_turns, magnitude)) = damage_type.rider(damage) else { return };
+    target.status_effects.retain(|s| s.effect_type != effect_type);
+    target.status_effects.push(StatusEffect { effect_type, remaining_turns, magnitude });
+}
+
+impl CombatEncounter {
+    /// [`inflict`] `damage` on `unit_id`, credit `source_id` with it in the
+    /// battle stats and report it.
+    pub(crate) fn deal_damage(&mut self, source_id: Option<&str>, unit_id: &str, damage: i32, damage_type: DamageType) {
+        let Some(unit) = self.unit_by_id_mut(unit_id) else { return };
+        let before = unit.health_points;
+        inflict(unit, damage, damage_type);
+        let after = unit.health_points;
+        self.stats.record_damage(source_id, unit_id, before - after);
+        if let Some(source_id) = source_id {
+            self.stats.record_kill(source_id, before, after);
+        }
+        self.events.extend(hp_change_event(unit_id, before, after, false));
+    }
+}
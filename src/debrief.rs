@@ -0,0 +1,158 @@
+use serde::{Deserialize, Serialize};
+
+use crate::campaign::Campaign;
+use crate::combat::CombatEncounter;
+use crate::localization::Localizer;
+use crate::loot::Loot;
+use crate::models::Unit;
+use crate::registry::Side;
+use crate::simulation::{BattleOutcome, XP_PER_KILL};
+use crate::stats::UnitStats;
+
+/// Experience each surviving player unit earns per objective met.
+pub const XP_PER_OBJECTIVE: u32 = 20;
+/// Experience between one level and the next.
+pub const XP_PER_LEVEL: u32 = 100;
+
+/// A goal a mission sets on top of winning.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Objective {
+    /// Bring down every enemy.
+    Eliminate,
+    /// Win without a player unit going down.
+    NoCasualties,
+    /// Win within this many rounds.
+    WinWithin { rounds: u32 },
+    /// End the battle with a player unit standing in the named region.
+    HoldRegion { region: String },
+}
+
+impl Objective {
+    /// Localization key for the objective's description.
+    pub fn label_key(&self) -> &'static str {
+        match self {
+            Objective::Eliminate => "objective.eliminate",
+            Objective::NoCasualties => "objective.no_casualties",
+            Objective::WinWithin { .. } => "objective.win_within",
+            Objective::HoldRegion { .. } => "objective.hold_region",
+        }
+    }
+
+    /// The objective as shown to the player.
+    pub fn describe(&self, loc: &Localizer) -> String {
+        match self {
+            Objective::WinWithin { rounds } => loc.plural(self.label_key(), *rounds as i64, &[]),
+            Objective::HoldRegion { region } => loc.format(self.label_key(), &[("region", region)]),
+            _ => loc.get(self.label_key()),
+        }
+    }
+
+    pub fn is_met(&self, encounter: &CombatEncounter, outcome: BattleOutcome) -> bool {
+        let won = outcome == BattleOutcome::PlayerVictory;
+        match self {
+            Objective::Eliminate => encounter.enemy_units().all(|u| u.health_points <= 0),
+            Objective::NoCasualties => won && encounter.player_units().all(|u| u.health_points > 0),
+            Objective::WinWithin { rounds } => won && encounter.stats.rounds <= *rounds,
+            Objective::HoldRegion { region } => {
+                let Some(region) = encounter.battlefield.regions.iter().find(|r| r.name == *region) else { return false };
+                encounter.player_units().any(|u| u.health_points > 0 && region.contains(&u.grid_position))
+            }
+        }
+    }
+}
+
+/// How one objective went.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ObjectiveResult {
+    pub objective: Objective,
+    pub met: bool,
+}
+
+/// One unit's part in the battle and what it earned.
+#[derive(Debug, Clone, PartialEq)]
+pub struct UnitReport {
+    pub unit_id: String,
+    pub name: String,
+    pub side: Side,
+    pub stats: UnitStats,
+    /// Experience earned; only player units left standing earn any.
+    pub experience: u32,
+    /// Levels the experience takes the unit up.
+    pub levels: u32,
+    pub downed: bool,
+}
+
+/// The results of a finished battle: objectives, what each unit did and
+/// earned, loot kept and the player units lost.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BattleReport {
+    pub outcome: BattleOutcome,
+    pub rounds: u32,
+    pub objectives: Vec<ObjectiveResult>,
+    /// Player units first.
+    pub units: Vec<UnitReport>,
+    /// Loot the squad picked up; lost along with the battle.
+    pub loot: Vec<Loot>,
+    /// Ids of player units that went down.
+    pub casualties: Vec<String>,
+}
+
+impl BattleReport {
+    /// Survivors earn [`XP_PER_KILL`] for each of their own kills and
+    /// [`XP_PER_OBJECTIVE`] for each objective met.
+    pub fn new(encounter: &CombatEncounter, outcome: BattleOutcome) -> Self {
+        let objectives: Vec<ObjectiveResult> = encounter
+            .objectives
+            .iter()
+            .map(|o| ObjectiveResult { objective: o.clone(), met: o.is_met(encounter, outcome) })
+            .collect();
+        let objective_xp = objectives.iter().filter(|o| o.met).count() as u32 * XP_PER_OBJECTIVE;
+        let units = [Side::Player, Side::Enemy]
+            .into_iter()
+            .flat_map(|side| encounter.units.on_side(side).map(move |u| (side, u)))
+            .map(|(side, u)| {
+                let stats = encounter.stats.unit(&u.id);
+                let downed = u.health_points <= 0;
+                let experience = if side == Side::Player && !downed { stats.kills * XP_PER_KILL + objective_xp } else { 0 };
+                let levels = levels_gained(u.experience, experience);
+                UnitReport { unit_id: u.id.clone(), name: u.name.clone(), side, stats, experience, levels, downed }
+            })
+            .collect::<Vec<_>>();
+        let casualties = units.iter().filter(|u| u.side == Side::Player && u.downed).map(|u| u.unit_id.clone()).collect();
+        let loot = if outcome == BattleOutcome::EnemyVictory { Vec::new() } else { encounter.recovered.clone() };
+        Self { outcome, rounds: encounter.stats.rounds, objectives, units, loot, casualties }
+    }
+
+    pub fn unit(&self, unit_id: &str) -> Option<&UnitReport> {
+        self.units.iter().find(|u| u.unit_id == unit_id)
+    }
+}
+
+/// Levels crossed going from `experience` to `experience + gained`.
+fn levels_gained(experience: u32, gained: u32) -> u32 {
+    (experience + gained) / XP_PER_LEVEL - experience / XP_PER_LEVEL
+}
+
+impl Unit {
+    /// Add `experience`, levelling up once per [`XP_PER_LEVEL`] crossed.
+    /// Returns the levels gained.
+    pub fn gain_experience(&mut self, experience: u32) -> u32 {
+        let levels = levels_gained(self.experience, experience);
+        self.experience += experience;
+        for _ in 0..levels {
+            self.level_up(None);
+        }
+        levels
+    }
+}
+
+impl Campaign {
+    /// Pay the report's experience to the roster units that earned it.
+    pub fn award_experience(&mut self, report: &BattleReport) {
+        for unit in &mut self.roster {
+            if let Some(earned) = report.unit(&unit.id) {
+                unit.gain_experience(earned.experience);
+            }
+        }
+    }
+}
@@ -0,0 +1,309 @@
+use std::collections::{BTreeSet, HashSet};
+
+use crate::frontend::text::{Color, WHITE};
+use crate::frontend::{DrawCall, DrawLayer, Renderer};
+use crate::grenade::throw_arc;
+use crate::grid::{find_path, reachable_cells, FlowField, TerrainType};
+use crate::models::{Position, Unit};
+use crate::state::GameState;
+use crate::weather::Weather;
+
+pub const ASTAR_SPRITE: &str = "debug:astar";
+pub const SCORE_SPRITE: &str = "debug:score";
+pub const LOS_SPRITE: &str = "debug:los";
+pub const LOS_BLOCKED_SPRITE: &str = "debug:los_blocked";
+pub const THREAT_SPRITE: &str = "debug:threat";
+
+const ASTAR_TINT: Color = [0.3, 0.9, 1.0, 0.4];
+const SCORE_TINT: Color = [1.0, 1.0, 0.2, 0.25];
+const LOS_TINT: Color = [0.2, 1.0, 0.3, 0.5];
+const LOS_BLOCKED_TINT: Color = [1.0, 0.2, 0.2, 0.5];
+const THREAT_TINT: Color = [1.0, 0.4, 0.0, 0.3];
+
+/// One kind of AI or pathfinding state the debug overlay can draw.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum DebugLayer {
+    /// Cells the A* search expanded on its way to the path target.
+    AstarExplored,
+    /// Flow-field cost of each cell towards the AI's target; lower is better.
+    AiScores,
+    /// Rays from the current unit to each opponent, red where terrain blocks.
+    LineOfSight,
+    /// Cells each opponent could attack after moving this turn.
+    ThreatRanges,
+    /// Round number and initiative order.
+    TurnQueue,
+}
+
+impl DebugLayer {
+    pub const ALL: [DebugLayer; 5] = [
+        DebugLayer::AstarExplored,
+        DebugLayer::AiScores,
+        DebugLayer::LineOfSight,
+        DebugLayer::ThreatRanges,
+        DebugLayer::TurnQueue,
+    ];
+
+    /// Name used by the debug console.
+    pub fn name(&self) -> &'static str {
+        match self {
+            DebugLayer::AstarExplored => "astar",
+            DebugLayer::AiScores => "scores",
+            DebugLayer::LineOfSight => "los",
+            DebugLayer::ThreatRanges => "threat",
+            DebugLayer::TurnQueue => "turns",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<DebugLayer> {
+        DebugLayer::ALL.into_iter().find(|l| l.name() == name)
+    }
+}
+
+/// Draws internal AI and pathfinding state over the battlefield so their
+/// decisions can be inspected. Every layer is off until enabled.
+#[derive(Debug, Clone, Default)]
+pub struct DebugOverlay {
+    pub enabled: BTreeSet<DebugLayer>,
+    /// Destination for the A* layer; the AI's target when unset.
+    pub path_target: Option<Position>,
+}
+
+impl DebugOverlay {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn set(&mut self, layer: DebugLayer, on: bool) {
+        if on {
+            self.enabled.insert(layer);
+        } else {
+            self.enabled.remove(&layer);
+        }
+    }
+
+    /// Flip `layer` and return whether it is now on.
+    pub fn toggle(&mut self, layer: DebugLayer) -> bool {
+        let on = !self.is_enabled(layer);
+        self.set(layer, on);
+        on
+    }
+
+    pub fn is_enabled(&self, layer: DebugLayer) -> bool {
+        self.enabled.contains(&layer)
+    }
+
+    /// Submit draw calls for each enabled layer, for the unit whose turn it
+    /// is, or the selected unit between turns. Call after the world has
+    /// been rendered and before the UI.
+    pub fn render(&self, renderer: &mut Renderer, state: &GameState, weather: Weather) {
+        if self.is_enabled(DebugLayer::TurnQueue) {
+            render_turn_queue(renderer, state);
+        }
+        let Some(actor) = current_unit(state) else { return };
+        let target = ai_target(state, actor);
+        let map = &state.map;
+
+        if self.is_enabled(DebugLayer::ThreatRanges) {
+            let mut threatened = HashSet::new();
+            for enemy in opponents(state, actor) {
+                let Some(weapon) = &enemy.equipment.weapon else { continue };
+                let standing = std::iter::once(enemy.grid_position.clone());
+                for cell in reachable_cells(enemy, map, 0, weather).into_iter().chain(standing) {
+                    threatened.extend(map.cells_within(&cell, weapon.range));
+                }
+            }
+            for cell in &threatened {
+                overlay(renderer, THREAT_SPRITE, cell, THREAT_TINT);
+            }
+        }
+
+        if self.is_enabled(DebugLayer::AiScores)
+            && let Some(target) = target
+        {
+            let field = FlowField::new(map, &target.grid_position, actor.movement_type, weather);
+            let (xs, ys) = renderer.visible_tiles(map);
+            for y in ys {
+                for x in xs.clone() {
+                    let pos = Position { x, y };
+                    if matches!(map.terrain_at(&pos), TerrainType::Blocked) {
+                        continue;
+                    }
+                    let Some(cost) = field.cost(map, &pos) else { continue };
+                    overlay(renderer, SCORE_SPRITE, &pos, SCORE_TINT);
+                    if let Some(px) = cell_to_screen(renderer, &pos) {
+                        renderer.draw_text(&cost.to_string(), px, 12.0, WHITE);
+                    }
+                }
+            }
+        }
+
+        if self.is_enabled(DebugLayer::AstarExplored)
+            && let Some(dest) = self.path_target.clone().or_else(|| target.map(|t| t.grid_position.clone()))
+        {
+            for cell in find_path(actor, &dest, map, 0, weather).explored {
+                overlay(renderer, ASTAR_SPRITE, &cell, ASTAR_TINT);
+            }
+        }
+
+        if self.is_enabled(DebugLayer::LineOfSight) {
+            for enemy in opponents(state, actor) {
+                let ray = throw_arc(&actor.grid_position, &enemy.grid_position);
+                let blocked = !map.has_line_of_sight(&actor.grid_position, &enemy.grid_position);
+                let (sprite, tint) = if blocked { (LOS_BLOCKED_SPRITE, LOS_BLOCKED_TINT) } else { (LOS_SPRITE, LOS_TINT) };
+                for cell in &ray {
+                    overlay(renderer, sprite, cell, tint);
+                }
+            }
+        }
+    }
+}
+
+fn overlay(renderer: &mut Renderer, sprite: &str, pos: &Position, tint: Color) {
+    let call = DrawCall { tint: Some(tint), ..DrawCall::new(sprite, (pos.x as u32, pos.y as u32), DrawLayer::Overlay) };
+    renderer.submit(call);
+}
+
+/// Top-left pixel of `pos` on screen, if it is not scrolled off the top
+/// or left edge.
+fn cell_to_screen(renderer: &Renderer, pos: &Position) -> Option<(u32, u32)> {
+    let zoom = if renderer.camera.zoom_level > 0.0 { renderer.camera.zoom_level } else { 1.0 };
+    let tile = renderer.tile_size as f32 * zoom;
+    let x = pos.x as f32 * tile - renderer.camera.x_offset;
+    let y = pos.y as f32 * tile - renderer.camera.y_offset;
+    (x >= 0.0 && y >= 0.0).then_some((x as u32, y as u32))
+}
+
+fn render_turn_queue(renderer: &mut Renderer, state: &GameState) {
+    let queue = &state.turn_queue;
+    let order: Vec<&str> = queue.initiative.iter().map(String::as_str).collect();
+    let current = queue.current_unit_id.as_deref().unwrap_or("-");
+    let text = format!(
+        "Round {} turn {}: [{}] {}",
+        queue.round_number,
+        queue.turns_this_round,
+        current,
+        order.join(" > ")
+    );
+    renderer.draw_text(&text, (8, renderer.height / 2), 14.0, WHITE);
+}
+
+/// The unit whose turn it is, or else the selected unit.
+fn current_unit(state: &GameState) -> Option<&Unit> {
+    match &state.turn_queue.current_unit_id {
+        Some(id) => state.units.by_name(id),
+        None => state.units.iter().find(|u| u.is_selected),
+    }
+}
+
+/// Living units on the other side from `unit`.
+fn opponents<'s>(state: &'s GameState, unit: &Unit) -> impl Iterator<Item = &'s Unit> {
+    let side = state.units.id(&unit.id).and_then(|i| state.units.side(i));
+    state.units.ids().filter_map(move |i| {
+        let other = &state.units[i];
+        (side.is_some() && state.units.side(i) == side.map(|s| s.opponent()) && other.health_points > 0).then_some(other)
+    })
+}
+
+/// The opponent [`CombatEncounter::ai_take_action`] would go for; see
+/// [`UnitRegistry::ai_target`](crate::registry::UnitRegistry::ai_target).
+///
+/// [`CombatEncounter::ai_take_action`]: crate::combat::CombatEncounter::ai_take_action
+fn ai_target<'s>(state: &'s GameState, unit: &Unit) -> Option<&'s Unit> {
+    let actor = state.units.id(&unit.id)?;
+    state.units.ai_target(actor).map(|id| &state.units[id])
+}
+
+/// Text commands for switching debug layers:
+///
+/// - `debug <layer|all> [on|off|toggle]`, toggling when no state is given
+/// - `debug path <x> <y>` / `debug path off` to pick the A* destination
+/// - `debug list` to show what is enabled
+#[derive(Debug, Clone, Default)]
+pub struct DebugConsole {
+    pub open: bool,
+    /// Line being typed.
+    pub input: String,
+    /// Commands run and their replies, oldest first.
+    pub log: Vec<String>,
+}
+
+/// Lines of [`DebugConsole::log`] drawn under the input line.
+pub const CONSOLE_LINES: usize = 6;
+
+impl DebugConsole {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Run the typed line, logging it and its reply, and clear the input.
+    pub fn submit(&mut self, overlay: &mut DebugOverlay) {
+        let line = std::mem::take(&mut self.input);
+        let reply = match Self::execute(&line, overlay) {
+            Ok(reply) => reply,
+            Err(e) => format!("error: {}", e),
+        };
+        self.log.push(format!("> {}", line));
+        self.log.push(reply);
+    }
+
+    /// Run one command against `overlay` and describe the result.
+    pub fn execute(line: &str, overlay: &mut DebugOverlay) -> Result<String, &'static str> {
+        let words: Vec<&str> = line.split_whitespace().collect();
+        match words.as_slice() {
+            ["debug", "list"] => {
+                let on: Vec<&str> = overlay.enabled.iter().map(DebugLayer::name).collect();
+                Ok(if on.is_empty() { "no layers enabled".to_string() } else { on.join(", ") })
+            }
+            ["debug", "path", "off"] => {
+                overlay.path_target = None;
+                Ok("path target cleared".to_string())
+            }
+            ["debug", "path", x, y] => {
+                let x = x.parse().map_err(|_| "bad x coordinate")?;
+                let y = y.parse().map_err(|_| "bad y coordinate")?;
+                overlay.path_target = Some(Position { x, y });
+                Ok(format!("path target {},{}", x, y))
+            }
+            ["debug", name, rest @ ..] => {
+                let layers: Vec<DebugLayer> = match *name {
+                    "all" => DebugLayer::ALL.to_vec(),
+                    _ => vec![DebugLayer::from_name(name).ok_or("unknown debug layer")?],
+                };
+                let state = match rest {
+                    [] | ["toggle"] => None,
+                    ["on"] => Some(true),
+                    ["off"] => Some(false),
+                    _ => return Err("expected on, off or toggle"),
+                };
+                let on = match state {
+                    Some(on) => on,
+                    // "all" toggles together: on unless everything already is.
+                    None => !layers.iter().all(|l| overlay.is_enabled(*l)),
+                };
+                for layer in &layers {
+                    overlay.set(*layer, on);
+                }
+                Ok(format!("{} {}", name, if on { "on" } else { "off" }))
+            }
+            [] => Err("empty command"),
+            _ => Err("unknown command"),
+        }
+    }
+
+    /// Draw the input line and the most recent log lines in the top-left
+    /// corner while open.
+    pub fn render(&self, renderer: &mut Renderer) {
+        if !self.open {
+            return;
+        }
+        let line_height = (14.0 * renderer.font_scale * 1.25).round() as u32;
+        let mut y = 32;
+        renderer.submit(DrawCall::new("panel.console", (8, y), DrawLayer::Ui));
+        for line in self.log.iter().skip(self.log.len().saturating_sub(CONSOLE_LINES)) {
+            renderer.draw_text(line, (8, y), 14.0, WHITE);
+            y += line_height;
+        }
+        renderer.draw_text(&format!("> {}_", self.input), (8, y), 14.0, WHITE);
+    }
+}
@@ -0,0 +1,138 @@
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use serde::Deserialize;
+
+use crate::campaign::Campaign;
+
+/// A branching conversation loaded from a JSON data file. Lines and choice
+/// labels are localization keys.
+#[derive(Debug, Clone, Deserialize)]
+pub struct DialogueTree {
+    pub id: String,
+    /// Id of the first node.
+    pub start: String,
+    pub nodes: HashMap<String, DialogueNode>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct DialogueNode {
+    pub speaker: String,
+    /// Sprite id of the speaker's portrait.
+    #[serde(default)]
+    pub portrait: Option<String>,
+    pub line: String,
+    /// Player choices; a node without choices continues to `next` or ends.
+    #[serde(default)]
+    pub choices: Vec<DialogueChoice>,
+    #[serde(default)]
+    pub next: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct DialogueChoice {
+    pub text: String,
+    /// Node to continue with; `None` ends the conversation.
+    #[serde(default)]
+    pub next: Option<String>,
+    /// Choice is only offered when this campaign flag is set.
+    #[serde(default)]
+    pub requires_flag: Option<String>,
+    #[serde(default)]
+    pub set_flags: Vec<String>,
+    #[serde(default)]
+    pub grant_items: Vec<String>,
+}
+
+impl DialogueChoice {
+    pub fn is_available(&self, campaign: &Campaign) -> bool {
+        self.requires_flag.as_deref().is_none_or(|f| campaign.has_flag(f))
+    }
+}
+
+impl DialogueTree {
+    /// A conversation of one line with no choices.
+    pub fn single_line(id: &str, speaker: &str, line: &str) -> Self {
+        let node = DialogueNode { speaker: speaker.to_string(), portrait: None, line: line.to_string(), choices: Vec::new(), next: None };
+        Self { id: id.to_string(), start: "start".to_string(), nodes: HashMap::from([("start".to_string(), node)]) }
+    }
+
+    /// Load a tree and check that the start node and every `next` reference
+    /// name an existing node.
+    pub fn load<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        Self::from_json(&fs::read_to_string(path)?)
+    }
+
+    pub fn from_json(data: &str) -> io::Result<Self> {
+        let tree: DialogueTree =
+            serde_json::from_str(data).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        let links = tree
+            .nodes
+            .values()
+            .flat_map(|n| n.next.iter().chain(n.choices.iter().filter_map(|c| c.next.as_ref())));
+        for id in std::iter::once(&tree.start).chain(links) {
+            if !tree.nodes.contains_key(id) {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("dialogue '{}' references unknown node '{}'", tree.id, id),
+                ));
+            }
+        }
+        Ok(tree)
+    }
+}
+
+/// Walks a [`DialogueTree`], applying choice outcomes to the campaign.
+#[derive(Debug, Clone)]
+pub struct DialoguePlayer {
+    pub tree: DialogueTree,
+    current: Option<String>,
+}
+
+impl DialoguePlayer {
+    pub fn new(tree: DialogueTree) -> Self {
+        let current = Some(tree.start.clone());
+        Self { tree, current }
+    }
+
+    pub fn current_node(&self) -> Option<&DialogueNode> {
+        self.current.as_ref().and_then(|id| self.tree.nodes.get(id))
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.current.is_none()
+    }
+
+    /// Choices on the current node that the campaign qualifies for.
+    pub fn available_choices(&self, campaign: &Campaign) -> Vec<&DialogueChoice> {
+        self.current_node()
+            .map(|n| n.choices.iter().filter(|c| c.is_available(campaign)).collect())
+            .unwrap_or_default()
+    }
+
+    /// Continue past a node that has no choices.
+    pub fn advance(&mut self) -> Result<(), &'static str> {
+        let node = self.current_node().ok_or("dialogue finished")?;
+        if !node.choices.is_empty() {
+            return Err("a choice is required");
+        }
+        self.current = node.next.clone();
+        Ok(())
+    }
+
+    /// Pick the `index`th available choice, setting its flags and granting
+    /// its items.
+    pub fn choose(&mut self, index: usize, campaign: &mut Campaign) -> Result<(), &'static str> {
+        let choice = self.available_choices(campaign).get(index).copied().cloned().ok_or("invalid choice")?;
+        for flag in &choice.set_flags {
+            campaign.set_flag(flag);
+        }
+        for item in &choice.grant_items {
+            campaign.grant_item(item);
+        }
+        self.current = choice.next;
+        Ok(())
+    }
+}
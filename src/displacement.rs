@@ -0,0 +1,115 @@
+use serde::{Deserialize, Serialize};
+
+use crate::combat::{hp_change_event, CombatEncounter, CombatEvent};
+use crate::damage::DamageType;
+use crate::grid::{hazard_damage, TerrainType};
+use crate::models::Position;
+
+/// Damage a unit takes when knocked into a wall, the map edge or another
+/// unit. A unit it is knocked into takes the same.
+pub const COLLISION_DAMAGE: i32 = 2;
+
+/// Movement forced on the target of a weapon hit or ability.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ForcedMove {
+    /// Drive the target up to `tiles` further along the line from the
+    /// attacker, until it hits something.
+    Knockback { tiles: u32 },
+    /// Drag the target up to `tiles` toward the attacker, stopping short of
+    /// anything in the way.
+    Pull { tiles: u32 },
+    /// The attacker and the target trade places.
+    Swap,
+}
+
+/// One step from `from` to `to` along each axis: -1, 0 or 1.
+fn heading(from: &Position, to: &Position) -> (i64, i64) {
+    ((to.x as i64 - from.x as i64).signum(), (to.y as i64 - from.y as i64).signum())
+}
+
+impl CombatEncounter {
+    /// Force `target_id` to move as `forced` says, away from or toward
+    /// `source_id`. Unlike a unit's own moves this ignores movement points
+    /// and costs, but stops at walls, the map edge and other units;
+    /// knockbacks that hit something hurt. Returns the cells the target
+    /// was moved through, its old cell first, or nothing if it stayed put.
+    pub fn force_move(&mut self, source_id: &str, target_id: &str, forced: ForcedMove) -> Vec<Position> {
+        let (Some(source), Some(target)) = (self.unit_by_id(source_id), self.unit_by_id(target_id)) else {
+            return Vec::new();
+        };
+        if source.health_points <= 0 || target.health_points <= 0 || source.id == target.id {
+            return Vec::new();
+        }
+        let (from, origin) = (target.grid_position.clone(), source.grid_position.clone());
+        let (tiles, (dx, dy)) = match forced {
+            ForcedMove::Knockback { tiles } => (tiles, heading(&origin, &from)),
+            ForcedMove::Pull { tiles } => (tiles, heading(&from, &origin)),
+            ForcedMove::Swap => {
+                self.place(source_id, &[origin.clone(), from.clone()]);
+                self.place(target_id, &[from.clone(), origin.clone()]);
+                return vec![from, origin];
+            }
+        };
+        let mut path = vec![from];
+        for _ in 0..tiles {
+            let here = &path[path.len() - 1];
+            let (x, y) = (here.x as i64 + dx, here.y as i64 + dy);
+            let next = Position { x: x as usize, y: y as usize };
+            let open = x >= 0 && y >= 0 && self.battlefield.in_bounds(&next);
+            let wall = !open || *self.battlefield.terrain_at(&next) == TerrainType::Blocked;
+            let struck = self
+                .units
+                .iter()
+                .find(|u| u.health_points > 0 && u.id != target_id && u.grid_position == next)
+                .map(|u| u.id.clone());
+            if !wall && struck.is_none() {
+                path.push(next);
+                continue;
+            }
+            if let ForcedMove::Knockback { .. } = forced {
+                self.collide(source_id, target_id);
+                if let Some(struck) = struck {
+                    self.collide(source_id, &struck);
+                }
+            }
+            break;
+        }
+        if path.len() < 2 {
+            return Vec::new();
+        }
+        self.place(target_id, &path);
+        path
+    }
+
+    /// Move a unit along `path` to its last cell and report it. Passengers
+    /// go with a vehicle, regions crossed on the way are entered and left,
+    /// and a unit that lands on hazardous ground is hurt.
+    fn place(&mut self, unit_id: &str, path: &[Position]) {
+        let Some(to) = path.last() else { return };
+        let Some(unit) = self.unit_by_id_mut(unit_id) else { return };
+        let from = std::mem::replace(&mut unit.grid_position, to.clone());
+        self.events.push(CombatEvent::UnitDisplaced { unit_id: unit_id.to_string(), from, to: to.clone() });
+        self.carry_passengers(unit_id);
+        for (region, entered) in self.battlefield.region_crossings(path) {
+            let (unit_id, region) = (unit_id.to_string(), region.to_string());
+            self.events.push(if entered {
+                CombatEvent::RegionEntered { unit_id, region }
+            } else {
+                CombatEvent::RegionExited { unit_id, region }
+            });
+        }
+        if let TerrainType::Hazardous = self.battlefield.terrain_at(to)
+            && let Some(unit) = self.unit_by_id_mut(unit_id)
+        {
+            let before = unit.health_points;
+            unit.health_points -= hazard_damage(unit);
+            let event = hp_change_event(unit_id, before, unit.health_points, false);
+            self.events.extend(event);
+        }
+    }
+
+    /// Deal collision damage to `unit_id`, credited to `source_id`.
+    fn collide(&mut self, source_id: &str, unit_id: &str) {
+        self.deal_damage(Some(source_id), unit_id, COLLISION_DAMAGE, DamageType::Kinetic);
+    }
+}
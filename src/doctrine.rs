@@ -0,0 +1,64 @@
+use serde::{Deserialize, Serialize};
+
+use crate::campaign::Campaign;
+use crate::combat::CombatEncounter;
+use crate::models::Faction;
+use crate::modifiers::{Condition, Modifier, ModifierSource, Stat};
+
+/// A campaign-level battle doctrine granting modifiers to every unit of
+/// one faction.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Doctrine {
+    /// Imperial: +10 cover bonus while in cover.
+    HoldTheLine,
+    /// Ork: +2 melee damage when charging.
+    Waaagh,
+    /// Chaos: +1 movement.
+    FrenziedAdvance,
+}
+
+impl Doctrine {
+    pub fn faction(&self) -> Faction {
+        match self {
+            Doctrine::HoldTheLine => Faction::Imperial,
+            Doctrine::Waaagh => Faction::Ork,
+            Doctrine::FrenziedAdvance => Faction::Chaos,
+        }
+    }
+
+    pub fn label_key(&self) -> &'static str {
+        match self {
+            Doctrine::HoldTheLine => "doctrine.hold_the_line",
+            Doctrine::Waaagh => "doctrine.waaagh",
+            Doctrine::FrenziedAdvance => "doctrine.frenzied_advance",
+        }
+    }
+
+    pub fn modifiers(&self) -> Vec<Modifier> {
+        let (stat, amount, condition) = match self {
+            Doctrine::HoldTheLine => (Stat::CoverBonus, 10, Condition::InCover),
+            Doctrine::Waaagh => (Stat::MeleeDamage, 2, Condition::Charging),
+            Doctrine::FrenziedAdvance => (Stat::Movement, 1, Condition::Always),
+        };
+        vec![Modifier { condition, ..Modifier::new(ModifierSource::Doctrine, stat, amount) }]
+    }
+}
+
+impl Campaign {
+    /// Adopt a doctrine for the rest of the campaign.
+    pub fn adopt_doctrine(&mut self, doctrine: Doctrine) {
+        if !self.doctrines.contains(&doctrine) {
+            self.doctrines.push(doctrine);
+        }
+    }
+}
+
+impl CombatEncounter {
+    /// Add a doctrine's modifiers to its faction's stack.
+    pub fn apply_doctrine(&mut self, doctrine: Doctrine) {
+        let stack = self.faction_modifiers.entry(doctrine.faction()).or_default();
+        for m in doctrine.modifiers() {
+            stack.push(m);
+        }
+    }
+}
@@ -0,0 +1,101 @@
+use crate::campaign::Campaign;
+use crate::models::{Equipment, Unit};
+
+/// Condition of equipment fresh from stores.
+pub const MAX_DURABILITY: u32 = 100;
+/// Durability a weapon loses with each attack.
+pub const WEAPON_WEAR_PER_ATTACK: u32 = 2;
+/// Durability armor loses with each hit that gets through.
+pub const ARMOR_WEAR_PER_HIT: u32 = 4;
+/// Below this, weapons start to jam and armor gives half its protection.
+pub const LOW_DURABILITY: u32 = 25;
+/// Durability restored per point of requisition spent on repairs.
+pub const REPAIR_POINTS_PER_REQUISITION: u32 = 4;
+
+pub(crate) fn full_durability() -> u32 {
+    MAX_DURABILITY
+}
+
+/// Percent chance a weapon in this condition jams: none at
+/// [`LOW_DURABILITY`] and above, rising by one per point below it.
+pub fn jam_chance(durability: u32) -> u32 {
+    LOW_DURABILITY.saturating_sub(durability)
+}
+
+impl Equipment {
+    pub fn weapon_worn(&self) -> bool {
+        self.weapon.is_some() && self.weapon_durability < LOW_DURABILITY
+    }
+
+    pub fn armor_worn(&self) -> bool {
+        self.armor.is_some() && self.armor_durability < LOW_DURABILITY
+    }
+
+    /// Durability missing from equipped items.
+    pub fn wear(&self) -> u32 {
+        let weapon = if self.weapon.is_some() { MAX_DURABILITY - self.weapon_durability.min(MAX_DURABILITY) } else { 0 };
+        let armor = if self.armor.is_some() { MAX_DURABILITY - self.armor_durability.min(MAX_DURABILITY) } else { 0 };
+        weapon + armor
+    }
+}
+
+impl Unit {
+    /// Wear the weapon down after an attack. Returns whether it jammed,
+    /// judged on its condition before the attack, given the attack roll.
+    pub(crate) fn wear_weapon(&mut self, roll: u8) -> bool {
+        if self.equipment.weapon.is_none() {
+            return false;
+        }
+        let durability = self.equipment.weapon_durability;
+        self.equipment.weapon_durability = durability.saturating_sub(WEAPON_WEAR_PER_ATTACK);
+        roll as u32 > 100 - jam_chance(durability)
+    }
+
+    /// Wear the armor down after a hit, losing protection once it is worn.
+    pub(crate) fn wear_armor(&mut self) {
+        if self.equipment.armor.is_none() {
+            return;
+        }
+        let was_worn = self.equipment.armor_worn();
+        self.equipment.armor_durability = self.equipment.armor_durability.saturating_sub(ARMOR_WEAR_PER_HIT);
+        if self.equipment.armor_worn() != was_worn {
+            self.recalculate_stats();
+        }
+    }
+
+    /// Requisition to restore the unit's equipment to full condition.
+    pub fn repair_cost(&self) -> u32 {
+        self.equipment.wear().div_ceil(REPAIR_POINTS_PER_REQUISITION)
+    }
+}
+
+impl Campaign {
+    /// Carry wear from a battle's copies of roster units back to the
+    /// roster.
+    pub fn record_wear<'a>(&mut self, units: impl IntoIterator<Item = &'a Unit>) {
+        for unit in units {
+            let Some(member) = self.roster.iter_mut().find(|u| u.id == unit.id) else { continue };
+            member.equipment.weapon_durability = unit.equipment.weapon_durability;
+            member.equipment.armor_durability = unit.equipment.armor_durability;
+            member.recalculate_stats();
+        }
+    }
+
+    /// Spend requisition to restore a unit's weapon and armor between
+    /// missions.
+    pub fn repair_equipment(&mut self, unit_id: &str) -> Result<(), &'static str> {
+        let unit = self.roster.iter_mut().find(|u| u.id == unit_id).ok_or("unknown unit")?;
+        let cost = unit.repair_cost();
+        if cost == 0 {
+            return Err("nothing to repair");
+        }
+        if cost > self.currency {
+            return Err("insufficient requisition");
+        }
+        self.currency -= cost;
+        unit.equipment.weapon_durability = MAX_DURABILITY;
+        unit.equipment.armor_durability = MAX_DURABILITY;
+        unit.recalculate_stats();
+        Ok(())
+    }
+}
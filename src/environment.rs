@@ -0,0 +1,108 @@
+use serde::{Deserialize, Serialize};
+
+use crate::combat::{CombatEncounter, EnvironmentalEffect};
+use crate::models::Position;
+
+/// Amount an acid pool's movement penalty recovers each round; the pool is
+/// gone once the penalty reaches 1.0.
+pub const ACID_EVAPORATION: f32 = 0.25;
+
+/// A patch an ability or grenade leaves behind on the cells it hits.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum Hazard {
+    Fire { damage_per_turn: i32 },
+    Smoke { turns: u32 },
+    Acid { movement_penalty: f32 },
+}
+
+impl Hazard {
+    /// The environmental effect this hazard creates on `cells`. Smoke is
+    /// centred on the first cell and sized to cover the rest.
+    pub fn effect_on(&self, cells: &[Position]) -> Option<EnvironmentalEffect> {
+        let first = cells.first()?;
+        Some(match self {
+            Hazard::Fire { damage_per_turn } => {
+                EnvironmentalEffect::FirePatch { grid_cells: cells.to_vec(), damage_per_turn: *damage_per_turn }
+            }
+            Hazard::Smoke { turns } => {
+                let radius = cells
+                    .iter()
+                    .map(|p| p.x.abs_diff(first.x).max(p.y.abs_diff(first.y)) as u32)
+                    .max()
+                    .unwrap_or(0);
+                EnvironmentalEffect::SmokeCloud { center: first.clone(), radius, turns_remaining: *turns }
+            }
+            Hazard::Acid { movement_penalty } => {
+                EnvironmentalEffect::AcidPool { grid_cells: cells.to_vec(), movement_penalty: *movement_penalty }
+            }
+        })
+    }
+}
+
+impl CombatEncounter {
+    /// Add the patch `hazard` leaves on `cells`.
+    pub fn spawn_hazard(&mut self, hazard: &Hazard, cells: &[Position]) {
+        self.environmental_effects.extend(hazard.effect_on(cells));
+    }
+
+    /// Cells currently covered by smoke.
+    pub fn smoke_cells(&self) -> Vec<Position> {
+        self.environmental_effects
+            .iter()
+            .filter_map(|e| match e {
+                EnvironmentalEffect::SmokeCloud { center, radius, .. } => Some(self.battlefield.circle(center, *radius)),
+                _ => None,
+            })
+            .flatten()
+            .collect()
+    }
+
+    /// Advance environmental effects by one round. In order: smoke puts out
+    /// fire on the cells it covers, each fire patch spreads to orthogonally
+    /// adjacent flammable cells that are neither burning nor smoked, and acid
+    /// pools weaken by [`ACID_EVAPORATION`]. Spent patches are removed, and
+    /// terrain changed by abilities counts down.
+    /// Patches are processed in list order and cells in stored order, so the
+    /// result depends only on the encounter state.
+    pub fn tick_environment(&mut self) {
+        let smoke = self.smoke_cells();
+        let mut burning: Vec<Position> = Vec::new();
+        for effect in &mut self.environmental_effects {
+            if let EnvironmentalEffect::FirePatch { grid_cells, .. } = effect {
+                grid_cells.retain(|c| !smoke.contains(c));
+                burning.extend(grid_cells.iter().cloned());
+            }
+        }
+        for effect in &mut self.environmental_effects {
+            match effect {
+                EnvironmentalEffect::FirePatch { grid_cells, .. } => {
+                    let mut spread = Vec::new();
+                    for cell in grid_cells.iter() {
+                        for next in self.battlefield.neighbours(cell) {
+                            if self.battlefield.flammable.contains(&next)
+                                && !smoke.contains(&next)
+                                && !burning.contains(&next)
+                            {
+                                burning.push(next.clone());
+                                spread.push(next);
+                            }
+                        }
+                    }
+                    grid_cells.extend(spread);
+                }
+                EnvironmentalEffect::AcidPool { movement_penalty, .. } => {
+                    *movement_penalty = (*movement_penalty + ACID_EVAPORATION).min(1.0);
+                }
+                EnvironmentalEffect::SmokeCloud { .. } => {}
+            }
+        }
+        self.environmental_effects.retain(|e| match e {
+            EnvironmentalEffect::FirePatch { grid_cells, .. } => !grid_cells.is_empty(),
+            EnvironmentalEffect::AcidPool { grid_cells, movement_penalty } => {
+                !grid_cells.is_empty() && *movement_penalty < 1.0
+            }
+            EnvironmentalEffect::SmokeCloud { .. } => true,
+        });
+        self.tick_terrain();
+    }
+}
@@ -0,0 +1,278 @@
+use std::collections::HashSet;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::assets::AssetProvider;
+use crate::campaign::Campaign;
+use crate::heroes::PLOT_ARMOR_INJURY;
+use crate::injuries::Injury;
+use crate::ioutil::invalid;
+use crate::models::{Faction, Stats, Trait, Unit, UnitType};
+use crate::modifiers::Stat;
+use crate::rng::GameRng;
+use crate::skills::{SkillTest, TestDifficulty};
+
+/// Campaign morale runs from `-MAX_MORALE` to `MAX_MORALE`.
+pub const MAX_MORALE: i32 = 10;
+
+/// A unit an event can add to the roster.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EventUnit {
+    pub id: String,
+    pub name: String,
+    pub unit_type: UnitType,
+    pub faction: Faction,
+    pub base_stats: Stats,
+}
+
+impl EventUnit {
+    pub fn spawn(&self, id: &str) -> Unit {
+        let mut unit = Unit::new(id, &self.name, self.unit_type.clone(), self.faction.clone());
+        unit.base_stats = self.base_stats.clone();
+        unit.traits = Trait::for_unit_type(&self.unit_type);
+        unit.recalculate_stats();
+        unit.health_points = unit.current_stats.max_health;
+        unit
+    }
+}
+
+/// One effect of an event's outcome on the campaign.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum Consequence {
+    GainUnit(EventUnit),
+    /// A random unit fit for duty leaves the roster. Heroes are wounded
+    /// instead, as in battle.
+    LoseUnit,
+    GainItem(String),
+    /// Removes one of the item, if the inventory holds any.
+    LoseItem(String),
+    /// Requisition gained, or lost when negative; never below 0.
+    Currency(i32),
+    Morale(i32),
+    SetFlag(String),
+}
+
+/// A skill test a choice calls for, taken by the roster's best unit.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct EventTest {
+    pub stat: Stat,
+    pub difficulty: TestDifficulty,
+}
+
+/// What happens after a choice: a locale key for the text shown and the
+/// consequences applied.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EventOutcome {
+    pub text: String,
+    #[serde(default)]
+    pub consequences: Vec<Consequence>,
+}
+
+/// A response to an event. Its cost and item are paid before any test.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EventChoice {
+    pub text: String,
+    /// Requisition spent on the choice.
+    #[serde(default)]
+    pub cost: u32,
+    /// Inventory item used up by the choice.
+    #[serde(default)]
+    pub consumes: Option<String>,
+    #[serde(default)]
+    pub test: Option<EventTest>,
+    pub success: EventOutcome,
+    /// Outcome when the test fails; required when there is a test.
+    #[serde(default)]
+    pub failure: Option<EventOutcome>,
+}
+
+/// Something that happens on the campaign map between missions. `title`
+/// and `text` are locale keys.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CampaignEvent {
+    pub id: String,
+    pub title: String,
+    pub text: String,
+    /// Relative chance of being drawn among available events.
+    pub weight: u32,
+    /// Event only happens once this campaign flag is set.
+    #[serde(default)]
+    pub requires_flag: Option<String>,
+    /// Whether the event can happen again once resolved.
+    #[serde(default)]
+    pub repeatable: bool,
+    pub choices: Vec<EventChoice>,
+}
+
+impl CampaignEvent {
+    /// Whether `campaign` can run into the event: its flag is set, and a
+    /// one-off event hasn't happened yet.
+    pub fn is_available(&self, campaign: &Campaign) -> bool {
+        self.requires_flag.as_deref().is_none_or(|f| campaign.has_flag(f))
+            && (self.repeatable || !campaign.event_log.iter().any(|r| r.event == self.id))
+    }
+}
+
+/// A resolved event, kept in the campaign so one-off events stay done.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EventRecord {
+    pub event: String,
+    pub choice: usize,
+    pub success: bool,
+}
+
+/// How a choice played out.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EventResult {
+    pub success: bool,
+    /// Id of the unit that took the test, with its result.
+    pub test: Option<(String, SkillTest)>,
+    /// Locale key of the outcome text.
+    pub text: String,
+}
+
+/// Every campaign event the game can draw from.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct EventTable {
+    pub events: Vec<CampaignEvent>,
+}
+
+impl EventTable {
+    /// Load a table, rejecting duplicate ids, events that can never be
+    /// drawn or answered, and tests without a failure outcome.
+    pub fn load<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        Self::parse(&fs::read_to_string(path)?)
+    }
+
+    /// [`EventTable::load`] from `path` in `assets`.
+    pub fn load_from(assets: &dyn AssetProvider, path: &str) -> io::Result<Self> {
+        Self::parse(&assets.read_to_string(path)?)
+    }
+
+    fn parse(data: &str) -> io::Result<Self> {
+        let table: EventTable = serde_json::from_str(data).map_err(invalid)?;
+        let mut seen = HashSet::new();
+        for event in &table.events {
+            if !seen.insert(event.id.as_str()) {
+                return Err(invalid(format!("duplicate event '{}'", event.id)));
+            }
+            if event.weight == 0 || event.choices.is_empty() {
+                return Err(invalid(format!("event '{}' needs a weight and a choice", event.id)));
+            }
+            if event.choices.iter().any(|c| c.test.is_some() && c.failure.is_none()) {
+                return Err(invalid(format!("event '{}' tests a choice without a failure outcome", event.id)));
+            }
+        }
+        Ok(table)
+    }
+
+    pub fn get(&self, id: &str) -> Option<&CampaignEvent> {
+        self.events.iter().find(|e| e.id == id)
+    }
+
+    /// Events `campaign` can run into.
+    pub fn available(&self, campaign: &Campaign) -> Vec<&CampaignEvent> {
+        self.events.iter().filter(|e| e.is_available(campaign)).collect()
+    }
+
+    /// Draw an available event by weight.
+    pub fn draw(&self, campaign: &Campaign, rng: &mut GameRng) -> Option<&CampaignEvent> {
+        let available = self.available(campaign);
+        let total: u32 = available.iter().map(|e| e.weight).sum();
+        if total == 0 {
+            return None;
+        }
+        let mut roll = rng.range(0, total - 1);
+        available.into_iter().find(|e| {
+            let hit = roll < e.weight;
+            roll = roll.saturating_sub(e.weight);
+            hit
+        })
+    }
+}
+
+impl Campaign {
+    /// Draw the next event into `pending_event`, unless one is already
+    /// waiting. Returns the pending event.
+    pub fn start_event<'a>(&mut self, table: &'a EventTable, rng: &mut GameRng) -> Option<&'a CampaignEvent> {
+        if self.pending_event.is_none() {
+            self.pending_event = table.draw(self, rng).map(|e| e.id.clone());
+        }
+        self.pending_event.as_deref().and_then(|id| table.get(id))
+    }
+
+    /// Whether the campaign can pay for `choice`.
+    pub fn can_afford(&self, choice: &EventChoice) -> Result<(), &'static str> {
+        if choice.cost > self.currency {
+            return Err("insufficient requisition");
+        }
+        if choice.consumes.as_ref().is_some_and(|item| !self.inventory.contains(item)) {
+            return Err("item not in inventory");
+        }
+        Ok(())
+    }
+
+    /// Answer the pending event with its `index`th choice: pay its cost,
+    /// take its test with the roster's best unit and apply the outcome. A
+    /// test nobody is fit to take fails.
+    pub fn resolve_event(&mut self, table: &EventTable, index: usize, rng: &mut GameRng) -> Result<EventResult, &'static str> {
+        let event = self.pending_event.as_deref().and_then(|id| table.get(id)).ok_or("no event pending")?;
+        let choice = event.choices.get(index).ok_or("invalid choice")?;
+        self.can_afford(choice)?;
+        self.currency -= choice.cost;
+        if let Some(item) = &choice.consumes
+            && let Some(slot) = self.inventory.iter().position(|i| i == item)
+        {
+            self.inventory.remove(slot);
+        }
+        let test = choice.test.and_then(|t| self.skill_test(t.stat, t.difficulty, rng)).map(|(u, test)| (u.id.clone(), test));
+        let success = choice.test.is_none() || test.as_ref().is_some_and(|(_, t)| t.success);
+        let outcome = if success { &choice.success } else { choice.failure.as_ref().unwrap_or(&choice.success) };
+        for consequence in &outcome.consequences {
+            self.apply_consequence(consequence, rng);
+        }
+        self.event_log.push(EventRecord { event: event.id.clone(), choice: index, success });
+        self.pending_event = None;
+        Ok(EventResult { success, test, text: outcome.text.clone() })
+    }
+
+    fn apply_consequence(&mut self, consequence: &Consequence, rng: &mut GameRng) {
+        match consequence {
+            Consequence::GainUnit(template) => {
+                let mut id = template.id.clone();
+                let mut n = 1;
+                while self.roster.iter().any(|u| u.id == id) {
+                    n += 1;
+                    id = format!("{}_{}", template.id, n);
+                }
+                self.roster.push(template.spawn(&id));
+            }
+            Consequence::LoseUnit => {
+                let fit: Vec<usize> = (0..self.roster.len()).filter(|&i| !self.roster[i].is_recovering()).collect();
+                if fit.is_empty() {
+                    return;
+                }
+                let index = fit[rng.range(0, fit.len() as u32 - 1) as usize];
+                let unit = &mut self.roster[index];
+                if unit.is_hero() {
+                    unit.injuries.push(Injury::new(PLOT_ARMOR_INJURY));
+                    unit.recalculate_stats();
+                } else {
+                    self.roster.remove(index);
+                }
+            }
+            Consequence::GainItem(item) => self.grant_item(item),
+            Consequence::LoseItem(item) => {
+                if let Some(slot) = self.inventory.iter().position(|i| i == item) {
+                    self.inventory.remove(slot);
+                }
+            }
+            Consequence::Currency(amount) => self.currency = self.currency.saturating_add_signed(*amount),
+            Consequence::Morale(amount) => self.morale = (self.morale + amount).clamp(-MAX_MORALE, MAX_MORALE),
+            Consequence::SetFlag(flag) => self.set_flag(flag),
+        }
+    }
+}
@@ -0,0 +1,110 @@
+use std::f32::consts::FRAC_PI_4;
+
+use serde::{Deserialize, Serialize};
+
+use crate::models::{Position, Unit};
+
+/// The eight ways a unit can face, clockwise from north, which is towards
+/// row 0.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Facing {
+    #[default]
+    North,
+    NorthEast,
+    East,
+    SouthEast,
+    South,
+    SouthWest,
+    West,
+    NorthWest,
+}
+
+/// Where something lies around a unit, relative to its facing.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum FacingArc {
+    Front,
+    Side,
+    Rear,
+}
+
+impl Facing {
+    pub const ALL: [Facing; 8] = [
+        Facing::North,
+        Facing::NorthEast,
+        Facing::East,
+        Facing::SouthEast,
+        Facing::South,
+        Facing::SouthWest,
+        Facing::West,
+        Facing::NorthWest,
+    ];
+
+    /// Grid offset of one step this way.
+    pub fn offset(self) -> (isize, isize) {
+        match self {
+            Facing::North => (0, -1),
+            Facing::NorthEast => (1, -1),
+            Facing::East => (1, 0),
+            Facing::SouthEast => (1, 1),
+            Facing::South => (0, 1),
+            Facing::SouthWest => (-1, 1),
+            Facing::West => (-1, 0),
+            Facing::NorthWest => (-1, -1),
+        }
+    }
+
+    /// The way closest to pointing from `from` at `to`; `None` when they
+    /// are the same cell.
+    pub fn toward(from: &Position, to: &Position) -> Option<Facing> {
+        let dx = to.x as f32 - from.x as f32;
+        let dy = to.y as f32 - from.y as f32;
+        if dx == 0.0 && dy == 0.0 {
+            return None;
+        }
+        // Clockwise from north, with y growing southward.
+        let eighths = (dx.atan2(-dy) / FRAC_PI_4).round() as i32;
+        Some(Facing::ALL[eighths.rem_euclid(8) as usize])
+    }
+
+    /// 45-degree turns from this way to `other`, the shorter way round.
+    pub fn turns_to(self, other: Facing) -> u32 {
+        let turns = (other as i32 - self as i32).rem_euclid(8);
+        turns.min(8 - turns) as u32
+    }
+
+    /// The arc `from` lies in for a unit at `position` facing this way:
+    /// the front reaches 45 degrees either side of the facing, the rear as
+    /// far either side of straight behind.
+    pub fn arc(self, position: &Position, from: &Position) -> FacingArc {
+        match Facing::toward(position, from).map(|way| self.turns_to(way)) {
+            None | Some(0 | 1) => FacingArc::Front,
+            Some(2) => FacingArc::Side,
+            _ => FacingArc::Rear,
+        }
+    }
+}
+
+impl Unit {
+    /// Turn to face `target`, unless standing on it.
+    pub(crate) fn face(&mut self, target: &Position) {
+        if let Some(facing) = Facing::toward(&self.grid_position, target) {
+            self.facing = facing;
+        }
+    }
+
+    /// Face the way the last step of `path` went, as a unit ends up after
+    /// walking it.
+    pub(crate) fn face_along(&mut self, path: &[Position]) {
+        if let [.., from, to] = path {
+            self.facing = Facing::toward(from, to).unwrap_or(self.facing);
+        }
+    }
+
+    /// The cell right behind the unit, where its rear arc starts; `None`
+    /// off the top or left edge of the map.
+    pub fn behind(&self) -> Option<Position> {
+        let (dx, dy) = self.facing.offset();
+        let Position { x, y } = self.grid_position;
+        Some(Position { x: x.checked_add_signed(-dx)?, y: y.checked_add_signed(-dy)? })
+    }
+}
@@ -0,0 +1,837 @@
+use std::io;
+use std::path::Path;
+
+use crate::achievements::{AchievementProfile, AchievementTracker};
+use crate::actions::PresentationStep;
+use crate::assets::streaming::{AssetManager, AssetScope};
+use crate::audio::AudioSystem;
+use crate::campaign::Campaign;
+use crate::codex::Codex;
+use crate::combat::{CameraState, CombatEncounter, CombatEvent, Controller, MAX_ZOOM};
+use crate::command::Command;
+use crate::debrief::BattleReport;
+use crate::debug::{DebugConsole, DebugOverlay};
+use crate::dialogue::{DialoguePlayer, DialogueTree};
+use crate::formation::MoveQueue;
+use crate::frontend::backend::DrawRect;
+use crate::frontend::text::Color;
+use crate::frontend::{DrawCall, DrawLayer, Renderer};
+use crate::hints::HintEngine;
+use crate::input::{GameAction, Gesture};
+use crate::interrupts::InterruptEvent;
+use crate::ironman::IronmanSession;
+use crate::localization::Localizer;
+use crate::models::{AnimationType, Position, Unit};
+use crate::profile::Difficulty;
+use crate::mods::ModLoader;
+use crate::recruitment::{RecruitmentOutcome, RecruitmentSession};
+#[cfg(feature = "scripting")]
+use crate::scripting::MissionScript;
+use crate::selection::SelectionChanged;
+use crate::simulation::BattleOutcome;
+use crate::state::GameState;
+use crate::ui::codex::CodexScreen;
+use crate::ui::dialogue::DialogueOverlay;
+use crate::ui::loading::LoadingScreen;
+use crate::ui::modal::{ModalChoice, ModalDialog};
+use crate::ui::mods::ModsScreen;
+use crate::ui::options::ConfirmationSettings;
+use crate::ui::recruitment::RecruitmentScreen;
+use crate::ui::summary::{BattleSummaryScreen, SummaryChoice};
+use crate::ui::toasts::Toast;
+use crate::ui::tooltip::{Tooltip, TooltipSubject};
+use crate::ui::{UiEvent, UiManager, UiTab};
+use crate::weather::Weather;
+
+/// Seconds each animation frame is shown before advancing.
+pub const ANIMATION_FRAME_DURATION: f32 = 0.1;
+/// How much closer the cinematic camera zooms, within [`MAX_ZOOM`].
+pub const CINEMATIC_ZOOM: f32 = 1.5;
+/// Fraction of the screen height each letterbox bar covers.
+pub const LETTERBOX_HEIGHT: f32 = 0.12;
+const LETTERBOX_COLOR: Color = [0.0, 0.0, 0.0, 1.0];
+
+/// A cinematic close-up in progress.
+#[derive(Debug, Clone)]
+struct CinematicShot {
+    /// The camera to go back to afterwards.
+    restore: CameraState,
+    /// Offsets and zoom closing in on the action.
+    to: (f32, f32, f32),
+    /// How far the camera has closed in, from 0 to 1.
+    progress: f32,
+}
+
+/// Top-level game flow driven by the runner: owns the game state and the
+/// UI and routes input, simulation updates and rendering between them.
+pub struct GameFlow {
+    pub state: GameState,
+    /// The battle being fought; `state` mirrors it for drawing.
+    pub battle: Option<CombatEncounter>,
+    /// The battle as it started, fought again if it is retried.
+    battle_start: Option<CombatEncounter>,
+    /// Length of the battle's command log when the current turn began.
+    turn_mark: usize,
+    pub ui: UiManager,
+    pub campaign: Campaign,
+    /// Conversation in progress; pauses the simulation and takes input.
+    pub dialogue: Option<DialogueOverlay>,
+    /// Recruitment challenge shown full screen in place of the battlefield.
+    pub recruitment: Option<RecruitmentScreen>,
+    /// End-of-battle stats, shown full screen until dismissed.
+    pub summary: Option<BattleSummaryScreen>,
+    /// Mod list, shown full screen until backed out of.
+    pub mods: Option<ModsScreen>,
+    /// Codex, shown full screen until backed out of.
+    pub codex: Option<CodexScreen>,
+    /// Set when the game wants the runner to shut down.
+    pub quit_requested: bool,
+    pub achievements: AchievementTracker,
+    /// Autosaves of an iron-man campaign, written after every command.
+    pub ironman: Option<IronmanSession>,
+    /// AI and pathfinding layers drawn over the battlefield.
+    pub debug: DebugOverlay,
+    /// Text commands for switching `debug` layers.
+    pub console: DebugConsole,
+    /// Which commands ask for confirmation before running.
+    pub confirmations: ConfirmationSettings,
+    /// Command waiting on the open confirmation dialog.
+    pub pending_command: Option<Command>,
+    /// Offers hints on the player's turns as toasts.
+    pub hints: HintEngine,
+    /// Group moves waiting to play out one unit at a time.
+    pub moves: MoveQueue,
+    /// Whether kills and critical hits play through the cinematic camera.
+    pub cinematic_camera: bool,
+    /// How hard the AI plays the battles started, from the player's
+    /// settings.
+    pub difficulty: Difficulty,
+    /// Streams sprites and sounds in the background; set by the runner.
+    pub assets: Option<AssetManager>,
+    /// Shown until the battle's assets have arrived.
+    pub loading: Option<LoadingScreen>,
+    /// Mission script of the battle; its hooks run as each turn begins and
+    /// on the events the battle raises.
+    #[cfg(feature = "scripting")]
+    pub script: Option<MissionScript>,
+    /// Camera offsets a scripted pan is moving from and to.
+    camera_pan: Option<((f32, f32), (f32, f32))>,
+    cinematic: Option<CinematicShot>,
+    /// The campaign and achievements before the battle on the summary
+    /// screen, put back if it is retried.
+    before_battle: Option<(Campaign, AchievementProfile)>,
+}
+
+impl GameFlow {
+    pub fn new(state: GameState, mut ui: UiManager) -> Self {
+        if !state.selection.is_empty() {
+            let selected = state.selection.ids().to_vec();
+            ui.handle_selection_changed(&SelectionChanged { selected }, &state.units);
+        }
+        Self {
+            state,
+            battle: None,
+            battle_start: None,
+            turn_mark: 0,
+            ui,
+            campaign: Campaign::new(),
+            dialogue: None,
+            recruitment: None,
+            summary: None,
+            mods: None,
+            codex: None,
+            quit_requested: false,
+            achievements: AchievementTracker::default(),
+            ironman: None,
+            debug: DebugOverlay::new(),
+            console: DebugConsole::new(),
+            confirmations: ConfirmationSettings::default(),
+            pending_command: None,
+            hints: HintEngine::default(),
+            moves: MoveQueue::new(),
+            cinematic_camera: false,
+            difficulty: Difficulty::default(),
+            assets: None,
+            loading: None,
+            camera_pan: None,
+            cinematic: None,
+            before_battle: None,
+            #[cfg(feature = "scripting")]
+            script: None,
+        }
+    }
+
+    pub fn start_dialogue(&mut self, tree: DialogueTree) {
+        self.dialogue = Some(DialogueOverlay::new(DialoguePlayer::new(tree)));
+    }
+
+    pub fn start_recruitment(&mut self, session: RecruitmentSession) {
+        self.recruitment = Some(RecruitmentScreen::new(session));
+    }
+
+    pub fn open_mods(&mut self, loader: ModLoader) {
+        self.mods = Some(ModsScreen::new(loader));
+    }
+
+    /// Open the codex on what the campaign has unlocked.
+    pub fn open_codex(&mut self, codex: Codex, loc: &Localizer) {
+        self.codex = Some(CodexScreen::new(codex, self.campaign.codex.clone(), loc));
+    }
+
+    /// Play `campaign` from here on. An iron-man campaign starts its
+    /// autosave at `save_path`, writing the first save at once; others
+    /// aren't saved by the flow.
+    pub fn begin_campaign<P: AsRef<Path>>(&mut self, mut campaign: Campaign, save_path: P) -> io::Result<()> {
+        self.ironman = if campaign.ironman { Some(IronmanSession::start(save_path, &mut campaign)?) } else { None };
+        self.campaign = campaign;
+        Ok(())
+    }
+
+    /// Load the iron-man campaign saved at `path` and go on autosaving to
+    /// it, picking the battle back up if it was saved mid-fight. Edited
+    /// saves and copies of earlier ones are refused.
+    pub fn resume_ironman<P: AsRef<Path>>(&mut self, path: P) -> io::Result<()> {
+        let (mut session, save) = IronmanSession::resume(path)?;
+        let battle = save.battle()?;
+        self.ironman = None;
+        self.campaign = save.campaign;
+        if let Some(encounter) = battle {
+            session.saved_commands = encounter.command_log.len();
+            self.start_battle(encounter);
+        }
+        self.ironman = Some(session);
+        Ok(())
+    }
+
+    /// Fight `encounter` at the player's difficulty: its first turn begins
+    /// if none has, the UI shows it, and from then on [`GameFlow::update`]
+    /// plays the AI's turns and ends the battle once a side is wiped out.
+    /// An iron-man campaign is saved with the battle as it starts.
+    pub fn start_battle(&mut self, mut encounter: CombatEncounter) {
+        encounter.difficulty = self.difficulty;
+        if encounter.turn_order.current_unit_id.is_none() {
+            begin_turn(&mut encounter);
+            #[cfg(feature = "scripting")]
+            script_turn_started(&mut self.script, &mut self.ui, &mut encounter);
+        }
+        self.autosave(Some(&encounter));
+        self.turn_mark = encounter.command_log.len();
+        self.state = GameState::from_encounter(&encounter);
+        self.battle_start = Some(encounter.clone());
+        self.battle = Some(encounter);
+        self.load_battle_assets();
+    }
+
+    /// Order `command` in the battle, queued once it is confirmed if it
+    /// needs to be; see [`GameFlow::request_command`].
+    pub fn order(&mut self, command: Command) {
+        let Some(battle) = self.battle.take() else { return };
+        let ready = self.request_command(command, &battle);
+        let battle = self.battle.insert(battle);
+        if let Some(command) = ready {
+            battle.queue_action(command);
+        }
+    }
+
+    /// Fold the finished battle's stats into the campaign and show the
+    /// summary screen. Loot the squad picked up goes into the campaign
+    /// unless the battle was lost, and roster units earn the experience
+    /// the [`BattleReport`] gives them. The base is tended, so injured
+    /// units count down recovery and research moves on, and roster units
+    /// downed in the battle roll for injuries. Iron-man campaigns are saved
+    /// with the results; others keep the campaign as it was so the battle
+    /// can be retried. Everything fought with or against is unlocked in
+    /// the codex.
+    pub fn finish_battle(&mut self, encounter: &CombatEncounter, outcome: BattleOutcome) {
+        self.before_battle = (!self.campaign.ironman).then(|| (self.campaign.clone(), self.achievements.profile.clone()));
+        let report = BattleReport::new(encounter, outcome);
+        self.campaign.record_battle(&encounter.stats, outcome);
+        self.campaign.record_sightings(encounter.units.iter());
+        self.campaign.record_wear(encounter.player_units());
+        self.campaign.collect_loot(&report.loot);
+        self.campaign.award_experience(&report);
+        if let Some(research) = self.campaign.tend_base() {
+            self.ui.toasts.push(Toast::research(&research.name));
+        }
+        let downed: Vec<String> = encounter.player_units().filter(|u| u.health_points <= 0).map(|u| u.id.clone()).collect();
+        for (unit_id, kind) in self.campaign.roll_injuries(&downed, &mut encounter.rng.clone()) {
+            let name = encounter.unit_by_id(&unit_id).map_or(unit_id.as_str(), |u| u.name.as_str());
+            self.ui.toasts.push(Toast::injury(name, kind));
+        }
+        self.summary = Some(BattleSummaryScreen::new(&report, self.before_battle.is_some()));
+        for achievement in self.achievements.finish_battle(encounter, outcome) {
+            self.ui.toasts.push(Toast::achievement(&achievement));
+        }
+        self.autosave(None);
+    }
+
+    /// Write the iron-man save, if this is an iron-man campaign, with the
+    /// battle in progress. A failed write is reported with a toast.
+    pub fn autosave(&mut self, encounter: Option<&CombatEncounter>) {
+        let Some(session) = &mut self.ironman else { return };
+        if let Err(e) = session.autosave(&self.campaign, encounter) {
+            self.ui.toasts.push(Toast::autosave_failed(&e));
+        }
+    }
+
+    /// Check events drained from `encounter` for achievements.
+    pub fn observe_combat(&mut self, events: &[CombatEvent], encounter: &CombatEncounter) {
+        for achievement in self.achievements.observe(events, encounter) {
+            self.ui.toasts.push(Toast::achievement(&achievement));
+        }
+    }
+
+    /// Start streaming the sprites the current battle draws, behind the
+    /// loading screen.
+    pub fn load_battle_assets(&mut self) {
+        let Some(assets) = &mut self.assets else { return };
+        assets.request_battle(&self.state);
+        self.loading = Some(LoadingScreen::new(assets.progress()));
+    }
+
+    /// Install assets that have arrived, and lift the loading screen once
+    /// all the battle's are in. Sprites arriving later replace their
+    /// placeholders as they come.
+    pub fn poll_assets(&mut self, renderer: &mut Renderer, audio: &mut AudioSystem) {
+        let Some(assets) = &mut self.assets else { return };
+        assets.poll(renderer, audio);
+        let progress = assets.progress();
+        if progress.is_done() {
+            self.loading = None;
+        } else if let Some(screen) = &mut self.loading {
+            screen.progress = progress;
+        }
+    }
+
+    /// Whether simulation updates are suspended.
+    pub fn is_paused(&self) -> bool {
+        self.loading.is_some()
+            || self.dialogue.is_some()
+            || self.recruitment.is_some()
+            || self.summary.is_some()
+            || self.mods.is_some()
+            || self.codex.is_some()
+            || self.ui.modal.is_some()
+    }
+
+    /// Check `command` against the warnings enabled in `confirmations`.
+    /// Returns it when it can run at once; otherwise shows a confirmation
+    /// dialog and holds the command until it is confirmed, when
+    /// [`UiEvent::CommandConfirmed`] hands it back.
+    pub fn request_command(&mut self, command: Command, encounter: &CombatEncounter) -> Option<Command> {
+        let current = encounter.turn_order.current_unit_id.as_deref().and_then(|id| encounter.unit_by_id(id));
+        let unspent = current.filter(|u| {
+            command == Command::EndTurn && self.confirmations.end_turn_with_ap && u.health_points > 0 && u.action_points > 0
+        });
+        let modal = if let Some(unit) = unspent {
+            ModalDialog::new("modal.end_turn.title", "modal.end_turn", &self.ui.layout)
+                .with_args(&[("name", &unit.name)])
+                .with_count(unit.action_points as i64)
+        } else {
+            let friendly = if self.confirmations.friendly_fire { encounter.friendly_fire(&command) } else { Vec::new() };
+            if friendly.is_empty() {
+                return Some(command);
+            }
+            let names: Vec<&str> = friendly.iter().map(|u| u.name.as_str()).collect();
+            ModalDialog::new("modal.friendly_fire.title", "modal.friendly_fire", &self.ui.layout)
+                .with_args(&[("names", &names.join(", "))])
+                .with_count(friendly.len() as i64)
+        };
+        self.ui.modal = Some(modal);
+        self.pending_command = Some(command);
+        None
+    }
+
+    /// Take the newest hint off the screen, never to be offered again.
+    /// Returns false when no hint is showing.
+    pub fn dismiss_hint(&mut self) -> bool {
+        let Some(hint) = self.ui.toasts.dismiss_where(|t| t.as_hint().is_some()).and_then(|t| t.as_hint()) else {
+            return false;
+        };
+        self.hints.dismiss(hint);
+        true
+    }
+
+    /// Close the open modal dialog as if cancelled. Returns false when none
+    /// was open.
+    pub fn cancel_modal(&mut self) -> bool {
+        self.pending_command = None;
+        self.ui.modal.take().is_some()
+    }
+
+    /// Swap a closed confirmation dialog's answer for the command it held,
+    /// when confirmed.
+    fn answer_modal(&mut self, event: Option<UiEvent>) -> Option<UiEvent> {
+        let Some(UiEvent::ModalClosed(choice)) = event else { return event };
+        match self.pending_command.take() {
+            Some(command) if choice == ModalChoice::Confirm => Some(UiEvent::CommandConfirmed(command)),
+            Some(_) => None,
+            None => event,
+        }
+    }
+
+    /// Route a high level input action to the open dialogue, or else to the
+    /// UI.
+    pub fn handle_action(&mut self, action: GameAction) -> Option<UiEvent> {
+        if let Some(dialogue) = &mut self.dialogue {
+            if dialogue.handle_input(action, &mut self.campaign) {
+                self.dialogue = None;
+            }
+            return None;
+        }
+        if let Some(screen) = &mut self.summary {
+            let choice = screen.handle_input(action)?;
+            self.summary = None;
+            match choice {
+                // Back to the campaign map: the battle's assets can go.
+                SummaryChoice::Continue => {
+                    self.before_battle = None;
+                    self.battle_start = None;
+                    if let Some(assets) = &mut self.assets {
+                        assets.release_scope(AssetScope::Battle);
+                    }
+                }
+                // The battle is fought again from its start, as if the
+                // first attempt never happened.
+                SummaryChoice::Retry => {
+                    if let Some((campaign, profile)) = self.before_battle.take() {
+                        self.campaign = campaign;
+                        self.achievements.restore(profile);
+                    }
+                    if let Some(encounter) = self.battle_start.take() {
+                        self.start_battle(encounter);
+                    }
+                }
+            }
+            return Some(UiEvent::SummaryClosed(choice));
+        }
+        if let Some(screen) = &mut self.mods {
+            // A failed save keeps the toggle; it is written with the next one.
+            if screen.handle_input(action).unwrap_or(false) {
+                self.mods = None;
+            }
+            return None;
+        }
+        if let Some(screen) = &mut self.codex {
+            if screen.handle_input(action) {
+                self.codex = None;
+            }
+            return None;
+        }
+        if let Some(screen) = &mut self.recruitment {
+            // Once the challenge is decided, the next confirm closes the screen.
+            if screen.session.outcome() != RecruitmentOutcome::InProgress {
+                if action == GameAction::Activate {
+                    self.recruitment = None;
+                }
+            } else if screen.handle_input(action) == RecruitmentOutcome::Recruited {
+                screen.session.finish(&mut self.campaign);
+            }
+            return None;
+        }
+        let event = self.ui.handle_input(action);
+        let event = self.answer_modal(event);
+        if let (Some(UiEvent::CommandConfirmed(command)), Some(battle)) = (&event, &mut self.battle) {
+            battle.queue_action(command.clone());
+        }
+        event
+    }
+
+    /// Act on a touch gesture the way the matching mouse or keyboard input
+    /// would. A tap on a button selects and activates it, giving the same
+    /// [`UiEvent`]; a tap on the battlefield selects the unit there. Drags
+    /// and pinches move `renderer`'s camera, and a long press shows a
+    /// tooltip until the finger lifts.
+    pub fn handle_gesture(&mut self, gesture: Gesture, renderer: &mut Renderer) -> Option<UiEvent> {
+        if self.ui.pass_device.is_none()
+            && let Some(modal) = &self.ui.modal
+        {
+            let choice = match gesture {
+                Gesture::Tap { x, y } => modal.button_at((x as u32, y as u32))?,
+                _ => return None,
+            };
+            self.ui.modal = None;
+            return self.answer_modal(Some(UiEvent::ModalClosed(choice)));
+        }
+        // Full-screen screens and overlays only take confirms.
+        if self.is_paused() || self.ui.pass_device.is_some() {
+            return match gesture {
+                Gesture::Tap { .. } => self.handle_action(GameAction::Activate),
+                _ => None,
+            };
+        }
+        match gesture {
+            Gesture::Tap { x, y } => {
+                let at = (x as u32, y as u32);
+                if let Some((tab, index)) = self.ui.button_at(at) {
+                    self.ui.current_tab = tab;
+                    self.ui.selected_index = index;
+                    return self.handle_action(GameAction::Activate);
+                }
+                self.select_at(at, false, renderer);
+            }
+            Gesture::LongPress { x, y } => {
+                let at = (x as u32, y as u32);
+                let subject = match self.ui.button_at(at) {
+                    Some((UiTab::Abilities, i)) => self.ui.selection.first().map(|unit_id| TooltipSubject::Ability {
+                        unit_id: unit_id.clone(),
+                        ability_id: self.ui.ability_buttons[i].id.clone(),
+                    }),
+                    Some((UiTab::Inventory, i)) => Some(TooltipSubject::Item(self.ui.inventory_buttons[i].id.clone())),
+                    None if self.ui.battlefield.contains(at) => renderer
+                        .cell_at((x, y))
+                        .and_then(|cell| self.state.units.iter().find(|u| u.grid_position == cell))
+                        .map(|u| TooltipSubject::Unit(u.id.clone())),
+                    None => None,
+                };
+                self.ui.tooltip = subject.map(|subject| Tooltip { subject, position: at });
+            }
+            Gesture::Pan { dx, dy } => renderer.camera.pan(dx, dy),
+            Gesture::Pinch { x, y, scale } => renderer.camera.zoom_at((x, y), scale),
+            Gesture::Released => self.ui.tooltip = None,
+        }
+        None
+    }
+
+    /// Select the unit under screen position `at` on the battlefield, as
+    /// with a click or tap; `additive` (shift held) adds or removes it
+    /// instead. See [`GameState::select_at`].
+    pub fn select_at(&mut self, at: (u32, u32), additive: bool, renderer: &Renderer) -> Option<SelectionChanged> {
+        if self.is_paused() || self.ui.pass_device.is_some() || !self.ui.battlefield.contains(at) {
+            return None;
+        }
+        let cell = renderer.cell_at((at.0 as f32, at.1 as f32));
+        let changed = self.state.select_at(cell, additive)?;
+        self.ui.handle_selection_changed(&changed, &self.state.units);
+        Some(changed)
+    }
+
+    /// Select the next player unit, as with Tab.
+    pub fn cycle_selection(&mut self) -> Option<SelectionChanged> {
+        if self.is_paused() || self.ui.pass_device.is_some() {
+            return None;
+        }
+        let changed = self.state.cycle_selection()?;
+        self.ui.handle_selection_changed(&changed, &self.state.units);
+        Some(changed)
+    }
+
+    /// Order the selected units to `target` in formation; see
+    /// [`CombatEncounter::plan_group_move`]. The moves are queued behind any
+    /// still playing and run through [`GameFlow::update_moves`]. Returns how
+    /// many units were given a move.
+    pub fn order_group_move(&mut self, target: &Position, encounter: &CombatEncounter) -> usize {
+        let moves = encounter.plan_group_move(self.state.selection.ids(), target);
+        let count = moves.len();
+        self.moves.push(moves);
+        count
+    }
+
+    /// Play queued group moves on `encounter` for `dt` seconds, returning
+    /// those that failed. Held while the game is paused.
+    pub fn update_moves(&mut self, dt: f32, encounter: &mut CombatEncounter) -> Vec<Command> {
+        if self.is_paused() {
+            return Vec::new();
+        }
+        self.moves.update(dt, encounter)
+    }
+
+    /// Play `encounter`'s queued actions for `dt` seconds; see
+    /// [`CombatEncounter::update_actions`]. Health changes pop up as
+    /// floating text over the units for as long as their step lasts.
+    /// Scripted interrupts pan `renderer`'s camera or open a dialogue, and
+    /// the turn resumes once the dialogue is closed. With the cinematic
+    /// camera on, the camera closes in on kills and critical hits while
+    /// they play in slow motion, then goes back. Iron-man campaigns are
+    /// saved after each command resolves, and commands the encounter
+    /// refused are shown as toasts, as are hints on the player's turns.
+    /// Held while the game is paused.
+    /// Returns the steps started.
+    pub fn present_actions(&mut self, dt: f32, encounter: &mut CombatEncounter, renderer: &mut Renderer) -> Vec<PresentationStep> {
+        if self.is_paused() {
+            return Vec::new();
+        }
+        // A dialogue interrupt whose overlay has been closed lets the turn go on.
+        if matches!(encounter.actions.current(), Some((PresentationStep::Interrupt(InterruptEvent::Dialogue { .. }), _))) {
+            encounter.resume_actions();
+        }
+        encounter.actions.cinematic = self.cinematic_camera;
+        let started = encounter.update_actions(dt);
+        for (_, error) in encounter.actions.rejected.drain(..) {
+            self.ui.toasts.push(Toast::rejected(error));
+        }
+        if let Some(hint) = self.hints.observe(encounter) {
+            self.ui.toasts.push(Toast::hint(hint));
+        }
+        if self.ironman.as_ref().is_some_and(|s| s.saved_commands != encounter.command_log.len()) {
+            self.autosave(Some(encounter));
+        }
+        if !matches!(encounter.actions.current(), Some((PresentationStep::FloatingText { .. }, _))) {
+            self.ui.floating_texts.clear();
+        }
+        for step in &started {
+            match step {
+                PresentationStep::FloatingText { changes } => {
+                    for (unit_id, value) in changes {
+                        let Some(unit) = encounter.unit_by_id(unit_id) else { continue };
+                        let cell = (unit.grid_position.x as u32, unit.grid_position.y as u32);
+                        let ((x, y), _) = renderer.screen_position(&DrawCall::new("", cell, DrawLayer::Units));
+                        self.ui.spawn_floating_text(*value, (x.max(0) as u32, y.max(0) as u32));
+                    }
+                }
+                PresentationStep::Interrupt(InterruptEvent::CameraPan { target }) => {
+                    let camera = &renderer.camera;
+                    let tile = renderer.tile_size as f32 * if camera.zoom_level > 0.0 { camera.zoom_level } else { 1.0 };
+                    let to = (
+                        ((target.x as f32 + 0.5) * tile - renderer.width as f32 / 2.0).max(0.0),
+                        ((target.y as f32 + 0.5) * tile - renderer.height as f32 / 2.0).max(0.0),
+                    );
+                    self.camera_pan = Some(((camera.x_offset, camera.y_offset), to));
+                }
+                PresentationStep::Interrupt(InterruptEvent::Dialogue { speaker, text }) => {
+                    self.start_dialogue(DialogueTree::single_line("interrupt", speaker, text));
+                }
+                PresentationStep::Cinematic { target } => {
+                    let camera = &renderer.camera;
+                    let zoom = (if camera.zoom_level > 0.0 { camera.zoom_level } else { 1.0 } * CINEMATIC_ZOOM).min(MAX_ZOOM);
+                    let tile = renderer.tile_size as f32 * zoom;
+                    let to = (
+                        ((target.x as f32 + 0.5) * tile - renderer.width as f32 / 2.0).max(0.0),
+                        ((target.y as f32 + 0.5) * tile - renderer.height as f32 / 2.0).max(0.0),
+                        zoom,
+                    );
+                    // A shot cut short by the next one goes back to where the first began.
+                    let restore = self.cinematic.take().map_or_else(|| camera.clone(), |shot| shot.restore);
+                    self.cinematic = Some(CinematicShot { restore, to, progress: 0.0 });
+                }
+                _ => {}
+            }
+        }
+        if let Some(shot) = &mut self.cinematic {
+            match encounter.actions.current() {
+                Some((PresentationStep::Cinematic { .. }, progress)) => shot.progress = progress,
+                Some(_) if encounter.actions.in_slow_motion() => shot.progress = 1.0,
+                _ => {
+                    renderer.camera = shot.restore.clone();
+                    self.cinematic = None;
+                }
+            }
+        }
+        if let Some(shot) = &self.cinematic {
+            let (from, t) = (&shot.restore, shot.progress);
+            renderer.camera.x_offset = from.x_offset + (shot.to.0 - from.x_offset) * t;
+            renderer.camera.y_offset = from.y_offset + (shot.to.1 - from.y_offset) * t;
+            renderer.camera.zoom_level = from.zoom_level + (shot.to.2 - from.zoom_level) * t;
+        }
+        if let Some((from, to)) = self.camera_pan {
+            let progress = match encounter.actions.current() {
+                Some((PresentationStep::Interrupt(InterruptEvent::CameraPan { .. }), progress)) => progress,
+                _ => {
+                    self.camera_pan = None;
+                    1.0
+                }
+            };
+            renderer.camera.x_offset = from.0 + (to.0 - from.0) * progress;
+            renderer.camera.y_offset = from.1 + (to.1 - from.1) * progress;
+        }
+        started
+    }
+
+    /// Play the battle's queued actions for `dt` seconds through
+    /// [`GameFlow::present_actions`], checking the events they raised for
+    /// achievements and hotseat hand-offs.
+    pub fn present_battle(&mut self, dt: f32, renderer: &mut Renderer) -> Vec<PresentationStep> {
+        let Some(mut battle) = self.battle.take() else { return Vec::new() };
+        let started = self.present_actions(dt, &mut battle, renderer);
+        let events = battle.drain_events();
+        for event in &events {
+            self.ui.handle_combat_event(event);
+        }
+        self.observe_combat(&events, &battle);
+        #[cfg(feature = "scripting")]
+        if let Some(script) = &mut self.script {
+            for event in &events {
+                if let Err(e) = script.handle_event(&mut battle, event) {
+                    self.ui.toasts.push(Toast::script_failed(&e));
+                }
+            }
+        }
+        self.battle = Some(battle);
+        started
+    }
+
+    /// Move the battle on once its queued actions have played: a finished
+    /// battle goes to the summary screen, a turn the player ended hands
+    /// over to the next unit, and units the AI controls take their turns.
+    /// Iron-man campaigns are saved as each turn begins.
+    fn advance_battle(&mut self) {
+        let Some(battle) = &mut self.battle else { return };
+        if battle.actions.is_busy() {
+            return;
+        }
+        if let Some(outcome) = battle.outcome() {
+            if let Some(battle) = self.battle.take() {
+                self.finish_battle(&battle, outcome);
+            }
+            return;
+        }
+        let ended = battle.command_log.get(self.turn_mark..).is_some_and(|log| {
+            log.iter().any(|c| matches!(c, Command::EndTurn | Command::Delay))
+        });
+        let standing = battle.turn_order.current_unit_id.as_deref().and_then(|id| battle.unit_by_id(id)).is_some_and(|u| u.health_points > 0);
+        if !ended && standing {
+            let current = battle.turn_order.current_unit_id.as_deref().unwrap_or_default();
+            // The player's units wait for orders.
+            if battle.controller_of(current) != Some(Controller::Ai) {
+                return;
+            }
+            let roll = battle.rng.roll_d100();
+            battle.ai_action(roll);
+        }
+        // Ending or delaying the turn already put it away.
+        if !ended {
+            battle.end_turn();
+        }
+        begin_turn(battle);
+        #[cfg(feature = "scripting")]
+        script_turn_started(&mut self.script, &mut self.ui, battle);
+        self.turn_mark = battle.command_log.len();
+        // The AI's action and the new turn aren't commands, so they're
+        // saved here rather than as they are shown.
+        if let Some(session) = &mut self.ironman
+            && let Err(e) = session.autosave(&self.campaign, Some(battle))
+        {
+            self.ui.toasts.push(Toast::autosave_failed(&e));
+        }
+    }
+
+    /// Advance the simulation by one fixed step of `dt` seconds.
+    pub fn update(&mut self, dt: f32) {
+        self.ui.captions.update(dt);
+        self.ui.toasts.update(dt);
+        if self.is_paused() {
+            return;
+        }
+        self.advance_battle();
+        match &mut self.battle {
+            Some(battle) => {
+                animate(battle.units.iter_mut(), dt);
+                let selection = self.state.selection.ids().to_vec();
+                self.state = GameState::from_encounter(battle);
+                self.state.set_selection(selection);
+            }
+            None => animate(self.state.units.iter_mut(), dt),
+        }
+    }
+
+    /// Render the world and then the UI on top. The world is skipped while
+    /// the hotseat pass-device screen is up.
+    pub fn render(&mut self, renderer: &mut Renderer, loc: &Localizer) {
+        if let Some(screen) = &self.loading {
+            renderer.clear_frame();
+            screen.render(renderer, loc);
+            return;
+        }
+        if let Some(screen) = &self.summary {
+            renderer.clear_frame();
+            screen.render(renderer, loc);
+            // Achievements unlocked by the battle pop up over the summary.
+            self.ui.toasts.render(renderer, loc);
+            return;
+        }
+        if let Some(screen) = &self.mods {
+            renderer.clear_frame();
+            screen.render(renderer, loc);
+            return;
+        }
+        if let Some(screen) = &self.codex {
+            renderer.clear_frame();
+            screen.render(renderer, loc);
+            return;
+        }
+        if let Some(screen) = &self.recruitment {
+            renderer.clear_frame();
+            screen.render(renderer, loc);
+            return;
+        }
+        if self.ui.pass_device.is_some() {
+            renderer.clear_frame();
+        } else {
+            renderer.render_state_retained(&self.state);
+            // The flow's state carries no weather, so debug costs are for clear skies.
+            self.debug.render(renderer, &self.state, Weather::default());
+            self.render_letterbox(renderer);
+        }
+        self.ui.render(renderer, loc);
+        if self.ui.pass_device.is_none() {
+            self.ui.render_selection(renderer, loc, &self.state.units, &self.state.map);
+        }
+        if self.ui.pass_device.is_none()
+            && let Some(tooltip) = &self.ui.tooltip
+        {
+            tooltip.render(renderer, loc, &self.state.units);
+        }
+        if let Some(dialogue) = &self.dialogue {
+            dialogue.render(renderer, loc, &self.campaign);
+        }
+        self.console.render(renderer);
+    }
+
+    /// Black bars closing in from the top and bottom of the screen while a
+    /// cinematic close-up plays.
+    fn render_letterbox(&self, renderer: &mut Renderer) {
+        let Some(shot) = &self.cinematic else { return };
+        let height = (renderer.height as f32 * LETTERBOX_HEIGHT * shot.progress).round() as u32;
+        if height == 0 {
+            return;
+        }
+        let size = (renderer.width, height);
+        for top in [0, renderer.height.saturating_sub(height)] {
+            renderer.draw_rect(DrawRect { position: (0, top), size, color: LETTERBOX_COLOR, layer: DrawLayer::Ui });
+        }
+    }
+}
+
+/// Start the next unit's turn with its action points back, unless it is
+/// taking up a turn it delayed.
+fn begin_turn(encounter: &mut CombatEncounter) {
+    let resuming = encounter.turn_order.initiative.front().is_some_and(|id| encounter.turn_order.delayed.contains(id));
+    encounter.start_turn();
+    if resuming {
+        return;
+    }
+    if let Some(unit) = encounter.turn_order.current_unit_id.clone().and_then(|id| encounter.unit_by_id_mut(&id)) {
+        unit.action_points = unit.current_stats.max_action;
+    }
+}
+
+/// Run the mission script's turn-start hook, if the battle has a script.
+/// A script that fails is reported with a toast.
+#[cfg(feature = "scripting")]
+fn script_turn_started(script: &mut Option<MissionScript>, ui: &mut UiManager, encounter: &mut CombatEncounter) {
+    if let Some(script) = script
+        && let Err(e) = script.turn_started(encounter)
+    {
+        ui.toasts.push(Toast::script_failed(&e));
+    }
+}
+
+/// Play the units' animations on by `dt` seconds.
+fn animate<'a>(units: impl Iterator<Item = &'a mut Unit>, dt: f32) {
+    for unit in units {
+        let anim = &mut unit.animation_state;
+        // The dead play their death frames from the start; the rare
+        // unit brought back gets up idle.
+        let dead = unit.health_points <= 0;
+        if dead != (anim.current_animation == AnimationType::Death) {
+            anim.current_animation = if dead { AnimationType::Death } else { AnimationType::Idle };
+            anim.frame_index = 0;
+            anim.timer = 0.0;
+        }
+        anim.timer += dt;
+        while anim.timer >= ANIMATION_FRAME_DURATION {
+            anim.timer -= ANIMATION_FRAME_DURATION;
+            anim.frame_index += 1;
+        }
+    }
+}
@@ -0,0 +1,140 @@
+use std::collections::{HashSet, VecDeque};
+
+use crate::combat::CombatEncounter;
+use crate::command::Command;
+use crate::models::{AnimationState, AnimationType, Position};
+
+/// Seconds each unit's move animation plays before the next queued move
+/// starts.
+pub const GROUP_MOVE_SECONDS: f32 = 0.4;
+
+/// Where `member` should stand when `leader` moves to `target`: the same
+/// offset from `target` it has from `leader` now. `None` when that falls
+/// off the top or left edge.
+pub fn formation_spot(leader: &Position, member: &Position, target: &Position) -> Option<Position> {
+    let x = (target.x + member.x).checked_sub(leader.x)?;
+    let y = (target.y + member.y).checked_sub(leader.y)?;
+    Some(Position { x, y })
+}
+
+fn octile(a: &Position, b: &Position) -> usize {
+    let (dx, dy) = (a.x.abs_diff(b.x), a.y.abs_diff(b.y));
+    dx.max(dy) + dx.min(dy)
+}
+
+impl CombatEncounter {
+    /// Moves taking the units in `unit_ids` to `target` in formation. The
+    /// first unit leads, heading for `target`; the others head for their
+    /// [`formation_spot`]. A spot the unit can't reach this turn, or that is
+    /// held by a unit staying put or already given to an earlier unit, is
+    /// swapped for the free reachable cell nearest to it. Units already on
+    /// their cell, or with nowhere to go, get no move. A unit moving onto a
+    /// cell another member is leaving is ordered after it.
+    pub fn plan_group_move(&self, unit_ids: &[String], target: &Position) -> Vec<Command> {
+        let members: Vec<_> = unit_ids.iter().filter_map(|id| self.unit_by_id(id)).filter(|u| u.health_points > 0).collect();
+        let Some(leader) = members.first().map(|u| u.grid_position.clone()) else { return Vec::new() };
+        let mut taken: HashSet<Position> = self
+            .units
+            .iter()
+            .filter(|u| u.health_points > 0 && !members.iter().any(|m| m.id == u.id))
+            .map(|u| u.grid_position.clone())
+            .collect();
+
+        let mut moves = Vec::new();
+        for unit in members {
+            let spot = formation_spot(&leader, &unit.grid_position, target).unwrap_or_else(|| target.clone());
+            let mut cells = self.movement_range(&unit.id);
+            cells.push(unit.grid_position.clone());
+            let Some(cell) = cells
+                .into_iter()
+                .filter(|c| !taken.contains(c))
+                .min_by_key(|c| (octile(c, &spot), octile(c, target), c.y, c.x))
+            else {
+                continue;
+            };
+            taken.insert(cell.clone());
+            if cell != unit.grid_position {
+                moves.push((unit.grid_position.clone(), unit.id.clone(), cell));
+            }
+        }
+
+        // Units swapping cells can't both wait, so the first of them goes.
+        let mut ordered = Vec::with_capacity(moves.len());
+        while !moves.is_empty() {
+            let next = moves.iter().position(|(_, _, to)| !moves.iter().any(|(from, _, _)| from == to)).unwrap_or(0);
+            let (_, unit_id, destination) = moves.remove(next);
+            ordered.push(Command::Move { unit_id, destination });
+        }
+        ordered
+    }
+}
+
+/// Moves played out one after another, each unit walking while its move
+/// animation runs before the next one sets off.
+#[derive(Debug, Clone, Default)]
+pub struct MoveQueue {
+    pending: VecDeque<Command>,
+    /// Unit currently moving and the seconds its animation has left.
+    active: Option<(String, f32)>,
+}
+
+impl MoveQueue {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn push(&mut self, moves: impl IntoIterator<Item = Command>) {
+        self.pending.extend(moves);
+    }
+
+    /// Moves playing or waiting.
+    pub fn len(&self) -> usize {
+        self.pending.len() + self.active.is_some() as usize
+    }
+
+    /// Whether no move is playing or waiting.
+    pub fn is_empty(&self) -> bool {
+        self.active.is_none() && self.pending.is_empty()
+    }
+
+    /// Drop every waiting move; the one playing finishes.
+    pub fn clear(&mut self) {
+        self.pending.clear();
+    }
+
+    /// Advance the playing animation by `dt` seconds. When it ends the unit
+    /// goes back to idle and the next move is executed on `encounter`,
+    /// starting its animation. Returns the moves that failed, e.g. because
+    /// the map changed since they were planned, and any command that isn't
+    /// a move; those are skipped.
+    pub fn update(&mut self, dt: f32, encounter: &mut CombatEncounter) -> Vec<Command> {
+        if let Some((id, left)) = &mut self.active {
+            *left -= dt;
+            if *left > 0.0 {
+                return Vec::new();
+            }
+            if let Some(unit) = encounter.unit_by_id_mut(id) {
+                unit.animation_state = AnimationState { current_animation: AnimationType::Idle, frame_index: 0, timer: 0.0 };
+            }
+            self.active = None;
+        }
+        let mut failed = Vec::new();
+        while let Some(command) = self.pending.pop_front() {
+            let Command::Move { unit_id, .. } = &command else {
+                failed.push(command);
+                continue;
+            };
+            let unit_id = unit_id.clone();
+            if encounter.execute(command.clone()).is_err() {
+                failed.push(command);
+                continue;
+            }
+            if let Some(unit) = encounter.unit_by_id_mut(&unit_id) {
+                unit.animation_state = AnimationState { current_animation: AnimationType::Move, frame_index: 0, timer: 0.0 };
+            }
+            self.active = Some((unit_id, GROUP_MOVE_SECONDS));
+            break;
+        }
+        failed
+    }
+}
@@ -0,0 +1,108 @@
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use serde::Deserialize;
+
+use crate::ioutil::invalid;
+use super::Renderer;
+
+/// Rectangle of a single sprite inside the atlas image, in pixels.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+pub struct AtlasRect {
+    pub x: u32,
+    pub y: u32,
+    pub w: u32,
+    pub h: u32,
+}
+
+/// JSON descriptor for a sprite atlas, following the `sprites.json` layout in
+/// the GDD with an added table of animation frame sequences.
+///
+/// ```json
+/// {
+///   "image": "sprites.png",
+///   "sprites": { "guardsman_idle_0": { "x": 0, "y": 0, "w": 32, "h": 32 } },
+///   "animations": { "guardsman_idle": ["guardsman_idle_0"] }
+/// }
+/// ```
+#[derive(Debug, Clone, Deserialize)]
+pub struct AtlasManifest {
+    /// Atlas image path, relative to the manifest file.
+    pub image: String,
+    pub sprites: HashMap<String, AtlasRect>,
+    #[serde(default)]
+    pub animations: HashMap<String, Vec<String>>,
+}
+
+/// Read a manifest and check it against the atlas image header: the image
+/// must exist, every rectangle must be non-empty and inside the image, and
+/// every animation frame must name a known sprite. Returns the manifest and
+/// the resolved image path.
+fn read_manifest(path: &Path) -> io::Result<(AtlasManifest, PathBuf)> {
+    let data = fs::read_to_string(path)?;
+    let manifest: AtlasManifest = serde_json::from_str(&data).map_err(|e| invalid(e.to_string()))?;
+    let image_path = path.parent().unwrap_or(Path::new("")).join(&manifest.image);
+    if !image_path.is_file() {
+        return Err(io::Error::new(
+            io::ErrorKind::NotFound,
+            format!("missing atlas image: {}", image_path.display()),
+        ));
+    }
+    let (width, height) = image::image_dimensions(&image_path).map_err(|e| invalid(e.to_string()))?;
+
+    for (id, rect) in &manifest.sprites {
+        if rect.w == 0
+            || rect.h == 0
+            || rect.x.checked_add(rect.w).is_none_or(|right| right > width)
+            || rect.y.checked_add(rect.h).is_none_or(|bottom| bottom > height)
+        {
+            return Err(invalid(format!("sprite '{}' lies outside the {}x{} atlas", id, width, height)));
+        }
+    }
+    for (id, frames) in &manifest.animations {
+        if frames.is_empty() {
+            return Err(invalid(format!("animation '{}' has no frames", id)));
+        }
+        if let Some(missing) = frames.iter().find(|f| !manifest.sprites.contains_key(*f)) {
+            return Err(invalid(format!("animation '{}' references unknown sprite '{}'", id, missing)));
+        }
+    }
+    Ok((manifest, image_path))
+}
+
+impl<'a> Renderer<'a> {
+    /// Validate an atlas manifest without decoding pixels or touching the GPU.
+    /// Useful for asset checks in tests and tooling.
+    pub fn validate_atlas<P: AsRef<Path>>(path: P) -> io::Result<AtlasManifest> {
+        read_manifest(path.as_ref()).map(|(manifest, _)| manifest)
+    }
+
+    /// Load a sprite atlas from a JSON manifest and its PNG image. Each sprite
+    /// rectangle is recorded in `sprites` and stored as a single-frame texture;
+    /// each animation is stored as a multi-frame texture under its own id.
+    /// Frames are kept as tightly packed RGBA8 bytes.
+    pub fn load_atlas<P: AsRef<Path>>(&mut self, path: P) -> io::Result<()> {
+        let (manifest, image_path) = read_manifest(path.as_ref())?;
+        let atlas = image::open(&image_path).map_err(|e| invalid(e.to_string()))?.to_rgba8();
+
+        let mut frames: HashMap<&str, Vec<u8>> = HashMap::new();
+        for (id, rect) in &manifest.sprites {
+            let pixels = image::imageops::crop_imm(&atlas, rect.x, rect.y, rect.w, rect.h)
+                .to_image()
+                .into_raw();
+            frames.insert(id.as_str(), pixels);
+            self.sprites.insert(id.clone(), (rect.x, rect.y, rect.w, rect.h));
+        }
+        for (id, sequence) in &manifest.animations {
+            let textures = sequence.iter().map(|f| frames[f.as_str()].clone()).collect();
+            self.sprite_textures.insert(id.clone(), textures);
+        }
+        for (id, pixels) in frames {
+            self.sprite_textures.entry(id.to_string()).or_insert_with(|| vec![pixels]);
+        }
+        self.texture_revision += 1;
+        Ok(())
+    }
+}
@@ -0,0 +1,342 @@
+use std::io;
+use std::sync::{Arc, Mutex};
+
+use image::{Rgba, RgbaImage};
+
+use super::text::{Color, DrawText};
+use super::{DrawCall, DrawLayer};
+
+/// Colour the screen is cleared to before each frame.
+pub const CLEAR_COLOR: Color = [0.02, 0.02, 0.03, 1.0];
+
+fn unsupported_capture() -> io::Error {
+    io::Error::new(io::ErrorKind::Unsupported, "this backend can't read frames back")
+}
+
+/// A filled rectangle in screen pixels.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DrawRect {
+    pub position: (u32, u32),
+    pub size: (u32, u32),
+    pub color: Color,
+    pub layer: DrawLayer,
+}
+
+/// Output for the frames the [`super::Renderer`] builds. The renderer
+/// culls, sorts and lays out; a backend only puts the result on screen,
+/// so the game and its UI draw the same way to a window, to memory in
+/// tests, or to a terminal.
+pub trait RenderBackend {
+    /// Draw `call` with its top-left corner at screen pixel `at`, scaled by
+    /// `scale`: the camera zoom for world layers, 1 for the UI.
+    fn draw_sprite(&mut self, call: &DrawCall, at: (i32, i32), scale: f32);
+
+    fn draw_text(&mut self, text: &DrawText);
+
+    fn draw_rect(&mut self, rect: &DrawRect);
+
+    /// Show everything drawn since the last present.
+    fn present(&mut self) -> io::Result<()>;
+
+    /// Pixels of the last presented frame.
+    fn capture(&mut self) -> io::Result<RgbaImage> {
+        Err(unsupported_capture())
+    }
+
+    /// Present modes the output supports.
+    fn present_modes(&self) -> Vec<wgpu::PresentMode> {
+        vec![wgpu::PresentMode::Fifo]
+    }
+
+    /// Match the output to a new size or present mode.
+    fn configure(&mut self, _width: u32, _height: u32, _present_mode: wgpu::PresentMode) {}
+}
+
+fn poisoned() -> io::Error {
+    io::Error::other("render backend lock poisoned")
+}
+
+/// A shared backend, so the caller can inspect what the renderer sent it.
+impl<B: RenderBackend> RenderBackend for Arc<Mutex<B>> {
+    fn draw_sprite(&mut self, call: &DrawCall, at: (i32, i32), scale: f32) {
+        if let Ok(mut backend) = self.lock() {
+            backend.draw_sprite(call, at, scale);
+        }
+    }
+
+    fn draw_text(&mut self, text: &DrawText) {
+        if let Ok(mut backend) = self.lock() {
+            backend.draw_text(text);
+        }
+    }
+
+    fn draw_rect(&mut self, rect: &DrawRect) {
+        if let Ok(mut backend) = self.lock() {
+            backend.draw_rect(rect);
+        }
+    }
+
+    fn present(&mut self) -> io::Result<()> {
+        self.lock().map_err(|_| poisoned())?.present()
+    }
+
+    fn capture(&mut self) -> io::Result<RgbaImage> {
+        self.lock().map_err(|_| poisoned())?.capture()
+    }
+
+    fn present_modes(&self) -> Vec<wgpu::PresentMode> {
+        self.lock().map(|backend| backend.present_modes()).unwrap_or_default()
+    }
+
+    fn configure(&mut self, width: u32, height: u32, present_mode: wgpu::PresentMode) {
+        if let Ok(mut backend) = self.lock() {
+            backend.configure(width, height, present_mode);
+        }
+    }
+}
+
+/// Everything drawn in one frame, in the order it was drawn.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct FrameCalls {
+    /// Sprites with the screen position and scale they were drawn at.
+    pub sprites: Vec<(DrawCall, (i32, i32), f32)>,
+    pub rects: Vec<DrawRect>,
+    pub texts: Vec<DrawText>,
+}
+
+/// Keeps the last presented frame in memory instead of showing it. Used
+/// by tests and servers without a display.
+#[derive(Debug, Default)]
+pub struct HeadlessBackend {
+    drawing: FrameCalls,
+    pub last_frame: FrameCalls,
+    pub frames_presented: u64,
+    size: (u32, u32),
+}
+
+impl RenderBackend for HeadlessBackend {
+    fn draw_sprite(&mut self, call: &DrawCall, at: (i32, i32), scale: f32) {
+        self.drawing.sprites.push((call.clone(), at, scale));
+    }
+
+    fn draw_text(&mut self, text: &DrawText) {
+        self.drawing.texts.push(text.clone());
+    }
+
+    fn draw_rect(&mut self, rect: &DrawRect) {
+        self.drawing.rects.push(rect.clone());
+    }
+
+    fn present(&mut self) -> io::Result<()> {
+        self.last_frame = std::mem::take(&mut self.drawing);
+        self.frames_presented += 1;
+        Ok(())
+    }
+
+    /// Without sprite or glyph pixels only the clear colour and the
+    /// rectangles are drawn, blended back to front.
+    fn capture(&mut self) -> io::Result<RgbaImage> {
+        let (width, height) = self.size;
+        let mut image = RgbaImage::from_pixel(width, height, to_rgba8(CLEAR_COLOR));
+        for rect in &self.last_frame.rects {
+            let [r, g, b, a] = rect.color;
+            let (x0, y0) = rect.position;
+            for y in y0..(y0 + rect.size.1).min(height) {
+                for x in x0..(x0 + rect.size.0).min(width) {
+                    let Rgba([dr, dg, db, _]) = *image.get_pixel(x, y);
+                    let blend = |src: f32, dst: u8| src * a + dst as f32 / 255.0 * (1.0 - a);
+                    image.put_pixel(x, y, to_rgba8([blend(r, dr), blend(g, dg), blend(b, db), 1.0]));
+                }
+            }
+        }
+        Ok(image)
+    }
+
+    /// Every mode, so vsync choices can be tested without a surface.
+    fn present_modes(&self) -> Vec<wgpu::PresentMode> {
+        vec![wgpu::PresentMode::Fifo, wgpu::PresentMode::Mailbox, wgpu::PresentMode::Immediate]
+    }
+
+    fn configure(&mut self, width: u32, height: u32, _present_mode: wgpu::PresentMode) {
+        self.size = (width, height);
+    }
+}
+
+fn to_rgba8(color: Color) -> Rgba<u8> {
+    Rgba(color.map(|c| (c.clamp(0.0, 1.0) * 255.0).round() as u8))
+}
+
+/// Draws to a window through wgpu. The sprite, text and rect pipelines
+/// aren't written yet: the frame's calls are collected for them and a
+/// frame is only the clear colour.
+pub struct WgpuBackend<'a> {
+    surface: wgpu::Surface<'a>,
+    device: wgpu::Device,
+    queue: wgpu::Queue,
+    config: wgpu::SurfaceConfiguration,
+    /// present modes the surface supports
+    present_modes: Vec<wgpu::PresentMode>,
+    drawing: FrameCalls,
+    /// the last presented frame, redrawn offscreen for captures
+    shown: FrameCalls,
+}
+
+impl<'a> WgpuBackend<'a> {
+    /// Set up the device and a surface for `window`. This is async because
+    /// wgpu device creation is async.
+    #[cfg(not(test))]
+    pub async fn new(window: &'a winit::window::Window) -> Self {
+        let size = window.inner_size();
+        let instance = wgpu::Instance::default();
+        let surface = instance.create_surface(window).expect("create surface");
+        let adapter = instance
+            .request_adapter(&wgpu::RequestAdapterOptions {
+                power_preference: wgpu::PowerPreference::LowPower,
+                compatible_surface: Some(&surface),
+                force_fallback_adapter: false,
+            })
+            .await
+            .expect("request adapter");
+        let (device, queue) = adapter
+            .request_device(&wgpu::DeviceDescriptor::default(), None)
+            .await
+            .expect("request device");
+        let capabilities = surface.get_capabilities(&adapter);
+        let config = wgpu::SurfaceConfiguration {
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
+            format: capabilities.formats[0],
+            width: size.width,
+            height: size.height,
+            present_mode: wgpu::PresentMode::Fifo,
+            alpha_mode: wgpu::CompositeAlphaMode::Opaque,
+            view_formats: vec![],
+            desired_maximum_frame_latency: 2,
+        };
+        surface.configure(&device, &config);
+        Self {
+            surface,
+            device,
+            queue,
+            config, present_modes: capabilities.present_modes,
+            drawing: FrameCalls::default(),
+            shown: FrameCalls::default(),
+        }
+    }
+
+    /// Record drawing the last presented frame into `target`.
+    fn encode_frame(&self, encoder: &mut wgpu::CommandEncoder, target: &wgpu::TextureView) {
+        let [r, g, b, a] = CLEAR_COLOR.map(f64::from);
+        encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            label: Some("frame"),
+            color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                view: target,
+                resolve_target: None,
+                ops: wgpu::Operations { load: wgpu::LoadOp::Clear(wgpu::Color { r, g, b, a }), store: wgpu::StoreOp::Store },
+            })],
+            depth_stencil_attachment: None,
+            timestamp_writes: None,
+            occlusion_query_set: None,
+        });
+    }
+}
+
+impl RenderBackend for WgpuBackend<'_> {
+    fn draw_sprite(&mut self, call: &DrawCall, at: (i32, i32), scale: f32) {
+        self.drawing.sprites.push((call.clone(), at, scale));
+    }
+
+    fn draw_text(&mut self, text: &DrawText) {
+        self.drawing.texts.push(text.clone());
+    }
+
+    fn draw_rect(&mut self, rect: &DrawRect) {
+        self.drawing.rects.push(rect.clone());
+    }
+
+    fn present(&mut self) -> io::Result<()> {
+        self.shown = std::mem::take(&mut self.drawing);
+        let output = match self.surface.get_current_texture() {
+            Ok(output) => output,
+            // The window changed under the surface; skip this frame.
+            Err(wgpu::SurfaceError::Outdated | wgpu::SurfaceError::Lost) => {
+                self.surface.configure(&self.device, &self.config);
+                return Ok(());
+            }
+            Err(e) => return Err(io::Error::other(e.to_string())),
+        };
+        let view = output.texture.create_view(&wgpu::TextureViewDescriptor::default());
+        let mut encoder = self.device.create_command_encoder(&wgpu::CommandEncoderDescriptor::default());
+        self.encode_frame(&mut encoder, &view);
+        self.queue.submit([encoder.finish()]);
+        output.present();
+        Ok(())
+    }
+
+    /// The swapchain image is gone once presented, so the frame is drawn
+    /// again into an offscreen texture of the same size and read back.
+    /// Fails in the browser, where the read can't be waited on.
+    fn capture(&mut self) -> io::Result<RgbaImage> {
+        let (width, height) = (self.config.width, self.config.height);
+        let size = wgpu::Extent3d { width, height, depth_or_array_layers: 1 };
+        let texture = self.device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("capture"),
+            size,
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: wgpu::TextureFormat::Rgba8Unorm,
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::COPY_SRC,
+            view_formats: &[],
+        });
+        // Rows of a texture copy must be padded to the copy alignment.
+        let row_bytes = width * 4;
+        let padded_row_bytes = row_bytes.div_ceil(wgpu::COPY_BYTES_PER_ROW_ALIGNMENT) * wgpu::COPY_BYTES_PER_ROW_ALIGNMENT;
+        let buffer = self.device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("capture"),
+            size: u64::from(padded_row_bytes * height),
+            usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::MAP_READ,
+            mapped_at_creation: false,
+        });
+        let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+        let mut encoder = self.device.create_command_encoder(&wgpu::CommandEncoderDescriptor::default());
+        self.encode_frame(&mut encoder, &view);
+        encoder.copy_texture_to_buffer(
+            texture.as_image_copy(),
+            wgpu::ImageCopyBuffer {
+                buffer: &buffer,
+                layout: wgpu::ImageDataLayout { offset: 0, bytes_per_row: Some(padded_row_bytes), rows_per_image: None },
+            },
+            size,
+        );
+        self.queue.submit([encoder.finish()]);
+
+        let slice = buffer.slice(..);
+        let (tx, rx) = std::sync::mpsc::channel();
+        slice.map_async(wgpu::MapMode::Read, move |result| {
+            let _ = tx.send(result);
+        });
+        self.device.poll(wgpu::Maintain::Wait);
+        match rx.try_recv() {
+            Ok(Ok(())) => {}
+            Ok(Err(e)) => return Err(io::Error::other(e.to_string())),
+            Err(_) => return Err(unsupported_capture()),
+        }
+        let mapped = slice.get_mapped_range();
+        let pixels: Vec<u8> =
+            mapped.chunks(padded_row_bytes as usize).flat_map(|row| &row[..row_bytes as usize]).copied().collect();
+        drop(mapped);
+        buffer.unmap();
+        RgbaImage::from_raw(width, height, pixels).ok_or_else(|| io::Error::other("captured frame has the wrong size"))
+    }
+
+    fn present_modes(&self) -> Vec<wgpu::PresentMode> {
+        self.present_modes.clone()
+    }
+
+    fn configure(&mut self, width: u32, height: u32, present_mode: wgpu::PresentMode) {
+        self.config.width = width;
+        self.config.height = height;
+        self.config.present_mode = present_mode;
+        self.surface.configure(&self.device, &self.config);
+    }
+}
@@ -0,0 +1,84 @@
+use std::collections::VecDeque;
+use std::io::{self, Cursor};
+
+use image::codecs::gif::{GifEncoder, Repeat};
+use image::{Delay, Frame, ImageFormat, RgbaImage};
+use web_time::{SystemTime, UNIX_EPOCH};
+
+/// Directory screenshots and clips are written to.
+pub const CAPTURE_DIR: &str = "captures";
+/// Frames per second kept for clips. Reading a frame back is slow, so
+/// clips sample far below the display rate.
+pub const CLIP_FPS: u32 = 10;
+
+/// A new file name under [`CAPTURE_DIR`] such as
+/// `captures/screenshot-1700000000123.png`, unique per millisecond.
+pub fn capture_path(kind: &str, extension: &str) -> String {
+    let millis = SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_millis()).unwrap_or_default();
+    format!("{}/{}-{}.{}", CAPTURE_DIR, kind, millis, extension)
+}
+
+pub fn encode_png(image: &RgbaImage) -> io::Result<Vec<u8>> {
+    let mut png = Cursor::new(Vec::new());
+    image.write_to(&mut png, ImageFormat::Png).map_err(io::Error::other)?;
+    Ok(png.into_inner())
+}
+
+/// Keeps the last few seconds of frames so a highlight or a bug can be
+/// saved as a GIF after it happened.
+#[derive(Debug, Clone)]
+pub struct ClipRecorder {
+    pub seconds: u32,
+    frames: VecDeque<RgbaImage>,
+    /// Seconds since the last kept frame.
+    since_frame: f32,
+}
+
+impl ClipRecorder {
+    pub fn new(seconds: u32) -> Self {
+        Self { seconds, frames: VecDeque::new(), since_frame: f32::INFINITY }
+    }
+
+    /// Advance by `dt` seconds and report whether the next frame should
+    /// be kept. Only read a frame back when this is true.
+    pub fn due(&mut self, dt: f32) -> bool {
+        self.since_frame += dt;
+        if self.since_frame < 1.0 / CLIP_FPS as f32 {
+            return false;
+        }
+        self.since_frame = 0.0;
+        true
+    }
+
+    /// Keep `frame`, dropping the oldest once the clip is full.
+    pub fn push(&mut self, frame: RgbaImage) {
+        self.frames.push_back(frame);
+        while self.frames.len() > (self.seconds * CLIP_FPS) as usize {
+            self.frames.pop_front();
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.frames.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.frames.is_empty()
+    }
+
+    /// The kept frames as a looping GIF, oldest first.
+    pub fn encode_gif(&self) -> io::Result<Vec<u8>> {
+        if self.frames.is_empty() {
+            return Err(io::Error::new(io::ErrorKind::InvalidInput, "no frames recorded"));
+        }
+        let mut gif = Vec::new();
+        let mut encoder = GifEncoder::new_with_speed(&mut gif, 10);
+        encoder.set_repeat(Repeat::Infinite).map_err(io::Error::other)?;
+        let delay = Delay::from_numer_denom_ms(1000, CLIP_FPS);
+        for frame in &self.frames {
+            encoder.encode_frame(Frame::from_parts(frame.clone(), 0, 0, delay)).map_err(io::Error::other)?;
+        }
+        drop(encoder);
+        Ok(gif)
+    }
+}
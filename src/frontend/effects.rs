@@ -0,0 +1,132 @@
+use crate::combat::CombatEvent;
+use crate::grid::GridMap;
+use crate::models::{EffectType, Unit};
+
+use super::palette::PaletteRole;
+use super::text::Color;
+use super::{DrawCall, DrawLayer, Renderer};
+
+/// Seconds a unit flashes white after taking damage.
+pub const HIT_FLASH_DURATION: f32 = 0.15;
+/// Seconds a unit shakes after a critical hit.
+pub const SHAKE_DURATION: f32 = 0.3;
+/// Peak shake displacement in pixels.
+pub const SHAKE_AMPLITUDE: f32 = 4.0;
+
+/// Seconds debris lingers where a map object was destroyed.
+pub const DEBRIS_DURATION: f32 = 0.5;
+/// Seconds the smoke and earth thrown up by a shell linger over its blast.
+pub const SHELL_BURST_DURATION: f32 = 0.8;
+
+pub const FLASH_WHITE: Color = [1.0, 1.0, 1.0, 1.0];
+pub const POISON_RED: Color = [1.0, 0.4, 0.4, 1.0];
+
+/// Timed visual state for a single unit. Timers count down in
+/// [`Renderer::update`] and the state is dropped once both reach zero.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct UnitVisuals {
+    pub flash_remaining: f32,
+    pub shake_remaining: f32,
+}
+
+impl UnitVisuals {
+    fn is_idle(&self) -> bool {
+        self.flash_remaining <= 0.0 && self.shake_remaining <= 0.0
+    }
+
+    /// Horizontal shake offset, alternating direction every 1/30 s and
+    /// fading out with the remaining time.
+    fn shake_offset(&self) -> (i32, i32) {
+        if self.shake_remaining <= 0.0 {
+            return (0, 0);
+        }
+        let strength = SHAKE_AMPLITUDE * (self.shake_remaining / SHAKE_DURATION);
+        let phase = (self.shake_remaining * 30.0) as i32;
+        let dx = if phase % 2 == 0 { strength } else { -strength };
+        (dx.round() as i32, 0)
+    }
+}
+
+impl<'a> Renderer<'a> {
+    /// Start visual feedback for a combat event.
+    pub fn handle_combat_event(&mut self, event: &CombatEvent) {
+        match event {
+            CombatEvent::UnitDamaged { unit_id, critical, .. } => {
+                let visuals = self.unit_visuals.entry(unit_id.clone()).or_default();
+                visuals.flash_remaining = HIT_FLASH_DURATION;
+                if *critical {
+                    visuals.shake_remaining = SHAKE_DURATION;
+                }
+                self.bleed(unit_id);
+            }
+            CombatEvent::ObjectDestroyed { position } => self.debris.push((position.clone(), DEBRIS_DURATION)),
+            CombatEvent::ShellImpact { cells, .. } => {
+                self.shell_bursts.extend(cells.iter().map(|c| (c.clone(), SHELL_BURST_DURATION)));
+            }
+            _ => {}
+        }
+    }
+
+    /// Advance timed visual states by `dt` seconds.
+    pub fn update(&mut self, dt: f32) {
+        for visuals in self.unit_visuals.values_mut() {
+            visuals.flash_remaining = (visuals.flash_remaining - dt).max(0.0);
+            visuals.shake_remaining = (visuals.shake_remaining - dt).max(0.0);
+        }
+        self.unit_visuals.retain(|_, v| !v.is_idle());
+        for (_, remaining) in self.debris.iter_mut().chain(&mut self.shell_bursts) {
+            *remaining -= dt;
+        }
+        self.debris.retain(|(_, remaining)| *remaining > 0.0);
+        self.shell_bursts.retain(|(_, remaining)| *remaining > 0.0);
+    }
+
+    /// Tint and shake offset for a unit's sprite. A hit flash takes priority
+    /// over the poison tint.
+    pub(super) fn unit_tint_and_offset(&self, unit: &Unit) -> (Option<Color>, (i32, i32)) {
+        let visuals = self.unit_visuals.get(&unit.id);
+        let tint = if visuals.is_some_and(|v| v.flash_remaining > 0.0) {
+            Some(FLASH_WHITE)
+        } else if has_status(unit, EffectType::Poison) {
+            Some(self.palette.color(PaletteRole::Poison))
+        } else {
+            None
+        };
+        (tint, visuals.map(|v| v.shake_offset()).unwrap_or((0, 0)))
+    }
+
+    /// Status-driven sprites drawn on top of a unit.
+    pub(super) fn status_overlays(&self, unit: &Unit, calls: &mut Vec<DrawCall>) {
+        let position = (unit.grid_position.x as u32, unit.grid_position.y as u32);
+        if has_status(unit, EffectType::Shield) {
+            let tint = Some(self.palette.color(PaletteRole::Shield));
+            calls.push(DrawCall { tint, ..DrawCall::new("effect:shield", position, DrawLayer::Effects) });
+        }
+        if has_status(unit, EffectType::Burning) {
+            calls.push(DrawCall::new("effect:burning", position, DrawLayer::Effects));
+        }
+        if has_status(unit, EffectType::Suppression) {
+            calls.push(DrawCall::new("effect:suppressed", position, DrawLayer::Effects));
+        }
+    }
+}
+
+impl<'a> Renderer<'a> {
+    /// Debris and shell bursts on visible cells.
+    pub(super) fn render_debris(&mut self, map: &GridMap) {
+        let debris = self.debris.iter().map(|(p, _)| (p, "effect:debris"));
+        let bursts = self.shell_bursts.iter().map(|(p, _)| (p, "effect:shell_burst"));
+        let calls: Vec<DrawCall> = debris
+            .chain(bursts)
+            .filter(|(p, _)| self.is_visible(map, p))
+            .map(|(p, sprite)| DrawCall::new(sprite, (p.x as u32, p.y as u32), DrawLayer::Effects))
+            .collect();
+        for call in calls {
+            self.submit(call);
+        }
+    }
+}
+
+pub(super) fn has_status(unit: &Unit, effect: EffectType) -> bool {
+    unit.status_effects.iter().any(|s| s.effect_type == effect && s.remaining_turns > 0)
+}
@@ -0,0 +1,437 @@
+use std::collections::HashMap;
+use std::io;
+
+use image::RgbaImage;
+
+use crate::state::GameState;
+use crate::models::Position;
+use crate::registry::UnitId;
+use crate::combat::CameraState;
+use crate::ui::options::AccessibilitySettings;
+use crate::weather::Weather;
+
+pub mod atlas;
+pub mod backend;
+pub mod capture;
+pub mod effects;
+pub mod palette;
+mod retained;
+pub mod terminal;
+pub mod text;
+pub mod tiles;
+pub mod wounds;
+
+use backend::{DrawRect, HeadlessBackend, RenderBackend};
+use effects::UnitVisuals;
+use palette::{Palette, PaletteRole};
+use retained::RetainedFrame;
+use text::{Color, DrawText};
+use tiles::TileOverlay;
+
+/// Sprite drawn under each unit, tinted with its side's palette colour,
+/// when loaded.
+pub const SIDE_MARKER: &str = "marker:side";
+/// Arrow drawn at each unit's feet pointing the way it faces, when
+/// loaded: one frame per [`crate::facing::Facing`], clockwise from north.
+pub const FACING_MARKER: &str = "marker:facing";
+/// Draw calls reserved per unit each frame: side marker, facing marker,
+/// sprite and a status overlay.
+const DRAW_CALLS_PER_UNIT: usize = 4;
+
+/// A very small renderer skeleton following the GDD specifications.
+/// Game and UI code record a frame of draw calls here; [`Renderer::present`]
+/// hands it to a [`RenderBackend`] (wgpu for a window, [`HeadlessBackend`]
+/// in tests), so nothing above this layer depends on the GPU.
+pub struct Renderer<'a> {
+    pub width: u32,
+    pub height: u32,
+    /// where presented frames go: a window, memory or a terminal
+    backend: Box<dyn RenderBackend + 'a>,
+    present_mode: wgpu::PresentMode,
+    /// mapping from sprite_id -> atlas rectangle
+    pub sprites: HashMap<String, (u32, u32, u32, u32)>,
+    /// loaded sprite textures (each sprite may have multiple frames)
+    pub sprite_textures: HashMap<String, Vec<Vec<u8>>>,
+    /// bumped whenever a texture is loaded or unloaded
+    texture_revision: u64,
+    /// record of draw calls issued during the last render
+    pub draw_log: Vec<DrawCall>,
+    /// camera used to cull tiles outside the viewport
+    pub camera: CameraState,
+    /// size of one grid tile in pixels at zoom 1.0
+    pub tile_size: u32,
+    /// highlight cells drawn above units (movement range, targeting, fog)
+    pub overlays: Vec<TileOverlay>,
+    /// record of text draw calls issued during the last render
+    pub text_log: Vec<DrawText>,
+    /// record of filled rectangles issued during the last render, ordered
+    /// by layer
+    pub rect_log: Vec<DrawRect>,
+    /// multiplier applied to every text size, from accessibility settings
+    pub font_scale: f32,
+    #[cfg(feature = "text")]
+    glyph_atlas: Option<text::GlyphAtlas>,
+    /// timed per-unit visual states (hit flash, shake) keyed by unit id
+    pub unit_visuals: HashMap<String, UnitVisuals>,
+    /// debris bursts at destroyed objects, with seconds remaining
+    pub debris: Vec<(Position, f32)>,
+    /// bursts over the blasts of indirect shells, with seconds remaining
+    pub shell_bursts: Vec<(Position, f32)>,
+    /// blood stains where units were hit, with the hits taken there
+    pub blood_decals: Vec<(Position, usize)>,
+    /// colour multiplied into terrain tiles, e.g. from the battle's weather
+    pub ambient_tint: Option<Color>,
+    /// colours for overlays, factions and status effects, from the
+    /// colour-blind option
+    pub palette: Palette,
+    /// world draws kept from the last [`Renderer::render_state_retained`]
+    retained: RetainedFrame,
+}
+
+/// Draw order buckets, back to front. World layers use grid positions and
+/// are culled against the camera; `Ui` uses screen pixels and always draws
+/// last.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum DrawLayer {
+    Terrain,
+    Objects,
+    Units,
+    Effects,
+    Overlay,
+    Ui,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct DrawCall {
+    pub sprite_id: String,
+    pub position: (u32, u32),
+    pub frame_index: usize,
+    pub layer: DrawLayer,
+    /// color multiplied into the sprite, if any
+    pub tint: Option<Color>,
+    /// screen-space offset in pixels, used for shake
+    pub offset: (i32, i32),
+}
+
+impl DrawCall {
+    /// Untinted, unshaken draw of the first frame of `sprite_id`.
+    pub fn new(sprite_id: impl Into<String>, position: (u32, u32), layer: DrawLayer) -> Self {
+        Self { sprite_id: sprite_id.into(), position, frame_index: 0, layer, tint: None, offset: (0, 0) }
+    }
+
+    /// Sort key within the draw log: layer first, then row for units so
+    /// sprites further down the screen overlap the ones above them.
+    fn sort_key(&self) -> (DrawLayer, u32) {
+        match self.layer {
+            DrawLayer::Units => (self.layer, self.position.1),
+            _ => (self.layer, 0),
+        }
+    }
+}
+
+impl<'a> Renderer<'a> {
+    /// Create a new renderer drawing to a window through wgpu. This is
+    /// async because wgpu device creation is async.
+    #[cfg(not(test))]
+    pub async fn new(window: &'a winit::window::Window) -> Self {
+        let size = window.inner_size();
+        Self::with_backend(size.width, size.height, backend::WgpuBackend::new(window).await)
+    }
+
+    /// Headless constructor used in tests or non-graphical environments.
+    pub fn new_headless(width: u32, height: u32) -> Self {
+        Self::with_backend(width, height, HeadlessBackend::default())
+    }
+
+    /// A renderer presenting its frames to `backend`.
+    pub fn with_backend(width: u32, height: u32, mut backend: impl RenderBackend + 'a) -> Self {
+        backend.configure(width, height, wgpu::PresentMode::Fifo);
+        Self {
+            width,
+            height,
+            backend: Box::new(backend),
+            present_mode: wgpu::PresentMode::Fifo,
+            sprites: HashMap::new(),
+            sprite_textures: HashMap::new(),
+            texture_revision: 0,
+            draw_log: Vec::new(),
+            camera: CameraState::new(width, height),
+            tile_size: 64,
+            overlays: Vec::new(),
+            text_log: Vec::new(),
+            rect_log: Vec::new(),
+            font_scale: 1.0,
+            #[cfg(feature = "text")]
+            glyph_atlas: None,
+            unit_visuals: HashMap::new(),
+            debris: Vec::new(),
+            shell_bursts: Vec::new(),
+            blood_decals: Vec::new(),
+            ambient_tint: None,
+            palette: Palette::default(),
+            retained: RetainedFrame::default(),
+        }
+    }
+
+    /// Follow a window resize: reconfigure the surface for the new size and
+    /// keep the camera centred. A zero size (minimised window) is ignored.
+    pub fn resize(&mut self, width: u32, height: u32) {
+        if width == 0 || height == 0 {
+            return;
+        }
+        self.camera.resize((self.width, self.height), (width, height));
+        self.width = width;
+        self.height = height;
+        self.reconfigure();
+    }
+
+    /// Present in step with the display (`Fifo`) or as fast as frames are
+    /// ready. Without vsync `Mailbox` is preferred since it doesn't tear;
+    /// surfaces supporting neither stay on `Fifo`.
+    pub fn set_vsync(&mut self, vsync: bool) {
+        use wgpu::PresentMode;
+
+        self.present_mode = if vsync {
+            PresentMode::Fifo
+        } else {
+            [PresentMode::Mailbox, PresentMode::Immediate]
+                .into_iter()
+                .find(|m| self.backend.present_modes().contains(m))
+                .unwrap_or(PresentMode::Fifo)
+        };
+        self.reconfigure();
+    }
+
+    pub fn present_mode(&self) -> wgpu::PresentMode {
+        self.present_mode
+    }
+
+    /// Apply the current size and present mode to the backend.
+    fn reconfigure(&mut self) {
+        self.backend.configure(self.width, self.height, self.present_mode);
+    }
+
+    /// Apply the player's accessibility options to subsequent draws.
+    pub fn apply_accessibility(&mut self, settings: &AccessibilitySettings) {
+        self.font_scale = settings.font_scale;
+        self.palette = Palette::new(settings.palette);
+    }
+
+    /// Tint the battlefield for the encounter's weather.
+    pub fn apply_weather(&mut self, weather: Weather) {
+        self.ambient_tint = weather.tint();
+    }
+
+        /// Load a sprite with one or more animation frames from raw byte data.
+    /// The renderer stores the bytes so tests can verify loading without a GPU.
+    pub fn load_sprite_from_bytes(&mut self, id: &str, frames: Vec<Vec<u8>>) {
+        self.sprite_textures.insert(id.to_string(), frames);
+        self.texture_revision += 1;
+    }
+
+    /// Drop a sprite's texture and atlas rectangle, e.g. once a battle that
+    /// used it is over.
+    pub fn unload_sprite(&mut self, id: &str) {
+        self.sprite_textures.remove(id);
+        self.sprites.remove(id);
+        self.texture_revision += 1;
+    }
+
+    /// Add a draw call to the log, keeping it ordered by layer. Calls with the
+    /// same sort key keep their submission order.
+    pub fn submit(&mut self, call: DrawCall) {
+        self.insert(call);
+    }
+
+    /// [`Self::submit`], returning where the call landed. A call landing
+    /// among the retained world draws means they can't be patched next
+    /// frame.
+    fn insert(&mut self, call: DrawCall) -> usize {
+        let key = call.sort_key();
+        let idx = self.draw_log.partition_point(|c| c.sort_key() <= key);
+        self.draw_log.insert(idx, call);
+        self.retained.displaced(idx);
+        idx
+    }
+
+    /// Add a filled rectangle to the frame, ordered by layer like
+    /// [`Self::submit`]. Rectangles are drawn under sprites on their layer.
+    pub fn draw_rect(&mut self, rect: DrawRect) {
+        let idx = self.rect_log.partition_point(|r| r.layer <= rect.layer);
+        self.rect_log.insert(idx, rect);
+    }
+
+    /// Start an empty frame.
+    pub fn clear_frame(&mut self) {
+        self.draw_log.clear();
+        self.retained.invalidate();
+        self.text_log.clear();
+        self.rect_log.clear();
+    }
+
+    /// Top-left screen pixel and scale of a draw call. World layers are
+    /// placed by the camera; the UI is already in screen pixels.
+    pub fn screen_position(&self, call: &DrawCall) -> ((i32, i32), f32) {
+        let (x, y) = (call.position.0 as f32, call.position.1 as f32);
+        let ((x, y), scale) = match call.layer {
+            DrawLayer::Ui => ((x, y), 1.0),
+            _ => {
+                let zoom = if self.camera.zoom_level > 0.0 { self.camera.zoom_level } else { 1.0 };
+                let tile = self.tile_size as f32 * zoom;
+                ((x * tile - self.camera.x_offset, y * tile - self.camera.y_offset), zoom)
+            }
+        };
+        ((x.round() as i32 + call.offset.0, y.round() as i32 + call.offset.1), scale)
+    }
+
+    /// Hand the frame to the backend and show it: sprites and rectangles
+    /// back to front by layer, then text on top. The logs are kept until
+    /// the next frame clears them.
+    pub fn present(&mut self) -> io::Result<()> {
+        let mut rects = self.rect_log.iter().peekable();
+        for call in &self.draw_log {
+            while let Some(rect) = rects.next_if(|r| r.layer <= call.layer) {
+                self.backend.draw_rect(rect);
+            }
+            let (at, scale) = self.screen_position(call);
+            self.backend.draw_sprite(call, at, scale);
+        }
+        for rect in rects {
+            self.backend.draw_rect(rect);
+        }
+        for text in &self.text_log {
+            self.backend.draw_text(text);
+        }
+        self.backend.present()
+    }
+
+    /// Pixels of the last presented frame, read back from the backend.
+    pub fn capture_image(&mut self) -> io::Result<RgbaImage> {
+        self.backend.capture()
+    }
+
+    /// The last presented frame as a PNG, for screenshots and bug reports.
+    pub fn capture_frame(&mut self) -> io::Result<Vec<u8>> {
+        capture::encode_png(&self.capture_image()?)
+    }
+
+    /// Update a unit's entry in `sprites`, only allocating its key the first
+    /// time it is drawn.
+    fn record_sprite(&mut self, unit_id: &str, entry: (u32, u32, u32, u32)) {
+        match self.sprites.get_mut(unit_id) {
+            Some(slot) => *slot = entry,
+            None => {
+                self.sprites.insert(unit_id.to_string(), entry);
+            }
+        }
+    }
+
+    /// Render the game state: terrain tiles inside the camera view, then
+    /// units, then highlight overlays. Units outside the view are skipped.
+    pub fn render_state(&mut self, state: &GameState) {
+        self.clear_frame();
+        let (xs, ys) = self.visible_tiles(&state.map);
+        self.draw_log.reserve(xs.len() * ys.len() + state.units.len() * DRAW_CALLS_PER_UNIT);
+        self.render_tiles(&state.map);
+        self.render_blood(&state.map);
+        self.render_loot(&state.map);
+        let mut calls = Vec::with_capacity(DRAW_CALLS_PER_UNIT);
+        for id in state.units.ids() {
+            self.unit_calls(state, id, &mut calls);
+            for call in calls.drain(..) {
+                self.submit(call);
+            }
+        }
+        self.render_debris(&state.map);
+        self.render_overlays(&state.map);
+    }
+
+    /// Record where a unit is drawn and push its draw calls, in submission
+    /// order: side marker, facing marker, sprite, status overlays. Nothing is pushed for a
+    /// unit without a loaded sprite or outside the view. The dead are
+    /// drawn as a corpse among the map's props, with nothing else.
+    fn unit_calls(&mut self, state: &GameState, id: UnitId, calls: &mut Vec<DrawCall>) {
+        let unit = &state.units[id];
+        let Position { x, y } = unit.grid_position;
+        let sprite = self.wound_sprite(unit);
+        let Some(frames) = self.sprite_textures.get(sprite.as_ref()).map(Vec::len) else {
+            // no sprite loaded; record position only
+            self.record_sprite(&unit.id, (x as u32, y as u32, 0, 0));
+            return;
+        };
+        let frame = wounds::sprite_frame(unit, frames);
+        self.record_sprite(&unit.id, (x as u32, y as u32, frame as u32, frames as u32));
+        let visible = self.is_visible(&state.map, &unit.grid_position) && !self.is_fogged(&unit.grid_position);
+        // Passengers ride out of sight inside their vehicle.
+        if !visible || unit.embarked_in.is_some() {
+            return;
+        }
+        if unit.health_points <= 0 {
+            calls.push(DrawCall {
+                frame_index: frame,
+                tint: self.ambient_tint,
+                ..DrawCall::new(sprite, (x as u32, y as u32), DrawLayer::Objects)
+            });
+            return;
+        }
+        // Optional ring under the unit in its side's colour.
+        if let Some(side) = state.units.side(id)
+            && self.sprite_textures.contains_key(SIDE_MARKER)
+        {
+            let tint = Some(self.palette.color(PaletteRole::of_side(side)));
+            calls.push(DrawCall { tint, ..DrawCall::new(SIDE_MARKER, (x as u32, y as u32), DrawLayer::Objects) });
+        }
+        if let Some(arrows) = self.sprite_textures.get(FACING_MARKER)
+            && !arrows.is_empty()
+        {
+            let frame_index = unit.facing as usize % arrows.len();
+            calls.push(DrawCall { frame_index, ..DrawCall::new(FACING_MARKER, (x as u32, y as u32), DrawLayer::Objects) });
+        }
+        let (tint, offset) = self.unit_tint_and_offset(unit);
+        calls.push(DrawCall {
+            frame_index: frame,
+            tint,
+            offset,
+            ..DrawCall::new(sprite, (x as u32, y as u32), DrawLayer::Units)
+        });
+        self.status_overlays(unit, calls);
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{Unit, UnitType, Faction};
+
+    #[test]
+    fn dummy_renderer_creates() {
+        let renderer = Renderer::new_headless(800, 600);
+        assert_eq!(renderer.width, 800);
+        assert_eq!(renderer.height, 600);
+    }
+
+    #[test]
+    fn render_updates_sprite_positions() {
+        let mut renderer = Renderer::new_headless(800, 600);
+        let mut unit = Unit::new("u1", "Test", UnitType::Guardsman, Faction::Imperial);
+        unit.grid_position = Position { x: 2, y: 3 };
+        let state = GameState::new(vec![unit]);
+        renderer.render_state(&state);
+        assert_eq!(renderer.sprites.get("u1"), Some(&(2, 3, 0, 0)));
+    }
+
+    #[test]
+    fn render_records_draw_calls() {
+        let mut renderer = Renderer::new_headless(100, 100);
+        renderer.load_sprite_from_bytes("s", vec![vec![1, 2, 3]]);
+        let mut unit = Unit::new("u", "U", UnitType::Guardsman, Faction::Imperial);
+        unit.sprite_id = "s".into();
+        unit.grid_position = Position { x: 1, y: 1 };
+        renderer.render_state(&GameState::new(vec![unit]));
+        assert_eq!(renderer.draw_log.len(), 1);
+        assert_eq!(renderer.draw_log[0].sprite_id, "s");
+        assert_eq!(renderer.draw_log[0].position, (1, 1));
+        assert_eq!(renderer.draw_log[0].frame_index, 0);
+    }
+}
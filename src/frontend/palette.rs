@@ -0,0 +1,138 @@
+use crate::registry::Side;
+use crate::ui::options::ColorBlindPalette;
+
+use super::effects::POISON_RED;
+use super::tiles::OverlayKind;
+use super::text::{Color, DAMAGE_RED, HEAL_GREEN};
+
+/// Something the renderer colours by meaning rather than by sprite, so a
+/// colour-blind palette can swap it for a distinguishable colour.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PaletteRole {
+    Ally,
+    Enemy,
+    MovementRange,
+    DashRange,
+    Targeting,
+    Fog,
+    AuraRing,
+    ThrowArc,
+    Trajectory,
+    BlastArea,
+    Poison,
+    Shield,
+    Damage,
+    Heal,
+}
+
+impl PaletteRole {
+    pub const ALL: [PaletteRole; 14] = [
+        PaletteRole::Ally,
+        PaletteRole::Enemy,
+        PaletteRole::MovementRange,
+        PaletteRole::DashRange,
+        PaletteRole::Targeting,
+        PaletteRole::Fog,
+        PaletteRole::AuraRing,
+        PaletteRole::ThrowArc,
+        PaletteRole::Trajectory,
+        PaletteRole::BlastArea,
+        PaletteRole::Poison,
+        PaletteRole::Shield,
+        PaletteRole::Damage,
+        PaletteRole::Heal,
+    ];
+
+    pub fn of_side(side: Side) -> PaletteRole {
+        match side {
+            Side::Player => PaletteRole::Ally,
+            Side::Enemy => PaletteRole::Enemy,
+        }
+    }
+}
+
+impl OverlayKind {
+    pub fn role(&self) -> PaletteRole {
+        match self {
+            OverlayKind::MovementRange => PaletteRole::MovementRange,
+            OverlayKind::DashRange => PaletteRole::DashRange,
+            OverlayKind::Targeting => PaletteRole::Targeting,
+            OverlayKind::Fog => PaletteRole::Fog,
+            OverlayKind::AuraRing => PaletteRole::AuraRing,
+            OverlayKind::ThrowArc => PaletteRole::ThrowArc,
+            OverlayKind::Trajectory => PaletteRole::Trajectory,
+            OverlayKind::BlastArea => PaletteRole::BlastArea,
+        }
+    }
+}
+
+/// Colour for `role` in `scheme`. The red-green schemes use the Okabe-Ito
+/// blue/orange pairs; tritanopia keeps red against bluish green.
+fn scheme_color(scheme: ColorBlindPalette, role: PaletteRole) -> Color {
+    use PaletteRole::*;
+    match (scheme, role) {
+        (_, Fog) => [0.0, 0.0, 0.0, 0.6],
+        (_, ThrowArc) => [1.0, 1.0, 1.0, 0.6],
+        (_, Trajectory) => [1.0, 0.9, 0.6, 0.7],
+        (ColorBlindPalette::Normal, Ally) => [0.2, 0.4, 1.0, 1.0],
+        (ColorBlindPalette::Normal, Enemy) => [0.9, 0.2, 0.2, 1.0],
+        (ColorBlindPalette::Normal, MovementRange) => [0.2, 0.6, 1.0, 0.5],
+        (ColorBlindPalette::Normal, DashRange) => [0.9, 0.9, 0.2, 0.5],
+        (ColorBlindPalette::Normal, Targeting) => [1.0, 0.2, 0.2, 0.5],
+        (ColorBlindPalette::Normal, AuraRing) => [1.0, 0.85, 0.3, 0.5],
+        (ColorBlindPalette::Normal, BlastArea) => [1.0, 0.5, 0.1, 0.5],
+        (ColorBlindPalette::Normal, Poison) => POISON_RED,
+        (ColorBlindPalette::Normal, Shield) => [0.4, 0.8, 1.0, 1.0],
+        (ColorBlindPalette::Normal, Damage) => DAMAGE_RED,
+        (ColorBlindPalette::Normal, Heal) => HEAL_GREEN,
+        (ColorBlindPalette::Protanopia | ColorBlindPalette::Deuteranopia, Ally) => [0.0, 0.45, 0.7, 1.0],
+        (ColorBlindPalette::Protanopia | ColorBlindPalette::Deuteranopia, Enemy) => [0.9, 0.62, 0.0, 1.0],
+        (ColorBlindPalette::Protanopia | ColorBlindPalette::Deuteranopia, MovementRange) => [0.34, 0.71, 0.91, 0.5],
+        (ColorBlindPalette::Protanopia | ColorBlindPalette::Deuteranopia, DashRange) => [0.0, 0.45, 0.7, 0.5],
+        (ColorBlindPalette::Protanopia | ColorBlindPalette::Deuteranopia, Targeting) => [0.9, 0.62, 0.0, 0.5],
+        (ColorBlindPalette::Protanopia | ColorBlindPalette::Deuteranopia, AuraRing) => [0.8, 0.47, 0.65, 0.5],
+        (ColorBlindPalette::Protanopia | ColorBlindPalette::Deuteranopia, BlastArea) => [0.94, 0.89, 0.26, 0.5],
+        (ColorBlindPalette::Protanopia | ColorBlindPalette::Deuteranopia, Poison) => [0.8, 0.47, 0.65, 1.0],
+        (ColorBlindPalette::Protanopia | ColorBlindPalette::Deuteranopia, Shield) => [0.34, 0.71, 0.91, 1.0],
+        // Reds look dim with protanopia, so damage uses yellow there.
+        (ColorBlindPalette::Protanopia, Damage) => [0.94, 0.89, 0.26, 1.0],
+        (ColorBlindPalette::Deuteranopia, Damage) => [0.9, 0.62, 0.0, 1.0],
+        (ColorBlindPalette::Protanopia | ColorBlindPalette::Deuteranopia, Heal) => [0.34, 0.71, 0.91, 1.0],
+        (ColorBlindPalette::Tritanopia, Ally) => [0.0, 0.62, 0.45, 1.0],
+        (ColorBlindPalette::Tritanopia, Enemy) => [0.84, 0.2, 0.3, 1.0],
+        (ColorBlindPalette::Tritanopia, MovementRange) => [0.0, 0.62, 0.45, 0.5],
+        (ColorBlindPalette::Tritanopia, DashRange) => [0.0, 0.35, 0.25, 0.5],
+        (ColorBlindPalette::Tritanopia, Targeting) => [0.84, 0.2, 0.3, 0.5],
+        (ColorBlindPalette::Tritanopia, AuraRing) => [0.8, 0.47, 0.65, 0.5],
+        (ColorBlindPalette::Tritanopia, BlastArea) => [0.9, 0.4, 0.2, 0.5],
+        (ColorBlindPalette::Tritanopia, Poison) => [0.8, 0.47, 0.65, 1.0],
+        (ColorBlindPalette::Tritanopia, Shield) => [0.6, 0.9, 0.9, 1.0],
+        (ColorBlindPalette::Tritanopia, Damage) => [0.84, 0.2, 0.3, 1.0],
+        (ColorBlindPalette::Tritanopia, Heal) => [0.0, 0.62, 0.45, 1.0],
+    }
+}
+
+/// Lookup table from [`PaletteRole`] to colour for one
+/// [`ColorBlindPalette`], built when the option changes so draws only
+/// index into it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Palette {
+    pub scheme: ColorBlindPalette,
+    lut: [Color; PaletteRole::ALL.len()],
+}
+
+impl Default for Palette {
+    fn default() -> Self {
+        Self::new(ColorBlindPalette::Normal)
+    }
+}
+
+impl Palette {
+    pub fn new(scheme: ColorBlindPalette) -> Self {
+        Self { scheme, lut: PaletteRole::ALL.map(|role| scheme_color(scheme, role)) }
+    }
+
+    pub fn color(&self, role: PaletteRole) -> Color {
+        self.lut[role as usize]
+    }
+}
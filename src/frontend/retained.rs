@@ -0,0 +1,245 @@
+use std::mem;
+use std::ops::Range;
+
+use crate::facing::Facing;
+use crate::models::{EffectType, Position};
+use crate::registry::{Side, UnitId};
+use crate::state::GameState;
+
+use super::effects::has_status;
+use super::palette::Palette;
+use super::text::Color;
+use super::tiles::{loot_sprites, OverlayKind};
+use super::wounds::{sprite_frame, WoundState};
+use super::{DrawCall, Renderer, DRAW_CALLS_PER_UNIT};
+
+/// Everything besides the units that the retained world draws depend on.
+/// Any change records the world afresh.
+#[derive(Debug, Clone, Default, PartialEq)]
+struct SceneKey {
+    revision: u64,
+    view: (Range<usize>, Range<usize>),
+    ambient_tint: Option<Color>,
+    palette: Palette,
+    textures: (usize, u64),
+    fog: Vec<Position>,
+    loot: Vec<(Position, &'static str)>,
+    debris: Vec<Position>,
+    blood: Vec<(Position, usize)>,
+}
+
+/// What a unit's draw calls are made from. `frame` is `None` while its
+/// sprite isn't loaded.
+#[derive(Debug, Clone, PartialEq)]
+struct UnitKey {
+    position: Position,
+    side: Option<Side>,
+    frame: Option<usize>,
+    tint: Option<Color>,
+    offset: (i32, i32),
+    statuses: [bool; 3],
+    /// Riding in a vehicle, and so not drawn.
+    embarked: bool,
+    facing: Facing,
+    wound: WoundState,
+}
+
+/// One unit's share of the retained draws. A unit's sprite is assumed
+/// not to change while it keeps its id and wound state.
+#[derive(Debug)]
+struct UnitSlot {
+    id: String,
+    /// frames loaded for its sprite, if any
+    frames: Option<usize>,
+    key: UnitKey,
+    /// draw log indices of its calls, in submission order
+    calls: Vec<usize>,
+}
+
+/// The world part of the draw log (terrain through effects) as recorded
+/// by the last [`Renderer::render_state_retained`], with what it was
+/// recorded from.
+#[derive(Debug, Default)]
+pub(super) struct RetainedFrame {
+    /// length of the world prefix of the draw log
+    len: usize,
+    /// false once the prefix was cleared or something was inserted into it
+    valid: bool,
+    scene: SceneKey,
+    /// the scene key being compared, kept to reuse its buffers
+    scratch: SceneKey,
+    units: Vec<UnitSlot>,
+    /// whether the last frame patched the prefix rather than rebuilding it
+    patched: bool,
+    changed: Vec<usize>,
+}
+
+impl RetainedFrame {
+    pub(super) fn invalidate(&mut self) {
+        self.valid = false;
+        self.patched = false;
+    }
+
+    /// Note a draw call inserted at `idx`.
+    pub(super) fn displaced(&mut self, idx: usize) {
+        if idx < self.len {
+            self.invalidate();
+        }
+    }
+}
+
+impl<'a> Renderer<'a> {
+    /// Render the game state like [`Self::render_state`], keeping the
+    /// world draws from the previous frame. Units whose position, animation
+    /// frame, tint or statuses changed are re-recorded in place; the rest
+    /// is only recorded again when the map, view, fog, loot, debris or
+    /// blood change, or a unit moves to another row or is wounded or
+    /// killed.
+    pub fn render_state_retained(&mut self, state: &GameState) {
+        self.text_log.clear();
+        self.rect_log.clear();
+        let mut scene = mem::take(&mut self.retained.scratch);
+        self.scene_key(state, &mut scene);
+        let unchanged = self.retained.valid && scene == self.retained.scene;
+        mem::swap(&mut self.retained.scene, &mut scene);
+        self.retained.scratch = scene;
+        if !(unchanged && self.patch_units(state)) {
+            self.rebuild_world(state);
+        }
+        self.render_overlays(&state.map);
+    }
+
+    /// Draw log indices the last [`Self::render_state_retained`]
+    /// re-recorded, so a backend keeping world sprites in an instance
+    /// buffer only uploads those. `None` when the whole frame was recorded
+    /// afresh.
+    pub fn changed_instances(&self) -> Option<&[usize]> {
+        self.retained.patched.then_some(&self.retained.changed[..])
+    }
+
+    fn scene_key(&self, state: &GameState, key: &mut SceneKey) {
+        key.revision = state.map.revision;
+        key.view = self.visible_tiles(&state.map);
+        key.ambient_tint = self.ambient_tint;
+        key.palette.clone_from(&self.palette);
+        key.textures = (self.sprite_textures.len(), self.texture_revision);
+        key.fog.clear();
+        key.fog.extend(self.overlays.iter().filter(|o| o.kind == OverlayKind::Fog).map(|o| o.position.clone()));
+        key.loot.clear();
+        key.loot.extend(loot_sprites(&state.map).map(|(p, sprite)| (p.clone(), sprite)));
+        key.debris.clear();
+        key.debris.extend(self.debris.iter().chain(&self.shell_bursts).map(|(p, _)| p.clone()));
+        key.blood.clone_from(&self.blood_decals);
+    }
+
+    /// The key for a unit whose sprite has `frames` frames loaded.
+    fn unit_key(&self, state: &GameState, id: UnitId, frames: Option<usize>) -> UnitKey {
+        let unit = &state.units[id];
+        let frame = frames.map(|n| sprite_frame(unit, n));
+        let (tint, offset) = self.unit_tint_and_offset(unit);
+        UnitKey {
+            position: unit.grid_position.clone(),
+            side: state.units.side(id),
+            frame,
+            tint,
+            offset,
+            statuses: [EffectType::Shield, EffectType::Burning, EffectType::Suppression].map(|e| has_status(unit, e)),
+            embarked: unit.embarked_in.is_some(),
+            facing: unit.facing,
+            wound: WoundState::of(unit),
+        }
+    }
+
+    /// Re-record the units that changed since the last frame over their
+    /// old draws. Returns false, leaving the log half patched, if the units
+    /// were added, removed or reordered, or a unit's draws no longer sort
+    /// where the old ones did.
+    fn patch_units(&mut self, state: &GameState) -> bool {
+        self.draw_log.truncate(self.retained.len);
+        self.retained.changed.clear();
+        if state.units.len() != self.retained.units.len() {
+            return false;
+        }
+        let mut units = mem::take(&mut self.retained.units);
+        let mut calls = Vec::with_capacity(DRAW_CALLS_PER_UNIT);
+        let patched = state.units.ids().zip(&mut units).all(|(id, slot)| self.patch_unit(state, id, slot, &mut calls));
+        self.retained.units = units;
+        self.retained.patched = patched;
+        patched
+    }
+
+    fn patch_unit(&mut self, state: &GameState, id: UnitId, slot: &mut UnitSlot, calls: &mut Vec<DrawCall>) -> bool {
+        let unit = &state.units[id];
+        if slot.id != unit.id {
+            return false;
+        }
+        let key = self.unit_key(state, id, slot.frames);
+        if key == slot.key {
+            return true;
+        }
+        // A new wound state swaps the sprite, and with it the frame count.
+        if key.wound != slot.key.wound {
+            return false;
+        }
+        calls.clear();
+        self.unit_calls(state, id, calls);
+        let fits = calls.len() == slot.calls.len()
+            && calls.iter().zip(&slot.calls).all(|(call, &idx)| call.sort_key() == self.draw_log[idx].sort_key());
+        if !fits {
+            return false;
+        }
+        for (call, &idx) in calls.drain(..).zip(&slot.calls) {
+            self.draw_log[idx] = call;
+        }
+        self.retained.changed.extend(&slot.calls);
+        slot.key = key;
+        true
+    }
+
+    /// Record the world from scratch, noting which draws belong to which
+    /// unit.
+    fn rebuild_world(&mut self, state: &GameState) {
+        self.draw_log.clear();
+        self.retained.invalidate();
+        self.retained.len = 0;
+        let (xs, ys) = self.visible_tiles(&state.map);
+        self.draw_log.reserve(xs.len() * ys.len() + state.units.len() * DRAW_CALLS_PER_UNIT);
+        self.render_tiles(&state.map);
+        self.render_blood(&state.map);
+        self.render_loot(&state.map);
+        // The unit each draw belongs to, kept in step with the log.
+        let mut owners = vec![None; self.draw_log.len()];
+        let mut calls = Vec::with_capacity(DRAW_CALLS_PER_UNIT);
+        for (slot, id) in state.units.ids().enumerate() {
+            self.unit_calls(state, id, &mut calls);
+            for call in calls.drain(..) {
+                let idx = self.insert(call);
+                owners.insert(idx, Some(slot));
+            }
+        }
+        let mut units: Vec<UnitSlot> = state
+            .units
+            .ids()
+            .map(|id| {
+                let unit = &state.units[id];
+                let frames = self.sprite_textures.get(self.wound_sprite(unit).as_ref()).map(Vec::len);
+                UnitSlot {
+                    id: unit.id.clone(),
+                    frames,
+                    key: self.unit_key(state, id, frames),
+                    calls: Vec::new(),
+                }
+            })
+            .collect();
+        for (idx, owner) in owners.into_iter().enumerate() {
+            if let Some(slot) = owner {
+                units[slot].calls.push(idx);
+            }
+        }
+        // Debris sorts after every unit draw, so their indices hold.
+        self.render_debris(&state.map);
+        self.retained.units = units;
+        self.retained.len = self.draw_log.len();
+        self.retained.valid = true;
+    }
+}
@@ -0,0 +1,259 @@
+use crate::grid::TerrainType;
+use crate::localization::Localizer;
+use crate::models::{Position, UnitType};
+use crate::registry::Side;
+use crate::state::GameState;
+
+use super::palette::{Palette, PaletteRole};
+use super::text::Color;
+
+/// Character drawn for an empty cell of `terrain`.
+pub fn terrain_glyph(terrain: &TerrainType) -> char {
+    match terrain {
+        TerrainType::Normal => '.',
+        TerrainType::Difficult => ':',
+        TerrainType::Hazardous => '~',
+        TerrainType::Blocked => '#',
+    }
+}
+
+/// Character drawn for a loot crate.
+pub const CRATE_GLYPH: char = '=';
+/// Character drawn for loot lying on the ground.
+pub const LOOT_GLYPH: char = '$';
+
+/// Character drawn for a unit. Capitals are the elite or heavy types of
+/// each faction.
+pub fn unit_glyph(unit_type: &UnitType) -> char {
+    match unit_type {
+        UnitType::SpaceMarine => 'M',
+        UnitType::Guardsman => 'G',
+        UnitType::Commissar => 'C',
+        UnitType::TechPriest => 'T',
+        UnitType::OrkBoy => 'o',
+        UnitType::OrkNob => 'O',
+        UnitType::Weirdboy => 'w',
+        UnitType::Cultist => 'c',
+        UnitType::ChaosMarine => 'X',
+        UnitType::Daemon => 'D',
+        UnitType::Chimera => 'H',
+        UnitType::Trukk => 'K',
+    }
+}
+
+/// One character of the battlefield.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TerminalCell {
+    pub glyph: char,
+    /// Side colour of the unit standing here; terrain uses the terminal's
+    /// default colour.
+    pub color: Option<Color>,
+    /// The selected unit, drawn reversed.
+    pub highlight: bool,
+}
+
+/// Everything the terminal frontend shows for one frame: the battlefield
+/// as characters, the turn order and the latest log lines. Built from the
+/// game state alone, so servers without a terminal can inspect it too.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TerminalFrame {
+    /// Rows of cells, top row first.
+    pub battlefield: Vec<Vec<TerminalCell>>,
+    /// Round header, then unit names in initiative order with the acting
+    /// unit marked.
+    pub turn_order: Vec<String>,
+    pub log: Vec<String>,
+}
+
+impl TerminalFrame {
+    pub fn new(state: &GameState, palette: &Palette, loc: &Localizer, log: &[String]) -> Self {
+        let map = &state.map;
+        let mut battlefield: Vec<Vec<TerminalCell>> = (0..map.height)
+            .map(|y| {
+                (0..map.width)
+                    .map(|x| TerminalCell {
+                        glyph: terrain_glyph(map.terrain_at(&Position { x, y })),
+                        color: None,
+                        highlight: false,
+                    })
+                    .collect()
+            })
+            .collect();
+        let loot = map.loot.iter().map(|t| (&t.position, LOOT_GLYPH));
+        for (pos, glyph) in loot.chain(map.crates.iter().map(|c| (&c.position, CRATE_GLYPH))) {
+            if map.in_bounds(pos) {
+                battlefield[pos.y][pos.x].glyph = glyph;
+            }
+        }
+        for id in state.units.ids() {
+            let unit = &state.units[id];
+            let Position { x, y } = unit.grid_position;
+            if unit.health_points <= 0 || !map.in_bounds(&unit.grid_position) {
+                continue;
+            }
+            let side = state.units.side(id).unwrap_or(Side::Player);
+            battlefield[y][x] = TerminalCell {
+                glyph: unit_glyph(&unit.unit_type),
+                color: Some(palette.color(PaletteRole::of_side(side))),
+                highlight: unit.is_selected,
+            };
+        }
+
+        let queue = &state.turn_queue;
+        let mut turn_order = vec![loc.format("tui.round", &[("round", &queue.round_number.to_string())])];
+        let current = queue.current_unit_id.as_deref();
+        let waiting = queue.initiative.iter().map(String::as_str).filter(|id| Some(*id) != current);
+        for id in current.into_iter().chain(waiting) {
+            let name = state.units.by_name(id).map_or(id, |u| u.name.as_str());
+            let marker = if Some(id) == current { '>' } else { ' ' };
+            turn_order.push(format!("{} {}", marker, name));
+        }
+        Self { battlefield, turn_order, log: log.to_vec() }
+    }
+
+    /// The battlefield as plain text, one line per row.
+    pub fn battlefield_text(&self) -> String {
+        self.battlefield.iter().map(|row| row.iter().map(|c| c.glyph).collect::<String>()).collect::<Vec<_>>().join("\n")
+    }
+}
+
+#[cfg(feature = "tui")]
+mod tui {
+    use std::io;
+    use std::time::{Duration, Instant};
+
+    use ratatui::crossterm::event::{self, Event, KeyCode, KeyEventKind};
+    use ratatui::layout::{Constraint, Layout};
+    use ratatui::style::{Color as TuiColor, Modifier, Style};
+    use ratatui::text::{Line, Span};
+    use ratatui::widgets::{Block, Borders, Paragraph};
+    use ratatui::Frame;
+
+    use super::TerminalFrame;
+    use crate::accessibility::Announcer;
+    use crate::flow::GameFlow;
+    use crate::frontend::palette::Palette;
+    use crate::localization::Localizer;
+    use crate::profile::Profile;
+    use crate::runner::{FixedTimestep, RunConfig};
+    use crate::ui::UiManager;
+
+    /// Width of each side panel in columns.
+    const PANEL_WIDTH: u16 = 28;
+
+    fn tui_color([r, g, b, _]: super::Color) -> TuiColor {
+        TuiColor::Rgb((r * 255.0) as u8, (g * 255.0) as u8, (b * 255.0) as u8)
+    }
+
+    /// Key names as [`crate::input::KeyBindings`] spells them, so the
+    /// player's bindings work in the terminal too.
+    fn key_name(code: KeyCode) -> Option<String> {
+        match code {
+            KeyCode::Up => Some("ArrowUp".into()),
+            KeyCode::Down => Some("ArrowDown".into()),
+            KeyCode::Left => Some("ArrowLeft".into()),
+            KeyCode::Right => Some("ArrowRight".into()),
+            KeyCode::Enter => Some("Enter".into()),
+            KeyCode::Char(c) if c.is_ascii_alphabetic() => Some(format!("Key{}", c.to_ascii_uppercase())),
+            KeyCode::Char(c) if c.is_ascii_digit() => Some(format!("Digit{}", c)),
+            _ => None,
+        }
+    }
+
+    impl TerminalFrame {
+        /// Draw the battlefield in the middle with the turn order on the
+        /// left and the log on the right.
+        pub fn draw(&self, frame: &mut Frame, loc: &Localizer) {
+            let [turn_order, battlefield, log] =
+                Layout::horizontal([Constraint::Length(PANEL_WIDTH), Constraint::Min(0), Constraint::Length(PANEL_WIDTH)])
+                    .areas(frame.area());
+
+            let rows: Vec<Line> = self
+                .battlefield
+                .iter()
+                .map(|row| {
+                    Line::from(
+                        row.iter()
+                            .map(|cell| {
+                                let mut style = Style::default();
+                                if let Some(color) = cell.color {
+                                    style = style.fg(tui_color(color)).add_modifier(Modifier::BOLD);
+                                }
+                                if cell.highlight {
+                                    style = style.add_modifier(Modifier::REVERSED);
+                                }
+                                Span::styled(cell.glyph.to_string(), style)
+                            })
+                            .collect::<Vec<_>>(),
+                    )
+                })
+                .collect();
+            let block = Block::default().borders(Borders::ALL).title(loc.get("tui.battlefield")).title_bottom(loc.get("tui.help"));
+            frame.render_widget(Paragraph::new(rows).block(block), battlefield);
+
+            let lines: Vec<Line> = self.turn_order.iter().map(|l| Line::from(l.as_str())).collect();
+            let block = Block::default().borders(Borders::ALL).title(loc.get("tui.turn_order"));
+            frame.render_widget(Paragraph::new(lines).block(block), turn_order);
+
+            // Newest lines at the bottom; older ones scroll off the top.
+            let visible = log.height.saturating_sub(2) as usize;
+            let lines: Vec<Line> =
+                self.log.iter().skip(self.log.len().saturating_sub(visible)).map(|l| Line::from(l.as_str())).collect();
+            let block = Block::default().borders(Borders::ALL).title(loc.get("tui.log"));
+            frame.render_widget(Paragraph::new(lines).block(block), log);
+        }
+    }
+
+    /// Play in the terminal instead of a window, for headless servers and
+    /// quick playtesting over SSH. Keys go through the player's bindings;
+    /// Tab selects the next player unit and `q` or Esc quits.
+    pub fn run_terminal(config: RunConfig) -> io::Result<()> {
+        let profile = Profile::load_default().unwrap_or_default();
+        let bindings = profile.settings().keybindings.clone();
+        let loc = Localizer::new(&config.language)?;
+        let palette = Palette::new(profile.settings().accessibility.palette);
+        let ui = UiManager::new(config.width, config.height, Vec::new(), Vec::new());
+        let mut flow = GameFlow::new(config.initial_state, ui);
+        let mut announcer = Announcer::new();
+        let mut timestep = FixedTimestep::new(config.updates_per_second);
+
+        let mut terminal = ratatui::init();
+        let result = (|| -> io::Result<()> {
+            let mut last_frame = Instant::now();
+            while !flow.quit_requested {
+                announcer.observe(&flow.ui, &flow.state.units, &loc);
+                let log: Vec<String> = announcer.history.iter().map(|a| a.text.clone()).collect();
+                let view = TerminalFrame::new(&flow.state, &palette, &loc, &log);
+                terminal.draw(|frame| view.draw(frame, &loc))?;
+
+                if event::poll(Duration::from_secs_f32(timestep.step))?
+                    && let Event::Key(key) = event::read()?
+                    && key.kind == KeyEventKind::Press
+                {
+                    match key.code {
+                        KeyCode::Esc | KeyCode::Char('q') => break,
+                        KeyCode::Tab => {
+                            flow.cycle_selection();
+                        }
+                        code => {
+                            if let Some(action) = key_name(code).and_then(|k| bindings.keys.get(&k).cloned()) {
+                                flow.handle_action(action);
+                            }
+                        }
+                    }
+                }
+                let now = Instant::now();
+                for _ in 0..timestep.advance((now - last_frame).as_secs_f32()) {
+                    flow.update(timestep.step);
+                }
+                last_frame = now;
+            }
+            Ok(())
+        })();
+        ratatui::restore();
+        result
+    }
+}
+
+#[cfg(feature = "tui")]
+pub use tui::run_terminal;
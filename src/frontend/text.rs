@@ -0,0 +1,220 @@
+use std::collections::BTreeMap;
+#[cfg(feature = "text")]
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use serde::Deserialize;
+use unicode_bidi::{BidiInfo, Level};
+use unicode_linebreak::{linebreaks, BreakOpportunity};
+
+use super::Renderer;
+use crate::localization::TextDirection;
+
+/// RGBA color with components in 0.0..=1.0.
+pub type Color = [f32; 4];
+
+pub const WHITE: Color = [1.0, 1.0, 1.0, 1.0];
+pub const DAMAGE_RED: Color = [0.9, 0.1, 0.1, 1.0];
+pub const HEAL_GREEN: Color = [0.1, 0.8, 0.2, 1.0];
+
+/// A single text draw call. `size` is the final pixel height after the
+/// renderer's font scale has been applied.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DrawText {
+    pub text: String,
+    pub position: (u32, u32),
+    pub size: f32,
+    pub color: Color,
+}
+
+/// Rasterized glyph cached in the atlas.
+#[cfg(feature = "text")]
+#[derive(Debug, Clone)]
+pub struct Glyph {
+    pub width: usize,
+    pub height: usize,
+    pub advance: f32,
+    /// 8-bit coverage values, row-major
+    pub bitmap: Vec<u8>,
+}
+
+/// Glyph cache backed by `fontdue`. Glyphs are rasterized on first use for
+/// each pixel size and kept for later frames.
+#[cfg(feature = "text")]
+pub struct GlyphAtlas {
+    font: fontdue::Font,
+    glyphs: HashMap<(char, u32), Glyph>,
+}
+
+#[cfg(feature = "text")]
+impl GlyphAtlas {
+    pub fn from_bytes(data: &[u8]) -> Result<Self, &'static str> {
+        let font = fontdue::Font::from_bytes(data, fontdue::FontSettings::default())?;
+        Ok(Self { font, glyphs: HashMap::new() })
+    }
+
+    pub fn glyph(&mut self, c: char, size: f32) -> &Glyph {
+        let font = &self.font;
+        self.glyphs.entry((c, size.round() as u32)).or_insert_with(|| {
+            let (metrics, bitmap) = font.rasterize(c, size);
+            Glyph { width: metrics.width, height: metrics.height, advance: metrics.advance_width, bitmap }
+        })
+    }
+
+    pub fn cached_glyphs(&self) -> usize {
+        self.glyphs.len()
+    }
+
+    fn measure(&self, text: &str, size: f32) -> (u32, u32) {
+        let width: f32 = text.chars().map(|c| self.font.metrics(c, size).advance_width).sum();
+        (width.ceil() as u32, size.ceil() as u32)
+    }
+}
+
+/// Which font file each language is drawn with, read from
+/// `assets/fonts/manifest.json`. Scripts the default font lacks glyphs for,
+/// such as Arabic or Japanese, get their own entry.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct FontManifest {
+    #[serde(default)]
+    pub default: Option<String>,
+    #[serde(default)]
+    pub languages: BTreeMap<String, String>,
+}
+
+impl FontManifest {
+    pub fn load<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        let data = fs::read_to_string(path)?;
+        serde_json::from_str(&data).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+    }
+
+    /// Font path for `language`: an exact match, then its primary subtag
+    /// (`zh` for `zh-TW`), then the default.
+    pub fn font_for(&self, language: &str) -> Option<&str> {
+        let primary = language.split(['-', '_']).next().unwrap_or(language);
+        self.languages
+            .get(language)
+            .or_else(|| self.languages.get(primary))
+            .or(self.default.as_ref())
+            .map(String::as_str)
+    }
+}
+
+/// Reorder one line of text from logical to display order with the Unicode
+/// bidirectional algorithm, so Arabic or Hebrew runs read right to left
+/// while embedded numbers and Latin names keep their own order.
+pub fn visual_order(line: &str, direction: TextDirection) -> String {
+    let level = match direction {
+        TextDirection::LeftToRight => Level::ltr(),
+        TextDirection::RightToLeft => Level::rtl(),
+    };
+    let info = BidiInfo::new(line, Some(level));
+    info.paragraphs.iter().map(|p| info.reorder_line(p, p.range.clone())).collect()
+}
+
+/// Whether `c` is a full-width character (CJK ideographs, kana, hangul and
+/// full-width forms), which the fixed-advance estimate draws one em wide.
+fn is_wide(c: char) -> bool {
+    matches!(c as u32,
+        0x1100..=0x115F | 0x2E80..=0xA4CF | 0xAC00..=0xD7A3 | 0xF900..=0xFAFF
+        | 0xFE30..=0xFE4F | 0xFF00..=0xFF60 | 0xFFE0..=0xFFE6 | 0x20000..=0x3FFFD)
+}
+
+impl<'a> Renderer<'a> {
+    /// Load a TTF/OTF font used for all text draw calls.
+    #[cfg(feature = "text")]
+    pub fn load_font(&mut self, data: &[u8]) -> Result<(), &'static str> {
+        self.glyph_atlas = Some(GlyphAtlas::from_bytes(data)?);
+        Ok(())
+    }
+
+    /// Load the font `manifest` picks for `language`. Does nothing if the
+    /// manifest has no font for it.
+    #[cfg(feature = "text")]
+    pub fn load_font_for(&mut self, manifest: &FontManifest, language: &str) -> io::Result<()> {
+        let Some(path) = manifest.font_for(language) else { return Ok(()) };
+        let data = fs::read(path)?;
+        self.load_font(&data).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+    }
+
+    /// Queue a text draw call. `size` is scaled by `font_scale`; glyphs are
+    /// rasterized into the atlas when a font is loaded.
+    pub fn draw_text(&mut self, text: &str, position: (u32, u32), size: f32, color: Color) {
+        let size = size * self.font_scale;
+        #[cfg(feature = "text")]
+        if let Some(atlas) = self.glyph_atlas.as_mut() {
+            for c in text.chars() {
+                atlas.glyph(c, size);
+            }
+        }
+        self.text_log.push(DrawText { text: text.to_string(), position, size, color });
+    }
+
+    /// Width and height in pixels of `text` at `size` after font scaling.
+    /// Without a loaded font a fixed-advance estimate is used so layout stays
+    /// deterministic in headless runs.
+    pub fn measure_text(&self, text: &str, size: f32) -> (u32, u32) {
+        let size = size * self.font_scale;
+        #[cfg(feature = "text")]
+        if let Some(atlas) = &self.glyph_atlas {
+            return atlas.measure(text, size);
+        }
+        let width: f32 = text.chars().map(|c| if is_wide(c) { size } else { size * 0.6 }).sum();
+        (width.round() as u32, size.round() as u32)
+    }
+
+    /// Split `text` into lines no wider than `max_width`, breaking only
+    /// where UAX #14 allows: between words, and between CJK characters
+    /// except before closing punctuation or small kana. Lines stay in
+    /// logical order; a single unbreakable run wider than `max_width` gets
+    /// a line of its own.
+    pub fn wrap_text(&self, text: &str, size: f32, max_width: u32) -> Vec<String> {
+        let mut lines = Vec::new();
+        let mut start = 0;
+        let mut last_fit = None;
+        for (pos, opportunity) in linebreaks(text) {
+            if let Some(fit) = last_fit
+                && self.measure_text(text[start..pos].trim_end(), size).0 > max_width
+            {
+                lines.push(text[start..fit].trim_end().to_string());
+                start = fit;
+            }
+            last_fit = Some(pos);
+            if opportunity == BreakOpportunity::Mandatory {
+                lines.push(text[start..pos].trim_end().to_string());
+                start = pos;
+                last_fit = None;
+            }
+        }
+        lines
+    }
+
+    /// Wrap `text` to `max_width` and draw each line in display order,
+    /// aligned to the left edge of the box for left-to-right text and to the
+    /// right edge for right-to-left. Returns the height used.
+    pub fn draw_paragraph(
+        &mut self,
+        text: &str,
+        position: (u32, u32),
+        max_width: u32,
+        size: f32,
+        color: Color,
+        direction: TextDirection,
+    ) -> u32 {
+        let line_height = (size * self.font_scale * 1.25).round() as u32;
+        let lines = self.wrap_text(text, size, max_width);
+        for (i, line) in lines.iter().enumerate() {
+            let line = visual_order(line, direction);
+            let x = match direction {
+                TextDirection::LeftToRight => position.0,
+                TextDirection::RightToLeft => {
+                    position.0 + max_width.saturating_sub(self.measure_text(&line, size).0)
+                }
+            };
+            self.draw_text(&line, (x, position.1 + i as u32 * line_height), size, color);
+        }
+        lines.len() as u32 * line_height
+    }
+}
@@ -0,0 +1,232 @@
+use std::collections::HashSet;
+use std::ops::Range;
+
+use crate::grenade::{blast_cells, throw_arc};
+use crate::indirect::trajectory;
+use crate::grid::{GridMap, TerrainType};
+use crate::loot::Loot;
+use crate::models::Position;
+
+use super::{DrawCall, DrawLayer, Renderer};
+
+/// Kinds of per-tile highlight drawn above units.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum OverlayKind {
+    MovementRange,
+    /// Cells a move reaches only by spending the unit's last AP.
+    DashRange,
+    Targeting,
+    Fog,
+    AuraRing,
+    ThrowArc,
+    /// Cells under an indirect shot's arc, drawn at the shell's height.
+    Trajectory,
+    BlastArea,
+}
+
+impl OverlayKind {
+    pub fn sprite_id(&self) -> &'static str {
+        match self {
+            OverlayKind::MovementRange => "overlay:move",
+            OverlayKind::DashRange => "overlay:dash",
+            OverlayKind::Targeting => "overlay:target",
+            OverlayKind::Fog => "overlay:fog",
+            OverlayKind::AuraRing => "overlay:aura",
+            OverlayKind::ThrowArc => "overlay:arc",
+            OverlayKind::Trajectory => "overlay:trajectory",
+            OverlayKind::BlastArea => "overlay:blast",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TileOverlay {
+    pub position: Position,
+    pub kind: OverlayKind,
+    /// Height above the ground in cells, drawn raised by half a tile per
+    /// cell; only trajectories leave the ground.
+    pub height: u32,
+}
+
+/// Crates, then loot tokens, with the sprite each is drawn with.
+pub(super) fn loot_sprites(map: &GridMap) -> impl Iterator<Item = (&Position, &'static str)> {
+    let crates = map.crates.iter().map(|c| (&c.position, "loot:crate"));
+    let tokens = map.loot.iter().map(|t| {
+        let sprite = match t.loot {
+            Loot::Item(_) => "loot:item",
+            Loot::Requisition(_) => "loot:requisition",
+        };
+        (&t.position, sprite)
+    });
+    crates.chain(tokens)
+}
+
+/// Default tile sprite id for a terrain type.
+pub fn terrain_sprite_id(terrain: &TerrainType) -> &'static str {
+    match terrain {
+        TerrainType::Normal => "tile_normal",
+        TerrainType::Difficult => "tile_difficult",
+        TerrainType::Hazardous => "tile_hazardous",
+        TerrainType::Blocked => "tile_blocked",
+    }
+}
+
+impl<'a> Renderer<'a> {
+    /// Range of tile columns and rows covered by the viewport at the current
+    /// camera offset and zoom, clamped to the map.
+    pub fn visible_tiles(&self, map: &GridMap) -> (Range<usize>, Range<usize>) {
+        let zoom = if self.camera.zoom_level > 0.0 { self.camera.zoom_level } else { 1.0 };
+        let tile = self.tile_size as f32 * zoom;
+        let span = |offset: f32, extent: u32, len: usize| {
+            let start = (offset / tile).floor().max(0.0) as usize;
+            let end = ((offset + extent as f32) / tile).ceil().max(0.0) as usize;
+            start.min(len)..end.min(len)
+        };
+        (
+            span(self.camera.x_offset, self.width, map.width),
+            span(self.camera.y_offset, self.height, map.height),
+        )
+    }
+
+    /// Grid cell under screen position `at` at the current camera offset
+    /// and zoom, or `None` left of or above the map. Cells past the map's
+    /// far edges are returned as they are.
+    pub fn cell_at(&self, at: (f32, f32)) -> Option<Position> {
+        let zoom = if self.camera.zoom_level > 0.0 { self.camera.zoom_level } else { 1.0 };
+        let tile = self.tile_size as f32 * zoom;
+        let x = ((self.camera.x_offset + at.0) / tile).floor();
+        let y = ((self.camera.y_offset + at.1) / tile).floor();
+        (x >= 0.0 && y >= 0.0).then_some(Position { x: x as usize, y: y as usize })
+    }
+
+    /// Whether a grid cell falls inside the camera view.
+    pub fn is_visible(&self, map: &GridMap, pos: &Position) -> bool {
+        let (xs, ys) = self.visible_tiles(map);
+        xs.contains(&pos.x) && ys.contains(&pos.y)
+    }
+
+    /// Replace all overlay cells of `kind` with `cells`.
+    pub fn set_overlay(&mut self, kind: OverlayKind, cells: &[Position]) {
+        self.clear_overlay(kind);
+        self.overlays
+            .extend(cells.iter().map(|p| TileOverlay { position: p.clone(), kind, height: 0 }));
+    }
+
+    pub fn clear_overlay(&mut self, kind: OverlayKind) {
+        self.overlays.retain(|o| o.kind != kind);
+    }
+
+    /// Show a unit's movement range from `(cell, AP cost)` bands, as
+    /// [`crate::combat::CombatEncounter::movement_bands`] gives them: cells
+    /// that leave AP to act with in one colour, cells taking the last of
+    /// its `action_points` in the dash colour.
+    pub fn set_movement_preview(&mut self, bands: &[(Position, u32)], action_points: u32) {
+        let (near, dash): (Vec<_>, Vec<_>) = bands.iter().partition(|(_, ap)| *ap < action_points);
+        let cells = |band: Vec<&(Position, u32)>| band.into_iter().map(|(p, _)| p.clone()).collect::<Vec<_>>();
+        self.set_overlay(OverlayKind::MovementRange, &cells(near));
+        self.set_overlay(OverlayKind::DashRange, &cells(dash));
+    }
+
+    pub fn clear_movement_preview(&mut self) {
+        self.clear_overlay(OverlayKind::MovementRange);
+        self.clear_overlay(OverlayKind::DashRange);
+    }
+
+    /// Cover every cell of `map` outside `visible` with fog. Units standing
+    /// in fog are not drawn.
+    pub fn set_fog(&mut self, map: &GridMap, visible: &HashSet<Position>) {
+        let fogged: Vec<Position> = (0..map.height)
+            .flat_map(|y| (0..map.width).map(move |x| Position { x, y }))
+            .filter(|p| !visible.contains(p))
+            .collect();
+        self.set_overlay(OverlayKind::Fog, &fogged);
+    }
+
+    /// Outline each `(center, radius)` aura with the cells at exactly that
+    /// grid distance from its center.
+    pub fn set_aura_rings(&mut self, map: &GridMap, auras: &[(Position, u32)]) {
+        let ring: Vec<Position> = (0..map.height)
+            .flat_map(|y| (0..map.width).map(move |x| Position { x, y }))
+            .filter(|p| auras.iter().any(|(c, r)| (c.x.abs_diff(p.x) + c.y.abs_diff(p.y)) as u32 == *r))
+            .collect();
+        self.set_overlay(OverlayKind::AuraRing, &ring);
+    }
+
+    /// Preview a grenade thrown from `from` at `target`: the arc's ground
+    /// track and the blast area if it lands on target.
+    pub fn set_grenade_preview(&mut self, map: &GridMap, from: &Position, target: &Position, radius: u32) {
+        self.set_overlay(OverlayKind::ThrowArc, &throw_arc(from, target));
+        self.set_overlay(OverlayKind::BlastArea, &blast_cells(map, target, radius));
+    }
+
+    pub fn clear_grenade_preview(&mut self) {
+        self.clear_overlay(OverlayKind::ThrowArc);
+        self.clear_overlay(OverlayKind::BlastArea);
+    }
+
+    /// Preview an indirect shot from `from` at `target`: the shell's arc,
+    /// raised to its height over each cell, and the blast area if it lands
+    /// on target.
+    pub fn set_bombard_preview(&mut self, map: &GridMap, from: &Position, target: &Position, radius: u32) {
+        self.clear_overlay(OverlayKind::Trajectory);
+        let arc = trajectory(from, target).into_iter();
+        self.overlays.extend(arc.map(|(position, height)| TileOverlay { position, kind: OverlayKind::Trajectory, height }));
+        self.set_overlay(OverlayKind::BlastArea, &blast_cells(map, target, radius));
+    }
+
+    pub fn clear_bombard_preview(&mut self) {
+        self.clear_overlay(OverlayKind::Trajectory);
+        self.clear_overlay(OverlayKind::BlastArea);
+    }
+
+    pub fn is_fogged(&self, pos: &Position) -> bool {
+        self.overlays.iter().any(|o| o.kind == OverlayKind::Fog && o.position == *pos)
+    }
+
+    /// Emit one draw call per visible tile whose terrain sprite is loaded.
+    pub(super) fn render_tiles(&mut self, map: &GridMap) {
+        let (xs, ys) = self.visible_tiles(map);
+        for y in ys {
+            for x in xs.clone() {
+                let sprite = terrain_sprite_id(map.terrain_at(&Position { x, y }));
+                if self.sprite_textures.contains_key(sprite) {
+                    let tint = self.ambient_tint;
+                    self.submit(DrawCall { tint, ..DrawCall::new(sprite, (x as u32, y as u32), DrawLayer::Terrain) });
+                }
+            }
+        }
+    }
+
+    /// Emit draw calls for crates and loot tokens on visible, unfogged
+    /// tiles; one token per cell however much lies there.
+    pub(super) fn render_loot(&mut self, map: &GridMap) {
+        let mut drawn = HashSet::new();
+        let calls: Vec<DrawCall> = loot_sprites(map)
+            .filter(|(p, _)| self.is_visible(map, p) && !self.is_fogged(p) && drawn.insert((*p).clone()))
+            .map(|(p, sprite)| DrawCall {
+                tint: self.ambient_tint,
+                ..DrawCall::new(sprite, (p.x as u32, p.y as u32), DrawLayer::Objects)
+            })
+            .collect();
+        for call in calls {
+            self.submit(call);
+        }
+    }
+
+    /// Emit draw calls for highlight overlays on visible tiles.
+    pub(super) fn render_overlays(&mut self, map: &GridMap) {
+        let calls: Vec<DrawCall> = self
+            .overlays
+            .iter()
+            .filter(|o| self.is_visible(map, &o.position))
+            .map(|o| DrawCall {
+                tint: Some(self.palette.color(o.kind.role())),
+                offset: (0, -((o.height * self.tile_size / 2) as i32)),
+                ..DrawCall::new(o.kind.sprite_id(), (o.position.x as u32, o.position.y as u32), DrawLayer::Overlay)
+            })
+            .collect();
+        for call in calls {
+            self.submit(call);
+        }
+    }
+}
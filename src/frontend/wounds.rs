@@ -0,0 +1,116 @@
+use std::borrow::Cow;
+
+use crate::grid::GridMap;
+use crate::models::{AnimationType, Position, Unit};
+
+use super::{DrawCall, DrawLayer, Renderer};
+
+/// Sprite for a dead unit whose own sprite has no `:corpse` variant.
+pub const CORPSE_SPRITE: &str = "prop:corpse";
+/// Blood pooled on a cell where a unit took damage; successive frames
+/// show heavier stains, one per hit up to the last frame.
+pub const BLOOD_DECAL: &str = "decal:blood";
+/// Share of its maximum health below which a unit looks wounded.
+pub const WOUNDED_BELOW: f32 = 0.5;
+/// Share of its maximum health below which a unit looks badly wounded.
+pub const CRITICAL_BELOW: f32 = 0.25;
+
+/// How hurt a unit looks. Each state draws the unit with a variant of its
+/// sprite, `<sprite>:wounded`, `<sprite>:critical` or `<sprite>:corpse`,
+/// when one is loaded.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum WoundState {
+    Healthy,
+    Wounded,
+    Critical,
+    Dead,
+}
+
+impl WoundState {
+    pub fn of(unit: &Unit) -> Self {
+        let share = unit.health_points as f32 / unit.current_stats.max_health.max(1) as f32;
+        if unit.health_points <= 0 {
+            WoundState::Dead
+        } else if share < CRITICAL_BELOW {
+            WoundState::Critical
+        } else if share < WOUNDED_BELOW {
+            WoundState::Wounded
+        } else {
+            WoundState::Healthy
+        }
+    }
+
+    /// Sprite suffixes to try, most specific first. A badly wounded unit
+    /// without its own frames makes do with the wounded ones.
+    fn variants(self) -> &'static [&'static str] {
+        match self {
+            WoundState::Healthy => &[],
+            WoundState::Wounded => &["wounded"],
+            WoundState::Critical => &["critical", "wounded"],
+            WoundState::Dead => &["corpse"],
+        }
+    }
+}
+
+/// The frame of a sprite with `frames` frames to draw for `unit`. Corpses
+/// play their death frames once and stay on the last.
+pub(super) fn sprite_frame(unit: &Unit, frames: usize) -> usize {
+    let index = unit.animation_state.frame_index;
+    match unit.animation_state.current_animation {
+        AnimationType::Death if unit.health_points <= 0 => index.min(frames.saturating_sub(1)),
+        _ => index.checked_rem(frames).unwrap_or(0),
+    }
+}
+
+impl<'a> Renderer<'a> {
+    /// The sprite `unit` is drawn with: the first of its wound state's
+    /// variants that is loaded, then [`CORPSE_SPRITE`] for the dead, then
+    /// its own sprite.
+    pub fn wound_sprite<'u>(&self, unit: &'u Unit) -> Cow<'u, str> {
+        let state = WoundState::of(unit);
+        for suffix in state.variants() {
+            let id = format!("{}:{suffix}", unit.sprite_id);
+            if self.sprite_textures.contains_key(&id) {
+                return Cow::Owned(id);
+            }
+        }
+        if state == WoundState::Dead && self.sprite_textures.contains_key(CORPSE_SPRITE) {
+            return Cow::Borrowed(CORPSE_SPRITE);
+        }
+        Cow::Borrowed(&unit.sprite_id)
+    }
+
+    /// Stain the cell `unit_id` was last drawn on. Stains stay for the
+    /// rest of the battle.
+    pub(super) fn bleed(&mut self, unit_id: &str) {
+        let Some(&(x, y, ..)) = self.sprites.get(unit_id) else { return };
+        let position = Position { x: x as usize, y: y as usize };
+        match self.blood_decals.iter_mut().find(|(p, _)| *p == position) {
+            Some((_, hits)) => *hits += 1,
+            None => self.blood_decals.push((position, 1)),
+        }
+    }
+
+    /// Wash the battlefield clean for the next battle.
+    pub fn clear_blood(&mut self) {
+        self.blood_decals.clear();
+    }
+
+    /// Blood stains on visible cells, under props and units.
+    pub(super) fn render_blood(&mut self, map: &GridMap) {
+        let Some(frames) = self.sprite_textures.get(BLOOD_DECAL).map(Vec::len) else { return };
+        let calls: Vec<DrawCall> = self
+            .blood_decals
+            .iter()
+            .filter(|(p, _)| self.is_visible(map, p) && !self.is_fogged(p))
+            .map(|(p, hits)| DrawCall {
+                frame_index: (hits - 1).min(frames.saturating_sub(1)),
+                tint: self.ambient_tint,
+                ..DrawCall::new(BLOOD_DECAL, (p.x as u32, p.y as u32), DrawLayer::Objects)
+            })
+            .collect();
+        for call in calls {
+            self.submit(call);
+        }
+    }
+}
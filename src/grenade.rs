@@ -0,0 +1,161 @@
+use crate::combat::{hp_change_event, CombatEncounter, CombatError, CombatEvent};
+use crate::grid::GridMap;
+use crate::models::{Accessory, Position, Stats, Unit};
+
+/// Action points spent on a throw.
+pub const GRENADE_AP_COST: u32 = 1;
+/// Throw range of a unit with no strength, in cells.
+pub const BASE_THROW_RANGE: u32 = 3;
+/// Misses within this many points of the hit chance land one cell off;
+/// worse misses land two cells off.
+pub const NEAR_MISS_MARGIN: i32 = 30;
+
+/// Neighbour offsets a scattered grenade can drift along, clockwise from north.
+const SCATTER_DIRECTIONS: [(i32, i32); 8] = [(0, -1), (1, -1), (1, 0), (1, 1), (0, 1), (-1, 1), (-1, 0), (-1, -1)];
+
+/// Maximum throw distance: the base range plus half the thrower's strength.
+pub fn throw_range(stats: &Stats) -> u32 {
+    (BASE_THROW_RANGE as i32 + stats.strength / 2).max(1) as u32
+}
+
+/// Percentile chance of landing on the aimed cell, falling off with distance.
+pub fn throw_hit_chance(stats: &Stats, distance: u32) -> i32 {
+    50 + stats.agility * 5 + stats.accuracy - distance as i32 * 5
+}
+
+pub(crate) fn distance(a: &Position, b: &Position) -> u32 {
+    a.x.abs_diff(b.x).max(a.y.abs_diff(b.y)) as u32
+}
+
+/// Cells a blast of `radius` centred on `center` covers.
+pub fn blast_cells(map: &GridMap, center: &Position, radius: u32) -> Vec<Position> {
+    map.circle(center, radius)
+}
+
+/// Cells the throw passes over on its way from `from` to `to`, excluding
+/// the thrower's own cell. Drawn as the arc preview.
+pub fn throw_arc(from: &Position, to: &Position) -> Vec<Position> {
+    let steps = distance(from, to);
+    let lerp = |a: usize, b: usize, i: u32| (a as f32 + (b as f32 - a as f32) * i as f32 / steps as f32).round() as usize;
+    (1..=steps).map(|i| Position { x: lerp(from.x, to.x, i), y: lerp(from.y, to.y, i) }).collect()
+}
+
+/// Shift `target` by `cells` along one of eight directions, stopping at the
+/// map edge.
+pub fn scatter(map: &GridMap, target: &Position, direction: usize, cells: u32) -> Position {
+    let (dx, dy) = SCATTER_DIRECTIONS[direction % SCATTER_DIRECTIONS.len()];
+    let mut pos = target.clone();
+    for _ in 0..cells {
+        let next = (pos.x as i32 + dx, pos.y as i32 + dy);
+        if next.0 < 0 || next.1 < 0 {
+            break;
+        }
+        let next = Position { x: next.0 as usize, y: next.1 as usize };
+        if !map.in_bounds(&next) {
+            break;
+        }
+        pos = next;
+    }
+    pos
+}
+
+/// Where a thrown grenade came down and what it covered.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GrenadeThrow {
+    pub impact: Position,
+    pub scattered: bool,
+    pub cells: Vec<Position>,
+}
+
+impl CombatEncounter {
+    /// Throw the grenade in the unit's accessory slot `accessory_index` at
+    /// `target`. A missed roll scatters the impact cell. The blast hits
+    /// every unit in range, friend or foe, destroys fragile objects, damages
+    /// structures and leaves
+    /// the grenade's hazard, if any, on the blast cells.
+    /// The grenade is used up.
+    pub fn throw_grenade(
+        &mut self,
+        thrower_id: &str,
+        accessory_index: usize,
+        target: &Position,
+    ) -> Result<GrenadeThrow, CombatError> {
+        let thrower = self.unit_by_id(thrower_id).ok_or(CombatError::UnknownUnit)?;
+        let Some(Accessory::Grenade { damage, aoe_radius, leaves }) = thrower.equipment.accessory_slots.get(accessory_index)
+        else {
+            return Err(CombatError::NoGrenade);
+        };
+        let (damage, aoe_radius, leaves) = (*damage, *aoe_radius, leaves.clone());
+        if !self.battlefield.in_bounds(target) {
+            return Err(CombatError::InvalidTarget);
+        }
+        let dist = distance(&thrower.grid_position, target);
+        if dist > throw_range(&thrower.current_stats) {
+            return Err(CombatError::OutOfRange);
+        }
+        if thrower.action_points < GRENADE_AP_COST {
+            return Err(CombatError::InsufficientAp);
+        }
+        let chance = throw_hit_chance(&thrower.current_stats, dist);
+        let impact = self.land(target, chance);
+
+        let thrower = self.unit_by_id_mut(thrower_id).ok_or(CombatError::UnknownUnit)?;
+        thrower.action_points -= GRENADE_AP_COST;
+        thrower.equipment.accessory_slots.remove(accessory_index);
+
+        let center = impact.clone();
+        let blast = |cells: &[Position]| CombatEvent::Explosion { center, cells: cells.to_vec() };
+        let cells = self.detonate(thrower_id, &impact, aoe_radius, damage, |_| damage, blast);
+        if let Some(hazard) = &leaves {
+            self.spawn_hazard(hazard, &cells);
+        }
+        Ok(GrenadeThrow { scattered: impact != *target, impact, cells })
+    }
+
+    /// Roll for a shot or throw aimed at `target` with a `chance` in 100 of
+    /// landing there. A miss scatters it a cell off, or two for a bad miss.
+    pub(crate) fn land(&mut self, target: &Position, chance: i32) -> Position {
+        let roll = self.rng.roll_d100() as i32;
+        if roll <= chance {
+            return target.clone();
+        }
+        let cells = if roll <= chance + NEAR_MISS_MARGIN { 1 } else { 2 };
+        let direction = self.rng.range(0, 7) as usize;
+        scatter(&self.battlefield, target, direction, cells)
+    }
+
+    /// Set off a blast of `radius` at `impact`, announced by the event
+    /// `blast` makes of its cells. Every unit on them, friend or foe, takes
+    /// what `damage` works out for it, credited to `source_id`; fragile
+    /// objects are destroyed and structures take `structure_damage`.
+    /// Returns the cells covered.
+    pub(crate) fn detonate(
+        &mut self,
+        source_id: &str,
+        impact: &Position,
+        radius: u32,
+        structure_damage: i32,
+        damage: impl Fn(&Unit) -> i32,
+        blast: impl FnOnce(&[Position]) -> CombatEvent,
+    ) -> Vec<Position> {
+        let cells = blast_cells(&self.battlefield, impact, radius);
+        self.events.push(blast(&cells));
+        let mut events = Vec::new();
+        for unit in self.units.iter_mut() {
+            if unit.health_points > 0 && unit.embarked_in.is_none() && cells.contains(&unit.grid_position) {
+                let before = unit.health_points;
+                unit.health_points -= damage(unit);
+                events.extend(hp_change_event(&unit.id, before, unit.health_points, false));
+                self.stats.record_damage(Some(source_id), &unit.id, before - unit.health_points);
+                self.stats.record_kill(source_id, before, unit.health_points);
+            }
+        }
+        for cell in &cells {
+            if self.battlefield.destroy_fragile(cell) || self.battlefield.damage_structure(cell, structure_damage) {
+                events.push(CombatEvent::ObjectDestroyed { position: cell.clone() });
+            }
+        }
+        self.events.extend(events);
+        cells
+    }
+}
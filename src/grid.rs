@@ -1,7 +1,12 @@
-use crate::models::{Position, Unit};
+use std::collections::{BTreeSet, BinaryHeap, HashMap};
+
+use crate::balance::MovementRules;
+use crate::loot::{LootCrate, LootToken};
+use crate::models::{ArmorTier, MovementType, Position, Unit, UnitType};
+use crate::weather::Weather;
 use serde::{Serialize, Deserialize};
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum TerrainType {
     Normal,
     Difficult,
@@ -14,20 +19,127 @@ pub struct GridMap {
     pub width: usize,
     pub height: usize,
     pub tiles: Vec<TerrainType>,
+    /// Blocked cells holding fragile objects (crates, barrels) that any
+    /// blast destroys. Ordered, so saves and checksums come out the same
+    /// every time.
+    #[serde(default)]
+    pub fragile: BTreeSet<Position>,
+    /// Cells fire can spread into (dry grass, fuel spills), ordered like
+    /// `fragile`.
+    #[serde(default)]
+    pub flammable: BTreeSet<Position>,
+    /// Blocked cells (walls, rocks) that can be knocked down.
+    #[serde(default)]
+    pub structures: Vec<Structure>,
+    /// Bumped on every terrain change so pathfinding and line-of-sight
+    /// caches know to rebuild.
+    #[serde(default)]
+    pub revision: u64,
+    /// Named areas that report units moving in and out.
+    #[serde(default)]
+    pub regions: Vec<TriggerRegion>,
+    /// Crates holding loot; see [`crate::loot`].
+    #[serde(default)]
+    pub crates: Vec<LootCrate>,
+    /// Loot lying on the ground.
+    #[serde(default)]
+    pub loot: Vec<LootToken>,
+    /// Step costs and movement points, from the encounter's balance.
+    #[serde(default)]
+    pub movement: MovementRules,
+}
+
+/// A rectangle of cells on the battlefield.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Region {
+    pub x: usize,
+    pub y: usize,
+    pub width: usize,
+    pub height: usize,
+}
+
+impl Region {
+    pub fn contains(&self, pos: &Position) -> bool {
+        (self.x..self.x + self.width).contains(&pos.x) && (self.y..self.y + self.height).contains(&pos.y)
+    }
+}
+
+/// The cells a [`TriggerRegion`] covers.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum RegionShape {
+    Rect(Region),
+    /// A set of cells, ordered so saves serialize the same every time.
+    Cells(BTreeSet<Position>),
+}
+
+/// A named area of the map, such as an extraction zone. Units moving into
+/// or out of it raise [`CombatEvent::RegionEntered`] and
+/// [`CombatEvent::RegionExited`].
+///
+/// [`CombatEvent::RegionEntered`]: crate::combat::CombatEvent::RegionEntered
+/// [`CombatEvent::RegionExited`]: crate::combat::CombatEvent::RegionExited
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TriggerRegion {
+    pub name: String,
+    pub shape: RegionShape,
+}
+
+impl TriggerRegion {
+    pub fn contains(&self, pos: &Position) -> bool {
+        match &self.shape {
+            RegionShape::Rect(rect) => rect.contains(pos),
+            RegionShape::Cells(cells) => cells.contains(pos),
+        }
+    }
+}
+
+/// A destructible blocking cell.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Structure {
+    pub position: Position,
+    pub hit_points: i32,
 }
 
 impl GridMap {
     pub fn new(width: usize, height: usize) -> Self {
-        Self { width, height, tiles: vec![TerrainType::Normal; width * height] }
+        Self {
+            width,
+            height,
+            tiles: vec![TerrainType::Normal; width * height],
+            fragile: BTreeSet::new(),
+            flammable: BTreeSet::new(),
+            structures: Vec::new(),
+            revision: 0,
+            regions: Vec::new(),
+            crates: Vec::new(),
+            loot: Vec::new(),
+            movement: MovementRules::DEFAULT,
+        }
     }
 
-    fn index(&self, pos: &Position) -> usize {
+    pub(crate) fn index(&self, pos: &Position) -> usize {
         pos.y * self.width + pos.x
     }
 
     pub fn set_terrain(&mut self, pos: &Position, terrain: TerrainType) {
         let idx = self.index(pos);
         self.tiles[idx] = terrain;
+        self.revision += 1;
+    }
+
+    /// Whether a shot from `from` reaches `to` without passing over a
+    /// blocked tile. The cells in between are those of
+    /// [`crate::grenade::throw_arc`].
+    pub fn has_line_of_sight(&self, from: &Position, to: &Position) -> bool {
+        crate::grenade::throw_arc(from, to)
+            .iter()
+            .all(|c| c == to || !matches!(self.terrain_at(c), TerrainType::Blocked))
+    }
+
+    /// Change step costs and movement points, rebuilding path caches.
+    pub fn set_movement_rules(&mut self, rules: MovementRules) {
+        self.movement = rules;
+        self.revision += 1;
     }
 
     pub fn terrain_at(&self, pos: &Position) -> &TerrainType {
@@ -37,43 +149,243 @@ impl GridMap {
     pub fn in_bounds(&self, pos: &Position) -> bool {
         pos.x < self.width && pos.y < self.height
     }
+
+    /// In-bounds cells within `radius` of `center`, as a rasterized circle.
+    pub fn circle(&self, center: &Position, radius: u32) -> Vec<Position> {
+        let r = radius as i64;
+        let (cx, cy) = (center.x as i64, center.y as i64);
+        (cy - r..=cy + r)
+            .flat_map(|y| (cx - r..=cx + r).map(move |x| (x, y)))
+            .filter(|&(x, y)| (x - cx).pow(2) + (y - cy).pow(2) <= r * r)
+            .filter(|&(x, y)| x >= 0 && y >= 0)
+            .map(|(x, y)| Position { x: x as usize, y: y as usize })
+            .filter(|p| self.in_bounds(p))
+            .collect()
+    }
+
+    /// In-bounds cells within manhattan distance `range` of `center`, the
+    /// shape of a weapon's reach.
+    pub fn cells_within(&self, center: &Position, range: u32) -> Vec<Position> {
+        let r = range as i64;
+        let (cx, cy) = (center.x as i64, center.y as i64);
+        (cy - r..=cy + r)
+            .flat_map(|y| (cx - r..=cx + r).map(move |x| (x, y)))
+            .filter(|&(x, y)| (x - cx).abs() + (y - cy).abs() <= r)
+            .filter(|&(x, y)| x >= 0 && y >= 0)
+            .map(|(x, y)| Position { x: x as usize, y: y as usize })
+            .filter(|p| self.in_bounds(p))
+            .collect()
+    }
+
+    /// In-bounds orthogonal neighbours of `pos`.
+    pub fn neighbours(&self, pos: &Position) -> Vec<Position> {
+        let mut out = Vec::with_capacity(4);
+        if pos.y > 0 {
+            out.push(Position { x: pos.x, y: pos.y - 1 });
+        }
+        out.push(Position { x: pos.x + 1, y: pos.y });
+        out.push(Position { x: pos.x, y: pos.y + 1 });
+        if pos.x > 0 {
+            out.push(Position { x: pos.x - 1, y: pos.y });
+        }
+        out.retain(|p| self.in_bounds(p));
+        out
+    }
+
+    /// In-bounds neighbours of `pos`, diagonals included.
+    pub fn adjacent(&self, pos: &Position) -> Vec<Position> {
+        self.adjacent_cells(pos).collect()
+    }
+
+    /// [`GridMap::adjacent`] without the allocation, for search loops.
+    fn adjacent_cells<'a>(&'a self, pos: &'a Position) -> impl Iterator<Item = Position> + 'a {
+        DIRECTIONS
+            .iter()
+            .filter_map(|(dx, dy)| {
+                let x = pos.x.checked_add_signed(*dx)?;
+                let y = pos.y.checked_add_signed(*dy)?;
+                Some(Position { x, y })
+            })
+            .filter(|p| self.in_bounds(p))
+    }
+
+    /// Place a fragile object, blocking the cell until it is destroyed.
+    pub fn place_fragile(&mut self, pos: &Position) {
+        self.set_terrain(pos, TerrainType::Blocked);
+        self.fragile.insert(pos.clone());
+    }
+
+    /// Block `pos` with a structure that collapses after `hit_points` damage.
+    pub fn place_structure(&mut self, pos: &Position, hit_points: i32) {
+        self.set_terrain(pos, TerrainType::Blocked);
+        self.structures.retain(|s| s.position != *pos);
+        self.structures.push(Structure { position: pos.clone(), hit_points });
+    }
+
+    /// Damage the structure at `pos`. When its hit points run out it
+    /// collapses into difficult rubble and this returns true.
+    pub fn damage_structure(&mut self, pos: &Position, amount: i32) -> bool {
+        let Some(idx) = self.structures.iter().position(|s| s.position == *pos) else { return false };
+        self.structures[idx].hit_points -= amount;
+        if self.structures[idx].hit_points > 0 {
+            return false;
+        }
+        self.structures.remove(idx);
+        self.set_terrain(pos, TerrainType::Difficult);
+        true
+    }
+
+    /// The trigger region called `name`.
+    pub fn region(&self, name: &str) -> Option<&TriggerRegion> {
+        self.regions.iter().find(|r| r.name == name)
+    }
+
+    /// Names of the trigger regions covering `pos`.
+    pub fn regions_at(&self, pos: &Position) -> impl Iterator<Item = &str> {
+        self.regions.iter().filter(move |r| r.contains(pos)).map(|r| r.name.as_str())
+    }
+
+    /// Regions entered (`true`) and left (`false`) walking `path` from its
+    /// first cell, in the order they are crossed. A region passed through
+    /// is both entered and left.
+    pub fn region_crossings(&self, path: &[Position]) -> Vec<(&str, bool)> {
+        let mut crossings = Vec::new();
+        for step in path.windows(2) {
+            for region in &self.regions {
+                let (was_in, is_in) = (region.contains(&step[0]), region.contains(&step[1]));
+                if was_in != is_in {
+                    crossings.push((region.name.as_str(), is_in));
+                }
+            }
+        }
+        crossings
+    }
+
+    /// Destroy the fragile object at `pos`, clearing the cell. Returns
+    /// false if there was none.
+    pub fn destroy_fragile(&mut self, pos: &Position) -> bool {
+        if !self.fragile.remove(pos) {
+            return false;
+        }
+        self.set_terrain(pos, TerrainType::Normal);
+        true
+    }
 }
 
-/// Calculate movement cost between two adjacent tiles
-fn tile_cost(terrain: &TerrainType, diagonal: bool) -> u32 {
-    let mut cost = if diagonal { 2 } else { 1 };
-    match terrain {
-        TerrainType::Difficult => cost += 1,
-        TerrainType::Hazardous => cost += 2,
-        TerrainType::Blocked => cost = u32::MAX,
-        TerrainType::Normal => {}
+/// Damage for entering a hazardous tile, before armour and unit type.
+/// Even, so power armour's half is still some damage.
+pub const HAZARD_DAMAGE: i32 = 2;
+
+/// Steps to the eight neighbouring cells.
+const DIRECTIONS: [(isize, isize); 8] = [(-1, 0), (1, 0), (0, -1), (0, 1), (-1, -1), (-1, 1), (1, -1), (1, 1)];
+
+/// Cost of stepping from `from` onto the adjacent cell `to`, including
+/// weather, or `None` if the step is not allowed. Ground units cannot cut
+/// the corner of a blocked tile diagonally.
+pub(crate) fn step_cost(map: &GridMap, from: &Position, to: &Position, movement: MovementType, weather: Weather) -> Option<u32> {
+    let diagonal = from.x != to.x && from.y != to.y;
+    if diagonal && movement.blocked_crossing_cost().is_none() {
+        let adj1 = Position { x: from.x, y: to.y };
+        let adj2 = Position { x: to.x, y: from.y };
+        if matches!(map.terrain_at(&adj1), TerrainType::Blocked) || matches!(map.terrain_at(&adj2), TerrainType::Blocked)
+        {
+            return None;
+        }
+    }
+    let terrain = map.terrain_at(to);
+    Some(map.movement.step(terrain, diagonal, movement)? + weather.step_cost(terrain))
+}
+
+/// Damage `unit` takes from a hazardous tile. Daemons are immune and power
+/// armour halves it.
+pub fn hazard_damage(unit: &Unit) -> i32 {
+    if unit.unit_type == UnitType::Daemon {
+        return 0;
+    }
+    match &unit.equipment.armor {
+        Some(armor) if matches!(armor.tier, ArmorTier::PowerArmor) => HAZARD_DAMAGE / 2,
+        _ => HAZARD_DAMAGE,
     }
-    cost
 }
 
 /// Attempt to move a unit to `dest` using A* pathfinding. The unit will move if
-/// the cheapest path costs no more movement points than allowed by its agility.
+/// it can pay for the cheapest path, spending AP as [`move_ap_cost`] says.
 pub fn try_move(unit: &mut Unit, dest: Position, map: &GridMap) -> bool {
-    use std::collections::{BinaryHeap, HashMap};
+    try_move_with_bonus(unit, dest, map, 0)
+}
 
-    if !map.in_bounds(&dest) {
-        return false;
+/// [`try_move`] with `bonus` extra movement points, e.g. from doctrines.
+pub fn try_move_with_bonus(unit: &mut Unit, dest: Position, map: &GridMap, bonus: i32) -> bool {
+    try_move_in(unit, dest, map, bonus, Weather::Clear)
+}
+
+/// Movement points one AP buys `unit`, with `bonus` extra.
+pub fn points_per_ap(unit: &Unit, map: &GridMap, bonus: i32) -> u32 {
+    (map.movement.points(&unit.current_stats) as i32 + bonus).max(0) as u32
+}
+
+/// Movement points `unit` can still spend this turn: those left from AP
+/// already spent moving, plus what its remaining AP buys.
+pub fn movement_budget(unit: &Unit, map: &GridMap, bonus: i32) -> u32 {
+    unit.movement_left + unit.action_points * points_per_ap(unit, map, bonus)
+}
+
+/// AP `unit` spends on a move costing `cost` movement points. Points left
+/// from earlier moves go first, then each AP buys [`points_per_ap`], so a
+/// unit can move, shoot and move again. `None` if its AP can't cover it.
+pub fn move_ap_cost(unit: &Unit, map: &GridMap, bonus: i32, cost: u32) -> Option<u32> {
+    let owed = cost.saturating_sub(unit.movement_left);
+    if owed == 0 {
+        return Some(0);
+    }
+    let per_ap = points_per_ap(unit, map, bonus);
+    if per_ap == 0 {
+        return None;
     }
+    let ap = owed.div_ceil(per_ap);
+    (ap <= unit.action_points).then_some(ap)
+}
 
-    if let TerrainType::Blocked = map.terrain_at(&dest) {
-        return false;
+/// Take what a move costing `cost` movement points costs `unit` from its
+/// AP and left-over points. The move must be affordable.
+pub(crate) fn pay_for_move(unit: &mut Unit, map: &GridMap, bonus: i32, cost: u32) {
+    let Some(ap) = move_ap_cost(unit, map, bonus, cost) else { return };
+    let bought = ap * points_per_ap(unit, map, bonus);
+    unit.action_points -= ap;
+    unit.movement_left = unit.movement_left + bought - cost;
+}
+
+/// Result of an A* search by [`find_path`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PathSearch {
+    /// Cost of the cheapest path, if the unit can pay for it.
+    pub cost: Option<u32>,
+    /// Cells taken off the open list, in the order they were expanded.
+    pub explored: Vec<Position>,
+    /// Cells along the cheapest path from the unit's cell to the
+    /// destination, both included; empty when there is none.
+    pub path: Vec<Position>,
+}
+
+/// A* search from `unit` to `dest` within its [`movement_budget`] with
+/// `bonus` extra points per AP, and step costs raised by `weather`. Leaves
+/// the unit where it is. Vehicles count the turns they make on the way.
+pub fn find_path(unit: &Unit, dest: &Position, map: &GridMap, bonus: i32, weather: Weather) -> PathSearch {
+    if !map.in_bounds(dest) {
+        return PathSearch::default();
     }
 
-    let max_mp = unit.current_stats.agility as u32 / 2;
+    if let TerrainType::Blocked = map.terrain_at(dest) {
+        return PathSearch::default();
+    }
 
-    // Heuristic using octile distance (diagonal cost = 2, straight = 1)
-    let heuristic = |a: &Position, b: &Position| -> u32 {
-        let dx = if a.x > b.x { a.x - b.x } else { b.x - a.x };
-        let dy = if a.y > b.y { a.y - b.y } else { b.y - a.y };
-        let diag = dx.min(dy);
-        let straight = dx.max(dy) - diag;
-        diag as u32 * 2 + straight as u32
-    };
+    let max_mp = movement_budget(unit, map, bonus);
+    if let Some(vehicle) = &unit.vehicle {
+        return crate::vehicle::find_path(unit, vehicle.turn_cost, dest, map, max_mp, weather);
+    }
+
+    // Heuristic using octile distance under the map's step costs
+    let heuristic = |a: &Position, b: &Position| -> u32 { map.movement.distance(a.x.abs_diff(b.x), a.y.abs_diff(b.y)) };
 
     #[derive(Eq, PartialEq)]
     struct Node {
@@ -99,35 +411,33 @@ pub fn try_move(unit: &mut Unit, dest: Position, map: &GridMap) -> bool {
 
     let mut open: BinaryHeap<Node> = BinaryHeap::new();
     let start = unit.grid_position.clone();
-    open.push(Node { score: heuristic(&start, &dest), cost: 0, pos: start.clone() });
+    open.push(Node { score: heuristic(&start, dest), cost: 0, pos: start.clone() });
 
     let mut best: HashMap<Position, u32> = HashMap::new();
     best.insert(start.clone(), 0);
+    let mut came_from: HashMap<Position, Position> = HashMap::new();
 
-    let dirs: &[(isize, isize)] = &[
-        (-1, 0),
-        (1, 0),
-        (0, -1),
-        (0, 1),
-        (-1, -1),
-        (-1, 1),
-        (1, -1),
-        (1, 1),
-    ];
-
-    let mut final_cost = None;
+    let mut search = PathSearch::default();
     while let Some(Node { score: _, cost, pos }) = open.pop() {
-        if let Some(best) = final_cost {
-            if cost > best {
-                continue;
-            }
+        if let Some(best) = search.cost
+            && cost > best
+        {
+            continue;
         }
-        if pos == dest {
-            final_cost = Some(cost);
+        search.explored.push(pos.clone());
+        if pos == *dest {
+            search.cost = Some(cost);
+            let mut cell = pos;
+            while let Some(prev) = came_from.get(&cell) {
+                let prev = prev.clone();
+                search.path.push(std::mem::replace(&mut cell, prev));
+            }
+            search.path.push(cell);
+            search.path.reverse();
             break;
         }
 
-        for (dx, dy) in dirs {
+        for (dx, dy) in DIRECTIONS {
             let nx = pos.x as isize + dx;
             let ny = pos.y as isize + dy;
             if nx < 0 || ny < 0 {
@@ -140,20 +450,9 @@ pub fn try_move(unit: &mut Unit, dest: Position, map: &GridMap) -> bool {
             if !map.in_bounds(&npos) {
                 continue;
             }
-            let diagonal = *dx != 0 && *dy != 0;
-            if diagonal {
-                let adj1 = Position { x: pos.x, y: ny as usize };
-                let adj2 = Position { x: nx as usize, y: pos.y };
-                if matches!(map.terrain_at(&adj1), TerrainType::Blocked)
-                    || matches!(map.terrain_at(&adj2), TerrainType::Blocked)
-                {
-                    continue;
-                }
-            }
-            let step = tile_cost(map.terrain_at(&npos), diagonal);
-            if step == u32::MAX {
+            let Some(step) = step_cost(map, &pos, &npos, unit.movement_type, weather) else {
                 continue;
-            }
+            };
             let next_cost = cost + step;
             if next_cost > max_mp {
                 continue;
@@ -161,26 +460,269 @@ pub fn try_move(unit: &mut Unit, dest: Position, map: &GridMap) -> bool {
             let entry = best.entry(npos.clone()).or_insert(u32::MAX);
             if next_cost < *entry {
                 *entry = next_cost;
+                came_from.insert(npos.clone(), pos.clone());
                 open.push(Node {
-                    score: next_cost + heuristic(&npos, &dest),
+                    score: next_cost + heuristic(&npos, dest),
                     cost: next_cost,
                     pos: npos,
                 });
             }
         }
     }
+    search
+}
+
+/// [`try_move_with_bonus`] with step costs raised by `weather`.
+pub fn try_move_in(unit: &mut Unit, dest: Position, map: &GridMap, bonus: i32, weather: Weather) -> bool {
+    move_along(unit, dest, map, bonus, weather).is_some()
+}
+
+/// [`try_move_in`], returning the cells walked, both ends included, or
+/// `None` when the unit can't reach `dest`.
+pub fn move_along(unit: &mut Unit, dest: Position, map: &GridMap, bonus: i32, weather: Weather) -> Option<Vec<Position>> {
+    let search = find_path(unit, &dest, map, bonus, weather);
+    pay_for_move(unit, map, bonus, search.cost?);
+    unit.grid_position = dest;
+    unit.face_along(&search.path);
+    if let TerrainType::Hazardous = map.terrain_at(&unit.grid_position) {
+        unit.health_points -= hazard_damage(unit);
+    }
+    Some(search.path)
+}
+
+/// A Dijkstra search over a map's cells that can stop once the cost of a
+/// given cell is final and pick up again later. Cells are kept in a bucket
+/// per cost: step costs are small integers, so that is much cheaper than a
+/// heap on large maps.
+#[derive(Debug, Clone, Default)]
+struct Dijkstra {
+    costs: Vec<Option<u32>>,
+    open: Vec<Vec<usize>>,
+    /// Cost of the next bucket to expand; every cost below it is final.
+    settled: u32,
+}
+
+impl Dijkstra {
+    /// Start a search from `source`, reusing the buffers of the last one.
+    fn restart(&mut self, map: &GridMap, source: &Position) {
+        self.costs.clear();
+        self.costs.resize(map.width * map.height, None);
+        self.open.iter_mut().for_each(Vec::clear);
+        self.settled = 0;
+        if map.in_bounds(source) {
+            self.costs[map.index(source)] = Some(0);
+            self.push(0, map.index(source));
+        }
+    }
+
+    fn push(&mut self, cost: u32, index: usize) {
+        let cost = cost as usize;
+        if self.open.len() <= cost {
+            self.open.resize_with(cost + 1, Vec::new);
+        }
+        self.open[cost].push(index);
+    }
 
-    if let Some(cost) = final_cost {
-        if cost <= max_mp {
-            unit.grid_position = dest;
-            if let TerrainType::Hazardous = map.terrain_at(&unit.grid_position) {
-                unit.health_points -= 1;
+    /// Expand cells in cost order, outward from the source or inward to it
+    /// when `inbound`, until the cost of `until` is final, or everything
+    /// within `limit` when `until` is `None`.
+    fn run(&mut self, map: &GridMap, movement: MovementType, weather: Weather, limit: u32, inbound: bool, until: Option<&Position>) {
+        let until = until.filter(|p| map.in_bounds(p)).map(|p| map.index(p));
+        while (self.settled as usize) < self.open.len() {
+            if let Some(index) = until
+                && self.costs[index].is_some_and(|c| c < self.settled)
+            {
+                return;
             }
-            return true;
+            let cost = self.settled;
+            // Steps cost at least one, so nothing lands back in this bucket.
+            let cells = std::mem::take(&mut self.open[cost as usize]);
+            for &index in &cells {
+                if self.costs[index].is_some_and(|c| c < cost) {
+                    continue;
+                }
+                let pos = Position { x: index % map.width, y: index / map.width };
+                for next in map.adjacent_cells(&pos) {
+                    let step = if inbound {
+                        step_cost(map, &next, &pos, movement, weather)
+                    } else {
+                        step_cost(map, &pos, &next, movement, weather)
+                    };
+                    let Some(step) = step else { continue };
+                    let next_cost = cost + step;
+                    let next_index = map.index(&next);
+                    if next_cost <= limit && self.costs[next_index].is_none_or(|c| next_cost < c) {
+                        self.costs[next_index] = Some(next_cost);
+                        self.push(next_cost, next_index);
+                    }
+                }
+            }
+            // Hand the emptied buffer back for later buckets.
+            self.open[cost as usize] = cells;
+            self.open[cost as usize].clear();
+            self.settled += 1;
         }
     }
+}
+
+/// Cheapest cost from `source` to every cell of `map`, or to `source` from
+/// every cell when `inbound`. Costs above `limit` are left unexplored.
+fn dijkstra(
+    map: &GridMap,
+    source: &Position,
+    movement: MovementType,
+    weather: Weather,
+    limit: u32,
+    inbound: bool,
+) -> Vec<Option<u32>> {
+    let mut search = Dijkstra::default();
+    search.restart(map, source);
+    search.run(map, movement, weather, limit, inbound, None);
+    search.costs
+}
 
-    false
+/// Cells `unit` can end a move on this turn, with `bonus` extra movement
+/// points per AP under `weather`. Used for movement-range highlighting.
+pub fn reachable_cells(unit: &Unit, map: &GridMap, bonus: i32, weather: Weather) -> Vec<Position> {
+    movement_bands(unit, map, bonus, weather).into_iter().map(|(cell, _)| cell).collect()
+}
+
+/// [`reachable_cells`] with the AP moving to each would cost, for
+/// previews banded by AP.
+pub fn movement_bands(unit: &Unit, map: &GridMap, bonus: i32, weather: Weather) -> Vec<(Position, u32)> {
+    let limit = movement_budget(unit, map, bonus);
+    let costs = match &unit.vehicle {
+        Some(vehicle) => crate::vehicle::cell_costs(unit, vehicle.turn_cost, map, limit, weather),
+        None => dijkstra(map, &unit.grid_position, unit.movement_type, weather, limit, false),
+    };
+    (0..map.height)
+        .flat_map(|y| (0..map.width).map(move |x| Position { x, y }))
+        .filter(|p| *p != unit.grid_position && !matches!(map.terrain_at(p), TerrainType::Blocked))
+        .filter_map(|p| {
+            let ap = move_ap_cost(unit, map, bonus, costs[map.index(&p)]?)?;
+            Some((p, ap))
+        })
+        .collect()
+}
+
+/// Cost of reaching one target cell from every cell of a map, built by a
+/// Dijkstra pass outward from the target. Units heading for the same
+/// target share one field instead of each running A*. A field from
+/// [`FlowFieldCache::towards`] only searches as far as it has been asked
+/// to; costs beyond that are not final.
+#[derive(Debug, Clone)]
+pub struct FlowField {
+    pub target: Position,
+    movement: MovementType,
+    weather: Weather,
+    search: Dijkstra,
+}
+
+impl FlowField {
+    pub fn new(map: &GridMap, target: &Position, movement: MovementType, weather: Weather) -> Self {
+        let mut field = Self::start(map, target, movement, weather, Dijkstra::default());
+        field.extend(map, None);
+        field
+    }
+
+    /// A field with nothing searched yet beyond the target, built in
+    /// `search`'s buffers.
+    fn start(map: &GridMap, target: &Position, movement: MovementType, weather: Weather, mut search: Dijkstra) -> Self {
+        search.restart(map, target);
+        Self { target: target.clone(), movement, weather, search }
+    }
+
+    /// Search until the cost from `from` is final, or the whole map.
+    fn extend(&mut self, map: &GridMap, from: Option<&Position>) {
+        self.search.run(map, self.movement, self.weather, u32::MAX, true, from);
+    }
+
+    /// Cost of moving from `pos` to the target, or `None` if it cannot be
+    /// reached.
+    pub fn cost(&self, map: &GridMap, pos: &Position) -> Option<u32> {
+        if map.in_bounds(pos) { self.search.costs[map.index(pos)] } else { None }
+    }
+
+    /// Follow the field downhill from `from` spending at most `budget`
+    /// movement points. Returns the last cell passed that a unit can stand
+    /// on, which is `from` itself if no step is affordable.
+    pub fn advance(&self, map: &GridMap, from: &Position, budget: u32) -> Position {
+        let mut pos = from.clone();
+        let mut stop = from.clone();
+        let mut spent = 0;
+        while pos != self.target {
+            let Some(here) = self.cost(map, &pos) else { break };
+            let next = map
+                .adjacent_cells(&pos)
+                .filter_map(|n| {
+                    let step = step_cost(map, &pos, &n, self.movement, self.weather)?;
+                    let remaining = self.cost(map, &n)?;
+                    (step + remaining == here).then_some((n, step))
+                })
+                .next();
+            let Some((next, step)) = next else { break };
+            if spent + step > budget {
+                break;
+            }
+            spent += step;
+            pos = next;
+            if !matches!(map.terrain_at(&pos), TerrainType::Blocked) {
+                stop = pos.clone();
+            }
+        }
+        stop
+    }
+}
+
+/// Flow fields built this round, keyed by target, movement type and
+/// weather. The whole cache is dropped when the map's revision changes;
+/// the encounter also clears it at the start of every round. Dropped
+/// fields' buffers are kept to build the next ones in.
+#[derive(Debug, Clone, Default)]
+pub struct FlowFieldCache {
+    revision: u64,
+    fields: HashMap<(Position, MovementType, Weather), FlowField>,
+    spare: Vec<Dijkstra>,
+}
+
+impl FlowFieldCache {
+    /// The field towards `target` over the whole map, building it if it is
+    /// not cached.
+    pub fn get(&mut self, map: &GridMap, target: &Position, movement: MovementType, weather: Weather) -> &FlowField {
+        self.field(map, target, movement, weather, None)
+    }
+
+    /// The field towards `target`, searched at least as far as `from`. The
+    /// AI moves with these: early in a battle, when the sides are far
+    /// apart, most of the map is never searched.
+    pub fn towards(&mut self, map: &GridMap, target: &Position, movement: MovementType, weather: Weather, from: &Position) -> &FlowField {
+        self.field(map, target, movement, weather, Some(from))
+    }
+
+    fn field(&mut self, map: &GridMap, target: &Position, movement: MovementType, weather: Weather, from: Option<&Position>) -> &FlowField {
+        if self.revision != map.revision {
+            self.clear();
+            self.revision = map.revision;
+        }
+        let field = self.fields.entry((target.clone(), movement, weather)).or_insert_with(|| {
+            FlowField::start(map, target, movement, weather, self.spare.pop().unwrap_or_default())
+        });
+        field.extend(map, from);
+        field
+    }
+
+    pub fn clear(&mut self) {
+        self.spare.extend(self.fields.drain().map(|(_, field)| field.search));
+    }
+
+    /// Number of fields currently cached.
+    pub fn len(&self) -> usize {
+        self.fields.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.fields.is_empty()
+    }
 }
 
 #[cfg(test)]
@@ -0,0 +1,172 @@
+use std::collections::HashSet;
+use std::io;
+
+use serde::{Deserialize, Serialize};
+
+use crate::campaign::Campaign;
+use crate::content::ContentDatabase;
+use crate::injuries::{Injury, InjuryKind};
+use crate::models::{Ability, Faction, Stats, Trait, Unit, UnitType};
+use crate::simulation::AutoResolveReport;
+
+/// The wound a hero carries home from a battle that would have killed a
+/// generic unit.
+pub const PLOT_ARMOR_INJURY: InjuryKind = InjuryKind::ChestWound;
+
+/// An ability in a hero's tree, learnable from `level` once the node
+/// before it in the branch has been learned.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AbilityNode {
+    pub level: u32,
+    pub ability: Ability,
+}
+
+/// One branch of a hero's ability tree; `name` is a locale key.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AbilityBranch {
+    pub name: String,
+    pub nodes: Vec<AbilityNode>,
+}
+
+/// A named character, unlike the generic templates built from a
+/// `UnitType`: fixed stats, a portrait, and an ability tree that earns one
+/// pick per level gained.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HeroTemplate {
+    pub name: String,
+    pub unit_type: UnitType,
+    pub faction: Faction,
+    /// Sprite id of the hero's portrait, shown in the unit info panel.
+    pub portrait: String,
+    #[serde(default)]
+    pub sprite_id: String,
+    pub base_stats: Stats,
+    #[serde(default)]
+    pub traits: Vec<Trait>,
+    /// Abilities the hero starts with, outside the tree.
+    #[serde(default)]
+    pub abilities: Vec<Ability>,
+    pub tree: Vec<AbilityBranch>,
+}
+
+impl HeroTemplate {
+    /// Every level must be reached in order within a branch, ability ids
+    /// must be unique across the tree, and ranks may only widen areas the
+    /// ability has.
+    pub(crate) fn validate(&self, id: &str) -> io::Result<()> {
+        let mut seen = HashSet::new();
+        for branch in &self.tree {
+            if branch.nodes.windows(2).any(|w| w[1].level < w[0].level) {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("hero '{}' branch '{}' is out of level order", id, branch.name),
+                ));
+            }
+            for node in &branch.nodes {
+                if !node.ability.ranks_valid() {
+                    return Err(io::Error::new(
+                        io::ErrorKind::InvalidData,
+                        format!("hero '{}' ability '{}' ranks up an area it lacks", id, node.ability.id),
+                    ));
+                }
+                if !seen.insert(node.ability.id.as_str()) {
+                    return Err(io::Error::new(
+                        io::ErrorKind::InvalidData,
+                        format!("hero '{}' repeats ability '{}'", id, node.ability.id),
+                    ));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// A fresh unit of this hero, with `id` as its unit and hero id.
+    pub fn spawn(&self, id: &str) -> Unit {
+        let mut unit = Unit::new(id, &self.name, self.unit_type.clone(), self.faction.clone());
+        unit.base_stats = self.base_stats.clone();
+        unit.abilities = self.abilities.clone();
+        unit.sprite_id = self.sprite_id.clone();
+        unit.portrait = Some(self.portrait.clone());
+        unit.hero = Some(id.to_string());
+        unit.traits = self.traits.clone();
+        unit.recalculate_stats();
+        unit.health_points = unit.current_stats.max_health;
+        unit.action_points = unit.current_stats.max_action;
+        unit
+    }
+
+    fn learned(&self, unit: &Unit) -> usize {
+        self.tree.iter().flat_map(|b| &b.nodes).filter(|n| unit.abilities.iter().any(|a| a.id == n.ability.id)).count()
+    }
+
+    /// Picks still to spend: one per level gained.
+    pub fn ability_points(&self, unit: &Unit) -> u32 {
+        unit.level.saturating_sub(1).saturating_sub(self.learned(unit) as u32)
+    }
+
+    /// The next unlearned node of each branch that `unit`'s level reaches.
+    pub fn unlockable(&self, unit: &Unit) -> Vec<&Ability> {
+        self.tree
+            .iter()
+            .filter_map(|b| b.nodes.iter().find(|n| !unit.abilities.iter().any(|a| a.id == n.ability.id)))
+            .filter(|n| n.level <= unit.level)
+            .map(|n| &n.ability)
+            .collect()
+    }
+
+    /// Spend a pick on `ability_id` from the tree.
+    pub fn learn(&self, unit: &mut Unit, ability_id: &str) -> Result<(), &'static str> {
+        if !self.tree.iter().flat_map(|b| &b.nodes).any(|n| n.ability.id == ability_id) {
+            return Err("unknown ability");
+        }
+        if unit.abilities.iter().any(|a| a.id == ability_id) {
+            return Err("ability already learned");
+        }
+        let ability = self.unlockable(unit).into_iter().find(|a| a.id == ability_id).ok_or("ability locked")?.clone();
+        if self.ability_points(unit) == 0 {
+            return Err("no ability points");
+        }
+        unit.abilities.push(ability);
+        Ok(())
+    }
+}
+
+impl Unit {
+    /// Whether the unit is a named character with plot armor.
+    pub fn is_hero(&self) -> bool {
+        self.hero.is_some()
+    }
+}
+
+impl ContentDatabase {
+    pub fn hero(&self, id: &str) -> Option<&HeroTemplate> {
+        self.heroes.get(id)
+    }
+
+    /// A fresh unit of the hero `id`, or `None` if there is no such hero.
+    pub fn spawn_hero(&self, id: &str) -> Option<Unit> {
+        self.hero(id).map(|h| h.spawn(id))
+    }
+}
+
+impl Campaign {
+    /// Have the hero `unit_id` learn `ability_id` from its tree.
+    pub fn learn_hero_ability(&mut self, db: &ContentDatabase, unit_id: &str, ability_id: &str) -> Result<(), &'static str> {
+        let unit = self.roster.iter_mut().find(|u| u.id == unit_id).ok_or("unknown unit")?;
+        let hero = unit.hero.as_deref().and_then(|id| db.hero(id)).ok_or("not a hero")?;
+        hero.learn(unit, ability_id)
+    }
+
+    /// Plot armor: heroes among an auto-resolved battle's casualties are
+    /// wounded with [`PLOT_ARMOR_INJURY`] and taken off the casualty list,
+    /// so they stay on the roster.
+    pub(crate) fn spare_heroes(&mut self, report: &AutoResolveReport) -> Vec<String> {
+        let mut casualties = report.casualties.clone();
+        for unit in self.roster.iter_mut().filter(|u| u.is_hero() && report.casualties.contains(&u.id)) {
+            unit.injuries.push(Injury::new(PLOT_ARMOR_INJURY));
+            unit.recalculate_stats();
+            casualties.retain(|id| *id != unit.id);
+        }
+        casualties
+    }
+}
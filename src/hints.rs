@@ -0,0 +1,121 @@
+use std::collections::{HashMap, HashSet};
+
+use serde::{Deserialize, Serialize};
+
+use crate::combat::{manhattan, CombatEncounter, Controller, EnvironmentalEffect};
+use crate::models::{AbilityType, Unit};
+use crate::planner::{ActionKind, ActionPlanner};
+use crate::registry::UnitId;
+use crate::ui::options::HintSettings;
+
+/// Something worth pointing out to the player about the turn in hand.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Hint {
+    /// The acting unit starts its turn standing in fire.
+    InFire,
+    /// An ability that was cooling down is ready, with an opponent in reach.
+    AbilityReady,
+    /// The unit has the action points left to shoot an opponent in range.
+    TargetInRange,
+}
+
+impl Hint {
+    /// Every hint, in the order they are offered when several apply.
+    pub const ALL: [Hint; 3] = [Hint::InFire, Hint::AbilityReady, Hint::TargetInRange];
+
+    /// Locale key of the hint's title; its text is under `<key>.body`.
+    pub fn key(&self) -> &'static str {
+        match self {
+            Hint::InFire => "hint.in_fire",
+            Hint::AbilityReady => "hint.ability_ready",
+            Hint::TargetInRange => "hint.target_in_range",
+        }
+    }
+
+    pub fn from_key(key: &str) -> Option<Hint> {
+        Hint::ALL.into_iter().find(|h| h.key() == key)
+    }
+
+    /// Whether the hint applies to `actor`'s turn.
+    fn applies(&self, encounter: &CombatEncounter, actor: UnitId) -> bool {
+        let unit = &encounter.units[actor];
+        match self {
+            Hint::InFire => encounter.environmental_effects.iter().any(|e| {
+                matches!(e, EnvironmentalEffect::FirePatch { grid_cells, .. } if grid_cells.contains(&unit.grid_position))
+            }),
+            Hint::AbilityReady => unit.abilities.iter().any(|a| {
+                let offensive = !matches!(a.ability_type, AbilityType::Healing | AbilityType::Buff | AbilityType::Summon);
+                offensive
+                    && a.cooldown > 0
+                    && a.current_cooldown == 0
+                    && unit.action_points >= a.action_point_cost
+                    && opponents(encounter, actor).any(|o| manhattan(&unit.grid_position, &o.grid_position) <= a.range)
+            }),
+            Hint::TargetInRange => ActionPlanner::new(encounter, &unit.id)
+                .is_some_and(|p| p.actions().iter().any(|a| a.kind == ActionKind::Attack)),
+        }
+    }
+}
+
+/// Living units on the other side from `actor`.
+fn opponents(encounter: &CombatEncounter, actor: UnitId) -> impl Iterator<Item = &Unit> {
+    let side = encounter.units.side(actor);
+    let units = &encounter.units;
+    units.ids().filter(move |&i| side.is_some_and(|s| units.side(i) == Some(s.opponent()))).map(|i| &units[i]).filter(|u| u.health_points > 0 && u.embarked_in.is_none())
+}
+
+/// Watches the battle for moments a hint would help. At most one hint is
+/// offered per turn, the same hint not again for
+/// [`HintSettings::cooldown_rounds`] rounds, and one the player dismissed
+/// never again.
+#[derive(Debug, Clone, Default)]
+pub struct HintEngine {
+    pub settings: HintSettings,
+    /// Round each hint was last offered in.
+    shown: HashMap<Hint, u32>,
+    dismissed: HashSet<Hint>,
+    /// The turn a hint was last looked for, as round and unit.
+    last_turn: Option<(u32, String)>,
+}
+
+impl HintEngine {
+    pub fn new(settings: HintSettings) -> Self {
+        Self { settings, ..Self::default() }
+    }
+
+    /// The hint to offer for the turn in hand, if any. Only turns a player
+    /// controls get hints, and each turn is looked at once.
+    pub fn observe(&mut self, encounter: &CombatEncounter) -> Option<Hint> {
+        if !self.settings.enabled {
+            return None;
+        }
+        let unit_id = encounter.turn_order.current_unit_id.as_deref()?;
+        let actor = encounter.units.id(unit_id)?;
+        if !matches!(encounter.controller_of(unit_id), Some(Controller::Player(_))) {
+            return None;
+        }
+        let round = encounter.turn_order.round_number;
+        let turn = (round, unit_id.to_string());
+        if self.last_turn.as_ref() == Some(&turn) {
+            return None;
+        }
+        let cooldown = self.settings.cooldown_rounds;
+        let hint = Hint::ALL.into_iter().find(|h| {
+            !self.dismissed.contains(h)
+                && self.shown.get(h).is_none_or(|&at| round >= at + cooldown)
+                && h.applies(encounter, actor)
+        })?;
+        self.last_turn = Some(turn);
+        self.shown.insert(hint, round);
+        Some(hint)
+    }
+
+    /// Stop offering `hint`.
+    pub fn dismiss(&mut self, hint: Hint) {
+        self.dismissed.insert(hint);
+    }
+
+    pub fn is_dismissed(&self, hint: Hint) -> bool {
+        self.dismissed.contains(&hint)
+    }
+}
@@ -0,0 +1,184 @@
+use serde::{Deserialize, Serialize};
+
+use crate::combat::{manhattan, AttackModifiers, CombatEncounter, CombatError, CombatEvent};
+use crate::models::{Position, Unit};
+
+/// What sets a held action off. Only the holding unit's opponents do.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum HoldTrigger {
+    /// An opponent steps into the cone reaching `radius` cells from the
+    /// unit towards `toward`, up to 45 degrees either side.
+    EntersCone { toward: Position, radius: u32 },
+    /// An opponent within reach of the response uses an ability.
+    UsesAbility,
+}
+
+/// What a unit does when its hold is set off, always at the unit that set
+/// it off.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum HeldResponse {
+    /// Fire the equipped weapon.
+    Attack,
+    /// Use the unit's ability at this index.
+    UseAbility { ability_index: usize },
+}
+
+impl HeldResponse {
+    /// AP and range of the response for `unit`, if it has the weapon or
+    /// ability.
+    fn cost_and_range(&self, unit: &Unit) -> Option<(u32, u32)> {
+        match self {
+            HeldResponse::Attack => unit.equipment.weapon.as_ref().map(|w| (w.action_point_cost, w.range)),
+            HeldResponse::UseAbility { ability_index } => {
+                unit.abilities.get(*ability_index).map(|a| (a.action_point_cost, a.range))
+            }
+        }
+    }
+}
+
+/// An action a unit holds back at the end of its turn to take out of turn
+/// once `trigger` happens. It lasts until then or the unit's next turn.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HeldAction {
+    pub trigger: HoldTrigger,
+    pub response: HeldResponse,
+}
+
+/// Whether `cell` lies in the cone from `origin` towards `toward`, no more
+/// than `radius` cells out and 45 degrees either side.
+pub fn in_cone(origin: &Position, toward: &Position, radius: u32, cell: &Position) -> bool {
+    let offset = |p: &Position| (p.x as i64 - origin.x as i64, p.y as i64 - origin.y as i64);
+    let (aim, to) = (offset(toward), offset(cell));
+    let distance = manhattan(origin, cell);
+    if aim == (0, 0) || distance == 0 || distance > radius {
+        return false;
+    }
+    let dot = aim.0 * to.0 + aim.1 * to.1;
+    // cos² of the angle between them is at least a half.
+    dot > 0 && 2 * dot * dot >= (aim.0 * aim.0 + aim.1 * aim.1) * (to.0 * to.0 + to.1 * to.1)
+}
+
+impl CombatEncounter {
+    /// Hold `action` back, spending the unit's remaining AP, which must
+    /// cover it.
+    pub fn hold_action(&mut self, unit_id: &str, action: HeldAction) -> Result<(), CombatError> {
+        let unit = self.units.by_name_mut(unit_id).ok_or(CombatError::UnknownUnit)?;
+        let (cost, _) = action.response.cost_and_range(unit).ok_or(match action.response {
+            HeldResponse::Attack => CombatError::NoWeapon,
+            HeldResponse::UseAbility { .. } => CombatError::InvalidAbility,
+        })?;
+        if unit.action_points == 0 || unit.action_points < cost {
+            return Err(CombatError::InsufficientAp);
+        }
+        unit.action_points = 0;
+        unit.held_action = Some(action);
+        Ok(())
+    }
+
+    /// Put the current unit's turn off until the next unit has had its
+    /// own. Only a unit yet to act can wait, and only for a unit still to
+    /// act this round.
+    pub fn delay_turn(&mut self) -> Result<(), CombatError> {
+        let id = self.turn_order.current_unit_id.clone().ok_or(CombatError::WrongPhase)?;
+        let unit = self.unit_by_id(&id).ok_or(CombatError::UnknownUnit)?;
+        if self.moved_this_turn.contains(&id) || unit.action_points < unit.current_stats.max_action {
+            return Err(CombatError::AlreadyActed);
+        }
+        if !self.turn_order.delay(1) {
+            return Err(CombatError::NobodyToWaitFor);
+        }
+        Ok(())
+    }
+
+    /// Set off the holds `mover` walks into along `path`, which starts at
+    /// `from`. A holding opponent acts with the mover standing on the cell
+    /// that took it into the cone; the mover carries on to the end of the
+    /// path if it survives. Returns whether anyone acted.
+    pub(crate) fn resolve_move_holds(&mut self, mover: &str, from: &Position, path: &[Position]) -> bool {
+        let Some(end) = self.unit_by_id(mover).map(|u| u.grid_position.clone()) else { return false };
+        let mut acted = false;
+        let mut previous = from;
+        for cell in path {
+            let holders = self.holders(mover, |holder, trigger| match trigger {
+                HoldTrigger::EntersCone { toward, radius } => {
+                    let origin = &holder.grid_position;
+                    in_cone(origin, toward, *radius, cell) && !in_cone(origin, toward, *radius, previous)
+                }
+                HoldTrigger::UsesAbility => false,
+            });
+            for holder in holders {
+                if let Some(unit) = self.unit_by_id_mut(mover) {
+                    unit.grid_position = cell.clone();
+                }
+                acted |= self.set_off(&holder, mover);
+                if self.unit_by_id(mover).is_none_or(|u| u.health_points <= 0) {
+                    return acted;
+                }
+            }
+            previous = cell;
+        }
+        if let Some(unit) = self.unit_by_id_mut(mover) {
+            unit.grid_position = end;
+        }
+        acted
+    }
+
+    /// Set off the holds of opponents with `user`, which has just used an
+    /// ability, in reach. Returns whether anyone acted.
+    pub(crate) fn resolve_ability_holds(&mut self, user: &str) -> bool {
+        let Some(position) = self.unit_by_id(user).map(|u| u.grid_position.clone()) else { return false };
+        let holders = self.holders(user, |holder, trigger| {
+            let held = holder.held_action.as_ref().and_then(|h| h.response.cost_and_range(holder));
+            *trigger == HoldTrigger::UsesAbility
+                && held.is_some_and(|(_, range)| manhattan(&holder.grid_position, &position) <= range)
+        });
+        let mut acted = false;
+        for holder in holders {
+            acted |= self.set_off(&holder, user);
+        }
+        acted
+    }
+
+    /// Living opponents of `actor` whose hold `sets_off` says it triggers.
+    fn holders(&self, actor: &str, sets_off: impl Fn(&Unit, &HoldTrigger) -> bool) -> Vec<String> {
+        let Some(side) = self.units.id(actor).and_then(|id| self.units.side(id)) else { return Vec::new() };
+        self.units
+            .ids()
+            .filter(|&id| self.units.side(id) == Some(side.opponent()))
+            .map(|id| &self.units[id])
+            .filter(|u| u.health_points > 0 && u.held_action.as_ref().is_some_and(|h| sets_off(u, &h.trigger)))
+            .map(|u| u.id.clone())
+            .collect()
+    }
+
+    /// Carry out `holder`'s held action at `target`. The hold is used up
+    /// if the action goes through and kept for a better moment if not, say
+    /// when the target is out of sight.
+    fn set_off(&mut self, holder: &str, target: &str) -> bool {
+        let Some(unit) = self.unit_by_id_mut(holder) else { return false };
+        let Some(held) = unit.held_action.clone() else { return false };
+        let Some((cost, _)) = held.response.cost_and_range(unit) else { return false };
+        // Paid for when the hold was taken.
+        unit.action_points = cost;
+        let events_before = self.events.len();
+        let (unit_id, target_id) = (holder.to_string(), target.to_string());
+        self.events.push(CombatEvent::HeldActionTriggered { unit_id, target_id });
+        let result = match held.response {
+            HeldResponse::Attack => {
+                let roll = self.rng.roll_d100();
+                self.attack_unit(holder, target, roll, AttackModifiers::default()).map(|_| ())
+            }
+            HeldResponse::UseAbility { ability_index } => self.execute_ability(holder, ability_index, &[target.to_string()]),
+        };
+        if result.is_err() {
+            self.events.truncate(events_before);
+        }
+        if let Some(unit) = self.unit_by_id_mut(holder) {
+            unit.action_points = 0;
+            if result.is_ok() {
+                unit.held_action = None;
+            }
+        }
+        result.is_ok()
+    }
+}
@@ -0,0 +1,97 @@
+use serde::{Deserialize, Serialize};
+
+use crate::combat::{CombatEncounter, CombatError, CombatEvent};
+use crate::damage::mitigate;
+use crate::grenade::{distance, throw_arc};
+use crate::models::{Position, Stats, Unit, Weapon};
+
+/// Closest any indirect weapon can fire, in cells: the shell can't come
+/// down on a neighbouring cell.
+pub const MIN_INDIRECT_RANGE: u32 = 2;
+/// Hit chance lost per cell to the target.
+pub const SCATTER_PER_CELL: i32 = 5;
+
+/// Makes a weapon lob its shots: mortars and grenade launchers fire over
+/// walls at a cell rather than at a unit in sight, and a miss scatters
+/// the shell. Their range is the weapon's.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IndirectFire {
+    /// Closest a target cell can be, in cells; never under
+    /// [`MIN_INDIRECT_RANGE`].
+    pub min_range: u32,
+    /// Blast radius around the impact cell.
+    pub aoe_radius: u32,
+}
+
+impl IndirectFire {
+    pub fn min_range(&self) -> u32 {
+        self.min_range.max(MIN_INDIRECT_RANGE)
+    }
+}
+
+/// Percentile chance a shell lands on the aimed cell: the weapon's
+/// accuracy and the firer's, less [`SCATTER_PER_CELL`] per cell.
+pub fn indirect_hit_chance(stats: &Stats, weapon: &Weapon, distance: u32) -> i32 {
+    (weapon.accuracy * 100.0) as i32 + stats.accuracy - distance as i32 * SCATTER_PER_CELL
+}
+
+/// The cells a shell from `from` passes over on its way to `to`, as
+/// [`throw_arc`], each with its height in cells. The arc is a parabola
+/// peaking halfway at half the distance, so longer shots climb higher.
+pub fn trajectory(from: &Position, to: &Position) -> Vec<(Position, u32)> {
+    let steps = distance(from, to) as f32;
+    throw_arc(from, to)
+        .into_iter()
+        .enumerate()
+        .map(|(i, cell)| {
+            let t = (i + 1) as f32 / steps;
+            (cell, (2.0 * steps * t * (1.0 - t)).round() as u32)
+        })
+        .collect()
+}
+
+/// Where a shell came down and what its blast covered.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Bombardment {
+    pub impact: Position,
+    pub scattered: bool,
+    pub cells: Vec<Position>,
+}
+
+impl CombatEncounter {
+    /// Fire the unit's indirect weapon at `target`, out of sight or not,
+    /// for the weapon's AP. A missed roll scatters the shell as a grenade
+    /// scatters. The blast hits every unit on its cells, friend or foe,
+    /// for the weapon's damage less their soak, and knocks down fragile
+    /// objects and structures.
+    pub fn bombard(&mut self, attacker_id: &str, target: &Position) -> Result<Bombardment, CombatError> {
+        let attacker = self.unit_by_id(attacker_id).ok_or(CombatError::UnknownUnit)?;
+        let weapon = attacker.equipment.weapon.clone().ok_or(CombatError::NoWeapon)?;
+        let indirect = weapon.indirect.ok_or(CombatError::NotIndirect)?;
+        if !self.battlefield.in_bounds(target) {
+            return Err(CombatError::InvalidTarget);
+        }
+        let dist = distance(&attacker.grid_position, target);
+        if dist < indirect.min_range() {
+            return Err(CombatError::TargetTooClose);
+        }
+        if dist > weapon.range {
+            return Err(CombatError::OutOfRange);
+        }
+        if attacker.action_points < weapon.action_point_cost {
+            return Err(CombatError::InsufficientAp);
+        }
+        let (from, chance) = (attacker.grid_position.clone(), indirect_hit_chance(&attacker.current_stats, &weapon, dist));
+
+        let impact = self.land(target, chance);
+        let attacker = self.unit_by_id_mut(attacker_id).ok_or(CombatError::UnknownUnit)?;
+        attacker.action_points -= weapon.action_point_cost;
+        attacker.face(target);
+
+        let (shooter, center) = (attacker_id.to_string(), impact.clone());
+        let blast = |cells: &[Position]| CombatEvent::ShellImpact { attacker_id: shooter, center, cells: cells.to_vec() };
+        let damage = |unit: &Unit| mitigate(weapon.damage - unit.hull_armor(&from, &weapon), weapon.damage_type, unit);
+        let cells = self.detonate(attacker_id, &impact, indirect.aoe_radius, weapon.damage, damage, blast);
+        Ok(Bombardment { scattered: impact != *target, impact, cells })
+    }
+}
@@ -0,0 +1,176 @@
+use serde::{Deserialize, Serialize};
+
+use crate::base::Facility;
+use crate::campaign::Campaign;
+use crate::content::{ContentDatabase, Item};
+use crate::models::{Accessory, Unit};
+use crate::modifiers::{Modifier, ModifierSource, Stat};
+use crate::rng::GameRng;
+
+/// Requisition for the first medbay upgrade; each further level costs as
+/// much again.
+pub const MEDBAY_UPGRADE_COST: u32 = 100;
+/// Highest medbay level.
+pub const MAX_MEDBAY_LEVEL: u32 = 2;
+
+/// A wound a downed unit can carry out of battle.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum InjuryKind {
+    /// -10 accuracy.
+    Concussion,
+    /// -2 strength.
+    BrokenArm,
+    /// -1 movement point.
+    LegWound,
+    /// -2 max health.
+    ChestWound,
+    /// -10 accuracy, for good.
+    LostEye,
+}
+
+impl InjuryKind {
+    /// The injury rolled on a d100 for a downed unit; low rolls walk it off.
+    pub fn from_roll(roll: u8) -> Option<Self> {
+        match roll {
+            0..=30 => None,
+            31..=50 => Some(InjuryKind::Concussion),
+            51..=70 => Some(InjuryKind::BrokenArm),
+            71..=85 => Some(InjuryKind::LegWound),
+            86..=95 => Some(InjuryKind::ChestWound),
+            _ => Some(InjuryKind::LostEye),
+        }
+    }
+
+    pub fn modifier(&self) -> Modifier {
+        let (stat, amount) = match self {
+            InjuryKind::Concussion | InjuryKind::LostEye => (Stat::Accuracy, -10),
+            InjuryKind::BrokenArm => (Stat::Strength, -2),
+            InjuryKind::LegWound => (Stat::Movement, -1),
+            InjuryKind::ChestWound => (Stat::MaxHealth, -2),
+        };
+        Modifier::new(ModifierSource::Injury, stat, amount)
+    }
+
+    /// Missions the unit sits out while it recovers.
+    pub fn recovery_missions(&self) -> u32 {
+        match self {
+            InjuryKind::Concussion => 1,
+            InjuryKind::BrokenArm | InjuryKind::LegWound => 2,
+            InjuryKind::ChestWound | InjuryKind::LostEye => 3,
+        }
+    }
+
+    /// Whether the penalty stays once the unit has recovered.
+    pub fn is_lasting(&self) -> bool {
+        matches!(self, InjuryKind::LostEye)
+    }
+
+    /// Localization key for the injury's display name.
+    pub fn label_key(&self) -> &'static str {
+        match self {
+            InjuryKind::Concussion => "injury.concussion",
+            InjuryKind::BrokenArm => "injury.broken_arm",
+            InjuryKind::LegWound => "injury.leg_wound",
+            InjuryKind::ChestWound => "injury.chest_wound",
+            InjuryKind::LostEye => "injury.lost_eye",
+        }
+    }
+}
+
+/// An injury on a roster unit. Its penalty applies when stats are
+/// recalculated, like a trait.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Injury {
+    pub kind: InjuryKind,
+    /// Missions left before the unit can deploy again.
+    pub missions_remaining: u32,
+}
+
+impl Injury {
+    pub fn new(kind: InjuryKind) -> Self {
+        Self { kind, missions_remaining: kind.recovery_missions() }
+    }
+}
+
+impl Unit {
+    /// Whether the unit is still in the medbay and can't deploy.
+    pub fn is_recovering(&self) -> bool {
+        self.injuries.iter().any(|i| i.missions_remaining > 0)
+    }
+
+    /// Missions until every injury has healed.
+    pub fn recovery_missions(&self) -> u32 {
+        self.injuries.iter().map(|i| i.missions_remaining).max().unwrap_or(0)
+    }
+}
+
+impl Campaign {
+    /// Roll an injury for each roster unit in `downed` after a battle.
+    /// Returns the injuries dealt.
+    pub fn roll_injuries(&mut self, downed: &[String], rng: &mut GameRng) -> Vec<(String, InjuryKind)> {
+        let mut dealt = Vec::new();
+        for unit in self.roster.iter_mut().filter(|u| downed.contains(&u.id)) {
+            let Some(kind) = InjuryKind::from_roll(rng.roll_d100()) else { continue };
+            unit.injuries.push(Injury::new(kind));
+            unit.recalculate_stats();
+            dealt.push((unit.id.clone(), kind));
+        }
+        dealt
+    }
+
+    /// Count down recovery after a mission: one mission, plus one per
+    /// medbay level. Healed injuries go away unless they are lasting.
+    pub fn advance_recovery(&mut self) {
+        let missions = 1 + self.medbay_level;
+        for unit in &mut self.roster {
+            for injury in &mut unit.injuries {
+                injury.missions_remaining = injury.missions_remaining.saturating_sub(missions);
+            }
+            unit.injuries.retain(|i| i.missions_remaining > 0 || i.kind.is_lasting());
+            unit.recalculate_stats();
+        }
+    }
+
+    /// Roster units carrying injuries, recovering or lasting.
+    pub fn injured(&self) -> impl Iterator<Item = &Unit> {
+        self.roster.iter().filter(|u| !u.injuries.is_empty())
+    }
+
+    /// The first medkit or stimpack in the inventory.
+    pub fn medical_supply<'a>(&'a self, db: &ContentDatabase) -> Option<&'a str> {
+        self.inventory.iter().map(String::as_str).find(|id| {
+            db.get(id).is_some_and(|e| {
+                matches!(e.item, Item::Accessory(Accessory::Medkit { .. } | Accessory::Stimpack { .. }))
+            })
+        })
+    }
+
+    /// Use a medical supply from the inventory on a recovering unit,
+    /// taking a mission off each of its injuries.
+    pub fn treat_injuries(&mut self, db: &ContentDatabase, unit_id: &str) -> Result<(), &'static str> {
+        let unit = self.roster.iter().find(|u| u.id == unit_id).ok_or("unknown unit")?;
+        if !unit.is_recovering() {
+            return Err("unit is not recovering");
+        }
+        let supply = self.medical_supply(db).ok_or("no medical supplies")?.to_string();
+        let slot = self.inventory.iter().position(|i| *i == supply).ok_or("no medical supplies")?;
+        self.inventory.remove(slot);
+        let unit = self.roster.iter_mut().find(|u| u.id == unit_id).ok_or("unknown unit")?;
+        for injury in &mut unit.injuries {
+            injury.missions_remaining = injury.missions_remaining.saturating_sub(1);
+        }
+        unit.injuries.retain(|i| i.missions_remaining > 0 || i.kind.is_lasting());
+        unit.recalculate_stats();
+        Ok(())
+    }
+
+    /// Requisition needed for the next medbay level, or `None` at the top.
+    pub fn medbay_upgrade_cost(&self) -> Option<u32> {
+        self.facility_upgrade_cost(Facility::Medbay)
+    }
+
+    /// Spend requisition on the next medbay level.
+    pub fn upgrade_medbay(&mut self) -> Result<(), &'static str> {
+        self.upgrade_facility(Facility::Medbay)
+    }
+}
@@ -1,26 +1,195 @@
-use winit::event::{Event, WindowEvent, DeviceEvent, ElementState, MouseButton, TouchPhase};
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+use winit::event::{Event, WindowEvent, DeviceEvent, ElementState, MouseButton, Touch, TouchPhase};
 use winit::keyboard::{KeyCode, PhysicalKey};
 
 /// High level actions used by the game.
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum GameAction {
     SelectUp,
     SelectDown,
     Activate,
 }
 
-/// Maps winit events to high level [`GameAction`]s.
+/// Keyboard layout: key names (winit's `KeyCode` names, e.g. `"ArrowUp"`)
+/// mapped to actions.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct KeyBindings {
+    pub keys: BTreeMap<String, GameAction>,
+}
+
+impl Default for KeyBindings {
+    fn default() -> Self {
+        let keys = [("ArrowUp", GameAction::SelectUp), ("ArrowDown", GameAction::SelectDown), ("Enter", GameAction::Activate)];
+        Self { keys: keys.into_iter().map(|(k, a)| (k.to_string(), a)).collect() }
+    }
+}
+
+impl KeyBindings {
+    /// Bind `key` to `action`, replacing any existing binding for the key.
+    pub fn bind(&mut self, key: &str, action: GameAction) {
+        self.keys.insert(key.to_string(), action);
+    }
+
+    pub fn action_for(&self, key: KeyCode) -> Option<GameAction> {
+        self.keys.get(&format!("{key:?}")).cloned()
+    }
+}
+
+/// Pixels a finger may drift and still count as a tap or long press.
+pub const TAP_SLOP: f32 = 12.0;
+/// Seconds a still finger must be held for a long press.
+pub const LONG_PRESS_SECONDS: f32 = 0.5;
+
+/// A touch gesture, positioned in physical pixels.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Gesture {
+    /// A finger lifted quickly without moving.
+    Tap { x: f32, y: f32 },
+    /// A finger held still for [`LONG_PRESS_SECONDS`].
+    LongPress { x: f32, y: f32 },
+    /// One finger dragged by this much since the last pan.
+    Pan { dx: f32, dy: f32 },
+    /// Two fingers spread apart (`scale` above 1) or pinched together
+    /// around their midpoint.
+    Pinch { x: f32, y: f32, scale: f32 },
+    /// The last finger lifted.
+    Released,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct TouchPoint {
+    start: (f32, f32),
+    last: (f32, f32),
+    held: f32,
+    /// Drifted past [`TAP_SLOP`], so it is a drag rather than a tap.
+    moved: bool,
+    long_pressed: bool,
+}
+
+fn distance(a: (f32, f32), b: (f32, f32)) -> f32 {
+    (a.0 - b.0).hypot(a.1 - b.1)
+}
+
+/// Turns raw touches into [`Gesture`]s: a tap, long press or pan with one
+/// finger and a pinch with two. Once a second finger lands nothing counts
+/// as a tap until every finger is lifted.
+#[derive(Debug, Clone, Default)]
+pub struct TouchGestures {
+    touches: BTreeMap<u64, TouchPoint>,
+    multi: bool,
+}
+
+impl TouchGestures {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Feed one touch event, returning the gestures it completes.
+    pub fn touch(&mut self, touch: &Touch) -> Vec<Gesture> {
+        let at = (touch.location.x as f32, touch.location.y as f32);
+        let mut gestures = Vec::new();
+        match touch.phase {
+            TouchPhase::Started => {
+                self.touches.insert(touch.id, TouchPoint { start: at, last: at, held: 0.0, moved: false, long_pressed: false });
+                self.multi |= self.touches.len() > 1;
+            }
+            TouchPhase::Moved => {
+                let before: Vec<(f32, f32)> = self.touches.values().take(2).map(|t| t.last).collect();
+                let Some(point) = self.touches.get_mut(&touch.id) else { return gestures };
+                let delta = (at.0 - point.last.0, at.1 - point.last.1);
+                point.last = at;
+                point.moved |= distance(point.start, at) > TAP_SLOP;
+                let moved = point.moved;
+                let after: Vec<(f32, f32)> = self.touches.values().take(2).map(|t| t.last).collect();
+                if let ([a0, b0], [a1, b1]) = (before.as_slice(), after.as_slice()) {
+                    let old = distance(*a0, *b0);
+                    if old > 0.0 {
+                        let (x, y) = ((a1.0 + b1.0) / 2.0, (a1.1 + b1.1) / 2.0);
+                        gestures.push(Gesture::Pinch { x, y, scale: distance(*a1, *b1) / old });
+                    }
+                } else if moved && !self.multi {
+                    gestures.push(Gesture::Pan { dx: delta.0, dy: delta.1 });
+                }
+            }
+            TouchPhase::Ended | TouchPhase::Cancelled => {
+                let Some(point) = self.touches.remove(&touch.id) else { return gestures };
+                let tapped = touch.phase == TouchPhase::Ended && !point.moved && !point.long_pressed && !self.multi;
+                if tapped {
+                    gestures.push(Gesture::Tap { x: at.0, y: at.1 });
+                }
+                if self.touches.is_empty() {
+                    self.multi = false;
+                    gestures.push(Gesture::Released);
+                }
+            }
+        }
+        gestures
+    }
+
+    /// Advance hold timers by `dt` seconds, returning a long press once a
+    /// lone finger has been held still long enough.
+    pub fn update(&mut self, dt: f32) -> Option<Gesture> {
+        if self.multi || self.touches.len() != 1 {
+            return None;
+        }
+        let point = self.touches.values_mut().next()?;
+        if point.moved || point.long_pressed {
+            return None;
+        }
+        point.held += dt;
+        if point.held < LONG_PRESS_SECONDS {
+            return None;
+        }
+        point.long_pressed = true;
+        Some(Gesture::LongPress { x: point.last.0, y: point.last.1 })
+    }
+}
+
+/// Maps winit events to high level [`GameAction`]s, and touches to
+/// [`Gesture`]s collected for [`InputHandler::take_gestures`].
 /// In tests the handler records all actions that were produced.
 pub struct InputHandler {
     pub action_log: Vec<GameAction>,
+    pub bindings: KeyBindings,
+    pub touch: TouchGestures,
+    gestures: Vec<Gesture>,
+    /// Set by the first touch event, after which the UI should offer
+    /// touch-sized targets.
+    pub touch_detected: bool,
+}
+
+impl Default for InputHandler {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl InputHandler {
     pub fn new() -> Self {
-        Self { action_log: Vec::new() }
+        Self {
+            action_log: Vec::new(),
+            bindings: KeyBindings::default(),
+            touch: TouchGestures::new(),
+            gestures: Vec::new(),
+            touch_detected: false,
+        }
+    }
+
+    /// Advance long-press timing by `dt` seconds.
+    pub fn update(&mut self, dt: f32) {
+        self.gestures.extend(self.touch.update(dt));
+    }
+
+    /// Gestures recognized since the last call, oldest first.
+    pub fn take_gestures(&mut self) -> Vec<Gesture> {
+        std::mem::take(&mut self.gestures)
     }
 
     /// Process an event, returning an action if one was recognized.
+    /// Touches produce gestures instead, since where a tap lands decides
+    /// what it does.
     pub fn process_event<T>(&mut self, event: &Event<T>) -> Option<GameAction> {
         use GameAction::*;
         let action = match event {
@@ -28,14 +197,15 @@ impl InputHandler {
                 if *button == MouseButton::Left { Some(Activate) } else { None }
             }
             Event::WindowEvent { event: WindowEvent::Touch(touch), .. } => {
-                if touch.phase == TouchPhase::Started { Some(Activate) } else { None }
+                self.touch_detected = true;
+                let gestures = self.touch.touch(touch);
+                self.gestures.extend(gestures);
+                None
             }
             Event::DeviceEvent { event: DeviceEvent::Key(raw), .. } => {
                 if raw.state == ElementState::Pressed {
                     match raw.physical_key {
-                        PhysicalKey::Code(KeyCode::ArrowUp) => Some(SelectUp),
-                        PhysicalKey::Code(KeyCode::ArrowDown) => Some(SelectDown),
-                        PhysicalKey::Code(KeyCode::Enter) => Some(Activate),
+                        PhysicalKey::Code(code) => self.bindings.action_for(code),
                         _ => None,
                     }
                 } else {
@@ -49,12 +219,12 @@ impl InputHandler {
     }
 
     /// Process an event and play a menu navigation sound when an action occurs.
-    pub fn process_event_with_audio<T>(&mut self, event: &Event<T>, mut audio: Option<&mut crate::audio::AudioSystem>) -> Option<GameAction> {
+    pub fn process_event_with_audio<T>(&mut self, event: &Event<T>, audio: Option<&mut crate::audio::AudioSystem>) -> Option<GameAction> {
         let action = self.process_event(event);
-        if action.is_some() {
-            if let Some(sys) = audio.as_deref_mut() {
-                sys.play("button_click");
-            }
+        if action.is_some()
+            && let Some(sys) = audio
+        {
+            sys.play("button_click");
         }
         action
     }
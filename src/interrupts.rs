@@ -0,0 +1,131 @@
+use serde::{Deserialize, Serialize};
+
+use crate::actions::PresentationStep;
+use crate::combat::{manhattan, CombatEncounter, CombatError};
+use crate::grid::{Region, TerrainType};
+use crate::models::Position;
+use crate::registry::Side;
+
+/// Seconds the camera takes to pan to a scripted reveal.
+pub const CAMERA_PAN_SECONDS: f32 = 0.8;
+
+/// When a [`ScriptedInterrupt`] fires.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Trigger {
+    /// A living unit stands in `region`: the unit with `unit_id`, or any
+    /// player unit when it is `None`.
+    EntersRegion {
+        region: Region,
+        #[serde(default)]
+        unit_id: Option<String>,
+    },
+    /// The unit's health drops below `hp`.
+    HealthBelow { unit_id: String, hp: i32 },
+    /// The battle reaches round `round`.
+    Round { round: u32 },
+}
+
+impl Trigger {
+    pub fn is_met(&self, encounter: &CombatEncounter) -> bool {
+        match self {
+            Trigger::EntersRegion { region, unit_id: Some(id) } => {
+                encounter.unit_by_id(id).is_some_and(|u| u.health_points > 0 && region.contains(&u.grid_position))
+            }
+            Trigger::EntersRegion { region, unit_id: None } => {
+                encounter.units.players().any(|u| u.health_points > 0 && region.contains(&u.grid_position))
+            }
+            Trigger::HealthBelow { unit_id, hp } => encounter.unit_by_id(unit_id).is_some_and(|u| u.health_points < *hp),
+            Trigger::Round { round } => encounter.turn_order.round_number >= *round,
+        }
+    }
+}
+
+/// Something shown or done while the turn is held for a scripted moment.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum InterruptEvent {
+    /// A line of dialogue; `speaker` and `text` are locale keys. Holds the
+    /// turn until the player dismisses it.
+    Dialogue { speaker: String, text: String },
+    /// Pan the camera to centre on `target`, e.g. to reveal an ambush.
+    CameraPan { target: Position },
+    /// A unit made from the template `unit_id` arrives at `position`, as
+    /// soon as the interrupt fires; once it has, `unit_id` is the id the
+    /// new unit goes by. See [`CombatEncounter::spawn_reinforcement`].
+    Reinforcement { unit_id: String, position: Position, enemy: bool },
+}
+
+/// Events played mid-turn the first time `trigger` is met, e.g. orks
+/// bursting out of a building when the squad reaches its door.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ScriptedInterrupt {
+    pub trigger: Trigger,
+    pub events: Vec<InterruptEvent>,
+    /// Set once played, so each interrupt fires only once per battle.
+    #[serde(default)]
+    pub fired: bool,
+}
+
+impl CombatEncounter {
+    /// Hold the turn to play `events` once the steps already queued have
+    /// been shown; queued actions resume afterwards. Reinforcements arrive
+    /// there and then, so saves and replays have them before they're shown,
+    /// and ones that can't arrive aren't shown at all.
+    pub fn interrupt(&mut self, mut events: Vec<InterruptEvent>) {
+        events.retain_mut(|event| match event {
+            InterruptEvent::Reinforcement { unit_id, position, enemy } => match self.spawn_reinforcement(unit_id, position, *enemy) {
+                Ok(id) => {
+                    *unit_id = id;
+                    true
+                }
+                Err(_) => false,
+            },
+            _ => true,
+        });
+        self.actions.push_steps(events.into_iter().map(PresentationStep::Interrupt));
+    }
+
+    /// Fire every interrupt whose trigger is now met. Checked after every
+    /// command and AI action and at the start of every turn. Returns how
+    /// many fired.
+    pub fn check_interrupts(&mut self) -> usize {
+        let due: Vec<usize> =
+            (0..self.interrupts.len()).filter(|&i| !self.interrupts[i].fired && self.interrupts[i].trigger.is_met(self)).collect();
+        for &i in &due {
+            self.interrupts[i].fired = true;
+            let events = self.interrupts[i].events.clone();
+            self.interrupt(events);
+        }
+        due.len()
+    }
+
+    /// Add a unit made from [`CombatEncounter::unit_templates`]`[template]`
+    /// at `position`, or the nearest open cell when that one is taken, on
+    /// the enemy side when `enemy`, and give it a turn. The unit goes by the
+    /// template id, numbered from `_2` on if that is taken. Returns its id;
+    /// refused when there is no such template, or no open cell on the map
+    /// or `position` is off it.
+    pub fn spawn_reinforcement(&mut self, template: &str, position: &Position, enemy: bool) -> Result<String, CombatError> {
+        let Some(template_unit) = self.unit_templates.get(template) else { return Err(CombatError::UnknownTemplate) };
+        if !self.battlefield.in_bounds(position) {
+            return Err(CombatError::InvalidTarget);
+        }
+        let open = |cell: &Position| {
+            !matches!(self.battlefield.terrain_at(cell), TerrainType::Blocked)
+                && !self.units.iter().any(|u| u.health_points > 0 && u.grid_position == *cell)
+        };
+        let reach = (self.battlefield.width + self.battlefield.height) as u32;
+        let cells = self.battlefield.cells_within(position, reach).into_iter().filter(|c| open(c));
+        let Some(cell) = cells.min_by_key(|c| manhattan(c, position)) else { return Err(CombatError::InvalidTarget) };
+        let mut id = template.to_string();
+        let mut n = 1;
+        while self.unit_by_id(&id).is_some() {
+            n += 1;
+            id = format!("{}_{}", template, n);
+        }
+        let mut unit = template_unit.build(&id);
+        unit.grid_position = cell;
+        self.units.insert(unit, if enemy { Side::Enemy } else { Side::Player });
+        self.turn_order.add_unit(id.clone());
+        Ok(id)
+    }
+}
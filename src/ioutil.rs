@@ -0,0 +1,9 @@
+//! Small helpers shared by the loaders that read game data from disk.
+
+use std::io;
+
+/// An [`io::ErrorKind::InvalidData`] error, for files that read fine but
+/// don't hold what they should.
+pub(crate) fn invalid(msg: impl ToString) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, msg.to_string())
+}
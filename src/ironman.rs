@@ -0,0 +1,141 @@
+use std::io;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::campaign::Campaign;
+use crate::combat::CombatEncounter;
+use crate::ioutil::invalid;
+use crate::saves::{self, SaveFormat};
+
+/// Extension of the history file kept next to an iron-man save.
+pub const HISTORY_EXTENSION: &str = "history";
+
+/// FNV-1a over `data`, seeded with the previous link of the chain.
+fn chain(previous: u64, data: &[u8]) -> u64 {
+    previous.to_le_bytes().iter().chain(data).fold(0xcbf2_9ce4_8422_2325, |hash, b| (hash ^ *b as u64).wrapping_mul(0x0100_0000_01b3))
+}
+
+/// The single save of an iron-man campaign, with the battle in progress
+/// if there is one. Each save's digest chains on the one before it, so an
+/// edited save or one restored from an earlier copy no longer matches the
+/// history kept beside it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IronmanSave {
+    pub campaign: Campaign,
+    /// The whole encounter, binary encoded: its hash maps wouldn't come
+    /// back in the order they were hashed in, so the bytes are kept as
+    /// written. See [`IronmanSave::battle`].
+    pub battle: Option<Vec<u8>>,
+    /// How many saves came before this one.
+    pub sequence: u64,
+    /// Digest of the previous save; 0 for the first.
+    pub previous: u64,
+    pub digest: u64,
+}
+
+impl IronmanSave {
+    fn new(campaign: &Campaign, encounter: Option<&CombatEncounter>, sequence: u64, previous: u64) -> io::Result<Self> {
+        let battle = encounter.map(|e| saves::encode(e, SaveFormat::Binary)).transpose()?;
+        let mut save = Self { campaign: campaign.clone(), battle, sequence, previous, digest: 0 };
+        save.digest = save.body_digest()?;
+        Ok(save)
+    }
+
+    /// The battle in progress when the save was written, with its dice,
+    /// interrupts, doctrines and command log where they were.
+    pub fn battle(&self) -> io::Result<Option<CombatEncounter>> {
+        self.battle.as_deref().map(saves::decode).transpose()
+    }
+
+    /// Digest of the binary encoding, which has to come out byte for byte
+    /// the same after a round trip: anything hashed in the save keeps its
+    /// collections ordered.
+    fn body_digest(&self) -> io::Result<u64> {
+        let body = saves::encode(&(&self.campaign, &self.battle, self.sequence), SaveFormat::Binary)?;
+        Ok(chain(self.previous, &body))
+    }
+
+    /// Whether the save still matches its digest.
+    pub fn is_intact(&self) -> bool {
+        self.body_digest().is_ok_and(|d| d == self.digest)
+    }
+}
+
+/// Digests of every save written for an iron-man campaign, oldest first.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SaveHistory {
+    pub digests: Vec<u64>,
+}
+
+impl SaveHistory {
+    pub fn head(&self) -> u64 {
+        self.digests.last().copied().unwrap_or(0)
+    }
+
+    /// Check `save` against the history: it must be intact, chain on the
+    /// save before it and be the latest one written.
+    pub fn verify(&self, save: &IronmanSave) -> Result<(), &'static str> {
+        if !save.is_intact() {
+            return Err("save has been tampered with");
+        }
+        let Some(index) = self.digests.iter().position(|d| *d == save.digest) else {
+            return Err("save is missing from its history");
+        };
+        let previous = index.checked_sub(1).map_or(0, |i| self.digests[i]);
+        if save.previous != previous || save.sequence != index as u64 {
+            return Err("save has been tampered with");
+        }
+        if index + 1 != self.digests.len() {
+            return Err("iron-man campaigns can't load earlier saves");
+        }
+        Ok(())
+    }
+}
+
+/// Writes an iron-man campaign's save after every action, with its
+/// history in a file beside it.
+#[derive(Debug, Clone)]
+pub struct IronmanSession {
+    pub path: PathBuf,
+    pub history: SaveHistory,
+    /// Length of the battle's command log at the last save.
+    pub saved_commands: usize,
+}
+
+impl IronmanSession {
+    /// Where the history of the save at `path` is kept.
+    pub fn history_path(path: &Path) -> PathBuf {
+        path.with_extension(HISTORY_EXTENSION)
+    }
+
+    /// Mark `campaign` as iron-man and write its first save to `path`,
+    /// starting a fresh history.
+    pub fn start<P: AsRef<Path>>(path: P, campaign: &mut Campaign) -> io::Result<Self> {
+        campaign.ironman = true;
+        let mut session = Self { path: path.as_ref().to_path_buf(), history: SaveHistory::default(), saved_commands: 0 };
+        session.autosave(campaign, None)?;
+        Ok(session)
+    }
+
+    /// Load the save at `path` and check it against its history. Edited
+    /// saves and copies of earlier ones are refused.
+    pub fn resume<P: AsRef<Path>>(path: P) -> io::Result<(Self, IronmanSave)> {
+        let path = path.as_ref().to_path_buf();
+        let save: IronmanSave = saves::load(&path)?;
+        let history: SaveHistory = saves::load(Self::history_path(&path))?;
+        history.verify(&save).map_err(invalid)?;
+        Ok((Self { path, history, saved_commands: 0 }, save))
+    }
+
+    /// Write the campaign, and the battle in progress if any, over the
+    /// save and add it to the history.
+    pub fn autosave(&mut self, campaign: &Campaign, encounter: Option<&CombatEncounter>) -> io::Result<()> {
+        let save = IronmanSave::new(campaign, encounter, self.history.digests.len() as u64, self.history.head())?;
+        saves::save(&save, &self.path, SaveFormat::Binary)?;
+        self.history.digests.push(save.digest);
+        saves::save(&self.history, Self::history_path(&self.path), SaveFormat::Json)?;
+        self.saved_commands = encounter.map_or(0, |e| e.command_log.len());
+        Ok(())
+    }
+}
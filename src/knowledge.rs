@@ -0,0 +1,110 @@
+use serde::{Deserialize, Serialize};
+
+use crate::combat::{manhattan, CombatEncounter};
+use crate::models::{Position, Unit};
+use crate::profile::Difficulty;
+use crate::registry::{Side, UnitId};
+
+/// Difficulties at which the AI knows where every unit is. Elsewhere it
+/// only knows what its side can see, the same as a player under fog of
+/// war.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PerfectInformation {
+    pub easy: bool,
+    pub normal: bool,
+    pub hard: bool,
+}
+
+impl PerfectInformation {
+    /// Only Hard plays fair.
+    pub const DEFAULT: Self = Self { easy: true, normal: true, hard: false };
+
+    pub fn at(&self, difficulty: Difficulty) -> bool {
+        match difficulty {
+            Difficulty::Easy => self.easy,
+            Difficulty::Normal => self.normal,
+            Difficulty::Hard => self.hard,
+        }
+    }
+}
+
+impl Default for PerfectInformation {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
+/// The battle as one side's AI may see it. AI decisions read units through
+/// here rather than straight off the encounter.
+pub struct KnowledgeView<'a> {
+    encounter: &'a CombatEncounter,
+    side: Side,
+    /// Where the side's living units stand, or `None` when it knows
+    /// everything.
+    eyes: Option<Vec<Position>>,
+}
+
+impl<'a> KnowledgeView<'a> {
+    pub fn side(&self) -> Side {
+        self.side
+    }
+
+    /// Whether the side can see `pos`: it is within sight range of one of
+    /// its living units.
+    pub fn sees(&self, pos: &Position) -> bool {
+        let sight = self.encounter.weather.sight_range();
+        self.eyes.as_ref().is_none_or(|eyes| eyes.iter().any(|e| manhattan(e, pos) <= sight))
+    }
+
+    /// Whether the side knows where `unit` is: its own units always, others
+    /// when seen.
+    pub fn knows(&self, unit: &Unit) -> bool {
+        let own = self.encounter.units.id(&unit.id).and_then(|i| self.encounter.units.side(i)) == Some(self.side);
+        own || self.sees(&unit.grid_position)
+    }
+
+    /// Living opponents the side knows of.
+    pub fn opponents(&self) -> impl Iterator<Item = &'a Unit> + '_ {
+        let units = &self.encounter.units;
+        units
+            .ids()
+            .filter(|&i| units.side(i) == Some(self.side.opponent()) && units[i].health_points > 0)
+            .map(move |i| &units[i])
+            .filter(|u| self.knows(u))
+    }
+
+    /// [`UnitRegistry::ai_target`] among the opponents the side knows of.
+    ///
+    /// [`UnitRegistry::ai_target`]: crate::registry::UnitRegistry::ai_target
+    pub fn target_for(&self, actor: UnitId) -> Option<UnitId> {
+        self.encounter.units.ai_target_where(actor, |u| self.knows(u))
+    }
+}
+
+impl CombatEncounter {
+    /// What the AI playing `side` may know, given the difficulty.
+    pub fn knowledge(&self, side: Side) -> KnowledgeView<'_> {
+        let eyes = (!self.balance.ai_perfect_information.at(self.difficulty)).then(|| {
+            self.units.on_side(side).filter(|u| u.health_points > 0).map(|u| u.grid_position.clone()).collect()
+        });
+        KnowledgeView { encounter: self, side, eyes }
+    }
+
+    /// What the AI playing the unit's side may know.
+    pub fn knowledge_of(&self, unit: UnitId) -> Option<KnowledgeView<'_>> {
+        Some(self.knowledge(self.units.side(unit)?))
+    }
+
+    /// Where an AI unit that knows of no opponent goes looking: the
+    /// nearest living one, so a side fighting blind still closes in rather
+    /// than stalling the battle. `None` once no opponent is left.
+    pub(crate) fn search_destination(&self, actor: UnitId) -> Option<Position> {
+        let from = &self.units[actor].grid_position;
+        self.units
+            .on_side(self.units.side(actor)?.opponent())
+            .filter(|u| u.health_points > 0)
+            .min_by_key(|u| (manhattan(from, &u.grid_position), &u.id))
+            .map(|u| u.grid_position.clone())
+    }
+}
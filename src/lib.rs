@@ -1,9 +1,84 @@
 pub mod models;
 pub mod grid;
 pub mod combat;
+pub mod command;
 pub mod state;
 pub mod frontend;
 pub mod input;
 pub mod audio;
 pub mod ui;
 pub mod localization;
+pub mod flow;
+pub mod rng;
+pub mod simulation;
+pub mod runner;
+pub mod net;
+pub mod campaign;
+pub mod dialogue;
+pub mod recruitment;
+pub mod content;
+pub mod requisition;
+pub mod loadout;
+pub mod modifiers;
+pub mod doctrine;
+pub mod aura;
+pub mod grenade;
+pub mod environment;
+pub mod weather;
+pub mod scenario;
+pub mod registry;
+pub mod saves;
+mod ioutil;
+pub mod archive;
+pub mod accessibility;
+pub mod debug;
+pub mod stats;
+pub mod achievements;
+pub mod profile;
+pub mod mods;
+pub mod assets;
+pub mod base;
+pub mod selection;
+pub mod formation;
+pub mod actions;
+pub mod interrupts;
+pub mod loot;
+pub mod injuries;
+pub mod heroes;
+pub mod skirmish;
+pub mod challenge;
+pub mod ironman;
+pub mod skills;
+pub mod events;
+pub mod overworld;
+pub mod research;
+pub mod durability;
+pub mod damage;
+pub mod suppression;
+pub mod charge;
+pub mod stance;
+pub mod hold;
+pub mod planner;
+pub mod facing;
+pub mod vehicle;
+pub mod indirect;
+pub mod balance;
+pub mod ranks;
+pub mod channel;
+pub mod reactions;
+pub mod terraform;
+pub mod displacement;
+pub mod targeting;
+pub mod autocontrol;
+pub mod commander;
+pub mod knowledge;
+pub mod tutorial;
+pub mod hints;
+pub mod codex;
+pub mod debrief;
+pub mod builders;
+pub mod prelude;
+#[cfg(feature = "scripting")]
+pub mod scripting;
+#[cfg(feature = "testkit")]
+pub mod testkit;
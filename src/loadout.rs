@@ -0,0 +1,58 @@
+use crate::campaign::Campaign;
+use crate::content::ContentDatabase;
+use crate::models::{ArmorTier, Unit, UnitType};
+
+/// An equipment slot on a unit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EquipmentSlot {
+    Weapon,
+    Armor,
+    Accessory(usize),
+}
+
+/// Whether a unit type can wear armor of `tier`. Power armor needs an
+/// Astartes; daemons wear nothing.
+pub fn armor_allowed(unit_type: &UnitType, tier: &ArmorTier) -> bool {
+    match (unit_type, tier) {
+        (UnitType::Daemon | UnitType::Chimera | UnitType::Trukk, _) => false,
+        (UnitType::SpaceMarine | UnitType::ChaosMarine, _) => true,
+        (_, ArmorTier::PowerArmor) => false,
+        _ => true,
+    }
+}
+
+impl Campaign {
+    /// Take an item off a roster unit and put it back into the inventory.
+    pub fn unequip_to_inventory(&mut self, db: &ContentDatabase, unit_id: &str, slot: EquipmentSlot) -> Result<(), &'static str> {
+        let unit = self.roster.iter_mut().find(|u| u.id == unit_id).ok_or("unknown unit")?;
+        let item_id = match slot {
+            EquipmentSlot::Weapon => unit.unequip_weapon().map(|w| w.id),
+            EquipmentSlot::Armor => unit.unequip_armor().map(|a| a.id),
+            EquipmentSlot::Accessory(i) => {
+                let accessory = unit.equipment.accessory_slots.get(i).ok_or("slot is empty")?;
+                let id = db.accessory_id(accessory).ok_or("unknown item")?.to_string();
+                unit.equipment.accessory_slots.remove(i);
+                Some(id)
+            }
+        };
+        self.inventory.push(item_id.ok_or("slot is empty")?);
+        Ok(())
+    }
+
+    /// Copies of the chosen roster units with their loadouts applied and HP
+    /// and AP filled, ready to place in an encounter. Units still
+    /// recovering from injuries stay behind.
+    pub fn deploy(&self, unit_ids: &[&str]) -> Vec<Unit> {
+        unit_ids
+            .iter()
+            .filter_map(|id| self.roster.iter().find(|u| u.id == *id && !u.is_recovering()))
+            .map(|u| {
+                let mut unit = u.clone();
+                unit.recalculate_stats();
+                unit.health_points = unit.current_stats.max_health;
+                unit.action_points = unit.current_stats.max_action;
+                unit
+            })
+            .collect()
+    }
+}
@@ -1,36 +1,249 @@
-use std::collections::HashMap;
-use std::fs;
+use std::collections::{BTreeMap, HashMap};
+use std::io;
+use std::sync::{Arc, RwLock};
 
 use serde::Deserialize;
 
-#[derive(Debug, Deserialize)]
-struct LanguageFile(HashMap<String, String>);
+use crate::assets::{AssetProvider, FileAssets};
+
+/// English strings built into the crate, used when `assets/` is missing.
+const EMBEDDED_EN: &str = include_str!("../assets/locales/en.json");
+
+/// Language every chain ends with.
+pub const BASE_LANGUAGE: &str = "en";
+
+/// Languages written right to left.
+const RTL_LANGUAGES: &[&str] = &["ar", "fa", "he", "ur"];
+
+/// Reading direction of a language's script.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TextDirection {
+    #[default]
+    LeftToRight,
+    RightToLeft,
+}
+
+impl TextDirection {
+    /// Direction of `language`, judged by its primary subtag so `ar-EG`
+    /// reads like `ar`.
+    pub fn of(language: &str) -> TextDirection {
+        let primary = language.split(['-', '_']).next().unwrap_or(language);
+        if RTL_LANGUAGES.contains(&primary) { TextDirection::RightToLeft } else { TextDirection::LeftToRight }
+    }
+}
+
+/// A translated string, or its plural forms keyed by category
+/// (`"zero"`, `"one"`, `"other"`, ...).
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+enum Message {
+    Text(String),
+    Plural(BTreeMap<String, String>),
+}
+
+impl Message {
+    fn form(&self, category: &str) -> Option<&str> {
+        match self {
+            Message::Text(text) => Some(text),
+            Message::Plural(forms) => forms.get(category).or_else(|| forms.get("other")).map(String::as_str),
+        }
+    }
+}
 
+#[derive(Debug)]
+struct Catalog {
+    language: String,
+    messages: HashMap<String, Message>,
+}
+
+impl Catalog {
+    fn parse(language: &str, data: &str) -> io::Result<Self> {
+        let messages = serde_json::from_str(data).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        Ok(Catalog { language: language.to_string(), messages })
+    }
+
+    fn load(assets: &dyn AssetProvider, language: &str) -> io::Result<Self> {
+        match assets.read_to_string(&format!("assets/locales/{}.json", language)) {
+            Ok(data) => Catalog::parse(language, &data),
+            Err(e) if e.kind() == io::ErrorKind::NotFound && language == BASE_LANGUAGE => {
+                Catalog::parse(language, EMBEDDED_EN)
+            }
+            Err(e) => Err(e),
+        }
+    }
+}
+
+/// Plural category of `count` in `language`.
+fn plural_category(language: &str, count: i64) -> &'static str {
+    match language {
+        "ja" | "zh" | "ko" => "other",
+        "fr" if count == 0 || count == 1 => "one",
+        _ if count == 0 => "zero",
+        _ if count == 1 => "one",
+        _ => "other",
+    }
+}
+
+/// Replace each `{name}` in `text` with its value from `args`. Unknown
+/// placeholders are left as they are.
+fn interpolate(text: &str, args: &[(&str, &str)]) -> String {
+    args.iter().fold(text.to_string(), |text, (name, value)| text.replace(&format!("{{{}}}", name), value))
+}
+
+/// Languages with a file in `assets/locales`, sorted. English is always
+/// available.
+pub fn available_languages() -> Vec<String> {
+    available_languages_in(&FileAssets::default())
+}
+
+/// [`available_languages`] as `assets` sees them.
+pub fn available_languages_in(assets: &dyn AssetProvider) -> Vec<String> {
+    let mut languages: Vec<String> = assets
+        .list("assets/locales")
+        .unwrap_or_default()
+        .into_iter()
+        .filter_map(|name| name.strip_suffix(".json").map(str::to_string))
+        .collect();
+    if !languages.iter().any(|l| l == BASE_LANGUAGE) {
+        languages.push(BASE_LANGUAGE.to_string());
+    }
+    languages.sort();
+    languages
+}
+
+/// A localizer shared between the options menu, which switches language,
+/// and everything that renders text.
+pub type SharedLocalizer = Arc<RwLock<Localizer>>;
+
+/// Looks strings up through a chain of languages, e.g. `de` then `en`,
+/// falling back to the key itself.
 #[derive(Debug)]
 pub struct Localizer {
-    translations: HashMap<String, String>,
+    chain: Vec<Catalog>,
+    revision: u64,
+    /// Where language files are read from, now and on later switches.
+    assets: Arc<dyn AssetProvider>,
 }
 
 impl Localizer {
-    pub fn new(language: &str) -> std::io::Result<Self> {
-        let mut loc = Localizer { translations: HashMap::new() };
-        loc.load(language)?;
+    /// Load `language` with English as its fallback.
+    pub fn new(language: &str) -> io::Result<Self> {
+        Self::with_chain(&[language])
+    }
+
+    /// Load each language in `languages` in lookup order. English is
+    /// appended if the chain does not already end with it.
+    pub fn with_chain(languages: &[&str]) -> io::Result<Self> {
+        Self::with_assets(Arc::new(FileAssets::default()), languages)
+    }
+
+    /// [`Localizer::with_chain`], reading language files from `assets`.
+    pub fn with_assets(assets: Arc<dyn AssetProvider>, languages: &[&str]) -> io::Result<Self> {
+        let mut chain = languages.iter().map(|l| Catalog::load(&*assets, l)).collect::<io::Result<Vec<_>>>()?;
+        if chain.last().is_none_or(|c| c.language != BASE_LANGUAGE) {
+            chain.push(Catalog::load(&*assets, BASE_LANGUAGE)?);
+        }
+        Ok(Localizer { chain, revision: 0, assets })
+    }
+
+    /// A localizer whose primary language is parsed from `data`, falling
+    /// back to English.
+    pub fn from_json(language: &str, data: &str) -> io::Result<Self> {
+        let mut loc = Self::with_chain(&[])?;
+        loc.chain.insert(0, Catalog::parse(language, data)?);
         Ok(loc)
     }
 
-    pub fn load(&mut self, language: &str) -> std::io::Result<()> {
-        let path = format!("assets/locales/{}.json", language);
-        let data = fs::read_to_string(path)?;
-        let map: HashMap<String, String> = serde_json::from_str(&data).unwrap_or_default();
-        self.translations = map;
+    /// Replace the primary language, keeping the rest of the chain.
+    pub fn load(&mut self, language: &str) -> io::Result<()> {
+        let catalog = Catalog::load(&*self.assets, language)?;
+        if self.chain.len() == 1 && language != BASE_LANGUAGE {
+            self.chain.insert(0, catalog);
+        } else {
+            self.chain[0] = catalog;
+        }
+        self.revision += 1;
         Ok(())
     }
 
+    /// Add the strings in `data` to `language`'s catalog, replacing any with
+    /// the same key, e.g. from a mod. Ignored if `language` is not in the
+    /// chain. Returns the keys merged.
+    pub fn merge_json(&mut self, language: &str, data: &str) -> io::Result<Vec<String>> {
+        let Some(catalog) = self.chain.iter_mut().find(|c| c.language == language) else { return Ok(Vec::new()) };
+        let extra = Catalog::parse(language, data)?;
+        let keys = extra.messages.keys().cloned().collect();
+        catalog.messages.extend(extra.messages);
+        self.revision += 1;
+        Ok(keys)
+    }
+
+    /// Languages in lookup order, primary first.
+    pub fn chain(&self) -> Vec<&str> {
+        self.chain.iter().map(|c| c.language.as_str()).collect()
+    }
+
+    /// Bumped on every language switch or merge, so anything laid out from
+    /// translated text knows to measure it again.
+    pub fn revision(&self) -> u64 {
+        self.revision
+    }
+
+    pub fn shared(self) -> SharedLocalizer {
+        Arc::new(RwLock::new(self))
+    }
+
+    /// The primary language.
+    pub fn language(&self) -> &str {
+        self.chain.first().map(|c| c.language.as_str()).unwrap_or(BASE_LANGUAGE)
+    }
+
+    /// Reading direction of the primary language.
+    pub fn direction(&self) -> TextDirection {
+        TextDirection::of(self.language())
+    }
+
+    fn lookup(&self, key: &str, count: Option<i64>) -> String {
+        self.chain
+            .iter()
+            .find_map(|c| {
+                let category = count.map(|n| plural_category(&c.language, n)).unwrap_or("other");
+                c.messages.get(key)?.form(category)
+            })
+            .unwrap_or(key)
+            .to_string()
+    }
+
     pub fn get(&self, key: &str) -> String {
-        self.translations
-            .get(key)
+        self.lookup(key, None)
+    }
+
+    /// [`Localizer::get`] with `{name}` placeholders filled from `args`.
+    pub fn format(&self, key: &str, args: &[(&str, &str)]) -> String {
+        interpolate(&self.lookup(key, None), args)
+    }
+
+    /// The plural form of `key` for `count`, with `{count}` and any other
+    /// placeholders filled in.
+    pub fn plural(&self, key: &str, count: i64, args: &[(&str, &str)]) -> String {
+        let count_text = count.to_string();
+        let text = interpolate(&self.lookup(key, Some(count)), &[("count", &count_text)]);
+        interpolate(&text, args)
+    }
+
+    /// Keys the fallback languages define but the primary language lacks,
+    /// sorted, so translators can see what is left to do.
+    pub fn missing_keys(&self) -> Vec<String> {
+        let Some((primary, fallbacks)) = self.chain.split_first() else { return Vec::new() };
+        let mut missing: Vec<String> = fallbacks
+            .iter()
+            .flat_map(|c| c.messages.keys())
+            .filter(|k| !primary.messages.contains_key(*k))
             .cloned()
-            .unwrap_or_else(|| key.to_string())
+            .collect();
+        missing.sort();
+        missing.dedup();
+        missing
     }
 }
 
@@ -43,4 +256,10 @@ mod tests {
         let loc = Localizer::new("en").unwrap();
         assert_eq!(loc.get("ui.tab.abilities"), "Abilities");
     }
+
+    #[test]
+    fn embedded_english_matches_the_asset() {
+        let embedded = Catalog::parse(BASE_LANGUAGE, EMBEDDED_EN).unwrap();
+        assert_eq!(embedded.messages.len(), Catalog::load(&FileAssets::default(), BASE_LANGUAGE).unwrap().messages.len());
+    }
 }
@@ -0,0 +1,151 @@
+use serde::{Deserialize, Serialize};
+
+use crate::campaign::Campaign;
+use crate::combat::{CombatEncounter, CombatError, CombatEvent};
+use crate::grid::GridMap;
+use crate::models::Position;
+
+/// AP spent picking up everything on a unit's tile.
+pub const PICKUP_AP_COST: u32 = 1;
+/// AP spent breaking open an adjacent crate.
+pub const OPEN_CRATE_AP_COST: u32 = 1;
+
+/// Something a unit can pick up off the battlefield.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Loot {
+    /// A weapon, armour or wargear such as grenades, by content id.
+    Item(String),
+    /// Requisition points.
+    Requisition(u32),
+}
+
+/// Loot lying on a cell, waiting to be picked up.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LootToken {
+    pub position: Position,
+    pub loot: Loot,
+}
+
+/// A crate blocking its cell until it is opened or blown apart, spilling
+/// `contents` onto the cell.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LootCrate {
+    pub position: Position,
+    pub contents: Vec<Loot>,
+}
+
+impl GridMap {
+    /// Place a crate holding `contents`. It is fragile, so blasts break it.
+    pub fn place_crate(&mut self, pos: &Position, contents: Vec<Loot>) {
+        self.place_fragile(pos);
+        self.crates.retain(|c| c.position != *pos);
+        self.crates.push(LootCrate { position: pos.clone(), contents });
+    }
+
+    pub fn crate_at(&self, pos: &Position) -> Option<&LootCrate> {
+        self.crates.iter().find(|c| c.position == *pos)
+    }
+
+    /// Loot lying on `pos`.
+    pub fn loot_at(&self, pos: &Position) -> impl Iterator<Item = &Loot> {
+        self.loot.iter().filter(move |t| t.position == *pos).map(|t| &t.loot)
+    }
+
+    pub fn drop_loot(&mut self, pos: &Position, loot: impl IntoIterator<Item = Loot>) {
+        self.loot.extend(loot.into_iter().map(|loot| LootToken { position: pos.clone(), loot }));
+    }
+
+    /// Remove and return the loot lying on `pos`.
+    pub fn take_loot(&mut self, pos: &Position) -> Vec<Loot> {
+        let (taken, left) = std::mem::take(&mut self.loot).into_iter().partition(|t| t.position == *pos);
+        self.loot = left;
+        taken.into_iter().map(|t| t.loot).collect()
+    }
+
+    /// Spill the crate at `pos` onto its cell, leaving the cell's terrain
+    /// alone. Returns false if there was none.
+    fn spill_crate(&mut self, pos: &Position) -> bool {
+        let Some(idx) = self.crates.iter().position(|c| c.position == *pos) else { return false };
+        let spilled = self.crates.remove(idx);
+        self.drop_loot(pos, spilled.contents);
+        true
+    }
+}
+
+impl Campaign {
+    /// Add loot brought back from a mission: items to the inventory and
+    /// requisition to the points available.
+    pub fn collect_loot(&mut self, loot: &[Loot]) {
+        for item in loot {
+            match item {
+                Loot::Item(id) => self.grant_item(id),
+                Loot::Requisition(points) => self.currency += points,
+            }
+        }
+    }
+}
+
+impl CombatEncounter {
+    /// Pick up everything on the unit's tile for [`PICKUP_AP_COST`]. Loot
+    /// picked up by player units is carried home in `recovered`.
+    pub(crate) fn pick_up(&mut self, unit_id: &str) -> Result<(), CombatError> {
+        let player = self.units.players().any(|u| u.id == unit_id);
+        let unit = self.units.by_name_mut(unit_id).ok_or(CombatError::UnknownUnit)?;
+        if self.battlefield.loot_at(&unit.grid_position).next().is_none() {
+            return Err(CombatError::NothingToPickUp);
+        }
+        if unit.action_points < PICKUP_AP_COST {
+            return Err(CombatError::InsufficientAp);
+        }
+        unit.action_points -= PICKUP_AP_COST;
+        let loot = self.battlefield.take_loot(&unit.grid_position);
+        if player {
+            self.recovered.extend(loot.iter().cloned());
+        }
+        self.events.push(CombatEvent::LootPickedUp { unit_id: unit_id.to_string(), loot });
+        Ok(())
+    }
+
+    /// Break open the crate at `target`, next to the unit, for
+    /// [`OPEN_CRATE_AP_COST`]. Its contents spill onto the freed cell.
+    pub(crate) fn open_crate(&mut self, unit_id: &str, target: &Position) -> Result<(), CombatError> {
+        if self.battlefield.crate_at(target).is_none() {
+            return Err(CombatError::InvalidTarget);
+        }
+        let unit = self.units.by_name_mut(unit_id).ok_or(CombatError::UnknownUnit)?;
+        if !self.battlefield.adjacent(&unit.grid_position).contains(target) {
+            return Err(CombatError::OutOfRange);
+        }
+        if unit.action_points < OPEN_CRATE_AP_COST {
+            return Err(CombatError::InsufficientAp);
+        }
+        unit.action_points -= OPEN_CRATE_AP_COST;
+        self.battlefield.destroy_fragile(target);
+        self.battlefield.spill_crate(target);
+        self.events.push(CombatEvent::ObjectDestroyed { position: target.clone() });
+        Ok(())
+    }
+
+    /// Drop loot for what happened since event `since`: fallen enemies
+    /// drop their weapon and crates destroyed by blasts spill open.
+    pub(crate) fn drop_loot(&mut self, since: usize) {
+        let destroyed: Vec<Position> = self.events[since..]
+            .iter()
+            .filter_map(|e| match e {
+                CombatEvent::ObjectDestroyed { position } => Some(position.clone()),
+                _ => None,
+            })
+            .collect();
+        for pos in &destroyed {
+            self.battlefield.spill_crate(pos);
+        }
+        let enemies: Vec<String> = self.enemy_units().filter(|u| u.health_points <= 0).map(|u| u.id.clone()).collect();
+        for id in enemies {
+            let Some(unit) = self.units.by_name_mut(&id) else { continue };
+            if let Some(weapon) = unit.equipment.weapon.take() {
+                let pos = unit.grid_position.clone();
+                self.battlefield.drop_loot(&pos, [Loot::Item(weapon.id)]);
+            }
+        }
+    }
+}
@@ -1,6 +1,23 @@
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+use crate::channel::{AbilityState, Channel};
+use crate::damage::DamageType;
+use crate::displacement::ForcedMove;
+use crate::durability::{MAX_DURABILITY, full_durability};
+use crate::environment::Hazard;
+use crate::indirect::IndirectFire;
+use crate::injuries::Injury;
+use crate::modifiers::{Modifier, ModifierSource, ModifierStack, Stat};
+use crate::ranks::{AbilityRank, first_rank};
+use crate::reactions::Reaction;
+use crate::facing::Facing;
+use crate::hold::HeldAction;
+use crate::stance::Stance;
+use crate::targeting::STEALTHY_CONCEALMENT;
+use crate::terraform::TerrainShift;
+use crate::vehicle::Vehicle;
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct Stats {
     pub strength: i32,
     pub toughness: i32,
@@ -10,24 +27,80 @@ pub struct Stats {
     pub fellowship: i32,
     pub max_health: i32,
     pub max_action: u32,
+    /// Bonus to hit chance, in percentage points.
+    #[serde(default)]
+    pub accuracy: i32,
+    /// Bonus damage with melee weapons.
+    #[serde(default)]
+    pub melee_damage: i32,
+    /// Bonus movement points on top of those from agility.
+    #[serde(default)]
+    pub movement: i32,
 }
 
-impl Default for Stats {
-    fn default() -> Self {
-        Self {
-            strength: 0,
-            toughness: 0,
-            agility: 0,
-            intellect: 0,
-            willpower: 0,
-            fellowship: 0,
-            max_health: 0,
-            max_action: 0,
+impl Stats {
+    /// Movement points each AP spent moving buys under the default
+    /// balance: half of agility plus any movement bonus.
+    pub fn movement_points(&self) -> u32 {
+        crate::balance::MovementRules::DEFAULT.points(self)
+    }
+}
+
+/// A permanent perk, granted by unit type or at level-up and applied when
+/// stats are recalculated.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Trait {
+    /// +10 accuracy.
+    SteadyHands,
+    /// +2 melee damage.
+    Brute,
+    /// +1 movement point.
+    Fleet,
+    /// Gains strength when a nearby ally falls; see [`crate::reactions`].
+    Vengeful,
+    /// Strikes back when hit in melee.
+    Riposte,
+    /// Harder for the AI to pick out as a target.
+    Stealthy,
+}
+
+impl Trait {
+    /// The trait's standing stat bonus; reactive traits have none.
+    pub fn modifier(&self) -> Option<Modifier> {
+        let (stat, amount) = match self {
+            Trait::SteadyHands => (Stat::Accuracy, 10),
+            Trait::Brute => (Stat::MeleeDamage, 2),
+            Trait::Fleet => (Stat::Movement, 1),
+            Trait::Stealthy => (Stat::Concealment, STEALTHY_CONCEALMENT),
+            Trait::Vengeful | Trait::Riposte => return None,
+        };
+        Some(Modifier::new(ModifierSource::Trait, stat, amount))
+    }
+
+    /// Localization key for the trait's display name.
+    pub fn label_key(&self) -> &'static str {
+        match self {
+            Trait::SteadyHands => "trait.steady_hands",
+            Trait::Brute => "trait.brute",
+            Trait::Fleet => "trait.fleet",
+            Trait::Vengeful => "trait.vengeful",
+            Trait::Riposte => "trait.riposte",
+            Trait::Stealthy => "trait.stealthy",
+        }
+    }
+
+    /// Traits every unit of a type starts with.
+    pub fn for_unit_type(unit_type: &UnitType) -> Vec<Trait> {
+        match unit_type {
+            UnitType::SpaceMarine | UnitType::ChaosMarine => vec![Trait::SteadyHands],
+            UnitType::OrkBoy | UnitType::OrkNob => vec![Trait::Brute],
+            UnitType::Cultist => vec![Trait::Fleet],
+            _ => Vec::new(),
         }
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum UnitType {
     SpaceMarine,
     Guardsman,
@@ -39,16 +112,43 @@ pub enum UnitType {
     Cultist,
     ChaosMarine,
     Daemon,
+    /// Imperial armoured transport.
+    Chimera,
+    /// Ork transport truck.
+    Trukk,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// How a unit gets across the battlefield.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum MovementType {
+    #[default]
+    Ground,
+    /// Jump packs: can leap over blocked tiles.
+    Jump,
+    /// Wings or grav-engines: can fly over blocked tiles.
+    Flying,
+}
+
+impl MovementType {
+    /// Extra cost to pass over a blocked tile, or `None` if the unit cannot.
+    /// No unit may end its move on one.
+    pub fn blocked_crossing_cost(&self) -> Option<u32> {
+        match self {
+            MovementType::Ground => None,
+            MovementType::Jump => Some(3),
+            MovementType::Flying => Some(2),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum Faction {
     Imperial,
     Ork,
     Chaos,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct Position {
     pub x: usize,
     pub y: usize,
@@ -61,12 +161,19 @@ pub struct StatusEffect {
     pub magnitude: i32,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum EffectType {
     Poison,
     Stun,
     Shield,
     Suppression,
+    /// Set alight by fire damage.
+    Burning,
+    /// Failed a nerve test under suppression; can't attack this turn.
+    Pinned,
+    /// Goaded into going after the unit that taunted it; see
+    /// [`crate::targeting`].
+    Taunted,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -76,7 +183,7 @@ pub struct AnimationState {
     pub timer: f32,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum AnimationType {
     Idle,
     Move,
@@ -90,6 +197,12 @@ pub struct Equipment {
     pub weapon: Option<Weapon>,
     pub armor: Option<Armor>,
     pub accessory_slots: Vec<Accessory>,
+    /// Condition of the equipped weapon, out of [`MAX_DURABILITY`].
+    #[serde(default = "full_durability")]
+    pub weapon_durability: u32,
+    /// Condition of the equipped armor, out of [`MAX_DURABILITY`].
+    #[serde(default = "full_durability")]
+    pub armor_durability: u32,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -104,9 +217,33 @@ pub struct Weapon {
     pub action_point_cost: u32,
     pub critical_chance: f32,
     pub abilities_granted: Vec<AbilityType>,
+    #[serde(default)]
+    pub damage_type: DamageType,
+    /// Movement forced on whoever it hits; see [`crate::displacement`].
+    #[serde(default)]
+    pub forced_move: Option<ForcedMove>,
+    /// Set for weapons lobbing shells at cells; see [`crate::indirect`].
+    #[serde(default)]
+    pub indirect: Option<IndirectFire>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+impl Weapon {
+    /// Weapons that only reach adjacent tiles count as melee.
+    pub fn is_melee(&self) -> bool {
+        self.range <= 1
+    }
+
+    /// Weapons hitting for at least [`HEAVY_WEAPON_DAMAGE`] can knock down
+    /// structures.
+    pub fn is_heavy(&self) -> bool {
+        self.damage >= HEAVY_WEAPON_DAMAGE
+    }
+}
+
+/// Base damage from which a weapon counts as heavy.
+pub const HEAVY_WEAPON_DAMAGE: i32 = 8;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum WeaponTier {
     Basic,
     Advanced,
@@ -123,7 +260,7 @@ pub struct Armor {
     pub special_properties: Vec<ArmorProperty>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum ArmorTier {
     Flak,
     Carapace,
@@ -136,9 +273,15 @@ pub enum ArmorProperty {
     InoculatedCeramite,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum Accessory {
-    Grenade { damage: i32, aoe_radius: u32 },
+    Grenade {
+        damage: i32,
+        aoe_radius: u32,
+        /// Patch left on the blast cells, for incendiary or smoke grenades.
+        #[serde(default)]
+        leaves: Option<Hazard>,
+    },
     Stimpack { heal_amount: i32, cooldown: u32 },
     Medkit { heal_over_time: i32, duration: u32 },
     Bionics { stat_bonus: StatsModifier, duration: u32 },
@@ -158,6 +301,16 @@ pub struct Ability {
     pub effect: AbilityEffect,
     pub animation: AnimationType,
     pub sound_effect_key: String,
+    /// Current rank, from 1; see [`crate::ranks`].
+    #[serde(default = "first_rank")]
+    pub rank: u32,
+    /// What each rank past the first changes, in order.
+    #[serde(default)]
+    pub ranks: Vec<AbilityRank>,
+    /// Set for abilities that charge up or are sustained over several
+    /// turns; see [`crate::channel`].
+    #[serde(default)]
+    pub channel: Option<Channel>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -187,9 +340,22 @@ pub struct AbilityEffect {
     pub debuff: Option<StatsModifier>,
     pub status_applied: Option<EffectType>,
     pub duration: Option<u32>,
+    /// Patch left on each target's cell.
+    #[serde(default)]
+    pub creates_hazard: Option<Hazard>,
+    /// Change made to the terrain of the cells it covers; see
+    /// [`crate::terraform`].
+    #[serde(default)]
+    pub alters_terrain: Option<TerrainShift>,
+    /// Movement forced on each target; see [`crate::displacement`].
+    #[serde(default)]
+    pub forced_move: Option<ForcedMove>,
+    /// Type of `damage`.
+    #[serde(default)]
+    pub damage_type: DamageType,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct StatsModifier {
     pub strength_mod: i32,
     pub toughness_mod: i32,
@@ -215,6 +381,57 @@ pub struct Unit {
     pub action_points: u32,
     pub faction: Faction,
     pub status_effects: Vec<StatusEffect>,
+    #[serde(default)]
+    pub traits: Vec<Trait>,
+    #[serde(default)]
+    pub movement_type: MovementType,
+    /// Timed status, environment and aura modifiers. Equipment and traits
+    /// are derived afresh on every recalculation.
+    #[serde(default)]
+    pub modifiers: ModifierStack,
+    /// Wounds carried between missions; see [`crate::injuries`].
+    #[serde(default)]
+    pub injuries: Vec<Injury>,
+    /// Hero template id for named characters; see [`crate::heroes`].
+    #[serde(default)]
+    pub hero: Option<String>,
+    /// Sprite id of the unit's portrait, if it has one.
+    #[serde(default)]
+    pub portrait: Option<String>,
+    /// Defensive stance taken at the end of its last turn; see
+    /// [`crate::stance`].
+    #[serde(default)]
+    pub stance: Option<Stance>,
+    /// Movement points left from AP already spent moving this turn, used
+    /// up before any more AP is; see [`crate::grid::move_ap_cost`].
+    #[serde(default)]
+    pub movement_left: u32,
+    /// Action held back to take out of turn; see [`crate::hold`].
+    #[serde(default)]
+    pub held_action: Option<HeldAction>,
+    /// Points from levelling up still to spend on ability ranks.
+    #[serde(default)]
+    pub rank_points: u32,
+    /// Channelled ability in progress, if any.
+    #[serde(default)]
+    pub ability_state: Option<AbilityState>,
+    /// Automatic responses beyond those its traits give; see
+    /// [`crate::reactions`].
+    #[serde(default)]
+    pub reactions: Vec<Reaction>,
+    /// Who last taunted the unit; only counts while it is
+    /// [`EffectType::Taunted`].
+    #[serde(default)]
+    pub taunted_by: Option<String>,
+    /// Way the unit faces; see [`crate::facing`].
+    #[serde(default)]
+    pub facing: Facing,
+    /// Set for vehicles; see [`crate::vehicle`].
+    #[serde(default)]
+    pub vehicle: Option<Vehicle>,
+    /// Id of the vehicle the unit rides in, if any.
+    #[serde(default)]
+    pub embarked_in: Option<String>,
     pub animation_state: AnimationState,
     pub sprite_id: String,
     pub is_selected: bool,
@@ -231,7 +448,13 @@ impl Unit {
             level: 1,
             base_stats: stats.clone(),
             current_stats: stats.clone(),
-            equipment: Equipment { weapon: None, armor: None, accessory_slots: Vec::new() },
+            equipment: Equipment {
+                weapon: None,
+                armor: None,
+                accessory_slots: Vec::new(),
+                weapon_durability: MAX_DURABILITY,
+                armor_durability: MAX_DURABILITY,
+            },
             abilities: Vec::new(),
             grid_position: Position { x: 0, y: 0 },
             experience: 0,
@@ -239,57 +462,100 @@ impl Unit {
             action_points: stats.max_action,
             faction,
             status_effects: Vec::new(),
+            traits: Vec::new(),
+            movement_type: MovementType::Ground,
+            modifiers: ModifierStack::new(),
+            injuries: Vec::new(),
+            hero: None,
+            portrait: None,
+            stance: None,
+            movement_left: 0,
+            held_action: None,
+            rank_points: 0,
+            ability_state: None,
+            reactions: Vec::new(),
+            taunted_by: None,
+            facing: Facing::default(),
+            vehicle: None,
+            embarked_in: None,
             animation_state: AnimationState { current_animation: AnimationType::Idle, frame_index: 0, timer: 0.0 },
             sprite_id: String::new(),
             is_selected: false,
         }
     }
 
-    /// Recalculate current_stats based on base_stats and all equipped items.
-    pub fn apply_equipment(&mut self) {
-        self.current_stats = self.base_stats.clone();
+    /// Recalculate current_stats from base_stats plus every modifier layer:
+    /// equipment, traits, then the unit's timed modifiers.
+    pub fn recalculate_stats(&mut self) {
+        let mut stats = self.base_stats.clone();
+        self.equipment_modifiers().apply_to(&mut stats);
+        self.modifiers.apply_to(&mut stats);
+        self.current_stats = stats;
+    }
+
+    /// Modifiers from equipped items, traits and injuries.
+    pub fn equipment_modifiers(&self) -> ModifierStack {
+        let mut stack = ModifierStack::new();
         if let Some(armor) = &self.equipment.armor {
-            self.current_stats.toughness += armor.toughness_bonus;
-            self.current_stats.agility += armor.agility_penalty;
+            // Worn armor gives half its protection until repaired.
+            let toughness = if self.equipment.armor_worn() { armor.toughness_bonus / 2 } else { armor.toughness_bonus };
+            stack.push(Modifier::new(ModifierSource::Equipment, Stat::Toughness, toughness));
+            stack.push(Modifier::new(ModifierSource::Equipment, Stat::Agility, armor.agility_penalty));
         }
-        // Weapons currently do not modify stats but are included for completeness.
-        if let Some(_weapon) = &self.equipment.weapon {
-            // Placeholder for future weapon stat modifiers
+        for m in self.traits.iter().filter_map(Trait::modifier) {
+            stack.push(m);
         }
+        for injury in &self.injuries {
+            stack.push(injury.kind.modifier());
+        }
+        stack
+    }
+
+    /// Add a modifier and recalculate stats.
+    pub fn add_modifier(&mut self, modifier: Modifier) {
+        self.modifiers.push(modifier);
+        self.recalculate_stats();
     }
 
-    /// Remove all equipment modifiers, returning stats to base values.
-    pub fn remove_equipment(&mut self) {
-        self.current_stats = self.base_stats.clone();
+    /// Advance one level, learning `new_trait` if the unit lacks it, and
+    /// gain a rank point.
+    pub fn level_up(&mut self, new_trait: Option<Trait>) {
+        self.level += 1;
+        self.rank_points += 1;
+        if let Some(t) = new_trait
+            && !self.traits.contains(&t)
+        {
+            self.traits.push(t);
+        }
+        self.recalculate_stats();
     }
 
-    /// Equip a new weapon and update stats accordingly.
+    /// Equip a new weapon, fresh from stores, and update stats accordingly.
     pub fn equip_weapon(&mut self, weapon: Weapon) {
-        self.remove_equipment();
         self.equipment.weapon = Some(weapon);
-        self.apply_equipment();
+        self.equipment.weapon_durability = MAX_DURABILITY;
+        self.recalculate_stats();
     }
 
     /// Unequip the current weapon and update stats.
     pub fn unequip_weapon(&mut self) -> Option<Weapon> {
         let old = self.equipment.weapon.take();
-        self.remove_equipment();
-        self.apply_equipment();
+        self.recalculate_stats();
         old
     }
 
-    /// Equip new armor and update stats to include its bonuses.
+    /// Equip new armor, fresh from stores, and update stats to include its
+    /// bonuses.
     pub fn equip_armor(&mut self, armor: Armor) {
-        self.remove_equipment();
         self.equipment.armor = Some(armor);
-        self.apply_equipment();
+        self.equipment.armor_durability = MAX_DURABILITY;
+        self.recalculate_stats();
     }
 
     /// Remove the current armor and revert its bonuses.
     pub fn unequip_armor(&mut self) -> Option<Armor> {
         let old = self.equipment.armor.take();
-        self.remove_equipment();
-        self.apply_equipment();
+        self.recalculate_stats();
         old
     }
 }
@@ -0,0 +1,190 @@
+use serde::{Deserialize, Serialize};
+
+use crate::models::{Stats, StatsModifier};
+
+/// A quantity modifiers can adjust.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Stat {
+    Strength,
+    Toughness,
+    Agility,
+    Intellect,
+    Willpower,
+    Fellowship,
+    MaxHealth,
+    MaxAction,
+    Accuracy,
+    MeleeDamage,
+    Movement,
+    /// Added to a defender's cover bonus while in cover.
+    CoverBonus,
+    /// Cells the AI adds to its distance to the unit when picking a target;
+    /// see [`crate::targeting`].
+    Concealment,
+}
+
+/// Where a modifier came from, so a whole layer can be replaced at once.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum ModifierSource {
+    Equipment,
+    Trait,
+    Status,
+    Environment,
+    Aura,
+    Doctrine,
+    Injury,
+}
+
+/// Situation a modifier needs in order to apply.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Condition {
+    Always,
+    /// Attacking after moving this turn.
+    Charging,
+    /// Defending from cover.
+    InCover,
+}
+
+/// Circumstances of the check a stack is being queried for.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Situation {
+    pub charging: bool,
+    pub in_cover: bool,
+}
+
+impl Condition {
+    fn holds(&self, situation: &Situation) -> bool {
+        match self {
+            Condition::Always => true,
+            Condition::Charging => situation.charging,
+            Condition::InCover => situation.in_cover,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Modifier {
+    pub source: ModifierSource,
+    pub stat: Stat,
+    pub amount: i32,
+    pub condition: Condition,
+    /// Turns left before the modifier expires; `None` lasts indefinitely.
+    pub remaining_turns: Option<u32>,
+}
+
+impl Modifier {
+    /// An unconditional, permanent modifier.
+    pub fn new(source: ModifierSource, stat: Stat, amount: i32) -> Self {
+        Self { source, stat, amount, condition: Condition::Always, remaining_turns: None }
+    }
+
+    /// A modifier lasting `turns` of the affected unit's turns.
+    pub fn timed(source: ModifierSource, stat: Stat, amount: i32, turns: u32) -> Self {
+        Self { remaining_turns: Some(turns), ..Self::new(source, stat, amount) }
+    }
+
+    /// Split an ability's stat buff into one timed modifier per changed
+    /// stat, negated when `sign` is negative.
+    pub fn from_stats_modifier(source: ModifierSource, m: &StatsModifier, sign: i32, turns: u32) -> Vec<Self> {
+        [
+            (Stat::Strength, m.strength_mod),
+            (Stat::Toughness, m.toughness_mod),
+            (Stat::Agility, m.agility_mod),
+            (Stat::Intellect, m.intellect_mod),
+            (Stat::Willpower, m.willpower_mod),
+            (Stat::Fellowship, m.fellowship_mod),
+        ]
+        .into_iter()
+        .filter(|&(_, amount)| amount != 0)
+        .map(|(stat, amount)| Self::timed(source, stat, amount * sign, turns))
+        .collect()
+    }
+}
+
+impl Stats {
+    /// Current value of one stat; [`Stat::CoverBonus`] and
+    /// [`Stat::Concealment`] read as 0.
+    pub fn get(&self, stat: Stat) -> i32 {
+        match stat {
+            Stat::Strength => self.strength,
+            Stat::Toughness => self.toughness,
+            Stat::Agility => self.agility,
+            Stat::Intellect => self.intellect,
+            Stat::Willpower => self.willpower,
+            Stat::Fellowship => self.fellowship,
+            Stat::MaxHealth => self.max_health,
+            Stat::MaxAction => self.max_action as i32,
+            Stat::Accuracy => self.accuracy,
+            Stat::MeleeDamage => self.melee_damage,
+            Stat::Movement => self.movement,
+            Stat::CoverBonus | Stat::Concealment => 0,
+        }
+    }
+
+    /// Adjust one stat in place. [`Stat::CoverBonus`] and
+    /// [`Stat::Concealment`] have no stat field and are only read from
+    /// stacks directly.
+    pub fn add(&mut self, stat: Stat, amount: i32) {
+        match stat {
+            Stat::Strength => self.strength += amount,
+            Stat::Toughness => self.toughness += amount,
+            Stat::Agility => self.agility += amount,
+            Stat::Intellect => self.intellect += amount,
+            Stat::Willpower => self.willpower += amount,
+            Stat::Fellowship => self.fellowship += amount,
+            Stat::MaxHealth => self.max_health += amount,
+            Stat::MaxAction => self.max_action = (self.max_action as i32 + amount).max(0) as u32,
+            Stat::Accuracy => self.accuracy += amount,
+            Stat::MeleeDamage => self.melee_damage += amount,
+            Stat::Movement => self.movement += amount,
+            Stat::CoverBonus | Stat::Concealment => {}
+        }
+    }
+}
+
+/// An ordered collection of modifiers summed on demand.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ModifierStack {
+    pub modifiers: Vec<Modifier>,
+}
+
+impl ModifierStack {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn push(&mut self, modifier: Modifier) {
+        self.modifiers.push(modifier);
+    }
+
+    /// Sum of all modifiers to `stat` whose condition holds.
+    pub fn total(&self, stat: Stat, situation: &Situation) -> i32 {
+        self.modifiers
+            .iter()
+            .filter(|m| m.stat == stat && m.condition.holds(situation))
+            .map(|m| m.amount)
+            .sum()
+    }
+
+    /// Add every unconditional modifier to `stats`, in push order.
+    /// Situational modifiers are left for combat resolution to query.
+    pub fn apply_to(&self, stats: &mut Stats) {
+        for m in self.modifiers.iter().filter(|m| m.condition == Condition::Always) {
+            stats.add(m.stat, m.amount);
+        }
+    }
+
+    pub fn remove_source(&mut self, source: ModifierSource) {
+        self.modifiers.retain(|m| m.source != source);
+    }
+
+    /// Count down timed modifiers and drop the expired ones.
+    pub fn tick(&mut self) {
+        for m in &mut self.modifiers {
+            if let Some(turns) = &mut m.remaining_turns {
+                *turns = turns.saturating_sub(1);
+            }
+        }
+        self.modifiers.retain(|m| m.remaining_turns != Some(0));
+    }
+}
@@ -0,0 +1,292 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::audio::AudioSystem;
+use crate::content::ContentDatabase;
+use crate::frontend::Renderer;
+use crate::ioutil::invalid;
+use crate::localization::Localizer;
+
+/// Directory scanned for mods, relative to the working directory.
+pub const MODS_DIR: &str = "mods";
+/// Load order file inside the mods directory.
+pub const LOAD_ORDER_FILE: &str = "load_order.json";
+/// Manifest every mod directory must contain.
+pub const MOD_MANIFEST: &str = "mod.json";
+
+/// Item catalog, in the same layout as `assets/content/items.json`.
+const CONTENT_FILE: &str = "content/items.json";
+/// Directory of locale files named by language, e.g. `de.json`.
+const LOCALES_DIR: &str = "locales";
+/// Sprite atlas manifest, see [`crate::frontend::atlas::AtlasManifest`].
+const ATLAS_FILE: &str = "sprites/atlas.json";
+/// Sound manifest, as read by [`AudioSystem::load_manifest`].
+const SOUNDS_FILE: &str = "sounds/manifest.json";
+
+/// A mod's `mod.json`.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+struct ModManifest {
+    name: String,
+    version: String,
+    #[serde(default)]
+    description: String,
+}
+
+/// A content pack found in the mods directory. Its id is the name of its
+/// directory.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ModInfo {
+    pub id: String,
+    pub name: String,
+    pub version: String,
+    pub description: String,
+    pub path: PathBuf,
+    pub enabled: bool,
+}
+
+impl ModInfo {
+    fn file(&self, relative: &str) -> Option<PathBuf> {
+        Some(self.path.join(relative)).filter(|p| p.is_file())
+    }
+
+    /// Language and path of each locale file the mod ships.
+    fn locale_files(&self) -> Vec<(String, PathBuf)> {
+        let mut files: Vec<(String, PathBuf)> = fs::read_dir(self.path.join(LOCALES_DIR))
+            .into_iter()
+            .flatten()
+            .flatten()
+            .filter_map(|e| Some((e.path().file_stem()?.to_str()?.to_string(), e.path())))
+            .collect();
+        files.sort();
+        files
+    }
+
+    /// Keys of every `kind` asset the mod defines. Locale keys are
+    /// prefixed with their language, e.g. `de/ui.tab.abilities`.
+    fn keys(&self, kind: AssetKind) -> io::Result<Vec<String>> {
+        let keys = match kind {
+            AssetKind::Item => match self.file(CONTENT_FILE) {
+                Some(path) => ContentDatabase::load(path)?.items.into_keys().collect(),
+                None => Vec::new(),
+            },
+            AssetKind::Locale => {
+                let mut keys = Vec::new();
+                for (language, path) in self.locale_files() {
+                    let table: BTreeMap<String, serde_json::Value> =
+                        serde_json::from_str(&fs::read_to_string(path)?).map_err(invalid)?;
+                    keys.extend(table.into_keys().map(|key| format!("{}/{}", language, key)));
+                }
+                keys
+            }
+            AssetKind::Sprite => match self.file(ATLAS_FILE) {
+                Some(path) => {
+                    let atlas = Renderer::validate_atlas(path)?;
+                    atlas.sprites.into_keys().chain(atlas.animations.into_keys()).collect()
+                }
+                None => Vec::new(),
+            },
+            AssetKind::Sound => match self.file(SOUNDS_FILE) {
+                Some(path) => {
+                    let table: BTreeMap<String, serde_json::Value> =
+                        serde_json::from_str(&fs::read_to_string(path)?).map_err(invalid)?;
+                    table.into_keys().collect()
+                }
+                None => Vec::new(),
+            },
+        };
+        Ok(keys)
+    }
+}
+
+/// The kinds of asset a mod can override or add.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum AssetKind {
+    Item,
+    Locale,
+    Sprite,
+    Sound,
+}
+
+impl AssetKind {
+    pub const ALL: [AssetKind; 4] = [AssetKind::Item, AssetKind::Locale, AssetKind::Sprite, AssetKind::Sound];
+}
+
+/// An asset defined by more than one enabled mod. The last mod in load
+/// order wins.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Conflict {
+    pub kind: AssetKind,
+    pub key: String,
+    /// Ids of the mods defining the asset, in load order.
+    pub mods: Vec<String>,
+}
+
+/// One line of the load order file.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct LoadOrderEntry {
+    id: String,
+    enabled: bool,
+}
+
+/// The mods found in a mods directory, in load order. Mods later in the
+/// order are applied after, and so win over, earlier ones; all of them
+/// win over the base game.
+///
+/// Each mod is a directory holding a `mod.json` and any of
+/// `content/items.json`, `locales/<language>.json`, `sprites/atlas.json`
+/// and `sounds/manifest.json`.
+#[derive(Debug, Clone, Default)]
+pub struct ModLoader {
+    pub root: PathBuf,
+    pub mods: Vec<ModInfo>,
+}
+
+impl ModLoader {
+    /// Find the mods under `root` and order them by its load order file.
+    /// Mods the file doesn't list are enabled and go last, sorted by id;
+    /// listed mods that are gone are dropped. A missing directory has no
+    /// mods.
+    pub fn scan<P: AsRef<Path>>(root: P) -> io::Result<Self> {
+        let root = root.as_ref().to_path_buf();
+        let mut found = BTreeMap::new();
+        let entries = match fs::read_dir(&root) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Self { root, mods: Vec::new() }),
+            Err(e) => return Err(e),
+        };
+        for entry in entries {
+            let path = entry?.path();
+            let manifest_path = path.join(MOD_MANIFEST);
+            if !manifest_path.is_file() {
+                continue;
+            }
+            let Some(id) = path.file_name().and_then(|n| n.to_str()).map(str::to_string) else { continue };
+            let manifest: ModManifest = serde_json::from_str(&fs::read_to_string(&manifest_path)?)
+                .map_err(|e| invalid(format!("mod '{}': {}", id, e)))?;
+            let info = ModInfo {
+                id: id.clone(),
+                name: manifest.name,
+                version: manifest.version,
+                description: manifest.description,
+                path,
+                enabled: true,
+            };
+            found.insert(id, info);
+        }
+
+        let order: Vec<LoadOrderEntry> = match fs::read_to_string(root.join(LOAD_ORDER_FILE)) {
+            Ok(data) => serde_json::from_str(&data).map_err(invalid)?,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Vec::new(),
+            Err(e) => return Err(e),
+        };
+        let mut mods = Vec::with_capacity(found.len());
+        for entry in order {
+            if let Some(mut info) = found.remove(&entry.id) {
+                info.enabled = entry.enabled;
+                mods.push(info);
+            }
+        }
+        mods.extend(found.into_values());
+        Ok(Self { root, mods })
+    }
+
+    /// Write the current order and enabled flags to the load order file.
+    pub fn save_order(&self) -> io::Result<()> {
+        let order: Vec<LoadOrderEntry> =
+            self.mods.iter().map(|m| LoadOrderEntry { id: m.id.clone(), enabled: m.enabled }).collect();
+        let data = serde_json::to_string_pretty(&order).map_err(invalid)?;
+        fs::write(self.root.join(LOAD_ORDER_FILE), data)
+    }
+
+    pub fn get(&self, id: &str) -> Option<&ModInfo> {
+        self.mods.iter().find(|m| m.id == id)
+    }
+
+    /// Enabled mods in load order.
+    pub fn enabled(&self) -> impl Iterator<Item = &ModInfo> {
+        self.mods.iter().filter(|m| m.enabled)
+    }
+
+    pub fn set_enabled(&mut self, id: &str, enabled: bool) -> Result<(), &'static str> {
+        let info = self.mods.iter_mut().find(|m| m.id == id).ok_or("unknown mod")?;
+        info.enabled = enabled;
+        Ok(())
+    }
+
+    /// Move a mod `offset` places later in the load order, or earlier if
+    /// negative, stopping at either end.
+    pub fn move_by(&mut self, id: &str, offset: isize) -> Result<(), &'static str> {
+        let from = self.mods.iter().position(|m| m.id == id).ok_or("unknown mod")?;
+        let to = from.saturating_add_signed(offset).min(self.mods.len() - 1);
+        let info = self.mods.remove(from);
+        self.mods.insert(to, info);
+        Ok(())
+    }
+
+    /// Add each enabled mod's items and heroes to `db`, replacing base
+    /// entries with the same id.
+    pub fn apply_content(&self, db: &mut ContentDatabase) -> io::Result<()> {
+        for info in self.enabled() {
+            if let Some(path) = info.file(CONTENT_FILE) {
+                let content = ContentDatabase::load(path)?;
+                db.items.extend(content.items);
+                db.heroes.extend(content.heroes);
+            }
+        }
+        Ok(())
+    }
+
+    /// Merge each enabled mod's strings for the languages in `loc`'s chain.
+    pub fn apply_locales(&self, loc: &mut Localizer) -> io::Result<()> {
+        let chain: Vec<String> = loc.chain().into_iter().map(str::to_string).collect();
+        for info in self.enabled() {
+            for (language, path) in info.locale_files() {
+                if chain.contains(&language) {
+                    loc.merge_json(&language, &fs::read_to_string(path)?)?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Load each enabled mod's sprite atlas over the base sprites.
+    pub fn apply_sprites(&self, renderer: &mut Renderer) -> io::Result<()> {
+        for info in self.enabled() {
+            if let Some(path) = info.file(ATLAS_FILE) {
+                renderer.load_atlas(path)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Load each enabled mod's sound manifest over the base sounds.
+    pub fn apply_sounds(&self, audio: &mut AudioSystem) -> io::Result<()> {
+        for info in self.enabled() {
+            if let Some(path) = info.file(SOUNDS_FILE) {
+                audio.load_manifest(path)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Assets defined by more than one enabled mod, by kind and then key.
+    pub fn conflicts(&self) -> io::Result<Vec<Conflict>> {
+        let mut conflicts = Vec::new();
+        for kind in AssetKind::ALL {
+            let mut owners: BTreeMap<String, Vec<String>> = BTreeMap::new();
+            for info in self.enabled() {
+                for key in info.keys(kind)? {
+                    owners.entry(key).or_default().push(info.id.clone());
+                }
+            }
+            conflicts.extend(
+                owners.into_iter().filter(|(_, mods)| mods.len() > 1).map(|(key, mods)| Conflict { kind, key, mods }),
+            );
+        }
+        Ok(conflicts)
+    }
+}
@@ -0,0 +1,179 @@
+use std::io::{self, BufRead, BufReader, Write};
+use std::net::{TcpListener, TcpStream, ToSocketAddrs};
+
+use serde::{Deserialize, Serialize};
+
+use crate::combat::{CombatEncounter, TurnQueue};
+use crate::command::Command;
+use crate::ioutil::invalid;
+use crate::models::{Position, StatusEffect};
+use crate::rng::GameRng;
+use crate::state::fnv1a;
+
+/// Player number of the hosting peer. The host's encounter is authoritative
+/// for joins and resyncs.
+pub const HOST_PLAYER: u8 = 0;
+/// Player number of the joining peer.
+pub const GUEST_PLAYER: u8 = 1;
+
+/// Messages exchanged between peers, one JSON object per line.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum NetMessage {
+    Hello { player: u8 },
+    /// Full encounter sent by the host on join, reconnect or after a desync.
+    FullState { turn: u32, encounter: Box<CombatEncounter> },
+    Commands { turn: u32, commands: Vec<Command> },
+    Checksum { turn: u32, value: u64 },
+}
+
+/// Result of one lockstep turn.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TurnOutcome {
+    InSync,
+    /// Checksums differed after applying the turn; call
+    /// [`LockstepSession::resync`] on both peers.
+    Desync { local: u64, remote: u64 },
+}
+
+/// What both peers simulate alike: where each unit stands, its health,
+/// action points and statuses, whose turn it is and the dice. Animation
+/// timers and selection run at each peer's own pace and are left out.
+#[derive(Serialize)]
+struct SimState<'a> {
+    units: Vec<(&'a str, &'a Position, i32, u32, &'a [StatusEffect])>,
+    turn_order: &'a TurnQueue,
+    rng: &'a GameRng,
+}
+
+/// Checksum of the simulation state of `encounter`, compared by peers after
+/// each turn to detect desyncs.
+pub fn sync_checksum(encounter: &CombatEncounter) -> u64 {
+    let state = SimState {
+        units: encounter
+            .units
+            .iter()
+            .map(|u| (u.id.as_str(), &u.grid_position, u.health_points, u.action_points, u.status_effects.as_slice()))
+            .collect(),
+        turn_order: &encounter.turn_order,
+        rng: &encounter.rng,
+    };
+    fnv1a(serde_json::to_string(&state).expect("serialize simulation state").as_bytes())
+}
+
+/// Line-delimited JSON connection to the other peer.
+pub struct Peer {
+    reader: BufReader<TcpStream>,
+    writer: TcpStream,
+}
+
+impl Peer {
+    pub fn new(stream: TcpStream) -> io::Result<Self> {
+        stream.set_nodelay(true)?;
+        Ok(Self { reader: BufReader::new(stream.try_clone()?), writer: stream })
+    }
+
+    pub fn send(&mut self, msg: &NetMessage) -> io::Result<()> {
+        let mut line = serde_json::to_string(msg).map_err(|e| invalid(e.to_string()))?;
+        line.push('\n');
+        self.writer.write_all(line.as_bytes())
+    }
+
+    pub fn recv(&mut self) -> io::Result<NetMessage> {
+        let mut line = String::new();
+        if self.reader.read_line(&mut line)? == 0 {
+            return Err(io::Error::new(io::ErrorKind::UnexpectedEof, "peer disconnected"));
+        }
+        serde_json::from_str(&line).map_err(|e| invalid(e.to_string()))
+    }
+}
+
+/// One side of a two-player lockstep game. Each turn both peers submit
+/// their commands, apply both sets in player order, and compare state
+/// checksums. Commands a player isn't allowed to issue are skipped on both
+/// sides alike, so they can't cause a desync.
+pub struct LockstepSession {
+    peer: Peer,
+    pub local_player: u8,
+    pub turn: u32,
+}
+
+impl LockstepSession {
+    /// Bind `addr` and wait for a guest to join, sending it `encounter`.
+    pub fn host<A: ToSocketAddrs>(addr: A, encounter: &CombatEncounter) -> io::Result<Self> {
+        let listener = TcpListener::bind(addr)?;
+        Self::accept(&listener, encounter, 0)
+    }
+
+    /// Accept a guest on an existing listener and send it the full state.
+    /// Used both for the first join and to let a dropped guest reconnect at
+    /// `turn`.
+    pub fn accept(listener: &TcpListener, encounter: &CombatEncounter, turn: u32) -> io::Result<Self> {
+        let (stream, _) = listener.accept()?;
+        let mut peer = Peer::new(stream)?;
+        match peer.recv()? {
+            NetMessage::Hello { player: GUEST_PLAYER } => {}
+            _ => return Err(invalid("expected guest hello")),
+        }
+        peer.send(&NetMessage::Hello { player: HOST_PLAYER })?;
+        peer.send(&NetMessage::FullState { turn, encounter: Box::new(encounter.clone()) })?;
+        Ok(Self { peer, local_player: HOST_PLAYER, turn })
+    }
+
+    /// Connect to a host, returning the session and the host's encounter.
+    /// Reconnecting after a dropped connection is the same call.
+    pub fn join<A: ToSocketAddrs>(addr: A) -> io::Result<(Self, CombatEncounter)> {
+        let mut peer = Peer::new(TcpStream::connect(addr)?)?;
+        peer.send(&NetMessage::Hello { player: GUEST_PLAYER })?;
+        match peer.recv()? {
+            NetMessage::Hello { player: HOST_PLAYER } => {}
+            _ => return Err(invalid("expected host hello")),
+        }
+        match peer.recv()? {
+            NetMessage::FullState { turn, encounter } => Ok((Self { peer, local_player: GUEST_PLAYER, turn }, *encounter)),
+            _ => Err(invalid("expected full state")),
+        }
+    }
+
+    /// Exchange this turn's commands with the peer, apply both sets and
+    /// compare checksums. Blocks until the peer has submitted.
+    pub fn submit_turn(&mut self, encounter: &mut CombatEncounter, commands: Vec<Command>) -> io::Result<TurnOutcome> {
+        self.peer.send(&NetMessage::Commands { turn: self.turn, commands: commands.clone() })?;
+        let remote = match self.peer.recv()? {
+            NetMessage::Commands { turn, commands } if turn == self.turn => commands,
+            _ => return Err(invalid("expected commands for this turn")),
+        };
+        let remote_player = 1 - self.local_player;
+        let mut ordered = [(self.local_player, commands), (remote_player, remote)];
+        ordered.sort_by_key(|(player, _)| *player);
+        for (player, cmds) in ordered {
+            for cmd in cmds {
+                let _ = encounter.execute_as(player, cmd);
+            }
+        }
+
+        let local = sync_checksum(encounter);
+        self.peer.send(&NetMessage::Checksum { turn: self.turn, value: local })?;
+        let remote = match self.peer.recv()? {
+            NetMessage::Checksum { turn, value } if turn == self.turn => value,
+            _ => return Err(invalid("expected checksum for this turn")),
+        };
+        self.turn += 1;
+        Ok(if local == remote { TurnOutcome::InSync } else { TurnOutcome::Desync { local, remote } })
+    }
+
+    /// Recover from a desync: the host sends its encounter and the guest
+    /// replaces its own with it.
+    pub fn resync(&mut self, encounter: &mut CombatEncounter) -> io::Result<()> {
+        if self.local_player == HOST_PLAYER {
+            return self.peer.send(&NetMessage::FullState { turn: self.turn, encounter: Box::new(encounter.clone()) });
+        }
+        match self.peer.recv()? {
+            NetMessage::FullState { turn, encounter: state } => {
+                *encounter = *state;
+                self.turn = turn;
+                Ok(())
+            }
+            _ => Err(invalid("expected full state")),
+        }
+    }
+}
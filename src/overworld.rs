@@ -0,0 +1,189 @@
+use std::collections::{BTreeSet, HashSet};
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::assets::AssetProvider;
+use crate::campaign::Campaign;
+use crate::ioutil::invalid;
+use crate::models::Faction;
+use crate::profile::Difficulty;
+use crate::rng::GameRng;
+use crate::scenario::Scenario;
+use crate::skirmish::SkirmishMission;
+
+/// Percent chance of being intercepted on arriving in enemy territory,
+/// for locations that don't set their own.
+pub const INTERCEPTION_CHANCE: u32 = 50;
+
+fn default_interception() -> u32 {
+    INTERCEPTION_CHANCE
+}
+
+/// A place on the overworld. `name` is a locale key; `position` is in
+/// screen pixels on the overworld screen.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Location {
+    pub id: String,
+    pub name: String,
+    pub position: (u32, u32),
+    /// Faction holding the territory; `None` for friendly ground.
+    #[serde(default)]
+    pub controller: Option<Faction>,
+    /// Percent chance of an interception battle on arrival while the
+    /// territory is held by the enemy.
+    #[serde(default = "default_interception")]
+    pub interception: u32,
+}
+
+/// A road between two locations, travelled either way.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Route {
+    pub from: String,
+    pub to: String,
+    pub days: u32,
+    /// Requisition spent on the journey.
+    #[serde(default)]
+    pub cost: u32,
+}
+
+impl Route {
+    fn joins(&self, a: &str, b: &str) -> bool {
+        (self.from == a && self.to == b) || (self.from == b && self.to == a)
+    }
+}
+
+/// The strategic map: locations joined by routes.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OverworldMap {
+    /// Where a new campaign starts.
+    pub start: String,
+    pub locations: Vec<Location>,
+    pub routes: Vec<Route>,
+}
+
+/// Where the squad is on the overworld and what it has taken back, kept
+/// in the campaign save.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OverworldState {
+    /// Current location; empty before the first journey, meaning the
+    /// map's start.
+    pub location: String,
+    /// Days spent travelling so far.
+    pub day: u32,
+    /// Enemy territories won back.
+    pub liberated: BTreeSet<String>,
+}
+
+/// An enemy force waiting at the end of a journey.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Interception {
+    pub location: String,
+    pub faction: Faction,
+}
+
+impl Interception {
+    /// The battle to fight, with an enemy squad generated for the
+    /// intercepting faction on `scenario`'s map.
+    pub fn mission(&self, scenario: Scenario, difficulty: Difficulty) -> SkirmishMission {
+        SkirmishMission { scenario, faction: self.faction.clone(), difficulty }
+    }
+}
+
+/// A finished journey.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Travel {
+    pub days: u32,
+    pub cost: u32,
+    pub interception: Option<Interception>,
+}
+
+impl OverworldMap {
+    /// Load a map, rejecting duplicate locations, routes to unknown ones
+    /// or that take no time, and a missing start.
+    pub fn load<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        Self::parse(&fs::read_to_string(path)?)
+    }
+
+    /// [`OverworldMap::load`] from `path` in `assets`.
+    pub fn load_from(assets: &dyn AssetProvider, path: &str) -> io::Result<Self> {
+        Self::parse(&assets.read_to_string(path)?)
+    }
+
+    fn parse(data: &str) -> io::Result<Self> {
+        let map: OverworldMap = serde_json::from_str(data).map_err(invalid)?;
+        let mut seen = HashSet::new();
+        if let Some(l) = map.locations.iter().find(|l| !seen.insert(l.id.as_str())) {
+            return Err(invalid(format!("duplicate location '{}'", l.id)));
+        }
+        if !seen.contains(map.start.as_str()) {
+            return Err(invalid(format!("unknown start location '{}'", map.start)));
+        }
+        for route in &map.routes {
+            if let Some(id) = [&route.from, &route.to].into_iter().find(|id| !seen.contains(id.as_str())) {
+                return Err(invalid(format!("route references unknown location '{}'", id)));
+            }
+            if route.days == 0 {
+                return Err(invalid(format!("route from '{}' to '{}' takes no time", route.from, route.to)));
+            }
+        }
+        Ok(map)
+    }
+
+    pub fn location(&self, id: &str) -> Option<&Location> {
+        self.locations.iter().find(|l| l.id == id)
+    }
+
+    pub fn route(&self, a: &str, b: &str) -> Option<&Route> {
+        self.routes.iter().find(|r| r.joins(a, b))
+    }
+
+    /// Locations one route away from `id`.
+    pub fn neighbours(&self, id: &str) -> Vec<&Location> {
+        self.locations.iter().filter(|l| l.id != id && self.route(id, &l.id).is_some()).collect()
+    }
+}
+
+impl Campaign {
+    /// Id of the squad's location on `map`.
+    pub fn location<'a>(&'a self, map: &'a OverworldMap) -> &'a str {
+        if self.overworld.location.is_empty() { &map.start } else { &self.overworld.location }
+    }
+
+    /// Faction holding `location`, unless the squad has liberated it.
+    pub fn controller(&self, map: &OverworldMap, location: &str) -> Option<Faction> {
+        if self.overworld.liberated.contains(location) {
+            return None;
+        }
+        map.location(location).and_then(|l| l.controller.clone())
+    }
+
+    /// Travel to a neighbouring location, paying the route's cost and
+    /// adding its days. Arriving in enemy territory may be intercepted.
+    pub fn travel(&mut self, map: &OverworldMap, to: &str, rng: &mut GameRng) -> Result<Travel, &'static str> {
+        let from = self.location(map);
+        if from == to {
+            return Err("already at location");
+        }
+        let route = map.route(from, to).ok_or("no route to location")?;
+        if route.cost > self.currency {
+            return Err("insufficient requisition");
+        }
+        let destination = map.location(to).ok_or("no route to location")?;
+        self.currency -= route.cost;
+        self.overworld.day += route.days;
+        self.overworld.location = to.to_string();
+        let interception = self
+            .controller(map, to)
+            .filter(|_| rng.roll_d100() as u32 <= destination.interception)
+            .map(|faction| Interception { location: to.to_string(), faction });
+        Ok(Travel { days: route.days, cost: route.cost, interception })
+    }
+
+    /// Take `location` back from the enemy, after winning there.
+    pub fn liberate(&mut self, location: &str) {
+        self.overworld.liberated.insert(location.to_string());
+    }
+}
@@ -0,0 +1,248 @@
+use crate::combat::{manhattan, AttackModifiers, CombatEncounter};
+use crate::command::Command;
+use crate::damage::{mitigate, resist};
+use crate::grenade::{blast_cells, distance, throw_hit_chance, throw_range, GRENADE_AP_COST};
+use crate::indirect::indirect_hit_chance;
+use crate::loot::PICKUP_AP_COST;
+use crate::models::{AbilityType, Accessory, Unit, Weapon};
+use crate::registry::Side;
+
+/// One of the actions on a unit's action bar.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ActionKind {
+    Attack,
+    Ability { ability_index: usize },
+    Grenade { accessory_index: usize },
+    PickUp,
+}
+
+impl ActionKind {
+    /// Whether pinned units are kept from it, as with
+    /// [`Command::is_offensive`].
+    fn is_offensive(&self) -> bool {
+        !matches!(self, ActionKind::PickUp)
+    }
+}
+
+/// An action and what it costs, for its button.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ActionCost {
+    pub kind: ActionKind,
+    pub ap_cost: u32,
+    /// Whether the unit can take it now: it has the AP, and no cooldown,
+    /// pinning or charging ability stands in the way.
+    pub affordable: bool,
+}
+
+/// What an action should do, before the dice are rolled.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Expectation {
+    /// Chance, from 0 to 1, that it lands where aimed.
+    pub hit_chance: f32,
+    /// Damage to opponents if it lands, after mitigation and no more than
+    /// the health they have left. Criticals are not counted.
+    pub damage: i32,
+    /// Damage to the unit's own side if it lands, from a blast.
+    pub friendly_damage: i32,
+    /// Health restored if it lands, up to each target's maximum.
+    pub healing: i32,
+}
+
+impl Expectation {
+    /// Health expected to swing the unit's way.
+    pub fn value(&self) -> f32 {
+        self.hit_chance * (self.damage + self.healing - self.friendly_damage) as f32
+    }
+}
+
+/// An affordable action aimed at one target.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlannedAction {
+    pub kind: ActionKind,
+    pub command: Command,
+    pub ap_cost: u32,
+    pub expected: Expectation,
+}
+
+/// Lays out what a unit can do with the AP it has left: every action with
+/// its cost, and every affordable one on each target in reach with what
+/// it should achieve. Terrain-shaping abilities, summons and charges are
+/// left to their own previews.
+#[derive(Debug, Clone, Copy)]
+pub struct ActionPlanner<'a> {
+    encounter: &'a CombatEncounter,
+    unit: &'a Unit,
+    side: Side,
+}
+
+impl<'a> ActionPlanner<'a> {
+    /// `None` for an unknown or downed unit.
+    pub fn new(encounter: &'a CombatEncounter, unit_id: &str) -> Option<Self> {
+        let id = encounter.units.id(unit_id)?;
+        let unit = &encounter.units[id];
+        (unit.health_points > 0).then_some(Self { encounter, unit, side: encounter.units.side(id)? })
+    }
+
+    /// The unit's actions in action-bar order: weapon, abilities,
+    /// grenades, then picking up loot when there is some underfoot.
+    pub fn costs(&self) -> Vec<ActionCost> {
+        let unit = self.unit;
+        let mut costs = Vec::new();
+        if let Some(weapon) = &unit.equipment.weapon {
+            costs.push((ActionKind::Attack, weapon.action_point_cost, true));
+        }
+        for (ability_index, ability) in unit.abilities.iter().enumerate() {
+            costs.push((ActionKind::Ability { ability_index }, ability.action_point_cost, ability.current_cooldown == 0));
+        }
+        for (accessory_index, accessory) in unit.equipment.accessory_slots.iter().enumerate() {
+            if let Accessory::Grenade { .. } = accessory {
+                costs.push((ActionKind::Grenade { accessory_index }, GRENADE_AP_COST, true));
+            }
+        }
+        if self.encounter.battlefield.loot_at(&unit.grid_position).next().is_some() {
+            costs.push((ActionKind::PickUp, PICKUP_AP_COST, true));
+        }
+        let held_back = |kind: ActionKind| unit.is_charging() || (kind.is_offensive() && unit.is_pinned());
+        costs
+            .into_iter()
+            .map(|(kind, ap_cost, ready)| ActionCost {
+                kind,
+                ap_cost,
+                affordable: ready && ap_cost <= unit.action_points && !held_back(kind),
+            })
+            .collect()
+    }
+
+    /// Every affordable action on every target in reach, best
+    /// [`Expectation::value`] first.
+    pub fn actions(&self) -> Vec<PlannedAction> {
+        let mut actions: Vec<PlannedAction> =
+            self.costs().into_iter().filter(|c| c.affordable).flat_map(|c| self.aimed(c)).collect();
+        actions.sort_by(|a, b| b.expected.value().total_cmp(&a.expected.value()));
+        actions
+    }
+
+    /// The play worth the most, if any is worth anything.
+    pub fn suggestion(&self) -> Option<PlannedAction> {
+        self.actions().into_iter().next().filter(|a| a.expected.value() > 0.0)
+    }
+
+    /// `cost`'s action on each target it can reach.
+    fn aimed(&self, cost: ActionCost) -> Vec<PlannedAction> {
+        let unit = self.unit;
+        let plan = |command, expected| PlannedAction { kind: cost.kind, command, ap_cost: cost.ap_cost, expected };
+        let in_reach = |range: u32| {
+            self.living(self.side.opponent()).filter(move |t| manhattan(&unit.grid_position, &t.grid_position) <= range)
+        };
+        match cost.kind {
+            ActionKind::Attack => {
+                let Some(weapon) = &unit.equipment.weapon else { return Vec::new() };
+                if let Some(indirect) = weapon.indirect {
+                    // Shells go over walls, at a cell rather than a unit.
+                    let reach = indirect.min_range()..=weapon.range;
+                    return self
+                        .living(self.side.opponent())
+                        .filter(|t| reach.contains(&distance(&unit.grid_position, &t.grid_position)))
+                        .map(|t| {
+                            let chance = indirect_hit_chance(&unit.current_stats, weapon, distance(&unit.grid_position, &t.grid_position));
+                            let cells = blast_cells(&self.encounter.battlefield, &t.grid_position, indirect.aoe_radius);
+                            let blast = |side| {
+                                self.living(side)
+                                    .filter(|u| cells.contains(&u.grid_position))
+                                    .map(|u| {
+                                        let raw = weapon.damage - u.hull_armor(&unit.grid_position, weapon);
+                                        mitigate(raw, weapon.damage_type, u).min(u.health_points)
+                                    })
+                                    .sum()
+                            };
+                            let expected = Expectation {
+                                hit_chance: (chance as f32 / 100.0).clamp(0.0, 1.0),
+                                damage: blast(self.side.opponent()),
+                                friendly_damage: blast(self.side),
+                                healing: 0,
+                            };
+                            plan(Command::Bombard { attacker_id: unit.id.clone(), target: t.grid_position.clone() }, expected)
+                        })
+                        .collect();
+                }
+                in_reach(weapon.range)
+                    .filter(|t| weapon.is_melee() || self.encounter.battlefield.has_line_of_sight(&unit.grid_position, &t.grid_position))
+                    .map(|t| {
+                        let command = Command::Attack { attacker_id: unit.id.clone(), target_id: t.id.clone() };
+                        plan(command, self.attack(weapon, t))
+                    })
+                    .collect()
+            }
+            ActionKind::Ability { ability_index } => {
+                let ability = &unit.abilities[ability_index];
+                if ability.effect.alters_terrain.is_some() || matches!(ability.ability_type, AbilityType::Summon) {
+                    return Vec::new();
+                }
+                let helpful = matches!(ability.ability_type, AbilityType::Healing | AbilityType::Buff);
+                let side = if helpful { self.side } else { self.side.opponent() };
+                let effect = &ability.effect;
+                self.living(side)
+                    .filter(|t| manhattan(&unit.grid_position, &t.grid_position) <= ability.range)
+                    .map(|t| {
+                        let damage = effect.damage.map_or(0, |d| resist(d, effect.damage_type, t).min(t.health_points));
+                        let healing = effect.healing.map_or(0, |h| h.min(t.current_stats.max_health - t.health_points));
+                        let (damage, friendly_damage) = if helpful { (0, damage) } else { (damage, 0) };
+                        let expected = Expectation { hit_chance: 1.0, damage, friendly_damage, healing };
+                        let command = Command::UseAbility { user_id: unit.id.clone(), ability_index, target_ids: vec![t.id.clone()] };
+                        plan(command, expected)
+                    })
+                    .collect()
+            }
+            ActionKind::Grenade { accessory_index } => {
+                let Some(Accessory::Grenade { damage, aoe_radius, .. }) = unit.equipment.accessory_slots.get(accessory_index) else {
+                    return Vec::new();
+                };
+                let reach = throw_range(&unit.current_stats);
+                self.living(self.side.opponent())
+                    .filter(|t| distance(&unit.grid_position, &t.grid_position) <= reach)
+                    .map(|t| {
+                        let chance = throw_hit_chance(&unit.current_stats, distance(&unit.grid_position, &t.grid_position));
+                        let cells = blast_cells(&self.encounter.battlefield, &t.grid_position, *aoe_radius);
+                        let blast = |side| {
+                            self.living(side).filter(|u| cells.contains(&u.grid_position)).map(|u| (*damage).min(u.health_points)).sum()
+                        };
+                        let expected = Expectation {
+                            hit_chance: (chance as f32 / 100.0).clamp(0.0, 1.0),
+                            damage: blast(self.side.opponent()),
+                            friendly_damage: blast(self.side),
+                            healing: 0,
+                        };
+                        let command = Command::ThrowGrenade { thrower_id: unit.id.clone(), accessory_index, target: t.grid_position.clone() };
+                        plan(command, expected)
+                    })
+                    .collect()
+            }
+            ActionKind::PickUp => vec![plan(Command::PickUp { unit_id: unit.id.clone() }, Expectation::default())],
+        }
+    }
+
+    /// A shot with `weapon` at `target`, worked out as
+    /// [`CombatEncounter::execute`] would resolve it.
+    fn attack(&self, weapon: &Weapon, target: &Unit) -> Expectation {
+        let (encounter, unit) = (self.encounter, self.unit);
+        let charging = encounter.moved_this_turn.contains(&unit.id);
+        let mods = AttackModifiers::from_stacks(&encounter.faction_modifiers, unit, target, weapon, charging, 0)
+            .in_weather(encounter.weather, weapon);
+        let cover = mods.cover + target.stance.map_or(0, |s| s.cover_bonus());
+        let (flank_accuracy, flank_damage) = encounter.balance.flanking.bonus(target, &unit.grid_position);
+        let accuracy = unit.current_stats.accuracy + mods.accuracy + flank_accuracy;
+        let chance =
+            encounter.balance.hit_chance(&unit.current_stats, weapon.accuracy, accuracy, target.current_stats.agility, cover);
+        let melee_bonus = if weapon.is_melee() { unit.current_stats.melee_damage } else { 0 };
+        let raw = weapon.damage + unit.current_stats.strength + melee_bonus + mods.damage + flank_damage;
+        Expectation {
+            hit_chance: (chance / 100.0).clamp(0.0, 1.0),
+            damage: mitigate(raw - target.hull_armor(&unit.grid_position, weapon), weapon.damage_type, target).min(target.health_points),
+            ..Expectation::default()
+        }
+    }
+
+    fn living(&self, side: Side) -> impl Iterator<Item = &'a Unit> {
+        self.encounter.units.on_side(side).filter(|u| u.health_points > 0 && u.embarked_in.is_none())
+    }
+}
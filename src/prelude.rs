@@ -0,0 +1,19 @@
+pub use crate::balance::BalanceConfig;
+pub use crate::builders::{AbilityBuilder, UnitBuilder, WeaponBuilder};
+pub use crate::campaign::Campaign;
+pub use crate::combat::{AttackResult, CombatEncounter, CombatEvent, Controller};
+pub use crate::command::Command;
+pub use crate::damage::DamageType;
+pub use crate::flow::GameFlow;
+pub use crate::grid::{GridMap, TerrainType};
+pub use crate::models::{
+    Ability, AbilityType, AreaOfEffect, Armor, ArmorTier, EffectType, Faction, MovementType, Position, Stats, Unit,
+    UnitType, Weapon, WeaponTier,
+};
+pub use crate::ranks::AbilityRank;
+pub use crate::rng::GameRng;
+#[cfg(not(test))]
+pub use crate::runner::run_game;
+pub use crate::runner::RunConfig;
+pub use crate::state::GameState;
+pub use crate::weather::Weather;
@@ -0,0 +1,171 @@
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::audio::AudioSettings;
+use crate::input::KeyBindings;
+use crate::localization::BASE_LANGUAGE;
+use crate::ui::options::{AccessibilitySettings, ConfirmationSettings, DisplaySettings, HintSettings, OptionsMenu, RenderSettings};
+
+/// File name of the settings inside [`config_dir`].
+pub const SETTINGS_FILE: &str = "settings.json";
+
+/// How hard the enemy plays.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Difficulty {
+    Easy,
+    #[default]
+    Normal,
+    Hard,
+}
+
+/// Every player-facing option, as stored on disk. Settings missing from
+/// the file keep their defaults, so older files still load.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    pub audio: AudioSettings,
+    pub accessibility: AccessibilitySettings,
+    pub keybindings: KeyBindings,
+    pub difficulty: Difficulty,
+    pub language: String,
+    pub display: DisplaySettings,
+    pub render: RenderSettings,
+    pub confirmations: ConfirmationSettings,
+    pub hints: HintSettings,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            audio: AudioSettings::default(),
+            accessibility: AccessibilitySettings::default(),
+            keybindings: KeyBindings::default(),
+            difficulty: Difficulty::default(),
+            language: BASE_LANGUAGE.to_string(),
+            display: DisplaySettings::default(),
+            render: RenderSettings::default(),
+            confirmations: ConfirmationSettings::default(),
+            hints: HintSettings::default(),
+        }
+    }
+}
+
+impl Settings {
+    /// The options screen's view of these settings.
+    pub fn options(&self) -> OptionsMenu {
+        OptionsMenu {
+            accessibility: self.accessibility.clone(),
+            language: self.language.clone(),
+            display: self.display.clone(),
+            render: self.render.clone(),
+            confirmations: self.confirmations.clone(),
+            hints: self.hints.clone(),
+        }
+    }
+
+    /// Take the settings the options screen edits from `options`.
+    pub fn set_options(&mut self, options: &OptionsMenu) {
+        self.accessibility = options.accessibility.clone();
+        self.language = options.language.clone();
+        self.display = options.display.clone();
+        self.render = options.render.clone();
+        self.confirmations = options.confirmations.clone();
+        self.hints = options.hints.clone();
+    }
+}
+
+/// The game's directory under the platform config directory, e.g.
+/// `~/.config/gero` on Linux or `%APPDATA%\gero` on Windows.
+pub fn config_dir() -> Option<PathBuf> {
+    dirs::config_dir().map(|dir| dir.join("gero"))
+}
+
+type Listener = Box<dyn FnMut(&Settings) + Send>;
+
+/// The player's settings and the file they live in. Changes go through
+/// [`Profile::update`], which saves them and tells every listener, so
+/// audio, rendering and input can apply them while the game runs.
+pub struct Profile {
+    settings: Settings,
+    /// File written on every change; `None` keeps the profile in memory.
+    pub path: Option<PathBuf>,
+    listeners: Vec<Listener>,
+}
+
+impl fmt::Debug for Profile {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Profile")
+            .field("settings", &self.settings)
+            .field("path", &self.path)
+            .field("listeners", &self.listeners.len())
+            .finish()
+    }
+}
+
+impl Default for Profile {
+    fn default() -> Self {
+        Self::new(Settings::default())
+    }
+}
+
+impl Profile {
+    /// An in-memory profile with `settings`.
+    pub fn new(settings: Settings) -> Self {
+        Self { settings, path: None, listeners: Vec::new() }
+    }
+
+    /// Load settings from `path`, starting from the defaults if the file
+    /// does not exist yet. Later changes are saved back to `path`.
+    pub fn load<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        let settings = match fs::read_to_string(&path) {
+            Ok(data) => serde_json::from_str(&data).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Settings::default(),
+            Err(e) => return Err(e),
+        };
+        Ok(Self { path: Some(path.as_ref().to_path_buf()), ..Self::new(settings) })
+    }
+
+    /// [`Profile::load`] from [`SETTINGS_FILE`] in [`config_dir`].
+    pub fn load_default() -> io::Result<Self> {
+        let dir = config_dir().ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "no config directory"))?;
+        Self::load(dir.join(SETTINGS_FILE))
+    }
+
+    pub fn settings(&self) -> &Settings {
+        &self.settings
+    }
+
+    /// Write the settings to `path`, creating its directory if needed.
+    pub fn save(&self) -> io::Result<()> {
+        let Some(path) = &self.path else { return Ok(()) };
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let data = serde_json::to_string_pretty(&self.settings).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        fs::write(path, data)
+    }
+
+    /// Call `listener` with the new settings after every change.
+    pub fn on_change(&mut self, listener: impl FnMut(&Settings) + Send + 'static) {
+        self.listeners.push(Box::new(listener));
+    }
+
+    /// Apply `change` to the settings. If anything changed, listeners are
+    /// told and the file is saved; the change stays applied even if saving
+    /// fails.
+    pub fn update(&mut self, change: impl FnOnce(&mut Settings)) -> io::Result<()> {
+        let before = self.settings.clone();
+        change(&mut self.settings);
+        if self.settings == before {
+            return Ok(());
+        }
+        for listener in &mut self.listeners {
+            listener(&self.settings);
+        }
+        self.save()
+    }
+}
@@ -0,0 +1,86 @@
+use serde::{Deserialize, Serialize};
+
+use crate::campaign::Campaign;
+use crate::models::{Ability, AreaOfEffect, Unit};
+
+/// What an ability changes to on reaching a rank past the first. Fields
+/// left out keep the value of the rank below.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AbilityRank {
+    pub damage: Option<i32>,
+    pub healing: Option<i32>,
+    /// Radius of a cone or circle, or length of a line.
+    pub area: Option<u32>,
+    pub cooldown: Option<u32>,
+}
+
+pub(crate) fn first_rank() -> u32 {
+    1
+}
+
+impl Ability {
+    /// The highest rank the ability can reach.
+    pub fn max_rank(&self) -> u32 {
+        1 + self.ranks.len() as u32
+    }
+
+    /// The upgrade the next rank brings, or `None` at the top rank.
+    pub fn next_rank(&self) -> Option<&AbilityRank> {
+        self.ranks.get(self.rank.saturating_sub(1) as usize)
+    }
+
+    /// Raise the ability one rank, applying that rank's values.
+    pub fn rank_up(&mut self) -> Result<(), &'static str> {
+        let next = self.next_rank().ok_or("ability at max rank")?.clone();
+        if let Some(damage) = next.damage {
+            self.effect.damage = Some(damage);
+        }
+        if let Some(healing) = next.healing {
+            self.effect.healing = Some(healing);
+        }
+        if let Some(size) = next.area {
+            match &mut self.area_of_effect {
+                Some(AreaOfEffect::Cone { radius } | AreaOfEffect::Circle { radius }) => *radius = size,
+                Some(AreaOfEffect::Line { length }) => *length = size,
+                None => return Err("ability has no area"),
+            }
+        }
+        if let Some(cooldown) = next.cooldown {
+            self.cooldown = cooldown;
+            self.current_cooldown = self.current_cooldown.min(cooldown);
+        }
+        self.rank += 1;
+        Ok(())
+    }
+
+    /// Whether every rank can be applied in turn; a rank can't widen an
+    /// area the ability doesn't have.
+    pub(crate) fn ranks_valid(&self) -> bool {
+        self.area_of_effect.is_some() || self.ranks.iter().all(|r| r.area.is_none())
+    }
+}
+
+impl Unit {
+    /// Spend a rank point raising the ability `ability_id` one rank.
+    pub fn upgrade_ability(&mut self, ability_id: &str) -> Result<(), &'static str> {
+        let ability = self.abilities.iter_mut().find(|a| a.id == ability_id).ok_or("unknown ability")?;
+        if ability.next_rank().is_none() {
+            return Err("ability at max rank");
+        }
+        if self.rank_points == 0 {
+            return Err("no rank points");
+        }
+        ability.rank_up()?;
+        self.rank_points -= 1;
+        Ok(())
+    }
+}
+
+impl Campaign {
+    /// Have roster unit `unit_id` raise `ability_id` one rank.
+    pub fn upgrade_ability(&mut self, unit_id: &str, ability_id: &str) -> Result<(), &'static str> {
+        let unit = self.roster.iter_mut().find(|u| u.id == unit_id).ok_or("unknown unit")?;
+        unit.upgrade_ability(ability_id)
+    }
+}
@@ -0,0 +1,176 @@
+use std::collections::HashSet;
+
+use serde::{Deserialize, Serialize};
+
+use crate::combat::{manhattan, AttackModifiers, CombatEncounter, CombatEvent};
+use crate::models::{AbilityType, Trait, Unit};
+use crate::modifiers::{Modifier, ModifierSource, Stat};
+
+/// Tiles within which a vengeful unit sees an ally fall.
+pub const VENGEFUL_RANGE: u32 = 3;
+/// Strength a vengeful unit gains when an ally falls.
+pub const VENGEFUL_STRENGTH: i32 = 2;
+/// Turns the vengeful strength bonus lasts.
+pub const VENGEFUL_TURNS: u32 = 2;
+
+/// What a [`Reaction`] responds to, from the reacting unit's side.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ReactionTrigger {
+    /// The unit is hurt; with `melee_only`, only by a melee weapon.
+    OnHit { melee_only: bool },
+    /// The unit itself falls.
+    OnDeath,
+    /// A living ally within `range` tiles is hurt.
+    OnAllyDamaged { range: u32 },
+    /// An ally within `range` tiles falls.
+    OnAllyDeath { range: u32 },
+}
+
+/// What a unit does when its reaction fires. Reactions cost no AP.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ReactionEffect {
+    /// Gain a timed modifier.
+    Modifier { stat: Stat, amount: i32, turns: u32 },
+    /// Strike back at the attacker with the equipped weapon, if in range.
+    Retaliate,
+    /// Use the unit's ability `ability_id` if it is off cooldown: healing
+    /// and buffs on the unit that was hurt, anything else on the attacker.
+    Ability { ability_id: String },
+}
+
+/// An automatic response to something happening in battle.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Reaction {
+    pub trigger: ReactionTrigger,
+    pub effect: ReactionEffect,
+}
+
+impl Trait {
+    /// The reaction the trait gives, if any.
+    pub fn reaction(&self) -> Option<Reaction> {
+        match self {
+            Trait::Vengeful => Some(Reaction {
+                trigger: ReactionTrigger::OnAllyDeath { range: VENGEFUL_RANGE },
+                effect: ReactionEffect::Modifier { stat: Stat::Strength, amount: VENGEFUL_STRENGTH, turns: VENGEFUL_TURNS },
+            }),
+            Trait::Riposte => Some(Reaction {
+                trigger: ReactionTrigger::OnHit { melee_only: true },
+                effect: ReactionEffect::Retaliate,
+            }),
+            Trait::SteadyHands | Trait::Brute | Trait::Fleet | Trait::Stealthy => None,
+        }
+    }
+}
+
+impl Unit {
+    /// The unit's own reactions followed by its traits'.
+    pub fn all_reactions(&self) -> Vec<Reaction> {
+        let mut reactions = self.reactions.clone();
+        reactions.extend(self.traits.iter().filter_map(Trait::reaction));
+        reactions
+    }
+}
+
+/// A unit being hurt, as seen by the reactions that might fire.
+struct Hurt {
+    unit_id: String,
+    died: bool,
+    /// The attacker and whether it struck in melee, when known.
+    source: Option<(String, bool)>,
+}
+
+impl CombatEncounter {
+    /// Fire the reactions of every unit to the harm done since event
+    /// `since`, and in turn to the harm those reactions do. Each reaction
+    /// fires at most once per call, so two units can't retaliate against
+    /// each other forever.
+    pub(crate) fn react(&mut self, since: usize) {
+        let mut fired: HashSet<(String, usize)> = HashSet::new();
+        let mut cursor = since;
+        while cursor < self.events.len() {
+            cursor += 1;
+            let Some(hurt) = self.hurt_by(since, cursor - 1) else { continue };
+            let due: Vec<(String, Reaction)> = self
+                .units
+                .iter()
+                .flat_map(|unit| unit.all_reactions().into_iter().enumerate().map(move |(i, r)| (unit, i, r)))
+                .filter(|(unit, _, reaction)| self.reaction_fires(unit, &reaction.trigger, &hurt))
+                .filter(|(unit, i, _)| fired.insert((unit.id.clone(), *i)))
+                .map(|(unit, _, reaction)| (unit.id.clone(), reaction))
+                .collect();
+            for (unit_id, reaction) in due {
+                self.events.push(CombatEvent::UnitReacted { unit_id: unit_id.clone() });
+                self.apply_reaction(&unit_id, &reaction.effect, &hurt);
+            }
+        }
+    }
+
+    /// The harm event `at` records, with its attacker from the events
+    /// before it.
+    fn hurt_by(&self, since: usize, at: usize) -> Option<Hurt> {
+        let CombatEvent::UnitDamaged { unit_id, .. } = &self.events[at] else { return None };
+        let source = self.events[since..at].iter().rev().find_map(|e| match e {
+            CombatEvent::UnitAttacked { attacker_id, target_id, melee } if target_id == unit_id => {
+                Some((attacker_id.clone(), *melee))
+            }
+            _ => None,
+        });
+        let died = self.unit_by_id(unit_id)?.health_points <= 0;
+        Some(Hurt { unit_id: unit_id.clone(), died, source })
+    }
+
+    fn reaction_fires(&self, unit: &Unit, trigger: &ReactionTrigger, hurt: &Hurt) -> bool {
+        let Some(victim) = self.unit_by_id(&hurt.unit_id) else { return false };
+        let own = unit.id == hurt.unit_id;
+        let ally = !own
+            && self.units.id(&unit.id).and_then(|id| self.units.side(id))
+                == self.units.id(&victim.id).and_then(|id| self.units.side(id));
+        let near = |range: u32| manhattan(&unit.grid_position, &victim.grid_position) <= range;
+        let alive = unit.health_points > 0;
+        match *trigger {
+            ReactionTrigger::OnHit { melee_only } => {
+                own && alive && (!melee_only || hurt.source.as_ref().is_some_and(|(_, melee)| *melee))
+            }
+            ReactionTrigger::OnDeath => own && hurt.died,
+            ReactionTrigger::OnAllyDamaged { range } => ally && alive && !hurt.died && near(range),
+            ReactionTrigger::OnAllyDeath { range } => ally && alive && hurt.died && near(range),
+        }
+    }
+
+    fn apply_reaction(&mut self, unit_id: &str, effect: &ReactionEffect, hurt: &Hurt) {
+        let attacker = hurt.source.as_ref().map(|(id, _)| id.as_str());
+        match effect {
+            ReactionEffect::Modifier { stat, amount, turns } => {
+                if let Some(unit) = self.unit_by_id_mut(unit_id) {
+                    unit.add_modifier(Modifier::timed(ModifierSource::Status, *stat, *amount, *turns));
+                }
+            }
+            ReactionEffect::Retaliate => {
+                let Some(attacker) = attacker else { return };
+                let Some(unit) = self.unit_by_id_mut(unit_id) else { return };
+                let Some(cost) = unit.equipment.weapon.as_ref().map(|w| w.action_point_cost) else { return };
+                let action_points = std::mem::replace(&mut unit.action_points, cost);
+                let roll = self.rng.roll_d100();
+                let _ = self.attack_unit(unit_id, attacker, roll, AttackModifiers::default());
+                if let Some(unit) = self.unit_by_id_mut(unit_id) {
+                    unit.action_points = action_points;
+                }
+            }
+            ReactionEffect::Ability { ability_id } => {
+                let Some(unit) = self.unit_by_id_mut(unit_id) else { return };
+                let Some(index) = unit.abilities.iter().position(|a| a.id == *ability_id) else { return };
+                let ability = &unit.abilities[index];
+                let target = match ability.ability_type {
+                    AbilityType::Healing | AbilityType::Buff => Some(hurt.unit_id.as_str()),
+                    _ => attacker,
+                };
+                let Some(target) = target.map(str::to_string) else { return };
+                let action_points = std::mem::replace(&mut unit.action_points, ability.action_point_cost);
+                let _ = self.execute_ability(unit_id, index, &[target]);
+                if let Some(unit) = self.unit_by_id_mut(unit_id) {
+                    unit.action_points = action_points;
+                }
+            }
+        }
+    }
+}
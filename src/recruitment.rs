@@ -0,0 +1,169 @@
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use serde::Deserialize;
+
+use crate::campaign::Campaign;
+use crate::ioutil::invalid;
+use crate::models::{Ability, Faction, LoreQuestion, RecruitmentChallenge, Stats, Trait, Unit, UnitType};
+use crate::rng::GameRng;
+
+/// Lore questions and the starting template for one recruitable unit type,
+/// loaded from `<dir>/<unit_type_key>.json`. Question, option and
+/// explanation strings are localization keys.
+#[derive(Debug, Clone, Deserialize)]
+pub struct QuestionPool {
+    pub unit_type: UnitType,
+    pub faction: Faction,
+    pub required_correct_answers: u32,
+    /// Number of questions drawn from the pool for one challenge.
+    pub questions_per_challenge: usize,
+    pub base_stats: Stats,
+    #[serde(default)]
+    pub abilities: Vec<Ability>,
+    #[serde(default)]
+    pub sprite_id: String,
+    pub questions: Vec<LoreQuestion>,
+}
+
+/// File stem used for a unit type's question pool.
+pub fn unit_type_key(unit_type: &UnitType) -> &'static str {
+    match unit_type {
+        UnitType::SpaceMarine => "space_marine",
+        UnitType::Guardsman => "guardsman",
+        UnitType::Commissar => "commissar",
+        UnitType::TechPriest => "tech_priest",
+        UnitType::OrkBoy => "ork_boy",
+        UnitType::OrkNob => "ork_nob",
+        UnitType::Weirdboy => "weirdboy",
+        UnitType::Cultist => "cultist",
+        UnitType::ChaosMarine => "chaos_marine",
+        UnitType::Daemon => "daemon",
+        UnitType::Chimera => "chimera",
+        UnitType::Trukk => "trukk",
+    }
+}
+
+impl QuestionPool {
+    /// Load and validate a pool: every question needs a valid answer index,
+    /// and a challenge must be winnable with the questions it draws.
+    pub fn load<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        let data = fs::read_to_string(path)?;
+        let pool: QuestionPool = serde_json::from_str(&data).map_err(|e| invalid(e.to_string()))?;
+        for (i, q) in pool.questions.iter().enumerate() {
+            if q.correct_answer_index >= q.options.len() {
+                return Err(invalid(format!("question {} has no option {}", i, q.correct_answer_index)));
+            }
+        }
+        let drawn = pool.questions_per_challenge.min(pool.questions.len());
+        if drawn < pool.required_correct_answers as usize {
+            return Err(invalid(format!(
+                "{} questions cannot reach {} correct answers",
+                drawn, pool.required_correct_answers
+            )));
+        }
+        Ok(pool)
+    }
+
+    /// Load the pool for `unit_type` from `dir`.
+    pub fn load_for<P: AsRef<Path>>(dir: P, unit_type: &UnitType) -> io::Result<Self> {
+        Self::load(dir.as_ref().join(format!("{}.json", unit_type_key(unit_type))))
+    }
+
+    /// Draw a challenge of `questions_per_challenge` shuffled questions.
+    pub fn challenge(&self, unit_name: &str, rng: &mut GameRng) -> RecruitmentChallenge {
+        let mut questions = self.questions.clone();
+        rng.shuffle(&mut questions);
+        questions.truncate(self.questions_per_challenge);
+        RecruitmentChallenge {
+            unit_name: unit_name.to_string(),
+            questions,
+            required_correct_answers: self.required_correct_answers,
+            player_score: 0,
+            is_completed: false,
+        }
+    }
+
+    /// A fresh unit built from this pool's template, with its type's traits.
+    pub fn recruit(&self, id: &str, name: &str) -> Unit {
+        let mut unit = Unit::new(id, name, self.unit_type.clone(), self.faction.clone());
+        unit.base_stats = self.base_stats.clone();
+        unit.abilities = self.abilities.clone();
+        unit.sprite_id = self.sprite_id.clone();
+        unit.traits = Trait::for_unit_type(&self.unit_type);
+        unit.recalculate_stats();
+        unit.health_points = unit.current_stats.max_health;
+        unit.action_points = unit.current_stats.max_action;
+        unit
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RecruitmentOutcome {
+    InProgress,
+    Recruited,
+    /// Too few questions remain to reach the required score.
+    Failed,
+}
+
+/// One run through a recruitment challenge, question by question.
+#[derive(Debug, Clone)]
+pub struct RecruitmentSession {
+    pub pool: QuestionPool,
+    pub challenge: RecruitmentChallenge,
+    pub current_question: usize,
+    /// Whether the previous answer was correct, for feedback.
+    pub last_answer: Option<bool>,
+}
+
+impl RecruitmentSession {
+    pub fn new(pool: QuestionPool, unit_name: &str, rng: &mut GameRng) -> Self {
+        let challenge = pool.challenge(unit_name, rng);
+        Self { pool, challenge, current_question: 0, last_answer: None }
+    }
+
+    pub fn current(&self) -> Option<&LoreQuestion> {
+        if self.outcome() != RecruitmentOutcome::InProgress {
+            return None;
+        }
+        self.challenge.present_question(self.current_question)
+    }
+
+    /// Answer the current question and move on to the next.
+    pub fn answer(&mut self, option: usize) -> Result<bool, &'static str> {
+        if self.current().is_none() {
+            return Err("challenge is over");
+        }
+        let correct = self.challenge.record_answer(self.current_question, option);
+        self.current_question += 1;
+        self.last_answer = Some(correct);
+        Ok(correct)
+    }
+
+    pub fn outcome(&self) -> RecruitmentOutcome {
+        if self.challenge.is_completed {
+            return RecruitmentOutcome::Recruited;
+        }
+        let remaining = self.challenge.questions.len().saturating_sub(self.current_question) as u32;
+        if self.challenge.player_score + remaining < self.challenge.required_correct_answers {
+            RecruitmentOutcome::Failed
+        } else {
+            RecruitmentOutcome::InProgress
+        }
+    }
+
+    /// Add the earned unit to the campaign roster. Returns its id, or
+    /// `None` unless the challenge was won.
+    pub fn finish(&self, campaign: &mut Campaign) -> Option<String> {
+        if self.outcome() != RecruitmentOutcome::Recruited {
+            return None;
+        }
+        let key = unit_type_key(&self.pool.unit_type);
+        let id = (1..)
+            .map(|n| format!("{}_{}", key, n))
+            .find(|id| campaign.roster.iter().all(|u| &u.id != id))?;
+        campaign.roster.push(self.pool.recruit(&id, &self.challenge.unit_name));
+        Some(id)
+    }
+}
@@ -0,0 +1,212 @@
+use std::collections::HashMap;
+use std::ops::{Index, IndexMut};
+
+use serde::{Deserialize, Serialize};
+
+use crate::models::Unit;
+
+/// Handle to a unit in a [`UnitRegistry`]. Units are never removed during a
+/// battle (the dead stay with no health), so a handle stays valid for the
+/// registry's lifetime.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct UnitId(u32);
+
+impl UnitId {
+    fn index(self) -> usize {
+        self.0 as usize
+    }
+}
+
+/// Which side of a battle a unit fights for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Side {
+    Player,
+    Enemy,
+}
+
+impl Side {
+    pub fn opponent(self) -> Side {
+        match self {
+            Side::Player => Side::Enemy,
+            Side::Enemy => Side::Player,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Entry {
+    side: Side,
+    unit: Unit,
+}
+
+/// Arena of every unit in a battle. Units are looked up in O(1) by
+/// [`UnitId`] or by their string id, and [`UnitRegistry::pair_mut`] hands
+/// out an attacker and defender at once.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(from = "Vec<Entry>", into = "Vec<Entry>")]
+pub struct UnitRegistry {
+    entries: Vec<Entry>,
+    ids: HashMap<String, UnitId>,
+}
+
+impl From<Vec<Entry>> for UnitRegistry {
+    fn from(entries: Vec<Entry>) -> Self {
+        let mut registry = UnitRegistry::new();
+        for entry in entries {
+            registry.insert(entry.unit, entry.side);
+        }
+        registry
+    }
+}
+
+impl From<UnitRegistry> for Vec<Entry> {
+    fn from(registry: UnitRegistry) -> Self {
+        registry.entries
+    }
+}
+
+impl UnitRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register `players` then `enemies`, in order.
+    pub fn with_sides(players: Vec<Unit>, enemies: Vec<Unit>) -> Self {
+        let mut registry = Self::new();
+        for unit in players {
+            registry.insert(unit, Side::Player);
+        }
+        for unit in enemies {
+            registry.insert(unit, Side::Enemy);
+        }
+        registry
+    }
+
+    /// Add a unit and return its handle. A unit whose string id is already
+    /// registered keeps the existing handle for lookups by name.
+    pub fn insert(&mut self, unit: Unit, side: Side) -> UnitId {
+        let id = UnitId(self.entries.len() as u32);
+        self.ids.entry(unit.id.clone()).or_insert(id);
+        self.entries.push(Entry { side, unit });
+        id
+    }
+
+    /// Handle of the unit whose string id is `name`.
+    pub fn id(&self, name: &str) -> Option<UnitId> {
+        self.ids.get(name).copied()
+    }
+
+    pub fn get(&self, id: UnitId) -> Option<&Unit> {
+        self.entries.get(id.index()).map(|e| &e.unit)
+    }
+
+    pub fn get_mut(&mut self, id: UnitId) -> Option<&mut Unit> {
+        self.entries.get_mut(id.index()).map(|e| &mut e.unit)
+    }
+
+    /// The unit whose string id is `name`, looked up through its handle.
+    pub fn by_name(&self, name: &str) -> Option<&Unit> {
+        self.get(self.id(name)?)
+    }
+
+    /// Mutable access to the unit whose string id is `name`.
+    pub fn by_name_mut(&mut self, name: &str) -> Option<&mut Unit> {
+        self.get_mut(self.id(name)?)
+    }
+
+    pub fn side(&self, id: UnitId) -> Option<Side> {
+        self.entries.get(id.index()).map(|e| e.side)
+    }
+
+    /// Mutable references to two distinct units. `None` if either handle is
+    /// unknown or both are the same unit.
+    pub fn pair_mut(&mut self, a: UnitId, b: UnitId) -> Option<(&mut Unit, &mut Unit)> {
+        let (i, j) = (a.index(), b.index());
+        if i == j || i.max(j) >= self.entries.len() {
+            return None;
+        }
+        let (low, high) = self.entries.split_at_mut(i.max(j));
+        let (first, second) = (&mut low[i.min(j)].unit, &mut high[0].unit);
+        Some(if i < j { (first, second) } else { (second, first) })
+    }
+
+    /// One unit mutably and any number of others, in the order given.
+    /// Unknown handles and repeats of `main` are skipped.
+    pub fn split_mut(&mut self, main: UnitId, others: &[UnitId]) -> Option<(&mut Unit, Vec<&mut Unit>)> {
+        let mut slots: Vec<Option<&mut Unit>> = self.entries.iter_mut().map(|e| Some(&mut e.unit)).collect();
+        let main = slots.get_mut(main.index())?.take()?;
+        let rest = others.iter().filter_map(|id| slots.get_mut(id.index())?.take()).collect();
+        Some((main, rest))
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Every unit in registration order.
+    pub fn iter(&self) -> impl Iterator<Item = &Unit> {
+        self.entries.iter().map(|e| &e.unit)
+    }
+
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = &mut Unit> {
+        self.entries.iter_mut().map(|e| &mut e.unit)
+    }
+
+    /// Handles of every unit in registration order.
+    pub fn ids(&self) -> impl Iterator<Item = UnitId> + use<> {
+        (0..self.entries.len() as u32).map(UnitId)
+    }
+
+    /// Units fighting for `side`, in registration order.
+    pub fn on_side(&self, side: Side) -> impl Iterator<Item = &Unit> {
+        self.entries.iter().filter(move |e| e.side == side).map(|e| &e.unit)
+    }
+
+    pub fn on_side_mut(&mut self, side: Side) -> impl Iterator<Item = &mut Unit> {
+        self.entries.iter_mut().filter(move |e| e.side == side).map(|e| &mut e.unit)
+    }
+
+    pub fn players(&self) -> impl Iterator<Item = &Unit> {
+        self.on_side(Side::Player)
+    }
+
+    pub fn enemies(&self) -> impl Iterator<Item = &Unit> {
+        self.on_side(Side::Enemy)
+    }
+}
+
+impl Index<UnitId> for UnitRegistry {
+    type Output = Unit;
+
+    fn index(&self, id: UnitId) -> &Unit {
+        &self.entries[id.index()].unit
+    }
+}
+
+impl IndexMut<UnitId> for UnitRegistry {
+    fn index_mut(&mut self, id: UnitId) -> &mut Unit {
+        &mut self.entries[id.index()].unit
+    }
+}
+
+impl<'a> IntoIterator for &'a UnitRegistry {
+    type Item = &'a Unit;
+    type IntoIter = Box<dyn Iterator<Item = &'a Unit> + 'a>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        Box::new(self.iter())
+    }
+}
+
+impl<'a> IntoIterator for &'a mut UnitRegistry {
+    type Item = &'a mut Unit;
+    type IntoIter = Box<dyn Iterator<Item = &'a mut Unit> + 'a>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        Box::new(self.iter_mut())
+    }
+}
@@ -0,0 +1,73 @@
+use crate::base::armory_level_for;
+use crate::campaign::Campaign;
+use crate::content::{ContentDatabase, Item};
+use crate::loadout::armor_allowed;
+
+/// Accessories a single unit can carry.
+pub const MAX_ACCESSORY_SLOTS: usize = 2;
+
+impl Campaign {
+    /// Record a finished mission and its currency reward. Each mission
+    /// unlocks further requisition stock.
+    pub fn complete_mission(&mut self, reward: u32) {
+        self.currency += reward;
+        self.missions_completed += 1;
+    }
+
+    /// Buy an item into the campaign inventory. Equipment tiers must be
+    /// researched first, and weapons above the tiers the armory has
+    /// unlocked can't be bought.
+    pub fn purchase(&mut self, db: &ContentDatabase, item_id: &str) -> Result<(), &'static str> {
+        let entry = db.get(item_id).ok_or("unknown item")?;
+        if entry.unlocks_after > self.missions_completed {
+            return Err("item not yet available");
+        }
+        if !self.tier_unlocked(&entry.item) {
+            return Err("research required");
+        }
+        if let Item::Weapon(weapon) = &entry.item
+            && armory_level_for(&weapon.tier) > self.armory_level
+        {
+            return Err("armory upgrade required");
+        }
+        if entry.price > self.currency {
+            return Err("insufficient requisition");
+        }
+        self.currency -= entry.price;
+        self.grant_item(item_id);
+        Ok(())
+    }
+
+    /// Move an item from the inventory onto a roster unit. A replaced weapon
+    /// or armor goes back into the inventory. Armor must suit the unit type.
+    pub fn equip_from_inventory(&mut self, db: &ContentDatabase, unit_id: &str, item_id: &str) -> Result<(), &'static str> {
+        let slot = self.inventory.iter().position(|i| i == item_id).ok_or("item not in inventory")?;
+        let item = db.get(item_id).ok_or("unknown item")?.item.clone();
+        let unit = self.roster.iter_mut().find(|u| u.id == unit_id).ok_or("unknown unit")?;
+        let replaced = match item {
+            Item::Weapon(weapon) => {
+                let old = unit.unequip_weapon();
+                unit.equip_weapon(weapon);
+                old.map(|w| w.id)
+            }
+            Item::Armor(armor) => {
+                if !armor_allowed(&unit.unit_type, &armor.tier) {
+                    return Err("armor tier not allowed for unit type");
+                }
+                let old = unit.unequip_armor();
+                unit.equip_armor(armor);
+                old.map(|a| a.id)
+            }
+            Item::Accessory(accessory) => {
+                if unit.equipment.accessory_slots.len() >= MAX_ACCESSORY_SLOTS {
+                    return Err("no free accessory slot");
+                }
+                unit.equipment.accessory_slots.push(accessory);
+                None
+            }
+        };
+        self.inventory.remove(slot);
+        self.inventory.extend(replaced);
+        Ok(())
+    }
+}
@@ -0,0 +1,175 @@
+use std::collections::{BTreeSet, HashSet};
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::assets::AssetProvider;
+use crate::campaign::Campaign;
+use crate::content::Item;
+use crate::ioutil::invalid;
+use crate::models::{ArmorTier, WeaponTier};
+
+/// An equipment tier a project makes available in the shop.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TierUnlock {
+    Weapon(WeaponTier),
+    Armor(ArmorTier),
+}
+
+/// A project in the research tree. It costs requisition to start and
+/// takes `missions` missions to finish. `name` is a locale key.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ResearchProject {
+    pub id: String,
+    pub name: String,
+    pub cost: u32,
+    pub missions: u32,
+    /// Projects that must be finished first.
+    #[serde(default)]
+    pub prerequisites: Vec<String>,
+    pub unlocks: Vec<TierUnlock>,
+}
+
+/// The project being researched, with what it will unlock, so it can
+/// finish without the tree at hand.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ActiveResearch {
+    pub project: String,
+    pub name: String,
+    pub missions_remaining: u32,
+    pub unlocks: Vec<TierUnlock>,
+}
+
+/// Research progress, kept in the campaign save.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ResearchState {
+    pub completed: BTreeSet<String>,
+    pub active: Option<ActiveResearch>,
+    /// Tiers unlocked by finished projects.
+    pub unlocked: Vec<TierUnlock>,
+}
+
+/// Where a project stands for a campaign.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProjectStatus {
+    Completed,
+    /// Being researched, with missions left.
+    Active(u32),
+    Available,
+    /// Waiting on a prerequisite.
+    Locked,
+}
+
+/// Every research project, in display order.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ResearchTree {
+    pub projects: Vec<ResearchProject>,
+}
+
+impl ResearchTree {
+    /// Load a tree, rejecting duplicate ids, projects that take no time,
+    /// and prerequisites that are unknown or not listed earlier, which
+    /// rules out cycles.
+    pub fn load<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        Self::parse(&fs::read_to_string(path)?)
+    }
+
+    /// [`ResearchTree::load`] from `path` in `assets`.
+    pub fn load_from(assets: &dyn AssetProvider, path: &str) -> io::Result<Self> {
+        Self::parse(&assets.read_to_string(path)?)
+    }
+
+    fn parse(data: &str) -> io::Result<Self> {
+        let tree: ResearchTree = serde_json::from_str(data).map_err(invalid)?;
+        let mut seen = HashSet::new();
+        for project in &tree.projects {
+            if project.missions == 0 {
+                return Err(invalid(format!("project '{}' takes no time", project.id)));
+            }
+            if let Some(p) = project.prerequisites.iter().find(|p| !seen.contains(p.as_str())) {
+                return Err(invalid(format!("project '{}' requires '{}', which is not listed before it", project.id, p)));
+            }
+            if !seen.insert(project.id.as_str()) {
+                return Err(invalid(format!("duplicate project '{}'", project.id)));
+            }
+        }
+        Ok(tree)
+    }
+
+    pub fn get(&self, id: &str) -> Option<&ResearchProject> {
+        self.projects.iter().find(|p| p.id == id)
+    }
+}
+
+/// Whether tiers are unlocked from the start, without research.
+fn unlocked_by_default(unlock: &TierUnlock) -> bool {
+    matches!(unlock, TierUnlock::Weapon(WeaponTier::Basic) | TierUnlock::Armor(ArmorTier::Flak))
+}
+
+impl Campaign {
+    pub fn project_status(&self, project: &ResearchProject) -> ProjectStatus {
+        let research = &self.research;
+        if research.completed.contains(&project.id) {
+            ProjectStatus::Completed
+        } else if let Some(active) = research.active.as_ref().filter(|a| a.project == project.id) {
+            ProjectStatus::Active(active.missions_remaining)
+        } else if project.prerequisites.iter().all(|p| research.completed.contains(p)) {
+            ProjectStatus::Available
+        } else {
+            ProjectStatus::Locked
+        }
+    }
+
+    /// Spend requisition to start researching a project. One project is
+    /// researched at a time.
+    pub fn start_research(&mut self, tree: &ResearchTree, id: &str) -> Result<(), &'static str> {
+        let project = tree.get(id).ok_or("unknown project")?;
+        match self.project_status(project) {
+            ProjectStatus::Completed => return Err("already researched"),
+            ProjectStatus::Active(_) => return Err("research in progress"),
+            ProjectStatus::Locked => return Err("prerequisites not met"),
+            ProjectStatus::Available => {}
+        }
+        if self.research.active.is_some() {
+            return Err("research in progress");
+        }
+        if project.cost > self.currency {
+            return Err("insufficient requisition");
+        }
+        self.currency -= project.cost;
+        self.research.active = Some(ActiveResearch {
+            project: project.id.clone(),
+            name: project.name.clone(),
+            missions_remaining: project.missions,
+            unlocks: project.unlocks.clone(),
+        });
+        Ok(())
+    }
+
+    /// Count a mission off the active project. Returns it once finished,
+    /// with its tiers unlocked.
+    pub fn advance_research(&mut self) -> Option<ActiveResearch> {
+        let active = self.research.active.as_mut()?;
+        active.missions_remaining = active.missions_remaining.saturating_sub(1);
+        if active.missions_remaining > 0 {
+            return None;
+        }
+        let finished = self.research.active.take()?;
+        self.research.completed.insert(finished.project.clone());
+        self.research.unlocked.extend(finished.unlocks.iter().cloned());
+        Some(finished)
+    }
+
+    /// Whether research has unlocked the item's tier for the shop.
+    /// Accessories have no tier and are always unlocked.
+    pub fn tier_unlocked(&self, item: &Item) -> bool {
+        let unlock = match item {
+            Item::Weapon(w) => TierUnlock::Weapon(w.tier.clone()),
+            Item::Armor(a) => TierUnlock::Armor(a.tier.clone()),
+            Item::Accessory(_) => return true,
+        };
+        unlocked_by_default(&unlock) || self.research.unlocked.contains(&unlock)
+    }
+}
@@ -0,0 +1,51 @@
+use serde::{Deserialize, Serialize};
+
+/// Small deterministic random number generator (SplitMix64). The whole
+/// state is one `u64`, so it serializes with saves and replays exactly from
+/// a seed.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GameRng {
+    state: u64,
+}
+
+impl GameRng {
+    pub fn new(seed: u64) -> Self {
+        Self { state: seed }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// Uniform integer in `low..=high`.
+    pub fn range(&mut self, low: u32, high: u32) -> u32 {
+        if high <= low {
+            return low;
+        }
+        let span = (high - low) as u64 + 1;
+        low + (self.next_u64() % span) as u32
+    }
+
+    /// Percentile roll in `1..=100`, as used by attack resolution.
+    pub fn roll_d100(&mut self) -> u8 {
+        self.range(1, 100) as u8
+    }
+
+    /// Fisher-Yates shuffle in place.
+    pub fn shuffle<T>(&mut self, items: &mut [T]) {
+        for i in (1..items.len()).rev() {
+            let j = self.range(0, i as u32) as usize;
+            items.swap(i, j);
+        }
+    }
+
+    /// Returns true with probability `p` (clamped to 0..=1).
+    pub fn chance(&mut self, p: f32) -> bool {
+        let unit = (self.next_u64() >> 40) as f32 / (1u64 << 24) as f32;
+        unit < p.clamp(0.0, 1.0)
+    }
+}
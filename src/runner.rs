@@ -0,0 +1,476 @@
+#[cfg(not(test))]
+use std::io;
+#[cfg(not(test))]
+use std::sync::mpsc;
+#[cfg(not(test))]
+use std::sync::Arc;
+
+// `std::time::Instant` panics in the browser; this one reads
+// `performance.now()` there and is the std type elsewhere.
+#[cfg(not(test))]
+use web_time::{Duration, Instant};
+
+#[cfg(not(test))]
+use winit::{
+    dpi::PhysicalSize,
+    event::{ElementState, Event, KeyEvent, MouseButton, WindowEvent},
+    event_loop::{ControlFlow, EventLoop, EventLoopWindowTarget},
+    keyboard::{KeyCode, PhysicalKey},
+    window::{Fullscreen, Window, WindowBuilder},
+};
+
+use crate::state::GameState;
+#[cfg(not(test))]
+use crate::{
+    accessibility::Announcer,
+    achievements::{AchievementTracker, ACHIEVEMENTS_PATH, PROFILE_PATH},
+    assets::{streaming::AssetManager, AssetProvider},
+    audio::AudioSystem,
+    flow::GameFlow,
+    frontend::capture::{capture_path, ClipRecorder},
+    frontend::Renderer,
+    input::InputHandler,
+    localization::Localizer,
+    mods::{ModLoader, MODS_DIR},
+    profile::{Profile, Settings},
+    ui::{layout::LayoutContext, options::OptionsMenu, toasts::Toast, UiManager},
+};
+
+/// Settings for [`run_game`].
+#[derive(Debug, Clone)]
+pub struct RunConfig {
+    pub title: String,
+    pub width: u32,
+    pub height: u32,
+    /// Simulation updates per second, independent of the render rate.
+    pub updates_per_second: u32,
+    pub language: String,
+    pub initial_state: GameState,
+}
+
+impl Default for RunConfig {
+    fn default() -> Self {
+        Self {
+            title: "Gero".into(),
+            width: 1280,
+            height: 720,
+            updates_per_second: 60,
+            language: "en".into(),
+            initial_state: GameState::new(Vec::new()),
+        }
+    }
+}
+
+/// Accumulates frame time and reports how many fixed simulation steps to
+/// run. Catch-up is capped so a long stall doesn't cause a burst of updates.
+#[derive(Debug, Clone)]
+pub struct FixedTimestep {
+    pub step: f32,
+    pub max_steps_per_frame: u32,
+    accumulator: f32,
+}
+
+impl FixedTimestep {
+    pub fn new(updates_per_second: u32) -> Self {
+        Self { step: 1.0 / updates_per_second.max(1) as f32, max_steps_per_frame: 5, accumulator: 0.0 }
+    }
+
+    /// Add `elapsed` seconds and return the number of steps now due.
+    pub fn advance(&mut self, elapsed: f32) -> u32 {
+        self.accumulator += elapsed;
+        let mut steps = 0;
+        while self.accumulator >= self.step && steps < self.max_steps_per_frame {
+            self.accumulator -= self.step;
+            steps += 1;
+        }
+        if steps == self.max_steps_per_frame {
+            self.accumulator = self.accumulator.min(self.step);
+        }
+        steps
+    }
+}
+
+/// Caps how often frames are drawn. The simulation keeps its own fixed
+/// rate, so a lower cap only saves power.
+#[derive(Debug, Clone)]
+pub struct FrameLimiter {
+    /// Shortest time between frames in seconds; zero when uncapped.
+    pub min_frame_time: f32,
+}
+
+impl FrameLimiter {
+    pub fn new(fps_cap: Option<u32>) -> Self {
+        Self { min_frame_time: fps_cap.map(|fps| 1.0 / fps.max(1) as f32).unwrap_or(0.0) }
+    }
+
+    /// Seconds to wait before the next frame when `since_last` seconds have
+    /// passed since the previous one.
+    pub fn delay(&self, since_last: f32) -> f32 {
+        (self.min_frame_time - since_last).max(0.0)
+    }
+}
+
+/// IndexedDB database the browser build saves player files in.
+#[cfg(all(not(test), target_arch = "wasm32"))]
+pub const WEB_DATABASE: &str = "gero";
+
+/// Open a window and run the game until it is closed, Escape is pressed,
+/// or the flow requests to quit.
+#[cfg(all(not(test), not(target_arch = "wasm32")))]
+pub fn run_game(config: RunConfig) -> Result<(), Box<dyn std::error::Error>> {
+    pollster::block_on(run(config, Arc::new(crate::assets::FileAssets::default())))
+}
+
+/// Start the game in the page once its assets are fetched. Returns at
+/// once; the browser drives the event loop from then on, and startup
+/// errors go to the console.
+#[cfg(all(not(test), target_arch = "wasm32"))]
+pub fn run_game(config: RunConfig) -> Result<(), Box<dyn std::error::Error>> {
+    wasm_bindgen_futures::spawn_local(async move {
+        let started = match crate::assets::WebAssets::load(WEB_DATABASE).await {
+            Ok(assets) => run(config, Arc::new(assets)).await,
+            Err(e) => Err(e.into()),
+        };
+        if let Err(e) = started {
+            web_sys::console::error_1(&e.to_string().into());
+        }
+    });
+    Ok(())
+}
+
+#[cfg(not(test))]
+async fn run(config: RunConfig, assets: Arc<dyn AssetProvider>) -> Result<(), Box<dyn std::error::Error>> {
+    let event_loop = EventLoop::new()?;
+    let mut profile = Profile::load_default().unwrap_or_default();
+    let mut options = profile.settings().options();
+    let builder = WindowBuilder::new()
+        .with_title(config.title.clone())
+        .with_inner_size(PhysicalSize::new(config.width, config.height))
+        .with_fullscreen(options.display.borderless_fullscreen.then_some(Fullscreen::Borderless(None)));
+    // In the browser the window is a canvas added to the page.
+    #[cfg(target_arch = "wasm32")]
+    let builder = winit::platform::web::WindowBuilderExtWebSys::with_append(builder, true);
+    // The event loop outlives this function in the browser, so the window
+    // it draws to must too.
+    let window: &'static Window = Box::leak(Box::new(builder.build(&event_loop)?));
+
+    let mut renderer = Renderer::new(window).await;
+    renderer.apply_accessibility(&options.accessibility);
+    renderer.set_vsync(options.render.vsync);
+    let mut audio = AudioSystem::new();
+    audio.apply_settings(&profile.settings().audio);
+    audio.apply_accessibility(&options.accessibility);
+    let mut input = InputHandler::new();
+    input.bindings = profile.settings().keybindings.clone();
+    let loc = Localizer::with_assets(assets.clone(), &[&config.language])?.shared();
+    // The player's saved language wins over the launch default; if it
+    // fails to load, the default stays.
+    let language = std::mem::replace(&mut options.language, config.language.clone());
+    if language != config.language {
+        let _ = options.switch_language(&loc, &language);
+    }
+    // Enabled mods go over the base assets. If one fails to load, the mods
+    // after it are skipped.
+    let mods = ModLoader::scan(MODS_DIR).unwrap_or_default();
+    let _ = mods.apply_sprites(&mut renderer);
+    let _ = mods.apply_sounds(&mut audio);
+    if let Ok(mut loc) = loc.write() {
+        let _ = mods.apply_locales(&mut loc);
+    }
+    // Settings changed elsewhere, e.g. on the options screen, reach the
+    // event loop through this channel and are applied between frames.
+    let (settings_tx, settings_rx) = mpsc::channel::<Settings>();
+    profile.on_change(move |settings| {
+        let _ = settings_tx.send(settings.clone());
+    });
+    #[cfg(feature = "text")]
+    let fonts = crate::frontend::text::FontManifest::load("assets/fonts/manifest.json").unwrap_or_default();
+    #[cfg(feature = "text")]
+    let mut font_revision = None;
+    let mut announcer = Announcer::new();
+    #[cfg(feature = "tts")]
+    announcer.on_announce(crate::accessibility::system_speech);
+    let ui = UiManager::with_layout(&layout_for(&renderer, window, &options, input.touch_detected), Vec::new(), Vec::new());
+    let mut flow = GameFlow::new(config.initial_state, ui);
+    flow.ui.fps_counter.visible = options.render.show_fps;
+    flow.confirmations = options.confirmations.clone();
+    flow.hints.settings = options.hints.clone();
+    flow.cinematic_camera = options.display.cinematic_camera;
+    flow.difficulty = profile.settings().difficulty;
+    flow.assets = Some(AssetManager::new(assets.clone()));
+    flow.load_battle_assets();
+    flow.achievements = AchievementTracker::load(ACHIEVEMENTS_PATH, PROFILE_PATH).unwrap_or_default();
+    let mut limiter = FrameLimiter::new(options.render.fps_cap);
+    let mut clips = options.render.clip_seconds.map(ClipRecorder::new);
+
+    let mut timestep = FixedTimestep::new(config.updates_per_second);
+    let mut last_frame = Instant::now();
+    let mut last_redraw = Instant::now();
+    // Pointer position and shift state, for clicks on the battlefield.
+    let mut cursor = (0, 0);
+    let mut shift = false;
+
+    let handler = move |event: Event<()>, target: &EventLoopWindowTarget<()>| {
+        // Keys typed into the open debug console don't reach the game.
+        if let Some(action) = input.process_event_with_audio(&event, Some(&mut audio))
+            && !flow.console.open
+        {
+            flow.handle_action(action);
+        }
+        match event {
+            Event::WindowEvent {
+                event:
+                    WindowEvent::KeyboardInput {
+                        event:
+                            KeyEvent {
+                                physical_key: PhysicalKey::Code(KeyCode::Backquote),
+                                state: ElementState::Pressed,
+                                repeat: false,
+                                ..
+                            },
+                        ..
+                    },
+                ..
+            } => flow.console.open = !flow.console.open,
+            Event::WindowEvent {
+                event:
+                    WindowEvent::KeyboardInput {
+                        event: KeyEvent { physical_key: PhysicalKey::Code(code), state: ElementState::Pressed, text, .. },
+                        ..
+                    },
+                ..
+            } if flow.console.open => match code {
+                KeyCode::Escape => flow.console.open = false,
+                KeyCode::Enter => flow.console.submit(&mut flow.debug),
+                KeyCode::Backspace => {
+                    flow.console.input.pop();
+                }
+                _ => flow.console.input.extend(text.iter().flat_map(|t| t.chars()).filter(|c| !c.is_control())),
+            },
+            // Typing into the open codex searches it.
+            Event::WindowEvent {
+                event:
+                    WindowEvent::KeyboardInput {
+                        event: KeyEvent { physical_key: PhysicalKey::Code(code), state: ElementState::Pressed, text, .. },
+                        ..
+                    },
+                ..
+            } if flow.codex.is_some() && (code == KeyCode::Backspace || text.as_ref().is_some_and(|t| !t.chars().any(char::is_control))) => {
+                if let Some(screen) = &mut flow.codex {
+                    match text {
+                        Some(text) if code != KeyCode::Backspace => screen.type_text(&text),
+                        _ => screen.erase(),
+                    }
+                }
+            }
+            Event::WindowEvent { event: WindowEvent::CloseRequested, .. } => target.exit(),
+            // Escape dismisses an open prompt, then a hint, before it quits.
+            Event::WindowEvent {
+                event:
+                    WindowEvent::KeyboardInput {
+                        event:
+                            KeyEvent {
+                                physical_key: PhysicalKey::Code(KeyCode::Escape),
+                                state: ElementState::Pressed,
+                                ..
+                            },
+                        ..
+                    },
+                ..
+            } => {
+                let dismissed = flow.cancel_modal() || flow.dismiss_hint();
+                if !dismissed {
+                    target.exit();
+                }
+            }
+            Event::WindowEvent { event: WindowEvent::CursorMoved { position, .. }, .. } => {
+                cursor = (position.x as u32, position.y as u32);
+                flow.ui.hover(cursor);
+            }
+            Event::WindowEvent { event: WindowEvent::ModifiersChanged(modifiers), .. } => {
+                shift = modifiers.state().shift_key();
+            }
+            Event::WindowEvent {
+                event: WindowEvent::MouseInput { state: ElementState::Pressed, button: MouseButton::Left, .. },
+                ..
+            } => {
+                flow.select_at(cursor, shift, &renderer);
+            }
+            Event::WindowEvent {
+                event:
+                    WindowEvent::KeyboardInput {
+                        event:
+                            KeyEvent {
+                                physical_key: PhysicalKey::Code(KeyCode::Tab),
+                                state: ElementState::Pressed,
+                                ..
+                            },
+                        ..
+                    },
+                ..
+            } if !flow.console.open => {
+                flow.cycle_selection();
+            }
+            Event::WindowEvent { event: WindowEvent::Resized(size), .. } => {
+                renderer.resize(size.width, size.height);
+                flow.ui.relayout(&layout_for(&renderer, window, &options, input.touch_detected));
+            }
+            Event::WindowEvent {
+                event:
+                    WindowEvent::KeyboardInput {
+                        event:
+                            KeyEvent {
+                                physical_key: PhysicalKey::Code(KeyCode::F11),
+                                state: ElementState::Pressed,
+                                repeat: false,
+                                ..
+                            },
+                        ..
+                    },
+                ..
+            } => {
+                let fullscreen = options.toggle_fullscreen().then_some(Fullscreen::Borderless(None));
+                window.set_fullscreen(fullscreen);
+                // The resize that follows re-lays the UI out.
+                let _ = profile.update(|s| s.display = options.display.clone());
+            }
+            Event::WindowEvent {
+                event:
+                    WindowEvent::KeyboardInput {
+                        event:
+                            KeyEvent {
+                                physical_key: PhysicalKey::Code(KeyCode::F12),
+                                state: ElementState::Pressed,
+                                repeat: false,
+                                ..
+                            },
+                        ..
+                    },
+                ..
+            } => {
+                let path = capture_path("screenshot", "png");
+                let saved = renderer.capture_frame().and_then(|png| assets.write(&path, &png));
+                flow.ui.toasts.push(Toast::capture("toast.screenshot", &path, &saved));
+            }
+            Event::WindowEvent {
+                event:
+                    WindowEvent::KeyboardInput {
+                        event:
+                            KeyEvent {
+                                physical_key: PhysicalKey::Code(KeyCode::F9),
+                                state: ElementState::Pressed,
+                                repeat: false,
+                                ..
+                            },
+                        ..
+                    },
+                ..
+            } => {
+                let path = capture_path("clip", "gif");
+                let saved = match &clips {
+                    Some(clips) => clips.encode_gif().and_then(|gif| assets.write(&path, &gif)),
+                    None => Err(io::Error::new(io::ErrorKind::NotFound, "clip recording is off")),
+                };
+                flow.ui.toasts.push(Toast::capture("toast.clip", &path, &saved));
+            }
+            Event::WindowEvent { event: WindowEvent::RedrawRequested, .. } => {
+                if let Ok(loc) = loc.read() {
+                    // Pick up the new language's font after a switch; if its
+                    // file is missing the previous font stays loaded.
+                    #[cfg(feature = "text")]
+                    if font_revision != Some(loc.revision()) {
+                        font_revision = Some(loc.revision());
+                        let _ = renderer.load_font_for(&fonts, loc.language());
+                    }
+                    flow.render(&mut renderer, &loc);
+                    // A failed present only drops this frame.
+                    let _ = renderer.present();
+                    let now = Instant::now();
+                    if let Some(clips) = &mut clips
+                        && clips.due((now - last_redraw).as_secs_f32())
+                        && let Ok(frame) = renderer.capture_image()
+                    {
+                        clips.push(frame);
+                    }
+                    last_redraw = now;
+                    announcer.observe(&flow.ui, &flow.state.units, &loc);
+                }
+            }
+            Event::AboutToWait => {
+                if flow.quit_requested {
+                    target.exit();
+                    return;
+                }
+                let now = Instant::now();
+                let elapsed = (now - last_frame).as_secs_f32();
+                let delay = limiter.delay(elapsed);
+                if delay > 0.0 {
+                    target.set_control_flow(ControlFlow::WaitUntil(now + Duration::from_secs_f32(delay)));
+                    return;
+                }
+                target.set_control_flow(ControlFlow::Poll);
+                last_frame = now;
+                for settings in settings_rx.try_iter() {
+                    audio.apply_settings(&settings.audio);
+                    audio.apply_accessibility(&settings.accessibility);
+                    renderer.apply_accessibility(&settings.accessibility);
+                    renderer.set_vsync(settings.render.vsync);
+                    input.bindings = settings.keybindings.clone();
+                    if settings.language != options.language {
+                        let _ = options.switch_language(&loc, &settings.language);
+                        // Switching reloads the language's catalog without the mods' strings.
+                        if let Ok(mut loc) = loc.write() {
+                            let _ = mods.apply_locales(&mut loc);
+                        }
+                    }
+                    options = settings.options();
+                    limiter = FrameLimiter::new(options.render.fps_cap);
+                    if options.render.clip_seconds != clips.as_ref().map(|c| c.seconds) {
+                        clips = options.render.clip_seconds.map(ClipRecorder::new);
+                    }
+                    flow.ui.fps_counter.visible = options.render.show_fps;
+                    flow.confirmations = options.confirmations.clone();
+                    flow.hints.settings = options.hints.clone();
+                    flow.cinematic_camera = options.display.cinematic_camera;
+                    flow.difficulty = settings.difficulty;
+                    flow.ui.relayout(&layout_for(&renderer, window, &options, input.touch_detected));
+                }
+                flow.ui.fps_counter.record(elapsed);
+                // The first touch switches the UI to touch-sized buttons.
+                if input.touch_detected && !flow.ui.layout.touch {
+                    flow.ui.relayout(&layout_for(&renderer, window, &options, true));
+                }
+                input.update(elapsed);
+                for gesture in input.take_gestures() {
+                    flow.handle_gesture(gesture, &mut renderer);
+                }
+                flow.poll_assets(&mut renderer, &mut audio);
+                flow.present_battle(elapsed, &mut renderer);
+                for caption in audio.take_captions() {
+                    flow.ui.captions.push(caption);
+                }
+                for _ in 0..timestep.advance(elapsed) {
+                    flow.update(timestep.step);
+                }
+                renderer.update(elapsed);
+                window.request_redraw();
+            }
+            _ => {}
+        }
+    };
+    #[cfg(target_arch = "wasm32")]
+    winit::platform::web::EventLoopExtWebSys::spawn(event_loop, handler);
+    #[cfg(not(target_arch = "wasm32"))]
+    event_loop.run(handler)?;
+    Ok(())
+}
+
+/// Layout for the renderer's current size at the window's DPI and the
+/// player's font scale, with touch-sized buttons once a touch is seen.
+#[cfg(not(test))]
+fn layout_for(renderer: &Renderer, window: &Window, options: &OptionsMenu, touch: bool) -> LayoutContext {
+    LayoutContext::new(renderer.width, renderer.height)
+        .with_scale_factor(window.scale_factor() as f32)
+        .with_font_scale(options.accessibility.font_scale)
+        .with_touch(touch)
+}
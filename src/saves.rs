@@ -0,0 +1,111 @@
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+use crate::assets::AssetProvider;
+use crate::ioutil::invalid;
+
+/// Leading bytes of every binary save, followed by one [`SaveFormat`] tag
+/// byte. JSON saves start with `{` or whitespace, so the two never clash.
+pub const MAGIC: &[u8; 4] = b"GERO";
+
+/// How a save is encoded on disk.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SaveFormat {
+    /// Human-readable JSON, as written by earlier versions.
+    Json,
+    /// Compact bincode.
+    Binary,
+    /// Bincode compressed with zstd. Needs the `compression` feature.
+    Compressed,
+}
+
+impl SaveFormat {
+    fn tag(self) -> u8 {
+        match self {
+            SaveFormat::Json => b'j',
+            SaveFormat::Binary => b'b',
+            SaveFormat::Compressed => b'z',
+        }
+    }
+
+    /// Detect the format of `data` from its header.
+    pub fn detect(data: &[u8]) -> io::Result<SaveFormat> {
+        match data {
+            [m0, m1, m2, m3, tag, ..] if [*m0, *m1, *m2, *m3] == *MAGIC => match tag {
+                b'b' => Ok(SaveFormat::Binary),
+                b'z' => Ok(SaveFormat::Compressed),
+                _ => Err(invalid("unknown save format tag")),
+            },
+            _ if data.iter().find(|b| !b.is_ascii_whitespace()) == Some(&b'{') => Ok(SaveFormat::Json),
+            _ => Err(invalid("unrecognised save data")),
+        }
+    }
+}
+
+/// zstd level used for compressed saves; favours speed over ratio.
+#[cfg(feature = "compression")]
+const COMPRESSION_LEVEL: i32 = 3;
+
+fn header(format: SaveFormat) -> Vec<u8> {
+    let mut out = MAGIC.to_vec();
+    out.push(format.tag());
+    out
+}
+
+/// Encode `value` in `format`.
+pub fn encode<T: Serialize>(value: &T, format: SaveFormat) -> io::Result<Vec<u8>> {
+    match format {
+        SaveFormat::Json => serde_json::to_vec(value).map_err(invalid),
+        SaveFormat::Binary => {
+            let mut out = header(format);
+            bincode::serialize_into(&mut out, value).map_err(invalid)?;
+            Ok(out)
+        }
+        #[cfg(feature = "compression")]
+        SaveFormat::Compressed => {
+            let body = bincode::serialize(value).map_err(invalid)?;
+            let mut out = header(format);
+            out.extend(zstd::encode_all(body.as_slice(), COMPRESSION_LEVEL)?);
+            Ok(out)
+        }
+        #[cfg(not(feature = "compression"))]
+        SaveFormat::Compressed => Err(io::Error::new(io::ErrorKind::Unsupported, "built without compression")),
+    }
+}
+
+/// Decode a save in any format, detected from its header.
+pub fn decode<T: DeserializeOwned>(data: &[u8]) -> io::Result<T> {
+    let body = data.get(MAGIC.len() + 1..).unwrap_or_default();
+    match SaveFormat::detect(data)? {
+        SaveFormat::Json => serde_json::from_slice(data).map_err(invalid),
+        SaveFormat::Binary => bincode::deserialize(body).map_err(invalid),
+        #[cfg(feature = "compression")]
+        SaveFormat::Compressed => bincode::deserialize(&zstd::decode_all(body)?).map_err(invalid),
+        #[cfg(not(feature = "compression"))]
+        SaveFormat::Compressed => Err(io::Error::new(io::ErrorKind::Unsupported, "built without compression")),
+    }
+}
+
+/// Write `value` to `path` in `format`.
+pub fn save<T: Serialize, P: AsRef<Path>>(value: &T, path: P, format: SaveFormat) -> io::Result<()> {
+    fs::write(path, encode(value, format)?)
+}
+
+/// Read a save written in any format.
+pub fn load<T: DeserializeOwned, P: AsRef<Path>>(path: P) -> io::Result<T> {
+    decode(&fs::read(path)?)
+}
+
+/// [`save`] to `path` in `assets`, e.g. browser storage.
+pub fn save_to<T: Serialize>(assets: &dyn AssetProvider, value: &T, path: &str, format: SaveFormat) -> io::Result<()> {
+    assets.write(path, &encode(value, format)?)
+}
+
+/// [`load`] from `path` in `assets`.
+pub fn load_from<T: DeserializeOwned>(assets: &dyn AssetProvider, path: &str) -> io::Result<T> {
+    decode(&assets.read(path)?)
+}
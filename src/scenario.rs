@@ -0,0 +1,100 @@
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::audio::AudioSystem;
+use crate::combat::CombatEncounter;
+use crate::debrief::Objective;
+use crate::grid::{GridMap, RegionShape, TerrainType, TriggerRegion};
+use crate::interrupts::ScriptedInterrupt;
+use crate::ioutil::invalid;
+use crate::loot::LootCrate;
+use crate::models::{Position, Unit};
+use crate::weather::Weather;
+
+/// A battle layout loaded from JSON: map size, non-normal terrain and
+/// conditions.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Scenario {
+    pub name: String,
+    pub width: usize,
+    pub height: usize,
+    #[serde(default)]
+    pub weather: Weather,
+    /// Cells whose terrain is not [`TerrainType::Normal`].
+    #[serde(default)]
+    pub terrain: Vec<(Position, TerrainType)>,
+    /// Named areas, such as an extraction zone, that report units moving
+    /// in and out.
+    #[serde(default)]
+    pub regions: Vec<TriggerRegion>,
+    /// Crates of loot units can break open.
+    #[serde(default)]
+    pub crates: Vec<LootCrate>,
+    /// Scripted events played mid-turn when their triggers fire.
+    #[serde(default)]
+    pub interrupts: Vec<ScriptedInterrupt>,
+    /// Goals set on top of winning.
+    #[serde(default)]
+    pub objectives: Vec<Objective>,
+}
+
+impl Scenario {
+    pub fn load<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        Self::from_json(&fs::read_to_string(path)?)
+    }
+
+    /// Parse a scenario, rejecting terrain, regions and crates placed
+    /// outside the map, and objectives naming regions it lacks.
+    pub fn from_json(data: &str) -> io::Result<Self> {
+        let scenario: Scenario = serde_json::from_str(data).map_err(|e| invalid(e.to_string()))?;
+        if let Some((pos, _)) = scenario.terrain.iter().find(|(p, _)| p.x >= scenario.width || p.y >= scenario.height) {
+            return Err(invalid(format!("terrain at ({}, {}) is outside the map", pos.x, pos.y)));
+        }
+        if let Some(c) = scenario.crates.iter().find(|c| c.position.x >= scenario.width || c.position.y >= scenario.height) {
+            return Err(invalid(format!("crate at ({}, {}) is outside the map", c.position.x, c.position.y)));
+        }
+        for region in &scenario.regions {
+            let inside = match &region.shape {
+                RegionShape::Rect(rect) => rect.x + rect.width <= scenario.width && rect.y + rect.height <= scenario.height,
+                RegionShape::Cells(cells) => cells.iter().all(|p| p.x < scenario.width && p.y < scenario.height),
+            };
+            if !inside {
+                return Err(invalid(format!("region '{}' is outside the map", region.name)));
+            }
+        }
+        for objective in &scenario.objectives {
+            if let Objective::HoldRegion { region } = objective
+                && !scenario.regions.iter().any(|r| r.name == *region)
+            {
+                return Err(invalid(format!("objective names unknown region '{}'", region)));
+            }
+        }
+        Ok(scenario)
+    }
+
+    pub fn map(&self) -> GridMap {
+        let mut map = GridMap::new(self.width, self.height);
+        for (pos, terrain) in &self.terrain {
+            map.set_terrain(pos, terrain.clone());
+        }
+        map.regions = self.regions.clone();
+        for c in &self.crates {
+            map.place_crate(&c.position, c.contents.clone());
+        }
+        map
+    }
+
+    /// Start an encounter on this scenario's map and weather.
+    pub fn encounter(&self, player_units: Vec<Unit>, enemy_units: Vec<Unit>, mut audio: Option<&mut AudioSystem>) -> CombatEncounter {
+        let mut encounter = CombatEncounter::new(player_units, enemy_units, self.map(), audio.as_deref_mut());
+        if self.weather != Weather::Clear {
+            encounter.set_weather(self.weather, audio);
+        }
+        encounter.interrupts = self.interrupts.clone();
+        encounter.objectives = self.objectives.clone();
+        encounter
+    }
+}
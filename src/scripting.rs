@@ -0,0 +1,225 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::io;
+use std::path::Path;
+use std::rc::Rc;
+
+use rhai::{Dynamic, Engine, EvalAltResult, Scope, AST};
+
+use crate::combat::{CombatEncounter, CombatError, CombatEvent, EnvironmentalEffect};
+use crate::damage::DamageType;
+use crate::interrupts::InterruptEvent;
+use crate::ioutil::invalid;
+use crate::models::Position;
+use crate::terraform::TerrainShift;
+
+/// Upper bound on operations per hook call, so a runaway script can't hang
+/// the game.
+pub const MAX_OPERATIONS: u64 = 100_000;
+
+/// Something a script asked for. Scripts never touch the encounter
+/// directly; requests are queued and applied after the hook returns.
+#[derive(Debug, Clone)]
+pub enum ScriptAction {
+    SpawnUnit { unit_id: String, position: Position, enemy: bool },
+    ApplyDamage { unit_id: String, amount: i32 },
+    AddEffect(EnvironmentalEffect),
+    ShowDialog { speaker: String, text: String },
+    /// Hold the turn for an event; see [`CombatEncounter::interrupt`].
+    Interrupt(InterruptEvent),
+    AlterTerrain { shift: TerrainShift, cell: Position },
+}
+
+/// A mission script. It may define any of these hooks:
+///
+/// - `on_turn_start(unit_id)`
+/// - `on_unit_damaged(unit_id, amount)`
+/// - `on_unit_healed(unit_id, amount)`
+/// - `on_region_entered(unit_id, region)`
+/// - `on_region_exited(unit_id, region)`
+///
+/// and call `spawn_unit(template, x, y, enemy)`, `damage(id, amount)`,
+/// `add_fire(x, y, damage)`, `add_smoke(x, y, radius, turns)`,
+/// `raise_wall(x, y, turns)`, `demolish(x, y)`, `ice_over(x, y, turns)`,
+/// `show_dialog(speaker, text)` and `unit_hp(id)`. To stop the turn for a
+/// scripted moment it can call `pause_dialog(speaker, text)`,
+/// `pan_camera(x, y)` and `reinforce(template, x, y, enemy)`, which play in
+/// order once the current action has been shown. The current round is
+/// available as `round`.
+pub struct MissionScript {
+    engine: Engine,
+    ast: AST,
+    actions: Rc<RefCell<Vec<ScriptAction>>>,
+    hp: Rc<RefCell<HashMap<String, i32>>>,
+    /// Dialog lines requested by the script, waiting for the UI.
+    pub dialogs: Vec<(String, String)>,
+}
+
+impl MissionScript {
+    pub fn load<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        Self::from_source(&std::fs::read_to_string(path)?)
+    }
+
+    pub fn from_source(source: &str) -> io::Result<Self> {
+        let actions = Rc::new(RefCell::new(Vec::new()));
+        let hp = Rc::new(RefCell::new(HashMap::new()));
+        let mut engine = Engine::new();
+        engine.set_max_operations(MAX_OPERATIONS);
+        engine.set_max_call_levels(32);
+        register_api(&mut engine, &actions, &hp);
+        let ast = engine.compile(source).map_err(|e| invalid(e.to_string()))?;
+        Ok(Self { engine, ast, actions, hp, dialogs: Vec::new() })
+    }
+
+    /// Run `on_turn_start` for the encounter's current unit.
+    pub fn turn_started(&mut self, encounter: &mut CombatEncounter) -> io::Result<()> {
+        let Some(id) = encounter.turn_order.current_unit_id.clone() else { return Ok(()) };
+        self.call(encounter, "on_turn_start", vec![id.into()])
+    }
+
+    /// Run the hook matching a combat event.
+    pub fn handle_event(&mut self, encounter: &mut CombatEncounter, event: &CombatEvent) -> io::Result<()> {
+        match event {
+            CombatEvent::UnitDamaged { unit_id, amount, .. } => {
+                self.call(encounter, "on_unit_damaged", vec![unit_id.clone().into(), (*amount as i64).into()])
+            }
+            CombatEvent::UnitHealed { unit_id, amount } => {
+                self.call(encounter, "on_unit_healed", vec![unit_id.clone().into(), (*amount as i64).into()])
+            }
+            CombatEvent::RegionEntered { unit_id, region } => {
+                self.call(encounter, "on_region_entered", vec![unit_id.clone().into(), region.clone().into()])
+            }
+            CombatEvent::RegionExited { unit_id, region } => {
+                self.call(encounter, "on_region_exited", vec![unit_id.clone().into(), region.clone().into()])
+            }
+            CombatEvent::TurnHandedOff { .. }
+            | CombatEvent::Explosion { .. }
+            | CombatEvent::ShellImpact { .. }
+            | CombatEvent::ObjectDestroyed { .. }
+            | CombatEvent::LootPickedUp { .. }
+            | CombatEvent::WeaponJammed { .. }
+            | CombatEvent::UnitSuppressed { .. }
+            | CombatEvent::UnitPinned { .. }
+            | CombatEvent::OverwatchTriggered { .. }
+            | CombatEvent::HeldActionTriggered { .. }
+            | CombatEvent::ChannelInterrupted { .. }
+            | CombatEvent::UnitAttacked { .. }
+            | CombatEvent::UnitReacted { .. }
+            | CombatEvent::UnitDisplaced { .. } => Ok(()),
+        }
+    }
+
+    fn call(&mut self, encounter: &mut CombatEncounter, hook: &str, args: Vec<Dynamic>) -> io::Result<()> {
+        if !self.ast.iter_functions().any(|f| f.name == hook && f.params.len() == args.len()) {
+            return Ok(());
+        }
+        *self.hp.borrow_mut() = encounter
+            .units
+            .iter()
+            .map(|u| (u.id.clone(), u.health_points))
+            .collect();
+        let mut scope = Scope::new();
+        scope.push_constant("round", encounter.turn_order.round_number as i64);
+        let result = self.engine.call_fn::<Dynamic>(&mut scope, &self.ast, hook, args);
+        let actions = std::mem::take(&mut *self.actions.borrow_mut());
+        let applied = self.apply(encounter, actions);
+        result.map(|_| ()).map_err(|e| io::Error::other(e.to_string()))?;
+        applied.map_err(io::Error::other)
+    }
+
+    /// Carry out `actions` in order. The first one the encounter refused is
+    /// returned once all have been tried.
+    fn apply(&mut self, encounter: &mut CombatEncounter, actions: Vec<ScriptAction>) -> Result<(), CombatError> {
+        let events_before = encounter.events.len();
+        let mut refused = Ok(());
+        let mut interrupts = Vec::new();
+        for action in actions {
+            match action {
+                ScriptAction::SpawnUnit { unit_id, position, enemy } => {
+                    refused = refused.and(encounter.spawn_reinforcement(&unit_id, &position, enemy).map(|_| ()));
+                }
+                ScriptAction::ApplyDamage { unit_id, amount } => {
+                    encounter.deal_damage(None, &unit_id, amount.max(0), DamageType::Kinetic);
+                }
+                ScriptAction::AddEffect(effect) => encounter.environmental_effects.push(effect),
+                ScriptAction::ShowDialog { speaker, text } => self.dialogs.push((speaker, text)),
+                ScriptAction::Interrupt(event) => interrupts.push(event),
+                ScriptAction::AlterTerrain { shift, cell } => {
+                    encounter.alter_terrain(shift, &[cell]);
+                }
+            }
+        }
+        encounter.settle(events_before);
+        if !interrupts.is_empty() {
+            encounter.interrupt(interrupts);
+        }
+        refused
+    }
+}
+
+fn register_api(engine: &mut Engine, actions: &Rc<RefCell<Vec<ScriptAction>>>, hp: &Rc<RefCell<HashMap<String, i32>>>) {
+    let queue = actions.clone();
+    engine.register_fn("spawn_unit", move |id: &str, x: i64, y: i64, enemy: bool| {
+        let position = Position { x: x.max(0) as usize, y: y.max(0) as usize };
+        queue.borrow_mut().push(ScriptAction::SpawnUnit { unit_id: id.to_string(), position, enemy });
+    });
+    let queue = actions.clone();
+    engine.register_fn("damage", move |id: &str, amount: i64| -> Result<(), Box<EvalAltResult>> {
+        let amount = i32::try_from(amount).map_err(|_| format!("damage {} is out of range", amount))?;
+        queue.borrow_mut().push(ScriptAction::ApplyDamage { unit_id: id.to_string(), amount });
+        Ok(())
+    });
+    let queue = actions.clone();
+    engine.register_fn("add_fire", move |x: i64, y: i64, damage: i64| -> Result<(), Box<EvalAltResult>> {
+        let cell = Position { x: x.max(0) as usize, y: y.max(0) as usize };
+        let damage_per_turn = i32::try_from(damage).map_err(|_| format!("fire damage {} is out of range", damage))?;
+        let effect = EnvironmentalEffect::FirePatch { grid_cells: vec![cell], damage_per_turn };
+        queue.borrow_mut().push(ScriptAction::AddEffect(effect));
+        Ok(())
+    });
+    let queue = actions.clone();
+    engine.register_fn("add_smoke", move |x: i64, y: i64, radius: i64, turns: i64| {
+        let center = Position { x: x.max(0) as usize, y: y.max(0) as usize };
+        let effect = EnvironmentalEffect::SmokeCloud { center, radius: radius.max(0) as u32, turns_remaining: turns.max(0) as u32 };
+        queue.borrow_mut().push(ScriptAction::AddEffect(effect));
+    });
+    let queue = actions.clone();
+    engine.register_fn("raise_wall", move |x: i64, y: i64, turns: i64| {
+        let cell = Position { x: x.max(0) as usize, y: y.max(0) as usize };
+        let shift = TerrainShift::Wall { turns: turns.max(1) as u32 };
+        queue.borrow_mut().push(ScriptAction::AlterTerrain { shift, cell });
+    });
+    let queue = actions.clone();
+    engine.register_fn("ice_over", move |x: i64, y: i64, turns: i64| {
+        let cell = Position { x: x.max(0) as usize, y: y.max(0) as usize };
+        let shift = TerrainShift::Ice { turns: turns.max(1) as u32 };
+        queue.borrow_mut().push(ScriptAction::AlterTerrain { shift, cell });
+    });
+    let queue = actions.clone();
+    engine.register_fn("demolish", move |x: i64, y: i64| {
+        let cell = Position { x: x.max(0) as usize, y: y.max(0) as usize };
+        queue.borrow_mut().push(ScriptAction::AlterTerrain { shift: TerrainShift::Demolish, cell });
+    });
+    let queue = actions.clone();
+    engine.register_fn("show_dialog", move |speaker: &str, text: &str| {
+        queue.borrow_mut().push(ScriptAction::ShowDialog { speaker: speaker.to_string(), text: text.to_string() });
+    });
+    let queue = actions.clone();
+    engine.register_fn("pause_dialog", move |speaker: &str, text: &str| {
+        let event = InterruptEvent::Dialogue { speaker: speaker.to_string(), text: text.to_string() };
+        queue.borrow_mut().push(ScriptAction::Interrupt(event));
+    });
+    let queue = actions.clone();
+    engine.register_fn("pan_camera", move |x: i64, y: i64| {
+        let target = Position { x: x.max(0) as usize, y: y.max(0) as usize };
+        queue.borrow_mut().push(ScriptAction::Interrupt(InterruptEvent::CameraPan { target }));
+    });
+    let queue = actions.clone();
+    engine.register_fn("reinforce", move |id: &str, x: i64, y: i64, enemy: bool| {
+        let position = Position { x: x.max(0) as usize, y: y.max(0) as usize };
+        let event = InterruptEvent::Reinforcement { unit_id: id.to_string(), position, enemy };
+        queue.borrow_mut().push(ScriptAction::Interrupt(event));
+    });
+    let hp = hp.clone();
+    engine.register_fn("unit_hp", move |id: &str| hp.borrow().get(id).copied().unwrap_or(0) as i64);
+}
@@ -0,0 +1,138 @@
+use serde::{Deserialize, Serialize};
+
+use crate::command::Command;
+use crate::formation::formation_spot;
+use crate::models::{Position, Unit};
+use crate::registry::UnitRegistry;
+use crate::state::GameState;
+
+/// Sent to the UI whenever the set of selected units changes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SelectionChanged {
+    /// Ids of the selected units, primary first.
+    pub selected: Vec<String>,
+}
+
+/// The units the player has picked, in the order they were picked. The
+/// first is the primary selection: the unit shown in the info panel, whose
+/// abilities are offered and who leads formation moves.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Selection {
+    ids: Vec<String>,
+}
+
+impl Selection {
+    /// The units already flagged as selected in `units`, in registry order.
+    pub fn from_flags(units: &UnitRegistry) -> Self {
+        Self { ids: units.iter().filter(|u| u.is_selected).map(|u| u.id.clone()).collect() }
+    }
+
+    pub fn ids(&self) -> &[String] {
+        &self.ids
+    }
+
+    pub fn primary(&self) -> Option<&str> {
+        self.ids.first().map(String::as_str)
+    }
+
+    pub fn contains(&self, id: &str) -> bool {
+        self.ids.iter().any(|s| s == id)
+    }
+
+    pub fn len(&self) -> usize {
+        self.ids.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ids.is_empty()
+    }
+}
+
+impl GameState {
+    /// Select exactly the units in `ids`, primary first, and flag them.
+    /// Unknown and repeated ids are dropped. Returns the change, or `None`
+    /// when the selection is unchanged.
+    pub fn set_selection(&mut self, ids: Vec<String>) -> Option<SelectionChanged> {
+        let mut selected: Vec<String> = Vec::new();
+        for id in ids {
+            if self.units.by_name(&id).is_some() && !selected.contains(&id) {
+                selected.push(id);
+            }
+        }
+        for unit in self.units.iter_mut() {
+            unit.is_selected = selected.contains(&unit.id);
+        }
+        if selected == self.selection.ids {
+            return None;
+        }
+        self.selection.ids = selected.clone();
+        Some(SelectionChanged { selected })
+    }
+
+    pub fn clear_selection(&mut self) -> Option<SelectionChanged> {
+        self.set_selection(Vec::new())
+    }
+
+    /// Select the unit on `cell`, as when it is clicked. A plain click
+    /// selects only that unit, or nothing on an empty cell. An additive
+    /// (shift) click adds a living player unit to the selection or removes
+    /// it again; other units are left alone, since only the player's own
+    /// squad moves in formation.
+    pub fn select_at(&mut self, cell: Option<Position>, additive: bool) -> Option<SelectionChanged> {
+        let unit = cell.and_then(|cell| self.units.iter().find(|u| u.grid_position == cell));
+        if !additive {
+            return self.set_selection(unit.map(|u| u.id.clone()).into_iter().collect());
+        }
+        let id = unit.filter(|u| u.health_points > 0 && self.units.players().any(|p| p.id == u.id))?.id.clone();
+        let players: Vec<&str> = self.units.players().map(|u| u.id.as_str()).collect();
+        let mut ids: Vec<String> = self.selection.ids.iter().filter(|s| players.contains(&s.as_str())).cloned().collect();
+        match ids.iter().position(|s| *s == id) {
+            Some(i) => {
+                ids.remove(i);
+            }
+            None => ids.push(id),
+        }
+        self.set_selection(ids)
+    }
+
+    /// Select the next living player unit after the primary selection,
+    /// wrapping around, as when Tab is pressed.
+    pub fn cycle_selection(&mut self) -> Option<SelectionChanged> {
+        let living: Vec<&str> = self.units.players().filter(|u| u.health_points > 0).map(|u| u.id.as_str()).collect();
+        let next = match self.selection.primary().and_then(|id| living.iter().position(|l| *l == id)) {
+            Some(i) => living[(i + 1) % living.len()],
+            None => *living.first()?,
+        };
+        self.set_selection(vec![next.to_string()])
+    }
+
+    /// The selected units, primary first.
+    pub fn selected_units(&self) -> Vec<&Unit> {
+        self.selection.ids.iter().filter_map(|id| self.units.by_name(id)).collect()
+    }
+
+    /// Moves sending the selected units to `destination` in formation: the
+    /// primary goes to `destination` and the others keep their offset from
+    /// it. Units whose spot would fall off the map stay put. Reach and
+    /// occupied tiles aren't checked; see
+    /// [`CombatEncounter::plan_group_move`](crate::combat::CombatEncounter::plan_group_move).
+    pub fn formation_moves(&self, destination: &Position) -> Vec<Command> {
+        let units = self.selected_units();
+        let Some(leader) = units.first().map(|u| u.grid_position.clone()) else { return Vec::new() };
+        units
+            .iter()
+            .filter_map(|u| {
+                let spot = formation_spot(&leader, &u.grid_position, destination)?;
+                self.map.in_bounds(&spot).then(|| Command::Move { unit_id: u.id.clone(), destination: spot })
+            })
+            .collect()
+    }
+
+    /// The command for the primary selection using its ability `ability_id`
+    /// on `target_ids`, as when its ability button is pressed.
+    pub fn ability_command(&self, ability_id: &str, target_ids: Vec<String>) -> Option<Command> {
+        let unit = self.selected_units().into_iter().next()?;
+        let ability_index = unit.abilities.iter().position(|a| a.id == ability_id)?;
+        Some(Command::UseAbility { user_id: unit.id.clone(), ability_index, target_ids })
+    }
+}
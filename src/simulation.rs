@@ -0,0 +1,238 @@
+use std::collections::HashMap;
+
+use crate::campaign::Campaign;
+use crate::combat::{tick_cooldowns, CombatEncounter};
+use crate::grid::GridMap;
+use crate::models::{Unit, UnitType};
+use crate::registry::Side;
+use crate::rng::GameRng;
+
+/// Rounds after which an undecided battle is scored as a draw.
+pub const MAX_ROUNDS: u32 = 100;
+/// Battles simulated by [`CombatEncounter::auto_resolve`].
+pub const AUTO_RESOLVE_ITERATIONS: u32 = 50;
+/// Experience each surviving player unit earns per enemy killed.
+pub const XP_PER_KILL: u32 = 10;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BattleOutcome {
+    PlayerVictory,
+    EnemyVictory,
+    Draw,
+}
+
+/// Aggregate statistics over a batch of simulated battles.
+#[derive(Debug, Clone, Default)]
+pub struct SimulationReport {
+    pub battles: u32,
+    pub player_wins: u32,
+    pub enemy_wins: u32,
+    pub draws: u32,
+    pub total_rounds: u32,
+    /// Damage dealt to opponents, summed over all battles, by attacker type.
+    pub damage_by_unit_type: HashMap<UnitType, i64>,
+}
+
+impl SimulationReport {
+    /// Fraction of battles won by the player side.
+    pub fn win_rate(&self) -> f32 {
+        if self.battles == 0 { 0.0 } else { self.player_wins as f32 / self.battles as f32 }
+    }
+
+    pub fn average_rounds(&self) -> f32 {
+        if self.battles == 0 { 0.0 } else { self.total_rounds as f32 / self.battles as f32 }
+    }
+
+    /// Average damage per battle dealt by units of `unit_type`.
+    pub fn average_damage(&self, unit_type: &UnitType) -> f32 {
+        if self.battles == 0 {
+            return 0.0;
+        }
+        self.damage_by_unit_type.get(unit_type).copied().unwrap_or(0) as f32 / self.battles as f32
+    }
+}
+
+fn side_alive(encounter: &CombatEncounter, side: Side) -> bool {
+    encounter.units.on_side(side).any(|u| u.health_points > 0)
+}
+
+fn living_hp(encounter: &CombatEncounter, side: Side) -> i64 {
+    encounter.units.on_side(side).map(|u| u.health_points.max(0) as i64).sum()
+}
+
+/// Play one AI round: every unit in the initiative order takes a turn,
+/// stopping early once a side is wiped out. Each turn restores the unit's
+/// AP, lets the AI act with a fresh d100 roll, and ticks its cooldowns.
+/// Damage is attributed to the acting unit's type.
+pub fn run_round(encounter: &mut CombatEncounter, rng: &mut GameRng, damage_by_unit_type: &mut HashMap<UnitType, i64>) {
+    for _ in 0..encounter.turn_order.initiative.len() {
+        encounter.start_turn();
+        let Some(id) = encounter.turn_order.current_unit_id.clone() else { break };
+        let Some(unit_id) = encounter.units.id(&id) else { continue };
+        let opponents = encounter.units.side(unit_id).unwrap_or(Side::Player).opponent();
+        let unit = &mut encounter.units[unit_id];
+        if unit.health_points > 0 {
+            unit.action_points = unit.current_stats.max_action;
+            let unit_type = unit.unit_type.clone();
+            let opponents_before = living_hp(encounter, opponents);
+
+            encounter.ai_take_action(rng.roll_d100());
+
+            let opponents_after = living_hp(encounter, opponents);
+            *damage_by_unit_type.entry(unit_type).or_insert(0) += opponents_before - opponents_after;
+        }
+        encounter.end_turn();
+        if let Some(unit) = encounter.unit_by_id_mut(&id) {
+            tick_cooldowns(unit);
+        }
+        encounter.events.clear();
+        if !side_alive(encounter, Side::Player) || !side_alive(encounter, Side::Enemy) {
+            break;
+        }
+    }
+}
+
+/// Run one AI-versus-AI battle to completion, a [`run_round`] at a time.
+pub fn run_battle(
+    encounter: &mut CombatEncounter,
+    rng: &mut GameRng,
+    damage_by_unit_type: &mut HashMap<UnitType, i64>,
+) -> (BattleOutcome, u32) {
+    let mut rounds = 0;
+    while rounds < MAX_ROUNDS && side_alive(encounter, Side::Player) && side_alive(encounter, Side::Enemy) {
+        rounds += 1;
+        run_round(encounter, rng, damage_by_unit_type);
+    }
+
+    (encounter.outcome().unwrap_or(BattleOutcome::Draw), rounds)
+}
+
+impl CombatEncounter {
+    /// How the battle ended, once a side has nobody left standing; `None`
+    /// while both fight on.
+    pub fn outcome(&self) -> Option<BattleOutcome> {
+        match (side_alive(self, Side::Player), side_alive(self, Side::Enemy)) {
+            (true, true) => None,
+            (true, false) => Some(BattleOutcome::PlayerVictory),
+            (false, true) => Some(BattleOutcome::EnemyVictory),
+            (false, false) => Some(BattleOutcome::Draw),
+        }
+    }
+}
+
+/// Simulate `iterations` battles between fresh copies of the two squads on
+/// `map`, with the AI controlling both sides. The same seed always produces
+/// the same report.
+pub fn simulate(players: &[Unit], enemies: &[Unit], map: &GridMap, iterations: u32, seed: u64) -> SimulationReport {
+    let mut rng = GameRng::new(seed);
+    let mut report = SimulationReport::default();
+    for _ in 0..iterations {
+        let mut encounter = CombatEncounter::new(players.to_vec(), enemies.to_vec(), map.clone(), None);
+        let (outcome, rounds) = run_battle(&mut encounter, &mut rng, &mut report.damage_by_unit_type);
+        report.battles += 1;
+        report.total_rounds += rounds;
+        match outcome {
+            BattleOutcome::PlayerVictory => report.player_wins += 1,
+            BattleOutcome::EnemyVictory => report.enemy_wins += 1,
+            BattleOutcome::Draw => report.draws += 1,
+        }
+    }
+    report
+}
+
+/// Expected result of skipping a battle with [`CombatEncounter::auto_resolve`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct AutoResolveReport {
+    /// Most frequent outcome across the simulated battles.
+    pub outcome: BattleOutcome,
+    /// Fraction of simulated battles that ended in `outcome`.
+    pub confidence: f32,
+    /// Player units that died in more than half of the simulated battles.
+    pub casualties: Vec<String>,
+    /// Experience each surviving player unit earns: [`XP_PER_KILL`] per
+    /// expected enemy death, rounded to the nearest point.
+    pub experience: u32,
+    /// Weapon and armor ids carried by enemies that died in more than half
+    /// of the simulated battles. Empty unless the player is expected to win.
+    pub loot: Vec<String>,
+}
+
+fn death_rates<'a>(units: impl Iterator<Item = &'a Unit>, deaths: &HashMap<String, u32>, battles: u32) -> Vec<f32> {
+    units.map(|u| deaths.get(&u.id).copied().unwrap_or(0) as f32 / battles.max(1) as f32).collect()
+}
+
+impl CombatEncounter {
+    /// Simulate the rest of this battle [`AUTO_RESOLVE_ITERATIONS`] times
+    /// from its current state, with the AI controlling both sides, and
+    /// summarise the expected outcome. Per-unit death rates decide
+    /// casualties and loot; their sum over enemies gives the expected kills
+    /// that experience is paid on. The encounter itself is untouched, and
+    /// its RNG seeds the simulations, so the same state always produces the
+    /// same report.
+    pub fn auto_resolve(&self) -> AutoResolveReport {
+        let mut rng = self.rng.clone();
+        let mut damage = HashMap::new();
+        let mut wins = [0u32; 3];
+        let mut deaths: HashMap<String, u32> = HashMap::new();
+        for _ in 0..AUTO_RESOLVE_ITERATIONS {
+            let mut encounter = self.clone();
+            let (outcome, _) = run_battle(&mut encounter, &mut rng, &mut damage);
+            wins[outcome as usize] += 1;
+            for unit in &encounter.units {
+                if unit.health_points <= 0 {
+                    *deaths.entry(unit.id.clone()).or_insert(0) += 1;
+                }
+            }
+        }
+
+        let outcome = [BattleOutcome::PlayerVictory, BattleOutcome::EnemyVictory, BattleOutcome::Draw]
+            .into_iter()
+            .max_by_key(|o| wins[*o as usize])
+            .unwrap_or(BattleOutcome::Draw);
+        let player_deaths = death_rates(self.player_units(), &deaths, AUTO_RESOLVE_ITERATIONS);
+        let enemy_deaths = death_rates(self.enemy_units(), &deaths, AUTO_RESOLVE_ITERATIONS);
+        let casualties = self
+            .player_units()
+            .zip(&player_deaths)
+            .filter(|(_, rate)| **rate > 0.5)
+            .map(|(u, _)| u.id.clone())
+            .collect();
+        let expected_kills: f32 = enemy_deaths.iter().sum();
+        let loot = if outcome == BattleOutcome::PlayerVictory {
+            self.enemy_units()
+                .zip(&enemy_deaths)
+                .filter(|(_, rate)| **rate > 0.5)
+                .flat_map(|(u, _)| {
+                    let weapon = u.equipment.weapon.as_ref().map(|w| w.id.clone());
+                    let armor = u.equipment.armor.as_ref().map(|a| a.id.clone());
+                    weapon.into_iter().chain(armor)
+                })
+                .collect()
+        } else {
+            Vec::new()
+        };
+        AutoResolveReport {
+            outcome,
+            confidence: wins[outcome as usize] as f32 / AUTO_RESOLVE_ITERATIONS as f32,
+            casualties,
+            experience: (expected_kills * XP_PER_KILL as f32).round() as u32,
+            loot,
+        }
+    }
+}
+
+impl Campaign {
+    /// Apply an auto-resolved battle: casualties leave the roster, except
+    /// heroes who are wounded instead, every other deployed roster unit
+    /// gains the report's experience and the loot goes into the inventory.
+    pub fn apply_auto_resolve(&mut self, deployed: &[String], report: &AutoResolveReport) {
+        let casualties = self.spare_heroes(report);
+        self.roster.retain(|u| !casualties.contains(&u.id));
+        for unit in self.roster.iter_mut().filter(|u| deployed.contains(&u.id)) {
+            unit.gain_experience(report.experience);
+        }
+        for item in &report.loot {
+            self.grant_item(item);
+        }
+    }
+}
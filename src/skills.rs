@@ -0,0 +1,108 @@
+use serde::{Deserialize, Serialize};
+
+use crate::campaign::Campaign;
+use crate::models::Unit;
+use crate::modifiers::Stat;
+use crate::rng::GameRng;
+
+/// Percentage points of target per point of the tested stat.
+pub const POINTS_PER_STAT: i32 = 10;
+/// Points of margin per extra degree of success or failure.
+pub const POINTS_PER_DEGREE: i32 = 10;
+
+/// How hard a skill test is; shifts the target number.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum TestDifficulty {
+    /// +30.
+    Easy,
+    /// +20.
+    Routine,
+    /// +10.
+    Ordinary,
+    /// No modifier.
+    Challenging,
+    /// -10.
+    Difficult,
+    /// -20.
+    Hard,
+    /// -30.
+    VeryHard,
+}
+
+impl TestDifficulty {
+    pub fn modifier(&self) -> i32 {
+        match self {
+            TestDifficulty::Easy => 30,
+            TestDifficulty::Routine => 20,
+            TestDifficulty::Ordinary => 10,
+            TestDifficulty::Challenging => 0,
+            TestDifficulty::Difficult => -10,
+            TestDifficulty::Hard => -20,
+            TestDifficulty::VeryHard => -30,
+        }
+    }
+
+    /// Localization key for the difficulty's display name.
+    pub fn label_key(&self) -> &'static str {
+        match self {
+            TestDifficulty::Easy => "skill.easy",
+            TestDifficulty::Routine => "skill.routine",
+            TestDifficulty::Ordinary => "skill.ordinary",
+            TestDifficulty::Challenging => "skill.challenging",
+            TestDifficulty::Difficult => "skill.difficult",
+            TestDifficulty::Hard => "skill.hard",
+            TestDifficulty::VeryHard => "skill.very_hard",
+        }
+    }
+}
+
+/// The result of a [`skill_test`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SkillTest {
+    pub stat: Stat,
+    /// Percentile the roll had to meet.
+    pub target: i32,
+    pub roll: u8,
+    pub success: bool,
+    /// How far the roll beat or missed the target: 1, plus one for every
+    /// [`POINTS_PER_DEGREE`] of margin.
+    pub degrees: u32,
+}
+
+impl SkillTest {
+    /// Localization key for the outcome, a plural over `degrees`.
+    pub fn label_key(&self) -> &'static str {
+        if self.success { "skill.success" } else { "skill.failure" }
+    }
+}
+
+/// Test `unit`'s current `stat` outside combat: roll d100 against
+/// [`POINTS_PER_STAT`] per stat point plus the difficulty's modifier. A 1
+/// always succeeds and a 100 always fails.
+pub fn skill_test(unit: &Unit, stat: Stat, difficulty: TestDifficulty, rng: &mut GameRng) -> SkillTest {
+    let target = unit.current_stats.get(stat) * POINTS_PER_STAT + difficulty.modifier();
+    let roll = rng.roll_d100();
+    let success = roll == 1 || (roll < 100 && roll as i32 <= target);
+    let margin = if success { target - roll as i32 } else { roll as i32 - target - 1 };
+    let degrees = 1 + (margin.max(0) / POINTS_PER_DEGREE) as u32;
+    SkillTest { stat, target, roll, success, degrees }
+}
+
+impl Campaign {
+    /// The roster unit fit for duty with the highest `stat`, first on ties,
+    /// to take a mission's skill test.
+    pub fn best_at(&self, stat: Stat) -> Option<&Unit> {
+        self.roster
+            .iter()
+            .filter(|u| !u.is_recovering())
+            .rev()
+            .max_by_key(|u| u.current_stats.get(stat))
+    }
+
+    /// Have the roster's best unit at `stat` take a skill test. `None` when
+    /// nobody is fit to try.
+    pub fn skill_test(&self, stat: Stat, difficulty: TestDifficulty, rng: &mut GameRng) -> Option<(&Unit, SkillTest)> {
+        let unit = self.best_at(stat)?;
+        Some((unit, skill_test(unit, stat, difficulty, rng)))
+    }
+}
@@ -0,0 +1,366 @@
+use std::collections::HashSet;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::assets::AssetProvider;
+use crate::campaign::Campaign;
+use crate::combat::CombatEncounter;
+use crate::grid::TerrainType;
+use crate::ioutil::invalid;
+use crate::models::{Ability, Faction, Position, Stats, Trait, Unit, UnitType, Weapon};
+use crate::profile::Difficulty;
+use crate::rng::GameRng;
+use crate::scenario::Scenario;
+
+/// Threat budget of a skirmish before any missions are completed, at
+/// [`Difficulty::Normal`].
+pub const BASE_THREAT_BUDGET: u32 = 6;
+/// Threat added to the budget per completed mission.
+pub const THREAT_PER_MISSION: u32 = 2;
+
+impl Difficulty {
+    /// Percentage of the threat budget fielded at this difficulty.
+    pub fn threat_percent(&self) -> u32 {
+        match self {
+            Difficulty::Easy => 75,
+            Difficulty::Normal => 100,
+            Difficulty::Hard => 150,
+        }
+    }
+}
+
+/// Threat the enemy may field in a skirmish after `missions_completed`
+/// missions.
+pub fn threat_budget(missions_completed: u32, difficulty: Difficulty) -> u32 {
+    (BASE_THREAT_BUDGET + THREAT_PER_MISSION * missions_completed) * difficulty.threat_percent() / 100
+}
+
+/// An enemy the generator can field: its threat cost, how often it turns
+/// up in its faction's squads, and the weapons it may carry.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EnemyArchetype {
+    pub id: String,
+    pub name: String,
+    pub unit_type: UnitType,
+    pub faction: Faction,
+    pub threat: u32,
+    /// Relative chance of being picked among affordable archetypes.
+    pub weight: u32,
+    pub base_stats: Stats,
+    /// One is carried by each unit, preferring weapons the squad lacks.
+    pub weapons: Vec<Weapon>,
+    #[serde(default)]
+    pub abilities: Vec<Ability>,
+    #[serde(default)]
+    pub sprite_id: String,
+}
+
+impl EnemyArchetype {
+    /// Whether fielding this archetype would bring a weapon or ability
+    /// the squad doesn't have yet.
+    fn adds_variety(&self, squad: &[Unit]) -> bool {
+        let weapons: HashSet<&str> = squad.iter().filter_map(|u| u.equipment.weapon.as_ref()).map(|w| w.id.as_str()).collect();
+        let abilities: HashSet<&str> = squad.iter().flat_map(|u| &u.abilities).map(|a| a.id.as_str()).collect();
+        self.weapons.iter().any(|w| !weapons.contains(w.id.as_str()))
+            || self.abilities.iter().any(|a| !abilities.contains(a.id.as_str()))
+    }
+
+    fn spawn(&self, id: &str, squad: &[Unit], rng: &mut GameRng) -> Unit {
+        let mut unit = Unit::new(id, &self.name, self.unit_type.clone(), self.faction.clone());
+        unit.base_stats = self.base_stats.clone();
+        unit.abilities = self.abilities.clone();
+        unit.sprite_id = self.sprite_id.clone();
+        unit.traits = Trait::for_unit_type(&self.unit_type);
+        let carried: HashSet<&str> = squad.iter().filter_map(|u| u.equipment.weapon.as_ref()).map(|w| w.id.as_str()).collect();
+        let fresh: Vec<&Weapon> = self.weapons.iter().filter(|w| !carried.contains(w.id.as_str())).collect();
+        let pool = if fresh.is_empty() { self.weapons.iter().collect() } else { fresh };
+        if !pool.is_empty() {
+            unit.equipment.weapon = Some(pool[rng.range(0, pool.len() as u32 - 1) as usize].clone());
+        }
+        unit.recalculate_stats();
+        unit.health_points = unit.current_stats.max_health;
+        unit.action_points = unit.current_stats.max_action;
+        unit
+    }
+}
+
+/// Every enemy archetype the generator knows, loaded from JSON.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct EnemyTable {
+    pub archetypes: Vec<EnemyArchetype>,
+}
+
+impl EnemyTable {
+    /// Load a table, rejecting archetypes that cost nothing or can never
+    /// be picked.
+    pub fn load<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        Self::parse(&fs::read_to_string(path)?)
+    }
+
+    /// [`EnemyTable::load`] from `path` in `assets`.
+    pub fn load_from(assets: &dyn AssetProvider, path: &str) -> io::Result<Self> {
+        Self::parse(&assets.read_to_string(path)?)
+    }
+
+    fn parse(data: &str) -> io::Result<Self> {
+        let table: EnemyTable = serde_json::from_str(data).map_err(|e| invalid(e.to_string()))?;
+        if let Some(a) = table.archetypes.iter().find(|a| a.threat == 0 || a.weight == 0) {
+            return Err(invalid(format!("archetype '{}' needs a threat and weight above zero", a.id)));
+        }
+        Ok(table)
+    }
+
+    pub fn for_faction<'a>(&'a self, faction: &'a Faction) -> impl Iterator<Item = &'a EnemyArchetype> {
+        self.archetypes.iter().filter(move |a| a.faction == *faction)
+    }
+
+    /// Build a `faction` squad costing at most `budget` threat, or just its
+    /// cheapest archetype if none fits. Archetypes are drawn by weight,
+    /// doubled for those that add a new weapon or ability and divided by
+    /// how many of them are already in the squad.
+    pub fn compose(&self, faction: &Faction, budget: u32, rng: &mut GameRng) -> Vec<Unit> {
+        let mut squad: Vec<Unit> = Vec::new();
+        let mut picks: Vec<&EnemyArchetype> = Vec::new();
+        let mut remaining = budget;
+        loop {
+            let weighted: Vec<(&EnemyArchetype, u32)> = self
+                .for_faction(faction)
+                .filter(|a| a.threat <= remaining)
+                .map(|a| {
+                    let variety = if a.adds_variety(&squad) { 2 } else { 1 };
+                    let fielded = picks.iter().filter(|p| p.id == a.id).count() as u32;
+                    (a, (a.weight * variety / (1 + fielded)).max(1))
+                })
+                .collect();
+            let total: u32 = weighted.iter().map(|(_, w)| w).sum();
+            if total == 0 {
+                break;
+            }
+            let mut roll = rng.range(0, total - 1);
+            let Some(&(archetype, _)) = weighted.iter().find(|(_, w)| {
+                let hit = roll < *w;
+                roll = roll.saturating_sub(*w);
+                hit
+            }) else {
+                break;
+            };
+            let id = format!("{}_{}", archetype.id, picks.iter().filter(|p| p.id == archetype.id).count() + 1);
+            squad.push(archetype.spawn(&id, &squad, rng));
+            picks.push(archetype);
+            remaining -= archetype.threat;
+        }
+        if squad.is_empty()
+            && let Some(cheapest) = self.for_faction(faction).min_by_key(|a| a.threat)
+        {
+            squad.push(cheapest.spawn(&format!("{}_1", cheapest.id), &squad, rng));
+        }
+        squad
+    }
+}
+
+/// A procedurally generated battle: a scenario's map with an enemy squad
+/// sized to the campaign's progress.
+#[derive(Debug, Clone)]
+pub struct SkirmishMission {
+    pub scenario: Scenario,
+    pub faction: Faction,
+    pub difficulty: Difficulty,
+}
+
+impl SkirmishMission {
+    /// Start the skirmish with `squad` deployed as given and a generated
+    /// enemy squad on free cells in the right third of the map.
+    pub fn encounter(&self, table: &EnemyTable, campaign: &Campaign, squad: Vec<Unit>, rng: &mut GameRng) -> CombatEncounter {
+        let budget = threat_budget(campaign.missions_completed, self.difficulty);
+        let mut enemies = table.compose(&self.faction, budget, rng);
+        let map = self.scenario.map();
+        let taken: HashSet<Position> = squad.iter().map(|u| u.grid_position.clone()).collect();
+        let mut cells: Vec<Position> = (self.scenario.width * 2 / 3..self.scenario.width)
+            .flat_map(|x| (0..self.scenario.height).map(move |y| Position { x, y }))
+            .filter(|p| *map.terrain_at(p) != TerrainType::Blocked && !taken.contains(p))
+            .collect();
+        rng.shuffle(&mut cells);
+        enemies.truncate(cells.len());
+        for (unit, cell) in enemies.iter_mut().zip(cells) {
+            unit.grid_position = cell;
+        }
+        let mut encounter = self.scenario.encounter(squad, enemies, None);
+        encounter.difficulty = self.difficulty;
+        encounter
+    }
+}
+
+/// Points a squad may spend in a standalone skirmish unless set otherwise.
+pub const DEFAULT_SQUAD_POINTS: u32 = 150;
+/// Columns kept clear of terrain at each edge of a procedural map, where
+/// the squads deploy.
+pub const DEPLOYMENT_COLUMNS: usize = 2;
+
+impl Unit {
+    /// Skirmish points the unit costs: its six core stats, half its max
+    /// health, 3 per action point, its weapon's damage twice over plus
+    /// half its range and 10 per point of armour piercing, and 4 per
+    /// accessory and 5 per ability carried.
+    pub fn point_cost(&self) -> u32 {
+        let s = &self.current_stats;
+        let stats = s.strength + s.toughness + s.agility + s.intellect + s.willpower + s.fellowship;
+        let mut cost = stats + s.max_health / 2 + 3 * s.max_action as i32;
+        if let Some(weapon) = &self.equipment.weapon {
+            cost += weapon.damage * 2 + weapon.range as i32 / 2 + (weapon.armor_piercing.unwrap_or(0.0) * 10.0).round() as i32;
+        }
+        cost += 4 * self.equipment.accessory_slots.len() as i32 + 5 * self.abilities.len() as i32;
+        cost.max(1) as u32
+    }
+}
+
+impl Scenario {
+    /// A random battlefield from `seed`: scattered rocks, rough ground and
+    /// hazards, with the [`DEPLOYMENT_COLUMNS`] at each edge left clear.
+    pub fn procedural(seed: u64, width: usize, height: usize) -> Scenario {
+        let mut rng = GameRng::new(seed);
+        let mut terrain = Vec::new();
+        for x in DEPLOYMENT_COLUMNS..width.saturating_sub(DEPLOYMENT_COLUMNS) {
+            for y in 0..height {
+                let kind = match rng.roll_d100() {
+                    1..=8 => TerrainType::Blocked,
+                    9..=14 => TerrainType::Difficult,
+                    15..=16 => TerrainType::Hazardous,
+                    _ => continue,
+                };
+                terrain.push((Position { x, y }, kind));
+            }
+        }
+        Scenario {
+            name: format!("Skirmish {}", seed),
+            width,
+            height,
+            weather: Default::default(),
+            terrain,
+            regions: Vec::new(),
+            crates: Vec::new(),
+            interrupts: Vec::new(),
+            objectives: Vec::new(),
+        }
+    }
+}
+
+/// Where a standalone skirmish is fought.
+#[derive(Debug, Clone)]
+pub enum SkirmishMap {
+    Scenario(Scenario),
+    /// A [`Scenario::procedural`] map.
+    Procedural { seed: u64, width: usize, height: usize },
+}
+
+impl SkirmishMap {
+    pub fn scenario(&self) -> Scenario {
+        match self {
+            SkirmishMap::Scenario(scenario) => scenario.clone(),
+            SkirmishMap::Procedural { seed, width, height } => Scenario::procedural(*seed, *width, *height),
+        }
+    }
+}
+
+/// Setup for a skirmish fought outside the campaign: pick a map and the
+/// two factions, buy each squad within `points`, then [`launch`].
+///
+/// [`launch`]: SkirmishSetup::launch
+#[derive(Debug, Clone)]
+pub struct SkirmishSetup {
+    pub map: SkirmishMap,
+    pub player_faction: Faction,
+    pub enemy_faction: Faction,
+    /// Points each squad may spend.
+    pub points: u32,
+    pub player_squad: Vec<Unit>,
+    pub enemy_squad: Vec<Unit>,
+}
+
+impl SkirmishSetup {
+    pub fn new(map: SkirmishMap, player_faction: Faction, enemy_faction: Faction) -> Self {
+        Self { map, player_faction, enemy_faction, points: DEFAULT_SQUAD_POINTS, player_squad: Vec::new(), enemy_squad: Vec::new() }
+    }
+
+    /// Points spent on `squad`.
+    pub fn spent(squad: &[Unit]) -> u32 {
+        squad.iter().map(Unit::point_cost).sum()
+    }
+
+    /// Points left for the player's or the enemy's squad.
+    pub fn remaining(&self, enemy: bool) -> u32 {
+        let squad = if enemy { &self.enemy_squad } else { &self.player_squad };
+        self.points.saturating_sub(Self::spent(squad))
+    }
+
+    /// Buy `unit` into the player's or the enemy's squad if it fits.
+    pub fn add_unit(&mut self, unit: Unit, enemy: bool) -> Result<(), &'static str> {
+        let (squad, faction) =
+            if enemy { (&self.enemy_squad, &self.enemy_faction) } else { (&self.player_squad, &self.player_faction) };
+        if unit.faction != *faction {
+            return Err("unit from wrong faction");
+        }
+        if self.player_squad.iter().chain(&self.enemy_squad).any(|u| u.id == unit.id) {
+            return Err("duplicate unit id");
+        }
+        if Self::spent(squad) + unit.point_cost() > self.points {
+            return Err("squad over budget");
+        }
+        if enemy {
+            self.enemy_squad.push(unit);
+        } else {
+            self.player_squad.push(unit);
+        }
+        Ok(())
+    }
+
+    /// Check both squads: each needs at least one unit, only of its own
+    /// faction, within the points, and ids must be unique across both.
+    pub fn validate(&self) -> Result<(), &'static str> {
+        for (squad, faction) in [(&self.player_squad, &self.player_faction), (&self.enemy_squad, &self.enemy_faction)] {
+            if squad.is_empty() {
+                return Err("squad is empty");
+            }
+            if squad.iter().any(|u| u.faction != *faction) {
+                return Err("unit from wrong faction");
+            }
+            if Self::spent(squad) > self.points {
+                return Err("squad over budget");
+            }
+        }
+        let mut ids = HashSet::new();
+        if !self.player_squad.iter().chain(&self.enemy_squad).all(|u| ids.insert(u.id.as_str())) {
+            return Err("duplicate unit id");
+        }
+        Ok(())
+    }
+
+    /// Validate the setup and start the battle, the player's squad down
+    /// the left edge and the enemy's down the right, column by column
+    /// around blocked cells.
+    pub fn launch(&self) -> Result<CombatEncounter, &'static str> {
+        self.validate()?;
+        let scenario = self.map.scenario();
+        let map = scenario.map();
+        let open = |x: usize| {
+            let map = &map;
+            (0..scenario.height).map(move |y| Position { x, y }).filter(|p| *map.terrain_at(p) != TerrainType::Blocked)
+        };
+        let left: Vec<Position> = (0..scenario.width).flat_map(open).collect();
+        let right: Vec<Position> = (0..scenario.width).rev().flat_map(open).collect();
+        if left.len() < self.player_squad.len() + self.enemy_squad.len() {
+            return Err("map too small for squads");
+        }
+        let mut players = self.player_squad.clone();
+        for (unit, cell) in players.iter_mut().zip(left) {
+            unit.grid_position = cell;
+        }
+        let mut enemies = self.enemy_squad.clone();
+        for (unit, cell) in enemies.iter_mut().zip(right) {
+            unit.grid_position = cell;
+        }
+        Ok(scenario.encounter(players, enemies, None))
+    }
+}
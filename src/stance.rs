@@ -0,0 +1,97 @@
+use serde::{Deserialize, Serialize};
+
+use crate::combat::{manhattan, AttackModifiers, CombatEncounter, CombatError, CombatEvent};
+use crate::registry::UnitId;
+
+/// Added to the cover bonus of a hunkered unit.
+pub const HUNKER_COVER_BONUS: i32 = 30;
+/// Accuracy of an overwatch shot, snapped off at a moving target.
+pub const OVERWATCH_ACCURACY_PENALTY: i32 = -10;
+/// Accuracy lost charging into a braced unit.
+pub const BRACE_ACCURACY_PENALTY: i32 = -20;
+/// Extra cells the AI counts to a hunkered unit when picking a target, so
+/// it goes after exposed ones first.
+pub const HUNKER_TARGET_PENALTY: u32 = 3;
+
+/// A defensive stance a unit takes with its remaining AP at the end of its
+/// turn. It holds until the unit's next activation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Stance {
+    /// Keep low: harder to hit.
+    Hunker,
+    /// Fire on the first opponent to move within weapon range.
+    Overwatch,
+    /// Set to meet a charge, which loses its bonuses.
+    Brace,
+}
+
+impl Stance {
+    /// Localization key for the stance's display name.
+    pub fn label_key(&self) -> &'static str {
+        match self {
+            Stance::Hunker => "stance.hunker",
+            Stance::Overwatch => "stance.overwatch",
+            Stance::Brace => "stance.brace",
+        }
+    }
+
+    /// Cells added to the distance to a unit in this stance when the AI
+    /// picks the nearest target.
+    pub fn ai_target_penalty(&self) -> u32 {
+        match self {
+            Stance::Hunker => HUNKER_TARGET_PENALTY,
+            Stance::Overwatch | Stance::Brace => 0,
+        }
+    }
+
+    /// Cover bonus the stance gives against attacks.
+    pub fn cover_bonus(&self) -> i32 {
+        match self {
+            Stance::Hunker => HUNKER_COVER_BONUS,
+            Stance::Overwatch | Stance::Brace => 0,
+        }
+    }
+}
+
+impl CombatEncounter {
+    /// Spend the unit's remaining AP taking a stance.
+    pub fn take_stance(&mut self, unit_id: &str, stance: Stance) -> Result<(), CombatError> {
+        let unit = self.units.by_name_mut(unit_id).ok_or(CombatError::UnknownUnit)?;
+        if unit.action_points == 0 {
+            return Err(CombatError::InsufficientAp);
+        }
+        if stance == Stance::Overwatch && unit.equipment.weapon.is_none() {
+            return Err(CombatError::NoWeapon);
+        }
+        unit.action_points = 0;
+        unit.stance = Some(stance);
+        Ok(())
+    }
+
+    /// Let a unit on overwatch fire on `mover`, which has just moved. The
+    /// first opponent with the mover in range takes the shot, ending its
+    /// overwatch. Returns whether anyone fired.
+    pub fn trigger_overwatch(&mut self, mover: &str) -> bool {
+        let Some(handle) = self.units.id(mover) else { return false };
+        let position = self.units[handle].grid_position.clone();
+        let Some(opponent) = self.units.side(handle).map(|s| s.opponent()) else { return false };
+        let watcher: Option<UnitId> = self.units.ids().find(|&id| {
+            let unit = &self.units[id];
+            self.units.side(id) == Some(opponent)
+                && unit.health_points > 0
+                && unit.stance == Some(Stance::Overwatch)
+                && unit.equipment.weapon.as_ref().is_some_and(|w| manhattan(&unit.grid_position, &position) <= w.range)
+        });
+        let Some(watcher) = watcher else { return false };
+        let unit = &mut self.units[watcher];
+        unit.stance = None;
+        // The shot was paid for when the stance was taken.
+        unit.action_points = unit.equipment.weapon.as_ref().map_or(0, |w| w.action_point_cost);
+        let watcher_id = unit.id.clone();
+        self.events.push(CombatEvent::OverwatchTriggered { unit_id: watcher_id.clone(), target_id: mover.to_string() });
+        let roll = self.rng.roll_d100();
+        let penalty = AttackModifiers { accuracy: OVERWATCH_ACCURACY_PENALTY, ..AttackModifiers::default() };
+        let _ = self.attack_unit(&watcher_id, mover, roll, penalty);
+        true
+    }
+}
@@ -1,23 +1,34 @@
+use std::io;
+
 use serde::{Serialize, Deserialize};
 use crate::models::Unit;
 use crate::grid::GridMap;
-use crate::combat::{TurnQueue, EnvironmentalEffect};
+use crate::combat::{CombatEncounter, TurnQueue, EnvironmentalEffect};
+use crate::registry::UnitRegistry;
+use crate::saves::{self, SaveFormat};
+use crate::selection::Selection;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct GameState {
-    pub units: Vec<Unit>,
+    pub units: UnitRegistry,
     pub map: GridMap,
     pub turn_queue: TurnQueue,
     pub environmental_effects: Vec<EnvironmentalEffect>,
+    /// Units picked by the player; see [`crate::selection`].
+    #[serde(default)]
+    pub selection: Selection,
 }
 
 impl GameState {
+    /// A state holding `units`, all registered on the player side.
     pub fn new(units: Vec<Unit>) -> Self {
         let mut turn_queue = TurnQueue::new();
         for u in &units {
             turn_queue.add_unit(u.id.clone());
         }
+        let units = UnitRegistry::with_sides(units, Vec::new());
         Self {
+            selection: Selection::from_flags(&units),
             units,
             map: GridMap::new(10, 10),
             turn_queue,
@@ -25,6 +36,24 @@ impl GameState {
         }
     }
 
+    /// Snapshot of an encounter's units, map, turn order and effects.
+    pub fn from_encounter(encounter: &CombatEncounter) -> Self {
+        Self {
+            units: encounter.units.clone(),
+            map: encounter.battlefield.clone(),
+            turn_queue: encounter.turn_order.clone(),
+            environmental_effects: encounter.environmental_effects.clone(),
+            selection: Selection::from_flags(&encounter.units),
+        }
+    }
+
+    /// FNV-1a hash of the serialized state. Equal states on different
+    /// machines produce equal checksums, so peers compare these to detect
+    /// desyncs.
+    pub fn checksum(&self) -> u64 {
+        fnv1a(self.save_to_string().as_bytes())
+    }
+
     pub fn save_to_string(&self) -> String {
         serde_json::to_string(self).expect("serialize game state")
     }
@@ -32,6 +61,21 @@ impl GameState {
     pub fn load_from_str(data: &str) -> Self {
         serde_json::from_str(data).expect("deserialize game state")
     }
+
+    /// Encode the state in `format`; see [`crate::saves`].
+    pub fn save_to_bytes(&self, format: SaveFormat) -> io::Result<Vec<u8>> {
+        saves::encode(self, format)
+    }
+
+    /// Decode a state saved in any format.
+    pub fn load_from_bytes(data: &[u8]) -> io::Result<Self> {
+        saves::decode(data)
+    }
+}
+
+/// 64-bit FNV-1a hash of `data`.
+pub(crate) fn fnv1a(data: &[u8]) -> u64 {
+    data.iter().fold(0xcbf2_9ce4_8422_2325, |hash, &b| (hash ^ b as u64).wrapping_mul(0x0100_0000_01b3))
 }
 
 #[cfg(test)]
@@ -46,7 +90,7 @@ mod tests {
         let state = GameState::new(vec![unit.clone()]);
         let data = state.save_to_string();
         let loaded = GameState::load_from_str(&data);
-        assert_eq!(loaded.units[0].id, unit.id);
+        assert_eq!(loaded.units.by_name("u").unwrap().id, unit.id);
         assert_eq!(loaded.map.width, 10);
         assert_eq!(loaded.turn_queue.initiative.len(), 1);
         assert!(loaded.environmental_effects.is_empty());
@@ -84,4 +128,3 @@ mod tests {
         assert_eq!(loaded.turn_queue.current_unit_id, state.turn_queue.current_unit_id);
     }
 }
-
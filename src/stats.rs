@@ -0,0 +1,167 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::campaign::Campaign;
+use crate::models::Position;
+use crate::simulation::BattleOutcome;
+
+/// What one unit did over a battle, or summed over a campaign.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UnitStats {
+    pub damage_dealt: u32,
+    pub damage_taken: u32,
+    /// Weapon attacks made, hit or miss.
+    pub attacks: u32,
+    pub hits: u32,
+    pub abilities_used: u32,
+    pub tiles_moved: u32,
+    /// Turns the unit ended still standing.
+    pub turns_survived: u32,
+    /// Opponents the unit brought down.
+    #[serde(default)]
+    pub kills: u32,
+}
+
+impl UnitStats {
+    /// Fraction of attacks that hit, or `None` before the first attack.
+    pub fn accuracy(&self) -> Option<f32> {
+        (self.attacks > 0).then(|| self.hits as f32 / self.attacks as f32)
+    }
+
+    pub fn add(&mut self, other: &UnitStats) {
+        self.damage_dealt += other.damage_dealt;
+        self.damage_taken += other.damage_taken;
+        self.attacks += other.attacks;
+        self.hits += other.hits;
+        self.abilities_used += other.abilities_used;
+        self.tiles_moved += other.tiles_moved;
+        self.turns_survived += other.turns_survived;
+        self.kills += other.kills;
+    }
+}
+
+/// Tiles between two cells when moving diagonally counts as one step.
+pub fn tiles_between(a: &Position, b: &Position) -> u32 {
+    a.x.abs_diff(b.x).max(a.y.abs_diff(b.y)) as u32
+}
+
+/// Per-unit metrics for one battle, keyed by unit id. Recorded by the
+/// encounter as commands and AI actions resolve and saved with it.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct BattleStats {
+    pub units: BTreeMap<String, UnitStats>,
+    /// Rounds started so far.
+    pub rounds: u32,
+}
+
+impl BattleStats {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Stats for `unit_id`, all zero if it has done nothing yet.
+    pub fn unit(&self, unit_id: &str) -> UnitStats {
+        self.units.get(unit_id).copied().unwrap_or_default()
+    }
+
+    pub fn unit_mut(&mut self, unit_id: &str) -> &mut UnitStats {
+        self.units.entry(unit_id.to_string()).or_default()
+    }
+
+    /// A weapon attack that took `damage` health from the target.
+    pub fn record_attack(&mut self, attacker_id: &str, target_id: &str, hit: bool, damage: i32) {
+        let attacker = self.unit_mut(attacker_id);
+        attacker.attacks += 1;
+        attacker.hits += hit as u32;
+        self.record_damage(Some(attacker_id), target_id, damage);
+    }
+
+    /// Health lost by `target_id`, credited to `source_id` if a unit caused
+    /// it. Healing (negative damage) is ignored.
+    pub fn record_damage(&mut self, source_id: Option<&str>, target_id: &str, damage: i32) {
+        let Ok(damage) = u32::try_from(damage) else { return };
+        if damage == 0 {
+            return;
+        }
+        self.unit_mut(target_id).damage_taken += damage;
+        if let Some(source) = source_id {
+            self.unit_mut(source).damage_dealt += damage;
+        }
+    }
+
+    /// Credit `killer_id` with a kill if its hit took a unit from `before`
+    /// health to down.
+    pub fn record_kill(&mut self, killer_id: &str, before: i32, after: i32) {
+        if before > 0 && after <= 0 {
+            self.unit_mut(killer_id).kills += 1;
+        }
+    }
+
+    pub fn record_ability(&mut self, user_id: &str) {
+        self.unit_mut(user_id).abilities_used += 1;
+    }
+
+    pub fn record_move(&mut self, unit_id: &str, from: &Position, to: &Position) {
+        self.unit_mut(unit_id).tiles_moved += tiles_between(from, to);
+    }
+
+    /// Take back a move recorded with [`BattleStats::record_move`].
+    pub fn undo_move(&mut self, unit_id: &str, from: &Position, to: &Position) {
+        let stats = self.unit_mut(unit_id);
+        stats.tiles_moved = stats.tiles_moved.saturating_sub(tiles_between(from, to));
+    }
+
+    pub fn record_turn_survived(&mut self, unit_id: &str) {
+        self.unit_mut(unit_id).turns_survived += 1;
+    }
+
+    /// Every unit's stats summed.
+    pub fn totals(&self) -> UnitStats {
+        self.units.values().fold(UnitStats::default(), |mut total, s| {
+            total.add(s);
+            total
+        })
+    }
+}
+
+/// Aggregates over every battle of a campaign, saved with it.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct CampaignStats {
+    pub battles: u32,
+    pub victories: u32,
+    pub defeats: u32,
+    pub rounds: u32,
+    /// Per-unit totals across battles, keyed by unit id.
+    pub units: BTreeMap<String, UnitStats>,
+}
+
+impl CampaignStats {
+    pub fn record_battle(&mut self, battle: &BattleStats, outcome: BattleOutcome) {
+        self.battles += 1;
+        match outcome {
+            BattleOutcome::PlayerVictory => self.victories += 1,
+            BattleOutcome::EnemyVictory => self.defeats += 1,
+            BattleOutcome::Draw => {}
+        }
+        self.rounds += battle.rounds;
+        for (id, stats) in &battle.units {
+            self.units.entry(id.clone()).or_default().add(stats);
+        }
+    }
+
+    /// Every unit's campaign stats summed.
+    pub fn totals(&self) -> UnitStats {
+        self.units.values().fold(UnitStats::default(), |mut total, s| {
+            total.add(s);
+            total
+        })
+    }
+}
+
+impl Campaign {
+    /// Fold a finished battle into the campaign-wide stats.
+    pub fn record_battle(&mut self, battle: &BattleStats, outcome: BattleOutcome) {
+        self.stats.record_battle(battle, outcome);
+    }
+}
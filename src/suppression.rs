@@ -0,0 +1,136 @@
+use crate::combat::{manhattan, CombatEncounter, CombatError, CombatEvent};
+use crate::durability::WEAPON_WEAR_PER_ATTACK;
+use crate::grenade::blast_cells;
+use crate::models::{AnimationType, EffectType, Position, StatusEffect, Unit};
+use crate::modifiers::{Modifier, ModifierSource, Stat};
+use crate::registry::UnitId;
+use crate::rng::GameRng;
+use crate::skills::{skill_test, TestDifficulty};
+
+/// Action points suppressing fire costs on top of the weapon's own.
+pub const SUPPRESSION_EXTRA_AP: u32 = 1;
+/// Shots in a burst of suppressing fire; each wears the weapon like an
+/// attack.
+pub const SUPPRESSION_BURST: u32 = 3;
+/// Radius of the area swept around the aimed cell.
+pub const SUPPRESSION_RADIUS: u32 = 1;
+/// Accuracy lost while suppressed.
+pub const SUPPRESSION_ACCURACY_PENALTY: i32 = -20;
+/// Movement points lost while suppressed.
+pub const SUPPRESSION_MOVEMENT_PENALTY: i32 = -1;
+/// Turns suppression lasts, counted on the suppressed unit's turns.
+pub const SUPPRESSION_TURNS: u32 = 1;
+/// Willpower test a suppressed unit takes at the start of its turn; on a
+/// failure it is pinned and can't attack that turn.
+pub const NERVE_TEST: TestDifficulty = TestDifficulty::Challenging;
+/// Opponents the AI wants under the area before it suppresses instead of
+/// shooting.
+pub const AI_SUPPRESSION_TARGETS: usize = 3;
+
+fn has_status(unit: &Unit, effect: EffectType) -> bool {
+    unit.status_effects.iter().any(|s| s.effect_type == effect && s.remaining_turns > 0)
+}
+
+impl Unit {
+    pub fn is_suppressed(&self) -> bool {
+        has_status(self, EffectType::Suppression)
+    }
+
+    /// Pinned units keep their heads down: they can move but not attack.
+    pub fn is_pinned(&self) -> bool {
+        has_status(self, EffectType::Pinned)
+    }
+
+    /// Put the unit under suppression, or keep it there if it already is.
+    fn suppress(&mut self) {
+        if let Some(status) = self.status_effects.iter_mut().find(|s| s.effect_type == EffectType::Suppression) {
+            status.remaining_turns = status.remaining_turns.max(SUPPRESSION_TURNS);
+            return;
+        }
+        self.status_effects.push(StatusEffect { effect_type: EffectType::Suppression, remaining_turns: SUPPRESSION_TURNS, magnitude: 0 });
+        for (stat, amount) in [(Stat::Accuracy, SUPPRESSION_ACCURACY_PENALTY), (Stat::Movement, SUPPRESSION_MOVEMENT_PENALTY)] {
+            self.add_modifier(Modifier::timed(ModifierSource::Status, stat, amount, SUPPRESSION_TURNS));
+        }
+    }
+
+    /// Take the nerve test for a suppressed unit at the start of its turn.
+    /// Returns whether it failed and the unit is now pinned.
+    pub(crate) fn test_nerve(&mut self, rng: &mut GameRng) -> bool {
+        if !self.is_suppressed() || skill_test(self, Stat::Willpower, NERVE_TEST, rng).success {
+            return false;
+        }
+        self.status_effects.push(StatusEffect { effect_type: EffectType::Pinned, remaining_turns: 1, magnitude: 0 });
+        true
+    }
+}
+
+impl CombatEncounter {
+    /// Lay down suppressing fire with the unit's ranged weapon on the area
+    /// around `target`. It costs extra AP and a burst's worth of weapon
+    /// wear, and suppresses every living opponent caught. Returns their ids.
+    pub fn suppress(&mut self, attacker_id: &str, target: &Position) -> Result<Vec<String>, CombatError> {
+        let handle = self.units.id(attacker_id).ok_or(CombatError::UnknownUnit)?;
+        let attacker = &self.units[handle];
+        let weapon = attacker.equipment.weapon.as_ref().ok_or(CombatError::NoWeapon)?;
+        if weapon.is_melee() {
+            return Err(CombatError::MeleeCantSuppress);
+        }
+        if !self.battlefield.in_bounds(target) {
+            return Err(CombatError::InvalidTarget);
+        }
+        if manhattan(&attacker.grid_position, target) > weapon.range {
+            return Err(CombatError::OutOfRange);
+        }
+        let cost = weapon.action_point_cost + SUPPRESSION_EXTRA_AP;
+        if attacker.action_points < cost {
+            return Err(CombatError::InsufficientAp);
+        }
+        let side = self.units.side(handle);
+        let attacker = &mut self.units[handle];
+        attacker.action_points -= cost;
+        let wear = WEAPON_WEAR_PER_ATTACK * SUPPRESSION_BURST;
+        attacker.equipment.weapon_durability = attacker.equipment.weapon_durability.saturating_sub(wear);
+        attacker.animation_state.current_animation = AnimationType::Attack;
+
+        let cells = blast_cells(&self.battlefield, target, SUPPRESSION_RADIUS);
+        let caught: Vec<UnitId> = self
+            .units
+            .ids()
+            .filter(|&id| self.units.side(id) != side)
+            .filter(|&id| self.units[id].health_points > 0 && cells.contains(&self.units[id].grid_position))
+            .collect();
+        let mut suppressed = Vec::new();
+        for id in caught {
+            let unit = &mut self.units[id];
+            unit.suppress();
+            self.events.push(CombatEvent::UnitSuppressed { unit_id: unit.id.clone() });
+            suppressed.push(unit.id.clone());
+        }
+        Ok(suppressed)
+    }
+
+    /// Where the unit should aim suppressing fire: the opponent in range
+    /// whose area catches the most opponents not yet suppressed, if that
+    /// is at least [`AI_SUPPRESSION_TARGETS`].
+    pub fn ai_suppression_target(&self, unit_id: &str) -> Option<Position> {
+        let handle = self.units.id(unit_id)?;
+        let unit = &self.units[handle];
+        let weapon = unit.equipment.weapon.as_ref().filter(|w| !w.is_melee())?;
+        if unit.action_points < weapon.action_point_cost + SUPPRESSION_EXTRA_AP {
+            return None;
+        }
+        let view = self.knowledge_of(handle)?;
+        let exposed: Vec<&Position> = view.opponents().filter(|u| !u.is_suppressed()).map(|u| &u.grid_position).collect();
+        exposed
+            .iter()
+            .filter(|p| manhattan(&unit.grid_position, p) <= weapon.range)
+            .map(|&center| {
+                let cells = blast_cells(&self.battlefield, center, SUPPRESSION_RADIUS);
+                (exposed.iter().filter(|p| cells.contains(p)).count(), center)
+            })
+            .filter(|&(caught, _)| caught >= AI_SUPPRESSION_TARGETS)
+            .rev()
+            .max_by_key(|&(caught, _)| caught)
+            .map(|(_, center)| center.clone())
+    }
+}
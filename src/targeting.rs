@@ -0,0 +1,60 @@
+use crate::combat::{manhattan, CombatEncounter};
+use crate::models::{EffectType, StatusEffect, Unit};
+use crate::modifiers::{Situation, Stat};
+use crate::registry::{UnitId, UnitRegistry};
+
+/// Concealment the [`Stealthy`](crate::models::Trait::Stealthy) trait gives.
+pub const STEALTHY_CONCEALMENT: i32 = 3;
+
+impl Unit {
+    /// Id of the unit taunting this one, while the taunt lasts.
+    pub fn taunter(&self) -> Option<&str> {
+        let taunted = self.status_effects.iter().any(|s| s.effect_type == EffectType::Taunted && s.remaining_turns > 0);
+        self.taunted_by.as_deref().filter(|_| taunted)
+    }
+
+    /// Extra cells the AI counts to this unit when picking a target: from a
+    /// hunkered stance and from [`Stat::Concealment`] modifiers, its traits'
+    /// included.
+    pub fn concealment(&self) -> u32 {
+        let situation = Situation::default();
+        let total = self.equipment_modifiers().total(Stat::Concealment, &situation)
+            + self.modifiers.total(Stat::Concealment, &situation);
+        let modifiers = total.max(0) as u32;
+        self.stance.map_or(0, |s| s.ai_target_penalty()) + modifiers
+    }
+}
+
+impl UnitRegistry {
+    /// The opponent the AI controlling `actor` goes for: its taunter while
+    /// that is alive, otherwise the nearest, with concealed units counted
+    /// as further away than they are.
+    pub fn ai_target(&self, actor: UnitId) -> Option<UnitId> {
+        self.ai_target_where(actor, |_| true)
+    }
+
+    /// [`UnitRegistry::ai_target`] choosing among the opponents `known`
+    /// lets through. A taunter is always known.
+    pub fn ai_target_where(&self, actor: UnitId, known: impl Fn(&Unit) -> bool) -> Option<UnitId> {
+        let unit = &self[actor];
+        let opponent = self.side(actor)?.opponent();
+        let fair_game = |i: UnitId| self.side(i) == Some(opponent) && self[i].health_points > 0 && self[i].embarked_in.is_none();
+        if let Some(taunter) = unit.taunter().and_then(|id| self.id(id)).filter(|&i| fair_game(i)) {
+            return Some(taunter);
+        }
+        self.ids()
+            .filter(|&i| fair_game(i) && known(&self[i]))
+            .min_by_key(|&i| manhattan(&unit.grid_position, &self[i].grid_position) + self[i].concealment())
+    }
+}
+
+impl CombatEncounter {
+    /// Force `target_id` to go after `taunter_id` for `turns` of its own
+    /// turns when the AI controls it. A fresh taunt replaces the last.
+    pub fn taunt(&mut self, taunter_id: &str, target_id: &str, turns: u32) {
+        let Some(target) = self.unit_by_id_mut(target_id) else { return };
+        target.status_effects.retain(|s| s.effect_type != EffectType::Taunted);
+        target.status_effects.push(StatusEffect { effect_type: EffectType::Taunted, remaining_turns: turns, magnitude: 0 });
+        target.taunted_by = Some(taunter_id.to_string());
+    }
+}
@@ -0,0 +1,149 @@
+use serde::{Deserialize, Serialize};
+
+use crate::combat::{apply_ability_effect, hp_change_event, manhattan, CombatEncounter, CombatError};
+use crate::grid::{GridMap, TerrainType};
+use crate::models::{AreaOfEffect, Position};
+
+/// A change an ability makes to the battlefield under it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TerrainShift {
+    /// Raise a wall: open cells without a unit on them become blocked for
+    /// `turns` rounds.
+    Wall { turns: u32 },
+    /// Knock blocked cells down into difficult rubble, for good.
+    Demolish,
+    /// Ice over open and difficult cells, making them hazardous for
+    /// `turns` rounds.
+    Ice { turns: u32 },
+}
+
+/// Terrain an ability changed for a while, and what it was before.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TerrainChange {
+    pub cells: Vec<(Position, TerrainType)>,
+    pub turns_remaining: u32,
+}
+
+/// Cells an area ability aimed at `target` from `from` covers: a circle for
+/// circles and cones, and for lines a run of cells across the line of fire,
+/// centred on the target, so a wall stands between caster and target.
+pub fn area_cells(map: &GridMap, from: &Position, target: &Position, area: Option<&AreaOfEffect>) -> Vec<Position> {
+    match area {
+        None => vec![target.clone()],
+        Some(AreaOfEffect::Circle { radius } | AreaOfEffect::Cone { radius }) => map.circle(target, *radius),
+        Some(AreaOfEffect::Line { length }) => {
+            let across_x = target.x.abs_diff(from.x) < target.y.abs_diff(from.y);
+            let half = (*length as i64 - 1) / 2;
+            (-half..*length as i64 - half)
+                .filter_map(|offset| {
+                    let (x, y) = if across_x {
+                        (target.x as i64 + offset, target.y as i64)
+                    } else {
+                        (target.x as i64, target.y as i64 + offset)
+                    };
+                    (x >= 0 && y >= 0).then_some(Position { x: x as usize, y: y as usize })
+                })
+                .filter(|p| map.in_bounds(p))
+                .collect()
+        }
+    }
+}
+
+impl CombatEncounter {
+    /// Apply `shift` to `cells`. Temporary changes are put back by
+    /// [`CombatEncounter::tick_terrain`]; path caches rebuild on their own
+    /// as the map's revision moves on. Returns the cells changed.
+    pub fn alter_terrain(&mut self, shift: TerrainShift, cells: &[Position]) -> Vec<Position> {
+        let occupied: Vec<Position> =
+            self.units.iter().filter(|u| u.health_points > 0).map(|u| u.grid_position.clone()).collect();
+        let map = &mut self.battlefield;
+        let mut changed = Vec::new();
+        for cell in cells {
+            if !map.in_bounds(cell) {
+                continue;
+            }
+            let from = map.terrain_at(cell).clone();
+            let to = match (shift, &from) {
+                (TerrainShift::Wall { .. }, TerrainType::Blocked) => continue,
+                (TerrainShift::Wall { .. }, _) if occupied.contains(cell) => continue,
+                (TerrainShift::Wall { .. }, _) => TerrainType::Blocked,
+                (TerrainShift::Demolish, TerrainType::Blocked) => TerrainType::Difficult,
+                (TerrainShift::Ice { .. }, TerrainType::Normal | TerrainType::Difficult) => TerrainType::Hazardous,
+                _ => continue,
+            };
+            if shift == TerrainShift::Demolish {
+                map.structures.retain(|s| s.position != *cell);
+                map.fragile.remove(cell);
+            }
+            map.set_terrain(cell, to);
+            changed.push((cell.clone(), from));
+        }
+        let turns = match shift {
+            TerrainShift::Wall { turns } | TerrainShift::Ice { turns } => Some(turns),
+            TerrainShift::Demolish => None,
+        };
+        let cells = changed.iter().map(|(c, _)| c.clone()).collect();
+        if let Some(turns) = turns
+            && !changed.is_empty()
+        {
+            self.terrain_changes.push(TerrainChange { cells: changed, turns_remaining: turns });
+        }
+        cells
+    }
+
+    /// Count temporary terrain down by a round, restoring what has run
+    /// out. Called from [`CombatEncounter::tick_environment`].
+    pub fn tick_terrain(&mut self) {
+        for change in &mut self.terrain_changes {
+            change.turns_remaining = change.turns_remaining.saturating_sub(1);
+        }
+        let (expired, left) = std::mem::take(&mut self.terrain_changes).into_iter().partition(|c| c.turns_remaining == 0);
+        self.terrain_changes = left;
+        for change in expired {
+            for (cell, terrain) in change.cells {
+                self.battlefield.set_terrain(&cell, terrain);
+            }
+        }
+    }
+
+    /// Use a terrain-altering ability on the cell `target`. Its effect
+    /// lands on every living unit in the area, then the terrain changes.
+    pub fn use_ability_at(&mut self, user_id: &str, ability_index: usize, target: &Position) -> Result<(), CombatError> {
+        let user = self.unit_by_id(user_id).ok_or(CombatError::UnknownUnit)?;
+        let ability = user.abilities.get(ability_index).ok_or(CombatError::InvalidAbility)?;
+        let shift = ability.effect.alters_terrain.ok_or(CombatError::NeedsUnitTarget)?;
+        if !self.battlefield.in_bounds(target) {
+            return Err(CombatError::InvalidTarget);
+        }
+        if user.action_points < ability.action_point_cost {
+            return Err(CombatError::InsufficientAp);
+        }
+        if ability.current_cooldown > 0 {
+            return Err(CombatError::OnCooldown);
+        }
+        if manhattan(&user.grid_position, target) > ability.range {
+            return Err(CombatError::OutOfRange);
+        }
+        let cells = area_cells(&self.battlefield, &user.grid_position, target, ability.area_of_effect.as_ref());
+        let effect = ability.effect.clone();
+        let user = self.unit_by_id_mut(user_id).ok_or(CombatError::UnknownUnit)?;
+        let ability = &mut user.abilities[ability_index];
+        user.action_points -= ability.action_point_cost;
+        ability.current_cooldown = ability.cooldown;
+        user.animation_state.current_animation = ability.animation.clone();
+
+        for unit in self.units.iter_mut().filter(|u| u.health_points > 0 && u.embarked_in.is_none() && cells.contains(&u.grid_position)) {
+            let before = unit.health_points;
+            apply_ability_effect(&effect, unit);
+            self.stats.record_damage(Some(user_id), &unit.id, before - unit.health_points);
+            self.stats.record_kill(user_id, before, unit.health_points);
+            self.events.extend(hp_change_event(&unit.id, before, unit.health_points, false));
+        }
+        self.stats.record_ability(user_id);
+        if let Some(hazard) = &effect.creates_hazard {
+            self.spawn_hazard(hazard, &cells);
+        }
+        self.alter_terrain(shift, &cells);
+        Ok(())
+    }
+}
@@ -0,0 +1,163 @@
+use std::collections::BTreeSet;
+
+use proptest::prelude::*;
+use proptest::sample::select;
+
+use crate::builders::{UnitBuilder, WeaponBuilder};
+use crate::combat::CombatEncounter;
+use crate::damage::DamageType;
+use crate::grid::{GridMap, RegionShape, TerrainType, TriggerRegion};
+use crate::models::{Faction, MovementType, Position, Stats, Unit, UnitType, Weapon};
+
+/// Largest side of a generated map.
+pub const MAX_MAP_SIZE: usize = 12;
+/// Most units a generated encounter puts on each side.
+pub const MAX_SQUAD: usize = 4;
+
+const TERRAIN: [TerrainType; 4] = [TerrainType::Normal, TerrainType::Difficult, TerrainType::Hazardous, TerrainType::Blocked];
+const DAMAGE_TYPES: [DamageType; 5] = [DamageType::Kinetic, DamageType::Energy, DamageType::Fire, DamageType::Toxic, DamageType::Warp];
+const MOVEMENT_TYPES: [MovementType; 3] = [MovementType::Ground, MovementType::Jump, MovementType::Flying];
+
+/// What [`maps`] may put on a cell besides its terrain.
+#[derive(Debug, Clone, Copy)]
+enum Feature {
+    Fragile,
+    Flammable,
+    Region,
+}
+
+/// Mostly plain cells, so fragile objects don't wall every map in.
+const FEATURES: [Option<Feature>; 6] = [None, None, None, Some(Feature::Fragile), Some(Feature::Flammable), Some(Feature::Region)];
+
+/// A unit of `unit_type` at `position`, with its stats applied and full
+/// health and action points.
+pub fn unit(id: &str, unit_type: UnitType, faction: Faction, position: Position) -> Unit {
+    UnitBuilder::new(id, unit_type, faction).at(position.x, position.y).build()
+}
+
+/// A basic kinetic weapon costing one action point.
+pub fn weapon(damage: i32, accuracy: f32, range: u32) -> Weapon {
+    WeaponBuilder::new("testkit_weapon").damage(damage).accuracy(accuracy).range(range).build()
+}
+
+/// A map of `width` by `height` with `terrain` laid out row by row; cells
+/// past its end are left normal.
+pub fn map(width: usize, height: usize, terrain: &[TerrainType]) -> GridMap {
+    let mut map = GridMap::new(width, height);
+    for (i, t) in terrain.iter().take(width * height).enumerate() {
+        map.set_terrain(&Position { x: i % width, y: i / width }, t.clone());
+    }
+    map
+}
+
+/// Cells a unit may stand on, row by row.
+pub fn open_cells(map: &GridMap) -> Vec<Position> {
+    (0..map.height)
+        .flat_map(|y| (0..map.width).map(move |x| Position { x, y }))
+        .filter(|p| !matches!(map.terrain_at(p), TerrainType::Blocked))
+        .collect()
+}
+
+pub fn stats() -> impl Strategy<Value = Stats> {
+    (0..8i32, 0..8i32, 0..8i32, 0..8i32, 1..30i32, 1..4u32, -20..20i32).prop_map(
+        |(strength, toughness, agility, willpower, max_health, max_action, accuracy)| Stats {
+            strength,
+            toughness,
+            agility,
+            willpower,
+            max_health,
+            max_action,
+            accuracy,
+            ..Stats::default()
+        },
+    )
+}
+
+pub fn weapons() -> impl Strategy<Value = Weapon> {
+    (0..12i32, 0.0..1.0f32, 1..10u32, 1..3u32, select(&DAMAGE_TYPES[..])).prop_map(
+        |(damage, accuracy, range, cost, damage_type)| {
+            WeaponBuilder::new("testkit_weapon")
+                .damage(damage)
+                .accuracy(accuracy)
+                .range(range)
+                .action_point_cost(cost)
+                .damage_type(damage_type)
+                .build()
+        },
+    )
+}
+
+/// A unit of `faction` with random stats, movement and weapon, if any,
+/// somewhere between one health point and full health.
+pub fn units(faction: Faction) -> impl Strategy<Value = Unit> {
+    (stats(), proptest::option::of(weapons()), select(&MOVEMENT_TYPES[..]), 0.0..1.0f64).prop_map(
+        move |(stats, weapon, movement_type, health)| {
+            let mut unit = Unit::new("unit", "Unit", UnitType::Guardsman, faction.clone());
+            unit.base_stats = stats;
+            unit.equipment.weapon = weapon;
+            unit.movement_type = movement_type;
+            unit.recalculate_stats();
+            let max = unit.current_stats.max_health;
+            unit.health_points = 1 + ((max - 1) as f64 * health) as i32;
+            unit.action_points = unit.current_stats.max_action;
+            unit
+        },
+    )
+}
+
+/// A map up to [`MAX_MAP_SIZE`] a side with random terrain and at least
+/// two open cells. Some cells hold fragile objects or are flammable, and
+/// some make up a cell-list trigger region, so the map's cell sets get
+/// exercised too.
+pub fn maps() -> impl Strategy<Value = GridMap> {
+    (2..=MAX_MAP_SIZE, 2..=MAX_MAP_SIZE)
+        .prop_flat_map(|(width, height)| {
+            let cells = width * height;
+            (proptest::collection::vec(select(&TERRAIN[..]), cells), proptest::collection::vec(select(&FEATURES[..]), cells))
+                .prop_map(move |(terrain, features)| {
+                    let mut map = map(width, height, &terrain);
+                    let mut region = BTreeSet::new();
+                    for (i, feature) in features.into_iter().enumerate() {
+                        let cell = Position { x: i % width, y: i / width };
+                        match feature {
+                            Some(Feature::Fragile) => map.place_fragile(&cell),
+                            Some(Feature::Flammable) => _ = map.flammable.insert(cell),
+                            Some(Feature::Region) => _ = region.insert(cell),
+                            None => {}
+                        }
+                    }
+                    if !region.is_empty() {
+                        map.regions.push(TriggerRegion { name: "testkit_region".into(), shape: RegionShape::Cells(region) });
+                    }
+                    map
+                })
+        })
+        .prop_filter("needs two open cells", |map| open_cells(map).len() >= 2)
+}
+
+/// An encounter on a random map with up to [`MAX_SQUAD`] random units a
+/// side, each on its own open cell, and a seeded RNG.
+pub fn encounters() -> impl Strategy<Value = CombatEncounter> {
+    (maps(), 1..=MAX_SQUAD, 1..=MAX_SQUAD, any::<u64>()).prop_flat_map(|(map, players, enemies, seed)| {
+        let cells = open_cells(&map);
+        let players = players.min(cells.len() - 1);
+        let enemies = enemies.min(cells.len() - players);
+        (
+            Just(map),
+            proptest::collection::vec(units(Faction::Imperial), players),
+            proptest::collection::vec(units(Faction::Ork), enemies),
+            Just(cells).prop_shuffle(),
+            Just(seed),
+        )
+            .prop_map(|(map, mut players, mut enemies, cells, seed)| {
+                let squads = players.iter_mut().map(|u| (u, "player")).chain(enemies.iter_mut().map(|u| (u, "enemy")));
+                for (i, ((unit, side), cell)) in squads.zip(cells).enumerate() {
+                    unit.id = format!("{side}_{i}");
+                    unit.grid_position = cell;
+                }
+                let mut encounter = CombatEncounter::new(players, enemies, map, None);
+                encounter.rng = crate::rng::GameRng::new(seed);
+                encounter
+            })
+    })
+}
@@ -0,0 +1,192 @@
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::challenge::{ChallengeRun, ChallengeUnit};
+use crate::combat::{CombatEncounter, CombatError, CombatEvent};
+use crate::command::Command;
+use crate::ioutil::invalid;
+use crate::models::Position;
+use crate::rng::GameRng;
+use crate::scenario::Scenario;
+
+/// A command a tutorial step lets through. Fields left out match
+/// anything.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum AllowedAction {
+    Move {
+        unit_id: String,
+        #[serde(default)]
+        destination: Option<Position>,
+    },
+    Attack {
+        attacker_id: String,
+        #[serde(default)]
+        target_id: Option<String>,
+    },
+    UseAbility { user_id: String, ability_index: usize },
+    EndTurn,
+}
+
+impl AllowedAction {
+    pub fn permits(&self, cmd: &Command) -> bool {
+        match (self, cmd) {
+            (AllowedAction::Move { unit_id, destination }, Command::Move { unit_id: id, destination: to }) => {
+                unit_id == id && destination.as_ref().is_none_or(|d| d == to)
+            }
+            (AllowedAction::Attack { attacker_id, target_id }, Command::Attack { attacker_id: a, target_id: t }) => {
+                attacker_id == a && target_id.as_ref().is_none_or(|id| id == t)
+            }
+            (
+                AllowedAction::UseAbility { user_id, ability_index },
+                Command::UseAbility { user_id: u, ability_index: i, .. } | Command::UseAbilityAt { user_id: u, ability_index: i, .. },
+            ) => user_id == u && ability_index == i,
+            (AllowedAction::EndTurn, Command::EndTurn) => true,
+            _ => false,
+        }
+    }
+}
+
+/// The part of the screen a tutorial step points the player at.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Highlight {
+    Unit { unit_id: String },
+    Cell { position: Position },
+    Ability { unit_id: String, ability_index: usize },
+    EndTurnButton,
+}
+
+/// One instruction of a tutorial.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TutorialStep {
+    /// Locale key of the instruction shown.
+    pub text: String,
+    #[serde(default)]
+    pub highlight: Option<Highlight>,
+    /// Commands the player may give during the step; empty lets any
+    /// through.
+    #[serde(default)]
+    pub allowed: Vec<AllowedAction>,
+    /// The event that completes the step, written as a [`CombatEvent`]
+    /// with only the fields that matter, e.g.
+    /// `{ "UnitDamaged": { "unit_id": "ork" } }`. Without one the step is
+    /// done once an allowed command goes through.
+    #[serde(default)]
+    pub advance_on: Option<Value>,
+}
+
+impl TutorialStep {
+    /// Whether `event` is the one the step is waiting for.
+    pub fn completed_by(&self, event: &CombatEvent) -> bool {
+        let Some(expected) = &self.advance_on else { return false };
+        serde_json::to_value(event).is_ok_and(|actual| contains(&actual, expected))
+    }
+}
+
+/// Whether `actual` has every field of `expected`, recursively.
+fn contains(actual: &Value, expected: &Value) -> bool {
+    match (actual, expected) {
+        (Value::Object(actual), Value::Object(expected)) => {
+            expected.iter().all(|(key, value)| actual.get(key).is_some_and(|a| contains(a, value)))
+        }
+        _ => actual == expected,
+    }
+}
+
+/// A scripted lesson: a small fixed battle played one instruction at a
+/// time. `name` is a locale key.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Tutorial {
+    pub id: String,
+    pub name: String,
+    pub seed: u64,
+    pub scenario: Scenario,
+    pub squad: Vec<ChallengeUnit>,
+    pub enemies: Vec<ChallengeUnit>,
+    pub steps: Vec<TutorialStep>,
+}
+
+impl Tutorial {
+    pub fn load<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        Self::from_json(&fs::read_to_string(path)?)
+    }
+
+    /// Parse a tutorial, rejecting ones without steps or without a unit
+    /// on either side.
+    pub fn from_json(data: &str) -> io::Result<Self> {
+        let tutorial: Tutorial = serde_json::from_str(data).map_err(invalid)?;
+        if tutorial.steps.is_empty() {
+            return Err(invalid(format!("tutorial '{}' has no steps", tutorial.id)));
+        }
+        if tutorial.squad.is_empty() || tutorial.enemies.is_empty() {
+            return Err(invalid(format!("tutorial '{}' needs units on both sides", tutorial.id)));
+        }
+        Ok(tutorial)
+    }
+
+    /// Start the lesson, up to the squad's first turn.
+    pub fn start(&self) -> TutorialRun {
+        let squad = self.squad.iter().map(ChallengeUnit::spawn).collect();
+        let enemies = self.enemies.iter().map(ChallengeUnit::spawn).collect();
+        let mut encounter = self.scenario.encounter(squad, enemies, None);
+        encounter.rng = GameRng::new(self.seed);
+        let mut run = ChallengeRun { par_rounds: u32::MAX, encounter };
+        run.advance();
+        TutorialRun { run, steps: self.steps.clone(), current: 0 }
+    }
+}
+
+/// A tutorial being played. Only the current step's commands are let
+/// through; enemy turns play out by themselves.
+#[derive(Debug, Clone)]
+pub struct TutorialRun {
+    run: ChallengeRun,
+    steps: Vec<TutorialStep>,
+    current: usize,
+}
+
+impl TutorialRun {
+    pub fn encounter(&self) -> &CombatEncounter {
+        &self.run.encounter
+    }
+
+    /// The step being played, or `None` once the tutorial is over.
+    pub fn step(&self) -> Option<&TutorialStep> {
+        self.steps.get(self.current)
+    }
+
+    /// Index of the current step.
+    pub fn step_index(&self) -> usize {
+        self.current
+    }
+
+    pub fn is_complete(&self) -> bool {
+        self.current >= self.steps.len()
+    }
+
+    /// Execute `cmd` if the current step allows it, moving on to the next
+    /// step when it completes this one.
+    pub fn execute(&mut self, cmd: Command) -> Result<(), CombatError> {
+        if let Some(step) = self.step()
+            && !step.allowed.is_empty()
+            && !step.allowed.iter().any(|a| a.permits(&cmd))
+        {
+            return Err(CombatError::TutorialStep);
+        }
+        let events_before = self.run.encounter.events.len();
+        self.run.execute(cmd)?;
+        if let Some(step) = self.step() {
+            let done = match step.advance_on {
+                Some(_) => self.run.encounter.events[events_before..].iter().any(|e| step.completed_by(e)),
+                None => true,
+            };
+            if done {
+                self.current += 1;
+            }
+        }
+        Ok(())
+    }
+}
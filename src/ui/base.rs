@@ -0,0 +1,67 @@
+use crate::base::Facility;
+use crate::campaign::Campaign;
+use crate::frontend::text::{DAMAGE_RED, WHITE};
+use crate::frontend::{DrawCall, DrawLayer, Renderer};
+use crate::input::GameAction;
+use crate::localization::Localizer;
+
+use super::dialogue::CHOICE_SELECTED;
+
+/// Between-mission screen listing the base's facilities with their levels
+/// and next upgrade cost. `Activate` buys the selected upgrade.
+#[derive(Debug, Clone, Default)]
+pub struct BaseScreen {
+    /// Index into [`Facility::ALL`].
+    pub selected_index: usize,
+    /// Error from the last upgrade, shown until the next input.
+    pub last_error: Option<&'static str>,
+}
+
+impl BaseScreen {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn handle_input(&mut self, action: GameAction, campaign: &mut Campaign) {
+        self.last_error = None;
+        match action {
+            GameAction::SelectUp => self.selected_index = self.selected_index.saturating_sub(1),
+            GameAction::SelectDown => {
+                if self.selected_index + 1 < Facility::ALL.len() {
+                    self.selected_index += 1;
+                }
+            }
+            GameAction::Activate => {
+                if let Some(&facility) = Facility::ALL.get(self.selected_index) {
+                    self.last_error = campaign.upgrade_facility(facility).err();
+                }
+            }
+        }
+    }
+
+    pub fn render(&self, renderer: &mut Renderer, loc: &Localizer, campaign: &Campaign) {
+        renderer.submit(DrawCall::new(loc.get("panel.base"), (0, 0), DrawLayer::Ui));
+        let header = format!("{} {}   {} {}", loc.get("requisition.funds"), campaign.currency, loc.get("base.morale"), campaign.morale);
+        renderer.draw_text(&header, (16, 16), 18.0, WHITE);
+        for (i, &facility) in Facility::ALL.iter().enumerate() {
+            let (level, max) = (campaign.facility_level(facility).to_string(), facility.max_level().to_string());
+            let cost = campaign.facility_upgrade_cost(facility);
+            let next = match cost {
+                Some(cost) => format!("{} {}", loc.get("base.upgrade"), cost),
+                None => loc.get("base.maxed"),
+            };
+            let line = format!("{} - {} - {}", loc.get(facility.label_key()), loc.format("base.level", &[("level", &level), ("max", &max)]), next);
+            let color = if i == self.selected_index {
+                CHOICE_SELECTED
+            } else if cost.is_some_and(|c| c > campaign.currency) {
+                DAMAGE_RED
+            } else {
+                WHITE
+            };
+            renderer.draw_text(&line, (16, 56 + i as u32 * 28), 16.0, color);
+        }
+        if let Some(err) = self.last_error {
+            renderer.draw_text(&loc.get(err), (16, renderer.height - 32), 16.0, DAMAGE_RED);
+        }
+    }
+}
@@ -0,0 +1,57 @@
+use std::collections::VecDeque;
+
+use crate::audio::Caption;
+use crate::frontend::text::WHITE;
+use crate::frontend::{DrawCall, DrawLayer, Renderer};
+use crate::localization::Localizer;
+
+/// Most captions shown at once; older ones are dropped first.
+pub const MAX_VISIBLE_CAPTIONS: usize = 3;
+
+/// Subtitles for sounds that just played, stacked above the bottom bar
+/// until each one's time runs out.
+#[derive(Debug, Clone, Default)]
+pub struct CaptionsWidget {
+    /// Captions with their seconds remaining, oldest first.
+    pub active: VecDeque<(Caption, f32)>,
+}
+
+impl CaptionsWidget {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn push(&mut self, caption: Caption) {
+        let duration = caption.duration;
+        self.active.push_back((caption, duration));
+        while self.active.len() > MAX_VISIBLE_CAPTIONS {
+            self.active.pop_front();
+        }
+    }
+
+    /// Count captions down by `dt` seconds and drop the expired ones.
+    pub fn update(&mut self, dt: f32) {
+        for (_, remaining) in &mut self.active {
+            *remaining -= dt;
+        }
+        self.active.retain(|(_, remaining)| *remaining > 0.0);
+    }
+
+    /// Draw each caption as "Speaker: text", newest at the bottom, ending
+    /// just above `bottom`. Strings are looked up now so a language switch
+    /// applies to captions already on screen.
+    pub fn render(&self, renderer: &mut Renderer, loc: &Localizer, bottom: u32) {
+        let x = renderer.width / 8;
+        let width = renderer.width - 2 * x;
+        let line_height = (16.0 * renderer.font_scale * 1.25).round() as u32;
+        let mut y = bottom.saturating_sub(line_height * self.active.len() as u32 + 8);
+        for (caption, _) in &self.active {
+            let text = match &caption.speaker {
+                Some(speaker) => format!("{}: {}", loc.get(speaker), loc.get(&caption.text)),
+                None => loc.get(&caption.text),
+            };
+            renderer.submit(DrawCall::new("panel.caption", (x, y), DrawLayer::Ui));
+            y += renderer.draw_paragraph(&text, (x, y), width, 16.0, WHITE, loc.direction());
+        }
+    }
+}
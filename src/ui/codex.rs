@@ -0,0 +1,122 @@
+use std::collections::BTreeSet;
+
+use crate::codex::{Codex, CodexCategory, CodexEntry, CodexFilter};
+use crate::frontend::text::WHITE;
+use crate::frontend::{DrawCall, DrawLayer, Renderer};
+use crate::input::GameAction;
+use crate::localization::Localizer;
+
+use super::dialogue::CHOICE_SELECTED;
+
+const ROW_HEIGHT: u32 = 24;
+const LIST_WIDTH: u32 = 280;
+/// Shown in place of the name and text of entries not yet unlocked.
+const LOCKED: &str = "codex.locked";
+
+/// Codex listing: a section filter row, then the entries passing the
+/// filter and search text, then a back row. The highlighted entry's stats
+/// and lore are shown beside the list once unlocked.
+#[derive(Debug, Clone)]
+pub struct CodexScreen {
+    pub codex: Codex,
+    pub unlocked: BTreeSet<String>,
+    pub filter: CodexFilter,
+    /// Highlighted row: 0 is the filter row, the entries follow, and the
+    /// last row is back.
+    pub selected: usize,
+    /// Entry names in the language the screen was opened in, for searching.
+    names: Vec<String>,
+    /// Indices into `codex.entries` passing the filter.
+    visible: Vec<usize>,
+}
+
+impl CodexScreen {
+    pub fn new(codex: Codex, unlocked: BTreeSet<String>, loc: &Localizer) -> Self {
+        let names = codex.entries.iter().map(|e| loc.get(&e.name)).collect();
+        let mut screen = Self { codex, unlocked, filter: CodexFilter::default(), selected: 0, names, visible: Vec::new() };
+        screen.refilter();
+        screen
+    }
+
+    /// Entries passing the filter, in list order.
+    pub fn visible(&self) -> impl Iterator<Item = &CodexEntry> {
+        self.visible.iter().map(|&i| &self.codex.entries[i])
+    }
+
+    /// The highlighted entry, if an entry row is highlighted.
+    pub fn current(&self) -> Option<&CodexEntry> {
+        let index = self.visible.get(self.selected.checked_sub(1)?)?;
+        Some(&self.codex.entries[*index])
+    }
+
+    pub fn is_unlocked(&self, entry: &CodexEntry) -> bool {
+        self.unlocked.contains(&entry.key())
+    }
+
+    /// Add typed text to the search.
+    pub fn type_text(&mut self, text: &str) {
+        self.filter.query.push_str(text);
+        self.refilter();
+    }
+
+    /// Remove the last character of the search.
+    pub fn erase(&mut self) {
+        self.filter.query.pop();
+        self.refilter();
+    }
+
+    fn refilter(&mut self) {
+        let (filter, unlocked) = (&self.filter, &self.unlocked);
+        self.visible = (0..self.codex.entries.len())
+            .filter(|&i| filter.matches(&self.codex.entries[i], &self.names[i], unlocked))
+            .collect();
+        self.selected = self.selected.min(self.visible.len() + 1);
+    }
+
+    /// Move the cursor, or on the filter row step to the next section.
+    /// Returns true when the back row is activated.
+    pub fn handle_input(&mut self, action: GameAction) -> bool {
+        let back = self.visible.len() + 1;
+        match action {
+            GameAction::SelectUp => self.selected = self.selected.saturating_sub(1),
+            GameAction::SelectDown => self.selected = (self.selected + 1).min(back),
+            GameAction::Activate if self.selected == 0 => {
+                self.filter.category = match self.filter.category {
+                    None => Some(CodexCategory::ALL[0]),
+                    Some(c) => CodexCategory::ALL.iter().skip_while(|&&x| x != c).nth(1).copied(),
+                };
+                self.refilter();
+            }
+            GameAction::Activate => return self.selected == back,
+        }
+        false
+    }
+
+    pub fn render(&self, renderer: &mut Renderer, loc: &Localizer) {
+        renderer.submit(DrawCall::new(loc.get("panel.codex"), (0, 0), DrawLayer::Ui));
+        renderer.draw_text(&loc.get("codex.title"), (16, 16), 20.0, WHITE);
+        let section = loc.get(self.filter.category.map_or("codex.all", |c| c.label_key()));
+        let color = if self.selected == 0 { CHOICE_SELECTED } else { WHITE };
+        renderer.draw_text(&format!("< {section} >  {}", self.filter.query), (16, 48), 16.0, color);
+        for (row, entry) in self.visible().enumerate() {
+            let y = 48 + (row as u32 + 1) * ROW_HEIGHT;
+            let color = if self.selected == row + 1 { CHOICE_SELECTED } else { WHITE };
+            let name = if self.is_unlocked(entry) { loc.get(&entry.name) } else { loc.get(LOCKED) };
+            renderer.draw_text(&name, (16, y), 16.0, color);
+        }
+        let y = 48 + (self.visible.len() as u32 + 1) * ROW_HEIGHT + 8;
+        let color = if self.selected == self.visible.len() + 1 { CHOICE_SELECTED } else { WHITE };
+        renderer.draw_text(&loc.get("codex.back"), (16, y), 16.0, color);
+
+        let Some(entry) = self.current().filter(|e| self.is_unlocked(e)) else { return };
+        let x = LIST_WIDTH + 32;
+        renderer.draw_text(&loc.get(&entry.name), (x, 48), 20.0, WHITE);
+        let mut y = 80;
+        for (label, value) in &entry.stats {
+            renderer.draw_text(&format!("{}: {}", loc.get(label), loc.get(value)), (x, y), 14.0, WHITE);
+            y += 20;
+        }
+        let width = renderer.width.saturating_sub(x + 16);
+        renderer.draw_paragraph(&loc.get(&entry.lore), (x, y + 8), width, 14.0, WHITE, loc.direction());
+    }
+}
@@ -0,0 +1,72 @@
+use crate::campaign::Campaign;
+use crate::dialogue::DialoguePlayer;
+use crate::frontend::text::{Color, WHITE};
+use crate::frontend::{DrawCall, DrawLayer, Renderer};
+use crate::input::GameAction;
+use crate::localization::Localizer;
+
+/// Highlight color for the selected choice.
+pub const CHOICE_SELECTED: Color = [1.0, 0.85, 0.3, 1.0];
+
+/// Conversation box across the bottom third of the screen. While open it
+/// takes all input and combat is paused.
+#[derive(Debug, Clone)]
+pub struct DialogueOverlay {
+    pub player: DialoguePlayer,
+    pub selected_choice: usize,
+}
+
+impl DialogueOverlay {
+    pub fn new(player: DialoguePlayer) -> Self {
+        Self { player, selected_choice: 0 }
+    }
+
+    /// Move the choice cursor or confirm. Returns true once the
+    /// conversation has ended.
+    pub fn handle_input(&mut self, action: GameAction, campaign: &mut Campaign) -> bool {
+        let choices = self.player.available_choices(campaign).len();
+        match action {
+            GameAction::SelectUp => self.selected_choice = self.selected_choice.saturating_sub(1),
+            GameAction::SelectDown => {
+                if self.selected_choice + 1 < choices {
+                    self.selected_choice += 1;
+                }
+            }
+            GameAction::Activate => {
+                let result = if choices == 0 {
+                    self.player.advance()
+                } else {
+                    self.player.choose(self.selected_choice, campaign)
+                };
+                if result.is_ok() {
+                    self.selected_choice = 0;
+                }
+            }
+        }
+        self.player.is_finished()
+    }
+
+    pub fn render(&self, renderer: &mut Renderer, loc: &Localizer, campaign: &Campaign) {
+        let Some(node) = self.player.current_node() else { return };
+        let top = renderer.height - renderer.height / 3;
+        renderer.submit(DrawCall::new(loc.get("panel.dialogue"), (0, top), DrawLayer::Ui));
+        let text_x = if let Some(portrait) = &node.portrait {
+            renderer.submit(DrawCall::new(portrait.clone(), (16, top + 16), DrawLayer::Ui));
+            112
+        } else {
+            16
+        };
+        // Wrapped to the box and mirrored for right-to-left languages; later
+        // rows move down when a line wraps.
+        let direction = loc.direction();
+        let width = renderer.width.saturating_sub(text_x + 16);
+        renderer.draw_paragraph(&loc.get(&node.speaker), (text_x, top + 16), width, 18.0, WHITE, direction);
+        let mut y = top + 44;
+        y += renderer.draw_paragraph(&loc.get(&node.line), (text_x, y), width, 16.0, WHITE, direction) + 12;
+        for (i, choice) in self.player.available_choices(campaign).iter().enumerate() {
+            let color = if i == self.selected_choice { CHOICE_SELECTED } else { WHITE };
+            let text = loc.get(&choice.text);
+            y += renderer.draw_paragraph(&text, (text_x + 16, y), width.saturating_sub(16), 16.0, color, direction) + 2;
+        }
+    }
+}
@@ -0,0 +1,93 @@
+use crate::campaign::Campaign;
+use crate::events::{EventResult, EventTable};
+use crate::frontend::text::{DAMAGE_RED, WHITE};
+use crate::frontend::{DrawCall, DrawLayer, Renderer};
+use crate::input::GameAction;
+use crate::localization::Localizer;
+use crate::rng::GameRng;
+
+use super::dialogue::CHOICE_SELECTED;
+
+/// Between-mission screen for the campaign's pending event: its text and
+/// choices, then how the picked choice played out until `Activate`
+/// dismisses it. Choices the campaign can't pay for are shown in red.
+#[derive(Debug, Clone, Default)]
+pub struct EventScreen {
+    pub selected_index: usize,
+    /// Outcome of the last choice, shown until dismissed.
+    pub result: Option<EventResult>,
+    /// Error from the last choice, shown until the next input.
+    pub last_error: Option<&'static str>,
+}
+
+impl EventScreen {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Whether there is nothing left to show: no outcome on screen and no
+    /// event pending.
+    pub fn is_finished(&self, campaign: &Campaign) -> bool {
+        self.result.is_none() && campaign.pending_event.is_none()
+    }
+
+    pub fn handle_input(&mut self, action: GameAction, table: &EventTable, campaign: &mut Campaign, rng: &mut GameRng) {
+        self.last_error = None;
+        if self.result.is_some() {
+            if action == GameAction::Activate {
+                self.result = None;
+                self.selected_index = 0;
+            }
+            return;
+        }
+        let choices = campaign.pending_event.as_deref().and_then(|id| table.get(id)).map_or(0, |e| e.choices.len());
+        match action {
+            GameAction::SelectUp => self.selected_index = self.selected_index.saturating_sub(1),
+            GameAction::SelectDown => {
+                if self.selected_index + 1 < choices {
+                    self.selected_index += 1;
+                }
+            }
+            GameAction::Activate => match campaign.resolve_event(table, self.selected_index, rng) {
+                Ok(result) => self.result = Some(result),
+                Err(e) => self.last_error = Some(e),
+            },
+        }
+    }
+
+    pub fn render(&self, renderer: &mut Renderer, loc: &Localizer, table: &EventTable, campaign: &Campaign) {
+        renderer.submit(DrawCall::new(loc.get("panel.event"), (0, 0), DrawLayer::Ui));
+        if let Some(result) = &self.result {
+            renderer.draw_text(&loc.get(&result.text), (16, 16), 18.0, WHITE);
+            if let Some((unit_id, test)) = &result.test {
+                let name = campaign.roster.iter().find(|u| u.id == *unit_id).map_or(unit_id.as_str(), |u| u.name.as_str());
+                let args = [("name", name), ("roll", &test.roll.to_string()), ("target", &test.target.to_string())];
+                renderer.draw_text(&loc.format("event.tested", &args), (16, 56), 16.0, WHITE);
+                let degrees = loc.plural(test.label_key(), test.degrees as i64, &[]);
+                renderer.draw_text(&degrees, (16, 84), 16.0, if test.success { WHITE } else { DAMAGE_RED });
+            }
+            renderer.draw_text(&loc.get("event.continue"), (16, 124), 16.0, CHOICE_SELECTED);
+            return;
+        }
+        let Some(event) = campaign.pending_event.as_deref().and_then(|id| table.get(id)) else { return };
+        renderer.draw_text(&loc.get(&event.title), (16, 16), 18.0, WHITE);
+        renderer.draw_text(&loc.get(&event.text), (16, 44), 16.0, WHITE);
+        for (i, choice) in event.choices.iter().enumerate() {
+            let color = if i == self.selected_index {
+                CHOICE_SELECTED
+            } else if campaign.can_afford(choice).is_err() {
+                DAMAGE_RED
+            } else {
+                WHITE
+            };
+            let line = match choice.cost {
+                0 => loc.get(&choice.text),
+                cost => format!("{} - {}", loc.get(&choice.text), cost),
+            };
+            renderer.draw_text(&line, (16, 84 + i as u32 * 28), 16.0, color);
+        }
+        if let Some(err) = self.last_error {
+            renderer.draw_text(&loc.get(err), (16, renderer.height - 32), 16.0, DAMAGE_RED);
+        }
+    }
+}
@@ -0,0 +1,59 @@
+use std::collections::VecDeque;
+
+use crate::frontend::backend::DrawRect;
+use crate::frontend::text::{Color, WHITE};
+use crate::frontend::{DrawLayer, Renderer};
+
+/// Frames averaged for the displayed numbers.
+pub const FPS_SAMPLES: usize = 60;
+/// Translucent box behind the readout so it stays legible over the map.
+const BACKDROP: Color = [0.0, 0.0, 0.0, 0.6];
+
+/// On-screen frames-per-second and frame-time readout for debugging,
+/// averaged over the last [`FPS_SAMPLES`] frames.
+#[derive(Debug, Clone, Default)]
+pub struct FpsCounter {
+    pub visible: bool,
+    frame_times: VecDeque<f32>,
+}
+
+impl FpsCounter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record a frame that took `dt` seconds.
+    pub fn record(&mut self, dt: f32) {
+        self.frame_times.push_back(dt);
+        if self.frame_times.len() > FPS_SAMPLES {
+            self.frame_times.pop_front();
+        }
+    }
+
+    /// Average frame time in milliseconds.
+    pub fn frame_time_ms(&self) -> f32 {
+        if self.frame_times.is_empty() {
+            return 0.0;
+        }
+        self.frame_times.iter().sum::<f32>() / self.frame_times.len() as f32 * 1000.0
+    }
+
+    pub fn fps(&self) -> f32 {
+        match self.frame_time_ms() {
+            ms if ms > 0.0 => 1000.0 / ms,
+            _ => 0.0,
+        }
+    }
+
+    /// Draw the readout in the top-right corner when visible.
+    pub fn render(&self, renderer: &mut Renderer) {
+        if !self.visible {
+            return;
+        }
+        let text = format!("{:.0} FPS  {:.1} ms", self.fps(), self.frame_time_ms());
+        let (width, height) = renderer.measure_text(&text, 14.0);
+        let x = renderer.width.saturating_sub(width + 8);
+        renderer.draw_rect(DrawRect { position: (x.saturating_sub(4), 4), size: (width + 8, height + 8), color: BACKDROP, layer: DrawLayer::Ui });
+        renderer.draw_text(&text, (x, 8), 14.0, WHITE);
+    }
+}
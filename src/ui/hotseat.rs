@@ -0,0 +1,30 @@
+use crate::frontend::text::WHITE;
+use crate::frontend::{DrawCall, DrawLayer, Renderer};
+use crate::input::GameAction;
+use crate::localization::Localizer;
+
+/// Full-screen cover shown between hotseat turns so the incoming player
+/// doesn't see the previous player's view. Dismissed with `Activate`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PassDeviceScreen {
+    pub next_player: u8,
+}
+
+impl PassDeviceScreen {
+    pub fn new(next_player: u8) -> Self {
+        Self { next_player }
+    }
+
+    /// Returns true once the incoming player confirms they have the device.
+    pub fn handle_input(&self, action: GameAction) -> bool {
+        matches!(action, GameAction::Activate)
+    }
+
+    pub fn render(&self, renderer: &mut Renderer, loc: &Localizer) {
+        renderer.submit(DrawCall::new(loc.get("panel.pass_device"), (0, 0), DrawLayer::Ui));
+        let prompt = loc.format("hotseat.pass_device", &[("player", &(self.next_player + 1).to_string())]);
+        let y = renderer.height / 2;
+        renderer.draw_text(&prompt, (renderer.width / 4, y), 24.0, WHITE);
+        renderer.draw_text(&loc.get("hotseat.continue"), (renderer.width / 4, y + 32), 16.0, WHITE);
+    }
+}
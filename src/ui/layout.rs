@@ -0,0 +1,177 @@
+use super::{Panel, BUTTON_SIZE, TOUCH_BUTTON_SIZE};
+
+/// Pixels kept clear on each edge, e.g. for a notch or rounded corners.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Insets {
+    pub top: u32,
+    pub right: u32,
+    pub bottom: u32,
+    pub left: u32,
+}
+
+impl Insets {
+    pub fn uniform(px: u32) -> Self {
+        Self { top: px, right: px, bottom: px, left: px }
+    }
+}
+
+/// The screen a layout is computed for. Sizes in the layout code are
+/// logical pixels; [`LayoutContext::px`] converts them for the display's
+/// DPI and [`LayoutContext::text_px`] also for the font scale.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LayoutContext {
+    pub width: u32,
+    pub height: u32,
+    pub scale_factor: f32,
+    pub font_scale: f32,
+    pub safe_area: Insets,
+    /// Played by touch, so buttons are sized for a fingertip.
+    pub touch: bool,
+}
+
+impl LayoutContext {
+    pub fn new(width: u32, height: u32) -> Self {
+        Self { width, height, scale_factor: 1.0, font_scale: 1.0, safe_area: Insets::default(), touch: false }
+    }
+
+    pub fn with_scale_factor(mut self, scale_factor: f32) -> Self {
+        self.scale_factor = scale_factor;
+        self
+    }
+
+    pub fn with_font_scale(mut self, font_scale: f32) -> Self {
+        self.font_scale = font_scale;
+        self
+    }
+
+    pub fn with_safe_area(mut self, safe_area: Insets) -> Self {
+        self.safe_area = safe_area;
+        self
+    }
+
+    pub fn with_touch(mut self, touch: bool) -> Self {
+        self.touch = touch;
+        self
+    }
+
+    /// Side of a square button, in logical pixels.
+    pub fn button_size(&self) -> u32 {
+        if self.touch { TOUCH_BUTTON_SIZE } else { BUTTON_SIZE }
+    }
+
+    /// `logical` pixels at the display's scale factor.
+    pub fn px(&self, logical: u32) -> u32 {
+        (logical as f32 * self.scale_factor).round() as u32
+    }
+
+    /// `logical` pixels of something sized by its text, which also grows
+    /// with the font scale.
+    pub fn text_px(&self, logical: u32) -> u32 {
+        (logical as f32 * self.scale_factor * self.font_scale).round() as u32
+    }
+
+    /// The screen minus the safe-area insets.
+    pub fn safe_rect(&self) -> Panel {
+        Panel { x: 0, y: 0, width: self.width, height: self.height }.inset(self.safe_area)
+    }
+}
+
+impl Panel {
+    /// This rect shrunk by `insets`, never below zero size.
+    pub fn inset(&self, insets: Insets) -> Panel {
+        Panel {
+            x: self.x + insets.left.min(self.width),
+            y: self.y + insets.top.min(self.height),
+            width: self.width.saturating_sub(insets.left + insets.right),
+            height: self.height.saturating_sub(insets.top + insets.bottom),
+        }
+    }
+
+    /// Whether screen position `at` lies inside.
+    pub fn contains(&self, (x, y): (u32, u32)) -> bool {
+        (self.x..self.x + self.width).contains(&x) && (self.y..self.y + self.height).contains(&y)
+    }
+}
+
+/// `fraction` of `total`, rounded down.
+pub fn share(total: u32, fraction: f32) -> u32 {
+    (total as f32 * fraction) as u32
+}
+
+/// Edge of the remaining space a docked region attaches to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Anchor {
+    Top,
+    Bottom,
+    Left,
+    Right,
+}
+
+/// Carves regions off the edges of a rect in turn. Each region is clamped
+/// to what is left, so later regions shrink rather than overlap.
+#[derive(Debug, Clone)]
+pub struct Dock {
+    remaining: Panel,
+}
+
+impl Dock {
+    pub fn new(area: Panel) -> Self {
+        Self { remaining: area }
+    }
+
+    /// Take `size` pixels off the `anchor` edge.
+    pub fn take(&mut self, anchor: Anchor, size: u32) -> Panel {
+        let r = &mut self.remaining;
+        match anchor {
+            Anchor::Top => {
+                let size = size.min(r.height);
+                let taken = Panel { x: r.x, y: r.y, width: r.width, height: size };
+                r.y += size;
+                r.height -= size;
+                taken
+            }
+            Anchor::Bottom => {
+                let size = size.min(r.height);
+                r.height -= size;
+                Panel { x: r.x, y: r.y + r.height, width: r.width, height: size }
+            }
+            Anchor::Left => {
+                let size = size.min(r.width);
+                let taken = Panel { x: r.x, y: r.y, width: size, height: r.height };
+                r.x += size;
+                r.width -= size;
+                taken
+            }
+            Anchor::Right => {
+                let size = size.min(r.width);
+                r.width -= size;
+                Panel { x: r.x + r.width, y: r.y, width: size, height: r.height }
+            }
+        }
+    }
+
+    pub fn remaining(&self) -> &Panel {
+        &self.remaining
+    }
+}
+
+/// Rows needed to fit `count` items of width `item` with `gap` between
+/// them in `width`.
+pub fn flow_rows(count: usize, width: u32, item: u32, gap: u32) -> u32 {
+    let per_row = ((width + gap) / (item + gap).max(1)).max(1);
+    (count as u32).div_ceil(per_row)
+}
+
+/// Lay out `count` items of `size` left to right inside `area`, starting a
+/// new row whenever the next item would not fit.
+pub fn flow(area: &Panel, count: usize, size: (u32, u32), gap: u32) -> Vec<Panel> {
+    let per_row = ((area.width + gap) / (size.0 + gap).max(1)).max(1) as usize;
+    (0..count)
+        .map(|i| Panel {
+            x: area.x + (i % per_row) as u32 * (size.0 + gap),
+            y: area.y + (i / per_row) as u32 * (size.1 + gap),
+            width: size.0,
+            height: size.1,
+        })
+        .collect()
+}
@@ -0,0 +1,42 @@
+use crate::assets::streaming::LoadingProgress;
+use crate::frontend::backend::DrawRect;
+use crate::frontend::text::{Color, WHITE};
+use crate::frontend::{DrawLayer, Renderer};
+use crate::localization::Localizer;
+
+/// Fraction of the screen width the progress bar spans.
+const BAR_WIDTH: f32 = 0.5;
+const BAR_HEIGHT: u32 = 12;
+const BACKDROP: Color = [0.0, 0.0, 0.0, 1.0];
+const BAR_TRACK: Color = [0.2, 0.2, 0.2, 1.0];
+const BAR_FILL: Color = [0.85, 0.7, 0.3, 1.0];
+
+/// Full-screen cover shown while a battle's assets stream in, with a bar
+/// filling as they arrive.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct LoadingScreen {
+    pub progress: LoadingProgress,
+}
+
+impl LoadingScreen {
+    pub fn new(progress: LoadingProgress) -> Self {
+        Self { progress }
+    }
+
+    pub fn render(&self, renderer: &mut Renderer, loc: &Localizer) {
+        let (width, height) = (renderer.width, renderer.height);
+        renderer.draw_rect(DrawRect { position: (0, 0), size: (width, height), color: BACKDROP, layer: DrawLayer::Ui });
+        let bar = (width as f32 * BAR_WIDTH) as u32;
+        let x = (width - bar) / 2;
+        let y = height / 2;
+        renderer.draw_rect(DrawRect { position: (x, y), size: (bar, BAR_HEIGHT), color: BAR_TRACK, layer: DrawLayer::Ui });
+        let filled = (bar as f32 * self.progress.fraction()) as u32;
+        if filled > 0 {
+            renderer.draw_rect(DrawRect { position: (x, y), size: (filled, BAR_HEIGHT), color: BAR_FILL, layer: DrawLayer::Ui });
+        }
+        renderer.draw_text(&loc.get("loading.title"), (x, y.saturating_sub(32)), 24.0, WHITE);
+        let done = (self.progress.loaded + self.progress.failed).to_string();
+        let count = loc.format("loading.progress", &[("done", &done), ("total", &self.progress.total.to_string())]);
+        renderer.draw_text(&count, (x, y + BAR_HEIGHT + 8), 16.0, WHITE);
+    }
+}
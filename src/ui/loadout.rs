@@ -0,0 +1,192 @@
+use crate::campaign::Campaign;
+use crate::content::ContentDatabase;
+use crate::frontend::text::{DAMAGE_RED, WHITE};
+use crate::frontend::{DrawCall, DrawLayer, Renderer};
+use crate::input::GameAction;
+use crate::loadout::EquipmentSlot;
+use crate::localization::Localizer;
+use crate::models::Accessory;
+
+use super::dialogue::CHOICE_SELECTED;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LoadoutMode {
+    /// Cursor moves over the roster.
+    SelectUnit,
+    /// Cursor moves over the selected unit's slots and the inventory.
+    EditUnit,
+}
+
+/// A selectable row while editing a unit.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LoadoutRow {
+    /// An occupied slot; activating it returns the item to the inventory.
+    Slot(EquipmentSlot),
+    /// An inventory item; activating it equips it on the unit.
+    Item(String),
+    /// Spend requisition restoring worn equipment.
+    Repair,
+    Done,
+}
+
+/// Pre-mission screen for assigning campaign inventory to roster units.
+#[derive(Debug, Clone)]
+pub struct LoadoutScreen {
+    pub mode: LoadoutMode,
+    pub unit_index: usize,
+    pub cursor: usize,
+    /// Error from the last assignment, shown until the next input.
+    pub last_error: Option<&'static str>,
+}
+
+impl Default for LoadoutScreen {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl LoadoutScreen {
+    pub fn new() -> Self {
+        Self { mode: LoadoutMode::SelectUnit, unit_index: 0, cursor: 0, last_error: None }
+    }
+
+    /// Rows offered for the selected unit: occupied slots, then inventory
+    /// items, then `Repair` if anything is worn, then `Done`.
+    pub fn rows(&self, campaign: &Campaign) -> Vec<LoadoutRow> {
+        let mut rows = Vec::new();
+        if let Some(unit) = campaign.roster.get(self.unit_index) {
+            if unit.equipment.weapon.is_some() {
+                rows.push(LoadoutRow::Slot(EquipmentSlot::Weapon));
+            }
+            if unit.equipment.armor.is_some() {
+                rows.push(LoadoutRow::Slot(EquipmentSlot::Armor));
+            }
+            rows.extend((0..unit.equipment.accessory_slots.len()).map(|i| LoadoutRow::Slot(EquipmentSlot::Accessory(i))));
+        }
+        rows.extend(campaign.inventory.iter().cloned().map(LoadoutRow::Item));
+        if campaign.roster.get(self.unit_index).is_some_and(|u| u.repair_cost() > 0) {
+            rows.push(LoadoutRow::Repair);
+        }
+        rows.push(LoadoutRow::Done);
+        rows
+    }
+
+    /// Equip `item_id` on the selected unit, as when an item is dropped onto
+    /// it.
+    pub fn assign(&mut self, db: &ContentDatabase, campaign: &mut Campaign, item_id: &str) -> Result<(), &'static str> {
+        let unit_id = campaign.roster.get(self.unit_index).ok_or("unknown unit")?.id.clone();
+        let result = campaign.equip_from_inventory(db, &unit_id, item_id);
+        self.last_error = result.err();
+        result
+    }
+
+    pub fn handle_input(&mut self, action: GameAction, db: &ContentDatabase, campaign: &mut Campaign) {
+        self.last_error = None;
+        let len = match self.mode {
+            LoadoutMode::SelectUnit => campaign.roster.len(),
+            LoadoutMode::EditUnit => self.rows(campaign).len(),
+        };
+        match action {
+            GameAction::SelectUp => self.cursor = self.cursor.saturating_sub(1),
+            GameAction::SelectDown => {
+                if self.cursor + 1 < len {
+                    self.cursor += 1;
+                }
+            }
+            GameAction::Activate => match self.mode {
+                LoadoutMode::SelectUnit if self.cursor < len => {
+                    self.unit_index = self.cursor;
+                    self.mode = LoadoutMode::EditUnit;
+                    self.cursor = 0;
+                }
+                LoadoutMode::SelectUnit => {}
+                LoadoutMode::EditUnit => match self.rows(campaign).get(self.cursor).cloned() {
+                    Some(LoadoutRow::Slot(slot)) => {
+                        let unit_id = campaign.roster[self.unit_index].id.clone();
+                        self.last_error = campaign.unequip_to_inventory(db, &unit_id, slot).err();
+                    }
+                    Some(LoadoutRow::Item(item_id)) => {
+                        let _ = self.assign(db, campaign, &item_id);
+                    }
+                    Some(LoadoutRow::Repair) => {
+                        let unit_id = campaign.roster[self.unit_index].id.clone();
+                        self.last_error = campaign.repair_equipment(&unit_id).err();
+                    }
+                    Some(LoadoutRow::Done) | None => {
+                        self.mode = LoadoutMode::SelectUnit;
+                        self.cursor = self.unit_index;
+                    }
+                },
+            },
+        }
+    }
+
+    pub fn render(&self, renderer: &mut Renderer, loc: &Localizer, db: &ContentDatabase, campaign: &Campaign) {
+        renderer.submit(DrawCall::new(loc.get("panel.loadout"), (0, 0), DrawLayer::Ui));
+        for (i, unit) in campaign.roster.iter().enumerate() {
+            let highlighted = match self.mode {
+                LoadoutMode::SelectUnit => i == self.cursor,
+                LoadoutMode::EditUnit => i == self.unit_index,
+            };
+            let worn = unit.equipment.weapon_worn() || unit.equipment.armor_worn();
+            let color = if highlighted {
+                CHOICE_SELECTED
+            } else if worn {
+                DAMAGE_RED
+            } else {
+                WHITE
+            };
+            renderer.draw_text(&unit.name, (16, 16 + i as u32 * 24), 16.0, color);
+        }
+
+        let Some(unit) = campaign.roster.get(self.unit_index) else { return };
+        let column = renderer.width / 2;
+        let empty = loc.get("loadout.empty");
+        let weapon = unit.equipment.weapon.as_ref().map(|w| w.name.clone()).unwrap_or_else(|| empty.clone());
+        let armor = unit.equipment.armor.as_ref().map(|a| a.name.clone()).unwrap_or_else(|| empty.clone());
+        let equipment = &unit.equipment;
+        let slots = [
+            ("loadout.weapon", &weapon, equipment.weapon.is_some(), equipment.weapon_durability, equipment.weapon_worn()),
+            ("loadout.armor", &armor, equipment.armor.is_some(), equipment.armor_durability, equipment.armor_worn()),
+        ];
+        for (i, (key, name, equipped, durability, worn)) in slots.into_iter().enumerate() {
+            let item = if equipped {
+                let condition = if worn { "loadout.worn" } else { "loadout.condition" };
+                loc.format(condition, &[("item", name), ("condition", &durability.to_string())])
+            } else {
+                name.clone()
+            };
+            let color = if worn { DAMAGE_RED } else { WHITE };
+            renderer.draw_text(&format!("{}: {}", loc.get(key), item), (column, 16 + i as u32 * 24), 16.0, color);
+        }
+        for (i, accessory) in unit.equipment.accessory_slots.iter().enumerate() {
+            let name = accessory_name(db, accessory);
+            renderer.draw_text(&format!("{}: {}", loc.get("loadout.accessory"), name), (column, 64 + i as u32 * 24), 16.0, WHITE);
+        }
+
+        if self.mode == LoadoutMode::EditUnit {
+            let top = 64 + unit.equipment.accessory_slots.len() as u32 * 24 + 24;
+            for (i, row) in self.rows(campaign).iter().enumerate() {
+                let label = match row {
+                    LoadoutRow::Slot(EquipmentSlot::Weapon) => format!("{} {}", loc.get("loadout.unequip"), weapon),
+                    LoadoutRow::Slot(EquipmentSlot::Armor) => format!("{} {}", loc.get("loadout.unequip"), armor),
+                    LoadoutRow::Slot(EquipmentSlot::Accessory(i)) => {
+                        format!("{} {}", loc.get("loadout.unequip"), accessory_name(db, &unit.equipment.accessory_slots[*i]))
+                    }
+                    LoadoutRow::Item(id) => db.get(id).map(|e| e.item.name().to_string()).unwrap_or_else(|| id.clone()),
+                    LoadoutRow::Repair => loc.format("loadout.repair", &[("cost", &unit.repair_cost().to_string())]),
+                    LoadoutRow::Done => loc.get("loadout.done"),
+                };
+                let color = if i == self.cursor { CHOICE_SELECTED } else { WHITE };
+                renderer.draw_text(&label, (column, top + i as u32 * 22), 16.0, color);
+            }
+        }
+        if let Some(err) = self.last_error {
+            renderer.draw_text(&loc.get(err), (16, renderer.height - 32), 16.0, DAMAGE_RED);
+        }
+    }
+}
+
+fn accessory_name<'d>(db: &'d ContentDatabase, accessory: &Accessory) -> &'d str {
+    db.accessory_id(accessory).and_then(|id| db.get(id)).map(|e| e.item.name()).unwrap_or("?")
+}
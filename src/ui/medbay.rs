@@ -0,0 +1,76 @@
+use crate::campaign::Campaign;
+use crate::content::ContentDatabase;
+use crate::frontend::text::{DAMAGE_RED, WHITE};
+use crate::frontend::{DrawCall, DrawLayer, Renderer};
+use crate::input::GameAction;
+use crate::localization::Localizer;
+
+use super::dialogue::CHOICE_SELECTED;
+
+/// Between-mission screen listing injured roster units and their recovery
+/// timers, with the medbay upgrade as the last row. `Activate` spends a
+/// medical supply on the selected unit, or buys the upgrade.
+#[derive(Debug, Clone, Default)]
+pub struct MedbayScreen {
+    pub selected_index: usize,
+    /// Error from the last treatment or upgrade, shown until the next input.
+    pub last_error: Option<&'static str>,
+}
+
+impl MedbayScreen {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn handle_input(&mut self, action: GameAction, db: &ContentDatabase, campaign: &mut Campaign) {
+        let injured: Vec<String> = campaign.injured().map(|u| u.id.clone()).collect();
+        self.last_error = None;
+        match action {
+            GameAction::SelectUp => self.selected_index = self.selected_index.saturating_sub(1),
+            GameAction::SelectDown => {
+                if self.selected_index < injured.len() {
+                    self.selected_index += 1;
+                }
+            }
+            GameAction::Activate => {
+                self.last_error = match injured.get(self.selected_index) {
+                    Some(id) => campaign.treat_injuries(db, id).err(),
+                    None => campaign.upgrade_medbay().err(),
+                };
+            }
+        }
+    }
+
+    pub fn render(&self, renderer: &mut Renderer, loc: &Localizer, campaign: &Campaign) {
+        renderer.submit(DrawCall::new(loc.get("panel.medbay"), (0, 0), DrawLayer::Ui));
+        let level = format!("{} {}", loc.get("medbay.level"), campaign.medbay_level);
+        renderer.draw_text(&level, (16, 16), 18.0, WHITE);
+        let mut rows = 0;
+        for (i, unit) in campaign.injured().enumerate() {
+            let injuries: Vec<String> = unit.injuries.iter().map(|i| loc.get(i.kind.label_key())).collect();
+            let timer = match unit.recovery_missions() {
+                0 => loc.get("medbay.fit"),
+                n => loc.plural("medbay.missions", n as i64, &[]),
+            };
+            let color = if i == self.selected_index { CHOICE_SELECTED } else { WHITE };
+            let line = format!("{} - {} ({})", unit.name, injuries.join(", "), timer);
+            renderer.draw_text(&line, (16, 56 + i as u32 * 28), 16.0, color);
+            rows += 1;
+        }
+        let upgrade = match campaign.medbay_upgrade_cost() {
+            Some(cost) => format!("{} - {}", loc.get("medbay.upgrade"), cost),
+            None => loc.get("medbay.upgraded"),
+        };
+        let color = if self.selected_index == rows {
+            CHOICE_SELECTED
+        } else if campaign.medbay_upgrade_cost().is_some_and(|c| c > campaign.currency) {
+            DAMAGE_RED
+        } else {
+            WHITE
+        };
+        renderer.draw_text(&upgrade, (16, 56 + rows as u32 * 28), 16.0, color);
+        if let Some(err) = self.last_error {
+            renderer.draw_text(&loc.get(err), (16, renderer.height - 32), 16.0, DAMAGE_RED);
+        }
+    }
+}
@@ -1,10 +1,62 @@
-use crate::frontend::{Renderer, DrawCall};
+use crate::combat::CombatEvent;
+use crate::command::Command;
+use crate::frontend::{Renderer, DrawCall, DrawLayer};
+use crate::frontend::palette::PaletteRole;
+use crate::frontend::text::WHITE;
+use crate::grid::GridMap;
 use crate::input::GameAction;
 use crate::localization::Localizer;
+use crate::loot::PICKUP_AP_COST;
+use crate::models::Unit;
+use crate::registry::UnitRegistry;
+use crate::selection::SelectionChanged;
 
+pub mod base;
+pub mod captions;
+pub mod codex;
+pub mod dialogue;
+pub mod events;
+pub mod fps;
+pub mod hotseat;
+pub mod layout;
+pub mod loading;
+pub mod loadout;
+pub mod medbay;
+pub mod modal;
+pub mod mods;
 pub mod options;
+pub mod overworld;
+pub mod recruitment;
+pub mod requisition;
+pub mod research;
+pub mod summary;
+pub mod toasts;
+pub mod tooltip;
 
-#[derive(Debug, Clone)]
+use captions::CaptionsWidget;
+use fps::FpsCounter;
+use hotseat::PassDeviceScreen;
+use modal::{ModalChoice, ModalDialog};
+use summary::SummaryChoice;
+use toasts::Toasts;
+use tooltip::Tooltip;
+use layout::{flow, flow_rows, share, Anchor, Dock, Insets, LayoutContext};
+
+/// Side of a unit portrait in the info panel, in logical pixels.
+pub const PORTRAIT_SIZE: u32 = 48;
+/// Side of a square button, in logical pixels.
+pub const BUTTON_SIZE: u32 = 32;
+/// Side of a square button on a touch screen, in logical pixels; about
+/// the width of a fingertip.
+pub const TOUCH_BUTTON_SIZE: u32 = 48;
+/// Space between and around buttons, in logical pixels.
+pub const BUTTON_GAP: u32 = 4;
+/// Smallest top bar that fits the tab label, before font scaling.
+pub const TOP_BAR_MIN: u32 = 24;
+/// Smallest info panel that fits unit names, before font scaling.
+pub const INFO_PANEL_MIN: u32 = 96;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Panel {
     pub x: u32,
     pub y: u32,
@@ -44,6 +96,16 @@ impl UiTab {
 pub enum UiEvent {
     AbilityPressed(String),
     InventoryPressed(String),
+    /// The hotseat player confirmed they have the device.
+    HandoffConfirmed(u8),
+    /// The open modal dialog was answered and closed.
+    ModalClosed(ModalChoice),
+    /// A command held back by a confirmation dialog was confirmed and
+    /// should run now.
+    CommandConfirmed(Command),
+    /// The battle summary was closed, going on with the campaign or back
+    /// into the battle. A retry has already put the campaign back as it was.
+    SummaryClosed(SummaryChoice),
 }
 
 #[derive(Debug)]
@@ -57,60 +119,131 @@ pub struct UiManager {
     pub floating_texts: Vec<FloatingText>,
     pub current_tab: UiTab,
     pub selected_index: usize,
+    /// Hotseat hand-off cover; while shown it hides the battlefield and
+    /// takes all input.
+    pub pass_device: Option<PassDeviceScreen>,
+    /// Subtitles for sounds, drawn above the bottom bar.
+    pub captions: CaptionsWidget,
+    /// Screen the panels were laid out for.
+    pub layout: LayoutContext,
+    pub fps_counter: FpsCounter,
+    /// Notifications such as unlocked achievements.
+    pub toasts: Toasts,
+    /// Details of whatever is under a long-pressed finger.
+    pub tooltip: Option<Tooltip>,
+    /// Confirm/cancel prompt; while open it takes all input.
+    pub modal: Option<ModalDialog>,
+    /// Ids of the selected units, primary first, as of the last
+    /// [`SelectionChanged`].
+    pub selection: Vec<String>,
 }
 
 impl UiManager {
+    /// Lay the UI out for a `screen_width` x `screen_height` screen at 1x
+    /// scale with no safe-area insets.
     pub fn new(screen_width: u32, screen_height: u32, abilities: Vec<String>, items: Vec<String>) -> Self {
-        let top_h = (screen_height as f32 * 0.10) as u32;
-        let bottom_h = top_h;
-        let info_w = (screen_width as f32 * 0.15) as u32;
-        let battlefield_w = (screen_width as f32 * 0.70) as u32;
-        let battlefield_h = screen_height - top_h - bottom_h;
-        let battlefield_x = 0;
-        let info_x = battlefield_w;
-
-        let ability_buttons = abilities
+        Self::with_layout(&LayoutContext::new(screen_width, screen_height), abilities, items)
+    }
+
+    /// Lay the UI out inside the safe area of `ctx`. Bars and the info
+    /// panel take their GDD share of the screen but never less than their
+    /// minimum size; inventory buttons wrap onto more rows, growing the
+    /// bottom bar, when one row doesn't fit.
+    pub fn with_layout(ctx: &LayoutContext, abilities: Vec<String>, items: Vec<String>) -> Self {
+        let screen = ctx.safe_rect();
+        let gap = ctx.px(BUTTON_GAP);
+        let button = ctx.px(ctx.button_size());
+        let mut dock = Dock::new(screen.clone());
+
+        let top_bar = dock.take(Anchor::Top, share(screen.height, 0.10).max(ctx.text_px(TOP_BAR_MIN)));
+        let rows = flow_rows(items.len(), screen.width.saturating_sub(2 * gap), button, gap).max(1);
+        let bottom_min = rows * (button + gap) + gap;
+        let bottom_bar = dock.take(Anchor::Bottom, share(screen.height, 0.10).max(bottom_min));
+        let info_w = share(screen.width, 0.15).max(ctx.text_px(INFO_PANEL_MIN));
+        let battlefield_w = share(screen.width, 0.70).min(dock.remaining().width.saturating_sub(info_w));
+        let battlefield = dock.take(Anchor::Left, battlefield_w);
+        let info_panel = dock.take(Anchor::Left, info_w);
+
+        let padding = Insets::uniform(gap);
+        let ability_area = info_panel.inset(padding);
+        let ability_buttons = flow(&ability_area, abilities.len(), (ability_area.width, button), gap)
             .into_iter()
-            .enumerate()
-            .map(|(i, id)| UiButton {
-                id,
-                bounds: Panel {
-                    x: info_x + 4,
-                    y: top_h + 4 + (i as u32) * 36,
-                    width: info_w - 8,
-                    height: 32,
-                },
-            })
+            .zip(abilities)
+            .map(|(bounds, id)| UiButton { id, bounds })
             .collect();
-
-        let inventory_buttons = items
+        let inventory_buttons = flow(&bottom_bar.inset(padding), items.len(), (button, button), gap)
             .into_iter()
-            .enumerate()
-            .map(|(i, id)| UiButton {
-                id,
-                bounds: Panel {
-                    x: 4 + (i as u32) * 36,
-                    y: screen_height - bottom_h + 4,
-                    width: 32,
-                    height: bottom_h - 8,
-                },
-            })
+            .zip(items)
+            .map(|(bounds, id)| UiButton { id, bounds })
             .collect();
 
         Self {
-            top_bar: Panel { x: 0, y: 0, width: screen_width, height: top_h },
-            battlefield: Panel { x: battlefield_x, y: top_h, width: battlefield_w, height: battlefield_h },
-            info_panel: Panel { x: info_x, y: top_h, width: info_w, height: battlefield_h },
-            bottom_bar: Panel { x: 0, y: screen_height - bottom_h, width: screen_width, height: bottom_h },
+            top_bar,
+            battlefield,
+            info_panel,
+            bottom_bar,
             ability_buttons,
             inventory_buttons,
             floating_texts: Vec::new(),
             current_tab: UiTab::Abilities,
             selected_index: 0,
+            pass_device: None,
+            captions: CaptionsWidget::new(),
+            layout: *ctx,
+            fps_counter: FpsCounter::new(),
+            toasts: Toasts::new(),
+            tooltip: None,
+            modal: None,
+            selection: Vec::new(),
         }
     }
 
+    /// Recompute every panel and button for a new screen, e.g. after a
+    /// window resize, keeping the buttons, selection and overlays.
+    pub fn relayout(&mut self, ctx: &LayoutContext) {
+        let ids = |buttons: &[UiButton]| buttons.iter().map(|b| b.id.clone()).collect();
+        let fresh = Self::with_layout(ctx, ids(&self.ability_buttons), ids(&self.inventory_buttons));
+        self.top_bar = fresh.top_bar;
+        self.battlefield = fresh.battlefield;
+        self.info_panel = fresh.info_panel;
+        self.bottom_bar = fresh.bottom_bar;
+        self.ability_buttons = fresh.ability_buttons;
+        self.inventory_buttons = fresh.inventory_buttons;
+        self.layout = fresh.layout;
+        if let Some(modal) = &mut self.modal {
+            modal.relayout(ctx);
+        }
+    }
+
+    /// Track the mouse pointer; over a modal's button it takes the focus.
+    pub fn hover(&mut self, at: (u32, u32)) {
+        if let Some(modal) = &mut self.modal {
+            modal.hover(at);
+        }
+    }
+
+    /// Tab and index of the button at screen position `at`.
+    pub fn button_at(&self, at: (u32, u32)) -> Option<(UiTab, usize)> {
+        let find = |buttons: &[UiButton]| buttons.iter().position(|b| b.bounds.contains(at));
+        find(&self.ability_buttons)
+            .map(|i| (UiTab::Abilities, i))
+            .or_else(|| find(&self.inventory_buttons).map(|i| (UiTab::Inventory, i)))
+    }
+
     pub fn handle_input(&mut self, action: GameAction) -> Option<UiEvent> {
+        if let Some(screen) = &self.pass_device {
+            if !screen.handle_input(action) {
+                return None;
+            }
+            let player = screen.next_player;
+            self.pass_device = None;
+            return Some(UiEvent::HandoffConfirmed(player));
+        }
+        if let Some(modal) = &mut self.modal {
+            let choice = modal.handle_input(action)?;
+            self.modal = None;
+            return Some(UiEvent::ModalClosed(choice));
+        }
         match action {
             GameAction::SelectUp => {
                 if self.selected_index > 0 {
@@ -135,27 +268,108 @@ impl UiManager {
         }
     }
 
+    /// React to a combat event; a hotseat hand-off raises the pass-device
+    /// screen.
+    pub fn handle_combat_event(&mut self, event: &CombatEvent) {
+        if let CombatEvent::TurnHandedOff { to, .. } = event {
+            self.pass_device = Some(PassDeviceScreen::new(*to));
+        }
+    }
+
+    /// Follow a change of selection: the info panel switches to the new
+    /// primary unit and the ability buttons to its abilities.
+    pub fn handle_selection_changed(&mut self, event: &SelectionChanged, units: &UnitRegistry) {
+        self.selection = event.selected.clone();
+        let abilities = self
+            .selection
+            .first()
+            .and_then(|id| units.by_name(id))
+            .map(|u| u.abilities.iter().map(|a| a.id.clone()).collect())
+            .unwrap_or_default();
+        let items = self.inventory_buttons.iter().map(|b| b.id.clone()).collect();
+        self.ability_buttons = Self::with_layout(&self.layout, abilities, items).ability_buttons;
+        if self.current_tab == UiTab::Abilities {
+            self.selected_index = 0;
+        }
+    }
+
     pub fn spawn_floating_text(&mut self, value: i32, position: (u32, u32)) {
         self.floating_texts.push(FloatingText { value, position, is_heal: value > 0 });
     }
 
+    /// Name, HP and traits of `unit`, listed in the info panel below the
+    /// tab buttons. Returns the y just below the listing.
+    pub fn render_unit_info(&self, renderer: &mut Renderer, loc: &Localizer, unit: &Unit) -> u32 {
+        let x = self.info_panel.x + 4;
+        let width = self.info_panel.width.saturating_sub(8);
+        let direction = loc.direction();
+        let mut y = self.info_panel.y + self.info_panel.height / 2;
+        if let Some(portrait) = &unit.portrait {
+            renderer.submit(DrawCall::new(portrait.clone(), (x, y), DrawLayer::Ui));
+            y += PORTRAIT_SIZE;
+        }
+        y += renderer.draw_paragraph(&unit.name, (x, y), width, 16.0, WHITE, direction);
+        let hp = format!("{} {}/{}", loc.get("ui.hp"), unit.health_points, unit.current_stats.max_health);
+        y += renderer.draw_paragraph(&hp, (x, y), width, 14.0, WHITE, direction) + 2;
+        for t in &unit.traits {
+            y += renderer.draw_paragraph(&loc.get(t.label_key()), (x, y), width, 14.0, WHITE, direction);
+        }
+        y
+    }
+
+    /// The primary selected unit's info, how many more units are selected
+    /// with it and any loot on its tile in `map`.
+    pub fn render_selection(&self, renderer: &mut Renderer, loc: &Localizer, units: &UnitRegistry, map: &GridMap) {
+        let Some(unit) = self.selection.first().and_then(|id| units.by_name(id)) else { return };
+        let mut y = self.render_unit_info(renderer, loc, unit) + 2;
+        let width = self.info_panel.width.saturating_sub(8);
+        if self.selection.len() > 1 {
+            let more = loc.plural("ui.selected_more", self.selection.len() as i64 - 1, &[]);
+            y += renderer.draw_paragraph(&more, (self.info_panel.x + 4, y), width, 14.0, WHITE, loc.direction());
+        }
+        let loot = map.loot_at(&unit.grid_position).count();
+        if loot > 0 {
+            let text = loc.plural("ui.loot_here", loot as i64, &[("ap", &PICKUP_AP_COST.to_string())]);
+            renderer.draw_paragraph(&text, (self.info_panel.x + 4, y), width, 14.0, WHITE, loc.direction());
+        }
+    }
+
     pub fn render(&mut self, renderer: &mut Renderer, loc: &Localizer) {
-        renderer.draw_log.push(DrawCall { sprite_id: loc.get("panel.top_bar"), position: (self.top_bar.x, self.top_bar.y), frame_index: 0 });
-        renderer.draw_log.push(DrawCall { sprite_id: loc.get("panel.battlefield"), position: (self.battlefield.x, self.battlefield.y), frame_index: 0 });
-        renderer.draw_log.push(DrawCall { sprite_id: loc.get("panel.info_panel"), position: (self.info_panel.x, self.info_panel.y), frame_index: 0 });
-        renderer.draw_log.push(DrawCall { sprite_id: loc.get("panel.bottom_bar"), position: (self.bottom_bar.x, self.bottom_bar.y), frame_index: 0 });
+        if let Some(screen) = &self.pass_device {
+            screen.render(renderer, loc);
+            return;
+        }
+        renderer.submit(DrawCall::new(loc.get("panel.top_bar"), (self.top_bar.x, self.top_bar.y), DrawLayer::Ui));
+        renderer.submit(DrawCall::new(loc.get("panel.battlefield"), (self.battlefield.x, self.battlefield.y), DrawLayer::Ui));
+        renderer.submit(DrawCall::new(loc.get("panel.info_panel"), (self.info_panel.x, self.info_panel.y), DrawLayer::Ui));
+        renderer.submit(DrawCall::new(loc.get("panel.bottom_bar"), (self.bottom_bar.x, self.bottom_bar.y), DrawLayer::Ui));
 
         for btn in &self.ability_buttons {
-            renderer.draw_log.push(DrawCall { sprite_id: format!("button:ability:{}", btn.id), position: (btn.bounds.x, btn.bounds.y), frame_index: 0 });
+            renderer.submit(DrawCall::new(format!("button:ability:{}", btn.id), (btn.bounds.x, btn.bounds.y), DrawLayer::Ui));
         }
         for btn in &self.inventory_buttons {
-            renderer.draw_log.push(DrawCall { sprite_id: format!("button:inventory:{}", btn.id), position: (btn.bounds.x, btn.bounds.y), frame_index: 0 });
+            renderer.submit(DrawCall::new(format!("button:inventory:{}", btn.id), (btn.bounds.x, btn.bounds.y), DrawLayer::Ui));
         }
 
+        // Measured every frame so a language switch re-centres the label.
+        let label = self.current_tab.label(loc);
+        let (width, _) = renderer.measure_text(&label, 16.0);
+        let x = self.info_panel.x + self.info_panel.width.saturating_sub(width) / 2;
+        renderer.draw_text(&label, (x, self.top_bar.y + 4), 16.0, WHITE);
+
         for ft in &self.floating_texts {
-            let kind_key = if ft.is_heal { "float.heal" } else { "float.damage" };
-            let prefix = loc.get(kind_key);
-            renderer.draw_log.push(DrawCall { sprite_id: format!("{}:{}", prefix, ft.value.abs()), position: ft.position, frame_index: 0 });
+            let (text, color) = if ft.is_heal {
+                (format!("+{}", ft.value), renderer.palette.color(PaletteRole::Heal))
+            } else {
+                (format!("-{}", ft.value.abs()), renderer.palette.color(PaletteRole::Damage))
+            };
+            renderer.draw_text(&text, ft.position, 20.0, color);
+        }
+        self.captions.render(renderer, loc, self.bottom_bar.y);
+        self.fps_counter.render(renderer);
+        self.toasts.render(renderer, loc);
+        if let Some(modal) = &self.modal {
+            modal.render(renderer, loc);
         }
     }
 }
@@ -0,0 +1,144 @@
+use crate::frontend::text::WHITE;
+use crate::frontend::{DrawCall, DrawLayer, Renderer};
+use crate::input::GameAction;
+use crate::localization::Localizer;
+
+use super::dialogue::CHOICE_SELECTED;
+use super::layout::LayoutContext;
+use super::Panel;
+
+/// Size of the dialog box, in logical pixels.
+const MODAL_WIDTH: u32 = 360;
+const MODAL_HEIGHT: u32 = 160;
+/// Width of each button, in logical pixels.
+const MODAL_BUTTON_WIDTH: u32 = 120;
+
+/// The button a [`ModalDialog`] was closed with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ModalChoice {
+    Confirm,
+    Cancel,
+}
+
+/// A confirm/cancel prompt centred over the UI. While open it takes all
+/// input. The title, message and button labels are locale keys, looked up
+/// when drawn; focus starts on cancel so a stray confirm is harmless.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ModalDialog {
+    pub title: String,
+    pub message: String,
+    /// Values for the message's `{name}` placeholders.
+    pub args: Vec<(String, String)>,
+    /// Count selecting the message's plural form, if it has them.
+    pub count: Option<i64>,
+    pub confirm_label: String,
+    pub cancel_label: String,
+    pub focused: ModalChoice,
+    pub bounds: Panel,
+    pub confirm_button: Panel,
+    pub cancel_button: Panel,
+}
+
+impl ModalDialog {
+    pub fn new(title: &str, message: &str, ctx: &LayoutContext) -> Self {
+        let mut dialog = Self {
+            title: title.into(),
+            message: message.into(),
+            args: Vec::new(),
+            count: None,
+            confirm_label: "modal.confirm".into(),
+            cancel_label: "modal.cancel".into(),
+            focused: ModalChoice::Cancel,
+            bounds: Panel { x: 0, y: 0, width: 0, height: 0 },
+            confirm_button: Panel { x: 0, y: 0, width: 0, height: 0 },
+            cancel_button: Panel { x: 0, y: 0, width: 0, height: 0 },
+        };
+        dialog.relayout(ctx);
+        dialog
+    }
+
+    pub fn with_args(mut self, args: &[(&str, &str)]) -> Self {
+        self.args = args.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect();
+        self
+    }
+
+    pub fn with_count(mut self, count: i64) -> Self {
+        self.count = Some(count);
+        self
+    }
+
+    pub fn with_labels(mut self, confirm: &str, cancel: &str) -> Self {
+        self.confirm_label = confirm.into();
+        self.cancel_label = cancel.into();
+        self
+    }
+
+    /// Centre the box in the safe area of `ctx` with the buttons along its
+    /// bottom edge, confirm on the left.
+    pub fn relayout(&mut self, ctx: &LayoutContext) {
+        let screen = ctx.safe_rect();
+        let (width, height) = (ctx.px(MODAL_WIDTH).min(screen.width), ctx.text_px(MODAL_HEIGHT).min(screen.height));
+        self.bounds = Panel { x: screen.x + (screen.width - width) / 2, y: screen.y + (screen.height - height) / 2, width, height };
+        let gap = ctx.px(super::BUTTON_GAP) * 4;
+        let button = Panel { x: 0, y: 0, width: ctx.px(MODAL_BUTTON_WIDTH), height: ctx.px(ctx.button_size()) };
+        let y = (self.bounds.y + height).saturating_sub(button.height + gap);
+        let centre = self.bounds.x + width / 2;
+        self.confirm_button = Panel { x: centre.saturating_sub(button.width + gap / 2), y, ..button };
+        self.cancel_button = Panel { x: centre + gap / 2, y, ..button };
+    }
+
+    /// Move focus between the buttons or choose the focused one.
+    pub fn handle_input(&mut self, action: GameAction) -> Option<ModalChoice> {
+        match action {
+            GameAction::SelectUp | GameAction::SelectDown => {
+                self.focused = match self.focused {
+                    ModalChoice::Confirm => ModalChoice::Cancel,
+                    ModalChoice::Cancel => ModalChoice::Confirm,
+                };
+                None
+            }
+            GameAction::Activate => Some(self.focused),
+        }
+    }
+
+    /// The button at screen position `at`, if any.
+    pub fn button_at(&self, at: (u32, u32)) -> Option<ModalChoice> {
+        if self.confirm_button.contains(at) {
+            Some(ModalChoice::Confirm)
+        } else if self.cancel_button.contains(at) {
+            Some(ModalChoice::Cancel)
+        } else {
+            None
+        }
+    }
+
+    /// Focus the button under the pointer, so a click chooses it.
+    pub fn hover(&mut self, at: (u32, u32)) {
+        if let Some(choice) = self.button_at(at) {
+            self.focused = choice;
+        }
+    }
+
+    pub fn message_text(&self, loc: &Localizer) -> String {
+        let args: Vec<(&str, &str)> = self.args.iter().map(|(k, v)| (k.as_str(), v.as_str())).collect();
+        match self.count {
+            Some(count) => loc.plural(&self.message, count, &args),
+            None => loc.format(&self.message, &args),
+        }
+    }
+
+    pub fn render(&self, renderer: &mut Renderer, loc: &Localizer) {
+        let Panel { x, y, width, .. } = self.bounds;
+        renderer.submit(DrawCall::new(loc.get("panel.modal"), (x, y), DrawLayer::Ui));
+        renderer.draw_text(&loc.get(&self.title), (x + 16, y + 12), 20.0, WHITE);
+        renderer.draw_paragraph(&self.message_text(loc), (x + 16, y + 44), width.saturating_sub(32), 14.0, WHITE, loc.direction());
+        for (choice, button, label) in [
+            (ModalChoice::Confirm, &self.confirm_button, &self.confirm_label),
+            (ModalChoice::Cancel, &self.cancel_button, &self.cancel_label),
+        ] {
+            renderer.submit(DrawCall::new("button:modal", (button.x, button.y), DrawLayer::Ui));
+            let color = if choice == self.focused { CHOICE_SELECTED } else { WHITE };
+            renderer.draw_text(&loc.get(label), (button.x + 8, button.y + 8), 16.0, color);
+        }
+    }
+}
@@ -0,0 +1,72 @@
+use std::io;
+
+use crate::frontend::text::WHITE;
+use crate::frontend::{DrawCall, DrawLayer, Renderer};
+use crate::input::GameAction;
+use crate::localization::Localizer;
+use crate::mods::{Conflict, ModLoader};
+
+use super::dialogue::CHOICE_SELECTED;
+
+const ROW_HEIGHT: u32 = 32;
+
+/// Mod list: one row per mod in load order with a checkbox and the number
+/// of conflicts it is part of, then a back row. Changes are saved to the
+/// load order file at once and take effect on the next launch.
+#[derive(Debug, Clone)]
+pub struct ModsScreen {
+    pub loader: ModLoader,
+    pub conflicts: Vec<Conflict>,
+    /// Highlighted row; `loader.mods.len()` is the back row.
+    pub selected: usize,
+}
+
+impl ModsScreen {
+    pub fn new(loader: ModLoader) -> Self {
+        let conflicts = loader.conflicts().unwrap_or_default();
+        Self { loader, conflicts, selected: 0 }
+    }
+
+    /// Number of conflicts the mod `id` is part of.
+    pub fn conflict_count(&self, id: &str) -> usize {
+        self.conflicts.iter().filter(|c| c.mods.iter().any(|m| m == id)).count()
+    }
+
+    /// Move the cursor or toggle the highlighted mod. Returns `Ok(true)`
+    /// when the back row is activated.
+    pub fn handle_input(&mut self, action: GameAction) -> io::Result<bool> {
+        match action {
+            GameAction::SelectUp => self.selected = self.selected.saturating_sub(1),
+            GameAction::SelectDown => self.selected = (self.selected + 1).min(self.loader.mods.len()),
+            GameAction::Activate => {
+                let Some(info) = self.loader.mods.get_mut(self.selected) else { return Ok(true) };
+                info.enabled = !info.enabled;
+                self.conflicts = self.loader.conflicts().unwrap_or_default();
+                self.loader.save_order()?;
+            }
+        }
+        Ok(false)
+    }
+
+    pub fn render(&self, renderer: &mut Renderer, loc: &Localizer) {
+        renderer.submit(DrawCall::new(loc.get("panel.mods"), (0, 0), DrawLayer::Ui));
+        renderer.draw_text(&loc.get("mods.title"), (16, 16), 20.0, WHITE);
+        if self.loader.mods.is_empty() {
+            renderer.draw_text(&loc.get("mods.none"), (16, 56), 16.0, WHITE);
+        }
+        for (i, info) in self.loader.mods.iter().enumerate() {
+            let y = 56 + i as u32 * ROW_HEIGHT;
+            let color = if i == self.selected { CHOICE_SELECTED } else { WHITE };
+            let mark = if info.enabled { "[x]" } else { "[ ]" };
+            renderer.draw_text(&format!("{} {} {}", mark, info.name, info.version), (16, y), 16.0, color);
+            let count = self.conflict_count(&info.id);
+            if count > 0 {
+                renderer.draw_text(&loc.plural("mods.conflicts", count as i64, &[]), (360, y), 14.0, color);
+            }
+        }
+        let y = 56 + self.loader.mods.len().max(1) as u32 * ROW_HEIGHT + 8;
+        let color = if self.selected == self.loader.mods.len() { CHOICE_SELECTED } else { WHITE };
+        renderer.draw_text(&loc.get("mods.back"), (16, y), 16.0, color);
+        renderer.draw_text(&loc.get("mods.restart"), (16, y + ROW_HEIGHT), 12.0, WHITE);
+    }
+}
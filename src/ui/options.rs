@@ -1,4 +1,12 @@
-#[derive(Debug, Clone)]
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::localization::{SharedLocalizer, BASE_LANGUAGE};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum ColorBlindPalette {
     Normal,
     Protanopia,
@@ -6,7 +14,7 @@ pub enum ColorBlindPalette {
     Tritanopia,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct AccessibilitySettings {
     pub palette: ColorBlindPalette,
     pub font_scale: f32,
@@ -19,13 +27,170 @@ impl Default for AccessibilitySettings {
     }
 }
 
+/// Where [`OptionsMenu::save_config`] writes by default.
+pub const CONFIG_PATH: &str = "config.json";
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct DisplaySettings {
+    /// Cover the screen with a borderless window instead of a decorated one.
+    pub borderless_fullscreen: bool,
+    /// Close in on kills and critical hits in slow motion, letterboxed.
+    #[serde(default)]
+    pub cinematic_camera: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RenderSettings {
+    /// Present in step with the display's refresh.
+    pub vsync: bool,
+    /// Most frames drawn per second, to save battery; `None` is uncapped.
+    #[serde(default)]
+    pub fps_cap: Option<u32>,
+    /// Show the FPS and frame-time counter.
+    #[serde(default)]
+    pub show_fps: bool,
+    /// Keep the last this many seconds of frames so they can be saved as a
+    /// GIF; `None` records nothing.
+    #[serde(default)]
+    pub clip_seconds: Option<u32>,
+}
+
+impl Default for RenderSettings {
+    fn default() -> Self {
+        Self { vsync: true, fps_cap: None, show_fps: false, clip_seconds: None }
+    }
+}
+
+/// Which risky actions ask for confirmation first.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ConfirmationSettings {
+    /// Ending a turn while the acting unit still has action points.
+    pub end_turn_with_ap: bool,
+    /// Attacks and blasts that would hit units on the actor's own side.
+    pub friendly_fire: bool,
+}
+
+impl Default for ConfirmationSettings {
+    fn default() -> Self {
+        Self { end_turn_with_ap: true, friendly_fire: true }
+    }
+}
+
+/// Whether and how often the game offers hints during battle.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HintSettings {
+    pub enabled: bool,
+    /// Rounds before the same hint may be offered again.
+    pub cooldown_rounds: u32,
+}
+
+impl Default for HintSettings {
+    fn default() -> Self {
+        Self { enabled: true, cooldown_rounds: 3 }
+    }
+}
+
+/// Options kept between runs.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct Config {
+    #[serde(default)]
+    accessibility: AccessibilitySettings,
+    #[serde(default)]
+    display: DisplaySettings,
+    #[serde(default)]
+    render: RenderSettings,
+    #[serde(default)]
+    confirmations: ConfirmationSettings,
+    #[serde(default)]
+    hints: HintSettings,
+    #[serde(default)]
+    language: Option<String>,
+}
+
 #[derive(Debug, Clone)]
 pub struct OptionsMenu {
     pub accessibility: AccessibilitySettings,
+    /// Language currently shown.
+    pub language: String,
+    pub display: DisplaySettings,
+    pub render: RenderSettings,
+    pub confirmations: ConfirmationSettings,
+    pub hints: HintSettings,
+}
+
+impl Default for OptionsMenu {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl OptionsMenu {
     pub fn new() -> Self {
-        Self { accessibility: AccessibilitySettings::default() }
+        Self {
+            accessibility: AccessibilitySettings::default(),
+            language: BASE_LANGUAGE.to_string(),
+            display: DisplaySettings::default(),
+            render: RenderSettings::default(),
+            confirmations: ConfirmationSettings::default(),
+            hints: HintSettings::default(),
+        }
+    }
+
+    /// Options saved by [`OptionsMenu::save_config`]. Settings missing from
+    /// the file keep their defaults.
+    pub fn load_config<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        let data = fs::read_to_string(path)?;
+        let config: Config = serde_json::from_str(&data).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        Ok(Self {
+            accessibility: config.accessibility,
+            language: config.language.unwrap_or_else(|| BASE_LANGUAGE.to_string()),
+            display: config.display,
+            render: config.render,
+            confirmations: config.confirmations,
+            hints: config.hints,
+        })
+    }
+
+    pub fn save_config<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        let config = Config {
+            accessibility: self.accessibility.clone(),
+            display: self.display.clone(),
+            render: self.render.clone(),
+            confirmations: self.confirmations.clone(),
+            hints: self.hints.clone(),
+            language: Some(self.language.clone()),
+        };
+        let data = serde_json::to_string_pretty(&config).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        fs::write(path, data)
+    }
+
+    /// Flip borderless fullscreen and return the new state.
+    pub fn toggle_fullscreen(&mut self) -> bool {
+        self.display.borderless_fullscreen = !self.display.borderless_fullscreen;
+        self.display.borderless_fullscreen
+    }
+
+    /// Flip the cinematic camera and return the new state.
+    pub fn toggle_cinematic_camera(&mut self) -> bool {
+        self.display.cinematic_camera = !self.display.cinematic_camera;
+        self.display.cinematic_camera
+    }
+
+    /// Turn hints on or off and return the new state.
+    pub fn toggle_hints(&mut self) -> bool {
+        self.hints.enabled = !self.hints.enabled;
+        self.hints.enabled
+    }
+
+    /// Switch every screen to `language` without a restart. Screens look
+    /// strings up each frame, so the next render picks it up. On failure the
+    /// previous language stays active.
+    pub fn switch_language(&mut self, loc: &SharedLocalizer, language: &str) -> io::Result<()> {
+        let mut loc = loc.write().map_err(|_| io::Error::other("localizer lock poisoned"))?;
+        loc.load(language)?;
+        self.language = language.to_string();
+        Ok(())
     }
 }
@@ -0,0 +1,103 @@
+use crate::campaign::Campaign;
+use crate::frontend::backend::DrawRect;
+use crate::frontend::text::{Color, DAMAGE_RED, WHITE};
+use crate::frontend::{DrawCall, DrawLayer, Renderer};
+use crate::input::GameAction;
+use crate::localization::Localizer;
+use crate::overworld::{OverworldMap, Travel};
+use crate::rng::GameRng;
+
+use super::dialogue::CHOICE_SELECTED;
+
+/// Side of a location marker, in logical pixels.
+pub const NODE_SIZE: u32 = 24;
+/// Pixels between the dots drawn along a route.
+const ROUTE_DOT_SPACING: u32 = 16;
+const ROUTE_DOT: Color = [0.7, 0.7, 0.6, 1.0];
+
+/// The strategic map: every location and route, with the squad's
+/// neighbours selectable as destinations. `Activate` travels to the
+/// selected one. Enemy-held territory is drawn with its own marker.
+#[derive(Debug, Clone, Default)]
+pub struct OverworldScreen {
+    /// Index into the current location's neighbours.
+    pub selected_index: usize,
+    /// The last journey, shown until the next input.
+    pub last_travel: Option<Travel>,
+    /// Error from the last journey, shown until the next input.
+    pub last_error: Option<&'static str>,
+}
+
+impl OverworldScreen {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn handle_input(&mut self, action: GameAction, map: &OverworldMap, campaign: &mut Campaign, rng: &mut GameRng) {
+        let destinations: Vec<String> = map.neighbours(campaign.location(map)).iter().map(|l| l.id.clone()).collect();
+        self.last_travel = None;
+        self.last_error = None;
+        match action {
+            GameAction::SelectUp => self.selected_index = self.selected_index.saturating_sub(1),
+            GameAction::SelectDown => {
+                if self.selected_index + 1 < destinations.len() {
+                    self.selected_index += 1;
+                }
+            }
+            GameAction::Activate => {
+                let Some(to) = destinations.get(self.selected_index) else { return };
+                match campaign.travel(map, to, rng) {
+                    Ok(travel) => {
+                        self.last_travel = Some(travel);
+                        self.selected_index = 0;
+                    }
+                    Err(e) => self.last_error = Some(e),
+                }
+            }
+        }
+    }
+
+    pub fn render(&self, renderer: &mut Renderer, loc: &Localizer, map: &OverworldMap, campaign: &Campaign) {
+        renderer.submit(DrawCall::new(loc.get("panel.overworld"), (0, 0), DrawLayer::Ui));
+        renderer.draw_text(&loc.format("overworld.day", &[("day", &campaign.overworld.day.to_string())]), (16, 16), 18.0, WHITE);
+        for route in &map.routes {
+            let (Some(a), Some(b)) = (map.location(&route.from), map.location(&route.to)) else { continue };
+            let (dx, dy) = (b.position.0 as f32 - a.position.0 as f32, b.position.1 as f32 - a.position.1 as f32);
+            let dots = ((dx * dx + dy * dy).sqrt() / ROUTE_DOT_SPACING as f32) as u32;
+            for i in 1..dots {
+                let t = i as f32 / dots as f32;
+                let position = ((a.position.0 as f32 + dx * t) as u32, (a.position.1 as f32 + dy * t) as u32);
+                renderer.draw_rect(DrawRect { position, size: (3, 3), color: ROUTE_DOT, layer: DrawLayer::Ui });
+            }
+        }
+        let here = campaign.location(map);
+        let destinations = map.neighbours(here);
+        let selected = destinations.get(self.selected_index).map(|l| l.id.as_str());
+        for location in &map.locations {
+            let corner = (location.position.0.saturating_sub(NODE_SIZE / 2), location.position.1.saturating_sub(NODE_SIZE / 2));
+            let sprite = if campaign.controller(map, &location.id).is_some() { "overworld:enemy" } else { "overworld:friendly" };
+            let tint = (selected == Some(location.id.as_str())).then_some(CHOICE_SELECTED);
+            renderer.submit(DrawCall { tint, ..DrawCall::new(sprite, corner, DrawLayer::Ui) });
+            if location.id == here {
+                renderer.submit(DrawCall::new("overworld:squad", corner, DrawLayer::Ui));
+            }
+            let label = (corner.0, location.position.1 + NODE_SIZE / 2 + 4);
+            renderer.draw_text(&loc.get(&location.name), label, 14.0, tint.unwrap_or(WHITE));
+        }
+        let footer = renderer.height - 32;
+        if let Some(err) = self.last_error {
+            renderer.draw_text(&loc.get(err), (16, footer), 16.0, DAMAGE_RED);
+        } else if let Some(travel) = &self.last_travel {
+            let text = match &travel.interception {
+                Some(_) => loc.get("overworld.intercepted"),
+                None => loc.plural("overworld.arrived", travel.days as i64, &[]),
+            };
+            let color = if travel.interception.is_some() { DAMAGE_RED } else { WHITE };
+            renderer.draw_text(&text, (16, footer), 16.0, color);
+        } else if let Some(route) = selected.and_then(|id| map.route(here, id)) {
+            let days = loc.plural("overworld.days", route.days as i64, &[]);
+            let color = if route.cost > campaign.currency { DAMAGE_RED } else { WHITE };
+            renderer.draw_text(&format!("{} - {} {}", days, loc.get("overworld.cost"), route.cost), (16, footer), 16.0, color);
+        }
+    }
+}
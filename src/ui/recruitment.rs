@@ -0,0 +1,73 @@
+use crate::frontend::text::{DAMAGE_RED, HEAL_GREEN, WHITE};
+use crate::frontend::{DrawCall, DrawLayer, Renderer};
+use crate::input::GameAction;
+use crate::localization::Localizer;
+use crate::recruitment::{RecruitmentOutcome, RecruitmentSession};
+
+use super::dialogue::CHOICE_SELECTED;
+
+/// Question screen for a recruitment challenge: the localized question,
+/// one button per answer, the running score and feedback on the previous
+/// answer.
+#[derive(Debug, Clone)]
+pub struct RecruitmentScreen {
+    pub session: RecruitmentSession,
+    pub selected_option: usize,
+}
+
+impl RecruitmentScreen {
+    pub fn new(session: RecruitmentSession) -> Self {
+        Self { session, selected_option: 0 }
+    }
+
+    /// Move the answer cursor or submit the selected answer. Returns the
+    /// outcome after the input is applied.
+    pub fn handle_input(&mut self, action: GameAction) -> RecruitmentOutcome {
+        let options = self.session.current().map(|q| q.options.len()).unwrap_or(0);
+        match action {
+            GameAction::SelectUp => self.selected_option = self.selected_option.saturating_sub(1),
+            GameAction::SelectDown => {
+                if self.selected_option + 1 < options {
+                    self.selected_option += 1;
+                }
+            }
+            GameAction::Activate => {
+                if self.session.answer(self.selected_option).is_ok() {
+                    self.selected_option = 0;
+                }
+            }
+        }
+        self.session.outcome()
+    }
+
+    pub fn render(&self, renderer: &mut Renderer, loc: &Localizer) {
+        renderer.submit(DrawCall::new(loc.get("panel.recruitment"), (0, 0), DrawLayer::Ui));
+        let challenge = &self.session.challenge;
+        let score = format!("{} {}/{}", loc.get("recruit.score"), challenge.player_score, challenge.required_correct_answers);
+        renderer.draw_text(&score, (16, 16), 16.0, WHITE);
+
+        if let Some(correct) = self.session.last_answer {
+            let (key, color) = if correct { ("recruit.correct", HEAL_GREEN) } else { ("recruit.incorrect", DAMAGE_RED) };
+            renderer.draw_text(&loc.get(key), (16, 40), 16.0, color);
+            if let Some(prev) = challenge.questions.get(self.session.current_question.wrapping_sub(1)) {
+                renderer.draw_text(&loc.get(&prev.explanation), (16, 60), 14.0, WHITE);
+            }
+        }
+
+        let Some(question) = self.session.current() else {
+            let key = match self.session.outcome() {
+                RecruitmentOutcome::Recruited => "recruit.recruited",
+                _ => "recruit.failed",
+            };
+            renderer.draw_text(&loc.get(key), (16, 96), 20.0, WHITE);
+            return;
+        };
+        renderer.draw_text(&loc.get(&question.question), (16, 96), 20.0, WHITE);
+        for (i, option) in question.options.iter().enumerate() {
+            let y = 136 + i as u32 * 40;
+            renderer.submit(DrawCall::new(format!("button:answer:{}", i), (16, y), DrawLayer::Ui));
+            let color = if i == self.selected_option { CHOICE_SELECTED } else { WHITE };
+            renderer.draw_text(&loc.get(option), (24, y + 8), 16.0, color);
+        }
+    }
+}
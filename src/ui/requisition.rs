@@ -0,0 +1,63 @@
+use crate::campaign::Campaign;
+use crate::content::ContentDatabase;
+use crate::frontend::text::{DAMAGE_RED, WHITE};
+use crate::frontend::{DrawCall, DrawLayer, Renderer};
+use crate::input::GameAction;
+use crate::localization::Localizer;
+
+use super::dialogue::CHOICE_SELECTED;
+
+/// Between-mission shop listing the stock unlocked so far. `Activate` buys
+/// the selected item into the campaign inventory.
+#[derive(Debug, Clone, Default)]
+pub struct RequisitionScreen {
+    pub selected_index: usize,
+    /// Error from the last purchase attempt, shown until the next input.
+    pub last_error: Option<&'static str>,
+}
+
+impl RequisitionScreen {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn handle_input(&mut self, action: GameAction, db: &ContentDatabase, campaign: &mut Campaign) {
+        let stock = db.available(campaign.missions_completed);
+        self.last_error = None;
+        match action {
+            GameAction::SelectUp => self.selected_index = self.selected_index.saturating_sub(1),
+            GameAction::SelectDown => {
+                if self.selected_index + 1 < stock.len() {
+                    self.selected_index += 1;
+                }
+            }
+            GameAction::Activate => {
+                if let Some(id) = stock.get(self.selected_index) {
+                    self.last_error = campaign.purchase(db, id).err();
+                }
+            }
+        }
+    }
+
+    pub fn render(&self, renderer: &mut Renderer, loc: &Localizer, db: &ContentDatabase, campaign: &Campaign) {
+        renderer.submit(DrawCall::new(loc.get("panel.requisition"), (0, 0), DrawLayer::Ui));
+        let funds = format!("{} {}", loc.get("requisition.funds"), campaign.currency);
+        renderer.draw_text(&funds, (16, 16), 18.0, WHITE);
+        for (i, id) in db.available(campaign.missions_completed).iter().enumerate() {
+            let Some(entry) = db.get(id) else { continue };
+            let y = 56 + i as u32 * 28;
+            let color = if i == self.selected_index {
+                CHOICE_SELECTED
+            } else if entry.price > campaign.currency {
+                DAMAGE_RED
+            } else {
+                WHITE
+            };
+            renderer.submit(DrawCall::new(format!("icon:item:{}", id), (16, y), DrawLayer::Ui));
+            renderer.draw_text(&format!("{} - {}", entry.item.name(), entry.price), (48, y + 4), 16.0, color);
+        }
+        if let Some(err) = self.last_error {
+            renderer.draw_text(&loc.get(err), (16, renderer.height - 32), 16.0, DAMAGE_RED);
+        }
+    }
+}
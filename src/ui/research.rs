@@ -0,0 +1,79 @@
+use crate::campaign::Campaign;
+use crate::frontend::text::{Color, DAMAGE_RED, HEAL_GREEN, WHITE};
+use crate::frontend::{DrawCall, DrawLayer, Renderer};
+use crate::input::GameAction;
+use crate::localization::Localizer;
+use crate::research::{ProjectStatus, ResearchTree};
+
+use super::dialogue::CHOICE_SELECTED;
+
+/// Projects waiting on a prerequisite are drawn dimmed.
+const LOCKED: Color = [0.5, 0.5, 0.5, 1.0];
+/// Indent per prerequisite depth, in logical pixels.
+const TIER_INDENT: u32 = 24;
+
+/// Between-mission screen listing the research tree, each project
+/// indented below the ones it builds on, with its status. `Activate`
+/// starts researching the selected project.
+#[derive(Debug, Clone, Default)]
+pub struct ResearchScreen {
+    pub selected_index: usize,
+    /// Error from the last attempt to start research, shown until the next
+    /// input.
+    pub last_error: Option<&'static str>,
+}
+
+impl ResearchScreen {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn handle_input(&mut self, action: GameAction, tree: &ResearchTree, campaign: &mut Campaign) {
+        self.last_error = None;
+        match action {
+            GameAction::SelectUp => self.selected_index = self.selected_index.saturating_sub(1),
+            GameAction::SelectDown => {
+                if self.selected_index + 1 < tree.projects.len() {
+                    self.selected_index += 1;
+                }
+            }
+            GameAction::Activate => {
+                if let Some(project) = tree.projects.get(self.selected_index) {
+                    self.last_error = campaign.start_research(tree, &project.id).err();
+                }
+            }
+        }
+    }
+
+    /// How many prerequisites deep `id` sits in `tree`.
+    fn depth(tree: &ResearchTree, id: &str) -> u32 {
+        tree.get(id).and_then(|p| p.prerequisites.iter().map(|p| 1 + Self::depth(tree, p)).max()).unwrap_or(0)
+    }
+
+    pub fn render(&self, renderer: &mut Renderer, loc: &Localizer, tree: &ResearchTree, campaign: &Campaign) {
+        renderer.submit(DrawCall::new(loc.get("panel.research"), (0, 0), DrawLayer::Ui));
+        let funds = format!("{} {}", loc.get("requisition.funds"), campaign.currency);
+        renderer.draw_text(&funds, (16, 16), 18.0, WHITE);
+        for (i, project) in tree.projects.iter().enumerate() {
+            let status = campaign.project_status(project);
+            let (detail, color) = match status {
+                ProjectStatus::Completed => (loc.get("research.done"), HEAL_GREEN),
+                ProjectStatus::Active(n) => (loc.plural("research.active", n as i64, &[]), WHITE),
+                ProjectStatus::Available => {
+                    let color = if project.cost > campaign.currency { DAMAGE_RED } else { WHITE };
+                    (format!("{} {}", loc.get("research.cost"), project.cost), color)
+                }
+                ProjectStatus::Locked => {
+                    let names: Vec<String> = project.prerequisites.iter().filter_map(|p| tree.get(p)).map(|p| loc.get(&p.name)).collect();
+                    (loc.format("research.locked", &[("projects", &names.join(", "))]), LOCKED)
+                }
+            };
+            let color = if i == self.selected_index { CHOICE_SELECTED } else { color };
+            let x = 16 + Self::depth(tree, &project.id) * TIER_INDENT;
+            renderer.draw_text(&format!("{} - {}", loc.get(&project.name), detail), (x, 56 + i as u32 * 28), 16.0, color);
+        }
+        if let Some(err) = self.last_error {
+            renderer.draw_text(&loc.get(err), (16, renderer.height - 32), 16.0, DAMAGE_RED);
+        }
+    }
+}
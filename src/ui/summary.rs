@@ -0,0 +1,193 @@
+use crate::debrief::{BattleReport, ObjectiveResult, UnitReport};
+use crate::frontend::palette::PaletteRole;
+use crate::frontend::text::{DAMAGE_RED, WHITE};
+use crate::frontend::{DrawCall, DrawLayer, Renderer};
+use crate::input::GameAction;
+use crate::localization::Localizer;
+use crate::loot::Loot;
+use crate::registry::Side;
+use crate::simulation::BattleOutcome;
+use crate::stats::UnitStats;
+
+use super::dialogue::CHOICE_SELECTED;
+
+/// Column headings after the unit name, in draw order.
+const COLUMNS: [&str; 8] = [
+    "summary.damage_dealt",
+    "summary.damage_taken",
+    "summary.kills",
+    "summary.accuracy",
+    "summary.abilities",
+    "summary.tiles",
+    "summary.turns",
+    "summary.xp",
+];
+const NAME_WIDTH: u32 = 200;
+const COLUMN_WIDTH: u32 = 100;
+const ROW_HEIGHT: u32 = 24;
+
+/// One unit's line in the summary table.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SummaryRow {
+    pub name: String,
+    pub side: Side,
+    pub stats: UnitStats,
+    pub experience: u32,
+    /// Levels gained from `experience`.
+    pub levels: u32,
+    pub downed: bool,
+}
+
+impl SummaryRow {
+    fn new(unit: &UnitReport) -> Self {
+        Self {
+            name: unit.name.clone(),
+            side: unit.side,
+            stats: unit.stats,
+            experience: unit.experience,
+            levels: unit.levels,
+            downed: unit.downed,
+        }
+    }
+
+    /// Values for [`COLUMNS`], formatted for display.
+    fn cells(&self) -> [String; 8] {
+        let s = &self.stats;
+        let accuracy = s.accuracy().map(|a| format!("{:.0}%", a * 100.0)).unwrap_or_else(|| "-".into());
+        let experience = if self.side == Side::Player { format!("+{}", self.experience) } else { "-".into() };
+        [
+            s.damage_dealt.to_string(),
+            s.damage_taken.to_string(),
+            s.kills.to_string(),
+            accuracy,
+            s.abilities_used.to_string(),
+            s.tiles_moved.to_string(),
+            s.turns_survived.to_string(),
+            experience,
+        ]
+    }
+}
+
+/// Where the player goes from the summary.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SummaryChoice {
+    /// On with the campaign, keeping the results.
+    Continue,
+    /// Fight the battle again from the start, as if it never happened.
+    Retry,
+}
+
+impl SummaryChoice {
+    pub fn label_key(&self) -> &'static str {
+        match self {
+            SummaryChoice::Continue => "summary.continue",
+            SummaryChoice::Retry => "summary.retry",
+        }
+    }
+}
+
+/// End-of-battle screen: the outcome, rounds fought, objectives, a table
+/// of each unit's stats and experience, player side first, the loot kept
+/// and the squad's casualties, then the way on.
+#[derive(Debug, Clone)]
+pub struct BattleSummaryScreen {
+    pub outcome: BattleOutcome,
+    pub rounds: u32,
+    pub rows: Vec<SummaryRow>,
+    pub objectives: Vec<ObjectiveResult>,
+    pub loot: Vec<Loot>,
+    /// Names of the player units that went down.
+    pub casualties: Vec<String>,
+    /// The choices offered; retrying is left out of iron-man campaigns.
+    pub choices: Vec<SummaryChoice>,
+    pub selected: usize,
+}
+
+impl BattleSummaryScreen {
+    pub fn new(report: &BattleReport, can_retry: bool) -> Self {
+        let casualties = report.casualties.iter().filter_map(|id| report.unit(id)).map(|u| u.name.clone()).collect();
+        let mut choices = vec![SummaryChoice::Continue];
+        if can_retry {
+            choices.push(SummaryChoice::Retry);
+        }
+        Self {
+            outcome: report.outcome,
+            rounds: report.rounds,
+            rows: report.units.iter().map(SummaryRow::new).collect(),
+            objectives: report.objectives.clone(),
+            loot: report.loot.clone(),
+            casualties,
+            choices,
+            selected: 0,
+        }
+    }
+
+    /// Move between the choices; returns the one activated.
+    pub fn handle_input(&mut self, action: GameAction) -> Option<SummaryChoice> {
+        match action {
+            GameAction::SelectUp => self.selected = self.selected.saturating_sub(1),
+            GameAction::SelectDown => self.selected = (self.selected + 1).min(self.choices.len() - 1),
+            GameAction::Activate => return self.choices.get(self.selected).copied(),
+        }
+        None
+    }
+
+    pub fn render(&self, renderer: &mut Renderer, loc: &Localizer) {
+        renderer.submit(DrawCall::new(loc.get("panel.summary"), (0, 0), DrawLayer::Ui));
+        let title = match self.outcome {
+            BattleOutcome::PlayerVictory => "summary.victory",
+            BattleOutcome::EnemyVictory => "summary.defeat",
+            BattleOutcome::Draw => "summary.draw",
+        };
+        renderer.draw_text(&loc.get(title), (16, 16), 24.0, WHITE);
+        let rounds = loc.plural("summary.rounds", self.rounds as i64, &[]);
+        renderer.draw_text(&rounds, (16, 48), 16.0, WHITE);
+
+        let mut y = 88;
+        for result in &self.objectives {
+            let (mark, color) = if result.met { ("[x]", WHITE) } else { ("[ ]", DAMAGE_RED) };
+            renderer.draw_text(&format!("{} {}", mark, result.objective.describe(loc)), (16, y), 14.0, color);
+            y += ROW_HEIGHT;
+        }
+
+        let column_x = |i: usize| 16 + NAME_WIDTH + i as u32 * COLUMN_WIDTH;
+        y += 8;
+        renderer.draw_text(&loc.get("summary.unit"), (16, y), 14.0, WHITE);
+        for (i, key) in COLUMNS.iter().enumerate() {
+            renderer.draw_text(&loc.get(key), (column_x(i), y), 14.0, WHITE);
+        }
+        for row in &self.rows {
+            y += ROW_HEIGHT;
+            let color = renderer.palette.color(PaletteRole::of_side(row.side));
+            renderer.draw_text(&row.name, (16, y), 14.0, color);
+            for (i, cell) in row.cells().iter().enumerate() {
+                renderer.draw_text(cell, (column_x(i), y), 14.0, WHITE);
+            }
+            if row.levels > 0 {
+                let text = loc.plural("summary.level_up", row.levels as i64, &[]);
+                renderer.draw_text(&text, (column_x(COLUMNS.len()), y), 14.0, CHOICE_SELECTED);
+            }
+        }
+
+        y += 2 * ROW_HEIGHT;
+        let loot: Vec<String> = self
+            .loot
+            .iter()
+            .map(|l| match l {
+                Loot::Item(id) => id.clone(),
+                Loot::Requisition(points) => loc.plural("summary.requisition", *points as i64, &[]),
+            })
+            .collect();
+        let loot = if loot.is_empty() { loc.get("summary.none") } else { loot.join(", ") };
+        renderer.draw_text(&format!("{} {}", loc.get("summary.loot"), loot), (16, y), 14.0, WHITE);
+        y += ROW_HEIGHT;
+        let casualties = if self.casualties.is_empty() { loc.get("summary.none") } else { self.casualties.join(", ") };
+        renderer.draw_text(&format!("{} {}", loc.get("summary.casualties"), casualties), (16, y), 14.0, WHITE);
+
+        y += 2 * ROW_HEIGHT;
+        for (i, choice) in self.choices.iter().enumerate() {
+            let color = if i == self.selected { CHOICE_SELECTED } else { WHITE };
+            renderer.draw_text(&loc.get(choice.label_key()), (16 + i as u32 * 200, y), 16.0, color);
+        }
+    }
+}
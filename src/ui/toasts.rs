@@ -0,0 +1,126 @@
+use std::collections::VecDeque;
+use std::io;
+
+use crate::achievements::Achievement;
+use crate::combat::CombatError;
+use crate::frontend::text::WHITE;
+use crate::frontend::{DrawCall, DrawLayer, Renderer};
+use crate::hints::Hint;
+use crate::injuries::InjuryKind;
+use crate::localization::Localizer;
+
+/// Seconds a toast stays on screen.
+pub const TOAST_DURATION: f32 = 4.0;
+/// Most toasts shown at once; older ones are dropped first.
+pub const MAX_TOASTS: usize = 3;
+const TOAST_WIDTH: u32 = 280;
+
+/// A short notification. All three strings are locale keys, looked up
+/// when drawn; text that isn't a key is shown as it is.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Toast {
+    pub heading: String,
+    pub title: String,
+    pub body: String,
+}
+
+impl Toast {
+    pub fn achievement(achievement: &Achievement) -> Self {
+        Self {
+            heading: "toast.achievement".into(),
+            title: achievement.name.clone(),
+            body: achievement.description.clone(),
+        }
+    }
+
+    /// A unit that came out of battle with an injury.
+    pub fn injury(unit_name: &str, kind: InjuryKind) -> Self {
+        Self { heading: "toast.injury".into(), title: unit_name.into(), body: kind.label_key().into() }
+    }
+
+    /// The outcome of saving a screenshot or clip to `path`; `kind` is the
+    /// locale key naming what was saved.
+    pub fn capture(kind: &str, path: &str, saved: &io::Result<()>) -> Self {
+        match saved {
+            Ok(()) => Self { heading: "toast.capture".into(), title: kind.into(), body: path.into() },
+            Err(e) => Self { heading: "toast.capture_failed".into(), title: kind.into(), body: e.to_string() },
+        }
+    }
+
+    /// A research project finished; `name` is its locale key.
+    pub fn research(name: &str) -> Self {
+        Self { heading: "toast.research".into(), title: name.into(), body: "toast.research.body".into() }
+    }
+
+    /// An iron-man autosave that couldn't be written.
+    pub fn autosave_failed(error: &io::Error) -> Self {
+        Self { heading: "toast.autosave_failed".into(), title: "toast.ironman".into(), body: error.to_string() }
+    }
+
+    /// A mission script hook that failed.
+    pub fn script_failed(error: &io::Error) -> Self {
+        Self { heading: "toast.script_failed".into(), title: "toast.mission_script".into(), body: error.to_string() }
+    }
+
+    /// Advice on the turn in hand.
+    pub fn hint(hint: Hint) -> Self {
+        Self { heading: "toast.hint".into(), title: hint.key().into(), body: format!("{}.body", hint.key()) }
+    }
+
+    /// The hint this toast gives, if it is one.
+    pub fn as_hint(&self) -> Option<Hint> {
+        (self.heading == "toast.hint").then(|| Hint::from_key(&self.title)).flatten()
+    }
+
+    /// A queued command the encounter refused, and why.
+    pub fn rejected(error: CombatError) -> Self {
+        Self { heading: "toast.rejected".into(), title: error.key().into(), body: String::new() }
+    }
+}
+
+/// Notifications stacked down the right edge under the FPS readout until
+/// each one's time runs out.
+#[derive(Debug, Clone, Default)]
+pub struct Toasts {
+    /// Toasts with their seconds remaining, oldest first.
+    pub active: VecDeque<(Toast, f32)>,
+}
+
+impl Toasts {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn push(&mut self, toast: Toast) {
+        self.active.push_back((toast, TOAST_DURATION));
+        while self.active.len() > MAX_TOASTS {
+            self.active.pop_front();
+        }
+    }
+
+    /// Take the newest toast matching `pred` off the screen early.
+    pub fn dismiss_where(&mut self, pred: impl Fn(&Toast) -> bool) -> Option<Toast> {
+        let index = self.active.iter().rposition(|(toast, _)| pred(toast))?;
+        self.active.remove(index).map(|(toast, _)| toast)
+    }
+
+    /// Count toasts down by `dt` seconds and drop the expired ones.
+    pub fn update(&mut self, dt: f32) {
+        for (_, remaining) in &mut self.active {
+            *remaining -= dt;
+        }
+        self.active.retain(|(_, remaining)| *remaining > 0.0);
+    }
+
+    pub fn render(&self, renderer: &mut Renderer, loc: &Localizer) {
+        let x = renderer.width.saturating_sub(TOAST_WIDTH + 8);
+        let mut y = 32;
+        for (toast, _) in &self.active {
+            renderer.submit(DrawCall::new("panel.toast", (x, y), DrawLayer::Ui));
+            renderer.draw_text(&loc.get(&toast.heading), (x + 8, y + 4), 12.0, WHITE);
+            renderer.draw_text(&loc.get(&toast.title), (x + 8, y + 20), 16.0, WHITE);
+            let body = renderer.draw_paragraph(&loc.get(&toast.body), (x + 8, y + 40), TOAST_WIDTH - 16, 12.0, WHITE, loc.direction());
+            y += 48 + body;
+        }
+    }
+}
@@ -0,0 +1,74 @@
+use crate::frontend::text::WHITE;
+use crate::frontend::{DrawCall, DrawLayer, Renderer};
+use crate::localization::Localizer;
+use crate::models::Ability;
+use crate::registry::UnitRegistry;
+
+const TOOLTIP_WIDTH: u32 = 200;
+/// Drawn this far above the finger so it isn't hidden under it.
+const FINGER_CLEARANCE: u32 = 64;
+
+/// What a tooltip describes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TooltipSubject {
+    /// A unit on the battlefield, by id.
+    Unit(String),
+    /// One of a unit's abilities, shown at its current rank.
+    Ability { unit_id: String, ability_id: String },
+    Item(String),
+}
+
+/// Details shown while a finger is held on a unit or button.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Tooltip {
+    pub subject: TooltipSubject,
+    /// Screen position that was pressed.
+    pub position: (u32, u32),
+}
+
+impl Tooltip {
+    pub fn render(&self, renderer: &mut Renderer, loc: &Localizer, units: &UnitRegistry) {
+        let lines = match &self.subject {
+            TooltipSubject::Unit(id) => {
+                let Some(unit) = units.by_name(id) else { return };
+                let hp = format!("{} {}/{}", loc.get("ui.hp"), unit.health_points, unit.current_stats.max_health);
+                let mut lines = vec![unit.name.clone(), hp];
+                lines.extend(unit.traits.iter().map(|t| loc.get(t.label_key())));
+                lines
+            }
+            TooltipSubject::Ability { unit_id, ability_id } => {
+                match units.by_name(unit_id).and_then(|u| u.abilities.iter().find(|a| a.id == *ability_id)) {
+                    Some(ability) => ability_lines(ability, loc),
+                    None => vec![loc.get(ability_id)],
+                }
+            }
+            TooltipSubject::Item(id) => vec![loc.get(id)],
+        };
+        let x = self.position.0.min(renderer.width.saturating_sub(TOOLTIP_WIDTH));
+        let mut y = self.position.1.saturating_sub(FINGER_CLEARANCE);
+        renderer.submit(DrawCall::new("panel.tooltip", (x, y), DrawLayer::Ui));
+        for line in lines {
+            y += renderer.draw_paragraph(&line, (x + 8, y + 4), TOOLTIP_WIDTH - 16, 14.0, WHITE, loc.direction());
+        }
+    }
+}
+
+/// An ability's name, then its rank if it has more than one, and what it
+/// does at that rank.
+fn ability_lines(ability: &Ability, loc: &Localizer) -> Vec<String> {
+    let mut lines = vec![loc.get(&ability.id)];
+    if ability.max_rank() > 1 {
+        let (rank, max) = (ability.rank.to_string(), ability.max_rank().to_string());
+        lines.push(loc.format("ability.rank", &[("rank", &rank), ("max", &max)]));
+    }
+    if let Some(damage) = ability.effect.damage {
+        lines.push(loc.format("ability.damage", &[("amount", &damage.to_string())]));
+    }
+    if let Some(healing) = ability.effect.healing {
+        lines.push(loc.format("ability.healing", &[("amount", &healing.to_string())]));
+    }
+    if ability.cooldown > 0 {
+        lines.push(loc.plural("ability.cooldown", ability.cooldown as i64, &[]));
+    }
+    lines
+}
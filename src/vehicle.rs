@@ -0,0 +1,350 @@
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap};
+
+use serde::{Deserialize, Serialize};
+
+use crate::combat::{AttackModifiers, AttackResult, CombatEncounter, CombatError};
+use crate::facing::{Facing, FacingArc};
+use crate::grid::{move_ap_cost, pay_for_move, step_cost, FlowField, GridMap, PathSearch, TerrainType};
+use crate::models::{Position, Unit, Weapon};
+use crate::weather::Weather;
+
+/// AP a unit spends getting into or out of a vehicle.
+pub const EMBARK_AP_COST: u32 = 1;
+
+/// Hull armour by the arc an attack comes from, taken off the damage of
+/// each hit before the usual soak.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FacingArmor {
+    pub front: i32,
+    pub side: i32,
+    pub rear: i32,
+}
+
+impl FacingArmor {
+    pub fn against(&self, arc: FacingArc) -> i32 {
+        match arc {
+            FacingArc::Front => self.front,
+            FacingArc::Side => self.side,
+            FacingArc::Rear => self.rear,
+        }
+    }
+}
+
+/// Where a hardpoint's weapon is mounted, which decides where it can fire.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Mount {
+    /// Fires all round.
+    Turret,
+    /// Fires into the vehicle's front arc only.
+    Hull,
+}
+
+/// A weapon built into a vehicle, fired on its own once a turn.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Hardpoint {
+    pub mount: Mount,
+    pub weapon: Weapon,
+    /// Whether it has fired this turn.
+    #[serde(default)]
+    pub fired: bool,
+}
+
+/// What makes a unit a vehicle: armour that depends on where it is hit
+/// from, turns that cost movement, room for infantry and weapons of its
+/// own.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Vehicle {
+    pub armor: FacingArmor,
+    /// Movement points each 45-degree turn costs.
+    pub turn_cost: u32,
+    /// Units it can carry at once.
+    pub capacity: u32,
+    /// Ids of the units aboard.
+    #[serde(default)]
+    pub passengers: Vec<String>,
+    #[serde(default)]
+    pub hardpoints: Vec<Hardpoint>,
+}
+
+impl Vehicle {
+    /// A vehicle turning for one point a step, with nobody aboard and no
+    /// hardpoints.
+    pub fn new(armor: FacingArmor, capacity: u32) -> Self {
+        Self { armor, turn_cost: 1, capacity, passengers: Vec::new(), hardpoints: Vec::new() }
+    }
+
+    pub fn hardpoint(mut self, mount: Mount, weapon: Weapon) -> Self {
+        self.hardpoints.push(Hardpoint { mount, weapon, fired: false });
+        self
+    }
+
+    pub fn is_full(&self) -> bool {
+        self.passengers.len() as u32 >= self.capacity
+    }
+}
+
+impl Unit {
+    /// Hull armour between the unit and `weapon` fired from `from`, less
+    /// the share the weapon's armour piercing cuts through. Zero for
+    /// infantry.
+    pub fn hull_armor(&self, from: &Position, weapon: &Weapon) -> i32 {
+        let Some(vehicle) = &self.vehicle else { return 0 };
+        let armor = vehicle.armor.against(self.facing.arc(&self.grid_position, from));
+        let pierced = (armor as f32 * weapon.armor_piercing.unwrap_or(0.0).clamp(0.0, 1.0)).round() as i32;
+        armor - pierced
+    }
+}
+
+/// A cell, by [`GridMap::index`], and the way a vehicle faces on it.
+type State = (usize, Facing);
+
+/// Cheapest costs of a vehicle's moves, by the state it arrives in, with
+/// the state each was reached from.
+struct VehicleSearch {
+    best: HashMap<State, (u32, Option<State>)>,
+    /// Cells taken off the open list, in order.
+    explored: Vec<Position>,
+    /// The state `until` was first settled in.
+    found: Option<State>,
+}
+
+/// Dijkstra over cell and facing from where `unit` stands, within `limit`
+/// points: a vehicle only drives the way it faces, and each 45-degree turn
+/// before a step costs its turn cost on top. Stops once `until` is
+/// reached.
+fn search(unit: &Unit, turn_cost: u32, map: &GridMap, weather: Weather, limit: u32, until: Option<&Position>) -> VehicleSearch {
+    let start = (map.index(&unit.grid_position), unit.facing);
+    let mut search = VehicleSearch { best: HashMap::from([(start, (0, None))]), explored: Vec::new(), found: None };
+    let mut open = BinaryHeap::from([Reverse((0, start.0, start.1 as usize))]);
+    while let Some(Reverse((cost, index, facing))) = open.pop() {
+        let state = (index, Facing::ALL[facing]);
+        if search.best.get(&state).is_some_and(|&(best, _)| best < cost) {
+            continue;
+        }
+        let pos = Position { x: index % map.width, y: index / map.width };
+        search.explored.push(pos.clone());
+        if until == Some(&pos) {
+            search.found = Some(state);
+            break;
+        }
+        for way in Facing::ALL {
+            let (dx, dy) = way.offset();
+            let (Some(x), Some(y)) = (pos.x.checked_add_signed(dx), pos.y.checked_add_signed(dy)) else { continue };
+            let next = Position { x, y };
+            if !map.in_bounds(&next) {
+                continue;
+            }
+            let Some(step) = step_cost(map, &pos, &next, unit.movement_type, weather) else { continue };
+            let next_cost = cost + turn_cost * state.1.turns_to(way) + step;
+            let next_state = (map.index(&next), way);
+            if next_cost <= limit && search.best.get(&next_state).is_none_or(|&(best, _)| next_cost < best) {
+                search.best.insert(next_state, (next_cost, Some(state)));
+                open.push(Reverse((next_cost, next_state.0, way as usize)));
+            }
+        }
+    }
+    search
+}
+
+/// [`crate::grid::find_path`] for a vehicle, counting its turns.
+pub(crate) fn find_path(unit: &Unit, turn_cost: u32, dest: &Position, map: &GridMap, limit: u32, weather: Weather) -> PathSearch {
+    let search = search(unit, turn_cost, map, weather, limit, Some(dest));
+    let mut result = PathSearch { explored: search.explored, ..PathSearch::default() };
+    let Some(found) = search.found else { return result };
+    result.cost = search.best.get(&found).map(|&(cost, _)| cost);
+    let mut state = Some(found);
+    while let Some(current) = state {
+        result.path.push(Position { x: current.0 % map.width, y: current.0 / map.width });
+        state = search.best.get(&current).and_then(|&(_, previous)| previous);
+    }
+    result.path.reverse();
+    result
+}
+
+/// The furthest cell along `field` from the vehicle that it can drive to
+/// within `budget` points, counting its turns, as a [`PathSearch`] with
+/// an empty path when it can't get anywhere.
+pub(crate) fn advance(unit: &Unit, turn_cost: u32, field: &FlowField, map: &GridMap, budget: u32, weather: Weather) -> PathSearch {
+    for points in (1..=budget).rev() {
+        let to = field.advance(map, &unit.grid_position, points);
+        if to == unit.grid_position {
+            break;
+        }
+        let search = find_path(unit, turn_cost, &to, map, budget, weather);
+        if search.cost.is_some() {
+            return search;
+        }
+    }
+    PathSearch::default()
+}
+
+/// Cheapest cost for a vehicle to reach each cell of `map`, whichever
+/// way it ends up facing, indexed as [`GridMap::index`].
+pub(crate) fn cell_costs(unit: &Unit, turn_cost: u32, map: &GridMap, limit: u32, weather: Weather) -> Vec<Option<u32>> {
+    let mut costs = vec![None; map.width * map.height];
+    for ((index, _), (cost, _)) in search(unit, turn_cost, map, weather, limit, None).best {
+        if costs[index].is_none_or(|c| cost < c) {
+            costs[index] = Some(cost);
+        }
+    }
+    costs
+}
+
+/// Cells apart, counting diagonals as one.
+fn reach(a: &Position, b: &Position) -> usize {
+    a.x.abs_diff(b.x).max(a.y.abs_diff(b.y))
+}
+
+impl CombatEncounter {
+    /// Put `unit_id` aboard the friendly vehicle `vehicle_id` on or next to
+    /// its cell, for [`EMBARK_AP_COST`]. It rides along out of reach until
+    /// it gets out again.
+    pub fn embark(&mut self, unit_id: &str, vehicle_id: &str) -> Result<(), CombatError> {
+        let (id, carrier_id) = self.units.id(unit_id).zip(self.units.id(vehicle_id)).ok_or(CombatError::UnknownUnit)?;
+        let (unit, carrier) = (&self.units[id], &self.units[carrier_id]);
+        if unit.health_points <= 0 {
+            return Err(CombatError::UnknownUnit);
+        }
+        let vehicle = carrier.vehicle.as_ref().ok_or(CombatError::NotAVehicle)?;
+        if unit.vehicle.is_some() || unit.embarked_in.is_some() {
+            return Err(CombatError::CantBoard);
+        }
+        if self.units.side(id) != self.units.side(carrier_id) || carrier.health_points <= 0 {
+            return Err(CombatError::InvalidTarget);
+        }
+        if reach(&unit.grid_position, &carrier.grid_position) > 1 {
+            return Err(CombatError::OutOfRange);
+        }
+        if vehicle.is_full() {
+            return Err(CombatError::VehicleFull);
+        }
+        if unit.action_points < EMBARK_AP_COST {
+            return Err(CombatError::InsufficientAp);
+        }
+        let position = carrier.grid_position.clone();
+        let (unit, carrier) = self.units.pair_mut(id, carrier_id).ok_or(CombatError::UnknownUnit)?;
+        unit.action_points -= EMBARK_AP_COST;
+        unit.grid_position = position;
+        unit.embarked_in = Some(carrier.id.clone());
+        carrier.vehicle.as_mut().ok_or(CombatError::UnknownUnit)?.passengers.push(unit.id.clone());
+        Ok(())
+    }
+
+    /// Set `unit_id` down from the vehicle it rides in onto `destination`,
+    /// a free cell next to the vehicle's, for [`EMBARK_AP_COST`]. The
+    /// vehicle's own cell is taken while it stands; a wreck turns its
+    /// passengers out there instead, see [`CombatEncounter::release_passengers`].
+    pub fn disembark(&mut self, unit_id: &str, destination: &Position) -> Result<(), CombatError> {
+        let id = self.units.id(unit_id).ok_or(CombatError::UnknownUnit)?;
+        let unit = &self.units[id];
+        let carrier_id = unit.embarked_in.as_deref().and_then(|v| self.units.id(v)).ok_or(CombatError::NotAboard)?;
+        let from = &self.units[carrier_id].grid_position;
+        if !self.battlefield.in_bounds(destination) || matches!(self.battlefield.terrain_at(destination), TerrainType::Blocked) {
+            return Err(CombatError::InvalidTarget);
+        }
+        if self.units.iter().any(|u| u.health_points > 0 && u.embarked_in.is_none() && u.grid_position == *destination) {
+            return Err(CombatError::InvalidTarget);
+        }
+        if reach(from, destination) > 1 {
+            return Err(CombatError::OutOfRange);
+        }
+        if unit.action_points < EMBARK_AP_COST {
+            return Err(CombatError::InsufficientAp);
+        }
+        let (unit, carrier) = self.units.pair_mut(id, carrier_id).ok_or(CombatError::UnknownUnit)?;
+        unit.action_points -= EMBARK_AP_COST;
+        unit.grid_position = destination.clone();
+        unit.embarked_in = None;
+        if let Some(vehicle) = &mut carrier.vehicle {
+            vehicle.passengers.retain(|p| *p != unit.id);
+        }
+        Ok(())
+    }
+
+    /// Turn `unit_id` to face `facing`. A vehicle pays its turn cost per
+    /// 45 degrees out of its movement, as for a move; anyone else turns
+    /// for free.
+    pub fn turn_to(&mut self, unit_id: &str, facing: Facing) -> Result<(), CombatError> {
+        let unit = self.unit_by_id(unit_id).ok_or(CombatError::UnknownUnit)?;
+        let bonus = self.movement_bonus(unit);
+        let unit = self.units.by_name_mut(unit_id).ok_or(CombatError::UnknownUnit)?;
+        let cost = unit.vehicle.as_ref().map_or(0, |v| v.turn_cost * unit.facing.turns_to(facing));
+        move_ap_cost(unit, &self.battlefield, bonus, cost).ok_or(CombatError::InsufficientAp)?;
+        pay_for_move(unit, &self.battlefield, bonus, cost);
+        unit.facing = facing;
+        Ok(())
+    }
+
+    /// Fire the vehicle's hardpoint at `target_id`. Each hardpoint fires
+    /// once a turn, for its weapon's AP; a hull mount only at targets in
+    /// the front arc.
+    pub fn fire_hardpoint(&mut self, vehicle_id: &str, hardpoint: usize, target_id: &str, roll: u8) -> Result<AttackResult, CombatError> {
+        let weapon = self.check_hardpoint(vehicle_id, hardpoint, target_id)?;
+        let result = self.attack_with(vehicle_id, target_id, &weapon, roll, AttackModifiers::default())?;
+        if let Some(vehicle) = self.unit_by_id_mut(vehicle_id).and_then(|u| u.vehicle.as_mut()) {
+            vehicle.hardpoints[hardpoint].fired = true;
+        }
+        Ok(result)
+    }
+
+    /// The weapon on the vehicle's hardpoint, if it can fire at
+    /// `target_id` now.
+    pub(crate) fn check_hardpoint(&self, vehicle_id: &str, hardpoint: usize, target_id: &str) -> Result<Weapon, CombatError> {
+        let unit = self.unit_by_id(vehicle_id).ok_or(CombatError::UnknownUnit)?;
+        let vehicle = unit.vehicle.as_ref().ok_or(CombatError::NotAVehicle)?;
+        let mounted = vehicle.hardpoints.get(hardpoint).ok_or(CombatError::NoSuchHardpoint)?;
+        if mounted.fired {
+            return Err(CombatError::HardpointFired);
+        }
+        let target = self.unit_by_id(target_id).ok_or(CombatError::InvalidTarget)?;
+        if mounted.mount == Mount::Hull && unit.facing.arc(&unit.grid_position, &target.grid_position) != FacingArc::Front {
+            return Err(CombatError::OutsideArc);
+        }
+        self.check_attack(vehicle_id, target_id, &mounted.weapon)?;
+        Ok(mounted.weapon.clone())
+    }
+
+    /// Fire each of the AI vehicle's hardpoints that bears on `target_id`,
+    /// each rolling its own dice. Returns whether any fired.
+    pub(crate) fn ai_fire_hardpoints(&mut self, vehicle_id: &str, target_id: &str) -> bool {
+        let count = self.unit_by_id(vehicle_id).and_then(|u| u.vehicle.as_ref()).map_or(0, |v| v.hardpoints.len());
+        let mut fired = false;
+        for hardpoint in 0..count {
+            if self.check_hardpoint(vehicle_id, hardpoint, target_id).is_err() {
+                continue;
+            }
+            let roll = self.rng.roll_d100();
+            fired |= self.fire_hardpoint(vehicle_id, hardpoint, target_id, roll).is_ok();
+        }
+        fired
+    }
+
+    /// Keep the units aboard `vehicle_id` on its cell after it moves.
+    pub(crate) fn carry_passengers(&mut self, vehicle_id: &str) {
+        let Some(carrier) = self.unit_by_id(vehicle_id) else { return };
+        let Some(vehicle) = &carrier.vehicle else { return };
+        let (position, passengers) = (carrier.grid_position.clone(), vehicle.passengers.clone());
+        for id in passengers {
+            if let Some(unit) = self.unit_by_id_mut(&id) {
+                unit.grid_position = position.clone();
+            }
+        }
+    }
+
+    /// Turn out the passengers of destroyed vehicles onto the wreck's cell.
+    pub(crate) fn release_passengers(&mut self) {
+        let freed: Vec<String> = self
+            .units
+            .iter_mut()
+            .filter(|u| u.health_points <= 0)
+            .filter_map(|u| u.vehicle.as_mut())
+            .flat_map(|v| std::mem::take(&mut v.passengers))
+            .collect();
+        for id in freed {
+            if let Some(unit) = self.unit_by_id_mut(&id) {
+                unit.embarked_in = None;
+            }
+        }
+    }
+}
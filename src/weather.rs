@@ -0,0 +1,67 @@
+use serde::{Deserialize, Serialize};
+
+use crate::combat::SIGHT_RANGE;
+use crate::frontend::text::Color;
+use crate::grid::TerrainType;
+
+/// Battlefield conditions declared by a scenario.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Weather {
+    #[default]
+    Clear,
+    /// Slower going through difficult ground; reduced sight and aim.
+    Rain,
+    /// Every step costs more; heavily reduced sight and aim.
+    DustStorm,
+    /// Short sight and reduced aim.
+    Night,
+}
+
+impl Weather {
+    /// Added to hit chance for ranged attacks.
+    pub fn ranged_accuracy(&self) -> i32 {
+        match self {
+            Weather::Clear => 0,
+            Weather::Rain => -10,
+            Weather::DustStorm => -20,
+            Weather::Night => -15,
+        }
+    }
+
+    pub fn sight_range(&self) -> u32 {
+        match self {
+            Weather::Clear => SIGHT_RANGE,
+            Weather::Rain => SIGHT_RANGE - 1,
+            Weather::DustStorm | Weather::Night => SIGHT_RANGE / 2,
+        }
+    }
+
+    /// Extra movement cost for stepping onto `terrain`.
+    pub fn step_cost(&self, terrain: &TerrainType) -> u32 {
+        match (self, terrain) {
+            (Weather::Rain, TerrainType::Difficult) => 1,
+            (Weather::DustStorm, _) => 1,
+            _ => 0,
+        }
+    }
+
+    /// Music track played for the battle.
+    pub fn music_key(&self) -> &'static str {
+        match self {
+            Weather::Clear => "combat",
+            Weather::Rain => "combat_rain",
+            Weather::DustStorm => "combat_dust",
+            Weather::Night => "combat_night",
+        }
+    }
+
+    /// Colour multiplied into the battlefield, if any.
+    pub fn tint(&self) -> Option<Color> {
+        match self {
+            Weather::Clear => None,
+            Weather::Rain => Some([0.8, 0.85, 0.95, 1.0]),
+            Weather::DustStorm => Some([1.0, 0.85, 0.6, 1.0]),
+            Weather::Night => Some([0.4, 0.45, 0.7, 1.0]),
+        }
+    }
+}
@@ -1,5 +1,6 @@
+use gero::damage::DamageType;
 use gero::models::{Unit, UnitType, Faction, Ability, AbilityType, AbilityEffect, AreaOfEffect, AnimationType};
-use gero::combat::{use_ability, tick_cooldowns};
+use gero::combat::{tick_cooldowns, use_ability, CombatError};
 
 #[test]
 fn single_target_ability() {
@@ -24,9 +25,16 @@ fn single_target_ability() {
             debuff: None,
             status_applied: None,
             duration: None,
+            creates_hazard: None,
+            alters_terrain: None,
+            forced_move: None,
+            damage_type: DamageType::Kinetic,
         },
         animation: AnimationType::AbilityCast,
         sound_effect_key: String::new(),
+        rank: 1,
+        ranks: Vec::new(),
+        channel: None,
     });
 
     let res = use_ability(&mut user, 0, &mut [&mut target], None);
@@ -63,9 +71,16 @@ fn aoe_hits_multiple_targets() {
             debuff: None,
             status_applied: None,
             duration: None,
+            creates_hazard: None,
+            alters_terrain: None,
+            forced_move: None,
+            damage_type: DamageType::Kinetic,
         },
         animation: AnimationType::AbilityCast,
         sound_effect_key: String::new(),
+        rank: 1,
+        ranks: Vec::new(),
+        channel: None,
     });
 
     let res = use_ability(&mut user, 0, &mut [&mut t1, &mut t2], None);
@@ -80,7 +95,7 @@ fn invalid_ability_index_returns_err() {
     let mut target = Unit::new("t", "Target", UnitType::OrkBoy, Faction::Ork);
     let start_hp = target.health_points;
     let res = use_ability(&mut user, 1, &mut [&mut target], None);
-    assert_eq!(res, Err("invalid ability"));
+    assert_eq!(res, Err(CombatError::InvalidAbility));
     assert_eq!(target.health_points, start_hp);
     assert_eq!(user.action_points, user.current_stats.max_action);
 }
@@ -107,14 +122,21 @@ fn use_ability_fails_without_ap() {
             debuff: None,
             status_applied: None,
             duration: None,
+            creates_hazard: None,
+            alters_terrain: None,
+            forced_move: None,
+            damage_type: DamageType::Kinetic,
         },
         animation: AnimationType::AbilityCast,
         sound_effect_key: String::new(),
+        rank: 1,
+        ranks: Vec::new(),
+        channel: None,
     });
     let start_hp = target.health_points;
     let start_cd = user.abilities[0].current_cooldown;
     let res = use_ability(&mut user, 0, &mut [&mut target], None);
-    assert_eq!(res, Err("not enough AP"));
+    assert_eq!(res, Err(CombatError::InsufficientAp));
     assert_eq!(user.action_points, 0);
     assert_eq!(user.abilities[0].current_cooldown, start_cd);
     assert_eq!(target.health_points, start_hp);
@@ -142,15 +164,22 @@ fn ability_cannot_be_used_when_on_cooldown() {
             debuff: None,
             status_applied: None,
             duration: None,
+            creates_hazard: None,
+            alters_terrain: None,
+            forced_move: None,
+            damage_type: DamageType::Kinetic,
         },
         animation: AnimationType::AbilityCast,
         sound_effect_key: String::new(),
+        rank: 1,
+        ranks: Vec::new(),
+        channel: None,
     });
     let start_hp = target.health_points;
     let start_cd = user.abilities[0].current_cooldown;
     let start_ap = user.action_points;
     let res = use_ability(&mut user, 0, &mut [&mut target], None);
-    assert_eq!(res, Err("ability on cooldown"));
+    assert_eq!(res, Err(CombatError::OnCooldown));
     assert_eq!(user.action_points, start_ap);
     assert_eq!(user.abilities[0].current_cooldown, start_cd);
     assert_eq!(target.health_points, start_hp);
@@ -1,3 +1,4 @@
+use gero::damage::DamageType;
 use gero::models::{Unit, UnitType, Faction, Ability, AbilityType, AbilityEffect, AnimationType, StatsModifier, EffectType};
 use gero::combat::use_ability;
 
@@ -19,9 +20,16 @@ fn make_heal_buff_ability() -> Ability {
             debuff: None,
             status_applied: None,
             duration: None,
+            creates_hazard: None,
+            alters_terrain: None,
+            forced_move: None,
+            damage_type: DamageType::Kinetic,
         },
         animation: AnimationType::AbilityCast,
         sound_effect_key: String::new(),
+        rank: 1,
+        ranks: Vec::new(),
+        channel: None,
     }
 }
 
@@ -43,9 +51,16 @@ fn make_status_ability() -> Ability {
             debuff: None,
             status_applied: Some(EffectType::Poison),
             duration: Some(2),
+            creates_hazard: None,
+            alters_terrain: None,
+            forced_move: None,
+            damage_type: DamageType::Kinetic,
         },
         animation: AnimationType::AbilityCast,
         sound_effect_key: String::new(),
+        rank: 1,
+        ranks: Vec::new(),
+        channel: None,
     }
 }
 
@@ -56,7 +71,7 @@ fn heal_and_buff_increases_stats() {
     target.health_points = 5;
     user.action_points = 2;
     user.abilities.push(make_heal_buff_ability());
-    let _ = use_ability(&mut user, 0, &mut [&mut target], None).unwrap();
+    use_ability(&mut user, 0, &mut [&mut target], None).unwrap();
     assert_eq!(target.health_points, 8);
     assert_eq!(target.current_stats.strength, target.base_stats.strength + 1);
 }
@@ -68,7 +83,7 @@ fn applying_status_effect_adds_to_unit() {
     user.action_points = 2;
     user.abilities.push(make_status_ability());
     assert!(target.status_effects.is_empty());
-    let _ = use_ability(&mut user, 0, &mut [&mut target], None).unwrap();
+    use_ability(&mut user, 0, &mut [&mut target], None).unwrap();
     assert_eq!(target.status_effects.len(), 1);
     let se = &target.status_effects[0];
     assert!(matches!(se.effect_type, EffectType::Poison));
@@ -0,0 +1,78 @@
+use std::sync::{Arc, Mutex};
+
+use gero::accessibility::{Announcer, Priority};
+use gero::combat::CombatEvent;
+use gero::input::GameAction;
+use gero::localization::Localizer;
+use gero::models::{Faction, Position, Unit, UnitType};
+use gero::registry::{Side, UnitRegistry};
+use gero::ui::UiManager;
+
+fn squad() -> UnitRegistry {
+    let mut registry = UnitRegistry::new();
+    registry.insert(Unit::new("imp", "Trooper Kell", UnitType::Guardsman, Faction::Imperial), Side::Player);
+    registry.insert(Unit::new("ork", "Grubnak", UnitType::OrkBoy, Faction::Ork), Side::Enemy);
+    registry
+}
+
+#[test]
+fn focus_and_selection_changes_are_announced_once() {
+    let loc = Localizer::new("en").unwrap();
+    let mut units = squad();
+    let mut ui = UiManager::new(800, 600, vec!["frag".into(), "heal".into()], vec![]);
+    let mut announcer = Announcer::new();
+    let heard = Arc::new(Mutex::new(Vec::new()));
+    let sink = heard.clone();
+    announcer.on_announce(move |a| sink.lock().unwrap().push(a.text.clone()));
+
+    announcer.observe(&ui, &units, &loc);
+    ui.handle_input(GameAction::SelectDown);
+    announcer.observe(&ui, &units, &loc);
+    announcer.observe(&ui, &units, &loc);
+    units.by_name_mut("imp").unwrap().is_selected = true;
+    announcer.observe(&ui, &units, &loc);
+
+    assert_eq!(
+        *heard.lock().unwrap(),
+        vec![
+            "Abilities: frag, 1 of 2",
+            "Abilities: heal, 2 of 2",
+            "Selected Trooper Kell, HP 10 of 10",
+        ]
+    );
+    assert_eq!(announcer.history.len(), 3);
+}
+
+#[test]
+fn combat_events_describe_attack_results_and_turns() {
+    let loc = Localizer::new("en").unwrap();
+    let mut units = squad();
+    units.by_name_mut("ork").unwrap().health_points = 0;
+    let mut announcer = Announcer::new();
+
+    announcer.announce_turn(units.by_name("imp").unwrap(), &loc);
+    announcer.announce_combat_event(&CombatEvent::UnitDamaged { unit_id: "ork".into(), amount: 12, critical: true }, &units, &loc);
+    announcer.announce_combat_event(&CombatEvent::UnitHealed { unit_id: "imp".into(), amount: 5 }, &units, &loc);
+    announcer.announce_combat_event(&CombatEvent::TurnHandedOff { from: 0, to: 1 }, &units, &loc);
+    let blast = CombatEvent::Explosion { center: Position { x: 0, y: 0 }, cells: vec![Position { x: 0, y: 0 }] };
+    announcer.announce_combat_event(&blast, &units, &loc);
+
+    let texts: Vec<_> = announcer.history.iter().map(|a| a.text.as_str()).collect();
+    assert_eq!(
+        texts,
+        vec![
+            "Trooper Kell's turn",
+            "Critical hit! Grubnak takes 12 damage",
+            "Grubnak is down",
+            "Trooper Kell recovers 5 health",
+            "Player 2's turn",
+            "Explosion, 2 units caught",
+        ]
+    );
+    assert_eq!(announcer.history[2].priority, Priority::Assertive);
+    assert_eq!(announcer.history[3].priority, Priority::Polite);
+
+    announcer.enabled = false;
+    announcer.announce("ignored", Priority::Polite);
+    assert_eq!(announcer.history.len(), 6);
+}
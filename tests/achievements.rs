@@ -0,0 +1,99 @@
+use gero::achievements::{load_achievements, AchievementProfile, AchievementTracker, Condition, ACHIEVEMENTS_PATH};
+use gero::combat::{CombatEncounter, CombatEvent};
+use gero::flow::GameFlow;
+use gero::frontend::Renderer;
+use gero::grid::GridMap;
+use gero::localization::Localizer;
+use gero::models::{Faction, Position, Unit, UnitType};
+use gero::simulation::BattleOutcome;
+use gero::state::GameState;
+use gero::ui::UiManager;
+
+fn battle() -> CombatEncounter {
+    let guard = Unit::new("g", "Trooper", UnitType::Guardsman, Faction::Imperial);
+    let mut daemon = Unit::new("d", "Bloodletter", UnitType::Daemon, Faction::Chaos);
+    daemon.grid_position = Position { x: 1, y: 0 };
+    let mut enc = CombatEncounter::new(vec![guard], vec![daemon], GridMap::new(4, 4), None);
+    enc.start_turn();
+    enc
+}
+
+/// Knock the daemon out on the guardsman's turn, as an attack would.
+fn slay_daemon(enc: &mut CombatEncounter) {
+    let daemon = enc.unit_by_id_mut("d").unwrap();
+    let amount = daemon.health_points;
+    daemon.health_points = 0;
+    enc.events.push(CombatEvent::UnitDamaged { unit_id: "d".into(), amount, critical: false });
+}
+
+#[test]
+fn shipped_achievements_load_and_are_translated() {
+    let achievements = load_achievements(ACHIEVEMENTS_PATH).unwrap();
+    let ids: Vec<&str> = achievements.iter().map(|a| a.id.as_str()).collect();
+    assert_eq!(ids, ["flawless", "daemon_slayer", "blitz"]);
+    assert_eq!(achievements[2].condition, Condition::WinWithinRounds(5));
+    let loc = Localizer::new("en").unwrap();
+    for a in &achievements {
+        assert_ne!(loc.get(&a.name), a.name);
+        assert_ne!(loc.get(&a.description), a.description);
+    }
+}
+
+#[test]
+fn killing_blows_unlock_once_and_persist() {
+    let path = std::env::temp_dir().join(format!("gero_profile_{}.json", std::process::id()));
+    let _ = std::fs::remove_file(&path);
+    let mut tracker = AchievementTracker::load(ACHIEVEMENTS_PATH, &path).unwrap();
+    let mut enc = battle();
+    slay_daemon(&mut enc);
+    let events = enc.drain_events();
+
+    let unlocked = tracker.observe(&events, &enc);
+    assert_eq!(unlocked.iter().map(|a| a.id.as_str()).collect::<Vec<_>>(), ["daemon_slayer"]);
+    assert!(tracker.observe(&events, &enc).is_empty());
+
+    let saved = AchievementProfile::load(&path).unwrap();
+    assert!(saved.unlocked.contains("daemon_slayer"));
+    assert!(AchievementTracker::load(ACHIEVEMENTS_PATH, &path).unwrap().is_unlocked("daemon_slayer"));
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn end_of_battle_achievements_need_a_quick_clean_win() {
+    let list = load_achievements(ACHIEVEMENTS_PATH).unwrap();
+    let mut enc = battle();
+    let mut tracker = AchievementTracker::new(list, AchievementProfile::default());
+    assert!(tracker.finish_battle(&enc, BattleOutcome::EnemyVictory).is_empty());
+
+    enc.stats.rounds = 6;
+    enc.unit_by_id_mut("g").unwrap().health_points = 0;
+    let unlocked = tracker.finish_battle(&enc, BattleOutcome::PlayerVictory);
+    assert!(unlocked.is_empty());
+
+    enc.stats.rounds = 5;
+    enc.unit_by_id_mut("g").unwrap().health_points = 1;
+    let unlocked: Vec<String> = tracker.finish_battle(&enc, BattleOutcome::PlayerVictory).into_iter().map(|a| a.id).collect();
+    assert_eq!(unlocked, ["flawless", "blitz"]);
+}
+
+#[test]
+fn unlocks_show_a_toast() {
+    let list = load_achievements(ACHIEVEMENTS_PATH).unwrap();
+    let mut flow = GameFlow::new(GameState::new(Vec::new()), UiManager::new(1280, 720, Vec::new(), Vec::new()));
+    flow.achievements = AchievementTracker::new(list, AchievementProfile::default());
+    let mut enc = battle();
+    slay_daemon(&mut enc);
+    let events = enc.drain_events();
+    flow.observe_combat(&events, &enc);
+    assert_eq!(flow.ui.toasts.active.len(), 1);
+
+    let loc = Localizer::new("en").unwrap();
+    let mut renderer = Renderer::new_headless(1280, 720);
+    flow.render(&mut renderer, &loc);
+    let texts: Vec<&str> = renderer.text_log.iter().map(|t| t.text.as_str()).collect();
+    assert!(texts.contains(&"Achievement unlocked"));
+    assert!(texts.contains(&"Faith Is My Shield"));
+
+    flow.update(5.0);
+    assert!(flow.ui.toasts.active.is_empty());
+}
@@ -0,0 +1,193 @@
+use gero::actions::{
+    PresentationStep, ACTION_ANIMATION_SECONDS, CINEMATIC_SECONDS, FLOATING_TEXT_SECONDS, SLOW_MOTION_SCALE, WALK_STEP_SECONDS,
+};
+use gero::combat::{CombatEncounter, CombatError};
+use gero::command::Command;
+use gero::damage::DamageType;
+use gero::flow::GameFlow;
+use gero::frontend::Renderer;
+use gero::grid::{find_path, GridMap};
+use gero::localization::Localizer;
+use gero::models::{Ability, AbilityEffect, AbilityType, AnimationType, Faction, Position, Unit, UnitType};
+use gero::state::GameState;
+use gero::ui::UiManager;
+use gero::weather::Weather;
+
+fn smite() -> Ability {
+    Ability {
+        id: "smite".into(),
+        name: "Smite".into(),
+        ability_type: AbilityType::PsychicBlast,
+        description: String::new(),
+        action_point_cost: 1,
+        cooldown: 0,
+        current_cooldown: 0,
+        range: 8,
+        area_of_effect: None,
+        effect: AbilityEffect {
+            damage: Some(3),
+            healing: None,
+            buff: None,
+            debuff: None,
+            status_applied: None,
+            duration: None,
+            creates_hazard: None,
+            alters_terrain: None,
+            forced_move: None,
+            damage_type: DamageType::Kinetic,
+        },
+        animation: AnimationType::AbilityCast,
+        sound_effect_key: String::new(),
+        rank: 1,
+        ranks: Vec::new(),
+        channel: None,
+    }
+}
+
+fn encounter() -> CombatEncounter {
+    let mut player = Unit::new("p", "P", UnitType::Guardsman, Faction::Imperial);
+    player.base_stats.agility = 6;
+    player.recalculate_stats();
+    player.abilities.push(smite());
+    let mut enemy = Unit::new("e", "E", UnitType::OrkBoy, Faction::Ork);
+    enemy.grid_position = Position { x: 5, y: 0 };
+    CombatEncounter::new(vec![player], vec![enemy], GridMap::new(8, 8), None)
+}
+
+fn walk_to(x: usize, y: usize) -> Command {
+    Command::Move { unit_id: "p".into(), destination: Position { x, y } }
+}
+
+fn smite_enemy() -> Command {
+    Command::UseAbility { user_id: "p".into(), ability_index: 0, target_ids: vec!["e".into()] }
+}
+
+#[test]
+fn find_path_returns_the_cells_walked() {
+    let enc = encounter();
+    let search = find_path(enc.unit_by_id("p").unwrap(), &Position { x: 3, y: 0 }, &enc.battlefield, 0, Weather::default());
+    let path: Vec<(usize, usize)> = search.path.iter().map(|p| (p.x, p.y)).collect();
+    assert_eq!(path, [(0, 0), (1, 0), (2, 0), (3, 0)]);
+    let blocked = find_path(enc.unit_by_id("p").unwrap(), &Position { x: 7, y: 7 }, &enc.battlefield, 0, Weather::default());
+    assert!(blocked.path.is_empty());
+}
+
+#[test]
+fn each_action_waits_for_the_last_to_be_shown() {
+    let mut enc = encounter();
+    enc.queue_action(walk_to(2, 0));
+    enc.queue_action(smite_enemy());
+    assert_eq!(enc.actions.len(), 2);
+
+    // The move is applied at once; its walk plays out before the smite.
+    let started = enc.update_actions(0.0);
+    assert_eq!(enc.unit_by_id("p").unwrap().grid_position, Position { x: 2, y: 0 });
+    assert!(matches!(&started[..], [PresentationStep::Walk { path, .. }] if path.len() == 3));
+    assert_eq!(enc.unit_by_id("p").unwrap().animation_state.current_animation, AnimationType::Move);
+    let (step, progress) = enc.actions.current().unwrap();
+    assert_eq!((step.walk_position(progress), progress), (Some(&Position { x: 0, y: 0 }), 0.0));
+
+    assert!(enc.update_actions(WALK_STEP_SECONDS).is_empty());
+    let (step, progress) = enc.actions.current().unwrap();
+    assert_eq!(step.walk_position(progress), Some(&Position { x: 1, y: 0 }));
+    assert_eq!(enc.unit_by_id("e").unwrap().health_points, 10);
+
+    let started = enc.update_actions(WALK_STEP_SECONDS);
+    assert_eq!(enc.unit_by_id("e").unwrap().health_points, 7);
+    assert_eq!(started, [PresentationStep::Animate { unit_id: "p".into(), animation: AnimationType::AbilityCast }]);
+
+    let started = enc.update_actions(ACTION_ANIMATION_SECONDS);
+    assert_eq!(started, [PresentationStep::FloatingText { changes: vec![("e".into(), -3)] }]);
+    assert_eq!(enc.unit_by_id("p").unwrap().animation_state.current_animation, AnimationType::Idle);
+    assert!(enc.actions.is_busy());
+    enc.update_actions(FLOATING_TEXT_SECONDS);
+    assert!(!enc.actions.is_busy());
+}
+
+#[test]
+fn refused_actions_are_reported_and_skipped() {
+    let mut enc = encounter();
+    enc.queue_action(walk_to(7, 7));
+    enc.queue_action(walk_to(1, 0));
+    let started = enc.update_actions(0.0);
+    assert_eq!(enc.actions.rejected, [(walk_to(7, 7), CombatError::Unreachable)]);
+    assert!(matches!(&started[..], [PresentationStep::Walk { .. }]));
+
+    // A long frame plays through everything queued.
+    enc.queue_action(smite_enemy());
+    enc.update_actions(10.0);
+    assert!(!enc.actions.is_busy());
+    assert_eq!(enc.unit_by_id("e").unwrap().health_points, 7);
+}
+
+#[test]
+fn the_flow_pops_up_health_changes_while_they_are_shown() {
+    let mut enc = encounter();
+    let mut flow = GameFlow::new(GameState::from_encounter(&enc), UiManager::new(1280, 720, Vec::new(), Vec::new()));
+    let mut renderer = Renderer::new_headless(1280, 720);
+    enc.queue_action(smite_enemy());
+    flow.present_actions(0.0, &mut enc, &mut renderer);
+    assert!(flow.ui.floating_texts.is_empty());
+
+    flow.present_actions(ACTION_ANIMATION_SECONDS, &mut enc, &mut renderer);
+    let text = &flow.ui.floating_texts[0];
+    assert_eq!((text.value, text.position, text.is_heal), (-3, (5 * 64, 0), false));
+
+    flow.present_actions(FLOATING_TEXT_SECONDS, &mut enc, &mut renderer);
+    assert!(flow.ui.floating_texts.is_empty());
+}
+
+fn doomed_enemy() -> CombatEncounter {
+    let mut enc = encounter();
+    enc.unit_by_id_mut("e").unwrap().health_points = 3;
+    enc
+}
+
+#[test]
+fn cinematic_kills_close_in_and_play_in_slow_motion() {
+    let mut enc = doomed_enemy();
+    enc.actions.cinematic = true;
+    enc.queue_action(smite_enemy());
+    let started = enc.update_actions(0.0);
+    assert_eq!(started, [PresentationStep::Cinematic { target: Position { x: 5, y: 0 } }]);
+    assert!(enc.actions.in_slow_motion());
+
+    // Each step takes longer than usual.
+    assert!(enc.update_actions(CINEMATIC_SECONDS).is_empty());
+    let started = enc.update_actions(CINEMATIC_SECONDS / SLOW_MOTION_SCALE - CINEMATIC_SECONDS);
+    assert!(matches!(&started[..], [PresentationStep::Animate { .. }]));
+    assert!(enc.update_actions(ACTION_ANIMATION_SECONDS).is_empty());
+    enc.update_actions(10.0);
+    assert!(!enc.actions.is_busy() && !enc.actions.in_slow_motion());
+
+    // Without a kill or critical, or with the option off, nothing changes.
+    let mut enc = encounter();
+    enc.actions.cinematic = true;
+    enc.queue_action(smite_enemy());
+    assert!(matches!(&enc.update_actions(0.0)[..], [PresentationStep::Animate { .. }]));
+    let mut enc = doomed_enemy();
+    enc.queue_action(smite_enemy());
+    assert!(matches!(&enc.update_actions(0.0)[..], [PresentationStep::Animate { .. }]));
+}
+
+#[test]
+fn the_flow_letterboxes_and_zooms_during_a_cinematic() {
+    let mut enc = doomed_enemy();
+    let mut flow = GameFlow::new(GameState::from_encounter(&enc), UiManager::new(1280, 720, Vec::new(), Vec::new()));
+    flow.cinematic_camera = true;
+    let mut renderer = Renderer::new_headless(1280, 720);
+    let loc = Localizer::new("en").unwrap();
+    enc.queue_action(smite_enemy());
+    flow.present_actions(0.0, &mut enc, &mut renderer);
+    flow.present_actions(CINEMATIC_SECONDS / SLOW_MOTION_SCALE, &mut enc, &mut renderer);
+    assert_eq!(renderer.camera.zoom_level, 1.5);
+    flow.render(&mut renderer, &loc);
+    let bars: Vec<_> = renderer.rect_log.iter().filter(|r| r.size == (1280, 86)).map(|r| r.position).collect();
+    assert_eq!(bars, [(0, 0), (0, 634)]);
+
+    flow.present_actions(10.0, &mut enc, &mut renderer);
+    assert_eq!((renderer.camera.zoom_level, renderer.camera.x_offset), (1.0, 0.0));
+    renderer.clear_frame();
+    flow.render(&mut renderer, &loc);
+    assert!(!renderer.rect_log.iter().any(|r| r.size == (1280, 86)));
+}
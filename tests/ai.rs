@@ -1,4 +1,5 @@
 use gero::combat::{CombatEncounter};
+use gero::damage::DamageType;
 use gero::models::{Unit, UnitType, Faction, Weapon, WeaponTier, Ability, AbilityType, AbilityEffect, AnimationType, Position};
 use gero::grid::GridMap;
 
@@ -14,6 +15,9 @@ fn basic_weapon(range: u32) -> Weapon {
         action_point_cost: 1,
         critical_chance: 0.0,
         abilities_granted: Vec::new(),
+        damage_type: DamageType::Kinetic,
+        forced_move: None,
+        indirect: None,
     }
 }
 
@@ -21,7 +25,7 @@ fn basic_weapon(range: u32) -> Weapon {
 fn ai_moves_toward_target_when_out_of_range() {
     let mut enemy = Unit::new("e", "E", UnitType::OrkBoy, Faction::Ork);
     enemy.base_stats.agility = 4;
-    enemy.apply_equipment(); // update current_stats
+    enemy.recalculate_stats(); // update current_stats
     enemy.equipment.weapon = Some(basic_weapon(1));
     let mut player = Unit::new("p", "P", UnitType::Guardsman, Faction::Imperial);
     player.grid_position = Position { x: 3, y: 0 };
@@ -33,8 +37,30 @@ fn ai_moves_toward_target_when_out_of_range() {
 
     encounter.run_enemy_turn(50);
 
-    assert_eq!(encounter.enemy_units[0].grid_position, Position { x: 2, y: 0 });
-    assert_eq!(encounter.player_units[0].health_points, encounter.player_units[0].current_stats.max_health);
+    assert_eq!(encounter.unit_by_id("e").unwrap().grid_position, Position { x: 2, y: 0 });
+    assert_eq!(encounter.unit_by_id("p").unwrap().health_points, encounter.unit_by_id("p").unwrap().current_stats.max_health);
+}
+
+#[test]
+fn ai_charges_targets_beyond_a_run_up() {
+    let mut enemy = Unit::new("e", "E", UnitType::OrkBoy, Faction::Ork);
+    enemy.base_stats.agility = 6;
+    enemy.recalculate_stats();
+    enemy.equipment.weapon = Some(basic_weapon(1));
+    let mut player = Unit::new("p", "P", UnitType::Guardsman, Faction::Imperial);
+    player.grid_position = Position { x: 4, y: 0 };
+
+    let mut encounter = CombatEncounter::new(vec![player], vec![enemy], GridMap::new(6,5), None);
+    encounter.turn_order.initiative.clear();
+    encounter.turn_order.add_unit("e".into());
+    encounter.turn_order.add_unit("p".into());
+
+    let starting_hp = encounter.unit_by_id("p").unwrap().health_points;
+    encounter.run_enemy_turn(50);
+
+    assert_eq!(encounter.unit_by_id("e").unwrap().grid_position, Position { x: 3, y: 0 });
+    // Weapon damage plus the charge and Ork charge bonuses.
+    assert_eq!(encounter.unit_by_id("p").unwrap().health_points, starting_hp - 4);
 }
 
 #[test]
@@ -52,9 +78,12 @@ fn ai_uses_best_available_ability() {
         current_cooldown: 0,
         range: 3,
         area_of_effect: None,
-        effect: AbilityEffect { damage: Some(5), healing: None, buff: None, debuff: None, status_applied: None, duration: None },
+        effect: AbilityEffect { damage: Some(5), healing: None, buff: None, debuff: None, status_applied: None, duration: None, creates_hazard: None, alters_terrain: None, forced_move: None, damage_type: DamageType::Kinetic },
         animation: AnimationType::AbilityCast,
         sound_effect_key: String::new(),
+        rank: 1,
+        ranks: Vec::new(),
+        channel: None,
     });
 
     let mut player = Unit::new("p", "P", UnitType::Guardsman, Faction::Imperial);
@@ -65,12 +94,12 @@ fn ai_uses_best_available_ability() {
     encounter.turn_order.add_unit("e".into());
     encounter.turn_order.add_unit("p".into());
 
-    let starting_hp = encounter.player_units[0].health_points;
+    let starting_hp = encounter.unit_by_id("p").unwrap().health_points;
     encounter.run_enemy_turn(50);
 
-    assert_eq!(encounter.player_units[0].health_points, starting_hp - 5);
-    assert_eq!(encounter.enemy_units[0].abilities[0].current_cooldown, 1);
-    assert_eq!(encounter.enemy_units[0].action_points, 1);
+    assert_eq!(encounter.unit_by_id("p").unwrap().health_points, starting_hp - 5);
+    assert_eq!(encounter.unit_by_id("e").unwrap().abilities[0].current_cooldown, 1);
+    assert_eq!(encounter.unit_by_id("e").unwrap().action_points, 1);
 }
 
 #[test]
@@ -88,9 +117,12 @@ fn ai_falls_back_to_weapon_when_ability_unavailable() {
         current_cooldown: 1, // not ready
         range: 3,
         area_of_effect: None,
-        effect: AbilityEffect { damage: Some(5), healing: None, buff: None, debuff: None, status_applied: None, duration: None },
+        effect: AbilityEffect { damage: Some(5), healing: None, buff: None, debuff: None, status_applied: None, duration: None, creates_hazard: None, alters_terrain: None, forced_move: None, damage_type: DamageType::Kinetic },
         animation: AnimationType::AbilityCast,
         sound_effect_key: String::new(),
+        rank: 1,
+        ranks: Vec::new(),
+        channel: None,
     });
 
     let mut player = Unit::new("p", "P", UnitType::Guardsman, Faction::Imperial);
@@ -101,11 +133,11 @@ fn ai_falls_back_to_weapon_when_ability_unavailable() {
     encounter.turn_order.add_unit("e".into());
     encounter.turn_order.add_unit("p".into());
 
-    let starting_hp = encounter.player_units[0].health_points;
+    let starting_hp = encounter.unit_by_id("p").unwrap().health_points;
     encounter.run_enemy_turn(50);
 
-    assert_eq!(encounter.player_units[0].health_points, starting_hp - 2); // weapon damage
-    assert_eq!(encounter.enemy_units[0].grid_position, Position { x: 0, y: 0 });
-    assert_eq!(encounter.enemy_units[0].abilities[0].current_cooldown, 1);
-    assert_eq!(encounter.enemy_units[0].action_points, 1);
+    assert_eq!(encounter.unit_by_id("p").unwrap().health_points, starting_hp - 2); // weapon damage
+    assert_eq!(encounter.unit_by_id("e").unwrap().grid_position, Position { x: 0, y: 0 });
+    assert_eq!(encounter.unit_by_id("e").unwrap().abilities[0].current_cooldown, 1);
+    assert_eq!(encounter.unit_by_id("e").unwrap().action_points, 1);
 }
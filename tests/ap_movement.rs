@@ -0,0 +1,92 @@
+use gero::builders::{UnitBuilder, WeaponBuilder};
+use gero::combat::{CombatEncounter, CombatError};
+use gero::command::Command;
+use gero::frontend::palette::PaletteRole;
+use gero::frontend::tiles::OverlayKind;
+use gero::frontend::Renderer;
+use gero::grid::{move_ap_cost, GridMap};
+use gero::models::{Faction, Position, UnitType};
+
+fn at(x: usize) -> Position {
+    Position { x, y: 0 }
+}
+
+/// A guardsman with three movement points per AP and a one-AP gun, and an
+/// ork at the far end of a 10x1 corridor.
+fn corridor() -> CombatEncounter {
+    let gun = WeaponBuilder::new("gun").damage(1).accuracy(1.0).range(9).build();
+    let guard = UnitBuilder::new("guard", UnitType::Guardsman, Faction::Imperial).weapon(gun).agility(6).at(0, 0).build();
+    let ork = UnitBuilder::new("ork", UnitType::OrkBoy, Faction::Ork).at(9, 0).build();
+    let mut encounter = CombatEncounter::new(vec![guard], vec![ork], GridMap::new(10, 1), None);
+    encounter.turn_order.initiative = ["guard", "ork"].map(String::from).into();
+    encounter.start_turn();
+    encounter.unit_by_id_mut("guard").unwrap().action_points = 2;
+    encounter
+}
+
+fn walk(encounter: &mut CombatEncounter, x: usize) -> Result<(), CombatError> {
+    encounter.execute(Command::Move { unit_id: "guard".into(), destination: at(x) })
+}
+
+#[test]
+fn units_move_shoot_and_move_again_with_leftover_points() {
+    let mut encounter = corridor();
+    walk(&mut encounter, 2).unwrap();
+    let guard = encounter.unit_by_id("guard").unwrap();
+    assert_eq!((guard.action_points, guard.movement_left), (1, 1));
+
+    encounter.execute(Command::Attack { attacker_id: "guard".into(), target_id: "ork".into() }).unwrap();
+    assert_eq!(encounter.unit_by_id("guard").unwrap().action_points, 0);
+    // The point left over from the first move still buys a step.
+    walk(&mut encounter, 3).unwrap();
+    assert_eq!(encounter.unit_by_id("guard").unwrap().movement_left, 0);
+    assert_eq!(walk(&mut encounter, 4), Err(CombatError::Unreachable));
+
+    // A new turn starts with no points carried over.
+    encounter.execute(Command::EndTurn).unwrap();
+    encounter.start_turn();
+    encounter.start_turn();
+    assert_eq!(encounter.unit_by_id("guard").unwrap().movement_left, 0);
+}
+
+#[test]
+fn moves_cost_an_ap_per_band_and_undo_refunds_them() {
+    let mut encounter = corridor();
+    let guard = encounter.unit_by_id("guard").unwrap();
+    let map = &encounter.battlefield;
+    assert_eq!([1, 3, 4, 6, 7].map(|cost| move_ap_cost(guard, map, 0, cost)), [Some(1), Some(1), Some(2), Some(2), None]);
+    assert_eq!(walk(&mut encounter, 7), Err(CombatError::Unreachable));
+
+    walk(&mut encounter, 5).unwrap();
+    let guard = encounter.unit_by_id("guard").unwrap();
+    assert_eq!((guard.action_points, guard.movement_left), (0, 1));
+    assert!(encounter.undo());
+    let guard = encounter.unit_by_id("guard").unwrap();
+    assert_eq!((guard.grid_position.clone(), guard.action_points, guard.movement_left), (at(0), 2, 0));
+}
+
+#[test]
+fn movement_previews_band_cells_by_ap() {
+    let mut encounter = corridor();
+    let bands = encounter.movement_bands("guard");
+    assert_eq!(bands, (1..=6).map(|x| (at(x), if x <= 3 { 1 } else { 2 })).collect::<Vec<_>>());
+
+    let mut renderer = Renderer::new_headless(320, 240);
+    renderer.set_movement_preview(&bands, 2);
+    let cells = |renderer: &Renderer, kind| {
+        renderer.overlays.iter().filter(|o| o.kind == kind).map(|o| o.position.x).collect::<Vec<_>>()
+    };
+    assert_eq!(cells(&renderer, OverlayKind::MovementRange), [1, 2, 3]);
+    assert_eq!(cells(&renderer, OverlayKind::DashRange), [4, 5, 6]);
+    assert_eq!(OverlayKind::DashRange.role(), PaletteRole::DashRange);
+    assert_ne!(renderer.palette.color(PaletteRole::MovementRange), renderer.palette.color(PaletteRole::DashRange));
+
+    // Steps the leftover point pays for leave the last AP free.
+    walk(&mut encounter, 2).unwrap();
+    let bands = encounter.movement_bands("guard");
+    renderer.set_movement_preview(&bands, 1);
+    assert_eq!(cells(&renderer, OverlayKind::MovementRange), [1, 3]);
+    assert_eq!(cells(&renderer, OverlayKind::DashRange), [0, 4, 5, 6]);
+    renderer.clear_movement_preview();
+    assert!(renderer.overlays.is_empty());
+}
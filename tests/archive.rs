@@ -0,0 +1,70 @@
+use gero::archive::{CampaignArchive, ARCHIVE_VERSION};
+use gero::campaign::Campaign;
+use gero::content::ContentDatabase;
+use gero::input::{GameAction, KeyBindings};
+use gero::models::{Faction, Unit, UnitType};
+use gero::saves::SaveFormat;
+use gero::state::GameState;
+use gero::ui::options::{AccessibilitySettings, ColorBlindPalette};
+
+fn db() -> ContentDatabase {
+    ContentDatabase::load("assets/content/items.json").unwrap()
+}
+
+fn archive() -> CampaignArchive {
+    let mut campaign = Campaign::new();
+    let mut unit = Unit::new("sgt", "Sergeant", UnitType::Guardsman, Faction::Imperial);
+    unit.experience = 40;
+    campaign.roster.push(unit.clone());
+    campaign.grant_item("lasgun");
+    campaign.currency = 75;
+    let options = AccessibilitySettings { palette: ColorBlindPalette::Deuteranopia, ..Default::default() };
+    let mut keys = KeyBindings::default();
+    keys.bind("KeyW", GameAction::SelectUp);
+    let mut archive = CampaignArchive::new(campaign, options, keys);
+    archive.add_save("autosave", GameState::new(vec![unit]));
+    archive
+}
+
+#[test]
+fn archives_move_everything_between_machines() {
+    for format in [SaveFormat::Json, SaveFormat::Binary] {
+        let data = archive().export(format).unwrap();
+        let imported = CampaignArchive::import(&data, &db()).unwrap();
+        assert_eq!(imported.version, ARCHIVE_VERSION);
+        assert_eq!(imported.campaign.roster[0].experience, 40);
+        assert_eq!(imported.campaign.inventory, vec!["lasgun".to_string()]);
+        assert_eq!(imported.options.palette, ColorBlindPalette::Deuteranopia);
+        assert_eq!(imported.keybindings.keys.get("KeyW"), Some(&GameAction::SelectUp));
+        assert_eq!(imported.saves[0].name, "autosave");
+        assert!(imported.saves[0].state.units.by_name("sgt").is_some());
+    }
+}
+
+#[test]
+fn newer_archives_are_refused() {
+    let mut future = archive();
+    future.version = ARCHIVE_VERSION + 1;
+    for format in [SaveFormat::Json, SaveFormat::Binary] {
+        let err = CampaignArchive::import(&future.export(format).unwrap(), &db()).unwrap_err();
+        assert!(err.to_string().contains("newer"));
+    }
+}
+
+#[test]
+fn broken_references_are_refused() {
+    let mut unknown_item = archive();
+    unknown_item.campaign.grant_item("doomsday_device");
+    assert!(CampaignArchive::import(&unknown_item.export(SaveFormat::Binary).unwrap(), &db()).is_err());
+
+    let mut duplicate = archive();
+    let copy = duplicate.campaign.roster[0].clone();
+    duplicate.campaign.roster.push(copy);
+    assert!(duplicate.validate(&db()).is_err());
+
+    let mut orphaned = archive();
+    orphaned.saves[0].state.turn_queue.add_unit("ghost".into());
+    assert!(orphaned.validate(&db()).unwrap_err().to_string().contains("ghost"));
+
+    assert!(archive().validate(&db()).is_ok());
+}
@@ -0,0 +1,157 @@
+use std::io;
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::{Arc, Mutex};
+
+use gero::assets::streaming::{sprite_path, AssetKind, AssetManager, AssetScope, PLACEHOLDER_SPRITE};
+use gero::assets::{AssetProvider, MemoryAssets};
+use gero::audio::AudioSystem;
+use gero::combat::CombatEncounter;
+use gero::flow::GameFlow;
+use gero::frontend::capture::encode_png;
+use gero::frontend::Renderer;
+use gero::grid::GridMap;
+use gero::input::GameAction;
+use gero::localization::Localizer;
+use gero::models::{Faction, Unit, UnitType};
+use gero::simulation::BattleOutcome;
+use gero::state::GameState;
+use gero::ui::UiManager;
+use image::{Rgba, RgbaImage};
+
+const RED: [u8; 4] = [200, 0, 0, 255];
+
+fn png() -> Vec<u8> {
+    encode_png(&RgbaImage::from_pixel(1, 1, Rgba(RED))).unwrap()
+}
+
+fn assets_with(sprites: &[&str]) -> MemoryAssets {
+    let assets = MemoryAssets::new();
+    for id in sprites {
+        assets.write(&sprite_path(id), &png()).unwrap();
+    }
+    assets
+}
+
+/// Assets that are only read once the test lets them through.
+#[derive(Debug)]
+struct Gated {
+    inner: MemoryAssets,
+    gate: Mutex<Receiver<()>>,
+}
+
+impl AssetProvider for Gated {
+    fn read(&self, path: &str) -> io::Result<Vec<u8>> {
+        let _ = self.gate.lock().unwrap().recv();
+        self.inner.read(path)
+    }
+
+    fn write(&self, path: &str, data: &[u8]) -> io::Result<()> {
+        self.inner.write(path, data)
+    }
+
+    fn list(&self, dir: &str) -> io::Result<Vec<String>> {
+        self.inner.list(dir)
+    }
+}
+
+fn gated(sprites: &[&str]) -> (AssetManager, Sender<()>) {
+    let (open, gate) = mpsc::channel();
+    let assets = Gated { inner: assets_with(sprites), gate: Mutex::new(gate) };
+    (AssetManager::new(Arc::new(assets)), open)
+}
+
+#[test]
+fn sprites_show_a_placeholder_until_they_arrive() {
+    let (mut manager, open) = gated(&["guard"]);
+    let mut renderer = Renderer::new_headless(64, 64);
+    let mut audio = AudioSystem::new();
+    manager.request(AssetKind::Sprite, "guard", &sprite_path("guard"), AssetScope::Battle);
+    assert_eq!(manager.poll(&mut renderer, &mut audio), 0);
+    assert_eq!(renderer.sprite_textures["guard"], [PLACEHOLDER_SPRITE.to_vec()]);
+    let progress = manager.progress();
+    assert_eq!((progress.loaded, progress.total, progress.fraction()), (0, 1, 0.0));
+
+    open.send(()).unwrap();
+    assert_eq!(manager.finish(&mut renderer, &mut audio), 1);
+    assert_eq!(renderer.sprite_textures["guard"], [RED.to_vec()]);
+    assert!(manager.progress().is_done());
+}
+
+#[test]
+fn failed_loads_keep_the_placeholder_and_other_sprites_win() {
+    let mut manager = AssetManager::new(Arc::new(assets_with(&["modded"])));
+    let mut renderer = Renderer::new_headless(64, 64);
+    let mut audio = AudioSystem::new();
+    renderer.load_sprite_from_bytes("modded", vec![vec![1, 2, 3, 4]]);
+    manager.request(AssetKind::Sprite, "missing", &sprite_path("missing"), AssetScope::Battle);
+    manager.request(AssetKind::Sprite, "modded", &sprite_path("modded"), AssetScope::Battle);
+    manager.finish(&mut renderer, &mut audio);
+
+    assert_eq!(renderer.sprite_textures["missing"], [PLACEHOLDER_SPRITE.to_vec()]);
+    assert!(manager.error(AssetKind::Sprite, "missing").is_some());
+    assert_eq!(renderer.sprite_textures["modded"], [vec![1, 2, 3, 4]]);
+    let progress = manager.progress();
+    assert_eq!((progress.loaded, progress.failed, progress.fraction()), (1, 1, 1.0));
+
+    // Released, the mod's sprite stays.
+    manager.release_scope(AssetScope::Battle);
+    manager.poll(&mut renderer, &mut audio);
+    assert!(renderer.sprite_textures.contains_key("modded"));
+    assert!(!renderer.sprite_textures.contains_key("missing"));
+}
+
+#[test]
+fn battle_assets_are_released_unless_the_campaign_holds_them() {
+    let assets = assets_with(&["banner", "ork"]);
+    assets.write("assets/sounds/waaagh.ogg", b"ogg").unwrap();
+    let mut manager = AssetManager::new(Arc::new(assets));
+    let mut renderer = Renderer::new_headless(64, 64);
+    let mut audio = AudioSystem::new();
+    manager.request(AssetKind::Sprite, "banner", &sprite_path("banner"), AssetScope::Campaign);
+    manager.request(AssetKind::Sprite, "banner", &sprite_path("banner"), AssetScope::Battle);
+    manager.request(AssetKind::Sprite, "ork", &sprite_path("ork"), AssetScope::Battle);
+    manager.request(AssetKind::Sound, "waaagh", "assets/sounds/waaagh.ogg", AssetScope::Battle);
+    manager.finish(&mut renderer, &mut audio);
+    assert_eq!(manager.references(AssetKind::Sprite, "banner"), 2);
+    assert!(audio.is_loaded("waaagh"));
+
+    manager.release_scope(AssetScope::Battle);
+    manager.poll(&mut renderer, &mut audio);
+    assert_eq!(manager.references(AssetKind::Sprite, "banner"), 1);
+    assert!(renderer.sprite_textures.contains_key("banner"));
+    assert!(!renderer.sprite_textures.contains_key("ork"));
+    assert!(!audio.is_loaded("waaagh"));
+    assert_eq!(manager.progress().total, 1);
+}
+
+#[test]
+fn the_flow_shows_a_loading_screen_and_frees_the_battle_afterwards() {
+    let mut unit = Unit::new("u", "U", UnitType::Guardsman, Faction::Imperial);
+    unit.sprite_id = "guard".into();
+    let state = GameState::new(vec![unit]);
+    let (manager, open) = gated(&["guard", "tile_normal"]);
+    let mut flow = GameFlow::new(state, UiManager::new(640, 480, Vec::new(), Vec::new()));
+    let mut renderer = Renderer::new_headless(640, 480);
+    let mut audio = AudioSystem::new();
+    let loc = Localizer::new("en").unwrap();
+    flow.assets = Some(manager);
+    flow.load_battle_assets();
+    flow.poll_assets(&mut renderer, &mut audio);
+    assert!(flow.is_paused());
+    flow.render(&mut renderer, &loc);
+    assert!(renderer.text_log.iter().any(|t| t.text == "0 of 2 assets"));
+
+    for _ in 0..2 {
+        open.send(()).unwrap();
+    }
+    flow.assets.as_mut().unwrap().finish(&mut renderer, &mut audio);
+    flow.poll_assets(&mut renderer, &mut audio);
+    assert!(flow.loading.is_none());
+    assert_eq!(renderer.sprite_textures["guard"], [RED.to_vec()]);
+
+    let encounter = CombatEncounter::new(flow.state.units.iter().cloned().collect(), Vec::new(), GridMap::new(2, 2), None);
+    flow.finish_battle(&encounter, BattleOutcome::PlayerVictory);
+    flow.handle_action(GameAction::Activate);
+    flow.poll_assets(&mut renderer, &mut audio);
+    assert!(!renderer.sprite_textures.contains_key("guard"));
+}
@@ -0,0 +1,76 @@
+use std::path::Path;
+use std::sync::Arc;
+
+use gero::assets::{AssetProvider, FileAssets, MemoryAssets, ASSET_INDEX};
+use gero::campaign::Campaign;
+use gero::content::ContentDatabase;
+use gero::localization::{available_languages_in, Localizer};
+use gero::saves::{self, SaveFormat};
+
+fn files_under(dir: &Path, out: &mut Vec<String>) {
+    for entry in std::fs::read_dir(dir).unwrap() {
+        let path = entry.unwrap().path();
+        if path.is_dir() {
+            files_under(&path, out);
+        } else {
+            out.push(path.to_str().unwrap().replace('\\', "/"));
+        }
+    }
+}
+
+/// The shipped assets copied into memory, as the browser build sees them.
+fn memory_assets() -> MemoryAssets {
+    let disk = FileAssets::default();
+    let memory = MemoryAssets::new();
+    let index: Vec<String> = serde_json::from_slice(&disk.read(ASSET_INDEX).unwrap()).unwrap();
+    for path in index {
+        memory.write(&path, &disk.read(&path).unwrap()).unwrap();
+    }
+    memory
+}
+
+#[test]
+fn asset_index_lists_every_shipped_file() {
+    let mut files = Vec::new();
+    files_under(Path::new("assets"), &mut files);
+    files.retain(|f| f != ASSET_INDEX);
+    files.sort();
+    let index: Vec<String> = serde_json::from_str(&std::fs::read_to_string(ASSET_INDEX).unwrap()).unwrap();
+    assert_eq!(index, files);
+}
+
+#[test]
+fn localizer_and_content_read_through_a_provider() {
+    let assets = Arc::new(memory_assets());
+    assert_eq!(available_languages_in(&*assets), ["de", "en"]);
+    assert_eq!(assets.list("assets/locales").unwrap(), ["de.json", "en.json"]);
+
+    let mut loc = Localizer::with_assets(assets.clone(), &["en"]).unwrap();
+    assert_eq!(loc.get("ui.tab.abilities"), "Abilities");
+    assets.write("assets/locales/xx.json", br#"{ "ui.tab.abilities": "Xbilities" }"#).unwrap();
+    loc.load("xx").unwrap();
+    assert_eq!(loc.get("ui.tab.abilities"), "Xbilities");
+    assert!(loc.load("zz").is_err());
+
+    let db = ContentDatabase::load_from(&*assets, "assets/content/items.json").unwrap();
+    assert_eq!(db.items.len(), ContentDatabase::load("assets/content/items.json").unwrap().items.len());
+}
+
+#[test]
+fn saves_round_trip_through_a_provider() {
+    let assets = MemoryAssets::new();
+    let mut campaign = Campaign::new();
+    campaign.currency = 75;
+    saves::save_to(&assets, &campaign, "saves/campaign.sav", SaveFormat::Binary).unwrap();
+    assert_eq!(assets.paths(), ["saves/campaign.sav"]);
+    let loaded: Campaign = saves::load_from(&assets, "saves/campaign.sav").unwrap();
+    assert_eq!(loaded.currency, 75);
+    assert_eq!(saves::load_from::<Campaign>(&assets, "saves/missing.sav").unwrap_err().kind(), std::io::ErrorKind::NotFound);
+
+    let dir = std::env::temp_dir().join(format!("gero_assets_{}", std::process::id()));
+    let disk = FileAssets::new(&dir);
+    saves::save_to(&disk, &campaign, "saves/campaign.sav", SaveFormat::Json).unwrap();
+    assert!(dir.join("saves/campaign.sav").is_file());
+    assert_eq!(disk.list("saves").unwrap(), ["campaign.sav"]);
+    std::fs::remove_dir_all(&dir).unwrap();
+}
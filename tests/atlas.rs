@@ -0,0 +1,95 @@
+use gero::frontend::Renderer;
+use gero::models::{Unit, UnitType, Faction};
+use gero::state::GameState;
+
+fn atlas_dir(name: &str) -> std::path::PathBuf {
+    let dir = std::env::temp_dir().join(format!("gero_atlas_{}_{}", name, std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    // 4x2 atlas: left half red, right half blue
+    let img = image::RgbaImage::from_fn(4, 2, |x, _| {
+        if x < 2 { image::Rgba([255, 0, 0, 255]) } else { image::Rgba([0, 0, 255, 255]) }
+    });
+    img.save(dir.join("sprites.png")).unwrap();
+    dir
+}
+
+#[test]
+fn load_atlas_populates_sprites_and_frames() {
+    let dir = atlas_dir("load");
+    std::fs::write(
+        dir.join("sprites.json"),
+        r#"{
+            "image": "sprites.png",
+            "sprites": {
+                "guard_idle_0": { "x": 0, "y": 0, "w": 2, "h": 2 },
+                "guard_idle_1": { "x": 2, "y": 0, "w": 2, "h": 2 }
+            },
+            "animations": { "guard_idle": ["guard_idle_0", "guard_idle_1"] }
+        }"#,
+    )
+    .unwrap();
+
+    let mut renderer = Renderer::new_headless(64, 64);
+    renderer.load_atlas(dir.join("sprites.json")).unwrap();
+
+    assert_eq!(renderer.sprites.get("guard_idle_1"), Some(&(2, 0, 2, 2)));
+    let frames = &renderer.sprite_textures["guard_idle"];
+    assert_eq!(frames.len(), 2);
+    assert_eq!(&frames[0][..4], &[255, 0, 0, 255]);
+    assert_eq!(&frames[1][..4], &[0, 0, 255, 255]);
+    assert_eq!(renderer.sprite_textures["guard_idle_0"].len(), 1);
+
+    let mut unit = Unit::new("u", "U", UnitType::Guardsman, Faction::Imperial);
+    unit.sprite_id = "guard_idle".into();
+    unit.animation_state.frame_index = 3;
+    renderer.render_state(&GameState::new(vec![unit]));
+    assert_eq!(renderer.draw_log[0].frame_index, 1);
+}
+
+#[test]
+fn validate_atlas_rejects_out_of_bounds_rects() {
+    let dir = atlas_dir("bounds");
+    std::fs::write(
+        dir.join("sprites.json"),
+        r#"{ "image": "sprites.png", "sprites": { "big": { "x": 2, "y": 0, "w": 4, "h": 2 } } }"#,
+    )
+    .unwrap();
+    let err = Renderer::validate_atlas(dir.join("sprites.json")).unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+}
+
+#[test]
+fn validate_atlas_rejects_rects_whose_edges_overflow() {
+    let dir = atlas_dir("overflow");
+    for rect in [r#"{ "x": 4294967295, "y": 0, "w": 2, "h": 2 }"#, r#"{ "x": 0, "y": 1, "w": 2, "h": 4294967295 }"#] {
+        let manifest = format!(r#"{{ "image": "sprites.png", "sprites": {{ "huge": {rect} }} }}"#);
+        std::fs::write(dir.join("sprites.json"), manifest).unwrap();
+        let err = Renderer::validate_atlas(dir.join("sprites.json")).unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+    }
+}
+
+#[test]
+fn validate_atlas_rejects_unknown_animation_frames() {
+    let dir = atlas_dir("frames");
+    std::fs::write(
+        dir.join("sprites.json"),
+        r#"{
+            "image": "sprites.png",
+            "sprites": { "a": { "x": 0, "y": 0, "w": 1, "h": 1 } },
+            "animations": { "walk": ["a", "b"] }
+        }"#,
+    )
+    .unwrap();
+    let err = Renderer::validate_atlas(dir.join("sprites.json")).unwrap_err();
+    assert!(err.to_string().contains("'b'"));
+}
+
+#[test]
+fn validate_atlas_reports_missing_image() {
+    let dir = atlas_dir("image");
+    std::fs::write(dir.join("sprites.json"), r#"{ "image": "nope.png", "sprites": {} }"#).unwrap();
+    let err = Renderer::validate_atlas(dir.join("sprites.json")).unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::NotFound);
+}
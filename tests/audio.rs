@@ -36,3 +36,126 @@ fn background_music_changes_track() {
     assert_eq!(audio.current_music.as_deref(), Some("track2"));
     assert_eq!(audio.played_log, vec!["music:track1", "music:track2"]);
 }
+
+fn manifest_dir(name: &str) -> std::path::PathBuf {
+    let dir = std::env::temp_dir().join(format!("gero_audio_{}_{}", name, std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+#[test]
+fn manifest_registers_sounds_lazily_and_preloads_on_request() {
+    let dir = manifest_dir("lazy");
+    std::fs::write(dir.join("click.wav"), [1, 2, 3]).unwrap();
+    std::fs::write(dir.join("theme.ogg"), [4, 5]).unwrap();
+    std::fs::write(
+        dir.join("audio.json"),
+        r#"{ "button_click": "click.wav", "combat": { "path": "theme.ogg", "preload": true } }"#,
+    )
+    .unwrap();
+
+    let mut audio = AudioSystem::new();
+    audio.load_manifest(dir.join("audio.json")).unwrap();
+    assert!(!audio.is_loaded("button_click"));
+    assert!(audio.is_loaded("combat"));
+
+    audio.play("button_click");
+    assert!(audio.is_loaded("button_click"));
+    assert_eq!(audio.played_log, vec!["button_click"]);
+}
+
+#[test]
+fn manifest_reports_missing_files() {
+    let dir = manifest_dir("missing");
+    std::fs::write(dir.join("audio.json"), r#"{ "boom": "explosion.wav" }"#).unwrap();
+
+    let mut audio = AudioSystem::new();
+    let err = audio.load_manifest(dir.join("audio.json")).unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::NotFound);
+    assert!(err.to_string().contains("explosion.wav"));
+}
+
+#[test]
+fn files_gone_missing_after_the_manifest_are_reported_on_play() {
+    let dir = manifest_dir("gone");
+    std::fs::write(dir.join("boom.wav"), [1, 2, 3]).unwrap();
+    std::fs::write(dir.join("audio.json"), r#"{ "boom": "boom.wav" }"#).unwrap();
+
+    let mut audio = AudioSystem::new();
+    audio.load_manifest(dir.join("audio.json")).unwrap();
+    std::fs::remove_file(dir.join("boom.wav")).unwrap();
+    let err = audio.load("boom").unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::NotFound);
+    assert!(err.to_string().contains("boom.wav"));
+
+    audio.play("boom");
+    let errors = audio.take_load_errors();
+    assert_eq!(errors.len(), 1);
+    assert!(errors[0].to_string().contains("boom"));
+    assert!(audio.take_load_errors().is_empty());
+
+    // Once the file is back the sound loads.
+    std::fs::write(dir.join("boom.wav"), [1, 2, 3]).unwrap();
+    audio.play("boom");
+    assert!(audio.is_loaded("boom") && audio.take_load_errors().is_empty());
+}
+
+#[test]
+fn manifest_rejects_unsupported_formats() {
+    let dir = manifest_dir("format");
+    std::fs::write(dir.join("voice.flac"), [0]).unwrap();
+    std::fs::write(dir.join("audio.json"), r#"{ "bark": "voice.flac" }"#).unwrap();
+
+    let mut audio = AudioSystem::new();
+    let err = audio.load_manifest(dir.join("audio.json")).unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+}
+
+#[test]
+fn captions_follow_the_subtitles_option_and_time_out() {
+    use gero::combat::CombatEvent;
+    use gero::frontend::Renderer;
+    use gero::localization::Localizer;
+    use gero::models::Position;
+    use gero::ui::UiManager;
+    use gero::ui::options::AccessibilitySettings;
+
+    let dir = manifest_dir("captions");
+    std::fs::write(dir.join("hold.ogg"), [1]).unwrap();
+    std::fs::write(
+        dir.join("audio.json"),
+        r#"{ "bark_hold": { "path": "hold.ogg", "caption": "doctrine.hold_the_line", "speaker": "speaker.commissar", "caption_seconds": 1.5 } }"#,
+    )
+    .unwrap();
+    let mut audio = AudioSystem::new();
+    audio.load_manifest(dir.join("audio.json")).unwrap();
+
+    audio.play_voice("bark_hold");
+    assert!(audio.take_captions().is_empty());
+
+    audio.apply_accessibility(&AccessibilitySettings { subtitles: true, ..Default::default() });
+    audio.play_voice("bark_hold");
+    audio.handle_combat_event(&CombatEvent::Explosion { center: Position { x: 0, y: 0 }, cells: Vec::new() });
+    audio.play("button_click");
+    let captions = audio.take_captions();
+    assert_eq!(captions.len(), 2);
+    assert_eq!(captions[0].duration, 1.5);
+    assert!(audio.take_captions().is_empty());
+
+    let mut ui = UiManager::new(800, 600, vec![], vec![]);
+    for caption in captions {
+        ui.captions.push(caption);
+    }
+    let mut renderer = Renderer::new_headless(800, 600);
+    let loc = Localizer::new("en").unwrap();
+    ui.render(&mut renderer, &loc);
+    assert!(renderer.text_log.iter().any(|t| t.text == "Commissar Vance: Hold the Line"));
+    assert!(renderer.text_log.iter().any(|t| t.text == "[Explosion]"));
+    assert_eq!(renderer.draw_log.iter().filter(|c| c.sprite_id == "panel.caption").count(), 2);
+
+    ui.captions.update(2.0);
+    assert_eq!(ui.captions.active.len(), 1);
+    ui.captions.update(1.0);
+    assert!(ui.captions.active.is_empty());
+}
@@ -0,0 +1,75 @@
+use gero::combat::CombatEncounter;
+use gero::frontend::tiles::OverlayKind;
+use gero::frontend::Renderer;
+use gero::grid::GridMap;
+use gero::models::{Faction, Position, Unit, UnitType};
+
+fn unit(id: &str, unit_type: UnitType, faction: Faction, x: usize) -> Unit {
+    let mut unit = Unit::new(id, id, unit_type, faction);
+    unit.grid_position = Position { x, y: 0 };
+    unit
+}
+
+#[test]
+fn leadership_buffs_allies_in_radius_only() {
+    let mut enc = CombatEncounter::new(
+        vec![
+            unit("c", UnitType::Commissar, Faction::Imperial, 0),
+            unit("near", UnitType::Guardsman, Faction::Imperial, 3),
+            unit("far", UnitType::Guardsman, Faction::Imperial, 4),
+        ],
+        vec![unit("o", UnitType::OrkBoy, Faction::Ork, 1)],
+        GridMap::new(8, 1),
+        None,
+    );
+    enc.start_turn();
+    let stats = |enc: &CombatEncounter, id: &str| enc.unit_by_id(id).unwrap().current_stats.clone();
+    assert_eq!(stats(&enc, "near").willpower, 2);
+    assert_eq!(stats(&enc, "near").accuracy, 5);
+    assert_eq!(stats(&enc, "far").willpower, 0);
+    assert_eq!(stats(&enc, "c").willpower, 0);
+    assert_eq!(stats(&enc, "o").willpower, 0);
+
+    // Walking out of range drops the bonus at the next turn start.
+    enc.unit_by_id_mut("near").unwrap().grid_position = Position { x: 6, y: 0 };
+    enc.start_turn();
+    assert_eq!(stats(&enc, "near").willpower, 0);
+}
+
+#[test]
+fn auras_of_one_kind_do_not_stack_and_die_with_the_source() {
+    let mut enc = CombatEncounter::new(
+        vec![],
+        vec![
+            unit("n1", UnitType::OrkNob, Faction::Ork, 0),
+            unit("n2", UnitType::OrkNob, Faction::Ork, 2),
+            unit("boy", UnitType::OrkBoy, Faction::Ork, 1),
+        ],
+        GridMap::new(8, 1),
+        None,
+    );
+    enc.refresh_auras();
+    assert_eq!(enc.unit_by_id("boy").unwrap().current_stats.strength, 1);
+    assert_eq!(enc.unit_by_id("n1").unwrap().current_stats.toughness, 1);
+
+    enc.unit_by_id_mut("n2").unwrap().health_points = 0;
+    enc.unit_by_id_mut("n1").unwrap().health_points = 0;
+    enc.refresh_auras();
+    assert_eq!(enc.unit_by_id("boy").unwrap().current_stats.strength, 0);
+}
+
+#[test]
+fn aura_ring_outlines_the_radius() {
+    let enc = CombatEncounter::new(
+        vec![unit("c", UnitType::Commissar, Faction::Imperial, 0)],
+        vec![],
+        GridMap::new(8, 8),
+        None,
+    );
+    let mut renderer = Renderer::new_headless(640, 480);
+    renderer.set_aura_rings(&enc.battlefield, &enc.aura_sources());
+    let ring: Vec<_> = renderer.overlays.iter().filter(|o| o.kind == OverlayKind::AuraRing).collect();
+    assert_eq!(ring.len(), 4);
+    assert!(ring.iter().any(|o| o.position == Position { x: 3, y: 0 }));
+    assert!(ring.iter().any(|o| o.position == Position { x: 1, y: 2 }));
+}
@@ -0,0 +1,53 @@
+use gero::builders::{UnitBuilder, WeaponBuilder};
+use gero::combat::{CombatEncounter, CombatError, Controller};
+use gero::command::Command;
+use gero::grid::GridMap;
+use gero::models::{Faction, Position, Unit, UnitType};
+
+fn soldier(id: &str, faction: Faction, x: usize) -> Unit {
+    let gun = WeaponBuilder::new("gun").damage(3).range(2).accuracy(1.0).critical_chance(0.0).build();
+    UnitBuilder::new(id, UnitType::Guardsman, faction).max_health(20).weapon(gun).at(x, 0).build()
+}
+
+/// Two guards for player 0 against an ork; it is the first guard's turn.
+fn encounter() -> CombatEncounter {
+    let guards = vec![soldier("guard", Faction::Imperial, 3), soldier("sarge", Faction::Imperial, 1)];
+    let mut encounter = CombatEncounter::new(guards, vec![soldier("ork", Faction::Ork, 5)], GridMap::new(8, 1), None);
+    encounter.balance.critical_multiplier = 1;
+    encounter.active_player = Some(0);
+    encounter.turn_order.current_unit_id = Some("guard".into());
+    encounter
+}
+
+#[test]
+fn auto_controlled_units_are_played_by_the_ai() {
+    let mut encounter = encounter();
+    assert!(!encounter.ai_action(50));
+    encounter.set_auto_control("guard", true).unwrap();
+    assert_eq!(encounter.controller_of("guard"), Some(Controller::Ai));
+    let step = Command::Move { unit_id: "guard".into(), destination: Position { x: 2, y: 0 } };
+    assert_eq!(encounter.execute_as(0, step), Err(CombatError::NotControlled));
+
+    assert!(encounter.ai_action(50));
+    assert_eq!(encounter.unit_by_id("ork").unwrap().health_points, 17);
+
+    encounter.set_auto_control("guard", false).unwrap();
+    assert_eq!(encounter.controller_of("guard"), Some(Controller::Player(0)));
+}
+
+#[test]
+fn auto_battle_covers_the_whole_squad() {
+    let mut encounter = encounter();
+    encounter.set_auto_battle(0, true);
+    assert!(encounter.is_auto_controlled("guard") && encounter.is_auto_controlled("sarge"));
+    assert!(!encounter.is_auto_controlled("ork"));
+    encounter.set_auto_battle(0, false);
+    assert!(!encounter.is_auto_controlled("sarge"));
+}
+
+#[test]
+fn only_player_units_can_be_handed_over() {
+    let mut encounter = encounter();
+    assert_eq!(encounter.set_auto_control("ork", true), Err(CombatError::NotControlled));
+    assert_eq!(encounter.set_auto_control("nobody", true), Err(CombatError::UnknownUnit));
+}
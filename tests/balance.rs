@@ -0,0 +1,94 @@
+use gero::assets::{AssetProvider, MemoryAssets};
+use gero::balance::{BalanceConfig, MovementRules, BALANCE_PATH};
+use gero::combat::{resolve_attack_with, AttackModifiers, CombatEncounter};
+use gero::damage::DamageType;
+use gero::grid::{find_path, reachable_cells, GridMap};
+use gero::models::{Faction, Position, Unit, UnitType, Weapon, WeaponTier};
+use gero::weather::Weather;
+
+fn walker() -> Unit {
+    let mut unit = Unit::new("u", "U", UnitType::Guardsman, Faction::Imperial);
+    unit.base_stats.agility = 4;
+    unit.recalculate_stats();
+    unit
+}
+
+fn rifle() -> Weapon {
+    Weapon {
+        id: "r".into(),
+        name: "R".into(),
+        tier: WeaponTier::Basic,
+        damage: 3,
+        accuracy: 0.6,
+        range: 8,
+        armor_piercing: None,
+        action_point_cost: 1,
+        critical_chance: 0.0,
+        abilities_granted: Vec::new(),
+        damage_type: DamageType::Kinetic,
+        forced_move: None,
+        indirect: None,
+    }
+}
+
+#[test]
+fn the_shipped_file_matches_the_compiled_in_defaults() {
+    assert_eq!(BalanceConfig::load(BALANCE_PATH).unwrap(), BalanceConfig::DEFAULT);
+
+    let assets = MemoryAssets::new();
+    assets.write("balance.json", br#"{ "critical_roll": 25, "movement": { "difficult": 3 } }"#).unwrap();
+    let config = BalanceConfig::load_from(&assets, "balance.json").unwrap();
+    assert_eq!(config.critical_roll, 25);
+    assert_eq!(config.movement.difficult, 3);
+    assert_eq!(config.movement.agility_per_point, MovementRules::DEFAULT.agility_per_point);
+    assert_eq!(config.agility_hit_chance, BalanceConfig::DEFAULT.agility_hit_chance);
+
+    assets.write("broken.json", br#"{ "movement": { "agility_per_point": 0 } }"#).unwrap();
+    assert!(BalanceConfig::load_from(&assets, "broken.json").is_err());
+}
+
+#[test]
+fn critical_threshold_and_multiplier_come_from_the_balance() {
+    let balance = BalanceConfig { critical_roll: 30, critical_multiplier: 3, ..BalanceConfig::DEFAULT };
+    let mut target = walker();
+    let hp = target.health_points;
+    let default = resolve_attack_with(&mut walker(), &rifle(), &mut target, 20, 0, AttackModifiers::default(), &BalanceConfig::DEFAULT).unwrap();
+    assert!(default.hit && !default.critical);
+    assert_eq!(hp - target.health_points, default.damage);
+
+    let mut target = walker();
+    let tuned = resolve_attack_with(&mut walker(), &rifle(), &mut target, 20, 0, AttackModifiers::default(), &balance).unwrap();
+    assert!(tuned.critical);
+    assert_eq!(tuned.damage, default.damage * 3);
+
+    // Heavier agility weighting makes the same roll miss a nimble target.
+    let evasive = BalanceConfig { agility_hit_chance: 40.0, ..BalanceConfig::DEFAULT };
+    let mut nimble = walker();
+    nimble.current_stats.agility = 8;
+    assert!(!resolve_attack_with(&mut walker(), &rifle(), &mut nimble, 50, 0, AttackModifiers::default(), &evasive).unwrap().hit);
+}
+
+#[test]
+fn the_encounter_hands_movement_rules_to_pathfinding() {
+    let mut encounter = CombatEncounter::new(vec![walker()], Vec::new(), GridMap::new(6, 1), None);
+    // One AP's worth of movement.
+    let mut unit = walker();
+    unit.action_points = 1;
+    let before = encounter.battlefield.revision;
+    assert_eq!(find_path(&unit, &Position { x: 2, y: 0 }, &encounter.battlefield, 0, Weather::Clear).cost, Some(2));
+    assert_eq!(reachable_cells(&unit, &encounter.battlefield, 0, Weather::Clear).len(), 2);
+
+    // Twice the points at twice the cost per step reach just as far.
+    let movement = MovementRules { agility_per_point: 1, straight_step: 2, ..MovementRules::DEFAULT };
+    encounter.set_balance(BalanceConfig { movement, ..BalanceConfig::DEFAULT });
+    assert!(encounter.battlefield.revision > before);
+    assert_eq!(find_path(&unit, &Position { x: 2, y: 0 }, &encounter.battlefield, 0, Weather::Clear).cost, Some(4));
+    assert_eq!(reachable_cells(&unit, &encounter.battlefield, 0, Weather::Clear).len(), 2);
+    assert_eq!(find_path(&unit, &Position { x: 3, y: 0 }, &encounter.battlefield, 0, Weather::Clear).cost, None);
+
+    // Older saves without a balance load with the defaults.
+    let mut value = serde_json::to_value(&encounter).unwrap();
+    value.as_object_mut().unwrap().remove("balance");
+    let loaded: CombatEncounter = serde_json::from_value(value).unwrap();
+    assert_eq!(loaded.balance, BalanceConfig::DEFAULT);
+}
@@ -0,0 +1,86 @@
+use gero::base::{Facility, ARMORY_UPGRADE_COST, CHAPEL_UPGRADE_COST};
+use gero::campaign::Campaign;
+use gero::content::ContentDatabase;
+use gero::events::MAX_MORALE;
+use gero::frontend::Renderer;
+use gero::injuries::{Injury, InjuryKind};
+use gero::input::GameAction;
+use gero::localization::Localizer;
+use gero::models::{Faction, Unit, UnitType, WeaponTier};
+use gero::research::TierUnlock;
+use gero::ui::base::BaseScreen;
+
+#[test]
+fn facilities_upgrade_along_their_tracks() {
+    let mut campaign = Campaign::new();
+    assert_eq!(campaign.upgrade_facility(Facility::Chapel), Err("insufficient requisition"));
+    campaign.currency = 1000;
+    for level in 1..=3 {
+        assert_eq!(campaign.facility_upgrade_cost(Facility::Chapel), Some(CHAPEL_UPGRADE_COST * level));
+        campaign.upgrade_facility(Facility::Chapel).unwrap();
+    }
+    assert_eq!(campaign.facility_upgrade_cost(Facility::Chapel), None);
+    assert_eq!(campaign.upgrade_facility(Facility::Chapel), Err("chapel fully upgraded"));
+    assert_eq!(campaign.currency, 1000 - 6 * CHAPEL_UPGRADE_COST);
+
+    // The medbay track is the one the medbay screen upgrades.
+    campaign.upgrade_facility(Facility::Medbay).unwrap();
+    assert_eq!(campaign.medbay_level, 1);
+    assert_eq!(campaign.medbay_upgrade_cost(), campaign.facility_upgrade_cost(Facility::Medbay));
+}
+
+#[test]
+fn the_armory_unlocks_master_crafted_weapons() {
+    let db = ContentDatabase::load("assets/content/items.json").unwrap();
+    let mut campaign = Campaign::new();
+    campaign.missions_completed = 10;
+    campaign.currency = 1000;
+    campaign.research.unlocked = vec![TierUnlock::Weapon(WeaponTier::Advanced), TierUnlock::Weapon(WeaponTier::MasterCrafted)];
+    campaign.purchase(&db, "bolter").unwrap();
+    assert_eq!(campaign.purchase(&db, "plasma_gun"), Err("armory upgrade required"));
+    campaign.upgrade_facility(Facility::Armory).unwrap();
+    assert_eq!(campaign.currency, 1000 - 60 - ARMORY_UPGRADE_COST);
+    campaign.purchase(&db, "plasma_gun").unwrap();
+    assert_eq!(campaign.upgrade_facility(Facility::Armory), Err("armory fully upgraded"));
+}
+
+#[test]
+fn tending_the_base_heals_and_lifts_morale() {
+    let mut campaign = Campaign::new();
+    let mut unit = Unit::new("g", "G", UnitType::Guardsman, Faction::Imperial);
+    unit.injuries.push(Injury::new(InjuryKind::BrokenArm));
+    campaign.roster.push(unit);
+    campaign.morale = -3;
+    campaign.tend_base();
+    assert_eq!(campaign.morale, -3);
+    assert_eq!(campaign.roster[0].recovery_missions(), 1);
+
+    campaign.chapel_level = 2;
+    campaign.tend_base();
+    assert_eq!(campaign.morale, -1);
+    assert!(!campaign.roster[0].is_recovering());
+    campaign.morale = MAX_MORALE - 1;
+    campaign.tend_base();
+    assert_eq!(campaign.morale, MAX_MORALE);
+}
+
+#[test]
+fn the_base_screen_buys_the_selected_upgrade() {
+    let loc = Localizer::new("en").unwrap();
+    let mut campaign = Campaign::new();
+    campaign.currency = 100;
+    let mut screen = BaseScreen::new();
+    screen.handle_input(GameAction::Activate, &mut campaign);
+    assert_eq!(screen.last_error, Some("insufficient requisition"));
+    for _ in 0..3 {
+        screen.handle_input(GameAction::SelectDown, &mut campaign);
+    }
+    assert_eq!(screen.selected_index, 2);
+    screen.handle_input(GameAction::Activate, &mut campaign);
+    assert_eq!((campaign.chapel_level, campaign.currency), (1, 40));
+
+    let mut renderer = Renderer::new_headless(1280, 720);
+    screen.render(&mut renderer, &loc, &campaign);
+    assert!(renderer.text_log.iter().any(|t| t.text == "Chapel - level 1/3 - upgrade for 120"));
+    assert!(renderer.text_log.iter().any(|t| t.text == "Armory - level 0/1 - upgrade for 150"));
+}
@@ -0,0 +1,50 @@
+use gero::combat::resolve_attack;
+use gero::prelude::*;
+
+#[test]
+fn builders_fill_in_defaults() {
+    let knife = WeaponBuilder::new("knife").build();
+    assert_eq!((knife.name.as_str(), knife.damage, knife.range, knife.action_point_cost), ("knife", 1, 1, 1));
+    assert!(knife.is_melee());
+
+    let ability = Ability::builder("mend", AbilityType::Healing).build();
+    assert_eq!((ability.action_point_cost, ability.cooldown, ability.range), (1, 0, 1));
+    assert!(ability.effect.damage.is_none() && ability.effect.healing.is_none());
+
+    let unit = UnitBuilder::new("g", UnitType::Guardsman, Faction::Imperial).build();
+    assert_eq!((unit.name.as_str(), unit.health_points, unit.action_points), ("g", 10, 2));
+    assert!(unit.equipment.weapon.is_none());
+}
+
+#[test]
+fn built_units_are_ready_to_fight() {
+    let rifle = Weapon::builder("lasgun").damage(3).accuracy(0.7).range(8).damage_type(DamageType::Energy).build();
+    let smite = AbilityBuilder::new("smite", AbilityType::PsychicBlast)
+        .damage(4, DamageType::Warp)
+        .range(5)
+        .cooldown(2)
+        .status(EffectType::Burning, 2)
+        .build();
+    let mut sergeant = Unit::builder("sgt", UnitType::Guardsman, Faction::Imperial)
+        .name("Sergeant Harker")
+        .max_health(14)
+        .max_action(3)
+        .agility(4)
+        .weapon(rifle)
+        .ability(smite)
+        .movement(MovementType::Jump)
+        .at(2, 3)
+        .build();
+    assert_eq!(sergeant.name, "Sergeant Harker");
+    assert_eq!((sergeant.health_points, sergeant.action_points), (14, 3));
+    assert_eq!(sergeant.current_stats.agility, 4);
+    assert_eq!(sergeant.grid_position, Position { x: 2, y: 3 });
+    assert_eq!(sergeant.equipment.weapon.as_ref().unwrap().damage_type, DamageType::Energy);
+    assert_eq!(sergeant.abilities[0].effect.duration, Some(2));
+
+    let mut ork = UnitBuilder::new("o", UnitType::OrkBoy, Faction::Ork).at(3, 3).build();
+    let rifle = sergeant.equipment.weapon.clone().unwrap();
+    assert!(resolve_attack(&mut sergeant, &rifle, &mut ork, 50, 0).unwrap().hit);
+    assert_eq!(sergeant.action_points, 2);
+    assert!(ork.health_points < 10);
+}
@@ -0,0 +1,53 @@
+use gero::frontend::backend::{DrawRect, CLEAR_COLOR};
+use gero::frontend::capture::{capture_path, ClipRecorder, CAPTURE_DIR, CLIP_FPS};
+use gero::frontend::{DrawLayer, Renderer};
+use gero::ui::toasts::Toast;
+use image::{ImageFormat, Rgba, RgbaImage};
+
+#[test]
+fn capture_frame_encodes_the_presented_frame_as_png() {
+    let mut renderer = Renderer::new_headless(40, 30);
+    renderer.draw_rect(DrawRect { position: (10, 10), size: (100, 5), color: [1.0, 0.0, 0.0, 1.0], layer: DrawLayer::Ui });
+    renderer.draw_rect(DrawRect { position: (10, 10), size: (1, 1), color: [0.0, 0.0, 1.0, 0.5], layer: DrawLayer::Ui });
+    renderer.present().unwrap();
+
+    let png = renderer.capture_frame().unwrap();
+    let image = image::load_from_memory_with_format(&png, ImageFormat::Png).unwrap().to_rgba8();
+    assert_eq!(image.dimensions(), (40, 30));
+    let clear = Rgba(CLEAR_COLOR.map(|c| (c * 255.0).round() as u8));
+    assert_eq!(*image.get_pixel(0, 0), clear);
+    // Clipped to the frame, blended back to front.
+    assert_eq!(*image.get_pixel(39, 12), Rgba([255, 0, 0, 255]));
+    assert_eq!(*image.get_pixel(10, 10), Rgba([128, 0, 128, 255]));
+    assert_eq!(*image.get_pixel(10, 15), clear);
+}
+
+#[test]
+fn clips_keep_the_last_seconds_at_the_clip_rate() {
+    let mut clips = ClipRecorder::new(1);
+    assert!(clips.encode_gif().is_err());
+    assert!(clips.due(0.0));
+    assert!(!clips.due(0.5 / CLIP_FPS as f32));
+    assert!(clips.due(0.5 / CLIP_FPS as f32));
+
+    for shade in 0..CLIP_FPS + 5 {
+        clips.push(RgbaImage::from_pixel(4, 4, Rgba([shade as u8 * 10, 0, 0, 255])));
+    }
+    assert_eq!(clips.len(), CLIP_FPS as usize);
+
+    let gif = clips.encode_gif().unwrap();
+    assert_eq!(image::guess_format(&gif).unwrap(), ImageFormat::Gif);
+    let first = image::load_from_memory_with_format(&gif, ImageFormat::Gif).unwrap().to_rgba8();
+    assert_eq!(first.get_pixel(0, 0)[0], 50);
+}
+
+#[test]
+fn captures_are_named_and_announced() {
+    let path = capture_path("screenshot", "png");
+    assert!(path.starts_with(&format!("{}/screenshot-", CAPTURE_DIR)) && path.ends_with(".png"));
+
+    let toast = Toast::capture("toast.screenshot", &path, &Ok(()));
+    assert_eq!((toast.heading.as_str(), toast.body.as_str()), ("toast.capture", path.as_str()));
+    let failed = Toast::capture("toast.clip", &path, &Err(std::io::Error::other("disk full")));
+    assert_eq!((failed.heading.as_str(), failed.body.as_str()), ("toast.capture_failed", "disk full"));
+}
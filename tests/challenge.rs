@@ -0,0 +1,103 @@
+use gero::challenge::{ChallengeMission, ChallengeRun, Leaderboard, POINTS_PER_HP, POINTS_PER_ROUND};
+use gero::combat::CombatError;
+use gero::command::Command;
+use gero::simulation::BattleOutcome;
+
+fn challenge() -> ChallengeMission {
+    ChallengeMission::load("assets/challenges/hold_the_bridge.json").unwrap()
+}
+
+/// The shipped challenge with sharpshooters facing orks on their last legs.
+fn easy_challenge() -> ChallengeMission {
+    let mut challenge = challenge();
+    for unit in &mut challenge.squad {
+        unit.base_stats.accuracy = 100;
+    }
+    for unit in &mut challenge.enemies {
+        unit.base_stats.max_health = 1;
+    }
+    challenge
+}
+
+/// Shoot the first standing enemy, then end the turn, until it's over.
+fn play(run: &mut ChallengeRun) {
+    while run.outcome().is_none() {
+        let attacker_id = run.encounter.turn_order.current_unit_id.clone().unwrap();
+        let target = run.encounter.enemy_units().find(|u| u.health_points > 0).map(|u| u.id.clone());
+        if let Some(target_id) = target {
+            let _ = run.execute(Command::Attack { attacker_id, target_id });
+        }
+        if run.outcome().is_none() {
+            run.execute(Command::EndTurn).unwrap();
+        }
+    }
+}
+
+fn snapshot(run: &ChallengeRun) -> Vec<(String, i32, usize, usize)> {
+    run.encounter.units.iter().map(|u| (u.id.clone(), u.health_points, u.grid_position.x, u.grid_position.y)).collect()
+}
+
+#[test]
+fn challenges_play_out_the_same_for_the_same_commands() {
+    let challenge = challenge();
+    let mut run = challenge.start();
+    let current = run.encounter.turn_order.current_unit_id.clone().unwrap();
+    assert!(challenge.squad.iter().any(|u| u.id == current));
+
+    let mut again = challenge.start();
+    for _ in 0..4 {
+        run.execute(Command::EndTurn).unwrap();
+        again.execute(Command::EndTurn).unwrap();
+    }
+    assert_eq!(snapshot(&run), snapshot(&again));
+    assert_eq!(run.encounter.rng, again.encounter.rng);
+
+    // Idling past par ends the attempt without a score.
+    while run.outcome().is_none() {
+        run.execute(Command::EndTurn).unwrap();
+    }
+    assert_ne!(run.outcome(), Some(BattleOutcome::PlayerVictory));
+    assert!(run.score().is_none());
+    assert_eq!(run.execute(Command::EndTurn), Err(CombatError::WrongPhase));
+    let data = std::fs::read_to_string("assets/challenges/hold_the_bridge.json").unwrap();
+    let err = ChallengeMission::from_json(&data.replace("\"par_rounds\": 6", "\"par_rounds\": 0")).unwrap_err();
+    assert!(err.to_string().contains("has no par"));
+}
+
+#[test]
+fn wins_are_scored_on_rounds_and_health_left() {
+    let challenge = easy_challenge();
+    let mut run = challenge.start();
+    play(&mut run);
+    let score = run.score().unwrap();
+    assert_eq!(score.hp_remaining, 22);
+    assert_eq!(score.points, (challenge.par_rounds + 1 - score.rounds) * POINTS_PER_ROUND + 22 * POINTS_PER_HP);
+    assert_eq!(challenge.verify(&run.encounter.command_log), Ok(score));
+    assert_eq!(challenge.verify(&[Command::EndTurn]), Err("challenge not won"));
+}
+
+#[test]
+fn the_leaderboard_keeps_verified_scores_in_order() {
+    let challenge = easy_challenge();
+    let mut run = challenge.start();
+    play(&mut run);
+    let entry = run.entry("hale").unwrap();
+
+    let mut board = Leaderboard::default();
+    let mut forged = entry.clone();
+    forged.score.points += 1;
+    assert_eq!(board.submit(&challenge, forged), Err("score does not match replay"));
+    assert_eq!(board.submit(&challenge, entry.clone()), Ok(Some(0)));
+    let mut second = entry.clone();
+    second.player = "voss".into();
+    assert_eq!(board.submit(&challenge, second), Ok(Some(1)));
+    assert_eq!(board.entries("hold_the_bridge")[0].player, "hale");
+    assert!(board.entries("other").is_empty());
+
+    let dir = std::env::temp_dir().join("gero_leaderboard_test");
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("leaderboard.json");
+    board.save(&path).unwrap();
+    assert_eq!(Leaderboard::load(&path).unwrap(), board);
+    assert_eq!(Leaderboard::load(dir.join("missing.json")).unwrap(), Leaderboard::default());
+}
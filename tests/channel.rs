@@ -0,0 +1,114 @@
+use gero::builders::{AbilityBuilder, UnitBuilder, WeaponBuilder};
+use gero::channel::{AbilityState, Channel};
+use gero::combat::{CombatEncounter, CombatError, CombatEvent};
+use gero::command::Command;
+use gero::damage::DamageType;
+use gero::grid::GridMap;
+use gero::models::{Ability, AbilityType, EffectType, Faction, Position, StatusEffect, UnitType};
+
+fn storm(channel: Channel) -> Ability {
+    AbilityBuilder::new("storm", AbilityType::PsychicBlast)
+        .damage(3, DamageType::Warp)
+        .range(6)
+        .channel(channel)
+        .build()
+}
+
+fn unit(id: &str, faction: Faction, x: usize) -> UnitBuilder {
+    UnitBuilder::new(id, UnitType::Guardsman, faction).max_health(20).at(x, 0)
+}
+
+/// A psyker at one end of the map and an ork at the other; the psyker
+/// acts first.
+fn encounter(channel: Channel) -> CombatEncounter {
+    let psyker = unit("psyker", Faction::Imperial, 0).ability(storm(channel)).build();
+    let gun = WeaponBuilder::new("gun").damage(5).range(6).accuracy(1.0).critical_chance(0.0).build();
+    let ork = unit("ork", Faction::Ork, 5).weapon(gun).build();
+    let mut encounter = CombatEncounter::new(vec![psyker], vec![ork], GridMap::new(6, 1), None);
+    encounter.start_turn();
+    encounter
+}
+
+fn cast() -> Command {
+    Command::UseAbility { user_id: "psyker".into(), ability_index: 0, target_ids: vec!["ork".into()] }
+}
+
+/// End the current turn and play through the next unit's, then start the
+/// psyker's again.
+fn next_round(encounter: &mut CombatEncounter) {
+    encounter.execute(Command::EndTurn).unwrap();
+    encounter.start_turn();
+    encounter.end_turn();
+    encounter.start_turn();
+}
+
+fn hp(encounter: &CombatEncounter, id: &str) -> i32 {
+    encounter.unit_by_id(id).unwrap().health_points
+}
+
+#[test]
+fn charged_abilities_go_off_after_their_turns() {
+    let mut encounter = encounter(Channel::Charged { turns: 2 });
+    encounter.execute(cast()).unwrap();
+    assert_eq!(hp(&encounter, "ork"), 20);
+    assert!(encounter.unit_by_id("psyker").unwrap().is_charging());
+
+    next_round(&mut encounter);
+    assert_eq!(hp(&encounter, "ork"), 20);
+    let psyker = encounter.unit_by_id("psyker").unwrap();
+    assert_eq!(psyker.action_points, 0);
+    let refused = Command::Move { unit_id: "psyker".into(), destination: Position { x: 1, y: 0 } };
+    assert_eq!(encounter.execute(refused), Err(CombatError::AbilityCharging));
+
+    next_round(&mut encounter);
+    assert_eq!(hp(&encounter, "ork"), 17);
+    assert_eq!(encounter.unit_by_id("psyker").unwrap().ability_state, None);
+}
+
+#[test]
+fn sustained_abilities_repeat_each_turn() {
+    let mut encounter = encounter(Channel::Sustained { turns: 2 });
+    encounter.execute(cast()).unwrap();
+    assert_eq!(hp(&encounter, "ork"), 17);
+    let state = encounter.unit_by_id("psyker").unwrap().ability_state.clone().unwrap();
+    assert_eq!(state, AbilityState::Channelling { ability_id: "storm".into(), target_ids: vec!["ork".into()], turns_left: 2 });
+
+    next_round(&mut encounter);
+    assert_eq!(hp(&encounter, "ork"), 14);
+    next_round(&mut encounter);
+    assert_eq!(hp(&encounter, "ork"), 11);
+    assert_eq!(encounter.unit_by_id("psyker").unwrap().ability_state, None);
+    next_round(&mut encounter);
+    assert_eq!(hp(&encounter, "ork"), 11);
+}
+
+#[test]
+fn damage_breaks_the_channel() {
+    let mut encounter = encounter(Channel::Charged { turns: 1 });
+    encounter.execute(cast()).unwrap();
+    encounter.execute(Command::EndTurn).unwrap();
+    encounter.start_turn();
+    encounter.execute(Command::Attack { attacker_id: "ork".into(), target_id: "psyker".into() }).unwrap();
+    assert!(hp(&encounter, "psyker") < 20);
+    assert_eq!(encounter.unit_by_id("psyker").unwrap().ability_state, None);
+    assert!(encounter.drain_events().contains(&CombatEvent::ChannelInterrupted {
+        unit_id: "psyker".into(),
+        ability_id: "storm".into(),
+    }));
+
+    encounter.end_turn();
+    encounter.start_turn();
+    assert_eq!(hp(&encounter, "ork"), 20);
+}
+
+#[test]
+fn stuns_break_the_channel() {
+    let mut encounter = encounter(Channel::Sustained { turns: 3 });
+    encounter.execute(cast()).unwrap();
+    encounter.execute(Command::EndTurn).unwrap();
+    encounter.start_turn();
+    let stun = StatusEffect { effect_type: EffectType::Stun, remaining_turns: 1, magnitude: 0 };
+    encounter.unit_by_id_mut("psyker").unwrap().status_effects.push(stun);
+    encounter.execute(Command::EndTurn).unwrap();
+    assert_eq!(encounter.unit_by_id("psyker").unwrap().ability_state, None);
+}
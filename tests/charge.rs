@@ -0,0 +1,117 @@
+use gero::combat::{CombatEncounter, CombatError};
+use gero::command::Command;
+use gero::damage::DamageType;
+use gero::doctrine::Doctrine;
+use gero::grid::GridMap;
+use gero::models::{Faction, Position, Unit, UnitType, Weapon, WeaponTier};
+use gero::rng::GameRng;
+use gero::stance::Stance;
+
+fn blade() -> Weapon {
+    Weapon {
+        id: "choppa".into(),
+        name: "Choppa".into(),
+        tier: WeaponTier::Basic,
+        damage: 3,
+        accuracy: 1.0,
+        range: 1,
+        armor_piercing: None,
+        action_point_cost: 1,
+        critical_chance: 0.0,
+        abilities_granted: Vec::new(),
+        damage_type: DamageType::Kinetic,
+        forced_move: None,
+        indirect: None,
+    }
+}
+
+fn fighter(id: &str, unit_type: UnitType, faction: Faction, x: usize) -> Unit {
+    let mut unit = Unit::new(id, id, unit_type, faction);
+    unit.base_stats.agility = 6;
+    unit.base_stats.max_health = 30;
+    unit.equip_weapon(blade());
+    unit.health_points = 30;
+    unit.grid_position = Position { x, y: 0 };
+    unit
+}
+
+fn charge(attacker: &str, target: &str) -> Command {
+    Command::Charge { attacker_id: attacker.into(), target_id: target.into() }
+}
+
+fn damage_taken(encounter: &CombatEncounter, id: &str) -> i32 {
+    30 - encounter.unit_by_id(id).unwrap().health_points
+}
+
+#[test]
+fn charges_run_in_and_hit_harder() {
+    let players = vec![fighter("guard", UnitType::Guardsman, Faction::Imperial, 0)];
+    let enemies = vec![fighter("ork", UnitType::OrkBoy, Faction::Ork, 4)];
+    let mut encounter = CombatEncounter::new(players, enemies, GridMap::new(8, 2), None);
+    // Stay clear of a critical hit.
+    encounter.rng = GameRng::new((0..).find(|&s| GameRng::new(s).roll_d100() > 10).unwrap());
+    encounter.start_turn();
+    encounter.execute(charge("guard", "ork")).unwrap();
+    assert_eq!(encounter.unit_by_id("guard").unwrap().grid_position, Position { x: 3, y: 0 });
+    // One AP for the run in, one for the blow.
+    assert_eq!(encounter.unit_by_id("guard").unwrap().action_points, 0);
+    // Weapon damage plus the charge bonus.
+    assert_eq!(damage_taken(&encounter, "ork"), 4);
+    assert!(!encounter.can_undo());
+    assert_eq!(encounter.execute(charge("guard", "ork")), Err(CombatError::TooCloseToCharge));
+}
+
+#[test]
+fn charges_need_a_run_up_a_blade_and_a_path() {
+    let mut gunner = fighter("gunner", UnitType::Guardsman, Faction::Imperial, 0);
+    gunner.equipment.weapon.as_mut().unwrap().range = 6;
+    let players = vec![fighter("guard", UnitType::Guardsman, Faction::Imperial, 0), gunner];
+    let enemies = vec![fighter("ork", UnitType::OrkBoy, Faction::Ork, 7)];
+    let mut encounter = CombatEncounter::new(players, enemies, GridMap::new(8, 2), None);
+    assert_eq!(encounter.execute(charge("guard", "ork")), Err(CombatError::OutOfRange));
+    assert_eq!(encounter.execute(charge("gunner", "ork")), Err(CombatError::NoMeleeWeapon));
+    assert_eq!(encounter.unit_by_id("guard").unwrap().grid_position, Position { x: 0, y: 0 });
+}
+
+#[test]
+fn orks_charge_best_and_the_ai_uses_it() {
+    let players = vec![fighter("guard", UnitType::Guardsman, Faction::Imperial, 0)];
+    let enemies = vec![fighter("ork", UnitType::OrkBoy, Faction::Ork, 4)];
+    let mut encounter = CombatEncounter::new(players, enemies, GridMap::new(8, 2), None);
+    encounter.apply_doctrine(Doctrine::Waaagh);
+    encounter.turn_order.current_unit_id = Some("ork".into());
+    assert!(encounter.ai_should_charge("ork", "guard"));
+    encounter.ai_take_action(50);
+    assert_eq!(encounter.unit_by_id("ork").unwrap().grid_position, Position { x: 1, y: 0 });
+    // Weapon, charge, Ork charge and Waaagh! bonuses.
+    assert_eq!(damage_taken(&encounter, "guard"), 3 + 1 + 1 + 2);
+}
+
+#[test]
+fn a_charger_shot_past_zero_on_the_way_in_lands_no_blow() {
+    let mut watcher = fighter("watcher", UnitType::Guardsman, Faction::Ork, 5);
+    watcher.grid_position.y = 1;
+    watcher.equipment.weapon.as_mut().unwrap().range = 3;
+    watcher.action_points = 1;
+    let mut guard = fighter("guard", UnitType::Guardsman, Faction::Imperial, 0);
+    guard.health_points = 1;
+    let enemies = vec![fighter("ork", UnitType::OrkBoy, Faction::Ork, 4), watcher];
+    let mut encounter = CombatEncounter::new(vec![guard], enemies, GridMap::new(8, 2), None);
+    encounter.take_stance("watcher", Stance::Overwatch).unwrap();
+    // The first roll is kept for the blow; the overwatch shot hits.
+    encounter.rng = GameRng::new(
+        (0..)
+            .find(|&s| {
+                let mut rng = GameRng::new(s);
+                rng.roll_d100();
+                rng.roll_d100() <= 30
+            })
+            .unwrap(),
+    );
+    encounter.start_turn();
+    encounter.execute(charge("guard", "ork")).unwrap();
+    assert_eq!(encounter.unit_by_id("guard").unwrap().grid_position, Position { x: 3, y: 0 });
+    assert!(encounter.unit_by_id("guard").unwrap().health_points < 0);
+    assert_eq!(damage_taken(&encounter, "ork"), 0);
+    assert!(!encounter.can_undo());
+}
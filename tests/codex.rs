@@ -0,0 +1,93 @@
+use gero::builders::UnitBuilder;
+use gero::campaign::Campaign;
+use gero::codex::{Codex, CodexCategory, CodexFilter};
+use gero::combat::CombatEncounter;
+use gero::content::ContentDatabase;
+use gero::flow::GameFlow;
+use gero::grid::GridMap;
+use gero::input::GameAction;
+use gero::localization::Localizer;
+use gero::models::{EffectType, Faction, StatusEffect, UnitType};
+use gero::simulation::BattleOutcome;
+use gero::state::GameState;
+use gero::ui::UiManager;
+
+fn codex() -> Codex {
+    Codex::from_content(&ContentDatabase::load("assets/content/items.json").unwrap())
+}
+
+#[test]
+fn the_codex_covers_the_shipped_content_in_both_languages() {
+    let codex = codex();
+    for category in CodexCategory::ALL {
+        assert!(codex.entries.iter().any(|e| e.category == category), "{category:?} is empty");
+    }
+    let lasgun = codex.get(CodexCategory::Weapon, "lasgun").unwrap();
+    assert_eq!(lasgun.name, "Lasgun");
+    assert!(lasgun.stats.contains(&("codex.stat.range", "8".into())));
+    assert_eq!(codex.get(CodexCategory::Ability, "rally_cry").unwrap().lore, "Steadies nearby troopers.");
+
+    for language in ["en", "de"] {
+        let loc = Localizer::new(language).unwrap();
+        let keys = codex.entries.iter().flat_map(|e| [&e.name, &e.lore]).filter(|text| text.starts_with("codex."));
+        for key in keys {
+            assert_ne!(&loc.get(key), key, "{language} lacks {key}");
+        }
+    }
+}
+
+#[test]
+fn searching_only_finds_unlocked_entries() {
+    let codex = codex();
+    let loc = Localizer::new("en").unwrap();
+    let mut unlocked = Default::default();
+    let weapons = CodexFilter { category: Some(CodexCategory::Weapon), query: String::new() };
+    assert_eq!(codex.search(&weapons, &unlocked, &loc).len(), 3);
+    let marine = CodexFilter { category: None, query: "MARINE".into() };
+    assert!(codex.search(&marine, &unlocked, &loc).is_empty());
+
+    let mut campaign = Campaign::new();
+    let mut traitor = UnitBuilder::new("traitor", UnitType::ChaosMarine, Faction::Chaos).build();
+    traitor.status_effects.push(StatusEffect { effect_type: EffectType::Burning, remaining_turns: 1, magnitude: 1 });
+    assert_eq!(campaign.record_sightings([&traitor]), 2);
+    assert_eq!(campaign.record_sightings([&traitor]), 0);
+    unlocked = campaign.codex;
+    let found: Vec<&str> = codex.search(&marine, &unlocked, &loc).iter().map(|e| e.id.as_str()).collect();
+    assert_eq!(found, ["chaos_marine"]);
+}
+
+#[test]
+fn battles_unlock_entries_shown_on_the_codex_screen() {
+    let mut flow = GameFlow::new(GameState::new(Vec::new()), UiManager::new(1280, 720, Vec::new(), Vec::new()));
+    let guard = UnitBuilder::new("guard", UnitType::Guardsman, Faction::Imperial).build();
+    let ork = UnitBuilder::new("ork", UnitType::OrkBoy, Faction::Ork).build();
+    let battle = CombatEncounter::new(vec![guard], vec![ork], GridMap::new(4, 4), None);
+    flow.finish_battle(&battle, BattleOutcome::PlayerVictory);
+    flow.summary = None;
+
+    let loc = Localizer::new("en").unwrap();
+    flow.open_codex(codex(), &loc);
+    assert!(flow.is_paused());
+    let screen = flow.codex.as_mut().unwrap();
+    screen.type_text("ork");
+    assert_eq!(screen.visible().map(|e| e.id.as_str()).collect::<Vec<_>>(), ["ork_boy"]);
+    screen.erase();
+    screen.erase();
+    screen.erase();
+
+    // The filter row steps through the sections.
+    flow.handle_action(GameAction::Activate);
+    let screen = flow.codex.as_ref().unwrap();
+    assert_eq!(screen.filter.category, Some(CodexCategory::UnitType));
+    assert!(screen.visible().all(|e| e.category == CodexCategory::UnitType));
+    flow.handle_action(GameAction::SelectDown);
+    let screen = flow.codex.as_ref().unwrap();
+    let first = screen.current().unwrap();
+    assert_eq!(first.id, "chaos_marine");
+    assert!(!screen.is_unlocked(first));
+    for _ in 0..20 {
+        flow.handle_action(GameAction::SelectDown);
+    }
+    flow.handle_action(GameAction::Activate);
+    assert!(flow.codex.is_none());
+}
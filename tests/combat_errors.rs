@@ -0,0 +1,119 @@
+use gero::combat::{resolve_attack, CombatEncounter, CombatError};
+use gero::command::Command;
+use gero::damage::DamageType;
+use gero::flow::GameFlow;
+use gero::frontend::Renderer;
+use gero::grid::{GridMap, TerrainType};
+use gero::localization::Localizer;
+use gero::models::{Ability, AbilityType, Faction, Position, Unit, UnitType, Weapon};
+use gero::state::GameState;
+use gero::ui::UiManager;
+
+fn rifleman(id: &str, faction: Faction, x: usize) -> Unit {
+    Unit::builder(id, UnitType::Guardsman, faction)
+        .weapon(Weapon::builder("lasgun").damage(3).accuracy(1.0).range(4).build())
+        .ability(Ability::builder("bolt", AbilityType::PsychicBlast).damage(2, DamageType::Energy).range(2).cooldown(2).build())
+        .at(x, 0)
+        .build()
+}
+
+fn encounter(enemy_x: usize) -> CombatEncounter {
+    let player = rifleman("p", Faction::Imperial, 0);
+    let enemy = rifleman("e", Faction::Ork, enemy_x);
+    CombatEncounter::new(vec![player], vec![enemy], GridMap::new(8, 3), None)
+}
+
+fn attack() -> Command {
+    Command::Attack { attacker_id: "p".into(), target_id: "e".into() }
+}
+
+#[test]
+fn attacks_without_action_points_are_refused() {
+    let mut attacker = rifleman("p", Faction::Imperial, 0);
+    let mut defender = rifleman("e", Faction::Ork, 1);
+    let weapon = attacker.equipment.weapon.clone().unwrap();
+    attacker.action_points = 0;
+    assert_eq!(resolve_attack(&mut attacker, &weapon, &mut defender, 1, 0).err(), Some(CombatError::InsufficientAp));
+    assert_eq!(defender.health_points, defender.current_stats.max_health);
+}
+
+#[test]
+fn attacks_check_range_and_line_of_sight() {
+    let mut far = encounter(6);
+    assert_eq!(far.execute(attack()), Err(CombatError::OutOfRange));
+
+    let mut walled = encounter(3);
+    walled.battlefield.set_terrain(&Position { x: 1, y: 0 }, TerrainType::Blocked);
+    walled.battlefield.set_terrain(&Position { x: 2, y: 0 }, TerrainType::Blocked);
+    assert_eq!(walled.execute(attack()), Err(CombatError::NoLineOfSight));
+    assert_eq!(walled.unit_by_id("p").unwrap().action_points, walled.unit_by_id("p").unwrap().current_stats.max_action);
+
+    let unknown = Command::Attack { attacker_id: "p".into(), target_id: "nobody".into() };
+    assert_eq!(encounter(2).execute(unknown), Err(CombatError::InvalidTarget));
+}
+
+#[test]
+fn abilities_report_range_and_cooldown() {
+    let bolt = Command::UseAbility { user_id: "p".into(), ability_index: 0, target_ids: vec!["e".into()] };
+    assert_eq!(encounter(5).execute(bolt.clone()), Err(CombatError::OutOfRange));
+
+    let mut enc = encounter(2);
+    enc.execute(bolt.clone()).unwrap();
+    assert_eq!(enc.execute(bolt), Err(CombatError::OnCooldown));
+}
+
+#[test]
+fn refused_queued_commands_pop_up_as_toasts() {
+    let mut enc = encounter(6);
+    let mut flow = GameFlow::new(GameState::from_encounter(&enc), UiManager::new(1280, 720, Vec::new(), Vec::new()));
+    enc.queue_action(attack());
+    flow.present_actions(0.0, &mut enc, &mut Renderer::new_headless(1280, 720));
+    assert!(enc.actions.rejected.is_empty());
+    let (toast, _) = flow.ui.toasts.active.back().unwrap();
+    assert_eq!(toast.title, CombatError::OutOfRange.key());
+}
+
+#[test]
+fn every_error_has_a_message() {
+    let loc = Localizer::new("en").unwrap();
+    let errors = [
+        CombatError::InsufficientAp,
+        CombatError::OnCooldown,
+        CombatError::OutOfRange,
+        CombatError::InvalidTarget,
+        CombatError::NoLineOfSight,
+        CombatError::WrongPhase,
+        CombatError::UnknownUnit,
+        CombatError::NotControlled,
+        CombatError::NoWeapon,
+        CombatError::Pinned,
+        CombatError::Unreachable,
+        CombatError::AbilityCharging,
+        CombatError::Embarked,
+        CombatError::InvalidAbility,
+        CombatError::NeedsUnitTarget,
+        CombatError::AlreadyActed,
+        CombatError::NobodyToWaitFor,
+        CombatError::NoMeleeWeapon,
+        CombatError::TooCloseToCharge,
+        CombatError::MeleeCantSuppress,
+        CombatError::NoGrenade,
+        CombatError::WeaponTooLight,
+        CombatError::NothingToPickUp,
+        CombatError::NotAVehicle,
+        CombatError::CantBoard,
+        CombatError::VehicleFull,
+        CombatError::NotAboard,
+        CombatError::NoSuchHardpoint,
+        CombatError::HardpointFired,
+        CombatError::OutsideArc,
+        CombatError::NotIndirect,
+        CombatError::TargetTooClose,
+        CombatError::FiresAtCells,
+        CombatError::TutorialStep,
+        CombatError::UnknownTemplate,
+    ];
+    for error in errors {
+        assert_ne!(loc.get(error.key()), error.key(), "{error:?} has no message");
+    }
+}
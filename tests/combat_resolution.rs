@@ -1,3 +1,4 @@
+use gero::damage::DamageType;
 use gero::models::{Unit, UnitType, Faction, Weapon, WeaponTier};
 use gero::combat::resolve_attack;
 
@@ -18,6 +19,9 @@ fn setup_units() -> (Unit, Unit, Weapon) {
         action_point_cost: 1,
         critical_chance: 0.0,
         abilities_granted: Vec::new(),
+        damage_type: DamageType::Kinetic,
+        forced_move: None,
+        indirect: None,
     };
     (attacker, defender, weapon)
 }
@@ -26,7 +30,7 @@ fn setup_units() -> (Unit, Unit, Weapon) {
 fn attack_misses_with_low_hit_chance() {
     let (mut a, mut d, w) = setup_units();
     // High roll so it should miss
-    let res = resolve_attack(&mut a, &w, &mut d, 99, 0);
+    let res = resolve_attack(&mut a, &w, &mut d, 99, 0).unwrap();
     assert!(!res.hit);
     assert_eq!(res.damage, 0);
     // action points spent even on miss
@@ -39,7 +43,7 @@ fn critical_hit_doubles_damage() {
     w.damage = 2;
     let starting_hp = d.health_points;
     // roll <=10 triggers critical
-    let res = resolve_attack(&mut a, &w, &mut d, 5, 0);
+    let res = resolve_attack(&mut a, &w, &mut d, 5, 0).unwrap();
     assert!(res.hit);
     assert_eq!(d.health_points, starting_hp - res.damage);
     assert!(res.damage > w.damage); // should be doubled
@@ -0,0 +1,81 @@
+use gero::builders::{UnitBuilder, WeaponBuilder};
+use gero::combat::CombatEncounter;
+use gero::commander::{Commander, SquadRole};
+use gero::grid::GridMap;
+use gero::models::{Faction, Position, Unit, UnitType};
+use gero::profile::Difficulty;
+
+fn at(x: usize, y: usize) -> Position {
+    Position { x, y }
+}
+
+fn guard(id: &str, x: usize, hp: i32) -> Unit {
+    let mut guard = UnitBuilder::new(id, UnitType::Guardsman, Faction::Imperial).max_health(20).at(x, 0).build();
+    guard.health_points = hp;
+    guard
+}
+
+/// An ork at `x` with a gun reaching across the whole map.
+fn shooter(id: &str, x: usize) -> Unit {
+    let gun = WeaponBuilder::new("gun").damage(3).range(20).accuracy(1.0).critical_chance(0.0).build();
+    UnitBuilder::new(id, UnitType::OrkBoy, Faction::Ork).weapon(gun).at(x, 0).build()
+}
+
+fn battle(guards: Vec<Unit>, orks: Vec<Unit>) -> CombatEncounter {
+    let mut encounter = CombatEncounter::new(guards, orks, GridMap::new(16, 1), None);
+    encounter.balance.critical_multiplier = 1;
+    encounter
+}
+
+/// Let `ork` take its turn, returning the hit points of `ids` after.
+fn act(encounter: &mut CombatEncounter, ork: &str, ids: &[&str]) -> Vec<i32> {
+    encounter.turn_order.current_unit_id = Some(ork.into());
+    encounter.unit_by_id_mut(ork).unwrap().action_points = 1;
+    encounter.ai_take_action(50);
+    ids.iter().map(|id| encounter.unit_by_id(id).unwrap().health_points).collect()
+}
+
+#[test]
+fn squads_focus_fire_on_the_weakest_in_sight() {
+    let guards = vec![guard("near", 2, 20), guard("weak", 4, 5)];
+    let mut encounter = battle(guards.clone(), vec![shooter("ork", 0)]);
+    assert_eq!(act(&mut encounter, "ork", &["near", "weak"]), [17, 5]);
+
+    let mut encounter = battle(guards, vec![shooter("ork", 0)]);
+    encounter.set_commander(Faction::Ork, Commander::default());
+    assert_eq!(encounter.commanders[&Faction::Ork].focus.as_deref(), Some("weak"));
+    assert_eq!(act(&mut encounter, "ork", &["near", "weak"]), [20, 2]);
+}
+
+#[test]
+fn squads_share_what_they_spot() {
+    let mut encounter = battle(vec![guard("guard", 12, 20)], vec![shooter("ork", 0), shooter("scout", 3)]);
+    encounter.difficulty = Difficulty::Hard;
+    encounter.set_commander(Faction::Ork, Commander::default());
+    // Nobody has seen the guard, so the ork holds its fire.
+    assert_eq!(act(&mut encounter, "ork", &["guard"]), [20]);
+
+    encounter.unit_by_id_mut("scout").unwrap().grid_position = at(8, 0);
+    act(&mut encounter, "scout", &[]);
+    assert!(encounter.commanders[&Faction::Ork].spotted.contains_key("guard"));
+    assert_eq!(act(&mut encounter, "ork", &["guard"]), [14]);
+}
+
+#[test]
+fn commanders_hand_out_roles() {
+    let brawler = UnitBuilder::new("brawler", UnitType::OrkBoy, Faction::Ork).at(6, 0).build();
+    let mut runner = shooter("runner", 9);
+    runner.base_stats.agility = 6;
+    runner.recalculate_stats();
+    let orks = vec![shooter("ork", 0), runner, brawler];
+    let mut encounter = battle(vec![guard("guard", 3, 20)], orks);
+    encounter.set_commander(Faction::Ork, Commander::new(vec![at(12, 0)]));
+    let commander = &encounter.commanders[&Faction::Ork];
+    assert_eq!(commander.role("ork"), Some(SquadRole::FireSupport));
+    assert_eq!(commander.role("brawler"), Some(SquadRole::Flanker));
+    assert_eq!(commander.role("runner"), Some(SquadRole::ObjectiveRunner));
+
+    // The runner heads for the objective instead of shooting.
+    assert_eq!(act(&mut encounter, "runner", &["guard"]), [20]);
+    assert!(encounter.unit_by_id("runner").unwrap().grid_position.x > 9);
+}
@@ -0,0 +1,138 @@
+use gero::builders::AbilityBuilder;
+use gero::combat::{CombatEncounter, CombatError};
+use gero::command::Command;
+use gero::damage::DamageType;
+use gero::grid::GridMap;
+use gero::models::{Unit, UnitType, Faction, Weapon, WeaponTier, Position, Ability, AbilityType, AbilityEffect, AnimationType};
+use gero::rng::GameRng;
+
+fn weapon() -> Weapon {
+    Weapon {
+        id: "w".into(),
+        name: "Lasgun".into(),
+        tier: WeaponTier::Basic,
+        damage: 3,
+        accuracy: 0.6,
+        range: 8,
+        armor_piercing: None,
+        action_point_cost: 1,
+        critical_chance: 0.1,
+        abilities_granted: Vec::new(),
+        damage_type: DamageType::Kinetic,
+        forced_move: None,
+        indirect: None,
+    }
+}
+
+fn encounter() -> CombatEncounter {
+    let mut player = Unit::new("p", "P", UnitType::Guardsman, Faction::Imperial);
+    player.base_stats.agility = 6;
+    player.recalculate_stats();
+    player.equipment.weapon = Some(weapon());
+    let mut enemy = Unit::new("e", "E", UnitType::OrkBoy, Faction::Ork);
+    enemy.grid_position = Position { x: 5, y: 0 };
+    CombatEncounter::new(vec![player], vec![enemy], GridMap::new(8, 8), None)
+}
+
+#[test]
+fn moves_can_be_undone_until_an_attack_commits() {
+    let mut enc = encounter();
+    enc.execute(Command::Move { unit_id: "p".into(), destination: Position { x: 2, y: 0 } }).unwrap();
+    enc.execute(Command::Move { unit_id: "p".into(), destination: Position { x: 2, y: 2 } }).unwrap();
+    assert!(enc.undo());
+    assert_eq!(enc.unit_by_id("p").unwrap().grid_position, Position { x: 2, y: 0 });
+    assert_eq!(enc.command_log.len(), 1);
+
+    enc.execute(Command::Attack { attacker_id: "p".into(), target_id: "e".into() }).unwrap();
+    assert!(!enc.can_undo());
+    assert!(!enc.undo());
+    assert_eq!(enc.unit_by_id("p").unwrap().grid_position, Position { x: 2, y: 0 });
+}
+
+#[test]
+fn failed_commands_are_not_logged() {
+    let mut enc = encounter();
+    assert!(enc.execute(Command::Move { unit_id: "p".into(), destination: Position { x: 7, y: 7 } }).is_err());
+    assert!(enc.execute(Command::Attack { attacker_id: "e".into(), target_id: "p".into() }).is_err());
+    assert!(enc.command_log.is_empty());
+}
+
+#[test]
+fn refused_attacks_leave_the_dice_alone() {
+    let mut enc = encounter();
+    enc.unit_by_id_mut("e").unwrap().grid_position = Position { x: 7, y: 7 };
+    let rng = enc.rng.clone();
+    let far = Command::Attack { attacker_id: "p".into(), target_id: "e".into() };
+    assert!(enc.execute(far.clone()).is_err());
+    let charge = Command::Charge { attacker_id: "p".into(), target_id: "e".into() };
+    assert!(enc.execute(charge).is_err());
+    enc.unit_by_id_mut("p").unwrap().action_points = 0;
+    enc.unit_by_id_mut("e").unwrap().grid_position = Position { x: 5, y: 0 };
+    assert!(enc.execute(far).is_err());
+    assert_eq!(enc.rng, rng);
+}
+
+#[test]
+fn replaying_the_command_log_reproduces_the_battle() {
+    let mut original = encounter();
+    original.rng = GameRng::new(5);
+    original.execute(Command::Move { unit_id: "p".into(), destination: Position { x: 1, y: 1 } }).unwrap();
+    original.execute(Command::Attack { attacker_id: "p".into(), target_id: "e".into() }).unwrap();
+    original.execute(Command::EndTurn).unwrap();
+
+    let mut copy = encounter();
+    copy.rng = GameRng::new(5);
+    copy.replay(&original.command_log.clone()).unwrap();
+    assert_eq!(copy.unit_by_id("p").unwrap().grid_position, original.unit_by_id("p").unwrap().grid_position);
+    assert_eq!(copy.unit_by_id("e").unwrap().health_points, original.unit_by_id("e").unwrap().health_points);
+    assert_eq!(copy.command_log, original.command_log);
+}
+
+#[test]
+fn abilities_are_used_through_commands() {
+    let mut enc = encounter();
+    enc.unit_by_id_mut("p").unwrap().abilities.push(Ability {
+        id: "frag".into(),
+        name: "Frag".into(),
+        ability_type: AbilityType::RangedAttack,
+        description: String::new(),
+        action_point_cost: 1,
+        cooldown: 2,
+        current_cooldown: 0,
+        range: 6,
+        area_of_effect: None,
+        effect: AbilityEffect { damage: Some(4), healing: None, buff: None, debuff: None, status_applied: None, duration: None, creates_hazard: None, alters_terrain: None, forced_move: None, damage_type: DamageType::Kinetic },
+        animation: AnimationType::AbilityCast,
+        sound_effect_key: String::new(),
+        rank: 1,
+        ranks: Vec::new(),
+        channel: None,
+    });
+    enc.execute(Command::UseAbility { user_id: "p".into(), ability_index: 0, target_ids: vec!["e".into()] }).unwrap();
+    assert_eq!(enc.unit_by_id("e").unwrap().health_points, 6);
+    assert_eq!(enc.unit_by_id("p").unwrap().abilities[0].current_cooldown, 2);
+    assert!(enc.execute(Command::UseAbility { user_id: "p".into(), ability_index: 0, target_ids: vec!["e".into()] }).is_err());
+}
+
+#[test]
+fn abilities_aimed_at_missing_or_dead_units_are_refused() {
+    let mut enc = encounter();
+    let frag = AbilityBuilder::new("frag", AbilityType::RangedAttack).range(6).damage(4, DamageType::Kinetic).build();
+    enc.unit_by_id_mut("p").unwrap().abilities.push(frag);
+    let at = |target: &str| Command::UseAbility { user_id: "p".into(), ability_index: 0, target_ids: vec![target.into()] };
+    assert_eq!(enc.execute(at("nobody")), Err(CombatError::InvalidTarget));
+    enc.unit_by_id_mut("e").unwrap().health_points = 0;
+    assert_eq!(enc.execute(at("e")), Err(CombatError::InvalidTarget));
+    let p = enc.unit_by_id("p").unwrap();
+    assert_eq!((p.action_points, p.abilities[0].current_cooldown), (p.current_stats.max_action, 0));
+}
+
+#[test]
+fn only_the_unit_whose_turn_it_is_may_act() {
+    let mut enc = encounter();
+    enc.turn_order.current_unit_id = Some("e".into());
+    let step = Command::Move { unit_id: "p".into(), destination: Position { x: 1, y: 0 } };
+    assert_eq!(enc.execute(step.clone()), Err(CombatError::WrongPhase));
+    enc.turn_order.current_unit_id = Some("p".into());
+    enc.execute(step).unwrap();
+}
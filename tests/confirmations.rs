@@ -0,0 +1,115 @@
+use gero::combat::CombatEncounter;
+use gero::command::Command;
+use gero::flow::GameFlow;
+use gero::frontend::Renderer;
+use gero::grid::GridMap;
+use gero::input::{GameAction, Gesture};
+use gero::localization::Localizer;
+use gero::models::{Accessory, Faction, Position, Unit, UnitType};
+use gero::state::GameState;
+use gero::ui::modal::ModalChoice;
+use gero::ui::{UiEvent, UiManager};
+
+fn unit(id: &str, name: &str, faction: Faction, x: usize) -> Unit {
+    let mut unit = Unit::new(id, name, UnitType::Guardsman, faction);
+    unit.grid_position = Position { x, y: 0 };
+    unit
+}
+
+fn encounter() -> CombatEncounter {
+    let mut thrower = unit("p", "Vance", Faction::Imperial, 0);
+    thrower.equipment.accessory_slots.push(Accessory::Grenade { damage: 4, aoe_radius: 1, leaves: None });
+    let ally = unit("a", "Ostrava", Faction::Imperial, 3);
+    let enemy = unit("e", "Grukk", Faction::Ork, 4);
+    let mut enc = CombatEncounter::new(vec![thrower, ally], vec![enemy], GridMap::new(8, 8), None);
+    enc.turn_order.current_unit_id = Some("p".into());
+    enc
+}
+
+fn flow() -> GameFlow {
+    GameFlow::new(GameState::new(Vec::new()), UiManager::new(1280, 720, Vec::new(), Vec::new()))
+}
+
+fn grenade_at(x: usize) -> Command {
+    Command::ThrowGrenade { thrower_id: "p".into(), accessory_index: 0, target: Position { x, y: 0 } }
+}
+
+#[test]
+fn friendly_fire_lists_allies_in_the_blast() {
+    let enc = encounter();
+    let hit: Vec<&str> = enc.friendly_fire(&grenade_at(4)).iter().map(|u| u.id.as_str()).collect();
+    assert_eq!(hit, ["a"]);
+    let hit: Vec<&str> = enc.friendly_fire(&grenade_at(1)).iter().map(|u| u.id.as_str()).collect();
+    assert_eq!(hit, ["p"]);
+    assert!(enc.friendly_fire(&grenade_at(6)).is_empty());
+    assert_eq!(enc.friendly_fire(&Command::Attack { attacker_id: "p".into(), target_id: "a".into() }).len(), 1);
+    assert!(enc.friendly_fire(&Command::Attack { attacker_id: "p".into(), target_id: "e".into() }).is_empty());
+}
+
+#[test]
+fn ending_a_turn_with_ap_left_waits_for_confirmation() {
+    let enc = encounter();
+    let mut flow = flow();
+    assert_eq!(flow.request_command(Command::EndTurn, &enc), None);
+    let loc = Localizer::new("en").unwrap();
+    let modal = flow.ui.modal.as_ref().unwrap();
+    assert_eq!(modal.message_text(&loc), "Vance still has 2 action points.");
+    assert_eq!(modal.focused, ModalChoice::Cancel);
+    assert!(flow.is_paused());
+
+    // Focus starts on cancel; move it to confirm first.
+    assert_eq!(flow.handle_action(GameAction::SelectDown), None);
+    assert_eq!(flow.handle_action(GameAction::Activate), Some(UiEvent::CommandConfirmed(Command::EndTurn)));
+    assert!(flow.ui.modal.is_none() && flow.pending_command.is_none());
+
+    flow.request_command(Command::EndTurn, &enc);
+    assert_eq!(flow.handle_action(GameAction::Activate), None);
+    assert!(flow.ui.modal.is_none() && flow.pending_command.is_none());
+}
+
+#[test]
+fn warnings_can_be_turned_off() {
+    let mut enc = encounter();
+    let mut flow = flow();
+    flow.confirmations.end_turn_with_ap = false;
+    assert_eq!(flow.request_command(Command::EndTurn, &enc), Some(Command::EndTurn));
+    flow.confirmations.friendly_fire = false;
+    assert_eq!(flow.request_command(grenade_at(4), &enc), Some(grenade_at(4)));
+
+    flow.confirmations.end_turn_with_ap = true;
+    enc.units.by_name_mut("p").unwrap().action_points = 0;
+    assert_eq!(flow.request_command(Command::EndTurn, &enc), Some(Command::EndTurn));
+    assert!(flow.ui.modal.is_none());
+}
+
+#[test]
+fn modal_buttons_answer_taps_and_hover() {
+    let enc = encounter();
+    let mut flow = flow();
+    let mut renderer = Renderer::new_headless(1280, 720);
+    flow.request_command(grenade_at(4), &enc);
+    let loc = Localizer::new("en").unwrap();
+    assert_eq!(flow.ui.modal.as_ref().unwrap().message_text(&loc), "This will hit Ostrava on your own side.");
+
+    flow.render(&mut renderer, &loc);
+    assert!(renderer.draw_log.iter().any(|d| d.sprite_id == "panel:modal"));
+    let texts: Vec<&str> = renderer.text_log.iter().map(|t| t.text.as_str()).collect();
+    assert!(texts.contains(&"Friendly fire") && texts.contains(&"Confirm") && texts.contains(&"Cancel"));
+
+    let modal = flow.ui.modal.clone().unwrap();
+    let (confirm, cancel) = (modal.confirm_button, modal.cancel_button);
+    // Taps off the buttons do nothing.
+    let outside = Gesture::Tap { x: modal.bounds.x as f32 + 2.0, y: modal.bounds.y as f32 + 2.0 };
+    assert_eq!(flow.handle_gesture(outside, &mut renderer), None);
+    assert!(flow.ui.modal.is_some());
+
+    flow.ui.hover((confirm.x + 1, confirm.y + 1));
+    assert_eq!(flow.ui.modal.as_ref().unwrap().focused, ModalChoice::Confirm);
+    let tap = Gesture::Tap { x: cancel.x as f32 + 1.0, y: cancel.y as f32 + 1.0 };
+    assert_eq!(flow.handle_gesture(tap, &mut renderer), None);
+    assert!(flow.ui.modal.is_none() && flow.pending_command.is_none());
+
+    flow.request_command(grenade_at(4), &enc);
+    assert!(flow.cancel_modal());
+    assert!(!flow.cancel_modal());
+}
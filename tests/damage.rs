@@ -0,0 +1,97 @@
+use gero::combat::{resolve_attack, CombatEncounter};
+use gero::content::{ContentDatabase, Item};
+use gero::damage::{mitigate, DamageType, BURN_TURNS, POISON_DAMAGE, POISON_TURNS};
+use gero::grid::GridMap;
+use gero::models::{EffectType, Faction, Unit, UnitType, Weapon, WeaponTier};
+
+fn weapon(damage_type: DamageType) -> Weapon {
+    Weapon {
+        id: "w".into(),
+        name: "Gun".into(),
+        tier: WeaponTier::Basic,
+        damage: 6,
+        accuracy: 1.0,
+        range: 5,
+        armor_piercing: None,
+        action_point_cost: 1,
+        critical_chance: 0.0,
+        abilities_granted: Vec::new(),
+        damage_type,
+        forced_move: None,
+        indirect: None,
+    }
+}
+
+fn target(unit_type: UnitType) -> Unit {
+    let mut unit = Unit::new("t", "Target", unit_type, Faction::Ork);
+    unit.base_stats.toughness = 4;
+    unit.base_stats.willpower = 1;
+    unit.base_stats.max_health = 30;
+    unit.recalculate_stats();
+    unit.health_points = 30;
+    unit
+}
+
+#[test]
+fn each_type_is_soaked_by_its_own_stat() {
+    let defender = target(UnitType::Cultist);
+    assert_eq!(mitigate(6, DamageType::Kinetic, &defender), 2);
+    assert_eq!(mitigate(6, DamageType::Energy, &defender), 2);
+    assert_eq!(mitigate(6, DamageType::Fire, &defender), 4);
+    assert_eq!(mitigate(6, DamageType::Toxic, &defender), 6);
+    assert_eq!(mitigate(6, DamageType::Warp, &defender), 5);
+    assert_eq!(mitigate(2, DamageType::Kinetic, &defender), 0);
+}
+
+#[test]
+fn bodies_and_armour_resist_damage_types() {
+    assert_eq!(target(UnitType::Daemon).resistance(DamageType::Toxic), 100);
+    assert_eq!(mitigate(8, DamageType::Toxic, &target(UnitType::OrkBoy)), 6);
+
+    let db = ContentDatabase::load("assets/content/items.json").unwrap();
+    let Item::Armor(power_armor) = db.get("power_armor").unwrap().item.clone() else { panic!() };
+    let mut marine = target(UnitType::SpaceMarine);
+    assert_eq!(marine.resistance(DamageType::Toxic), 25);
+    marine.equip_armor(power_armor);
+    assert_eq!(marine.resistance(DamageType::Toxic), 75);
+    assert_eq!(marine.resistance(DamageType::Kinetic), 0);
+}
+
+#[test]
+fn fire_ignites_and_toxins_poison() {
+    let mut attacker = Unit::new("a", "Attacker", UnitType::Guardsman, Faction::Imperial);
+    let mut defender = target(UnitType::Cultist);
+    let result = resolve_attack(&mut attacker, &weapon(DamageType::Fire), &mut defender, 50, 0).unwrap();
+    assert_eq!(result.damage, 4);
+    let burning = defender.status_effects.iter().find(|s| s.effect_type == EffectType::Burning).unwrap();
+    assert_eq!((burning.remaining_turns, burning.magnitude), (BURN_TURNS, 2));
+
+    attacker.action_points = 2;
+    resolve_attack(&mut attacker, &weapon(DamageType::Toxic), &mut defender, 50, 0).unwrap();
+    let poison = defender.status_effects.iter().find(|s| s.effect_type == EffectType::Poison).unwrap();
+    assert_eq!((poison.remaining_turns, poison.magnitude), (POISON_TURNS, POISON_DAMAGE));
+
+    // Daemons can't be poisoned.
+    let mut daemon = target(UnitType::Daemon);
+    let result = resolve_attack(&mut attacker, &weapon(DamageType::Toxic), &mut daemon, 50, 0).unwrap();
+    assert!(result.hit && result.damage == 0);
+    assert!(daemon.status_effects.is_empty());
+}
+
+#[test]
+fn statuses_hurt_each_turn_until_they_run_out() {
+    let mut attacker = Unit::new("a", "Attacker", UnitType::Guardsman, Faction::Imperial);
+    let mut defender = target(UnitType::Cultist);
+    resolve_attack(&mut attacker, &weapon(DamageType::Fire), &mut defender, 50, 0).unwrap();
+    let hp = defender.health_points;
+    let mut encounter = CombatEncounter::new(vec![], vec![defender], GridMap::new(2, 2), None);
+
+    let mut taken = Vec::new();
+    for _ in 0..3 {
+        encounter.start_turn();
+        taken.push(hp - encounter.unit_by_id("t").unwrap().health_points);
+        encounter.end_turn();
+    }
+    assert_eq!(taken, [2, 4, 4]);
+    assert!(encounter.unit_by_id("t").unwrap().status_effects.is_empty());
+}
@@ -0,0 +1,179 @@
+use gero::achievements::{Achievement, AchievementProfile, AchievementTracker, Condition};
+use gero::builders::{UnitBuilder, WeaponBuilder};
+use gero::combat::CombatEncounter;
+use gero::command::Command;
+use gero::debrief::{BattleReport, Objective};
+use gero::flow::GameFlow;
+use gero::frontend::Renderer;
+use gero::grid::GridMap;
+use gero::input::GameAction;
+use gero::localization::Localizer;
+use gero::loot::Loot;
+use gero::models::{Faction, Position, UnitType};
+use gero::scenario::Scenario;
+use gero::simulation::BattleOutcome;
+use gero::state::GameState;
+use gero::ui::summary::SummaryChoice;
+use gero::ui::{UiEvent, UiManager};
+
+fn encounter() -> CombatEncounter {
+    let gun = WeaponBuilder::new("gun").damage(5).accuracy(1.0).range(6).build();
+    let mut guard = UnitBuilder::new("guard", UnitType::Guardsman, Faction::Imperial).weapon(gun).build();
+    guard.base_stats.accuracy = 100;
+    guard.recalculate_stats();
+    let mut ork = UnitBuilder::new("ork", UnitType::OrkBoy, Faction::Ork).build();
+    ork.grid_position = Position { x: 2, y: 0 };
+    ork.health_points = 1;
+    let mut enc = CombatEncounter::new(vec![guard], vec![ork], GridMap::new(6, 6), None);
+    enc.balance.critical_multiplier = 1;
+    enc
+}
+
+fn flow() -> GameFlow {
+    GameFlow::new(GameState::new(Vec::new()), UiManager::new(1280, 720, Vec::new(), Vec::new()))
+}
+
+#[test]
+fn kills_and_objectives_earn_experience() {
+    let mut enc = encounter();
+    enc.objectives = vec![Objective::Eliminate, Objective::NoCasualties, Objective::WinWithin { rounds: 0 }];
+    enc.turn_order.current_unit_id = Some("guard".into());
+    enc.execute(Command::Attack { attacker_id: "guard".into(), target_id: "ork".into() }).unwrap();
+    assert_eq!(enc.stats.unit("guard").kills, 1);
+    enc.stats.rounds = 2;
+
+    let report = BattleReport::new(&enc, BattleOutcome::PlayerVictory);
+    let met: Vec<bool> = report.objectives.iter().map(|o| o.met).collect();
+    assert_eq!(met, [true, true, false]);
+    let guard = report.unit("guard").unwrap();
+    assert_eq!(guard.experience, 10 + 2 * 20);
+    assert_eq!(report.unit("ork").unwrap().experience, 0);
+    assert!(report.casualties.is_empty());
+
+    let mut unit = enc.unit_by_id("guard").unwrap().clone();
+    unit.experience = 90;
+    assert_eq!(unit.gain_experience(guard.experience), 1);
+    assert_eq!((unit.level, unit.experience), (2, 140));
+    assert_eq!(unit.gain_experience(200), 2);
+}
+
+#[test]
+fn scenario_objectives_must_name_known_regions() {
+    let raid = Scenario::load("assets/scenarios/night_raid.json").unwrap();
+    assert_eq!(raid.objectives[1], Objective::HoldRegion { region: "extraction".into() });
+    let mut guard = UnitBuilder::new("guard", UnitType::Guardsman, Faction::Imperial).build();
+    guard.grid_position = Position { x: 10, y: 6 };
+    let ork = UnitBuilder::new("ork", UnitType::OrkBoy, Faction::Ork).build();
+    let enc = raid.encounter(vec![guard], vec![ork], None);
+    assert!(enc.objectives[1].is_met(&enc, BattleOutcome::Draw));
+    assert!(!enc.objectives[0].is_met(&enc, BattleOutcome::Draw));
+
+    let bad = r#"{ "name": "Bad", "width": 4, "height": 4, "objectives": [{ "HoldRegion": { "region": "nowhere" } }] }"#;
+    assert!(Scenario::from_json(bad).is_err());
+}
+
+#[test]
+fn the_summary_can_retry_the_battle_unless_iron_man() {
+    let mut enc = encounter();
+    enc.recovered.push(Loot::Requisition(25));
+    enc.stats.record_kill("guard", 1, 0);
+    let mut flow = flow();
+    flow.campaign.roster.push(enc.unit_by_id("guard").unwrap().clone());
+    flow.finish_battle(&enc, BattleOutcome::PlayerVictory);
+    assert_eq!(flow.campaign.roster[0].experience, 10);
+
+    let loc = Localizer::new("en").unwrap();
+    let mut renderer = Renderer::new_headless(1280, 720);
+    flow.render(&mut renderer, &loc);
+    let texts: Vec<&str> = renderer.text_log.iter().map(|t| t.text.as_str()).collect();
+    for expected in ["Kills", "+10", "Loot: 25 requisition points", "Casualties: None", "Continue", "Retry"] {
+        assert!(texts.contains(&expected), "missing {expected:?} in {texts:?}");
+    }
+
+    // Retrying puts the campaign back as it was before the battle.
+    flow.handle_action(GameAction::SelectDown);
+    assert_eq!(flow.handle_action(GameAction::Activate), Some(UiEvent::SummaryClosed(SummaryChoice::Retry)));
+    assert_eq!(flow.campaign.roster[0].experience, 0);
+    assert_eq!(flow.campaign.stats.victories, 0);
+
+    // Continuing keeps the results.
+    flow.finish_battle(&enc, BattleOutcome::PlayerVictory);
+    assert_eq!(flow.handle_action(GameAction::Activate), Some(UiEvent::SummaryClosed(SummaryChoice::Continue)));
+    assert_eq!(flow.campaign.roster[0].experience, 10);
+
+    flow.campaign.ironman = true;
+    flow.finish_battle(&enc, BattleOutcome::EnemyVictory);
+    let summary = flow.summary.as_ref().unwrap();
+    assert_eq!(summary.choices, [SummaryChoice::Continue]);
+    assert!(summary.loot.is_empty());
+}
+
+#[test]
+fn retrying_fights_the_battle_again_without_its_achievements() {
+    let mut flow = flow();
+    let blitz = Achievement { id: "blitz".into(), name: String::new(), description: String::new(), condition: Condition::WinWithinRounds(5) };
+    flow.achievements = AchievementTracker::new(vec![blitz], AchievementProfile::default());
+    flow.start_battle(encounter());
+    assert_eq!(flow.battle.as_ref().unwrap().turn_order.current_unit_id.as_deref(), Some("guard"));
+
+    flow.order(Command::Attack { attacker_id: "guard".into(), target_id: "ork".into() });
+    let mut renderer = Renderer::new_headless(1280, 720);
+    flow.present_battle(10.0, &mut renderer);
+    flow.update(0.1);
+    assert!(flow.battle.is_none());
+    assert!(flow.summary.is_some());
+    assert!(flow.achievements.profile.unlocked.contains("blitz"));
+    assert_eq!(flow.campaign.stats.victories, 1);
+
+    flow.handle_action(GameAction::SelectDown);
+    assert_eq!(flow.handle_action(GameAction::Activate), Some(UiEvent::SummaryClosed(SummaryChoice::Retry)));
+    assert!(flow.achievements.profile.unlocked.is_empty());
+    assert_eq!(flow.campaign.stats.victories, 0);
+    let battle = flow.battle.as_ref().unwrap();
+    assert_eq!(battle.unit_by_id("ork").unwrap().health_points, 1);
+    assert!(battle.command_log.is_empty());
+    assert_eq!(flow.state.units.len(), 2);
+}
+
+#[test]
+fn the_flow_plays_the_battle_turn_by_turn() {
+    let mut enc = encounter();
+    let ork = enc.unit_by_id_mut("ork").unwrap();
+    ork.health_points = 30;
+    ork.grid_position = Position { x: 5, y: 5 };
+    let mut flow = flow();
+    flow.start_battle(enc);
+    let mut renderer = Renderer::new_headless(1280, 720);
+
+    // Ending the turn with AP left is queued once confirmed.
+    flow.order(Command::EndTurn);
+    assert!(!flow.battle.as_ref().unwrap().actions.is_busy());
+    flow.handle_action(GameAction::SelectDown);
+    assert_eq!(flow.handle_action(GameAction::Activate), Some(UiEvent::CommandConfirmed(Command::EndTurn)));
+    flow.present_battle(10.0, &mut renderer);
+    flow.update(0.1);
+    assert_eq!(flow.battle.as_ref().unwrap().turn_order.current_unit_id.as_deref(), Some("ork"));
+
+    // The AI takes its turn and hands back to the player with fresh AP.
+    flow.update(0.1);
+    let battle = flow.battle.as_ref().unwrap();
+    assert_eq!(battle.turn_order.current_unit_id.as_deref(), Some("guard"));
+    assert_eq!(battle.turn_order.round_number, 2);
+    let guard = battle.unit_by_id("guard").unwrap();
+    assert_eq!(guard.action_points, guard.current_stats.max_action);
+    // Waiting on orders, the player's turn doesn't move on.
+    flow.update(0.1);
+    assert_eq!(flow.battle.as_ref().unwrap().turn_order.current_unit_id.as_deref(), Some("guard"));
+}
+
+#[test]
+fn continuing_leaves_the_battle_behind() {
+    let mut flow = flow();
+    flow.start_battle(encounter());
+    flow.order(Command::Attack { attacker_id: "guard".into(), target_id: "ork".into() });
+    flow.present_battle(10.0, &mut Renderer::new_headless(1280, 720));
+    flow.update(0.1);
+    assert_eq!(flow.handle_action(GameAction::Activate), Some(UiEvent::SummaryClosed(SummaryChoice::Continue)));
+    assert!(flow.battle.is_none() && flow.summary.is_none());
+    assert_eq!(flow.campaign.stats.victories, 1);
+}
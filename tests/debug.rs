@@ -0,0 +1,112 @@
+use gero::damage::DamageType;
+use gero::debug::{DebugConsole, DebugLayer, DebugOverlay};
+use gero::frontend::{DrawLayer, Renderer};
+use gero::grid::{find_path, GridMap, TerrainType};
+use gero::models::{Faction, Position, Unit, UnitType, Weapon, WeaponTier};
+use gero::registry::UnitRegistry;
+use gero::state::GameState;
+use gero::weather::Weather;
+
+fn rifle(range: u32) -> Weapon {
+    Weapon {
+        id: "w".into(),
+        name: "Rifle".into(),
+        tier: WeaponTier::Basic,
+        damage: 2,
+        accuracy: 1.0,
+        range,
+        armor_piercing: None,
+        action_point_cost: 1,
+        critical_chance: 0.0,
+        abilities_granted: Vec::new(),
+        damage_type: DamageType::Kinetic,
+        forced_move: None,
+        indirect: None,
+    }
+}
+
+/// A guardsman at (0,2) whose turn it is, an ork at (4,2) and a wall
+/// across column 2 with a gap at the bottom.
+fn walled_battle() -> GameState {
+    let mut guard = Unit::new("g", "Guard", UnitType::Guardsman, Faction::Imperial);
+    guard.grid_position = Position { x: 0, y: 2 };
+    let mut ork = Unit::new("o", "Ork", UnitType::Guardsman, Faction::Ork);
+    ork.grid_position = Position { x: 4, y: 2 };
+    ork.equip_weapon(rifle(1));
+    let mut state = GameState::new(Vec::new());
+    state.units = UnitRegistry::with_sides(vec![guard], vec![ork]);
+    state.map = GridMap::new(5, 5);
+    for y in 0..4 {
+        state.map.set_terrain(&Position { x: 2, y }, TerrainType::Blocked);
+    }
+    state.turn_queue.add_unit("g".into());
+    state.turn_queue.add_unit("o".into());
+    state.turn_queue.next_turn();
+    state
+}
+
+#[test]
+fn console_switches_layers_and_reports_errors() {
+    let mut overlay = DebugOverlay::new();
+    assert_eq!(DebugConsole::execute("debug astar on", &mut overlay), Ok("astar on".into()));
+    assert!(overlay.is_enabled(DebugLayer::AstarExplored));
+    DebugConsole::execute("debug astar", &mut overlay).unwrap();
+    assert!(!overlay.is_enabled(DebugLayer::AstarExplored));
+
+    DebugConsole::execute("debug all", &mut overlay).unwrap();
+    assert!(DebugLayer::ALL.iter().all(|l| overlay.is_enabled(*l)));
+    DebugConsole::execute("debug threat off", &mut overlay).unwrap();
+    assert_eq!(DebugConsole::execute("debug list", &mut overlay), Ok("astar, scores, los, turns".into()));
+
+    DebugConsole::execute("debug path 3 4", &mut overlay).unwrap();
+    assert_eq!(overlay.path_target, Some(Position { x: 3, y: 4 }));
+    assert_eq!(DebugConsole::execute("debug fog on", &mut overlay), Err("unknown debug layer"));
+    assert_eq!(DebugConsole::execute("debug los maybe", &mut overlay), Err("expected on, off or toggle"));
+
+    let mut console = DebugConsole::new();
+    console.input = "debug nope".into();
+    console.submit(&mut overlay);
+    assert_eq!(console.log, vec!["> debug nope".to_string(), "error: unknown debug layer".to_string()]);
+    assert!(console.input.is_empty());
+}
+
+#[test]
+fn find_path_reports_the_cells_it_explored() {
+    let state = walled_battle();
+    let guard = state.units.by_name("g").unwrap();
+    let search = find_path(guard, &Position { x: 4, y: 4 }, &state.map, 10, Weather::default());
+    assert!(search.cost.is_some());
+    assert_eq!(search.explored.first(), Some(&guard.grid_position));
+    assert_eq!(search.explored.last(), Some(&Position { x: 4, y: 4 }));
+    assert!(search.explored.iter().all(|c| !matches!(state.map.terrain_at(c), TerrainType::Blocked)));
+}
+
+#[test]
+fn enabled_layers_submit_overlay_draw_calls() {
+    let state = walled_battle();
+    let mut renderer = Renderer::new_headless(640, 640);
+    renderer.render_state(&state);
+    let mut overlay = DebugOverlay::new();
+    overlay.render(&mut renderer, &state, Weather::default());
+    assert!(renderer.draw_log.iter().all(|c| !c.sprite_id.starts_with("debug:")));
+
+    for layer in DebugLayer::ALL {
+        overlay.set(layer, true);
+    }
+    overlay.render(&mut renderer, &state, Weather::default());
+    let cells = |sprite: &str| -> Vec<(u32, u32)> {
+        renderer.draw_log.iter().filter(|c| c.sprite_id == sprite).map(|c| c.position).collect()
+    };
+    assert!(renderer.draw_log.iter().filter(|c| c.sprite_id.starts_with("debug:")).all(|c| c.layer == DrawLayer::Overlay));
+    // The wall sits between them, so the sight line is blocked.
+    assert_eq!(cells("debug:los_blocked"), vec![(1, 2), (2, 2), (3, 2), (4, 2)]);
+    assert!(cells("debug:los").is_empty());
+    // The ork can shoot one tile from where it stands.
+    assert!(cells("debug:threat").contains(&(3, 2)));
+    assert!(!cells("debug:threat").contains(&(0, 0)));
+    assert!(cells("debug:astar").contains(&(0, 2)));
+    // Scores are costs to the ork, so the wall has none and its cell is 0.
+    assert!(!cells("debug:score").contains(&(2, 0)));
+    assert!(renderer.text_log.iter().any(|t| t.text == "0" && t.position == (256, 128)));
+    assert!(renderer.text_log.iter().any(|t| t.text == "Round 1 turn 1: [g] o > g"));
+}
@@ -0,0 +1,100 @@
+use gero::audio::AudioSystem;
+use gero::combat::{CombatEncounter, CombatError, CombatEvent};
+use gero::command::Command;
+use gero::damage::DamageType;
+use gero::frontend::Renderer;
+use gero::grid::{try_move, GridMap, TerrainType};
+use gero::models::{Accessory, Faction, Position, Unit, UnitType, Weapon, WeaponTier};
+use gero::state::GameState;
+
+fn pos(x: usize, y: usize) -> Position {
+    Position { x, y }
+}
+
+fn weapon(damage: i32) -> Weapon {
+    Weapon {
+        id: "w".into(),
+        name: "W".into(),
+        tier: WeaponTier::Basic,
+        damage,
+        accuracy: 0.5,
+        range: 4,
+        armor_piercing: None,
+        action_point_cost: 1,
+        critical_chance: 0.0,
+        abilities_granted: Vec::new(),
+        damage_type: DamageType::Kinetic,
+        forced_move: None,
+        indirect: None,
+    }
+}
+
+fn walled_encounter(damage: i32) -> CombatEncounter {
+    let mut unit = Unit::new("u", "U", UnitType::Guardsman, Faction::Imperial);
+    unit.base_stats.agility = 6;
+    unit.base_stats.accuracy = 100;
+    unit.equip_weapon(weapon(damage));
+    unit.equipment.accessory_slots.push(Accessory::Grenade { damage: 5, aoe_radius: 0, leaves: None });
+    let mut map = GridMap::new(4, 1);
+    map.place_structure(&pos(1, 0), 10);
+    CombatEncounter::new(vec![unit], vec![], map, None)
+}
+
+#[test]
+fn structures_collapse_into_rubble() {
+    let mut map = GridMap::new(4, 1);
+    map.place_structure(&pos(1, 0), 6);
+    let mut unit = Unit::new("u", "U", UnitType::Guardsman, Faction::Imperial);
+    unit.base_stats.agility = 6;
+    unit.recalculate_stats();
+    assert!(!try_move(&mut unit, pos(2, 0), &map));
+
+    let revision = map.revision;
+    assert!(!map.damage_structure(&pos(1, 0), 5));
+    assert_eq!(*map.terrain_at(&pos(1, 0)), TerrainType::Blocked);
+    assert!(map.damage_structure(&pos(1, 0), 1));
+    assert_eq!(*map.terrain_at(&pos(1, 0)), TerrainType::Difficult);
+    assert!(map.revision > revision);
+    assert!(map.structures.is_empty());
+    assert!(try_move(&mut unit, pos(2, 0), &map));
+}
+
+#[test]
+fn heavy_weapons_and_grenades_break_walls() {
+    let mut light = walled_encounter(4);
+    let fire = Command::AttackTerrain { attacker_id: "u".into(), target: pos(1, 0) };
+    assert_eq!(light.execute(fire.clone()), Err(CombatError::WeaponTooLight));
+
+    let mut heavy = walled_encounter(10);
+    heavy.execute(fire).unwrap();
+    assert_eq!(heavy.drain_events(), vec![CombatEvent::ObjectDestroyed { position: pos(1, 0) }]);
+    assert_eq!(*heavy.battlefield.terrain_at(&pos(1, 0)), TerrainType::Difficult);
+
+    let mut blasted = walled_encounter(4);
+    let throw = || Command::ThrowGrenade { thrower_id: "u".into(), accessory_index: 0, target: pos(1, 0) };
+    blasted.execute(throw()).unwrap();
+    assert_eq!(blasted.battlefield.structures[0].hit_points, 5);
+    blasted.unit_by_id_mut("u").unwrap().equipment.accessory_slots.push(Accessory::Grenade {
+        damage: 5,
+        aoe_radius: 0,
+        leaves: None,
+    });
+    blasted.execute(throw()).unwrap();
+    assert!(blasted.events.contains(&CombatEvent::ObjectDestroyed { position: pos(1, 0) }));
+}
+
+#[test]
+fn destruction_events_drive_debris_and_sound() {
+    let event = CombatEvent::ObjectDestroyed { position: pos(1, 0) };
+    let mut audio = AudioSystem::new();
+    audio.handle_combat_event(&event);
+    assert_eq!(audio.played_log, vec!["rubble"]);
+
+    let enc = walled_encounter(4);
+    let mut renderer = Renderer::new_headless(640, 480);
+    renderer.handle_combat_event(&event);
+    renderer.render_state(&GameState::from_encounter(&enc));
+    assert!(renderer.draw_log.iter().any(|c| c.sprite_id == "effect:debris"));
+    renderer.update(1.0);
+    assert!(renderer.debris.is_empty());
+}
@@ -0,0 +1,73 @@
+use gero::campaign::Campaign;
+use gero::dialogue::{DialoguePlayer, DialogueTree};
+use gero::flow::GameFlow;
+use gero::frontend::Renderer;
+use gero::input::GameAction;
+use gero::localization::Localizer;
+use gero::models::{Faction, Unit, UnitType};
+use gero::state::GameState;
+use gero::ui::UiManager;
+
+#[test]
+fn choices_set_flags_and_grant_items() {
+    let tree = DialogueTree::load("assets/dialogue/briefing.json").unwrap();
+    let mut campaign = Campaign::new();
+    let mut player = DialoguePlayer::new(tree);
+    assert_eq!(player.current_node().unwrap().portrait.as_deref(), Some("portrait:commissar"));
+    assert_eq!(player.advance(), Err("a choice is required"));
+
+    player.choose(0, &mut campaign).unwrap();
+    assert!(campaign.has_flag("mechanicus_support"));
+    assert_eq!(campaign.inventory, vec!["auspex".to_string()]);
+    assert_eq!(player.current_node().unwrap().speaker, "speaker.techpriest");
+    player.advance().unwrap();
+    assert!(player.is_finished());
+}
+
+#[test]
+fn flag_gated_choices_are_hidden() {
+    let json = r#"{
+        "id": "gate", "start": "a",
+        "nodes": { "a": { "speaker": "s", "line": "l", "choices": [
+            { "text": "always" },
+            { "text": "secret", "requires_flag": "knows_secret" }
+        ] } }
+    }"#;
+    let mut campaign = Campaign::new();
+    let player = DialoguePlayer::new(DialogueTree::from_json(json).unwrap());
+    assert_eq!(player.available_choices(&campaign).len(), 1);
+    campaign.set_flag("knows_secret");
+    assert_eq!(player.available_choices(&campaign).len(), 2);
+}
+
+#[test]
+fn dangling_node_references_are_rejected() {
+    let json = r#"{ "id": "bad", "start": "a", "nodes": { "a": { "speaker": "s", "line": "l", "next": "missing" } } }"#;
+    let err = DialogueTree::from_json(json).unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+}
+
+#[test]
+fn dialogue_overlay_pauses_flow_and_takes_input() {
+    let loc = Localizer::new("en").unwrap();
+    let mut renderer = Renderer::new_headless(640, 480);
+    let unit = Unit::new("u", "U", UnitType::Guardsman, Faction::Imperial);
+    let mut flow = GameFlow::new(GameState::new(vec![unit]), UiManager::new(640, 480, vec![], vec![]));
+    flow.start_dialogue(DialogueTree::load("assets/dialogue/briefing.json").unwrap());
+
+    flow.update(1.0);
+    assert_eq!(flow.state.units.by_name("u").unwrap().animation_state.frame_index, 0);
+
+    flow.render(&mut renderer, &loc);
+    assert!(renderer.draw_log.iter().any(|c| c.sprite_id == "portrait:commissar"));
+    assert!(renderer.text_log.iter().any(|t| t.text == "The Orks hold the refinery. We retake it today."));
+    assert!(renderer.text_log.iter().any(|t| t.text == "We need no help."));
+
+    flow.handle_action(GameAction::SelectDown);
+    flow.handle_action(GameAction::Activate);
+    assert!(!flow.campaign.has_flag("mechanicus_support"));
+    flow.handle_action(GameAction::Activate);
+    assert!(flow.dialogue.is_none());
+    flow.update(1.0);
+    assert!(flow.state.units.by_name("u").unwrap().animation_state.frame_index > 0);
+}
@@ -0,0 +1,116 @@
+use gero::builders::{AbilityBuilder, UnitBuilder, WeaponBuilder};
+use gero::combat::{CombatEncounter, CombatEvent};
+use gero::command::Command;
+use gero::displacement::{ForcedMove, COLLISION_DAMAGE};
+use gero::grid::{GridMap, Region, RegionShape, TerrainType, TriggerRegion};
+use gero::models::{AbilityType, Faction, Position, Unit, UnitType};
+use gero::vehicle::{FacingArmor, Vehicle};
+
+fn at(x: usize, y: usize) -> Position {
+    Position { x, y }
+}
+
+/// A unit hitting for 1 damage, moving whoever it hits as `forced` says.
+fn unit(id: &str, faction: Faction, x: usize, forced: ForcedMove) -> Unit {
+    let hammer = WeaponBuilder::new("hammer").range(6).accuracy(1.0).critical_chance(0.0).forced_move(forced).build();
+    UnitBuilder::new(id, UnitType::Guardsman, faction).max_health(20).weapon(hammer).at(x, 0).build()
+}
+
+/// An ogryn at the left of an 8x1 map, an ork two tiles away and anyone
+/// in `others`.
+fn encounter(forced: ForcedMove, others: Vec<Unit>) -> CombatEncounter {
+    let ogryn = unit("ogryn", Faction::Imperial, 0, forced);
+    let mut enemies = vec![unit("ork", Faction::Ork, 2, forced)];
+    enemies.extend(others);
+    let mut encounter = CombatEncounter::new(vec![ogryn], enemies, GridMap::new(8, 1), None);
+    encounter.balance.critical_multiplier = 1;
+    encounter
+}
+
+fn hit(encounter: &mut CombatEncounter) {
+    encounter.execute(Command::Attack { attacker_id: "ogryn".into(), target_id: "ork".into() }).unwrap();
+}
+
+fn unit_at(encounter: &CombatEncounter, id: &str) -> (Position, i32) {
+    let unit = encounter.unit_by_id(id).unwrap();
+    (unit.grid_position.clone(), unit.health_points)
+}
+
+#[test]
+fn knockback_drives_the_target_along_the_attack() {
+    let mut encounter = encounter(ForcedMove::Knockback { tiles: 3 }, Vec::new());
+    hit(&mut encounter);
+    assert_eq!(unit_at(&encounter, "ork"), (at(5, 0), 19));
+    assert!(encounter.drain_events().contains(&CombatEvent::UnitDisplaced {
+        unit_id: "ork".into(),
+        from: at(2, 0),
+        to: at(5, 0),
+    }));
+}
+
+#[test]
+fn knockback_into_a_wall_hurts() {
+    let mut encounter = encounter(ForcedMove::Knockback { tiles: 3 }, Vec::new());
+    encounter.battlefield.set_terrain(&at(4, 0), TerrainType::Blocked);
+    hit(&mut encounter);
+    assert_eq!(unit_at(&encounter, "ork"), (at(3, 0), 19 - COLLISION_DAMAGE));
+}
+
+#[test]
+fn knockback_into_a_unit_hurts_both() {
+    let grot = unit("grot", Faction::Ork, 3, ForcedMove::Swap);
+    let mut encounter = encounter(ForcedMove::Knockback { tiles: 3 }, vec![grot]);
+    hit(&mut encounter);
+    assert_eq!(unit_at(&encounter, "ork"), (at(2, 0), 19 - COLLISION_DAMAGE));
+    assert_eq!(unit_at(&encounter, "grot"), (at(3, 0), 20 - COLLISION_DAMAGE));
+}
+
+#[test]
+fn pulls_stop_short_of_the_caster_without_harm() {
+    let pull = AbilityBuilder::new("grapple", AbilityType::Special).range(6).forced_move(ForcedMove::Pull { tiles: 4 }).build();
+    let mut encounter = encounter(ForcedMove::Swap, Vec::new());
+    encounter.unit_by_id_mut("ogryn").unwrap().abilities.push(pull);
+    let cmd = Command::UseAbility { user_id: "ogryn".into(), ability_index: 0, target_ids: vec!["ork".into()] };
+    encounter.execute(cmd).unwrap();
+    assert_eq!(unit_at(&encounter, "ork"), (at(1, 0), 20));
+}
+
+#[test]
+fn swaps_trade_places() {
+    let mut encounter = encounter(ForcedMove::Swap, Vec::new());
+    hit(&mut encounter);
+    assert_eq!(unit_at(&encounter, "ogryn").0, at(2, 0));
+    assert_eq!(unit_at(&encounter, "ork").0, at(0, 0));
+}
+
+#[test]
+fn swaps_cross_regions_and_land_on_hazards_like_any_forced_move() {
+    let mut encounter = encounter(ForcedMove::Swap, Vec::new());
+    encounter.battlefield.set_terrain(&at(0, 0), TerrainType::Hazardous);
+    encounter.battlefield.regions.push(TriggerRegion { name: "bridge".into(), shape: RegionShape::Rect(Region { x: 2, y: 0, width: 1, height: 1 }) });
+    hit(&mut encounter);
+    assert!(encounter.events.contains(&CombatEvent::RegionEntered { unit_id: "ogryn".into(), region: "bridge".into() }));
+    assert!(encounter.events.contains(&CombatEvent::RegionExited { unit_id: "ork".into(), region: "bridge".into() }));
+    assert!(unit_at(&encounter, "ork").1 < 19);
+}
+
+#[test]
+fn passengers_go_where_their_vehicle_is_thrown() {
+    let mut grot = unit("grot", Faction::Ork, 2, ForcedMove::Swap);
+    grot.embarked_in = Some("ork".into());
+    let mut encounter = encounter(ForcedMove::Knockback { tiles: 3 }, vec![grot]);
+    let mut trukk = Vehicle::new(FacingArmor { front: 0, side: 0, rear: 0 }, 1);
+    trukk.passengers.push("grot".into());
+    encounter.unit_by_id_mut("ork").unwrap().vehicle = Some(trukk);
+    hit(&mut encounter);
+    assert_eq!(unit_at(&encounter, "ork").0, at(5, 0));
+    assert_eq!(unit_at(&encounter, "grot").0, at(5, 0));
+}
+
+#[test]
+fn misses_move_nobody() {
+    let mut encounter = encounter(ForcedMove::Knockback { tiles: 3 }, Vec::new());
+    encounter.unit_by_id_mut("ogryn").unwrap().equipment.weapon.as_mut().unwrap().accuracy = 0.0;
+    hit(&mut encounter);
+    assert_eq!(unit_at(&encounter, "ork"), (at(2, 0), 20));
+}
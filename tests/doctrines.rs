@@ -0,0 +1,121 @@
+use std::collections::HashMap;
+
+use gero::campaign::Campaign;
+use gero::combat::{AttackModifiers, CombatEncounter};
+use gero::command::Command;
+use gero::damage::DamageType;
+use gero::doctrine::Doctrine;
+use gero::facing::Facing;
+use gero::grid::GridMap;
+use gero::models::{Faction, Position, Unit, UnitType, Weapon, WeaponTier};
+use gero::modifiers::{Modifier, ModifierSource, ModifierStack, Situation, Stat};
+
+fn weapon(range: u32) -> Weapon {
+    Weapon {
+        id: "w".into(),
+        name: "W".into(),
+        tier: WeaponTier::Basic,
+        damage: 3,
+        accuracy: 1.0,
+        range,
+        armor_piercing: None,
+        action_point_cost: 1,
+        critical_chance: 0.0,
+        abilities_granted: Vec::new(),
+        damage_type: DamageType::Kinetic,
+        forced_move: None,
+        indirect: None,
+    }
+}
+
+fn unit(id: &str, unit_type: UnitType, faction: Faction, x: usize) -> Unit {
+    let mut unit = Unit::new(id, id, unit_type, faction);
+    unit.base_stats.agility = 4;
+    unit.base_stats.max_health = 50;
+    unit.grid_position = Position { x, y: 0 };
+    unit.equip_weapon(weapon(1));
+    unit.recalculate_stats();
+    unit.health_points = 50;
+    unit
+}
+
+#[test]
+fn modifier_stack_sums_and_expires() {
+    let mut stack = ModifierStack::new();
+    stack.push(Modifier::new(ModifierSource::Status, Stat::Accuracy, 5));
+    stack.push(Modifier { remaining_turns: Some(1), ..Modifier::new(ModifierSource::Aura, Stat::Accuracy, 3) });
+    for m in Doctrine::Waaagh.modifiers() {
+        stack.push(m);
+    }
+    assert_eq!(stack.total(Stat::Accuracy, &Situation::default()), 8);
+    assert_eq!(stack.total(Stat::MeleeDamage, &Situation::default()), 0);
+    assert_eq!(stack.total(Stat::MeleeDamage, &Situation { charging: true, in_cover: false }), 2);
+    stack.tick();
+    assert_eq!(stack.total(Stat::Accuracy, &Situation::default()), 5);
+    stack.remove_source(ModifierSource::Status);
+    assert_eq!(stack.total(Stat::Accuracy, &Situation::default()), 0);
+}
+
+fn ork_skirmish() -> CombatEncounter {
+    let mut enc = CombatEncounter::new(
+        vec![unit("g", UnitType::Guardsman, Faction::Imperial, 3)],
+        vec![unit("o", UnitType::OrkBoy, Faction::Ork, 2)],
+        GridMap::new(8, 2),
+        None,
+    );
+    enc.apply_doctrine(Doctrine::Waaagh);
+    // Facing the ork where it stands and where it charges to.
+    enc.unit_by_id_mut("g").unwrap().facing = Facing::SouthWest;
+    enc
+}
+
+#[test]
+fn waaagh_only_boosts_charging_melee() {
+    let attack = || Command::Attack { attacker_id: "o".into(), target_id: "g".into() };
+
+    let mut standing = ork_skirmish();
+    standing.execute(attack()).unwrap();
+    let standing_damage = 50 - standing.unit_by_id("g").unwrap().health_points;
+
+    let mut charging = ork_skirmish();
+    charging.execute(Command::Move { unit_id: "o".into(), destination: Position { x: 3, y: 1 } }).unwrap();
+    charging.execute(attack()).unwrap();
+    let charging_damage = 50 - charging.unit_by_id("g").unwrap().health_points;
+    assert_eq!(charging_damage, standing_damage + 2);
+}
+
+#[test]
+fn hold_the_line_only_applies_in_cover() {
+    let mut stacks = HashMap::new();
+    let mut stack = ModifierStack::new();
+    for m in Doctrine::HoldTheLine.modifiers() {
+        stack.push(m);
+    }
+    stacks.insert(Faction::Imperial, stack);
+    let ork = unit("o", UnitType::OrkBoy, Faction::Ork, 0);
+    let guard = unit("g", UnitType::Guardsman, Faction::Imperial, 1);
+    let w = weapon(6);
+    assert_eq!(AttackModifiers::from_stacks(&stacks, &ork, &guard, &w, false, 0).cover, 0);
+    assert_eq!(AttackModifiers::from_stacks(&stacks, &ork, &guard, &w, false, 20).cover, 10);
+    assert_eq!(AttackModifiers::from_stacks(&stacks, &guard, &ork, &w, false, 20).cover, 0);
+}
+
+#[test]
+fn campaign_doctrines_extend_movement() {
+    let mut campaign = Campaign::new();
+    campaign.adopt_doctrine(Doctrine::FrenziedAdvance);
+    campaign.adopt_doctrine(Doctrine::FrenziedAdvance);
+    assert_eq!(campaign.doctrines, vec![Doctrine::FrenziedAdvance]);
+
+    // A single AP to move with.
+    let mut cultist = unit("c", UnitType::Cultist, Faction::Chaos, 0);
+    cultist.action_points = 1;
+    let mut enc = CombatEncounter::new(vec![cultist], vec![], GridMap::new(8, 1), None);
+    let far = Position { x: 3, y: 0 };
+    assert!(enc.execute(Command::Move { unit_id: "c".into(), destination: far.clone() }).is_err());
+    for doctrine in &campaign.doctrines {
+        enc.apply_doctrine(*doctrine);
+    }
+    enc.execute(Command::Move { unit_id: "c".into(), destination: far.clone() }).unwrap();
+    assert_eq!(enc.unit_by_id("c").unwrap().grid_position, far);
+}
@@ -0,0 +1,121 @@
+use gero::campaign::Campaign;
+use gero::combat::resolve_attack;
+use gero::content::{ContentDatabase, Item};
+use gero::durability::{jam_chance, LOW_DURABILITY, MAX_DURABILITY};
+use gero::frontend::text::DAMAGE_RED;
+use gero::frontend::Renderer;
+use gero::input::GameAction;
+use gero::localization::Localizer;
+use gero::models::{Armor, Faction, Unit, UnitType, Weapon};
+use gero::ui::loadout::{LoadoutRow, LoadoutScreen};
+
+fn items() -> (Weapon, Armor) {
+    let db = ContentDatabase::load("assets/content/items.json").unwrap();
+    let Item::Weapon(lasgun) = db.get("lasgun").unwrap().item.clone() else { panic!() };
+    let Item::Armor(carapace) = db.get("carapace_armor").unwrap().item.clone() else { panic!() };
+    (lasgun, carapace)
+}
+
+fn trooper(id: &str) -> Unit {
+    let (lasgun, carapace) = items();
+    let mut unit = Unit::new(id, "Trooper", UnitType::Guardsman, Faction::Imperial);
+    unit.base_stats.agility = 3;
+    unit.base_stats.max_action = 100;
+    unit.equip_weapon(lasgun);
+    unit.equip_armor(carapace);
+    unit.action_points = 100;
+    unit
+}
+
+#[test]
+fn attacks_wear_weapons_and_hits_wear_armor() {
+    let (lasgun, _) = items();
+    let mut attacker = trooper("a");
+    let mut defender = trooper("d");
+    defender.base_stats.max_health = 1000;
+    defender.health_points = 1000;
+    defender.recalculate_stats();
+    let toughness = defender.current_stats.toughness;
+
+    let result = resolve_attack(&mut attacker, &lasgun, &mut defender, 20, 0).unwrap();
+    assert!(result.hit && result.damage > 0 && !result.jammed);
+    assert!(attacker.equipment.weapon_durability < MAX_DURABILITY);
+    assert!(defender.equipment.armor_durability < MAX_DURABILITY);
+
+    // Worn armor gives half its protection.
+    while !defender.equipment.armor_worn() {
+        resolve_attack(&mut attacker, &lasgun, &mut defender, 20, 0).unwrap();
+    }
+    assert_eq!(defender.current_stats.toughness, toughness - 1);
+}
+
+#[test]
+fn worn_weapons_jam_on_high_rolls() {
+    let (lasgun, _) = items();
+    assert_eq!(jam_chance(LOW_DURABILITY), 0);
+    assert_eq!(jam_chance(0), LOW_DURABILITY);
+    let mut attacker = trooper("a");
+    let mut defender = trooper("d");
+    attacker.equipment.weapon_durability = LOW_DURABILITY - 10;
+    let result = resolve_attack(&mut attacker, &lasgun, &mut defender, 95, 0).unwrap();
+    assert!(result.jammed && !result.hit);
+    assert_eq!(defender.health_points, defender.current_stats.max_health);
+    assert_eq!(attacker.action_points, 100 - lasgun.action_point_cost);
+    let result = resolve_attack(&mut attacker, &lasgun, &mut defender, 85, 0).unwrap();
+    assert!(!result.jammed);
+}
+
+#[test]
+fn repairs_cost_requisition_and_restore_condition() {
+    let mut campaign = Campaign::new();
+    campaign.roster.push(trooper("a"));
+    assert_eq!(campaign.repair_equipment("a"), Err("nothing to repair"));
+    assert_eq!(campaign.repair_equipment("nobody"), Err("unknown unit"));
+
+    let mut battered = trooper("a");
+    battered.equipment.weapon_durability = 50;
+    battered.equipment.armor_durability = 10;
+    campaign.record_wear([&battered]);
+    assert!(campaign.roster[0].equipment.armor_worn());
+    let cost = campaign.roster[0].repair_cost();
+    assert_eq!(cost, 35);
+    assert_eq!(campaign.repair_equipment("a"), Err("insufficient requisition"));
+
+    campaign.currency = 50;
+    campaign.repair_equipment("a").unwrap();
+    assert_eq!(campaign.currency, 15);
+    let equipment = &campaign.roster[0].equipment;
+    assert_eq!((equipment.weapon_durability, equipment.armor_durability), (MAX_DURABILITY, MAX_DURABILITY));
+    assert!(!equipment.armor_worn());
+
+    // Condition is kept in the campaign save.
+    campaign.roster[0].equipment.weapon_durability = 40;
+    let saved: Campaign = serde_json::from_str(&serde_json::to_string(&campaign).unwrap()).unwrap();
+    assert_eq!(saved.roster[0].equipment.weapon_durability, 40);
+}
+
+#[test]
+fn the_loadout_screen_warns_about_worn_equipment() {
+    let db = ContentDatabase::load("assets/content/items.json").unwrap();
+    let loc = Localizer::new("en").unwrap();
+    let mut campaign = Campaign::new();
+    let mut unit = trooper("a");
+    unit.equipment.weapon_durability = 20;
+    campaign.roster.push(unit);
+    campaign.currency = 30;
+
+    let mut screen = LoadoutScreen::new();
+    screen.handle_input(GameAction::Activate, &db, &mut campaign);
+    let mut renderer = Renderer::new_headless(1280, 720);
+    screen.render(&mut renderer, &loc, &db, &campaign);
+    let warning = renderer.text_log.iter().find(|t| t.text == "Weapon: Lasgun (20%) - needs repair").unwrap();
+    assert_eq!(warning.color, DAMAGE_RED);
+    assert!(renderer.text_log.iter().any(|t| t.text == "Armour: Carapace Armour (100%)"));
+    assert!(renderer.text_log.iter().any(|t| t.text == "Repair equipment - 20"));
+
+    let rows = screen.rows(&campaign);
+    screen.cursor = rows.iter().position(|r| *r == LoadoutRow::Repair).unwrap();
+    screen.handle_input(GameAction::Activate, &db, &mut campaign);
+    assert_eq!(campaign.currency, 10);
+    assert!(!screen.rows(&campaign).contains(&LoadoutRow::Repair));
+}
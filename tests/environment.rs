@@ -1,6 +1,8 @@
 use gero::combat::{CombatEncounter, EnvironmentalEffect};
+use gero::damage::DamageType;
 use gero::models::{Unit, UnitType, Faction, Position};
 use gero::grid::GridMap;
+use gero::state::GameState;
 
 #[test]
 fn smoke_cloud_expires() {
@@ -38,7 +40,7 @@ fn fire_patch_deals_damage() {
     });
 
     encounter.start_turn();
-    let hp_after = encounter.player_units[0].health_points;
+    let hp_after = encounter.unit_by_id("u").unwrap().health_points;
     assert_eq!(hp_after, starting_hp - 2);
 }
 
@@ -46,7 +48,7 @@ fn fire_patch_deals_damage() {
 fn acid_pool_reduces_agility_temporarily() {
     let mut unit = Unit::new("u", "U", UnitType::Guardsman, Faction::Imperial);
     unit.base_stats.agility = 4;
-    unit.apply_equipment();
+    unit.recalculate_stats();
     unit.grid_position = Position { x: 0, y: 0 };
     let mut encounter = CombatEncounter::new(vec![unit], vec![], GridMap::new(2, 2), None);
     encounter.environmental_effects.push(EnvironmentalEffect::AcidPool {
@@ -55,7 +57,168 @@ fn acid_pool_reduces_agility_temporarily() {
     });
 
     encounter.start_turn();
-    assert_eq!(encounter.player_units[0].current_stats.agility, 2);
+    assert_eq!(encounter.unit_by_id("u").unwrap().current_stats.agility, 2);
     encounter.end_turn();
-    assert_eq!(encounter.player_units[0].current_stats.agility, 4);
+    assert_eq!(encounter.unit_by_id("u").unwrap().current_stats.agility, 4);
+}
+
+#[test]
+fn fire_patch_damage_is_recorded_as_event() {
+    use gero::combat::CombatEvent;
+
+    let unit = Unit::new("u", "U", UnitType::Guardsman, Faction::Imperial);
+    let mut encounter = CombatEncounter::new(vec![unit], vec![], GridMap::new(2, 2), None);
+    encounter.environmental_effects.push(EnvironmentalEffect::FirePatch {
+        grid_cells: vec![Position { x: 0, y: 0 }],
+        damage_per_turn: 2,
+    });
+
+    encounter.start_turn();
+    assert_eq!(
+        encounter.drain_events(),
+        vec![CombatEvent::UnitDamaged { unit_id: "u".into(), amount: 2, critical: false }]
+    );
+    assert!(encounter.events.is_empty());
+}
+
+fn fire_cells(encounter: &CombatEncounter) -> Vec<Position> {
+    encounter
+        .environmental_effects
+        .iter()
+        .filter_map(|e| match e {
+            EnvironmentalEffect::FirePatch { grid_cells, .. } => Some(grid_cells.clone()),
+            _ => None,
+        })
+        .flatten()
+        .collect()
+}
+
+#[test]
+fn fire_spreads_one_flammable_cell_per_round() {
+    let mut map = GridMap::new(5, 1);
+    for x in 1..3 {
+        map.flammable.insert(Position { x, y: 0 });
+    }
+    let mut encounter = CombatEncounter::new(vec![], vec![], map, None);
+    encounter.environmental_effects.push(EnvironmentalEffect::FirePatch {
+        grid_cells: vec![Position { x: 0, y: 0 }],
+        damage_per_turn: 1,
+    });
+
+    encounter.tick_environment();
+    assert_eq!(fire_cells(&encounter), vec![Position { x: 0, y: 0 }, Position { x: 1, y: 0 }]);
+    encounter.tick_environment();
+    encounter.tick_environment();
+    assert_eq!(fire_cells(&encounter).len(), 3);
+}
+
+#[test]
+fn maps_with_flammable_cells_reload_with_the_same_checksum() {
+    let mut map = GridMap::new(8, 2);
+    for x in 0..8 {
+        map.flammable.insert(Position { x, y: 1 });
+    }
+    let state = GameState::from_encounter(&CombatEncounter::new(vec![], vec![], map, None));
+    let reloaded = GameState::load_from_str(&state.save_to_string());
+    assert_eq!(reloaded.checksum(), state.checksum());
+}
+
+#[test]
+fn smoke_puts_out_fire_and_stops_spread() {
+    let mut map = GridMap::new(5, 1);
+    map.flammable.insert(Position { x: 3, y: 0 });
+    let mut encounter = CombatEncounter::new(vec![], vec![], map, None);
+    encounter.environmental_effects.push(EnvironmentalEffect::FirePatch {
+        grid_cells: vec![Position { x: 0, y: 0 }, Position { x: 4, y: 0 }],
+        damage_per_turn: 1,
+    });
+    encounter.environmental_effects.push(EnvironmentalEffect::SmokeCloud {
+        center: Position { x: 4, y: 0 },
+        radius: 1,
+        turns_remaining: 3,
+    });
+
+    encounter.tick_environment();
+    assert_eq!(fire_cells(&encounter), vec![Position { x: 0, y: 0 }]);
+}
+
+#[test]
+fn acid_evaporates_over_rounds() {
+    let unit = Unit::new("u", "U", UnitType::Guardsman, Faction::Imperial);
+    let mut encounter = CombatEncounter::new(vec![unit], vec![], GridMap::new(2, 2), None);
+    encounter.environmental_effects.push(EnvironmentalEffect::AcidPool {
+        grid_cells: vec![Position { x: 1, y: 1 }],
+        movement_penalty: 0.5,
+    });
+
+    // Each start_turn after the first begins a new round for a lone unit.
+    encounter.start_turn();
+    encounter.start_turn();
+    assert!(matches!(
+        encounter.environmental_effects[0],
+        EnvironmentalEffect::AcidPool { movement_penalty, .. } if movement_penalty == 0.75
+    ));
+    encounter.start_turn();
+    assert!(encounter.environmental_effects.is_empty());
+    assert_eq!(encounter.turn_order.round_number, 3);
+}
+
+#[test]
+fn grenades_and_abilities_leave_patches() {
+    use gero::command::Command;
+    use gero::environment::Hazard;
+    use gero::models::{Ability, AbilityEffect, AbilityType, Accessory, AnimationType};
+
+    let mut thrower = Unit::new("t", "T", UnitType::Guardsman, Faction::Imperial);
+    thrower.base_stats.accuracy = 100;
+    thrower.action_points = 3;
+    thrower.recalculate_stats();
+    thrower.equipment.accessory_slots.push(Accessory::Grenade {
+        damage: 0,
+        aoe_radius: 0,
+        leaves: Some(Hazard::Fire { damage_per_turn: 2 }),
+    });
+    thrower.abilities.push(Ability {
+        id: "acid".into(),
+        name: "Acid Spit".into(),
+        ability_type: AbilityType::Special,
+        description: String::new(),
+        action_point_cost: 1,
+        cooldown: 0,
+        current_cooldown: 0,
+        range: 5,
+        area_of_effect: None,
+        effect: AbilityEffect {
+            damage: None,
+            healing: None,
+            buff: None,
+            debuff: None,
+            status_applied: None,
+            duration: None,
+            creates_hazard: Some(Hazard::Acid { movement_penalty: 0.5 }),
+            alters_terrain: None,
+            forced_move: None,
+            damage_type: DamageType::Kinetic,
+        },
+        animation: AnimationType::AbilityCast,
+        sound_effect_key: String::new(),
+        rank: 1,
+        ranks: Vec::new(),
+        channel: None,
+    });
+    let mut target = Unit::new("o", "O", UnitType::OrkBoy, Faction::Ork);
+    target.grid_position = Position { x: 3, y: 0 };
+    let mut encounter = CombatEncounter::new(vec![thrower], vec![target], GridMap::new(5, 1), None);
+
+    encounter
+        .execute(Command::ThrowGrenade { thrower_id: "t".into(), accessory_index: 0, target: Position { x: 2, y: 0 } })
+        .unwrap();
+    assert_eq!(fire_cells(&encounter), vec![Position { x: 2, y: 0 }]);
+
+    encounter
+        .execute(Command::UseAbility { user_id: "t".into(), ability_index: 0, target_ids: vec!["o".into()] })
+        .unwrap();
+    assert!(encounter.environmental_effects.iter().any(
+        |e| matches!(e, EnvironmentalEffect::AcidPool { grid_cells, .. } if grid_cells == &vec![Position { x: 3, y: 0 }])
+    ));
 }
@@ -1,3 +1,4 @@
+use gero::damage::DamageType;
 use gero::models::{Unit, UnitType, Faction, Armor, ArmorTier, Weapon, WeaponTier};
 
 #[test]
@@ -5,7 +6,7 @@ fn armor_modifiers_change_stats() {
     let mut unit = Unit::new("u", "Unit", UnitType::Guardsman, Faction::Imperial);
     unit.base_stats.toughness = 3;
     unit.base_stats.agility = 4;
-    unit.apply_equipment();
+    unit.recalculate_stats();
 
     let armor = Armor {
         id: "a1".into(),
@@ -30,7 +31,7 @@ fn weapon_equipment_pipeline_keeps_stats_unchanged() {
     let mut unit = Unit::new("u", "Unit", UnitType::Guardsman, Faction::Imperial);
     unit.base_stats.strength = 2;
     unit.base_stats.agility = 3;
-    unit.apply_equipment();
+    unit.recalculate_stats();
 
     let weapon = Weapon {
         id: "w1".into(),
@@ -43,6 +44,9 @@ fn weapon_equipment_pipeline_keeps_stats_unchanged() {
         action_point_cost: 1,
         critical_chance: 0.0,
         abilities_granted: Vec::new(),
+        damage_type: DamageType::Kinetic,
+        forced_move: None,
+        indirect: None,
     };
 
     let base = unit.base_stats.clone();
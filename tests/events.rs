@@ -0,0 +1,147 @@
+use gero::campaign::Campaign;
+use gero::content::ContentDatabase;
+use gero::events::{EventTable, MAX_MORALE};
+use gero::frontend::Renderer;
+use gero::heroes::PLOT_ARMOR_INJURY;
+use gero::input::GameAction;
+use gero::localization::Localizer;
+use gero::models::{Faction, Unit, UnitType};
+use gero::rng::GameRng;
+use gero::ui::events::EventScreen;
+
+fn table() -> EventTable {
+    EventTable::load("assets/content/events.json").unwrap()
+}
+
+fn pending(campaign: &mut Campaign, id: &str) {
+    campaign.pending_event = Some(id.into());
+}
+
+#[test]
+fn draws_skip_done_and_flagged_events() {
+    let table = table();
+    let mut campaign = Campaign::new();
+    let ids: Vec<&str> = table.available(&campaign).iter().map(|e| e.id.as_str()).collect();
+    assert_eq!(ids, ["sealed_vault", "stranded_guardsman", "black_market"]);
+
+    let drawn = campaign.start_event(&table, &mut GameRng::new(1)).unwrap().id.clone();
+    assert_eq!(campaign.pending_event.as_deref(), Some(drawn.as_str()));
+    // A pending event stays put until answered.
+    assert_eq!(campaign.start_event(&table, &mut GameRng::new(99)).unwrap().id, drawn);
+
+    campaign.pending_event = None;
+    pending(&mut campaign, "sealed_vault");
+    campaign.resolve_event(&table, 1, &mut GameRng::new(0)).unwrap();
+    pending(&mut campaign, "black_market");
+    campaign.resolve_event(&table, 1, &mut GameRng::new(0)).unwrap();
+    let ids: Vec<&str> = table.available(&campaign).iter().map(|e| e.id.as_str()).collect();
+    assert_eq!(ids, ["stranded_guardsman", "black_market", "warp_whispers"]);
+}
+
+#[test]
+fn choices_pay_their_way_and_apply_consequences() {
+    let table = table();
+    let mut campaign = Campaign::new();
+    pending(&mut campaign, "black_market");
+    assert_eq!(campaign.resolve_event(&table, 0, &mut GameRng::new(0)), Err("insufficient requisition"));
+    assert_eq!(campaign.resolve_event(&table, 5, &mut GameRng::new(0)), Err("invalid choice"));
+    campaign.currency = 50;
+    let result = campaign.resolve_event(&table, 0, &mut GameRng::new(0)).unwrap();
+    assert!(result.success && result.test.is_none());
+    assert_eq!(result.text, "event.black_market.bought");
+    assert_eq!(campaign.currency, 10);
+    assert_eq!(campaign.inventory, ["carapace_armor"]);
+    assert_eq!(campaign.pending_event, None);
+    assert_eq!(campaign.resolve_event(&table, 0, &mut GameRng::new(0)), Err("no event pending"));
+
+    pending(&mut campaign, "stranded_guardsman");
+    assert_eq!(campaign.resolve_event(&table, 1, &mut GameRng::new(0)), Err("item not in inventory"));
+    campaign.grant_item("lasgun");
+    campaign.morale = MAX_MORALE - 1;
+    campaign.resolve_event(&table, 1, &mut GameRng::new(0)).unwrap();
+    assert_eq!(campaign.morale, MAX_MORALE);
+    assert_eq!(campaign.inventory, ["carapace_armor"]);
+
+    // The log and pending event are kept in the campaign save.
+    pending(&mut campaign, "sealed_vault");
+    let saved: Campaign = serde_json::from_str(&serde_json::to_string(&campaign).unwrap()).unwrap();
+    assert_eq!(saved.event_log, campaign.event_log);
+    assert_eq!(saved.event_log.len(), 2);
+    assert_eq!(saved.pending_event.as_deref(), Some("sealed_vault"));
+    assert_eq!(saved.morale, MAX_MORALE);
+}
+
+#[test]
+fn tests_pick_the_outcome_and_units_come_and_go() {
+    let table = table();
+    let mut campaign = Campaign::new();
+    // With nobody to take it, the test fails.
+    pending(&mut campaign, "stranded_guardsman");
+    let result = campaign.resolve_event(&table, 0, &mut GameRng::new(0)).unwrap();
+    assert!(!result.success);
+    assert_eq!(result.text, "event.stranded_guardsman.refused");
+    assert_eq!(campaign.morale, -1);
+
+    let mut speaker = Unit::new("trooper_vell", "Vell", UnitType::Guardsman, Faction::Imperial);
+    speaker.base_stats.fellowship = 20;
+    speaker.recalculate_stats();
+    campaign.roster.push(speaker);
+    let seed = (0..).find(|&s| GameRng::new(s).roll_d100() < 100).unwrap();
+    pending(&mut campaign, "stranded_guardsman");
+    let result = campaign.resolve_event(&table, 0, &mut GameRng::new(seed)).unwrap();
+    assert!(result.success);
+    assert_eq!(result.test.unwrap().0, "trooper_vell");
+    let ids: Vec<&str> = campaign.roster.iter().map(|u| u.id.as_str()).collect();
+    assert_eq!(ids, ["trooper_vell", "trooper_vell_2"]);
+    assert_eq!(campaign.roster[1].name, "Trooper Vell");
+
+    // Heroes lost to an event are wounded instead.
+    let db = ContentDatabase::load("assets/content/items.json").unwrap();
+    campaign.roster = vec![db.spawn_hero("commissar_raine").unwrap()];
+    campaign.set_flag("informant");
+    let seed = (0..).find(|&s| GameRng::new(s).roll_d100() > 60).unwrap();
+    pending(&mut campaign, "warp_whispers");
+    let result = campaign.resolve_event(&table, 0, &mut GameRng::new(seed)).unwrap();
+    assert!(!result.success);
+    assert_eq!(campaign.roster.len(), 1);
+    assert_eq!(campaign.roster[0].injuries[0].kind, PLOT_ARMOR_INJURY);
+}
+
+#[test]
+fn tested_choices_need_a_failure_outcome() {
+    let data = std::fs::read_to_string("assets/content/events.json").unwrap();
+    let dir = std::env::temp_dir().join("gero_events_test");
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("events.json");
+    let broken = data.replacen(r#""failure": { "text": "event.sealed_vault.alarm", "consequences": [{ "Morale": -1 }] }"#, r#""cost": 0"#, 1);
+    std::fs::write(&path, broken).unwrap();
+    let err = EventTable::load(&path).unwrap_err();
+    assert!(err.to_string().contains("'sealed_vault' tests a choice without a failure outcome"));
+}
+
+#[test]
+fn the_event_screen_walks_through_a_choice() {
+    let table = table();
+    let loc = Localizer::new("en").unwrap();
+    let mut campaign = Campaign::new();
+    pending(&mut campaign, "black_market");
+    let mut screen = EventScreen::new();
+    let mut rng = GameRng::new(0);
+    let mut renderer = Renderer::new_headless(1280, 720);
+    screen.render(&mut renderer, &loc, &table, &campaign);
+    assert!(renderer.text_log.iter().any(|t| t.text == "Buy the armour - 40"));
+
+    screen.handle_input(GameAction::Activate, &table, &mut campaign, &mut rng);
+    assert_eq!(screen.last_error, Some("insufficient requisition"));
+    screen.handle_input(GameAction::SelectDown, &table, &mut campaign, &mut rng);
+    screen.handle_input(GameAction::SelectDown, &table, &mut campaign, &mut rng);
+    assert_eq!(screen.selected_index, 1);
+    screen.handle_input(GameAction::Activate, &table, &mut campaign, &mut rng);
+    assert_eq!(campaign.currency, 20);
+    assert!(!screen.is_finished(&campaign));
+    let mut renderer = Renderer::new_headless(1280, 720);
+    screen.render(&mut renderer, &loc, &table, &campaign);
+    assert!(renderer.text_log.iter().any(|t| t.text == "The Munitorum pays a bounty for the tip."));
+    screen.handle_input(GameAction::Activate, &table, &mut campaign, &mut rng);
+    assert!(screen.is_finished(&campaign));
+}
@@ -0,0 +1,86 @@
+use gero::balance::BalanceConfig;
+use gero::builders::{UnitBuilder, WeaponBuilder};
+use gero::combat::{resolve_attack_with, AttackModifiers, CombatEncounter};
+use gero::command::Command;
+use gero::facing::{Facing, FacingArc};
+use gero::frontend::{Renderer, FACING_MARKER};
+use gero::grid::GridMap;
+use gero::models::{Faction, Position, Unit, UnitType};
+use gero::state::GameState;
+use gero::vehicle::{FacingArmor, Vehicle};
+
+fn at(x: usize, y: usize) -> Position {
+    Position { x, y }
+}
+
+fn guard_facing(facing: Facing) -> Unit {
+    UnitBuilder::new("guard", UnitType::Guardsman, Faction::Imperial).max_health(50).facing(facing).at(2, 2).build()
+}
+
+/// Whether a shot rolling `roll` from `from` hits a guard at (2, 2)
+/// facing `facing`, and the damage it deals.
+fn shot(facing: Facing, from: Position, roll: u8) -> (bool, i32) {
+    let gun = WeaponBuilder::new("gun").damage(4).accuracy(0.5).range(8).build();
+    let mut ork = UnitBuilder::new("ork", UnitType::OrkBoy, Faction::Ork).at(from.x, from.y).build();
+    let mut guard = guard_facing(facing);
+    let balance = BalanceConfig { critical_multiplier: 1, ..BalanceConfig::DEFAULT };
+    let result = resolve_attack_with(&mut ork, &gun, &mut guard, roll, 0, AttackModifiers::default(), &balance).unwrap();
+    // The shooter turns to face its target.
+    assert_eq!(Facing::toward(&ork.grid_position, &guard.grid_position), Some(ork.facing));
+    (result.hit, 50 - guard.health_points)
+}
+
+#[test]
+fn attacks_from_the_side_and_rear_hit_more_often_and_harder() {
+    let front = shot(Facing::North, at(2, 0), 55);
+    let side = shot(Facing::North, at(4, 2), 55);
+    let rear = shot(Facing::North, at(2, 4), 55);
+    assert_eq!((front.0, side.0, rear.0), (false, true, true));
+    assert_eq!(rear.1, side.1 + BalanceConfig::DEFAULT.flanking.rear_damage);
+
+    let flanking = BalanceConfig::DEFAULT.flanking;
+    let guard = guard_facing(Facing::East);
+    assert_eq!(flanking.bonus(&guard, &at(4, 2)), (0, 0));
+    assert_eq!(flanking.bonus(&guard, &at(2, 0)), (flanking.side_accuracy, 0));
+    assert_eq!(flanking.bonus(&guard, &at(0, 3)), (flanking.rear_accuracy, flanking.rear_damage));
+    // Vehicles answer flanking with their rear armour instead.
+    let mut rhino = guard_facing(Facing::East);
+    rhino.vehicle = Some(Vehicle::new(FacingArmor { front: 3, side: 2, rear: 1 }, 0));
+    assert_eq!(flanking.bonus(&rhino, &at(0, 3)), (0, 0));
+}
+
+#[test]
+fn units_face_the_way_they_last_moved() {
+    let mut guard = guard_facing(Facing::North);
+    guard.current_stats.agility = 6;
+    let mut encounter = CombatEncounter::new(vec![guard], Vec::new(), GridMap::new(6, 6), None);
+    encounter.execute(Command::Move { unit_id: "guard".into(), destination: at(1, 3) }).unwrap();
+    assert_eq!(encounter.unit_by_id("guard").unwrap().facing, Facing::SouthWest);
+    assert!(encounter.undo());
+    assert_eq!(encounter.unit_by_id("guard").unwrap().facing, Facing::North);
+}
+
+#[test]
+fn the_ai_goes_round_behind_its_target() {
+    let guard = UnitBuilder::new("guard", UnitType::Guardsman, Faction::Imperial).facing(Facing::West).at(4, 1).build();
+    let slugga = WeaponBuilder::new("slugga").damage(3).range(2).build();
+    let ork = UnitBuilder::new("ork", UnitType::OrkBoy, Faction::Ork).agility(16).weapon(slugga).at(0, 1).build();
+    let mut encounter = CombatEncounter::new(vec![guard], vec![ork], GridMap::new(8, 3), None);
+    encounter.turn_order.current_unit_id = Some("ork".into());
+    encounter.ai_take_action(50);
+    let (ork, guard) = (encounter.unit_by_id("ork").unwrap(), encounter.unit_by_id("guard").unwrap());
+    assert_eq!((ork.grid_position.clone(), ork.facing), (at(5, 1), Facing::East));
+    assert_eq!(guard.facing.arc(&guard.grid_position, &ork.grid_position), FacingArc::Rear);
+}
+
+#[test]
+fn units_are_drawn_with_an_arrow_the_way_they_face() {
+    let mut renderer = Renderer::new_headless(320, 240);
+    renderer.load_sprite_from_bytes("guard", vec![vec![0]]);
+    renderer.load_sprite_from_bytes(FACING_MARKER, vec![vec![0]; 8]);
+    let mut guard = guard_facing(Facing::SouthEast);
+    guard.sprite_id = "guard".into();
+    renderer.render_state(&GameState::new(vec![guard]));
+    let arrow = renderer.draw_log.iter().find(|c| c.sprite_id == FACING_MARKER).unwrap();
+    assert_eq!((arrow.position, arrow.frame_index), ((2, 2), Facing::SouthEast as usize));
+}
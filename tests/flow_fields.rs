@@ -0,0 +1,105 @@
+use gero::combat::CombatEncounter;
+use gero::facing::Facing;
+use gero::grid::{try_move, FlowFieldCache, GridMap, TerrainType};
+use gero::models::{Faction, MovementType, Position, Unit, UnitType};
+use gero::weather::Weather;
+
+/// 5x5 map with a wall down column 2, open only at the bottom row.
+fn walled_map() -> GridMap {
+    let mut map = GridMap::new(5, 5);
+    for y in 0..4 {
+        map.set_terrain(&Position { x: 2, y }, TerrainType::Blocked);
+    }
+    map
+}
+
+#[test]
+fn flow_field_cost_matches_cheapest_path() {
+    let map = walled_map();
+    let target = Position { x: 4, y: 0 };
+    let mut cache = FlowFieldCache::default();
+    let field = cache.get(&map, &target, MovementType::Ground, Weather::Clear);
+    // Down to the gap, across and back up: 3 + 1 + 2 diagonal + 2 + 1.
+    let cost = field.cost(&map, &Position { x: 0, y: 0 }).unwrap();
+    assert_eq!(field.cost(&map, &target), Some(0));
+
+    let mover = |mp: u32| {
+        let mut unit = Unit::new("u", "U", UnitType::Guardsman, Faction::Imperial);
+        unit.current_stats.agility = mp as i32 * 2;
+        unit.action_points = 1;
+        unit
+    };
+    assert!(try_move(&mut mover(cost), target.clone(), &map));
+    assert!(!try_move(&mut mover(cost - 1), target.clone(), &map));
+
+    let end = field.advance(&map, &Position { x: 0, y: 0 }, 3);
+    assert_eq!(field.cost(&map, &end), Some(cost - 3));
+    assert_eq!(field.advance(&map, &Position { x: 0, y: 0 }, u32::MAX), target);
+}
+
+#[test]
+fn cache_reuses_fields_until_the_map_changes() {
+    let mut map = walled_map();
+    let target = Position { x: 4, y: 0 };
+    let mut cache = FlowFieldCache::default();
+    cache.get(&map, &target, MovementType::Ground, Weather::Clear);
+    cache.get(&map, &target, MovementType::Ground, Weather::Clear);
+    assert_eq!(cache.len(), 1);
+    cache.get(&map, &target, MovementType::Flying, Weather::Clear);
+    assert_eq!(cache.len(), 2);
+
+    map.set_terrain(&Position { x: 2, y: 0 }, TerrainType::Normal);
+    let field = cache.get(&map, &target, MovementType::Ground, Weather::Clear);
+    assert_eq!(field.cost(&map, &Position { x: 0, y: 0 }), Some(4));
+    assert_eq!(cache.len(), 1);
+}
+
+#[test]
+fn fields_towards_a_unit_only_search_as_far_as_it() {
+    let map = GridMap::new(40, 1);
+    let target = Position { x: 0, y: 0 };
+    let full = FlowFieldCache::default().get(&map, &target, MovementType::Ground, Weather::Clear).clone();
+    let mut cache = FlowFieldCache::default();
+    let near = Position { x: 5, y: 0 };
+    let field = cache.towards(&map, &target, MovementType::Ground, Weather::Clear, &near);
+    assert_eq!(field.cost(&map, &near), Some(5));
+    assert_eq!(field.cost(&map, &Position { x: 39, y: 0 }), None);
+    assert_eq!(field.advance(&map, &near, 3), full.advance(&map, &near, 3));
+
+    // Asking from further away carries on the same search.
+    let far = Position { x: 30, y: 0 };
+    let field = cache.towards(&map, &target, MovementType::Ground, Weather::Clear, &far);
+    assert_eq!(field.cost(&map, &far), Some(30));
+    assert_eq!(cache.len(), 1);
+
+    // Fields dropped at the end of a round are rebuilt from scratch.
+    cache.clear();
+    let field = cache.get(&map, &target, MovementType::Ground, Weather::Clear);
+    assert_eq!(field.cost(&map, &Position { x: 39, y: 0 }), Some(39));
+}
+
+#[test]
+fn ai_walks_around_walls_and_range_skips_blocked_cells() {
+    let map = walled_map();
+    let mut ork = Unit::new("o", "O", UnitType::OrkBoy, Faction::Ork);
+    ork.current_stats.agility = 8;
+    ork.action_points = 1;
+    let mut guard = Unit::new("g", "G", UnitType::Guardsman, Faction::Imperial);
+    guard.grid_position = Position { x: 4, y: 0 };
+    // Back to the map edge, so there is no getting behind it.
+    guard.facing = Facing::South;
+    let mut enc = CombatEncounter::new(vec![guard], vec![ork], map, None);
+
+    let range = enc.movement_range("o");
+    assert!(range.contains(&Position { x: 0, y: 4 }));
+    assert!(!range.contains(&Position { x: 0, y: 0 }));
+    assert!(range.iter().all(|p| p.x < 2 || p.y == 4));
+
+    enc.turn_order.current_unit_id = Some("o".into());
+    enc.ai_take_action(50);
+    // Four points spent heading for the gap instead of stopping at the wall.
+    let end = enc.unit_by_id("o").unwrap().grid_position.clone();
+    let field = enc.flow_fields.get(&enc.battlefield, &Position { x: 4, y: 0 }, MovementType::Ground, Weather::Clear);
+    assert_eq!(field.cost(&enc.battlefield, &end), Some(field.cost(&enc.battlefield, &Position { x: 0, y: 0 }).unwrap() - 4));
+    assert_eq!(enc.flow_fields.len(), 1);
+}
@@ -0,0 +1,125 @@
+use gero::combat::CombatEncounter;
+use gero::command::Command;
+use gero::flow::GameFlow;
+use gero::formation::{formation_spot, MoveQueue, GROUP_MOVE_SECONDS};
+use gero::grid::{GridMap, TerrainType};
+use gero::models::{AnimationType, Faction, Position, Unit, UnitType};
+use gero::state::GameState;
+use gero::ui::UiManager;
+
+fn unit(id: &str, faction: Faction, x: usize, y: usize) -> Unit {
+    let mut unit = Unit::new(id, id, UnitType::Guardsman, faction);
+    unit.grid_position = Position { x, y };
+    unit.current_stats.agility = 12; // 6 MP
+    unit
+}
+
+fn pos(x: usize, y: usize) -> Position {
+    Position { x, y }
+}
+
+fn ids(ids: &[&str]) -> Vec<String> {
+    ids.iter().map(|s| s.to_string()).collect()
+}
+
+fn destinations(moves: &[Command]) -> Vec<(&str, Position)> {
+    moves
+        .iter()
+        .map(|c| match c {
+            Command::Move { unit_id, destination } => (unit_id.as_str(), destination.clone()),
+            other => panic!("not a move: {other:?}"),
+        })
+        .collect()
+}
+
+/// A column of three guardsmen on the left of a 10x10 map.
+fn encounter() -> CombatEncounter {
+    let squad = vec![unit("a", Faction::Imperial, 1, 4), unit("b", Faction::Imperial, 1, 5), unit("c", Faction::Imperial, 1, 6)];
+    CombatEncounter::new(squad, vec![unit("ork", Faction::Ork, 9, 9)], GridMap::new(10, 10), None)
+}
+
+#[test]
+fn formation_spots_keep_offsets_from_the_leader() {
+    assert_eq!(formation_spot(&pos(1, 4), &pos(1, 6), &pos(5, 2)), Some(pos(5, 4)));
+    assert_eq!(formation_spot(&pos(3, 3), &pos(1, 3), &pos(1, 0)), None);
+}
+
+#[test]
+fn group_moves_keep_formation_around_the_clicked_cell() {
+    let enc = encounter();
+    let moves = enc.plan_group_move(&ids(&["a", "b", "c"]), &pos(4, 4));
+    assert_eq!(destinations(&moves), [("a", pos(4, 4)), ("b", pos(4, 5)), ("c", pos(4, 6))]);
+    assert!(enc.plan_group_move(&[], &pos(4, 4)).is_empty());
+}
+
+#[test]
+fn conflicts_move_units_to_the_nearest_free_reachable_cell() {
+    let mut enc = encounter();
+    enc.battlefield.set_terrain(&pos(4, 5), TerrainType::Blocked);
+    enc.units.by_name_mut("ork").unwrap().grid_position = pos(4, 6);
+    let moves = enc.plan_group_move(&ids(&["a", "b", "c"]), &pos(4, 4));
+    let planned = destinations(&moves);
+    assert_eq!(planned[0], ("a", pos(4, 4)));
+    let taken: Vec<&Position> = planned.iter().map(|(_, p)| p).collect();
+    for (id, cell) in &planned[1..] {
+        assert!(*cell != pos(4, 5) && *cell != pos(4, 6), "{id} sent to {cell:?}");
+        assert_eq!(taken.iter().filter(|p| **p == cell).count(), 1);
+    }
+
+    // Out of reach: the leader gets as close as its movement allows.
+    enc.units.by_name_mut("a").unwrap().action_points = 1;
+    let far = enc.plan_group_move(&ids(&["a"]), &pos(9, 0));
+    let (_, cell) = destinations(&far)[0].clone();
+    assert!(cell.x <= 7 && enc.movement_range("a").contains(&cell));
+}
+
+#[test]
+fn units_leave_a_cell_before_another_moves_onto_it() {
+    let enc = encounter();
+    // Shifting the column down one: c must go before b, and b before a.
+    let moves = enc.plan_group_move(&ids(&["a", "b", "c"]), &pos(1, 5));
+    assert_eq!(destinations(&moves), [("c", pos(1, 7)), ("b", pos(1, 6)), ("a", pos(1, 5))]);
+}
+
+#[test]
+fn queued_moves_play_one_at_a_time() {
+    let mut enc = encounter();
+    let mut queue = MoveQueue::new();
+    queue.push(enc.plan_group_move(&ids(&["a", "b"]), &pos(4, 4)));
+    queue.push([Command::Move { unit_id: "c".into(), destination: pos(9, 0) }]);
+    assert_eq!(queue.len(), 3);
+
+    assert!(queue.update(0.0, &mut enc).is_empty());
+    assert_eq!(enc.unit_by_id("a").unwrap().grid_position, pos(4, 4));
+    assert!(matches!(enc.unit_by_id("a").unwrap().animation_state.current_animation, AnimationType::Move));
+    assert_eq!(enc.unit_by_id("b").unwrap().grid_position, pos(1, 5));
+
+    queue.update(GROUP_MOVE_SECONDS / 2.0, &mut enc);
+    assert_eq!(enc.unit_by_id("b").unwrap().grid_position, pos(1, 5));
+    queue.update(GROUP_MOVE_SECONDS, &mut enc);
+    assert!(matches!(enc.unit_by_id("a").unwrap().animation_state.current_animation, AnimationType::Idle));
+    assert_eq!(enc.unit_by_id("b").unwrap().grid_position, pos(4, 5));
+
+    // c can't reach its cell, so it is skipped and the queue empties.
+    let failed = queue.update(GROUP_MOVE_SECONDS, &mut enc);
+    assert_eq!(failed, [Command::Move { unit_id: "c".into(), destination: pos(9, 0) }]);
+    assert!(queue.is_empty());
+    assert!(matches!(enc.unit_by_id("b").unwrap().animation_state.current_animation, AnimationType::Idle));
+}
+
+#[test]
+fn the_flow_orders_the_selection_and_holds_moves_while_paused() {
+    let mut enc = encounter();
+    let mut flow = GameFlow::new(GameState::from_encounter(&enc), UiManager::new(1280, 720, Vec::new(), Vec::new()));
+    flow.state.set_selection(ids(&["b", "c"]));
+    assert_eq!(flow.order_group_move(&pos(5, 5), &enc), 2);
+
+    flow.ui.modal = Some(gero::ui::modal::ModalDialog::new("t", "m", &flow.ui.layout));
+    flow.update_moves(1.0, &mut enc);
+    assert_eq!(enc.unit_by_id("b").unwrap().grid_position, pos(1, 5));
+    flow.ui.modal = None;
+    flow.update_moves(0.0, &mut enc);
+    flow.update_moves(GROUP_MOVE_SECONDS, &mut enc);
+    assert_eq!(enc.unit_by_id("b").unwrap().grid_position, pos(5, 5));
+    assert_eq!(enc.unit_by_id("c").unwrap().grid_position, pos(5, 6));
+}
@@ -14,7 +14,7 @@ fn renderer_can_render_state() {
 
 #[test]
 fn renderer_issues_draw_calls() {
-    let mut renderer = Renderer::new_headless(64, 64);
+    let mut renderer = Renderer::new_headless(320, 320);
     renderer.load_sprite_from_bytes("guard", vec![vec![0, 1], vec![2, 3]]);
     let mut unit = Unit::new("u", "U", UnitType::Guardsman, Faction::Imperial);
     unit.sprite_id = "guard".into();
@@ -28,3 +28,138 @@ fn renderer_issues_draw_calls() {
     assert_eq!(call.position, (3, 4));
     assert_eq!(call.frame_index, 1);
 }
+
+#[test]
+fn terrain_tiles_are_drawn_below_units_and_culled_by_camera() {
+    use gero::grid::{GridMap, TerrainType};
+
+    let mut renderer = Renderer::new_headless(128, 64);
+    renderer.tile_size = 32;
+    renderer.load_sprite_from_bytes("tile_normal", vec![vec![0]]);
+    renderer.load_sprite_from_bytes("tile_blocked", vec![vec![0]]);
+    renderer.load_sprite_from_bytes("guard", vec![vec![0]]);
+    let mut unit = Unit::new("u", "U", UnitType::Guardsman, Faction::Imperial);
+    unit.sprite_id = "guard".into();
+    let mut state = GameState::new(vec![unit]);
+    state.map = GridMap::new(8, 8);
+    state.map.set_terrain(&Position { x: 1, y: 0 }, TerrainType::Blocked);
+
+    renderer.render_state(&state);
+    // viewport covers 4x2 tiles, plus the unit drawn last
+    assert_eq!(renderer.draw_log.len(), 9);
+    assert_eq!(renderer.draw_log[1].sprite_id, "tile_blocked");
+    assert_eq!(renderer.draw_log[8].sprite_id, "guard");
+
+    renderer.camera.x_offset = 160.0;
+    renderer.render_state(&state);
+    let tiles: Vec<_> = renderer.draw_log.iter().filter(|c| c.sprite_id.starts_with("tile_")).collect();
+    assert_eq!(tiles.len(), 6);
+    assert!(tiles.iter().all(|c| c.position.0 >= 5));
+}
+
+#[test]
+fn overlays_render_above_units() {
+    use gero::frontend::tiles::OverlayKind;
+
+    let mut renderer = Renderer::new_headless(640, 640);
+    renderer.load_sprite_from_bytes("guard", vec![vec![0]]);
+    let mut unit = Unit::new("u", "U", UnitType::Guardsman, Faction::Imperial);
+    unit.sprite_id = "guard".into();
+    let state = GameState::new(vec![unit]);
+
+    renderer.set_overlay(OverlayKind::MovementRange, &[Position { x: 0, y: 1 }, Position { x: 1, y: 0 }]);
+    renderer.set_overlay(OverlayKind::Targeting, &[Position { x: 2, y: 2 }]);
+    renderer.render_state(&state);
+    let ids: Vec<_> = renderer.draw_log.iter().map(|c| c.sprite_id.as_str()).collect();
+    assert_eq!(ids, vec!["guard", "overlay:move", "overlay:move", "overlay:target"]);
+
+    renderer.clear_overlay(OverlayKind::MovementRange);
+    renderer.render_state(&state);
+    assert_eq!(renderer.draw_log.len(), 2);
+}
+
+#[test]
+fn offscreen_units_are_culled() {
+    let mut renderer = Renderer::new_headless(128, 128);
+    renderer.load_sprite_from_bytes("guard", vec![vec![0]]);
+    let mut near = Unit::new("near", "Near", UnitType::Guardsman, Faction::Imperial);
+    near.sprite_id = "guard".into();
+    near.grid_position = Position { x: 1, y: 1 };
+    let mut far = Unit::new("far", "Far", UnitType::Guardsman, Faction::Imperial);
+    far.sprite_id = "guard".into();
+    far.grid_position = Position { x: 8, y: 8 };
+    renderer.render_state(&GameState::new(vec![near, far]));
+    assert_eq!(renderer.draw_log.len(), 1);
+    assert_eq!(renderer.draw_log[0].position, (1, 1));
+    // culled units still have their position tracked
+    assert_eq!(renderer.sprites.get("far"), Some(&(8, 8, 0, 1)));
+}
+
+#[test]
+fn draw_calls_are_sorted_by_layer_and_row() {
+    use gero::frontend::{DrawCall, DrawLayer};
+    use gero::ui::UiManager;
+    use gero::localization::Localizer;
+
+    let mut renderer = Renderer::new_headless(640, 640);
+    renderer.load_sprite_from_bytes("tile_normal", vec![vec![0]]);
+    renderer.load_sprite_from_bytes("guard", vec![vec![0]]);
+    let mut lower = Unit::new("lower", "Lower", UnitType::Guardsman, Faction::Imperial);
+    lower.sprite_id = "guard".into();
+    lower.grid_position = Position { x: 0, y: 5 };
+    let mut upper = Unit::new("upper", "Upper", UnitType::Guardsman, Faction::Imperial);
+    upper.sprite_id = "guard".into();
+    upper.grid_position = Position { x: 0, y: 2 };
+    renderer.render_state(&GameState::new(vec![lower, upper]));
+
+    let mut ui = UiManager::new(640, 640, vec![], vec![]);
+    ui.render(&mut renderer, &Localizer::new("en").unwrap());
+    // an effect submitted after the UI still lands below it
+    renderer.submit(DrawCall::new("muzzle", (0, 2), DrawLayer::Effects));
+
+    let layers: Vec<_> = renderer.draw_log.iter().map(|c| c.layer).collect();
+    let mut sorted = layers.clone();
+    sorted.sort();
+    assert_eq!(layers, sorted);
+    let units: Vec<_> = renderer.draw_log.iter().filter(|c| c.layer == DrawLayer::Units).map(|c| c.position.1).collect();
+    assert_eq!(units, vec![2, 5]);
+    assert_eq!(renderer.draw_log.last().unwrap().layer, DrawLayer::Ui);
+}
+
+#[test]
+fn present_hands_the_frame_to_the_backend_in_screen_pixels() {
+    use std::sync::{Arc, Mutex};
+
+    use gero::frontend::backend::{DrawRect, HeadlessBackend};
+    use gero::frontend::text::WHITE;
+    use gero::frontend::{DrawCall, DrawLayer};
+
+    let backend = Arc::new(Mutex::new(HeadlessBackend::default()));
+    let mut renderer = Renderer::with_backend(640, 480, backend.clone());
+    renderer.load_sprite_from_bytes("guard", vec![vec![0]]);
+    let mut unit = Unit::new("u", "U", UnitType::Guardsman, Faction::Imperial);
+    unit.sprite_id = "guard".into();
+    unit.grid_position = Position { x: 3, y: 2 };
+    renderer.camera.x_offset = 32.0;
+    renderer.camera.zoom_level = 2.0;
+    renderer.render_state(&GameState::new(vec![unit]));
+    renderer.submit(DrawCall::new("panel.hud", (10, 20), DrawLayer::Ui));
+    let backdrop = DrawRect { position: (0, 0), size: (40, 40), color: WHITE, layer: DrawLayer::Ui };
+    renderer.draw_rect(backdrop.clone());
+    renderer.draw_text("hi", (5, 5), 12.0, WHITE);
+    renderer.present().unwrap();
+
+    let backend = backend.lock().unwrap();
+    assert_eq!(backend.frames_presented, 1);
+    let frame = &backend.last_frame;
+    let placed: Vec<(&str, (i32, i32), f32)> = frame.sprites.iter().map(|(c, at, s)| (c.sprite_id.as_str(), *at, *s)).collect();
+    let guard = placed.iter().find(|(id, ..)| *id == "guard").unwrap();
+    assert_eq!(*guard, ("guard", (3 * 128 - 32, 2 * 128), 2.0));
+    assert_eq!(placed.last(), Some(&("panel.hud", (10, 20), 1.0)));
+    assert_eq!(frame.rects, [backdrop]);
+    assert_eq!(frame.texts.len(), 1);
+    // The logs stay for inspection until the next frame.
+    assert_eq!(renderer.text_log.len(), 1);
+    renderer.clear_frame();
+    assert!(renderer.draw_log.is_empty() && renderer.rect_log.is_empty());
+}
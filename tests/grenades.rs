@@ -0,0 +1,100 @@
+use gero::combat::{CombatEncounter, CombatError, CombatEvent};
+use gero::command::Command;
+use gero::frontend::tiles::OverlayKind;
+use gero::frontend::Renderer;
+use gero::grenade::{blast_cells, scatter, throw_range};
+use gero::grid::{GridMap, TerrainType};
+use gero::models::{Accessory, Faction, Position, Stats, Unit, UnitType};
+use gero::state::GameState;
+
+fn pos(x: usize, y: usize) -> Position {
+    Position { x, y }
+}
+
+fn unit(id: &str, faction: Faction, at: Position) -> Unit {
+    let mut unit = Unit::new(id, id, UnitType::Guardsman, faction);
+    unit.grid_position = at;
+    unit
+}
+
+fn encounter(accuracy: i32) -> CombatEncounter {
+    let mut thrower = unit("t", Faction::Imperial, pos(0, 2));
+    thrower.base_stats.accuracy = accuracy;
+    thrower.equipment.accessory_slots.push(Accessory::Grenade { damage: 4, aoe_radius: 1, leaves: None });
+    thrower.recalculate_stats();
+    let mut map = GridMap::new(8, 5);
+    map.place_fragile(&pos(3, 3));
+    CombatEncounter::new(
+        vec![thrower, unit("ally", Faction::Imperial, pos(3, 1))],
+        vec![unit("ork", Faction::Ork, pos(3, 2)), unit("far", Faction::Ork, pos(6, 2))],
+        map,
+        None,
+    )
+}
+
+#[test]
+fn throw_range_grows_with_strength() {
+    assert_eq!(throw_range(&Stats::default()), 3);
+    assert_eq!(throw_range(&Stats { strength: 4, ..Default::default() }), 5);
+}
+
+#[test]
+fn blast_is_a_clipped_circle() {
+    let map = GridMap::new(8, 8);
+    assert_eq!(blast_cells(&map, &pos(4, 4), 1).len(), 5);
+    assert_eq!(blast_cells(&map, &pos(4, 4), 2).len(), 13);
+    assert_eq!(blast_cells(&map, &pos(0, 0), 1).len(), 3);
+    assert_eq!(scatter(&map, &pos(0, 0), 0, 2), pos(0, 0));
+    assert_eq!(scatter(&map, &pos(4, 4), 2, 2), pos(6, 4));
+}
+
+#[test]
+fn grenade_hits_friends_and_foes_and_breaks_objects() {
+    let mut enc = encounter(100);
+    enc.execute(Command::ThrowGrenade { thrower_id: "t".into(), accessory_index: 0, target: pos(3, 2) }).unwrap();
+    assert_eq!(enc.unit_by_id("ork").unwrap().health_points, 6);
+    assert_eq!(enc.unit_by_id("ally").unwrap().health_points, 6);
+    assert_eq!(enc.unit_by_id("far").unwrap().health_points, 10);
+    assert_eq!(enc.unit_by_id("t").unwrap().health_points, 10);
+    assert_eq!(*enc.battlefield.terrain_at(&pos(3, 3)), TerrainType::Normal);
+    assert!(enc.events.contains(&CombatEvent::ObjectDestroyed { position: pos(3, 3) }));
+    assert!(enc.events.iter().any(|e| matches!(e, CombatEvent::Explosion { center, .. } if *center == pos(3, 2))));
+
+    let t = enc.unit_by_id("t").unwrap();
+    assert!(t.equipment.accessory_slots.is_empty());
+    assert_eq!(t.action_points, 1);
+}
+
+#[test]
+fn misses_scatter_and_bad_throws_are_refused() {
+    let mut enc = encounter(-500);
+    assert_eq!(enc.throw_grenade("t", 0, &pos(5, 2)), Err(CombatError::OutOfRange));
+    assert_eq!(enc.throw_grenade("t", 1, &pos(3, 2)), Err(CombatError::NoGrenade));
+    let throw = enc.throw_grenade("t", 0, &pos(3, 2)).unwrap();
+    assert!(throw.scattered);
+    let offset = throw.impact.x.abs_diff(3).max(throw.impact.y.abs_diff(2));
+    assert!((1..=2).contains(&offset));
+}
+
+#[test]
+fn preview_shows_arc_and_blast() {
+    let map = GridMap::new(8, 5);
+    let mut renderer = Renderer::new_headless(640, 480);
+    renderer.set_grenade_preview(&map, &pos(0, 2), &pos(3, 2), 1);
+    let cells = |kind| renderer.overlays.iter().filter(|o| o.kind == kind).count();
+    assert_eq!(cells(OverlayKind::ThrowArc), 3);
+    assert_eq!(cells(OverlayKind::BlastArea), 5);
+    renderer.clear_grenade_preview();
+    assert!(renderer.overlays.is_empty());
+}
+
+#[test]
+fn maps_with_fragile_objects_reload_with_the_same_checksum() {
+    let mut enc = encounter(0);
+    for x in 0..8 {
+        enc.battlefield.place_fragile(&pos(x, 4));
+    }
+    let state = GameState::from_encounter(&enc);
+    let reloaded = GameState::load_from_str(&state.save_to_string());
+    assert_eq!(reloaded.checksum(), state.checksum());
+}
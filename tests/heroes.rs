@@ -0,0 +1,107 @@
+use gero::campaign::Campaign;
+use gero::content::ContentDatabase;
+use gero::frontend::Renderer;
+use gero::grid::GridMap;
+use gero::heroes::PLOT_ARMOR_INJURY;
+use gero::localization::Localizer;
+use gero::models::{Faction, Unit, UnitType};
+use gero::registry::{Side, UnitRegistry};
+use gero::simulation::{AutoResolveReport, BattleOutcome};
+use gero::ui::UiManager;
+
+fn db() -> ContentDatabase {
+    ContentDatabase::load("assets/content/items.json").unwrap()
+}
+
+fn learned(unit: &Unit) -> Vec<&str> {
+    unit.abilities.iter().map(|a| a.id.as_str()).collect()
+}
+
+#[test]
+fn heroes_spawn_from_their_templates() {
+    let db = db();
+    let raine = db.spawn_hero("commissar_raine").unwrap();
+    assert_eq!(raine.name, "Commissar Raine");
+    assert_eq!(raine.unit_type, UnitType::Commissar);
+    assert_eq!(raine.hero.as_deref(), Some("commissar_raine"));
+    assert_eq!(raine.portrait.as_deref(), Some("portrait:commissar"));
+    assert_eq!(raine.health_points, 16);
+    assert!(raine.is_hero());
+    assert!(db.spawn_hero("nobody").is_none());
+    assert!(!Unit::new("g", "G", UnitType::Commissar, Faction::Imperial).is_hero());
+}
+
+#[test]
+fn ability_trees_unlock_one_pick_per_level() {
+    let db = db();
+    let hero = db.hero("commissar_raine").unwrap();
+    let mut raine = hero.spawn("commissar_raine");
+    assert!(hero.unlockable(&raine).is_empty());
+    assert_eq!(hero.learn(&mut raine, "rally_cry"), Err("ability locked"));
+
+    raine.level_up(None);
+    let ids: Vec<&str> = hero.unlockable(&raine).iter().map(|a| a.id.as_str()).collect();
+    assert_eq!(ids, ["rally_cry", "summary_execution"]);
+    hero.learn(&mut raine, "summary_execution").unwrap();
+    assert_eq!(hero.learn(&mut raine, "rally_cry"), Err("no ability points"));
+    assert_eq!(hero.learn(&mut raine, "summary_execution"), Err("ability already learned"));
+    assert_eq!(hero.learn(&mut raine, "lasgun"), Err("unknown ability"));
+
+    // Later nodes wait on both the level and the node before them.
+    raine.level_up(None);
+    assert_eq!(hero.learn(&mut raine, "inspiring_presence"), Err("ability locked"));
+    hero.learn(&mut raine, "chainsword_flurry").unwrap();
+    assert_eq!(learned(&raine), ["summary_execution", "chainsword_flurry"]);
+    assert_eq!(hero.ability_points(&raine), 0);
+
+    let mut campaign = Campaign::new();
+    raine.level_up(None);
+    campaign.roster = vec![raine, Unit::new("g", "G", UnitType::Guardsman, Faction::Imperial)];
+    assert_eq!(campaign.learn_hero_ability(&db, "g", "rally_cry"), Err("not a hero"));
+    campaign.learn_hero_ability(&db, "commissar_raine", "rally_cry").unwrap();
+    assert_eq!(learned(&campaign.roster[0]).len(), 3);
+}
+
+#[test]
+fn malformed_trees_are_rejected() {
+    let data = std::fs::read_to_string("assets/content/items.json").unwrap();
+    let dir = std::env::temp_dir().join("gero_heroes_test");
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("items.json");
+    std::fs::write(&path, data.replace("\"id\": \"chainsword_flurry\"", "\"id\": \"rally_cry\"")).unwrap();
+    let err = ContentDatabase::load(&path).unwrap_err();
+    assert!(err.to_string().contains("repeats ability 'rally_cry'"));
+    std::fs::write(&path, data.replace("\"level\": 4", "\"level\": 1")).unwrap();
+    assert!(ContentDatabase::load(&path).unwrap_err().to_string().contains("out of level order"));
+}
+
+#[test]
+fn plot_armor_wounds_heroes_instead_of_killing_them() {
+    let db = db();
+    let mut campaign = Campaign::new();
+    let grunt = Unit::new("g", "G", UnitType::Guardsman, Faction::Imperial);
+    campaign.roster = vec![db.spawn_hero("commissar_raine").unwrap(), grunt];
+    let report = AutoResolveReport {
+        outcome: BattleOutcome::EnemyVictory,
+        confidence: 1.0,
+        casualties: vec!["commissar_raine".into(), "g".into()],
+        experience: 0,
+        loot: Vec::new(),
+    };
+    campaign.apply_auto_resolve(&["commissar_raine".into(), "g".into()], &report);
+    assert_eq!(campaign.roster.len(), 1);
+    assert_eq!(campaign.roster[0].injuries[0].kind, PLOT_ARMOR_INJURY);
+    assert!(campaign.roster[0].is_recovering());
+}
+
+#[test]
+fn the_info_panel_shows_hero_portraits() {
+    let mut units = UnitRegistry::new();
+    units.insert(db().spawn_hero("commissar_raine").unwrap(), Side::Player);
+    let mut ui = UiManager::new(1280, 720, Vec::new(), Vec::new());
+    ui.selection = vec!["commissar_raine".into()];
+    let mut renderer = Renderer::new_headless(1280, 720);
+    ui.render_selection(&mut renderer, &Localizer::new("en").unwrap(), &units, &GridMap::new(4, 4));
+    assert!(renderer.draw_log.iter().any(|c| c.sprite_id == "portrait:commissar"));
+    assert!(renderer.text_log.iter().any(|t| t.text == "Commissar Raine"));
+}
@@ -0,0 +1,87 @@
+use gero::builders::{AbilityBuilder, UnitBuilder, WeaponBuilder};
+use gero::combat::{CombatEncounter, EnvironmentalEffect};
+use gero::flow::GameFlow;
+use gero::frontend::Renderer;
+use gero::grid::GridMap;
+use gero::hints::{Hint, HintEngine};
+use gero::models::{AbilityType, Faction, Position, UnitType};
+use gero::state::GameState;
+use gero::ui::options::HintSettings;
+use gero::ui::UiManager;
+
+/// A guard with a gun and `ap` action points, whose turn it is, and an ork
+/// five cells away.
+fn battle(ap: u32) -> CombatEncounter {
+    let gun = WeaponBuilder::new("gun").range(6).build();
+    let mut guard = UnitBuilder::new("guard", UnitType::Guardsman, Faction::Imperial).weapon(gun).at(0, 0).build();
+    guard.action_points = ap;
+    let ork = UnitBuilder::new("ork", UnitType::OrkBoy, Faction::Ork).at(5, 0).build();
+    let mut battle = CombatEncounter::new(vec![guard], vec![ork], GridMap::new(8, 1), None);
+    battle.turn_order.current_unit_id = Some("guard".into());
+    battle
+}
+
+fn next_round(battle: &mut CombatEncounter) {
+    battle.turn_order.round_number += 1;
+}
+
+#[test]
+fn hints_follow_the_situation() {
+    let mut hints = HintEngine::default();
+    assert_eq!(hints.observe(&battle(0)), None);
+
+    let mut battle = battle(1);
+    assert_eq!(hints.observe(&battle), Some(Hint::TargetInRange));
+    // Fire takes priority over everything else.
+    battle.environmental_effects.push(EnvironmentalEffect::FirePatch { grid_cells: vec![Position { x: 0, y: 0 }], damage_per_turn: 2 });
+    next_round(&mut battle);
+    assert_eq!(hints.observe(&battle), Some(Hint::InFire));
+
+    let blast = AbilityBuilder::new("blast", AbilityType::PsychicBlast).cooldown(2).range(5).build();
+    battle.unit_by_id_mut("guard").unwrap().abilities.push(blast);
+    battle.environmental_effects.clear();
+    next_round(&mut battle);
+    assert_eq!(hints.observe(&battle), Some(Hint::AbilityReady));
+    battle.unit_by_id_mut("guard").unwrap().abilities[0].current_cooldown = 1;
+    next_round(&mut battle);
+    assert_eq!(hints.observe(&battle), Some(Hint::TargetInRange));
+
+    // AI turns get no hints.
+    let mut battle = self::battle(1);
+    battle.turn_order.current_unit_id = Some("ork".into());
+    assert_eq!(HintEngine::default().observe(&battle), None);
+}
+
+#[test]
+fn hints_are_throttled() {
+    let mut battle = battle(1);
+    let mut hints = HintEngine::new(HintSettings { enabled: true, cooldown_rounds: 2 });
+    assert_eq!(hints.observe(&battle), Some(Hint::TargetInRange));
+    assert_eq!(hints.observe(&battle), None, "one look per turn");
+    next_round(&mut battle);
+    assert_eq!(hints.observe(&battle), None);
+    next_round(&mut battle);
+    assert_eq!(hints.observe(&battle), Some(Hint::TargetInRange));
+
+    hints.dismiss(Hint::TargetInRange);
+    battle.turn_order.round_number += 5;
+    assert_eq!(hints.observe(&battle), None);
+
+    let mut off = HintEngine::new(HintSettings { enabled: false, ..HintSettings::default() });
+    assert_eq!(off.observe(&self::battle(1)), None);
+}
+
+#[test]
+fn hints_show_as_dismissible_toasts() {
+    let mut flow = GameFlow::new(GameState::new(Vec::new()), UiManager::new(1280, 720, Vec::new(), Vec::new()));
+    let mut renderer = Renderer::new_headless(640, 480);
+    let mut battle = battle(1);
+    flow.present_actions(0.0, &mut battle, &mut renderer);
+    let toast = &flow.ui.toasts.active[0].0;
+    assert_eq!((toast.title.as_str(), toast.body.as_str()), ("hint.target_in_range", "hint.target_in_range.body"));
+
+    assert!(flow.dismiss_hint());
+    assert!(flow.ui.toasts.active.is_empty());
+    assert!(flow.hints.is_dismissed(Hint::TargetInRange));
+    assert!(!flow.dismiss_hint());
+}
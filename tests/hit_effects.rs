@@ -0,0 +1,64 @@
+use gero::combat::CombatEvent;
+use gero::frontend::{DrawLayer, Renderer};
+use gero::frontend::effects::{FLASH_WHITE, POISON_RED};
+use gero::models::{Unit, UnitType, Faction, StatusEffect, EffectType};
+use gero::state::GameState;
+
+fn guard(id: &str) -> Unit {
+    let mut unit = Unit::new(id, id, UnitType::Guardsman, Faction::Imperial);
+    unit.sprite_id = "guard".into();
+    unit
+}
+
+fn renderer() -> Renderer<'static> {
+    let mut renderer = Renderer::new_headless(640, 640);
+    renderer.load_sprite_from_bytes("guard", vec![vec![0]]);
+    renderer
+}
+
+#[test]
+fn damage_flashes_white_until_timer_expires() {
+    let mut renderer = renderer();
+    let state = GameState::new(vec![guard("u")]);
+    renderer.handle_combat_event(&CombatEvent::UnitDamaged { unit_id: "u".into(), amount: 3, critical: false });
+
+    renderer.render_state(&state);
+    assert_eq!(renderer.draw_log[0].tint, Some(FLASH_WHITE));
+    assert_eq!(renderer.draw_log[0].offset, (0, 0));
+
+    renderer.update(0.2);
+    renderer.render_state(&state);
+    assert_eq!(renderer.draw_log[0].tint, None);
+    assert!(renderer.unit_visuals.is_empty());
+}
+
+#[test]
+fn critical_hits_shake_the_sprite() {
+    let mut renderer = renderer();
+    let state = GameState::new(vec![guard("u")]);
+    renderer.handle_combat_event(&CombatEvent::UnitDamaged { unit_id: "u".into(), amount: 8, critical: true });
+    renderer.render_state(&state);
+    assert_ne!(renderer.draw_log[0].offset, (0, 0));
+
+    renderer.update(0.3);
+    renderer.render_state(&state);
+    assert_eq!(renderer.draw_log[0].offset, (0, 0));
+}
+
+#[test]
+fn status_effects_tint_and_overlay() {
+    let mut renderer = renderer();
+    let mut poisoned = guard("p");
+    poisoned.status_effects.push(StatusEffect { effect_type: EffectType::Poison, remaining_turns: 2, magnitude: 1 });
+    let mut shielded = guard("s");
+    shielded.grid_position.x = 2;
+    shielded.status_effects.push(StatusEffect { effect_type: EffectType::Shield, remaining_turns: 1, magnitude: 0 });
+
+    renderer.render_state(&GameState::new(vec![poisoned, shielded]));
+    let units: Vec<_> = renderer.draw_log.iter().filter(|c| c.layer == DrawLayer::Units).collect();
+    assert_eq!(units[0].tint, Some(POISON_RED));
+    assert_eq!(units[1].tint, None);
+    let shield = renderer.draw_log.iter().find(|c| c.sprite_id == "effect:shield").unwrap();
+    assert_eq!(shield.position, (2, 0));
+    assert_eq!(shield.layer, DrawLayer::Effects);
+}
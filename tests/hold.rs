@@ -0,0 +1,127 @@
+use gero::builders::{AbilityBuilder, UnitBuilder, WeaponBuilder};
+use gero::combat::{CombatEncounter, CombatError, CombatEvent, EnvironmentalEffect};
+use gero::command::Command;
+use gero::damage::DamageType;
+use gero::grid::GridMap;
+use gero::hold::{in_cone, HeldAction, HeldResponse, HoldTrigger};
+use gero::models::{AbilityType, Faction, Position, Unit, UnitType};
+use gero::rng::GameRng;
+
+fn at(x: usize, y: usize) -> Position {
+    Position { x, y }
+}
+
+fn soldier(id: &str, faction: Faction, x: usize, range: u32) -> Unit {
+    let gun = WeaponBuilder::new("gun").damage(3).accuracy(1.0).range(range).build();
+    let mut unit = UnitBuilder::new(id, UnitType::Guardsman, faction).weapon(gun).agility(6).at(x, 0).build();
+    unit.base_stats.max_health = 30;
+    unit.recalculate_stats();
+    unit.health_points = 30;
+    unit
+}
+
+fn watch_the_gap(range: u32) -> CombatEncounter {
+    let players = vec![soldier("guard", Faction::Imperial, 2, 3)];
+    let enemies = vec![soldier("ork", Faction::Ork, 7, range)];
+    let mut encounter = CombatEncounter::new(players, enemies, GridMap::new(8, 1), None);
+    encounter.balance.critical_multiplier = 1;
+    // A hit, but not a critical one.
+    encounter.rng = GameRng::new((0..).find(|&s| (11..=30).contains(&GameRng::new(s).roll_d100())).unwrap());
+    let cone = HeldAction { trigger: HoldTrigger::EntersCone { toward: at(0, 0), radius: 3 }, response: HeldResponse::Attack };
+    encounter.execute(Command::Hold { unit_id: "ork".into(), action: cone }).unwrap();
+    encounter
+}
+
+#[test]
+fn cones_spread_45_degrees_either_side() {
+    let origin = at(4, 4);
+    let east = at(8, 4);
+    assert!(in_cone(&origin, &east, 3, &at(5, 4)));
+    assert!(in_cone(&origin, &east, 3, &at(6, 5)));
+    assert!(!in_cone(&origin, &east, 3, &at(5, 6)));
+    assert!(!in_cone(&origin, &east, 3, &at(8, 4)));
+    assert!(!in_cone(&origin, &east, 3, &at(3, 4)));
+    assert!(!in_cone(&origin, &east, 3, &origin));
+}
+
+#[test]
+fn a_held_shot_hits_the_first_enemy_to_enter_its_cone() {
+    let mut encounter = watch_the_gap(3);
+    assert_eq!(encounter.unit_by_id("ork").unwrap().action_points, 0);
+    encounter.start_turn();
+
+    // The guard is shot on the cell it enters by and walks on.
+    encounter.execute(Command::Move { unit_id: "guard".into(), destination: at(5, 0) }).unwrap();
+    assert!(!encounter.can_undo());
+    assert!(encounter.events.contains(&CombatEvent::HeldActionTriggered { unit_id: "ork".into(), target_id: "guard".into() }));
+    let guard = encounter.unit_by_id("guard").unwrap();
+    assert_eq!((guard.health_points, guard.grid_position.clone()), (27, at(5, 0)));
+    assert_eq!(encounter.unit_by_id("ork").unwrap().held_action, None);
+
+    // Once only.
+    encounter.execute(Command::Move { unit_id: "guard".into(), destination: at(4, 0) }).unwrap();
+    encounter.execute(Command::Move { unit_id: "guard".into(), destination: at(6, 0) }).unwrap();
+    assert_eq!(encounter.unit_by_id("guard").unwrap().health_points, 27);
+}
+
+#[test]
+fn holds_wait_for_a_shot_they_can_take_until_the_next_turn() {
+    let mut encounter = watch_the_gap(1);
+    encounter.turn_order.initiative = ["guard", "ork"].map(String::from).into();
+    encounter.start_turn();
+    encounter.execute(Command::Move { unit_id: "guard".into(), destination: at(4, 0) }).unwrap();
+    assert!(encounter.can_undo());
+    assert!(encounter.unit_by_id("ork").unwrap().held_action.is_some());
+    encounter.execute(Command::EndTurn).unwrap();
+    encounter.start_turn();
+    assert_eq!(encounter.unit_by_id("ork").unwrap().held_action, None);
+
+    let blast = AbilityBuilder::new("blast", AbilityType::PsychicBlast).damage(4, DamageType::Energy).range(5).build();
+    let roar = AbilityBuilder::new("roar", AbilityType::Buff).range(0).build();
+    let mut psyker = soldier("psyker", Faction::Imperial, 0, 3);
+    psyker.abilities.push(blast);
+    let mut ork = soldier("ork", Faction::Ork, 4, 3);
+    ork.abilities.push(roar);
+    let mut encounter = CombatEncounter::new(vec![psyker], vec![ork], GridMap::new(8, 1), None);
+    let answer = HeldAction { trigger: HoldTrigger::UsesAbility, response: HeldResponse::UseAbility { ability_index: 0 } };
+    encounter.hold_action("psyker", answer).unwrap();
+    let use_roar = Command::UseAbility { user_id: "ork".into(), ability_index: 0, target_ids: vec!["ork".into()] };
+    encounter.execute(use_roar).unwrap();
+    assert!(encounter.unit_by_id("ork").unwrap().health_points < 30);
+    assert_eq!(encounter.unit_by_id("psyker").unwrap().held_action, None);
+}
+
+#[test]
+fn delayed_units_act_later_in_the_round_and_keep_their_place() {
+    let units = |id: &str, x| soldier(id, if id == "ork" { Faction::Ork } else { Faction::Imperial }, x, 3);
+    let mut encounter = CombatEncounter::new(vec![units("a", 0), units("b", 1)], vec![units("ork", 7)], GridMap::new(8, 1), None);
+    encounter.turn_order.initiative = ["a", "b", "ork"].map(String::from).into();
+    encounter.environmental_effects.push(EnvironmentalEffect::FirePatch { grid_cells: vec![at(0, 0)], damage_per_turn: 2 });
+
+    let mut order = Vec::new();
+    encounter.start_turn();
+    order.push(encounter.turn_order.current_unit_id.clone().unwrap());
+    let burn = 30 - encounter.unit_by_id("a").unwrap().health_points;
+    assert!(burn > 0);
+    encounter.execute(Command::Delay).unwrap();
+    for _ in 0..6 {
+        encounter.start_turn();
+        order.push(encounter.turn_order.current_unit_id.clone().unwrap());
+        encounter.execute(Command::EndTurn).unwrap();
+    }
+    assert_eq!(order, ["a", "b", "a", "ork", "b", "a", "ork"]);
+    assert_eq!(encounter.turn_order.round_number, 2);
+    // The fire burned once a round, not again for the resumed turn.
+    assert_eq!(encounter.unit_by_id("a").unwrap().health_points, 30 - 2 * burn);
+
+    // Only before acting, and only for someone still to act this round.
+    encounter.start_turn();
+    assert_eq!(encounter.turn_order.current_unit_id.as_deref(), Some("b"));
+    encounter.execute(Command::Move { unit_id: "b".into(), destination: at(2, 0) }).unwrap();
+    assert_eq!(encounter.execute(Command::Delay), Err(CombatError::AlreadyActed));
+    encounter.execute(Command::EndTurn).unwrap();
+    encounter.start_turn();
+    encounter.start_turn();
+    assert_eq!(encounter.turn_order.current_unit_id.as_deref(), Some("ork"));
+    assert_eq!(encounter.execute(Command::Delay), Err(CombatError::NobodyToWaitFor));
+}
@@ -0,0 +1,93 @@
+use gero::combat::{CombatEncounter, CombatError, CombatEvent, Controller};
+use gero::command::Command;
+use gero::flow::GameFlow;
+use gero::frontend::Renderer;
+use gero::grid::GridMap;
+use gero::input::GameAction;
+use gero::localization::Localizer;
+use gero::models::{Faction, Position, Unit, UnitType};
+use gero::state::GameState;
+use gero::ui::{UiEvent, UiManager};
+
+fn hotseat_encounter() -> CombatEncounter {
+    let mut imperial = Unit::new("imp", "Imp", UnitType::Guardsman, Faction::Imperial);
+    imperial.base_stats.agility = 4;
+    imperial.recalculate_stats();
+    let mut ork = Unit::new("ork", "Ork", UnitType::OrkBoy, Faction::Ork);
+    ork.grid_position = Position { x: 19, y: 0 };
+    let mut enc = CombatEncounter::new(vec![imperial], vec![ork], GridMap::new(20, 4), None);
+    enc.set_controller(Faction::Ork, Controller::Player(1));
+    enc
+}
+
+#[test]
+fn factions_default_to_player_and_ai_control() {
+    let player = Unit::new("p", "P", UnitType::Guardsman, Faction::Imperial);
+    let enemy = Unit::new("e", "E", UnitType::OrkBoy, Faction::Ork);
+    let enc = CombatEncounter::new(vec![player], vec![enemy], GridMap::new(4, 4), None);
+    assert_eq!(enc.controller_of("p"), Some(Controller::Player(0)));
+    assert_eq!(enc.controller_of("e"), Some(Controller::Ai));
+}
+
+#[test]
+fn turn_passing_between_humans_emits_handoff() {
+    let mut enc = hotseat_encounter();
+    enc.start_turn();
+    assert_eq!(enc.active_player, Some(0));
+    assert!(enc.drain_events().is_empty());
+    enc.execute_as(0, Command::EndTurn).unwrap();
+    enc.start_turn();
+    assert_eq!(enc.active_player, Some(1));
+    assert_eq!(enc.drain_events(), vec![CombatEvent::TurnHandedOff { from: 0, to: 1 }]);
+}
+
+#[test]
+fn commands_for_other_players_units_are_refused() {
+    let mut enc = hotseat_encounter();
+    enc.start_turn();
+    let ork_move = Command::Move { unit_id: "ork".into(), destination: Position { x: 18, y: 0 } };
+    assert_eq!(enc.execute_as(0, ork_move.clone()), Err(CombatError::NotControlled));
+    assert_eq!(enc.execute_as(1, ork_move), Err(CombatError::WrongPhase));
+    let imp_move = Command::Move { unit_id: "imp".into(), destination: Position { x: 1, y: 0 } };
+    assert!(enc.execute_as(0, imp_move).is_ok());
+    assert_eq!(enc.command_log.len(), 1);
+}
+
+#[test]
+fn human_controlled_enemies_are_not_driven_by_the_ai() {
+    let mut enc = hotseat_encounter();
+    enc.start_turn();
+    enc.end_turn();
+    enc.start_turn();
+    enc.enemy_ai_action(1);
+    assert_eq!(enc.unit_by_id("ork").unwrap().grid_position, Position { x: 19, y: 0 });
+}
+
+#[test]
+fn pass_device_screen_hides_battlefield_until_confirmed() {
+    let mut enc = hotseat_encounter();
+    let loc = Localizer::new("en").unwrap();
+    let mut renderer = Renderer::new_headless(1280, 256);
+    renderer.sprite_textures.insert("tile_normal".into(), vec![vec![0]]);
+    let ui = UiManager::new(1280, 256, vec![], vec![]);
+    let mut flow = GameFlow::new(GameState::new(Vec::new()), ui);
+    flow.state.map = enc.battlefield.clone();
+
+    enc.start_turn();
+    enc.end_turn();
+    enc.start_turn();
+    for event in enc.drain_events() {
+        flow.ui.handle_combat_event(&event);
+    }
+    flow.render(&mut renderer, &loc);
+    assert!(renderer.draw_log.iter().all(|c| c.sprite_id == "panel:pass_device"));
+    assert!(renderer.text_log.iter().any(|t| t.text == "Pass the device to Player 2"));
+
+    assert_eq!(flow.handle_action(GameAction::SelectDown), None);
+    assert_eq!(flow.handle_action(GameAction::Activate), Some(UiEvent::HandoffConfirmed(1)));
+    renderer.set_fog(&enc.battlefield, &enc.visible_cells(1));
+    flow.render(&mut renderer, &loc);
+    assert!(renderer.draw_log.iter().any(|c| c.sprite_id == "tile_normal"));
+    assert!(renderer.is_fogged(&Position { x: 0, y: 0 }));
+    assert!(!renderer.is_fogged(&Position { x: 18, y: 1 }));
+}
@@ -0,0 +1,119 @@
+use gero::audio::AudioSystem;
+use gero::builders::{UnitBuilder, WeaponBuilder};
+use gero::combat::{CombatEncounter, CombatError, CombatEvent};
+use gero::command::Command;
+use gero::frontend::tiles::OverlayKind;
+use gero::frontend::Renderer;
+use gero::grid::{GridMap, TerrainType};
+use gero::indirect::trajectory;
+use gero::models::{Faction, Position, UnitType};
+use gero::planner::{ActionKind, ActionPlanner};
+use gero::rng::GameRng;
+use gero::state::GameState;
+
+fn at(x: usize, y: usize) -> Position {
+    Position { x, y }
+}
+
+/// An ork mortar at (0, 2) with a wall in front of it, two guardsmen
+/// side by side beyond the wall and one right next to the mortar, on an
+/// 8x5 field. The mortar's accuracy is `accuracy`.
+fn battery(accuracy: f32) -> CombatEncounter {
+    let mortar = WeaponBuilder::new("mortar").damage(6).accuracy(accuracy).range(6).indirect(2, 1).build();
+    let ork = UnitBuilder::new("ork", UnitType::OrkBoy, Faction::Ork).weapon(mortar).at(0, 2).build();
+    let guards = [("near", at(1, 2)), ("left", at(5, 2)), ("right", at(6, 2))]
+        .map(|(id, p)| UnitBuilder::new(id, UnitType::Guardsman, Faction::Imperial).max_health(20).at(p.x, p.y).build());
+    let mut map = GridMap::new(8, 5);
+    for y in 0..5 {
+        map.set_terrain(&at(3, y), TerrainType::Blocked);
+    }
+    let mut encounter = CombatEncounter::new(guards.into(), vec![ork], map, None);
+    encounter.rng = GameRng::new((0..).find(|&s| GameRng::new(s).roll_d100() == 1).unwrap());
+    encounter.unit_by_id_mut("ork").unwrap().action_points = 2;
+    encounter
+}
+
+fn hurt(encounter: &CombatEncounter, id: &str) -> bool {
+    encounter.unit_by_id(id).unwrap().health_points < 20
+}
+
+#[test]
+fn shells_fly_over_walls_and_blast_everyone_round_the_impact() {
+    let mut encounter = battery(1.0);
+    assert!(!encounter.battlefield.has_line_of_sight(&at(0, 2), &at(5, 2)));
+    encounter.execute(Command::Bombard { attacker_id: "ork".into(), target: at(5, 2) }).unwrap();
+    assert_eq!((hurt(&encounter, "left"), hurt(&encounter, "right"), hurt(&encounter, "near")), (true, true, false));
+    assert_eq!(encounter.unit_by_id("ork").unwrap().action_points, 1);
+    assert!(encounter.events.iter().any(|e| matches!(e, CombatEvent::ShellImpact { center, .. } if *center == at(5, 2))));
+}
+
+#[test]
+fn indirect_fire_has_a_minimum_range_and_only_targets_cells() {
+    let mut encounter = battery(1.0);
+    assert_eq!(encounter.bombard("ork", &at(1, 2)), Err(CombatError::TargetTooClose));
+    assert_eq!(encounter.bombard("ork", &at(7, 2)), Err(CombatError::OutOfRange));
+    assert_eq!(encounter.bombard("ork", &at(9, 2)), Err(CombatError::InvalidTarget));
+    let shoot = Command::Attack { attacker_id: "ork".into(), target_id: "near".into() };
+    assert_eq!(encounter.execute(shoot), Err(CombatError::FiresAtCells));
+    // Only indirect weapons can bombard.
+    assert_eq!(encounter.bombard("near", &at(5, 2)), Err(CombatError::NoWeapon));
+}
+
+#[test]
+fn missed_shells_scatter_off_target() {
+    let mut encounter = battery(-5.0);
+    let shell = encounter.bombard("ork", &at(5, 2)).unwrap();
+    assert!(shell.scattered);
+    let offset = shell.impact.x.abs_diff(5).max(shell.impact.y.abs_diff(2));
+    assert!((1..=2).contains(&offset));
+}
+
+#[test]
+fn trajectories_climb_with_range_and_preview_raised_off_the_map() {
+    let heights: Vec<u32> = trajectory(&at(0, 2), &at(4, 2)).into_iter().map(|(_, h)| h).collect();
+    assert_eq!(heights, [2, 2, 2, 0]);
+    let long: u32 = trajectory(&at(0, 0), &at(8, 0)).into_iter().map(|(_, h)| h).max().unwrap();
+    assert_eq!(long, 4);
+
+    let map = GridMap::new(8, 5);
+    let mut renderer = Renderer::new_headless(640, 480);
+    renderer.set_bombard_preview(&map, &at(0, 2), &at(4, 2), 1);
+    let raised: Vec<u32> = renderer.overlays.iter().filter(|o| o.kind == OverlayKind::Trajectory).map(|o| o.height).collect();
+    assert_eq!(raised, heights);
+    renderer.render_state(&GameState::from_encounter(&CombatEncounter::new(Vec::new(), Vec::new(), map, None)));
+    let peak = renderer.draw_log.iter().find(|c| c.sprite_id == "overlay:trajectory" && c.position == (2, 2)).unwrap();
+    assert_eq!(peak.offset, (0, -(renderer.tile_size as i32)));
+    renderer.clear_bombard_preview();
+    assert!(renderer.overlays.is_empty());
+}
+
+#[test]
+fn shell_impacts_have_their_own_sound_and_burst() {
+    let event = CombatEvent::ShellImpact { attacker_id: "ork".into(), center: at(5, 2), cells: vec![at(5, 2), at(6, 2)] };
+    let mut audio = AudioSystem::new();
+    audio.handle_combat_event(&event);
+    assert_eq!(audio.played_log, vec!["shell_impact"]);
+
+    let encounter = battery(1.0);
+    let mut renderer = Renderer::new_headless(640, 480);
+    renderer.handle_combat_event(&event);
+    renderer.render_state(&GameState::from_encounter(&encounter));
+    assert_eq!(renderer.draw_log.iter().filter(|c| c.sprite_id == "effect:shell_burst").count(), 2);
+    renderer.update(1.0);
+    assert!(renderer.shell_bursts.is_empty());
+}
+
+#[test]
+fn the_ai_and_planner_shell_targets_out_of_sight() {
+    let mut encounter = battery(1.0);
+    encounter.unit_by_id_mut("near").unwrap().health_points = 0;
+    let planner = ActionPlanner::new(&encounter, "ork").unwrap();
+    let bombards: Vec<_> = planner.actions().into_iter().filter(|a| a.kind == ActionKind::Attack).map(|a| a.command).collect();
+    assert_eq!(bombards.len(), 2);
+    assert!(bombards.contains(&Command::Bombard { attacker_id: "ork".into(), target: at(5, 2) }));
+
+    encounter.turn_order.current_unit_id = Some("ork".into());
+    encounter.ai_take_action(1);
+    assert_eq!(encounter.unit_by_id("ork").unwrap().grid_position, at(0, 2));
+    assert!(encounter.events.iter().any(|e| matches!(e, CombatEvent::ShellImpact { .. })));
+}
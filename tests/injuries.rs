@@ -0,0 +1,152 @@
+use gero::campaign::Campaign;
+use gero::combat::CombatEncounter;
+use gero::content::ContentDatabase;
+use gero::flow::GameFlow;
+use gero::frontend::Renderer;
+use gero::grid::GridMap;
+use gero::injuries::{Injury, InjuryKind, MEDBAY_UPGRADE_COST};
+use gero::input::GameAction;
+use gero::localization::Localizer;
+use gero::models::{Faction, Unit, UnitType};
+use gero::rng::GameRng;
+use gero::simulation::BattleOutcome;
+use gero::state::GameState;
+use gero::ui::medbay::MedbayScreen;
+use gero::ui::UiManager;
+
+fn trooper(id: &str) -> Unit {
+    let mut unit = Unit::new(id, "Trooper", UnitType::Guardsman, Faction::Imperial);
+    unit.base_stats.accuracy = 60;
+    unit.base_stats.agility = 4;
+    unit.recalculate_stats();
+    unit
+}
+
+fn campaign_with(injuries: Vec<Injury>) -> Campaign {
+    let mut campaign = Campaign::new();
+    let mut unit = trooper("t");
+    unit.injuries = injuries;
+    unit.recalculate_stats();
+    campaign.roster.push(unit);
+    campaign
+}
+
+#[test]
+fn injuries_cost_stats_until_healed() {
+    assert_eq!(InjuryKind::from_roll(30), None);
+    assert_eq!(InjuryKind::from_roll(31), Some(InjuryKind::Concussion));
+    assert_eq!(InjuryKind::from_roll(100), Some(InjuryKind::LostEye));
+
+    let mut campaign = campaign_with(vec![Injury::new(InjuryKind::Concussion), Injury::new(InjuryKind::LegWound)]);
+    let unit = &campaign.roster[0];
+    assert_eq!((unit.current_stats.accuracy, unit.current_stats.movement_points()), (50, 1));
+    assert!(unit.is_recovering());
+    assert_eq!(unit.recovery_missions(), 2);
+    assert!(campaign.deploy(&["t"]).is_empty());
+
+    campaign.advance_recovery();
+    assert_eq!(campaign.roster[0].injuries, [Injury { kind: InjuryKind::LegWound, missions_remaining: 1 }]);
+    assert_eq!(campaign.roster[0].current_stats.accuracy, 60);
+    campaign.advance_recovery();
+    assert!(campaign.roster[0].injuries.is_empty());
+    assert_eq!(campaign.deploy(&["t"]).len(), 1);
+}
+
+#[test]
+fn lasting_injuries_stay_after_recovery_and_the_medbay_speeds_it_up() {
+    let mut campaign = campaign_with(vec![Injury::new(InjuryKind::LostEye)]);
+    campaign.medbay_level = 1;
+    campaign.advance_recovery();
+    assert_eq!(campaign.roster[0].recovery_missions(), 1);
+    campaign.advance_recovery();
+    assert!(!campaign.roster[0].is_recovering());
+    assert_eq!(campaign.roster[0].injuries.len(), 1);
+    assert_eq!(campaign.roster[0].current_stats.accuracy, 50);
+    assert_eq!(campaign.deploy(&["t"]).len(), 1);
+}
+
+#[test]
+fn downed_units_roll_on_the_injury_table() {
+    let mut campaign = Campaign::new();
+    campaign.roster = vec![trooper("a"), trooper("b"), trooper("c")];
+    let downed = ["a".to_string(), "c".to_string(), "ghost".to_string()];
+    let mut expected = GameRng::new(7);
+    let rolls = [expected.roll_d100(), expected.roll_d100()];
+
+    let dealt = campaign.roll_injuries(&downed, &mut GameRng::new(7));
+    let expected: Vec<(String, InjuryKind)> = ["a", "c"]
+        .iter()
+        .zip(rolls)
+        .filter_map(|(id, roll)| InjuryKind::from_roll(roll).map(|k| (id.to_string(), k)))
+        .collect();
+    assert_eq!(dealt, expected);
+    assert!(campaign.roster[1].injuries.is_empty());
+    assert_eq!(campaign.injured().count(), dealt.len());
+}
+
+#[test]
+fn medical_supplies_and_medbay_upgrades() {
+    let db = ContentDatabase::load("assets/content/items.json").unwrap();
+    let mut campaign = campaign_with(vec![Injury::new(InjuryKind::ChestWound)]);
+    assert_eq!(campaign.treat_injuries(&db, "t"), Err("no medical supplies"));
+    campaign.inventory = vec!["lasgun".into(), "stimpack".into()];
+    campaign.treat_injuries(&db, "t").unwrap();
+    assert_eq!(campaign.inventory, ["lasgun"]);
+    assert_eq!(campaign.roster[0].recovery_missions(), 2);
+    assert_eq!(campaign.treat_injuries(&db, "nobody"), Err("unknown unit"));
+
+    assert_eq!(campaign.upgrade_medbay(), Err("insufficient requisition"));
+    campaign.currency = 3 * MEDBAY_UPGRADE_COST;
+    campaign.upgrade_medbay().unwrap();
+    assert_eq!(campaign.medbay_upgrade_cost(), Some(2 * MEDBAY_UPGRADE_COST));
+    campaign.upgrade_medbay().unwrap();
+    assert_eq!((campaign.medbay_level, campaign.currency), (2, 0));
+    assert_eq!(campaign.upgrade_medbay(), Err("medbay fully upgraded"));
+}
+
+#[test]
+fn finishing_a_battle_injures_downed_units_and_counts_down_recovery() {
+    let seed = (0..).find(|&s| InjuryKind::from_roll(GameRng::new(s).roll_d100()).is_some()).unwrap();
+    let mut downed = trooper("t");
+    downed.health_points = 0;
+    let enemy = Unit::new("e", "E", UnitType::OrkBoy, Faction::Ork);
+    let mut enc = CombatEncounter::new(vec![downed, trooper("u")], vec![enemy], GridMap::new(4, 4), None);
+    enc.rng = GameRng::new(seed);
+
+    let mut flow = GameFlow::new(GameState::from_encounter(&enc), UiManager::new(1280, 720, Vec::new(), Vec::new()));
+    let mut recovering = trooper("u");
+    recovering.injuries.push(Injury::new(InjuryKind::Concussion));
+    flow.campaign.roster = vec![trooper("t"), recovering];
+    flow.finish_battle(&enc, BattleOutcome::PlayerVictory);
+    assert_eq!(flow.campaign.roster[0].injuries.len(), 1);
+    assert!(flow.campaign.roster[0].is_recovering());
+    assert!(flow.campaign.roster[1].injuries.is_empty());
+}
+
+#[test]
+fn the_medbay_screen_lists_timers_and_treats_the_selected_unit() {
+    let db = ContentDatabase::load("assets/content/items.json").unwrap();
+    let loc = Localizer::new("en").unwrap();
+    let mut campaign = campaign_with(vec![Injury::new(InjuryKind::BrokenArm)]);
+    campaign.inventory = vec!["stimpack".into()];
+    let mut screen = MedbayScreen::new();
+    let mut renderer = Renderer::new_headless(1280, 720);
+    screen.render(&mut renderer, &loc, &campaign);
+    let texts: Vec<&str> = renderer.text_log.iter().map(|t| t.text.as_str()).collect();
+    assert!(texts.contains(&"Trooper - Broken arm (out for 2 missions)"));
+    assert!(texts.contains(&"Upgrade medbay - 100"));
+
+    screen.handle_input(GameAction::Activate, &db, &mut campaign);
+    assert_eq!(campaign.roster[0].recovery_missions(), 1);
+    screen.handle_input(GameAction::Activate, &db, &mut campaign);
+    assert_eq!(screen.last_error, Some("no medical supplies"));
+
+    screen.handle_input(GameAction::SelectDown, &db, &mut campaign);
+    screen.handle_input(GameAction::SelectDown, &db, &mut campaign);
+    assert_eq!(screen.selected_index, 1);
+    screen.handle_input(GameAction::Activate, &db, &mut campaign);
+    assert_eq!(screen.last_error, Some("insufficient requisition"));
+    renderer.text_log.clear();
+    screen.render(&mut renderer, &loc, &campaign);
+    assert!(renderer.text_log.iter().any(|t| t.text == "Not enough requisition."));
+}
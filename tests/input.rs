@@ -1,4 +1,4 @@
-use gero::input::{InputHandler, GameAction};
+use gero::input::{InputHandler, GameAction, Gesture};
 use gero::audio::AudioSystem;
 use winit::event::{Event, WindowEvent, DeviceEvent, ElementState, MouseButton, TouchPhase, Touch};
 use winit::event::DeviceId;
@@ -37,20 +37,24 @@ fn mouse_event_triggers_action() {
 }
 
 #[test]
-fn touch_event_triggers_action() {
+fn touch_taps_become_gestures() {
     let mut handler = InputHandler::new();
-    let touch = Touch {
-        device_id: unsafe { DeviceId::dummy() },
-        phase: TouchPhase::Started,
-        location: PhysicalPosition { x: 0.0, y: 0.0 },
-        force: None,
-        id: 1,
-    };
-    let event = Event::<()>::WindowEvent {
-        window_id: unsafe { WindowId::dummy() },
-        event: WindowEvent::Touch(touch),
-    };
-    assert_eq!(handler.process_event(&event), Some(GameAction::Activate));
+    for phase in [TouchPhase::Started, TouchPhase::Ended] {
+        let touch = Touch {
+            device_id: unsafe { DeviceId::dummy() },
+            phase,
+            location: PhysicalPosition { x: 0.0, y: 0.0 },
+            force: None,
+            id: 1,
+        };
+        let event = Event::<()>::WindowEvent {
+            window_id: unsafe { WindowId::dummy() },
+            event: WindowEvent::Touch(touch),
+        };
+        assert_eq!(handler.process_event(&event), None);
+    }
+    assert!(handler.touch_detected);
+    assert_eq!(handler.take_gestures(), [Gesture::Tap { x: 0.0, y: 0.0 }, Gesture::Released]);
 }
 
 #[test]
@@ -81,3 +85,14 @@ fn menu_actions_trigger_audio() {
     assert_eq!(handler.process_event_with_audio(&event, Some(&mut audio)), Some(GameAction::Activate));
     assert_eq!(audio.played_log, vec!["button_click"]);
 }
+
+#[test]
+fn rebound_keys_trigger_actions() {
+    let mut handler = InputHandler::new();
+    handler.bindings.bind("KeyW", GameAction::SelectUp);
+    let event = Event::<()>::DeviceEvent {
+        device_id: unsafe { DeviceId::dummy() },
+        event: DeviceEvent::Key(RawKeyEvent { physical_key: PhysicalKey::Code(KeyCode::KeyW), state: ElementState::Pressed }),
+    };
+    assert_eq!(handler.process_event(&event), Some(GameAction::SelectUp));
+}
@@ -0,0 +1,174 @@
+use gero::actions::{PresentationStep, ACTION_ANIMATION_SECONDS, WALK_STEP_SECONDS};
+use gero::combat::{CombatEncounter, CombatError};
+use gero::content::ContentDatabase;
+use gero::command::Command;
+use gero::flow::GameFlow;
+use gero::frontend::Renderer;
+use gero::grid::{GridMap, Region, TerrainType};
+use gero::input::GameAction;
+use gero::interrupts::{InterruptEvent, ScriptedInterrupt, Trigger, CAMERA_PAN_SECONDS};
+use gero::models::{Faction, Position, Unit, UnitType};
+use gero::scenario::Scenario;
+use gero::state::GameState;
+use gero::ui::UiManager;
+
+fn pos(x: usize, y: usize) -> Position {
+    Position { x, y }
+}
+
+fn encounter(width: usize) -> CombatEncounter {
+    let mut player = Unit::new("p", "P", UnitType::Guardsman, Faction::Imperial);
+    player.base_stats.agility = 6;
+    player.recalculate_stats();
+    let mut enemy = Unit::new("e", "E", UnitType::OrkBoy, Faction::Ork);
+    enemy.grid_position = pos(5, 0);
+    let mut enc = CombatEncounter::new(vec![player], vec![enemy], GridMap::new(width, 8), None);
+    enc.unit_templates = ContentDatabase::load("assets/content/items.json").unwrap().units;
+    enc
+}
+
+fn on(trigger: Trigger, events: Vec<InterruptEvent>) -> ScriptedInterrupt {
+    ScriptedInterrupt { trigger, events, fired: false }
+}
+
+fn dialogue() -> InterruptEvent {
+    InterruptEvent::Dialogue { speaker: "speaker.commissar".into(), text: "dialogue.night_raid.ambush".into() }
+}
+
+#[test]
+fn triggers_fire_once_when_met() {
+    let mut enc = encounter(8);
+    let region = Region { x: 2, y: 0, width: 2, height: 1 };
+    enc.interrupts = vec![
+        on(Trigger::EntersRegion { region, unit_id: None }, vec![dialogue()]),
+        on(Trigger::HealthBelow { unit_id: "e".into(), hp: 5 }, vec![dialogue()]),
+        on(Trigger::Round { round: 2 }, vec![dialogue()]),
+    ];
+    assert_eq!(enc.check_interrupts(), 0);
+    assert!(!enc.actions.is_busy());
+
+    enc.unit_by_id_mut("p").unwrap().grid_position = pos(3, 0);
+    assert_eq!(enc.check_interrupts(), 1);
+    assert!(enc.actions.is_busy());
+    assert_eq!(enc.check_interrupts(), 0);
+
+    enc.unit_by_id_mut("e").unwrap().health_points = 4;
+    enc.turn_order.round_number = 2;
+    assert_eq!(enc.check_interrupts(), 2);
+    assert!(enc.interrupts.iter().all(|i| i.fired));
+}
+
+#[test]
+fn interrupts_hold_the_turn_until_the_dialogue_is_dismissed() {
+    let mut enc = encounter(8);
+    let events = vec![
+        InterruptEvent::CameraPan { target: pos(7, 7) },
+        InterruptEvent::Reinforcement { unit_id: "ork_raider".into(), position: pos(7, 7), enemy: true },
+        dialogue(),
+    ];
+    let region = Region { x: 2, y: 0, width: 1, height: 1 };
+    enc.interrupts = vec![on(Trigger::EntersRegion { region, unit_id: Some("p".into()) }, events)];
+    enc.queue_action(Command::Move { unit_id: "p".into(), destination: pos(2, 0) });
+    enc.queue_action(Command::Move { unit_id: "p".into(), destination: pos(3, 0) });
+
+    // The walk into the region plays out, then the interrupt takes over.
+    // The reinforcement is already on the field, so a save made while the
+    // interrupt plays keeps it.
+    assert!(matches!(&enc.update_actions(0.0)[..], [PresentationStep::Walk { .. }]));
+    let ork = enc.unit_by_id("ork_raider").unwrap();
+    assert_eq!((ork.faction.clone(), ork.grid_position.clone()), (Faction::Ork, pos(7, 7)));
+    assert!(enc.turn_order.initiative.contains(&"ork_raider".to_string()));
+    let saved: CombatEncounter = serde_json::from_str(&serde_json::to_string(&enc).unwrap()).unwrap();
+    assert!(saved.unit_by_id("ork_raider").is_some());
+    assert!(saved.interrupts[0].fired);
+
+    let started = enc.update_actions(WALK_STEP_SECONDS * 2.0);
+    assert_eq!(started, [PresentationStep::Interrupt(InterruptEvent::CameraPan { target: pos(7, 7) })]);
+    enc.update_actions(CAMERA_PAN_SECONDS);
+
+    assert_eq!(enc.update_actions(ACTION_ANIMATION_SECONDS), [PresentationStep::Interrupt(dialogue())]);
+    assert!(enc.update_actions(100.0).is_empty());
+    assert_eq!(enc.unit_by_id("p").unwrap().grid_position, pos(2, 0));
+
+    // Dismissing the line resumes the turn; the interrupt doesn't fire again.
+    assert!(enc.resume_actions());
+    assert!(!enc.resume_actions());
+    assert!(matches!(&enc.update_actions(0.0)[..], [PresentationStep::Walk { .. }]));
+    assert_eq!(enc.unit_by_id("p").unwrap().grid_position, pos(3, 0));
+    enc.update_actions(WALK_STEP_SECONDS);
+    assert!(!enc.actions.is_busy());
+}
+
+#[test]
+fn the_flow_pans_the_camera_and_waits_on_the_dialogue_overlay() {
+    let mut enc = encounter(16);
+    enc.interrupts = vec![on(Trigger::Round { round: 1 }, vec![InterruptEvent::CameraPan { target: pos(10, 2) }, dialogue()])];
+    let mut flow = GameFlow::new(GameState::from_encounter(&enc), UiManager::new(640, 480, Vec::new(), Vec::new()));
+    let mut renderer = Renderer::new_headless(640, 480);
+    assert_eq!(enc.check_interrupts(), 1);
+
+    flow.present_actions(0.0, &mut enc, &mut renderer);
+    assert_eq!((renderer.camera.x_offset, renderer.camera.y_offset), (0.0, 0.0));
+    flow.present_actions(CAMERA_PAN_SECONDS / 2.0, &mut enc, &mut renderer);
+    assert_eq!(renderer.camera.x_offset, 176.0);
+    flow.present_actions(CAMERA_PAN_SECONDS / 2.0, &mut enc, &mut renderer);
+    // Centred on the cell, without scrolling past the top of the map.
+    assert_eq!((renderer.camera.x_offset, renderer.camera.y_offset), (352.0, 0.0));
+    assert!(flow.dialogue.is_some());
+
+    assert!(flow.present_actions(1.0, &mut enc, &mut renderer).is_empty());
+    assert!(enc.actions.is_busy());
+    flow.handle_action(GameAction::Activate);
+    assert!(flow.dialogue.is_none());
+    flow.present_actions(0.0, &mut enc, &mut renderer);
+    assert!(!enc.actions.is_busy());
+}
+
+#[test]
+fn scenarios_declare_their_interrupts() {
+    let scenario = Scenario::load("assets/scenarios/night_raid.json").unwrap();
+    let mut player = Unit::new("p", "P", UnitType::Guardsman, Faction::Imperial);
+    player.grid_position = pos(7, 2);
+    let mut enc = scenario.encounter(vec![player], vec![], None);
+    assert_eq!(enc.interrupts.len(), 1);
+    assert_eq!(enc.check_interrupts(), 1);
+    assert!(matches!(enc.update_actions(0.0)[..], [PresentationStep::Interrupt(InterruptEvent::CameraPan { .. })]));
+}
+
+#[test]
+fn a_move_that_sets_off_an_interrupt_cannot_be_undone() {
+    let mut enc = encounter(8);
+    let region = Region { x: 2, y: 0, width: 1, height: 1 };
+    let events = vec![InterruptEvent::Reinforcement { unit_id: "ork_raider".into(), position: pos(7, 7), enemy: true }];
+    enc.interrupts = vec![on(Trigger::EntersRegion { region, unit_id: Some("p".into()) }, events)];
+
+    enc.execute(Command::Move { unit_id: "p".into(), destination: pos(1, 0) }).unwrap();
+    assert!(enc.can_undo());
+    enc.execute(Command::Move { unit_id: "p".into(), destination: pos(2, 0) }).unwrap();
+    assert!(!enc.can_undo());
+    assert!(!enc.undo());
+    assert_eq!(enc.unit_by_id("p").unwrap().grid_position, pos(2, 0));
+    assert!(enc.unit_by_id("ork_raider").is_some());
+    assert_eq!(enc.command_log.len(), 2);
+}
+
+#[test]
+fn reinforcements_arrive_beside_a_unit_in_their_way() {
+    let mut enc = encounter(8);
+    enc.battlefield.set_terrain(&pos(4, 0), TerrainType::Blocked);
+    assert_eq!(enc.spawn_reinforcement("ork_raider", &pos(5, 0), true), Ok("ork_raider".to_string()));
+    // Not into the wall beside it either.
+    assert_eq!(enc.unit_by_id("ork_raider").unwrap().grid_position, pos(6, 0));
+    assert_eq!(enc.unit_by_id("e").unwrap().grid_position, pos(5, 0));
+}
+
+#[test]
+fn each_reinforcement_gets_its_own_unit() {
+    let mut enc = encounter(8);
+    assert_eq!(enc.spawn_reinforcement("ork_raider", &pos(7, 7), true), Ok("ork_raider".to_string()));
+    assert_eq!(enc.spawn_reinforcement("ork_raider", &pos(7, 7), true), Ok("ork_raider_2".to_string()));
+    let second = enc.unit_by_id("ork_raider_2").unwrap();
+    assert_eq!((second.name.as_str(), second.unit_type.clone(), second.faction.clone()), ("Ork Raider", UnitType::OrkBoy, Faction::Ork));
+    assert!(second.equipment.weapon.is_some());
+    assert_eq!(enc.spawn_reinforcement("titan", &pos(7, 7), true), Err(CombatError::UnknownTemplate));
+}
@@ -0,0 +1,98 @@
+#![cfg(feature = "testkit")]
+
+use std::collections::HashMap;
+
+use gero::balance::BalanceConfig;
+use gero::combat::{resolve_attack_with, AttackModifiers, CombatEncounter};
+use gero::grid::{find_path, reachable_cells, TerrainType};
+use gero::models::{Faction, MovementType, Position};
+use gero::saves::SaveFormat;
+use gero::simulation::run_round;
+use gero::state::GameState;
+use gero::testkit::{encounters, maps, open_cells, units, weapons};
+use gero::weather::Weather;
+use proptest::prelude::*;
+
+fn check_limits(encounter: &CombatEncounter) -> Result<(), TestCaseError> {
+    for unit in encounter.units.iter() {
+        prop_assert!(unit.health_points <= unit.current_stats.max_health, "{} overhealed", unit.id);
+        prop_assert!(unit.action_points <= unit.current_stats.max_action, "{} has spare AP", unit.id);
+        prop_assert!(encounter.battlefield.in_bounds(&unit.grid_position), "{} left the map", unit.id);
+        let terrain = encounter.battlefield.terrain_at(&unit.grid_position);
+        prop_assert!(!matches!(terrain, TerrainType::Blocked), "{} stands on a blocked tile", unit.id);
+    }
+    Ok(())
+}
+
+proptest! {
+    #[test]
+    fn attacks_never_heal_or_refund_action_points(
+        mut attacker in units(Faction::Imperial),
+        weapon in weapons(),
+        mut defender in units(Faction::Ork),
+        roll in 1..=100u8,
+        cover in 0..40i32,
+    ) {
+        let (ap, hp) = (attacker.action_points, defender.health_points);
+        let result = resolve_attack_with(&mut attacker, &weapon, &mut defender, roll, cover, AttackModifiers::default(), &BalanceConfig::DEFAULT);
+        prop_assert!(attacker.action_points <= ap);
+        prop_assert!(defender.health_points <= hp);
+        prop_assert!(defender.health_points <= defender.current_stats.max_health);
+        match result {
+            Ok(result) if result.hit => {}
+            Ok(_) => prop_assert_eq!(defender.health_points, hp),
+            Err(_) => prop_assert_eq!((attacker.action_points, defender.health_points), (ap, hp)),
+        }
+    }
+
+    #[test]
+    fn ai_rounds_keep_units_within_their_limits(mut encounter in encounters()) {
+        let mut rng = encounter.rng.clone();
+        let mut damage = HashMap::new();
+        check_limits(&encounter)?;
+        for _ in 0..4 {
+            run_round(&mut encounter, &mut rng, &mut damage);
+            check_limits(&encounter)?;
+        }
+    }
+
+    #[test]
+    fn paths_never_end_on_or_walk_through_blocked_tiles(
+        map in maps(),
+        mut unit in units(Faction::Imperial),
+        start in any::<prop::sample::Index>(),
+        dest in any::<prop::sample::Index>(),
+    ) {
+        let open = open_cells(&map);
+        unit.grid_position = start.get(&open).clone();
+        let cells = map.width * map.height;
+        let i = dest.index(cells);
+        let dest = Position { x: i % map.width, y: i / map.width };
+        let search = find_path(&unit, &dest, &map, 0, Weather::Clear);
+        if search.cost.is_some() {
+            prop_assert_eq!(search.path.first(), Some(&unit.grid_position));
+            prop_assert_eq!(search.path.last(), Some(&dest));
+            prop_assert!(!matches!(map.terrain_at(&dest), TerrainType::Blocked));
+            if unit.movement_type == MovementType::Ground {
+                prop_assert!(search.path.iter().all(|p| !matches!(map.terrain_at(p), TerrainType::Blocked)));
+            }
+        }
+        for cell in reachable_cells(&unit, &map, 0, Weather::Clear) {
+            prop_assert!(!matches!(map.terrain_at(&cell), TerrainType::Blocked));
+        }
+    }
+
+    #[test]
+    fn saves_round_trip_mid_battle(mut encounter in encounters()) {
+        let mut rng = encounter.rng.clone();
+        run_round(&mut encounter, &mut rng, &mut HashMap::new());
+        let state = GameState::from_encounter(&encounter);
+        for format in [SaveFormat::Json, SaveFormat::Binary] {
+            let loaded = GameState::load_from_bytes(&state.save_to_bytes(format).unwrap()).unwrap();
+            prop_assert_eq!(loaded.checksum(), state.checksum());
+        }
+        let json = serde_json::to_string(&encounter).unwrap();
+        let loaded: CombatEncounter = serde_json::from_str(&json).unwrap();
+        prop_assert_eq!(GameState::from_encounter(&loaded).checksum(), state.checksum());
+    }
+}
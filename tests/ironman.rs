@@ -0,0 +1,143 @@
+use std::path::PathBuf;
+
+use gero::archive::CampaignArchive;
+use gero::campaign::Campaign;
+use gero::combat::CombatEncounter;
+use gero::command::Command;
+use gero::doctrine::Doctrine;
+use gero::flow::GameFlow;
+use gero::frontend::Renderer;
+use gero::grid::{GridMap, RegionShape, TriggerRegion};
+use gero::input::KeyBindings;
+use gero::interrupts::{ScriptedInterrupt, Trigger};
+use gero::ironman::{IronmanSave, IronmanSession, SaveHistory};
+use gero::models::{Faction, Position, Unit, UnitType};
+use gero::rng::GameRng;
+use gero::saves::{self, SaveFormat};
+use gero::state::GameState;
+use gero::ui::options::AccessibilitySettings;
+use gero::ui::UiManager;
+
+fn save_path(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join("gero_ironman_test");
+    std::fs::create_dir_all(&dir).unwrap();
+    dir.join(format!("{}.sav", name))
+}
+
+fn encounter() -> CombatEncounter {
+    let mut guard = Unit::new("g", "G", UnitType::Guardsman, Faction::Imperial);
+    guard.grid_position = Position { x: 0, y: 0 };
+    let mut ork = Unit::new("o", "O", UnitType::OrkBoy, Faction::Ork);
+    ork.grid_position = Position { x: 3, y: 3 };
+    CombatEncounter::new(vec![guard], vec![ork], GridMap::new(4, 4), None)
+}
+
+#[test]
+fn every_autosave_chains_on_the_one_before() {
+    let path = save_path("chain");
+    let mut campaign = Campaign::new();
+    let mut session = IronmanSession::start(&path, &mut campaign).unwrap();
+    assert!(campaign.ironman);
+    campaign.currency = 50;
+    session.autosave(&campaign, Some(&encounter())).unwrap();
+    assert_eq!(session.history.digests.len(), 2);
+
+    let (resumed, save) = IronmanSession::resume(&path).unwrap();
+    assert_eq!(resumed.history, session.history);
+    assert_eq!(save.sequence, 1);
+    assert_eq!(save.previous, session.history.digests[0]);
+    assert_eq!(save.campaign.currency, 50);
+    assert!(save.campaign.ironman);
+    assert!(save.battle.is_some());
+}
+
+#[test]
+fn untouched_mid_battle_saves_resume_on_maps_with_cell_sets() {
+    let path = save_path("cell_sets");
+    let mut campaign = Campaign::new();
+    let mut session = IronmanSession::start(&path, &mut campaign).unwrap();
+    let mut battle = encounter();
+    let cells: Vec<Position> = (0..4).flat_map(|x| (1..3).map(move |y| Position { x, y })).collect();
+    for cell in &cells {
+        battle.battlefield.flammable.insert(cell.clone());
+    }
+    for x in 0..4 {
+        battle.battlefield.place_fragile(&Position { x, y: 3 });
+    }
+    let shape = RegionShape::Cells(cells.into_iter().collect());
+    battle.battlefield.regions.push(TriggerRegion { name: "field".into(), shape });
+    session.autosave(&campaign, Some(&battle)).unwrap();
+
+    let (_, save) = IronmanSession::resume(&path).unwrap();
+    assert!(save.is_intact());
+    assert_eq!(save.battle().unwrap().unwrap().battlefield.fragile.len(), 4);
+}
+
+#[test]
+fn edited_and_earlier_saves_are_refused() {
+    let path = save_path("tamper");
+    let mut campaign = Campaign::new();
+    let mut session = IronmanSession::start(&path, &mut campaign).unwrap();
+    let first = std::fs::read(&path).unwrap();
+    campaign.currency = 10;
+    session.autosave(&campaign, None).unwrap();
+
+    let mut save: IronmanSave = saves::load(&path).unwrap();
+    assert!(save.is_intact());
+    save.campaign.currency = 9999;
+    assert!(!save.is_intact());
+    assert_eq!(session.history.verify(&save), Err("save has been tampered with"));
+    saves::save(&save, &path, SaveFormat::Binary).unwrap();
+    assert!(IronmanSession::resume(&path).unwrap_err().to_string().contains("tampered"));
+
+    // Restoring the first save's file is caught by the history.
+    std::fs::write(&path, &first).unwrap();
+    let err = IronmanSession::resume(&path).unwrap_err();
+    assert_eq!(err.to_string(), "iron-man campaigns can't load earlier saves");
+    let save: IronmanSave = saves::load(&path).unwrap();
+    assert_eq!(SaveHistory::default().verify(&save), Err("save is missing from its history"));
+}
+
+#[test]
+fn iron_man_archives_refuse_manual_loads() {
+    let mut archive = CampaignArchive::new(Campaign::new(), AccessibilitySettings::default(), KeyBindings::default());
+    archive.add_save("before the push", GameState::from_encounter(&encounter()));
+    assert!(archive.load_save("before the push").is_ok());
+    assert_eq!(archive.load_save("later").err(), Some("unknown save"));
+    archive.campaign.ironman = true;
+    assert_eq!(archive.load_save("before the push").err(), Some("iron-man campaigns can't load saves"));
+}
+
+#[test]
+fn iron_man_battles_are_saved_by_the_flow_and_resumed_whole() {
+    let path = save_path("flow");
+    let new_flow = || GameFlow::new(GameState::from_encounter(&encounter()), UiManager::new(640, 480, Vec::new(), Vec::new()));
+    let mut campaign = Campaign::new();
+    campaign.ironman = true;
+    let mut flow = new_flow();
+    flow.begin_campaign(campaign, &path).unwrap();
+    assert!(IronmanSession::resume(&path).unwrap().1.battle.is_none());
+
+    let mut battle = encounter();
+    let guard = battle.unit_by_id_mut("g").unwrap();
+    guard.base_stats.agility = 6;
+    guard.recalculate_stats();
+    battle.rng = GameRng::new(7);
+    battle.apply_doctrine(Doctrine::Waaagh);
+    battle.interrupts = vec![ScriptedInterrupt { trigger: Trigger::Round { round: 9 }, events: Vec::new(), fired: false }];
+    flow.start_battle(battle);
+    flow.order(Command::Move { unit_id: "g".into(), destination: Position { x: 1, y: 0 } });
+    flow.present_battle(0.0, &mut Renderer::new_headless(640, 480));
+
+    let mut resumed = new_flow();
+    resumed.resume_ironman(&path).unwrap();
+    assert!(resumed.campaign.ironman);
+    let (saved, live) = (resumed.battle.as_mut().unwrap(), flow.battle.as_mut().unwrap());
+    assert_eq!(saved.command_log, live.command_log);
+    assert_eq!(saved.unit_by_id("g").unwrap().grid_position, Position { x: 1, y: 0 });
+    assert_eq!(saved.interrupts, live.interrupts);
+    assert_eq!(saved.faction_modifiers.len(), live.faction_modifiers.len());
+    assert_eq!(saved.rng.roll_d100(), live.rng.roll_d100());
+    // Resuming doesn't write another save.
+    assert_eq!(resumed.ironman.unwrap().history, flow.ironman.unwrap().history);
+}
@@ -0,0 +1,80 @@
+use gero::builders::{UnitBuilder, WeaponBuilder};
+use gero::combat::CombatEncounter;
+use gero::flow::GameFlow;
+use gero::grid::GridMap;
+use gero::models::{Faction, Position, Unit, UnitType};
+use gero::profile::Difficulty;
+use gero::registry::Side;
+use gero::state::GameState;
+use gero::ui::UiManager;
+use gero::weather::Weather;
+
+fn guard(id: &str, x: usize) -> Unit {
+    UnitBuilder::new(id, UnitType::Guardsman, Faction::Imperial).max_health(20).at(x, 0).build()
+}
+
+/// An ork with a gun reaching across the map, whose turn it is, against a
+/// guard at `x`.
+fn encounter(x: usize, difficulty: Difficulty) -> CombatEncounter {
+    let gun = WeaponBuilder::new("gun").damage(3).range(20).accuracy(1.0).critical_chance(0.0).build();
+    let ork = UnitBuilder::new("ork", UnitType::OrkBoy, Faction::Ork).weapon(gun).at(0, 0).build();
+    let mut encounter = CombatEncounter::new(vec![guard("guard", x)], vec![ork], GridMap::new(16, 1), None);
+    encounter.balance.critical_multiplier = 1;
+    encounter.difficulty = difficulty;
+    encounter.turn_order.current_unit_id = Some("ork".into());
+    encounter
+}
+
+fn guard_hp_after_ork_acts(encounter: &mut CombatEncounter) -> i32 {
+    encounter.ai_take_action(50);
+    encounter.unit_by_id("guard").unwrap().health_points
+}
+
+#[test]
+fn the_ai_sees_everything_below_hard() {
+    for difficulty in [Difficulty::Easy, Difficulty::Normal] {
+        let mut encounter = encounter(12, difficulty);
+        assert_eq!(guard_hp_after_ork_acts(&mut encounter), 17);
+    }
+}
+
+#[test]
+fn on_hard_the_ai_only_knows_what_it_sees() {
+    let mut hidden = encounter(12, Difficulty::Hard);
+    let ork = hidden.unit_by_id_mut("ork").unwrap();
+    ork.base_stats.agility = 4;
+    ork.recalculate_stats();
+    assert_eq!(guard_hp_after_ork_acts(&mut hidden), 20);
+    // Seeing nobody, it goes looking rather than stand about.
+    assert!(hidden.unit_by_id("ork").unwrap().grid_position.x > 0);
+    let mut seen = encounter(5, Difficulty::Hard);
+    assert_eq!(guard_hp_after_ork_acts(&mut seen), 17);
+
+    let mut cheating = encounter(12, Difficulty::Hard);
+    cheating.balance.ai_perfect_information.hard = true;
+    assert_eq!(guard_hp_after_ork_acts(&mut cheating), 17);
+}
+
+#[test]
+fn knowledge_follows_sight_range() {
+    let mut encounter = encounter(5, Difficulty::Hard);
+    let view = encounter.knowledge(Side::Enemy);
+    assert!(view.sees(&Position { x: 5, y: 0 }));
+    assert_eq!(view.opponents().count(), 1);
+    assert!(view.knows(encounter.unit_by_id("ork").unwrap()));
+
+    encounter.weather = Weather::Night;
+    let view = encounter.knowledge(Side::Enemy);
+    assert!(!view.sees(&Position { x: 5, y: 0 }));
+    assert_eq!(view.opponents().count(), 0);
+    // Each side knows where its own units are.
+    assert!(encounter.knowledge(Side::Player).knows(encounter.unit_by_id("guard").unwrap()));
+}
+
+#[test]
+fn battles_are_fought_at_the_players_difficulty() {
+    let mut flow = GameFlow::new(GameState::new(Vec::new()), UiManager::new(1280, 720, Vec::new(), Vec::new()));
+    flow.difficulty = Difficulty::Hard;
+    flow.start_battle(encounter(12, Difficulty::Normal));
+    assert_eq!(flow.battle.unwrap().difficulty, Difficulty::Hard);
+}
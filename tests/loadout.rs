@@ -0,0 +1,95 @@
+use gero::campaign::Campaign;
+use gero::content::ContentDatabase;
+use gero::frontend::Renderer;
+use gero::input::GameAction;
+use gero::loadout::{armor_allowed, EquipmentSlot};
+use gero::localization::Localizer;
+use gero::models::{ArmorTier, Faction, Unit, UnitType};
+use gero::ui::loadout::{LoadoutMode, LoadoutRow, LoadoutScreen};
+
+fn campaign() -> Campaign {
+    let mut campaign = Campaign::new();
+    campaign.roster.push(Unit::new("trooper", "Trooper", UnitType::Guardsman, Faction::Imperial));
+    campaign.roster.push(Unit::new("brother", "Brother", UnitType::SpaceMarine, Faction::Imperial));
+    for id in ["lasgun", "power_armor", "frag_grenade"] {
+        campaign.grant_item(id);
+    }
+    campaign
+}
+
+#[test]
+fn armor_tiers_are_restricted_by_unit_type() {
+    assert!(armor_allowed(&UnitType::SpaceMarine, &ArmorTier::PowerArmor));
+    assert!(!armor_allowed(&UnitType::Guardsman, &ArmorTier::PowerArmor));
+    assert!(armor_allowed(&UnitType::Guardsman, &ArmorTier::Carapace));
+    assert!(!armor_allowed(&UnitType::Daemon, &ArmorTier::Flak));
+
+    let db = ContentDatabase::load("assets/content/items.json").unwrap();
+    let mut campaign = campaign();
+    assert_eq!(
+        campaign.equip_from_inventory(&db, "trooper", "power_armor"),
+        Err("armor tier not allowed for unit type")
+    );
+    campaign.equip_from_inventory(&db, "brother", "power_armor").unwrap();
+    assert!(!campaign.inventory.contains(&"power_armor".to_string()));
+}
+
+#[test]
+fn unequipped_items_return_to_inventory() {
+    let db = ContentDatabase::load("assets/content/items.json").unwrap();
+    let mut campaign = campaign();
+    campaign.equip_from_inventory(&db, "trooper", "frag_grenade").unwrap();
+    campaign.unequip_to_inventory(&db, "trooper", EquipmentSlot::Accessory(0)).unwrap();
+    assert!(campaign.roster[0].equipment.accessory_slots.is_empty());
+    assert_eq!(campaign.inventory.last().map(String::as_str), Some("frag_grenade"));
+    assert_eq!(campaign.unequip_to_inventory(&db, "trooper", EquipmentSlot::Weapon), Err("slot is empty"));
+}
+
+#[test]
+fn deployed_units_carry_their_loadout() {
+    let db = ContentDatabase::load("assets/content/items.json").unwrap();
+    let mut campaign = campaign();
+    campaign.equip_from_inventory(&db, "brother", "power_armor").unwrap();
+    campaign.roster[1].health_points = 1;
+    let deployed = campaign.deploy(&["brother", "missing"]);
+    assert_eq!(deployed.len(), 1);
+    assert_eq!(deployed[0].equipment.armor.as_ref().unwrap().id, "power_armor");
+    assert_eq!(deployed[0].current_stats.toughness, 4);
+    assert_eq!(deployed[0].health_points, deployed[0].current_stats.max_health);
+}
+
+#[test]
+fn loadout_screen_assigns_and_removes_items() {
+    let db = ContentDatabase::load("assets/content/items.json").unwrap();
+    let loc = Localizer::new("en").unwrap();
+    let mut renderer = Renderer::new_headless(640, 480);
+    let mut campaign = campaign();
+    let mut screen = LoadoutScreen::new();
+
+    screen.handle_input(GameAction::Activate, &db, &mut campaign);
+    assert_eq!(screen.mode, LoadoutMode::EditUnit);
+    assert_eq!(screen.rows(&campaign)[0], LoadoutRow::Item("lasgun".into()));
+    screen.handle_input(GameAction::Activate, &db, &mut campaign);
+    assert_eq!(campaign.roster[0].equipment.weapon.as_ref().unwrap().id, "lasgun");
+    assert_eq!(screen.rows(&campaign)[0], LoadoutRow::Slot(EquipmentSlot::Weapon));
+
+    screen.handle_input(GameAction::SelectDown, &db, &mut campaign);
+    screen.handle_input(GameAction::Activate, &db, &mut campaign);
+    assert_eq!(screen.last_error, Some("armor tier not allowed for unit type"));
+    screen.render(&mut renderer, &loc, &db, &campaign);
+    assert!(renderer.text_log.iter().any(|t| t.text == "Weapon: Lasgun (100%)"));
+    assert!(renderer.text_log.iter().any(|t| t.text == "Unequip Lasgun"));
+    assert!(renderer.text_log.iter().any(|t| t.text == "This unit cannot wear that armour."));
+
+    screen.handle_input(GameAction::SelectUp, &db, &mut campaign);
+    screen.handle_input(GameAction::Activate, &db, &mut campaign);
+    assert!(campaign.roster[0].equipment.weapon.is_none());
+    assert!(campaign.inventory.contains(&"lasgun".to_string()));
+
+    screen.cursor = screen.rows(&campaign).len() - 1;
+    screen.handle_input(GameAction::Activate, &db, &mut campaign);
+    assert_eq!(screen.mode, LoadoutMode::SelectUnit);
+    screen.unit_index = 1;
+    screen.assign(&db, &mut campaign, "power_armor").unwrap();
+    assert!(campaign.roster[1].equipment.armor.is_some());
+}
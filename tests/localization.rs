@@ -15,6 +15,67 @@ fn ui_render_uses_localized_strings() {
     let mut renderer = Renderer::new_headless(50, 50);
     let loc = Localizer::new("en").unwrap();
     ui.render(&mut renderer, &loc);
-    assert!(renderer.draw_log.iter().any(|c| c.sprite_id == "float:damage:3"));
+    assert!(renderer.text_log.iter().any(|t| t.text == "-3"));
+    assert!(renderer.text_log.iter().any(|t| t.text == "Abilities"));
     assert_eq!(UiTab::Abilities.label(&loc), "Abilities");
 }
+
+const GERMAN: &str = r#"{
+    "ui.tab.abilities": "Fähigkeiten",
+    "squad.size": { "one": "{count} Soldat in {squad}", "other": "{count} Soldaten in {squad}" }
+}"#;
+
+#[test]
+fn lookups_fall_back_through_the_chain() {
+    let loc = Localizer::from_json("de", GERMAN).unwrap();
+    assert_eq!(loc.language(), "de");
+    assert_eq!(loc.get("ui.tab.abilities"), "Fähigkeiten");
+    assert_eq!(loc.get("ui.tab.inventory"), "Inventory");
+    assert_eq!(loc.get("no.such.key"), "no.such.key");
+}
+
+#[test]
+fn messages_interpolate_and_pluralize() {
+    let loc = Localizer::from_json("de", GERMAN).unwrap();
+    assert_eq!(loc.format("hotseat.pass_device", &[("player", "2")]), "Pass the device to Player 2");
+    assert_eq!(loc.plural("squad.size", 1, &[("squad", "Alpha")]), "1 Soldat in Alpha");
+    assert_eq!(loc.plural("squad.size", 5, &[("squad", "Alpha")]), "5 Soldaten in Alpha");
+    assert_eq!(loc.get("squad.size"), "{count} Soldaten in {squad}");
+}
+
+#[test]
+fn missing_keys_lists_untranslated_strings() {
+    let loc = Localizer::from_json("de", GERMAN).unwrap();
+    let missing = loc.missing_keys();
+    assert!(missing.contains(&"ui.tab.inventory".to_string()));
+    assert!(!missing.contains(&"ui.tab.abilities".to_string()));
+    assert!(missing.windows(2).all(|w| w[0] < w[1]));
+    assert!(Localizer::new("en").unwrap().missing_keys().is_empty());
+    assert!(Localizer::new("xx").is_err());
+}
+
+#[test]
+fn language_switches_take_effect_on_the_next_render() {
+    use gero::localization::available_languages;
+    use gero::ui::options::OptionsMenu;
+
+    assert!(available_languages().contains(&"de".to_string()));
+    let loc = Localizer::new("en").unwrap().shared();
+    let mut ui = UiManager::new(1280, 720, vec![], vec![]);
+    let mut renderer = Renderer::new_headless(1280, 720);
+    ui.render(&mut renderer, &loc.read().unwrap());
+    let english = renderer.text_log.iter().find(|t| t.text == "Abilities").unwrap().position;
+
+    let mut options = OptionsMenu::new();
+    options.switch_language(&loc, "de").unwrap();
+    assert_eq!(options.language, "de");
+    assert_eq!(loc.read().unwrap().revision(), 1);
+    renderer.text_log.clear();
+    ui.render(&mut renderer, &loc.read().unwrap());
+    let german = renderer.text_log.iter().find(|t| t.text == "Fähigkeiten").unwrap().position;
+    assert!(german.0 < english.0);
+
+    assert!(options.switch_language(&loc, "xx").is_err());
+    assert_eq!(options.language, "de");
+    assert_eq!(loc.read().unwrap().get("ui.tab.inventory"), "Inventar");
+}
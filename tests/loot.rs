@@ -0,0 +1,129 @@
+use gero::campaign::Campaign;
+use gero::combat::{CombatEncounter, CombatError, CombatEvent};
+use gero::command::Command;
+use gero::damage::DamageType;
+use gero::flow::GameFlow;
+use gero::frontend::Renderer;
+use gero::grid::{GridMap, TerrainType};
+use gero::localization::Localizer;
+use gero::loot::{Loot, OPEN_CRATE_AP_COST, PICKUP_AP_COST};
+use gero::models::{Accessory, Faction, Position, Unit, UnitType, Weapon, WeaponTier};
+use gero::scenario::Scenario;
+use gero::simulation::BattleOutcome;
+use gero::state::GameState;
+use gero::ui::UiManager;
+
+fn pos(x: usize, y: usize) -> Position {
+    Position { x, y }
+}
+
+fn choppa() -> Weapon {
+    Weapon {
+        id: "choppa".into(),
+        name: "Choppa".into(),
+        tier: WeaponTier::Basic,
+        damage: 4,
+        accuracy: 0.7,
+        range: 1,
+        armor_piercing: None,
+        action_point_cost: 1,
+        critical_chance: 0.1,
+        abilities_granted: Vec::new(),
+        damage_type: DamageType::Kinetic,
+        forced_move: None,
+        indirect: None,
+    }
+}
+
+/// A guardsman with a grenade facing a wounded ork, and a crate by the
+/// guardsman.
+fn encounter() -> CombatEncounter {
+    let mut player = Unit::new("p", "P", UnitType::Guardsman, Faction::Imperial);
+    player.base_stats.accuracy = 100;
+    player.base_stats.agility = 6;
+    player.recalculate_stats();
+    player.equipment.accessory_slots.push(Accessory::Grenade { damage: 4, aoe_radius: 0, leaves: None });
+    let mut ork = Unit::new("ork", "Ork", UnitType::OrkBoy, Faction::Ork);
+    ork.grid_position = pos(3, 0);
+    ork.health_points = 3;
+    ork.equipment.weapon = Some(choppa());
+    let mut map = GridMap::new(8, 4);
+    map.place_crate(&pos(1, 1), vec![Loot::Item("frag_grenade".into()), Loot::Requisition(25)]);
+    CombatEncounter::new(vec![player], vec![ork], map, None)
+}
+
+#[test]
+fn fallen_enemies_drop_their_weapons_for_pickup() {
+    let mut enc = encounter();
+    enc.execute(Command::ThrowGrenade { thrower_id: "p".into(), accessory_index: 0, target: pos(3, 0) }).unwrap();
+    assert!(enc.unit_by_id("ork").unwrap().equipment.weapon.is_none());
+    assert_eq!(enc.battlefield.loot_at(&pos(3, 0)).collect::<Vec<_>>(), [&Loot::Item("choppa".into())]);
+
+    // Nothing under the unit yet; it has to walk over first.
+    assert_eq!(enc.execute(Command::PickUp { unit_id: "p".into() }), Err(CombatError::NothingToPickUp));
+    enc.unit_by_id_mut("p").unwrap().action_points = 2;
+    enc.execute(Command::Move { unit_id: "p".into(), destination: pos(3, 0) }).unwrap();
+    enc.execute(Command::PickUp { unit_id: "p".into() }).unwrap();
+    // One AP for the walk over.
+    assert_eq!(enc.unit_by_id("p").unwrap().action_points, 1 - PICKUP_AP_COST);
+    assert!(enc.battlefield.loot.is_empty());
+    assert_eq!(enc.recovered, [Loot::Item("choppa".into())]);
+    let event = CombatEvent::LootPickedUp { unit_id: "p".into(), loot: vec![Loot::Item("choppa".into())] };
+    assert!(enc.events.contains(&event));
+}
+
+#[test]
+fn crates_spill_when_opened_or_blown_apart() {
+    let mut enc = encounter();
+    assert_eq!(enc.execute(Command::OpenCrate { unit_id: "p".into(), target: pos(2, 2) }), Err(CombatError::InvalidTarget));
+    enc.unit_by_id_mut("p").unwrap().grid_position = pos(4, 3);
+    assert_eq!(enc.execute(Command::OpenCrate { unit_id: "p".into(), target: pos(1, 1) }), Err(CombatError::OutOfRange));
+
+    enc.unit_by_id_mut("p").unwrap().grid_position = pos(0, 0);
+    enc.execute(Command::OpenCrate { unit_id: "p".into(), target: pos(1, 1) }).unwrap();
+    assert_eq!(enc.unit_by_id("p").unwrap().action_points, 2 - OPEN_CRATE_AP_COST);
+    assert_eq!(*enc.battlefield.terrain_at(&pos(1, 1)), TerrainType::Normal);
+    assert!(enc.battlefield.crates.is_empty());
+    assert_eq!(enc.battlefield.loot_at(&pos(1, 1)).count(), 2);
+
+    let mut enc = encounter();
+    enc.execute(Command::ThrowGrenade { thrower_id: "p".into(), accessory_index: 0, target: pos(1, 1) }).unwrap();
+    assert_eq!(enc.battlefield.loot_at(&pos(1, 1)).count(), 2);
+}
+
+#[test]
+fn recovered_loot_goes_to_the_campaign_unless_the_battle_is_lost() {
+    let mut campaign = Campaign::new();
+    campaign.collect_loot(&[Loot::Item("bolter".into()), Loot::Requisition(30), Loot::Requisition(5)]);
+    assert_eq!((campaign.inventory.clone(), campaign.currency), (vec!["bolter".to_string()], 35));
+
+    let mut enc = encounter();
+    enc.recovered = vec![Loot::Requisition(10)];
+    let mut flow = GameFlow::new(GameState::from_encounter(&enc), UiManager::new(1280, 720, Vec::new(), Vec::new()));
+    flow.finish_battle(&enc, BattleOutcome::EnemyVictory);
+    assert_eq!(flow.campaign.currency, 0);
+    enc.stats = Default::default();
+    flow.finish_battle(&enc, BattleOutcome::PlayerVictory);
+    assert_eq!(flow.campaign.currency, 10);
+}
+
+#[test]
+fn loot_is_drawn_and_listed_under_the_selected_unit() {
+    let mut enc = encounter();
+    enc.battlefield.drop_loot(&pos(0, 0), [Loot::Requisition(5), Loot::Item("lasgun".into())]);
+    let mut state = GameState::from_encounter(&enc);
+    state.set_selection(vec!["p".into()]);
+    let mut renderer = Renderer::new_headless(1280, 720);
+    renderer.render_state(&state);
+    let sprites: Vec<&str> = renderer.draw_log.iter().map(|c| c.sprite_id.as_str()).collect();
+    assert!(sprites.contains(&"loot:crate") && sprites.contains(&"loot:requisition"));
+    assert_eq!(sprites.iter().filter(|s| s.starts_with("loot:")).count(), 2);
+
+    let loc = Localizer::new("en").unwrap();
+    let mut flow = GameFlow::new(state, UiManager::new(1280, 720, Vec::new(), Vec::new()));
+    flow.render(&mut renderer, &loc);
+    assert!(renderer.text_log.iter().any(|t| t.text == "2 items here (1 AP)"));
+
+    let scenario = Scenario::load("assets/scenarios/night_raid.json").unwrap();
+    assert_eq!(scenario.map().crate_at(&pos(5, 6)).map(|c| c.contents.len()), Some(2));
+}
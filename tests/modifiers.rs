@@ -0,0 +1,94 @@
+use gero::combat::{use_ability, CombatEncounter};
+use gero::damage::DamageType;
+use gero::grid::GridMap;
+use gero::models::{
+    Ability, AbilityEffect, AbilityType, AnimationType, Armor, ArmorTier, Faction, StatsModifier, Unit, UnitType,
+};
+use gero::modifiers::{Modifier, ModifierSource, Stat};
+use gero::state::GameState;
+
+fn rally(duration: u32) -> Ability {
+    Ability {
+        id: "rally".into(),
+        name: "Rally".into(),
+        ability_type: AbilityType::Buff,
+        description: String::new(),
+        action_point_cost: 1,
+        cooldown: 0,
+        current_cooldown: 0,
+        range: 5,
+        area_of_effect: None,
+        effect: AbilityEffect {
+            damage: None,
+            healing: None,
+            buff: Some(StatsModifier { strength_mod: 2, toughness_mod: 0, agility_mod: 0, intellect_mod: 0, willpower_mod: 1, fellowship_mod: 0 }),
+            debuff: None,
+            status_applied: None,
+            duration: Some(duration),
+            creates_hazard: None,
+            alters_terrain: None,
+            forced_move: None,
+            damage_type: DamageType::Kinetic,
+        },
+        animation: AnimationType::AbilityCast,
+        sound_effect_key: String::new(),
+        rank: 1,
+        ranks: Vec::new(),
+        channel: None,
+    }
+}
+
+fn flak() -> Armor {
+    Armor {
+        id: "flak".into(),
+        name: "Flak".into(),
+        tier: ArmorTier::Flak,
+        toughness_bonus: 2,
+        agility_penalty: -1,
+        special_properties: Vec::new(),
+    }
+}
+
+#[test]
+fn buffs_survive_turn_start_and_expire_with_duration() {
+    let mut user = Unit::new("u", "User", UnitType::Commissar, Faction::Imperial);
+    user.abilities.push(rally(2));
+    let target = Unit::new("t", "Target", UnitType::Guardsman, Faction::Imperial);
+    let mut enc = CombatEncounter::new(vec![target], vec![], GridMap::new(4, 4), None);
+    use_ability(&mut user, 0, &mut [enc.unit_by_id_mut("t").unwrap()], None).unwrap();
+    assert_eq!(enc.unit_by_id("t").unwrap().current_stats.strength, 2);
+
+    enc.start_turn();
+    assert_eq!(enc.unit_by_id("t").unwrap().current_stats.strength, 2);
+    assert_eq!(enc.unit_by_id("t").unwrap().current_stats.willpower, 1);
+    enc.end_turn();
+    enc.start_turn();
+    assert_eq!(enc.unit_by_id("t").unwrap().current_stats.strength, 2);
+    enc.end_turn();
+    assert_eq!(enc.unit_by_id("t").unwrap().current_stats.strength, 0);
+    assert!(enc.unit_by_id("t").unwrap().modifiers.modifiers.is_empty());
+}
+
+#[test]
+fn equipment_changes_keep_timed_modifiers() {
+    let mut unit = Unit::new("u", "U", UnitType::Guardsman, Faction::Imperial);
+    unit.base_stats.toughness = 3;
+    unit.add_modifier(Modifier::timed(ModifierSource::Status, Stat::Toughness, 1, 3));
+    unit.equip_armor(flak());
+    assert_eq!(unit.current_stats.toughness, 6);
+    unit.unequip_armor();
+    assert_eq!(unit.current_stats.toughness, 4);
+}
+
+#[test]
+fn stats_are_derived_the_same_after_a_save_round_trip() {
+    let mut unit = Unit::new("u", "U", UnitType::Guardsman, Faction::Imperial);
+    unit.base_stats.agility = 4;
+    unit.equip_armor(flak());
+    unit.add_modifier(Modifier::timed(ModifierSource::Aura, Stat::Accuracy, 5, 1));
+    let mut loaded = GameState::load_from_str(&GameState::new(vec![unit.clone()]).save_to_string()).units.by_name("u").cloned().unwrap();
+    loaded.recalculate_stats();
+    assert_eq!(loaded.current_stats.agility, 3);
+    assert_eq!(loaded.current_stats.accuracy, 5);
+    assert_eq!(loaded.modifiers, unit.modifiers);
+}
@@ -0,0 +1,153 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use gero::audio::AudioSystem;
+use gero::content::ContentDatabase;
+use gero::flow::GameFlow;
+use gero::frontend::Renderer;
+use gero::input::GameAction;
+use gero::localization::Localizer;
+use gero::mods::{AssetKind, ModLoader, LOAD_ORDER_FILE};
+use gero::state::GameState;
+use gero::ui::UiManager;
+
+fn mods_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("gero_mods_{}_{}", name, std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+/// Write `files` under `root/id`, next to a `mod.json` named after the id.
+fn write_mod(root: &Path, id: &str, files: &[(&str, &str)]) {
+    let dir = root.join(id);
+    fs::create_dir_all(&dir).unwrap();
+    fs::write(dir.join("mod.json"), format!(r#"{{ "name": "{} pack", "version": "1.0" }}"#, id)).unwrap();
+    for (path, data) in files {
+        let path = dir.join(path);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, data).unwrap();
+    }
+}
+
+const CHEAP_LASGUN: &str = r#"{ "items": { "lasgun": { "price": 5, "item": { "weapon": {
+    "id": "lasgun", "name": "Lasgun", "tier": "Basic", "damage": 3, "accuracy": 0.6,
+    "range": 8, "armor_piercing": null, "action_point_cost": 1, "critical_chance": 0.05,
+    "abilities_granted": [] } } } } }"#;
+
+#[test]
+fn unlisted_mods_load_after_the_saved_order() {
+    let root = mods_dir("order");
+    write_mod(&root, "alpha", &[]);
+    write_mod(&root, "beta", &[]);
+    write_mod(&root, "gamma", &[]);
+    fs::create_dir_all(root.join("not_a_mod")).unwrap();
+    fs::write(root.join(LOAD_ORDER_FILE), r#"[{ "id": "gamma", "enabled": false }, { "id": "gone", "enabled": true }]"#)
+        .unwrap();
+
+    let mut loader = ModLoader::scan(&root).unwrap();
+    let ids: Vec<&str> = loader.mods.iter().map(|m| m.id.as_str()).collect();
+    assert_eq!(ids, ["gamma", "alpha", "beta"]);
+    assert_eq!(loader.enabled().map(|m| m.id.as_str()).collect::<Vec<_>>(), ["alpha", "beta"]);
+    assert_eq!(loader.get("alpha").unwrap().name, "alpha pack");
+
+    loader.set_enabled("gamma", true).unwrap();
+    loader.move_by("gamma", 5).unwrap();
+    assert_eq!(loader.set_enabled("gone", true), Err("unknown mod"));
+    loader.save_order().unwrap();
+    let ids: Vec<String> = ModLoader::scan(&root).unwrap().enabled().map(|m| m.id.clone()).collect();
+    assert_eq!(ids, ["alpha", "beta", "gamma"]);
+
+    assert!(ModLoader::scan(root.join("missing")).unwrap().mods.is_empty());
+    fs::remove_dir_all(&root).unwrap();
+}
+
+#[test]
+fn later_mods_override_content_strings_and_sounds() {
+    let root = mods_dir("apply");
+    write_mod(
+        &root,
+        "a_cheap",
+        &[("content/items.json", CHEAP_LASGUN), ("locales/en.json", r#"{ "ui.tab.abilities": "Powers" }"#)],
+    );
+    write_mod(
+        &root,
+        "b_grim",
+        &[
+            ("locales/en.json", r#"{ "ui.tab.abilities": "Litanies", "mod.grim.motto": "Only war" }"#),
+            ("locales/fr.json", r#"{ "ui.tab.abilities": "Pouvoirs" }"#),
+            ("sounds/manifest.json", r#"{ "button_click": "click.wav" }"#),
+            ("sounds/click.wav", "RIFF"),
+        ],
+    );
+    let loader = ModLoader::scan(&root).unwrap();
+
+    let mut db = ContentDatabase::load("assets/content/items.json").unwrap();
+    let items = db.items.len();
+    loader.apply_content(&mut db).unwrap();
+    assert_eq!(db.items.len(), items);
+    assert_eq!(db.get("lasgun").unwrap().price, 5);
+
+    let mut loc = Localizer::new("en").unwrap();
+    let revision = loc.revision();
+    loader.apply_locales(&mut loc).unwrap();
+    assert_eq!(loc.get("ui.tab.abilities"), "Litanies");
+    assert_eq!(loc.get("mod.grim.motto"), "Only war");
+    assert!(loc.revision() > revision);
+
+    let mut audio = AudioSystem::new();
+    loader.apply_sounds(&mut audio).unwrap();
+    audio.play("button_click");
+    assert!(audio.is_loaded("button_click"));
+
+    let conflicts = loader.conflicts().unwrap();
+    assert_eq!(conflicts.len(), 1);
+    assert_eq!(conflicts[0].kind, AssetKind::Locale);
+    assert_eq!(conflicts[0].key, "en/ui.tab.abilities");
+    assert_eq!(conflicts[0].mods, ["a_cheap", "b_grim"]);
+    fs::remove_dir_all(&root).unwrap();
+}
+
+#[test]
+fn sprite_overrides_replace_base_frames() {
+    let root = mods_dir("sprites");
+    write_mod(
+        &root,
+        "recolor",
+        &[("sprites/atlas.json", r#"{ "image": "atlas.png", "sprites": { "guard_idle_0": { "x": 0, "y": 0, "w": 1, "h": 1 } } }"#)],
+    );
+    image::RgbaImage::from_pixel(1, 1, image::Rgba([0, 255, 0, 255])).save(root.join("recolor/sprites/atlas.png")).unwrap();
+
+    let mut renderer = Renderer::new_headless(64, 64);
+    ModLoader::scan(&root).unwrap().apply_sprites(&mut renderer).unwrap();
+    assert_eq!(renderer.sprite_textures["guard_idle_0"][0], [0, 255, 0, 255]);
+    fs::remove_dir_all(&root).unwrap();
+}
+
+#[test]
+fn mods_screen_toggles_and_saves() {
+    let root = mods_dir("screen");
+    write_mod(&root, "a_cheap", &[("content/items.json", CHEAP_LASGUN)]);
+    write_mod(&root, "b_cheap", &[("content/items.json", CHEAP_LASGUN)]);
+    let mut flow = GameFlow::new(GameState::new(Vec::new()), UiManager::new(1280, 720, Vec::new(), Vec::new()));
+    flow.open_mods(ModLoader::scan(&root).unwrap());
+    assert!(flow.is_paused());
+
+    let loc = Localizer::new("en").unwrap();
+    let mut renderer = Renderer::new_headless(1280, 720);
+    flow.render(&mut renderer, &loc);
+    let texts: Vec<&str> = renderer.text_log.iter().map(|t| t.text.as_str()).collect();
+    assert!(texts.contains(&"[x] a_cheap pack 1.0"));
+    assert!(texts.contains(&"1 conflict"));
+
+    flow.handle_action(GameAction::SelectDown);
+    flow.handle_action(GameAction::Activate);
+    assert!(flow.mods.as_ref().unwrap().conflicts.is_empty());
+    let saved = ModLoader::scan(&root).unwrap();
+    assert!(!saved.get("b_cheap").unwrap().enabled);
+
+    flow.handle_action(GameAction::SelectDown);
+    flow.handle_action(GameAction::Activate);
+    assert!(flow.mods.is_none());
+    fs::remove_dir_all(&root).unwrap();
+}
@@ -1,5 +1,5 @@
 use gero::models::{Unit, UnitType, Faction, Position};
-use gero::grid::{GridMap, TerrainType, try_move};
+use gero::grid::{GridMap, TerrainType, try_move, HAZARD_DAMAGE};
 
 #[test]
 fn hazardous_tile_applies_damage() {
@@ -10,7 +10,7 @@ fn hazardous_tile_applies_damage() {
     let start_hp = unit.health_points;
     assert!(try_move(&mut unit, Position { x: 2, y: 0 }, &map));
     assert_eq!(unit.grid_position, Position { x: 2, y: 0 });
-    assert_eq!(unit.health_points, start_hp - 1);
+    assert_eq!(unit.health_points, start_hp - HAZARD_DAMAGE);
 }
 
 #[test]
@@ -21,3 +21,54 @@ fn move_out_of_bounds_fails() {
     assert!(!try_move(&mut unit, Position { x: 2, y: 2 }, &map));
     assert_eq!(unit.grid_position, Position { x: 0, y: 0 });
 }
+
+#[test]
+fn hazard_damage_depends_on_unit() {
+    use gero::models::{Armor, ArmorTier};
+
+    let mut map = GridMap::new(2, 1);
+    map.set_terrain(&Position { x: 1, y: 0 }, TerrainType::Hazardous);
+
+    let mut daemon = Unit::new("d", "D", UnitType::Daemon, Faction::Chaos);
+    daemon.current_stats.agility = 10;
+    assert!(try_move(&mut daemon, Position { x: 1, y: 0 }, &map));
+    assert_eq!(daemon.health_points, daemon.current_stats.max_health);
+
+    let mut marine = Unit::new("m", "M", UnitType::SpaceMarine, Faction::Imperial);
+    marine.equip_armor(Armor {
+        id: "pa".into(),
+        name: "Power Armour".into(),
+        tier: ArmorTier::PowerArmor,
+        toughness_bonus: 0,
+        agility_penalty: 0,
+        special_properties: Vec::new(),
+    });
+    marine.current_stats.agility = 10;
+    assert!(try_move(&mut marine, Position { x: 1, y: 0 }, &map));
+    // Power armour halves the damage but doesn't stop it.
+    let lost = marine.current_stats.max_health - marine.health_points;
+    assert!(lost > 0 && lost < HAZARD_DAMAGE);
+}
+
+#[test]
+fn airborne_units_cross_blocked_tiles_at_extra_cost() {
+    use gero::models::MovementType;
+
+    let mut map = GridMap::new(3, 1);
+    map.set_terrain(&Position { x: 1, y: 0 }, TerrainType::Blocked);
+    let mover = |movement_type, agility| {
+        let mut unit = Unit::new("u", "U", UnitType::Guardsman, Faction::Imperial);
+        unit.movement_type = movement_type;
+        unit.current_stats.agility = agility;
+        unit.action_points = 1;
+        unit
+    };
+
+    assert!(!try_move(&mut mover(MovementType::Ground, 20), Position { x: 2, y: 0 }, &map));
+    // Flying: 1 + 2 over the wall, then 1.
+    assert!(!try_move(&mut mover(MovementType::Flying, 6), Position { x: 2, y: 0 }, &map));
+    assert!(try_move(&mut mover(MovementType::Flying, 8), Position { x: 2, y: 0 }, &map));
+    assert!(!try_move(&mut mover(MovementType::Jump, 8), Position { x: 2, y: 0 }, &map));
+    assert!(try_move(&mut mover(MovementType::Jump, 10), Position { x: 2, y: 0 }, &map));
+    assert!(!try_move(&mut mover(MovementType::Flying, 20), Position { x: 1, y: 0 }, &map));
+}
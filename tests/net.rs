@@ -0,0 +1,108 @@
+use std::net::TcpListener;
+use std::thread;
+
+use gero::combat::{CombatEncounter, Controller};
+use gero::command::Command;
+use gero::grid::GridMap;
+use gero::models::{Faction, Position, Unit, UnitType};
+use gero::net::{sync_checksum, LockstepSession, TurnOutcome};
+use gero::state::GameState;
+
+fn encounter() -> CombatEncounter {
+    let mut imperial = Unit::new("imp", "Imp", UnitType::Guardsman, Faction::Imperial);
+    imperial.base_stats.agility = 4;
+    imperial.recalculate_stats();
+    let mut ork = Unit::new("ork", "Ork", UnitType::OrkBoy, Faction::Ork);
+    ork.grid_position = Position { x: 7, y: 0 };
+    let mut enc = CombatEncounter::new(vec![imperial], vec![ork], GridMap::new(8, 8), None);
+    enc.set_controller(Faction::Ork, Controller::Player(1));
+    enc
+}
+
+fn imp_move() -> Command {
+    Command::Move { unit_id: "imp".into(), destination: Position { x: 2, y: 0 } }
+}
+
+#[test]
+fn peers_stay_in_sync_over_a_turn() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    let guest = thread::spawn(move || {
+        let (mut session, mut enc) = LockstepSession::join(addr).unwrap();
+        // The guest can't move the host's unit; the command is skipped on both sides.
+        let outcome = session.submit_turn(&mut enc, vec![imp_move()]).unwrap();
+        (outcome, GameState::from_encounter(&enc).checksum())
+    });
+
+    let mut enc = encounter();
+    enc.start_turn();
+    let mut session = LockstepSession::accept(&listener, &enc, 0).unwrap();
+    let outcome = session.submit_turn(&mut enc, vec![imp_move()]).unwrap();
+    let (guest_outcome, guest_checksum) = guest.join().unwrap();
+
+    assert_eq!(outcome, TurnOutcome::InSync);
+    assert_eq!(guest_outcome, TurnOutcome::InSync);
+    assert_eq!(session.turn, 1);
+    assert_eq!(enc.unit_by_id("imp").unwrap().grid_position, Position { x: 2, y: 0 });
+    assert_eq!(GameState::from_encounter(&enc).checksum(), guest_checksum);
+}
+
+#[test]
+fn desync_is_detected_and_resolved_by_resync() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    let guest = thread::spawn(move || {
+        let (mut session, mut enc) = LockstepSession::join(addr).unwrap();
+        enc.unit_by_id_mut("ork").unwrap().health_points -= 3;
+        let outcome = session.submit_turn(&mut enc, Vec::new()).unwrap();
+        session.resync(&mut enc).unwrap();
+        (outcome, enc.unit_by_id("ork").unwrap().health_points, session.turn)
+    });
+
+    let mut enc = encounter();
+    enc.start_turn();
+    let mut session = LockstepSession::accept(&listener, &enc, 0).unwrap();
+    let outcome = session.submit_turn(&mut enc, Vec::new()).unwrap();
+    assert!(matches!(outcome, TurnOutcome::Desync { .. }));
+    session.resync(&mut enc).unwrap();
+
+    let (guest_outcome, guest_hp, guest_turn) = guest.join().unwrap();
+    assert!(matches!(guest_outcome, TurnOutcome::Desync { .. }));
+    assert_eq!(guest_hp, 10);
+    assert_eq!(guest_turn, 1);
+}
+
+#[test]
+fn reconnecting_guest_receives_current_state() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    let mut enc = encounter();
+    enc.start_turn();
+    enc.execute(imp_move()).unwrap();
+
+    let guest = thread::spawn(move || {
+        let (session, enc) = LockstepSession::join(addr).unwrap();
+        (session.turn, enc)
+    });
+    let session = LockstepSession::accept(&listener, &enc, 4).unwrap();
+    let (turn, guest_enc) = guest.join().unwrap();
+
+    assert_eq!(session.turn, 4);
+    assert_eq!(turn, 4);
+    assert_eq!(guest_enc.unit_by_id("imp").unwrap().grid_position, Position { x: 2, y: 0 });
+    assert_eq!(guest_enc.command_log, enc.command_log);
+}
+
+#[test]
+fn checksums_leave_out_animation_and_selection() {
+    let enc = encounter();
+    let mut other = enc.clone();
+    let imp = other.unit_by_id_mut("imp").unwrap();
+    imp.animation_state.timer += 0.5;
+    imp.animation_state.frame_index += 1;
+    imp.is_selected = true;
+    assert_eq!(sync_checksum(&other), sync_checksum(&enc));
+
+    other.unit_by_id_mut("imp").unwrap().health_points -= 1;
+    assert_ne!(sync_checksum(&other), sync_checksum(&enc));
+}
@@ -0,0 +1,104 @@
+use gero::campaign::Campaign;
+use gero::frontend::Renderer;
+use gero::input::GameAction;
+use gero::localization::Localizer;
+use gero::models::Faction;
+use gero::overworld::OverworldMap;
+use gero::profile::Difficulty;
+use gero::rng::GameRng;
+use gero::scenario::Scenario;
+use gero::ui::overworld::OverworldScreen;
+
+fn map() -> OverworldMap {
+    OverworldMap::load("assets/content/overworld.json").unwrap()
+}
+
+#[test]
+fn travel_follows_routes_and_pays_the_way() {
+    let map = map();
+    let mut campaign = Campaign::new();
+    let mut rng = GameRng::new(0);
+    assert_eq!(campaign.location(&map), "cadia_gate");
+    let ids: Vec<&str> = map.neighbours("cadia_gate").iter().map(|l| l.id.as_str()).collect();
+    assert_eq!(ids, ["forge_delta", "hive_tertius"]);
+    assert_eq!(campaign.travel(&map, "cadia_gate", &mut rng), Err("already at location"));
+    assert_eq!(campaign.travel(&map, "ash_wastes", &mut rng), Err("no route to location"));
+    assert_eq!(campaign.travel(&map, "hive_tertius", &mut rng), Err("insufficient requisition"));
+
+    let travel = campaign.travel(&map, "forge_delta", &mut rng).unwrap();
+    assert_eq!((travel.days, travel.cost, travel.interception), (2, 0, None));
+    campaign.currency = 25;
+    campaign.travel(&map, "ash_wastes", &mut rng).unwrap();
+    assert_eq!(campaign.location(&map), "ash_wastes");
+    assert_eq!(campaign.overworld.day, 6);
+    assert_eq!(campaign.currency, 5);
+
+    // Position and progress are kept in the campaign save.
+    let saved: Campaign = serde_json::from_str(&serde_json::to_string(&campaign).unwrap()).unwrap();
+    assert_eq!(saved.overworld, campaign.overworld);
+}
+
+#[test]
+fn enemy_territory_intercepts_until_liberated() {
+    let map = map();
+    let mut campaign = Campaign::new();
+    campaign.currency = 100;
+    campaign.overworld.location = "ash_wastes".into();
+    let travel = campaign.travel(&map, "warlord_keep", &mut GameRng::new(3)).unwrap();
+    let interception = travel.interception.unwrap();
+    assert_eq!(interception.location, "warlord_keep");
+    assert_eq!(interception.faction, Faction::Ork);
+    let scenario = Scenario::load("assets/scenarios/night_raid.json").unwrap();
+    let mission = interception.mission(scenario, Difficulty::Hard);
+    assert_eq!(mission.faction, Faction::Ork);
+
+    campaign.liberate("ash_wastes");
+    assert_eq!(campaign.controller(&map, "ash_wastes"), None);
+    assert_eq!(campaign.controller(&map, "warlord_keep"), Some(Faction::Ork));
+    for seed in 0..20 {
+        campaign.overworld.location = "warlord_keep".into();
+        campaign.currency = 30;
+        assert_eq!(campaign.travel(&map, "ash_wastes", &mut GameRng::new(seed)).unwrap().interception, None);
+    }
+}
+
+#[test]
+fn malformed_maps_are_rejected() {
+    let data = std::fs::read_to_string("assets/content/overworld.json").unwrap();
+    let dir = std::env::temp_dir().join("gero_overworld_test");
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("overworld.json");
+    std::fs::write(&path, data.replace(r#""to": "warlord_keep""#, r#""to": "terra""#)).unwrap();
+    assert!(OverworldMap::load(&path).unwrap_err().to_string().contains("unknown location 'terra'"));
+    std::fs::write(&path, data.replace(r#""days": 2 }"#, r#""days": 0 }"#)).unwrap();
+    assert!(OverworldMap::load(&path).unwrap_err().to_string().contains("takes no time"));
+}
+
+#[test]
+fn the_overworld_screen_selects_and_travels() {
+    let map = map();
+    let loc = Localizer::new("en").unwrap();
+    let mut campaign = Campaign::new();
+    let mut screen = OverworldScreen::new();
+    let mut rng = GameRng::new(0);
+    let mut renderer = Renderer::new_headless(1280, 720);
+    screen.render(&mut renderer, &loc, &map, &campaign);
+    assert_eq!(renderer.draw_log.iter().filter(|c| c.sprite_id == "overworld:enemy").count(), 3);
+    assert!(renderer.draw_log.iter().any(|c| c.sprite_id == "overworld:squad" && c.position == (108, 348)));
+    assert!(renderer.draw_log.iter().any(|c| c.tint.is_some() && c.position == (348, 188)));
+    assert!(renderer.text_log.iter().any(|t| t.text == "Day 0"));
+    assert!(renderer.text_log.iter().any(|t| t.text == "2 days - Requisition: 0"));
+    assert!(!renderer.rect_log.is_empty());
+
+    screen.handle_input(GameAction::SelectDown, &map, &mut campaign, &mut rng);
+    screen.handle_input(GameAction::SelectDown, &map, &mut campaign, &mut rng);
+    assert_eq!(screen.selected_index, 1);
+    screen.handle_input(GameAction::Activate, &map, &mut campaign, &mut rng);
+    assert_eq!(screen.last_error, Some("insufficient requisition"));
+    screen.handle_input(GameAction::SelectUp, &map, &mut campaign, &mut rng);
+    screen.handle_input(GameAction::Activate, &map, &mut campaign, &mut rng);
+    assert_eq!(campaign.location(&map), "forge_delta");
+    let mut renderer = Renderer::new_headless(1280, 720);
+    screen.render(&mut renderer, &loc, &map, &campaign);
+    assert!(renderer.text_log.iter().any(|t| t.text == "Arrived after 2 days"));
+}
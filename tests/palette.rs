@@ -0,0 +1,61 @@
+use gero::frontend::palette::{Palette, PaletteRole};
+use gero::frontend::text::{DAMAGE_RED, HEAL_GREEN};
+use gero::frontend::tiles::OverlayKind;
+use gero::frontend::{DrawLayer, Renderer, SIDE_MARKER};
+use gero::localization::Localizer;
+use gero::models::{EffectType, Faction, Position, StatusEffect, Unit, UnitType};
+use gero::state::GameState;
+use gero::ui::UiManager;
+use gero::ui::options::{AccessibilitySettings, ColorBlindPalette};
+
+fn poisoned_guard() -> Unit {
+    let mut unit = Unit::new("u", "U", UnitType::Guardsman, Faction::Imperial);
+    unit.sprite_id = "guard".into();
+    unit.status_effects.push(StatusEffect { effect_type: EffectType::Poison, remaining_turns: 2, magnitude: 1 });
+    unit
+}
+
+#[test]
+fn every_scheme_keeps_allies_enemies_and_highlights_apart() {
+    for scheme in [
+        ColorBlindPalette::Normal,
+        ColorBlindPalette::Protanopia,
+        ColorBlindPalette::Deuteranopia,
+        ColorBlindPalette::Tritanopia,
+    ] {
+        let palette = Palette::new(scheme);
+        assert_ne!(palette.color(PaletteRole::Ally), palette.color(PaletteRole::Enemy));
+        assert_ne!(palette.color(PaletteRole::MovementRange), palette.color(PaletteRole::Targeting));
+        assert_ne!(palette.color(PaletteRole::Damage), palette.color(PaletteRole::Heal));
+    }
+    let normal = Palette::default();
+    assert_eq!(normal.color(PaletteRole::Damage), DAMAGE_RED);
+    assert_eq!(normal.color(PaletteRole::Heal), HEAL_GREEN);
+}
+
+#[test]
+fn renderer_applies_the_palette_from_accessibility_settings() {
+    let mut renderer = Renderer::new_headless(640, 640);
+    renderer.load_sprite_from_bytes("guard", vec![vec![0]]);
+    renderer.load_sprite_from_bytes(SIDE_MARKER, vec![vec![0]]);
+    renderer.apply_accessibility(&AccessibilitySettings { palette: ColorBlindPalette::Deuteranopia, ..Default::default() });
+    let palette = Palette::new(ColorBlindPalette::Deuteranopia);
+    renderer.set_overlay(OverlayKind::MovementRange, &[Position { x: 1, y: 0 }]);
+    renderer.set_overlay(OverlayKind::Targeting, &[Position { x: 2, y: 0 }]);
+
+    renderer.render_state(&GameState::new(vec![poisoned_guard()]));
+    let unit = renderer.draw_log.iter().find(|c| c.layer == DrawLayer::Units).unwrap();
+    assert_eq!(unit.tint, Some(palette.color(PaletteRole::Poison)));
+    let marker = renderer.draw_log.iter().find(|c| c.sprite_id == SIDE_MARKER).unwrap();
+    assert_eq!(marker.tint, Some(palette.color(PaletteRole::Ally)));
+    let overlay = |sprite: &str| renderer.draw_log.iter().find(|c| c.sprite_id == sprite).unwrap().tint;
+    assert_eq!(overlay("overlay:move"), Some(palette.color(PaletteRole::MovementRange)));
+    assert_eq!(overlay("overlay:target"), Some(palette.color(PaletteRole::Targeting)));
+
+    let mut ui = UiManager::new(640, 640, vec![], vec![]);
+    ui.spawn_floating_text(-4, (5, 5));
+    ui.render(&mut renderer, &Localizer::new("en").unwrap());
+    let damage = renderer.text_log.iter().find(|t| t.text == "-4").unwrap();
+    assert_eq!(damage.color, palette.color(PaletteRole::Damage));
+    assert_ne!(damage.color, DAMAGE_RED);
+}
@@ -0,0 +1,101 @@
+use gero::builders::{AbilityBuilder, UnitBuilder, WeaponBuilder};
+use gero::combat::CombatEncounter;
+use gero::command::Command;
+use gero::grid::{GridMap, TerrainType};
+use gero::loot::Loot;
+use gero::models::{AbilityType, Accessory, EffectType, Faction, Position, StatusEffect, UnitType};
+use gero::planner::{ActionKind, ActionPlanner};
+use gero::rng::GameRng;
+
+fn at(x: usize, y: usize) -> Position {
+    Position { x, y }
+}
+
+/// A medic with a gun, a heal and a grenade, a wounded ally beside the
+/// target ork and a second ork out of sight behind a wall.
+fn skirmish() -> CombatEncounter {
+    let gun = WeaponBuilder::new("gun").damage(4).accuracy(1.0).range(6).build();
+    let heal = AbilityBuilder::new("heal", AbilityType::Healing).healing(10).range(6).action_point_cost(2).build();
+    let mut medic = UnitBuilder::new("medic", UnitType::Guardsman, Faction::Imperial).weapon(gun).ability(heal).build();
+    medic.equipment.accessory_slots.push(Accessory::Grenade { damage: 6, aoe_radius: 1, leaves: None });
+    let mut ally = UnitBuilder::new("ally", UnitType::Guardsman, Faction::Imperial).max_health(20).at(3, 1).build();
+    ally.health_points = 14;
+    let ork = UnitBuilder::new("ork", UnitType::OrkBoy, Faction::Ork).max_health(20).at(3, 0).build();
+    let hidden = UnitBuilder::new("hidden", UnitType::OrkBoy, Faction::Ork).at(0, 4).build();
+    let mut map = GridMap::new(6, 6);
+    map.set_terrain(&at(0, 2), TerrainType::Blocked);
+    map.set_terrain(&at(1, 2), TerrainType::Blocked);
+    CombatEncounter::new(vec![medic, ally], vec![ork, hidden], map, None)
+}
+
+#[test]
+fn costs_list_every_action_and_gray_out_what_the_unit_cannot_pay_for() {
+    let mut encounter = skirmish();
+    let planner = ActionPlanner::new(&encounter, "medic").unwrap();
+    let costs: Vec<_> = planner.costs().iter().map(|c| (c.kind, c.ap_cost, c.affordable)).collect();
+    assert_eq!(
+        costs,
+        [
+            (ActionKind::Attack, 1, true),
+            (ActionKind::Ability { ability_index: 0 }, 2, true),
+            (ActionKind::Grenade { accessory_index: 0 }, 1, true),
+        ]
+    );
+
+    encounter.unit_by_id_mut("medic").unwrap().action_points = 1;
+    encounter.battlefield.drop_loot(&at(0, 0), vec![Loot::Requisition(5)]);
+    let planner = ActionPlanner::new(&encounter, "medic").unwrap();
+    let affordable: Vec<_> = planner.costs().iter().filter(|c| c.affordable).map(|c| c.kind).collect();
+    assert_eq!(affordable, [ActionKind::Attack, ActionKind::Grenade { accessory_index: 0 }, ActionKind::PickUp]);
+    assert!(planner.actions().iter().all(|a| a.kind != ActionKind::Ability { ability_index: 0 }));
+
+    // Pinned units can still pick things up, but not fight.
+    let pinned = StatusEffect { effect_type: EffectType::Pinned, remaining_turns: 1, magnitude: 0 };
+    encounter.unit_by_id_mut("medic").unwrap().status_effects.push(pinned);
+    let planner = ActionPlanner::new(&encounter, "medic").unwrap();
+    let actions: Vec<_> = planner.actions().into_iter().map(|a| a.command).collect();
+    assert_eq!(actions, [Command::PickUp { unit_id: "medic".into() }]);
+    assert_eq!(planner.suggestion(), None);
+}
+
+#[test]
+fn shots_preview_the_chance_and_damage_the_attack_resolves_with() {
+    let mut encounter = skirmish();
+    encounter.balance.critical_multiplier = 1;
+    let planner = ActionPlanner::new(&encounter, "medic").unwrap();
+    let shots: Vec<_> = planner.actions().into_iter().filter(|a| a.kind == ActionKind::Attack).collect();
+    // The ork behind the wall is out of sight.
+    assert_eq!(shots.len(), 1);
+    let shot = &shots[0];
+    assert_eq!(shot.command, Command::Attack { attacker_id: "medic".into(), target_id: "ork".into() });
+    let (medic, ork) = (encounter.unit_by_id("medic").unwrap(), encounter.unit_by_id("ork").unwrap());
+    let chance = encounter.balance.hit_chance(&medic.current_stats, 1.0, medic.current_stats.accuracy, ork.current_stats.agility, 0);
+    assert_eq!(shot.expected.hit_chance, (chance / 100.0).clamp(0.0, 1.0));
+
+    // A sure hit deals just what was expected.
+    encounter.rng = GameRng::new((0..).find(|&s| GameRng::new(s).roll_d100() == 1).unwrap());
+    encounter.unit_by_id_mut("medic").unwrap().current_stats.accuracy = 200;
+    let planner = ActionPlanner::new(&encounter, "medic").unwrap();
+    let damage = planner.actions().into_iter().find(|a| a.command == shot.command).unwrap().expected.damage;
+    encounter.execute(shot.command.clone()).unwrap();
+    assert_eq!(20 - encounter.unit_by_id("ork").unwrap().health_points, damage);
+}
+
+#[test]
+fn blasts_that_catch_friends_are_worth_less_and_heals_stop_at_full_health() {
+    let encounter = skirmish();
+    let planner = ActionPlanner::new(&encounter, "medic").unwrap();
+    let actions = planner.actions();
+    let at_ork = Command::ThrowGrenade { thrower_id: "medic".into(), accessory_index: 0, target: at(3, 0) };
+    let grenade = actions.iter().find(|a| a.command == at_ork).unwrap();
+    assert_eq!((grenade.expected.damage, grenade.expected.friendly_damage), (6, 6));
+    assert_eq!(grenade.expected.value(), 0.0);
+
+    let heals: Vec<_> = actions.iter().filter(|a| a.kind == ActionKind::Ability { ability_index: 0 }).collect();
+    let healing: Vec<_> = heals.iter().map(|a| a.expected.healing).collect();
+    assert_eq!(healing, [6, 0]);
+    // Patching up the ally beats a four-damage shot.
+    let heal_ally = Command::UseAbility { user_id: "medic".into(), ability_index: 0, target_ids: vec!["ally".into()] };
+    assert_eq!(planner.suggestion().unwrap().command, heal_ally);
+    assert!(actions.windows(2).all(|w| w[0].expected.value() >= w[1].expected.value()));
+}
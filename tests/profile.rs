@@ -0,0 +1,74 @@
+use std::sync::{Arc, Mutex};
+
+use gero::audio::{AudioChannel, AudioSystem};
+use gero::input::GameAction;
+use gero::profile::{Difficulty, Profile, Settings, SETTINGS_FILE};
+use gero::ui::options::ColorBlindPalette;
+
+fn temp_settings(name: &str) -> std::path::PathBuf {
+    std::env::temp_dir().join(format!("gero_profile_{}_{}", name, std::process::id())).join(SETTINGS_FILE)
+}
+
+#[test]
+fn settings_round_trip_through_the_config_file() {
+    let path = temp_settings("round_trip");
+    let _ = std::fs::remove_dir_all(path.parent().unwrap());
+    let mut profile = Profile::load(&path).unwrap();
+    assert_eq!(profile.settings(), &Settings::default());
+
+    profile
+        .update(|s| {
+            s.audio.music = 0.25;
+            s.accessibility.palette = ColorBlindPalette::Tritanopia;
+            s.keybindings.bind("KeyW", GameAction::SelectUp);
+            s.difficulty = Difficulty::Hard;
+            s.language = "de".into();
+        })
+        .unwrap();
+
+    let loaded = Profile::load(&path).unwrap();
+    assert_eq!(loaded.settings(), profile.settings());
+    assert_eq!(loaded.settings().difficulty, Difficulty::Hard);
+    std::fs::remove_dir_all(path.parent().unwrap()).unwrap();
+}
+
+#[test]
+fn missing_fields_keep_their_defaults() {
+    let path = temp_settings("partial");
+    std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+    std::fs::write(&path, r#"{ "difficulty": "Easy" }"#).unwrap();
+    let profile = Profile::load(&path).unwrap();
+    assert_eq!(profile.settings().difficulty, Difficulty::Easy);
+    assert_eq!(profile.settings().language, "en");
+    assert_eq!(profile.settings().keybindings, Default::default());
+    std::fs::remove_dir_all(path.parent().unwrap()).unwrap();
+}
+
+#[test]
+fn listeners_hear_only_real_changes() {
+    let mut profile = Profile::default();
+    let seen = Arc::new(Mutex::new(Vec::new()));
+    let log = seen.clone();
+    profile.on_change(move |s| log.lock().unwrap().push(s.audio.master));
+
+    profile.update(|s| s.audio.master = 0.5).unwrap();
+    profile.update(|s| s.audio.master = 0.5).unwrap();
+    assert_eq!(*seen.lock().unwrap(), vec![0.5]);
+
+    let mut audio = AudioSystem::new();
+    audio.set_volume(AudioChannel::Sfx, 0.1);
+    audio.apply_settings(&profile.settings().audio);
+    assert_eq!(audio.settings, profile.settings().audio);
+}
+
+#[test]
+fn options_screen_edits_map_onto_settings() {
+    let mut settings = Settings::default();
+    let mut options = settings.options();
+    options.toggle_fullscreen();
+    options.render.show_fps = true;
+    settings.set_options(&options);
+    assert!(settings.display.borderless_fullscreen);
+    assert!(settings.render.show_fps);
+    assert_eq!(settings.options().language, settings.language);
+}
@@ -0,0 +1,97 @@
+use gero::builders::AbilityBuilder;
+use gero::campaign::Campaign;
+use gero::content::ContentDatabase;
+use gero::damage::DamageType;
+use gero::frontend::Renderer;
+use gero::localization::Localizer;
+use gero::models::{AbilityType, AreaOfEffect, Unit};
+use gero::ranks::AbilityRank;
+use gero::registry::{Side, UnitRegistry};
+use gero::ui::tooltip::{Tooltip, TooltipSubject};
+
+fn db() -> ContentDatabase {
+    ContentDatabase::load("assets/content/items.json").unwrap()
+}
+
+/// Raine, levelled once and having learned Summary Execution.
+fn raine(db: &ContentDatabase) -> Unit {
+    let hero = db.hero("commissar_raine").unwrap();
+    let mut raine = hero.spawn("commissar_raine");
+    raine.level_up(None);
+    hero.learn(&mut raine, "summary_execution").unwrap();
+    raine
+}
+
+#[test]
+fn level_ups_buy_ranks_from_the_content_tables() {
+    let db = db();
+    let mut raine = raine(&db);
+    assert_eq!(raine.rank_points, 1);
+    let ability = &raine.abilities[0];
+    assert_eq!((ability.rank, ability.max_rank(), ability.effect.damage, ability.cooldown), (1, 3, Some(8), 3));
+
+    raine.upgrade_ability("summary_execution").unwrap();
+    assert_eq!(raine.upgrade_ability("summary_execution"), Err("no rank points"));
+    raine.level_up(None);
+    raine.upgrade_ability("summary_execution").unwrap();
+    let ability = &raine.abilities[0];
+    assert_eq!((ability.rank, ability.effect.damage, ability.cooldown), (3, Some(12), 2));
+    assert!(ability.next_rank().is_none());
+
+    raine.level_up(None);
+    assert_eq!(raine.upgrade_ability("summary_execution"), Err("ability at max rank"));
+    assert_eq!(raine.upgrade_ability("lasgun"), Err("unknown ability"));
+    assert_eq!(raine.rank_points, 1);
+
+    let mut campaign = Campaign::new();
+    campaign.roster = vec![raine];
+    assert_eq!(campaign.upgrade_ability("nobody", "summary_execution"), Err("unknown unit"));
+}
+
+#[test]
+fn ranks_widen_areas_and_clamp_running_cooldowns() {
+    let mut ability = AbilityBuilder::new("blast", AbilityType::PsychicBlast)
+        .damage(4, DamageType::Kinetic)
+        .cooldown(4)
+        .area(AreaOfEffect::Circle { radius: 1 })
+        .rank(AbilityRank { area: Some(2), cooldown: Some(2), ..Default::default() })
+        .build();
+    ability.current_cooldown = 4;
+    ability.rank_up().unwrap();
+    assert!(matches!(ability.area_of_effect, Some(AreaOfEffect::Circle { radius: 2 })));
+    assert_eq!((ability.cooldown, ability.current_cooldown, ability.effect.damage), (2, 2, Some(4)));
+
+    let mut pointless = AbilityBuilder::new("bolt", AbilityType::PsychicBlast).rank(AbilityRank { area: Some(2), ..Default::default() }).build();
+    assert_eq!(pointless.rank_up(), Err("ability has no area"));
+    assert_eq!(pointless.rank, 1);
+}
+
+#[test]
+fn malformed_rank_tables_are_rejected() {
+    let data = std::fs::read_to_string("assets/content/items.json").unwrap();
+    let dir = std::env::temp_dir().join("gero_ranks_test");
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("items.json");
+    std::fs::write(&path, data.replace("\"ranks\": [ { \"healing\": 6 } ]", "\"ranks\": [ { \"area\": 2 } ]")).unwrap();
+    let err = ContentDatabase::load(&path).unwrap_err();
+    assert!(err.to_string().contains("ranks up an area it lacks"));
+}
+
+#[test]
+fn tooltips_show_the_current_rank() {
+    let db = db();
+    let mut raine = raine(&db);
+    raine.upgrade_ability("summary_execution").unwrap();
+    let mut units = UnitRegistry::new();
+    units.insert(raine, Side::Player);
+    let tooltip = Tooltip {
+        subject: TooltipSubject::Ability { unit_id: "commissar_raine".into(), ability_id: "summary_execution".into() },
+        position: (300, 300),
+    };
+    let mut renderer = Renderer::new_headless(1280, 720);
+    tooltip.render(&mut renderer, &Localizer::new("en").unwrap(), &units);
+    let texts: Vec<&str> = renderer.text_log.iter().map(|t| t.text.as_str()).collect();
+    assert!(texts.contains(&"Rank 2 of 3"));
+    assert!(texts.contains(&"10 damage"));
+    assert!(texts.contains(&"Recharges in 3 turns"));
+}
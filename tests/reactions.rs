@@ -0,0 +1,80 @@
+use gero::builders::{AbilityBuilder, UnitBuilder, WeaponBuilder};
+use gero::combat::{CombatEncounter, CombatEvent};
+use gero::command::Command;
+use gero::grid::GridMap;
+use gero::models::{AbilityType, Faction, Trait, Unit, UnitType, Weapon};
+use gero::reactions::{Reaction, ReactionEffect, ReactionTrigger, VENGEFUL_STRENGTH};
+
+fn blade() -> Weapon {
+    WeaponBuilder::new("blade").damage(4).range(1).accuracy(1.0).critical_chance(0.0).build()
+}
+
+fn unit(id: &str, faction: Faction, x: usize) -> UnitBuilder {
+    UnitBuilder::new(id, UnitType::Guardsman, faction).max_health(20).weapon(blade()).at(x, 0)
+}
+
+/// An encounter where criticals do no extra damage.
+fn encounter(players: Vec<Unit>, enemies: Vec<Unit>, width: usize) -> CombatEncounter {
+    let mut encounter = CombatEncounter::new(players, enemies, GridMap::new(width, 1), None);
+    encounter.balance.critical_multiplier = 1;
+    encounter
+}
+
+fn hp(encounter: &CombatEncounter, id: &str) -> i32 {
+    encounter.unit_by_id(id).unwrap().health_points
+}
+
+fn attack(attacker: &str, target: &str) -> Command {
+    Command::Attack { attacker_id: attacker.into(), target_id: target.into() }
+}
+
+#[test]
+fn riposte_strikes_back_once() {
+    let guard = unit("guard", Faction::Imperial, 0).with_trait(Trait::Riposte).build();
+    let ork = unit("ork", Faction::Ork, 1).with_trait(Trait::Riposte).build();
+    let mut encounter = encounter(vec![guard], vec![ork], 4);
+    encounter.execute(attack("guard", "ork")).unwrap();
+    // The ork strikes back, and the guard's riposte answers once; neither
+    // fires again for the same action.
+    assert_eq!(hp(&encounter, "ork"), 12);
+    assert_eq!(hp(&encounter, "guard"), 16);
+    let reacted = encounter.drain_events().iter().filter(|e| matches!(e, CombatEvent::UnitReacted { .. })).count();
+    assert_eq!(reacted, 2);
+    assert_eq!(encounter.unit_by_id("ork").unwrap().action_points, 2);
+}
+
+#[test]
+fn vengeful_units_grow_stronger_when_allies_fall() {
+    let near = unit("near", Faction::Imperial, 1).with_trait(Trait::Vengeful).build();
+    let far = unit("far", Faction::Imperial, 4).with_trait(Trait::Vengeful).build();
+    let mut doomed = unit("doomed", Faction::Imperial, 0).build();
+    doomed.health_points = 1;
+    let ork = unit("ork", Faction::Ork, 8).weapon(WeaponBuilder::new("gun").range(9).accuracy(1.0).build()).build();
+    let mut encounter = encounter(vec![doomed, near, far], vec![ork], 9);
+    let strength = encounter.unit_by_id("near").unwrap().current_stats.strength;
+    encounter.execute(attack("ork", "doomed")).unwrap();
+    assert!(hp(&encounter, "doomed") <= 0);
+    assert_eq!(encounter.unit_by_id("near").unwrap().current_stats.strength, strength + VENGEFUL_STRENGTH);
+    assert_eq!(encounter.unit_by_id("far").unwrap().current_stats.strength, strength);
+}
+
+#[test]
+fn abilities_can_react_to_allies_being_hurt() {
+    let mut medic = unit("medic", Faction::Imperial, 2)
+        .ability(AbilityBuilder::new("patch_up", AbilityType::Healing).healing(3).range(3).cooldown(2).build())
+        .build();
+    medic.reactions.push(Reaction {
+        trigger: ReactionTrigger::OnAllyDamaged { range: 3 },
+        effect: ReactionEffect::Ability { ability_id: "patch_up".into() },
+    });
+    let guard = unit("guard", Faction::Imperial, 0).build();
+    let ork = unit("ork", Faction::Ork, 1).build();
+    let mut encounter = encounter(vec![guard, medic], vec![ork], 4);
+    encounter.execute(attack("ork", "guard")).unwrap();
+    assert_eq!(hp(&encounter, "guard"), 19);
+    assert_eq!(encounter.unit_by_id("medic").unwrap().abilities[0].current_cooldown, 2);
+
+    // On cooldown, so the second hit goes unanswered.
+    encounter.execute(attack("ork", "guard")).unwrap();
+    assert_eq!(hp(&encounter, "guard"), 15);
+}
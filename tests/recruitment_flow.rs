@@ -0,0 +1,91 @@
+use gero::campaign::Campaign;
+use gero::flow::GameFlow;
+use gero::frontend::Renderer;
+use gero::input::GameAction;
+use gero::localization::Localizer;
+use gero::models::UnitType;
+use gero::recruitment::{QuestionPool, RecruitmentOutcome, RecruitmentSession};
+use gero::rng::GameRng;
+use gero::state::GameState;
+use gero::ui::UiManager;
+
+fn correct_answer(session: &RecruitmentSession) -> usize {
+    session.current().unwrap().correct_answer_index
+}
+
+#[test]
+fn pools_load_per_unit_type() {
+    let pool = QuestionPool::load_for("assets/recruitment", &UnitType::SpaceMarine).unwrap();
+    assert_eq!(pool.unit_type, UnitType::SpaceMarine);
+    let unit = pool.recruit("m", "Brother Tarkus");
+    assert_eq!(unit.health_points, 20);
+    assert_eq!(unit.action_points, 3);
+    assert_eq!(unit.abilities[0].id, "bolter_burst");
+    assert!(QuestionPool::load_for("assets/recruitment", &UnitType::Daemon).is_err());
+}
+
+#[test]
+fn challenges_draw_a_shuffled_subset() {
+    let pool = QuestionPool::load_for("assets/recruitment", &UnitType::Guardsman).unwrap();
+    let a = pool.challenge("a", &mut GameRng::new(1));
+    let b = pool.challenge("b", &mut GameRng::new(1));
+    assert_eq!(a.questions.len(), 3);
+    let questions = |c: &gero::models::RecruitmentChallenge| c.questions.iter().map(|q| q.question.clone()).collect::<Vec<_>>();
+    assert_eq!(questions(&a), questions(&b));
+}
+
+#[test]
+fn winning_adds_the_unit_to_the_roster() {
+    let pool = QuestionPool::load_for("assets/recruitment", &UnitType::Guardsman).unwrap();
+    let mut session = RecruitmentSession::new(pool, "Trooper Hask", &mut GameRng::new(3));
+    let mut campaign = Campaign::new();
+    assert_eq!(session.finish(&mut campaign), None);
+
+    assert_eq!(session.answer(correct_answer(&session)), Ok(true));
+    assert_eq!(session.outcome(), RecruitmentOutcome::InProgress);
+    assert_eq!(session.answer(correct_answer(&session)), Ok(true));
+    assert_eq!(session.outcome(), RecruitmentOutcome::Recruited);
+    assert!(session.answer(0).is_err());
+
+    assert_eq!(session.finish(&mut campaign).as_deref(), Some("guardsman_1"));
+    assert_eq!(session.finish(&mut campaign).as_deref(), Some("guardsman_2"));
+    assert_eq!(campaign.roster[0].name, "Trooper Hask");
+    assert_eq!(campaign.roster[0].current_stats.agility, 4);
+}
+
+#[test]
+fn too_many_wrong_answers_fail_the_challenge() {
+    let pool = QuestionPool::load_for("assets/recruitment", &UnitType::SpaceMarine).unwrap();
+    let mut session = RecruitmentSession::new(pool, "Aspirant", &mut GameRng::new(9));
+    let wrong = 1 - correct_answer(&session).min(1);
+    assert_eq!(session.answer(wrong), Ok(false));
+    assert_eq!(session.outcome(), RecruitmentOutcome::Failed);
+    assert!(session.current().is_none());
+}
+
+#[test]
+fn recruitment_screen_drives_the_flow() {
+    let loc = Localizer::new("en").unwrap();
+    let mut renderer = Renderer::new_headless(640, 480);
+    let mut flow = GameFlow::new(GameState::new(Vec::new()), UiManager::new(640, 480, vec![], vec![]));
+    let pool = QuestionPool::load_for("assets/recruitment", &UnitType::Guardsman).unwrap();
+    flow.start_recruitment(RecruitmentSession::new(pool, "Trooper Hask", &mut GameRng::new(5)));
+
+    flow.render(&mut renderer, &loc);
+    assert!(renderer.draw_log.iter().any(|c| c.sprite_id == "button:answer:0"));
+    assert!(renderer.text_log.iter().any(|t| t.text == "Score 0/2"));
+
+    for _ in 0..2 {
+        let answer = correct_answer(&flow.recruitment.as_ref().unwrap().session);
+        for _ in 0..answer {
+            flow.handle_action(GameAction::SelectDown);
+        }
+        flow.handle_action(GameAction::Activate);
+    }
+    assert_eq!(flow.campaign.roster.len(), 1);
+    flow.render(&mut renderer, &loc);
+    assert!(renderer.text_log.iter().any(|t| t.text == "Recruit accepted into the squad."));
+    flow.handle_action(GameAction::Activate);
+    assert!(flow.recruitment.is_none());
+    assert_eq!(flow.campaign.roster.len(), 1);
+}
@@ -0,0 +1,91 @@
+use std::collections::BTreeSet;
+
+use gero::combat::{CombatEncounter, CombatEvent};
+use gero::command::Command;
+use gero::grid::{GridMap, Region, RegionShape, TerrainType, TriggerRegion};
+use gero::models::{Faction, Position, Unit, UnitType};
+use gero::scenario::Scenario;
+use gero::state::GameState;
+
+fn pos(x: usize, y: usize) -> Position {
+    Position { x, y }
+}
+
+fn rect(name: &str, x: usize, y: usize, width: usize, height: usize) -> TriggerRegion {
+    TriggerRegion { name: name.into(), shape: RegionShape::Rect(Region { x, y, width, height }) }
+}
+
+fn encounter() -> CombatEncounter {
+    let mut map = GridMap::new(10, 3);
+    map.regions.push(rect("ford", 2, 0, 2, 3));
+    map.regions.push(TriggerRegion { name: "extraction".into(), shape: RegionShape::Cells(BTreeSet::from([pos(5, 0)])) });
+    let mut player = Unit::new("p", "P", UnitType::Guardsman, Faction::Imperial);
+    player.base_stats.agility = 12;
+    player.recalculate_stats();
+    let mut enemy = Unit::new("e", "E", UnitType::OrkBoy, Faction::Ork);
+    enemy.grid_position = pos(9, 2);
+    CombatEncounter::new(vec![player], vec![enemy], map, None)
+}
+
+fn crossings(enc: &CombatEncounter) -> Vec<(String, String, bool)> {
+    enc.events
+        .iter()
+        .filter_map(|e| match e {
+            CombatEvent::RegionEntered { unit_id, region } => Some((unit_id.clone(), region.clone(), true)),
+            CombatEvent::RegionExited { unit_id, region } => Some((unit_id.clone(), region.clone(), false)),
+            _ => None,
+        })
+        .collect()
+}
+
+#[test]
+fn regions_report_the_cells_they_cover() {
+    let enc = encounter();
+    let map = &enc.battlefield;
+    assert_eq!(map.regions_at(&pos(3, 2)).collect::<Vec<_>>(), ["ford"]);
+    assert_eq!(map.regions_at(&pos(5, 0)).collect::<Vec<_>>(), ["extraction"]);
+    assert!(map.regions_at(&pos(5, 1)).next().is_none());
+    assert!(map.region("extraction").is_some_and(|r| r.contains(&pos(5, 0))));
+
+    let path = [pos(1, 0), pos(2, 0), pos(3, 0), pos(4, 0), pos(5, 0)];
+    assert_eq!(map.region_crossings(&path), [("ford", true), ("ford", false), ("extraction", true)]);
+}
+
+#[test]
+fn moves_raise_events_for_regions_crossed() {
+    let mut enc = encounter();
+    enc.execute(Command::Move { unit_id: "p".into(), destination: pos(5, 0) }).unwrap();
+    let expected = [("p", "ford", true), ("p", "ford", false), ("p", "extraction", true)];
+    assert_eq!(crossings(&enc), expected.map(|(u, r, e)| (u.to_string(), r.to_string(), e)));
+
+    enc.events.clear();
+    enc.execute(Command::Move { unit_id: "p".into(), destination: pos(6, 0) }).unwrap();
+    assert_eq!(crossings(&enc), [("p".to_string(), "extraction".to_string(), false)]);
+
+    // A refused move raises nothing.
+    enc.events.clear();
+    enc.battlefield.set_terrain(&pos(2, 1), TerrainType::Blocked);
+    assert!(enc.execute(Command::Move { unit_id: "p".into(), destination: pos(2, 1) }).is_err());
+    assert!(crossings(&enc).is_empty());
+}
+
+#[test]
+fn maps_with_cell_regions_reload_with_the_same_checksum() {
+    let mut enc = encounter();
+    let cells = (0..8).map(|x| pos(x, 2)).collect();
+    enc.battlefield.regions.push(TriggerRegion { name: "trench".into(), shape: RegionShape::Cells(cells) });
+    let state = GameState::from_encounter(&enc);
+    let reloaded = GameState::load_from_str(&state.save_to_string());
+    assert_eq!(reloaded.checksum(), state.checksum());
+}
+
+#[test]
+fn scenarios_place_regions_on_the_map() {
+    let scenario = Scenario::load("assets/scenarios/night_raid.json").unwrap();
+    let map = scenario.map();
+    assert!(map.region("extraction").is_some_and(|r| r.contains(&pos(11, 7))));
+
+    let bad = r#"{ "name": "x", "width": 4, "height": 4,
+        "regions": [{ "name": "zone", "shape": { "Rect": { "x": 3, "y": 0, "width": 2, "height": 1 } } }] }"#;
+    assert!(Scenario::from_json(bad).is_err());
+}
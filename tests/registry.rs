@@ -0,0 +1,51 @@
+use gero::models::{Faction, Unit, UnitType};
+use gero::registry::{Side, UnitRegistry};
+
+fn registry() -> UnitRegistry {
+    UnitRegistry::with_sides(
+        vec![Unit::new("g", "G", UnitType::Guardsman, Faction::Imperial)],
+        vec![
+            Unit::new("o1", "O", UnitType::OrkBoy, Faction::Ork),
+            Unit::new("o2", "O", UnitType::OrkBoy, Faction::Ork),
+        ],
+    )
+}
+
+#[test]
+fn units_are_found_by_handle_and_name() {
+    let mut units = registry();
+    let ork = units.id("o2").unwrap();
+    assert_eq!(units[ork].id, "o2");
+    assert_eq!(units.side(ork), Some(Side::Enemy));
+    assert!(units.id("missing").is_none());
+
+    units.by_name_mut("g").unwrap().health_points = 3;
+    assert_eq!(units.by_name("g").unwrap().health_points, 3);
+    assert_eq!(units.players().count(), 1);
+    assert_eq!(units.enemies().map(|u| u.id.as_str()).collect::<Vec<_>>(), ["o1", "o2"]);
+}
+
+#[test]
+fn pair_mut_borrows_two_distinct_units() {
+    let mut units = registry();
+    let (g, o) = (units.id("g").unwrap(), units.id("o1").unwrap());
+    let (defender, attacker) = units.pair_mut(o, g).unwrap();
+    assert_eq!((defender.id.as_str(), attacker.id.as_str()), ("o1", "g"));
+    defender.health_points -= attacker.current_stats.max_health;
+    assert_eq!(units[o].health_points, 0);
+    assert!(units.pair_mut(g, g).is_none());
+
+    let (user, targets) = units.split_mut(g, &[units.id("o2").unwrap(), g, o]).unwrap();
+    assert_eq!(user.id, "g");
+    assert_eq!(targets.iter().map(|t| t.id.as_str()).collect::<Vec<_>>(), ["o2", "o1"]);
+}
+
+#[test]
+fn sides_and_names_survive_serialization() {
+    let units = registry();
+    let loaded: UnitRegistry = serde_json::from_str(&serde_json::to_string(&units).unwrap()).unwrap();
+    let ork = loaded.id("o1").unwrap();
+    assert_eq!(ork, units.id("o1").unwrap());
+    assert_eq!(loaded.side(ork), Some(Side::Enemy));
+    assert_eq!(loaded.len(), 3);
+}
@@ -0,0 +1,87 @@
+use gero::campaign::Campaign;
+use gero::content::ContentDatabase;
+use gero::frontend::Renderer;
+use gero::input::GameAction;
+use gero::localization::Localizer;
+use gero::models::{ArmorTier, Faction, Unit, UnitType, WeaponTier};
+use gero::research::TierUnlock;
+use gero::ui::requisition::RequisitionScreen;
+
+fn campaign_with_trooper() -> Campaign {
+    let mut campaign = Campaign::new();
+    let mut unit = Unit::new("trooper", "Trooper", UnitType::Guardsman, Faction::Imperial);
+    unit.base_stats.toughness = 3;
+    unit.recalculate_stats();
+    campaign.roster.push(unit);
+    campaign
+}
+
+#[test]
+fn stock_unlocks_as_missions_complete() {
+    let db = ContentDatabase::load("assets/content/items.json").unwrap();
+    assert_eq!(db.available(0), vec!["flak_armor", "frag_grenade", "lasgun", "stimpack"]);
+    assert_eq!(db.available(1).len(), 6);
+    assert_eq!(db.available(3).len(), 8);
+}
+
+#[test]
+fn purchases_spend_currency_and_respect_tiers() {
+    let db = ContentDatabase::load("assets/content/items.json").unwrap();
+    let mut campaign = Campaign::new();
+    assert_eq!(campaign.purchase(&db, "lasgun"), Err("insufficient requisition"));
+    campaign.complete_mission(100);
+    assert_eq!(campaign.purchase(&db, "plasma_gun"), Err("item not yet available"));
+    assert_eq!(campaign.purchase(&db, "missing"), Err("unknown item"));
+    assert_eq!(campaign.purchase(&db, "bolter"), Err("research required"));
+    campaign.research.unlocked.push(TierUnlock::Weapon(WeaponTier::Advanced));
+    campaign.purchase(&db, "bolter").unwrap();
+    assert_eq!(campaign.currency, 40);
+    assert_eq!(campaign.inventory, vec!["bolter".to_string()]);
+}
+
+#[test]
+fn inventory_transfers_onto_roster_units() {
+    let db = ContentDatabase::load("assets/content/items.json").unwrap();
+    let mut campaign = campaign_with_trooper();
+    campaign.currency = 200;
+    campaign.research.unlocked.push(TierUnlock::Armor(ArmorTier::Carapace));
+    for id in ["flak_armor", "carapace_armor", "frag_grenade", "frag_grenade", "stimpack"] {
+        campaign.missions_completed = 1;
+        campaign.purchase(&db, id).unwrap();
+    }
+
+    campaign.equip_from_inventory(&db, "trooper", "flak_armor").unwrap();
+    assert_eq!(campaign.roster[0].current_stats.toughness, 4);
+    campaign.equip_from_inventory(&db, "trooper", "carapace_armor").unwrap();
+    assert_eq!(campaign.roster[0].current_stats.toughness, 5);
+    assert!(campaign.inventory.contains(&"flak_armor".to_string()));
+
+    campaign.equip_from_inventory(&db, "trooper", "frag_grenade").unwrap();
+    campaign.equip_from_inventory(&db, "trooper", "frag_grenade").unwrap();
+    assert_eq!(campaign.equip_from_inventory(&db, "trooper", "stimpack"), Err("no free accessory slot"));
+    assert_eq!(campaign.equip_from_inventory(&db, "trooper", "lasgun"), Err("item not in inventory"));
+    assert_eq!(campaign.inventory, vec!["stimpack".to_string(), "flak_armor".to_string()]);
+}
+
+#[test]
+fn requisition_screen_buys_selected_item() {
+    let db = ContentDatabase::load("assets/content/items.json").unwrap();
+    let loc = Localizer::new("en").unwrap();
+    let mut renderer = Renderer::new_headless(640, 480);
+    let mut campaign = Campaign::new();
+    campaign.currency = 15;
+    let mut screen = RequisitionScreen::new();
+
+    screen.handle_input(GameAction::SelectDown, &db, &mut campaign);
+    screen.handle_input(GameAction::SelectDown, &db, &mut campaign);
+    screen.handle_input(GameAction::Activate, &db, &mut campaign);
+    assert_eq!(screen.last_error, Some("insufficient requisition"));
+    screen.render(&mut renderer, &loc, &db, &campaign);
+    assert!(renderer.text_log.iter().any(|t| t.text == "Not enough requisition."));
+    assert!(renderer.text_log.iter().any(|t| t.text == "Lasgun - 20"));
+
+    screen.handle_input(GameAction::SelectUp, &db, &mut campaign);
+    screen.handle_input(GameAction::Activate, &db, &mut campaign);
+    assert_eq!(campaign.inventory, vec!["frag_grenade".to_string()]);
+    assert_eq!(campaign.currency, 5);
+}
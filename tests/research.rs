@@ -0,0 +1,92 @@
+use gero::campaign::Campaign;
+use gero::content::ContentDatabase;
+use gero::frontend::Renderer;
+use gero::input::GameAction;
+use gero::localization::Localizer;
+use gero::models::{ArmorTier, WeaponTier};
+use gero::research::{ProjectStatus, ResearchTree, TierUnlock};
+use gero::ui::research::ResearchScreen;
+
+fn tree() -> ResearchTree {
+    ResearchTree::load("assets/content/research.json").unwrap()
+}
+
+#[test]
+fn projects_follow_prerequisites_and_take_missions() {
+    let tree = tree();
+    let mut campaign = Campaign::new();
+    campaign.currency = 200;
+    assert_eq!(campaign.start_research(&tree, "master_crafting"), Err("prerequisites not met"));
+    assert_eq!(campaign.start_research(&tree, "warp_drive"), Err("unknown project"));
+    campaign.start_research(&tree, "carapace_plating").unwrap();
+    assert_eq!(campaign.currency, 150);
+    assert_eq!(campaign.start_research(&tree, "advanced_weapons"), Err("research in progress"));
+    assert_eq!(campaign.project_status(tree.get("carapace_plating").unwrap()), ProjectStatus::Active(2));
+
+    assert_eq!(campaign.advance_research(), None);
+    let finished = campaign.advance_research().unwrap();
+    assert_eq!(finished.project, "carapace_plating");
+    assert_eq!(campaign.research.unlocked, [TierUnlock::Armor(ArmorTier::Carapace)]);
+    assert_eq!(campaign.start_research(&tree, "carapace_plating"), Err("already researched"));
+    assert_eq!(campaign.project_status(tree.get("power_armor").unwrap()), ProjectStatus::Locked);
+    assert_eq!(campaign.project_status(tree.get("advanced_weapons").unwrap()), ProjectStatus::Available);
+
+    // Progress is kept in the campaign save, and missions move it on.
+    campaign.start_research(&tree, "advanced_weapons").unwrap();
+    let mut saved: Campaign = serde_json::from_str(&serde_json::to_string(&campaign).unwrap()).unwrap();
+    assert_eq!(saved.research, campaign.research);
+    assert_eq!(saved.tend_base().unwrap().name, "research.advanced_weapons");
+    assert!(saved.research.completed.contains("advanced_weapons"));
+}
+
+#[test]
+fn the_shop_only_sells_researched_tiers() {
+    let db = ContentDatabase::load("assets/content/items.json").unwrap();
+    let tree = tree();
+    let mut campaign = Campaign::new();
+    campaign.missions_completed = 10;
+    campaign.currency = 1000;
+    campaign.purchase(&db, "lasgun").unwrap();
+    campaign.purchase(&db, "flak_armor").unwrap();
+    campaign.purchase(&db, "frag_grenade").unwrap();
+    assert_eq!(campaign.purchase(&db, "bolter"), Err("research required"));
+    assert_eq!(campaign.purchase(&db, "carapace_armor"), Err("research required"));
+    campaign.start_research(&tree, "advanced_weapons").unwrap();
+    campaign.advance_research();
+    assert!(campaign.research.unlocked.contains(&TierUnlock::Weapon(WeaponTier::Advanced)));
+    campaign.purchase(&db, "bolter").unwrap();
+}
+
+#[test]
+fn malformed_trees_are_rejected() {
+    let data = std::fs::read_to_string("assets/content/research.json").unwrap();
+    let dir = std::env::temp_dir().join("gero_research_test");
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("research.json");
+    std::fs::write(&path, data.replace(r#"["advanced_weapons"]"#, r#"["power_armor"]"#)).unwrap();
+    let err = ResearchTree::load(&path).unwrap_err();
+    assert!(err.to_string().contains("'master_crafting' requires 'power_armor'"));
+    std::fs::write(&path, data.replace(r#""missions": 1"#, r#""missions": 0"#)).unwrap();
+    assert!(ResearchTree::load(&path).unwrap_err().to_string().contains("takes no time"));
+}
+
+#[test]
+fn the_research_screen_shows_the_tree() {
+    let tree = tree();
+    let loc = Localizer::new("en").unwrap();
+    let mut campaign = Campaign::new();
+    campaign.currency = 45;
+    let mut screen = ResearchScreen::new();
+    screen.handle_input(GameAction::Activate, &tree, &mut campaign);
+    assert_eq!(campaign.research.active.as_ref().unwrap().project, "advanced_weapons");
+    screen.handle_input(GameAction::SelectDown, &tree, &mut campaign);
+    screen.handle_input(GameAction::Activate, &tree, &mut campaign);
+    assert_eq!(screen.last_error, Some("research in progress"));
+
+    let mut renderer = Renderer::new_headless(1280, 720);
+    screen.render(&mut renderer, &loc, &tree, &campaign);
+    let line = |text: &str| renderer.text_log.iter().find(|t| t.text == text).map(|t| t.position.0);
+    assert_eq!(line("Advanced Weapons - 1 mission left"), Some(16));
+    assert_eq!(line("Master Crafting - requires Advanced Weapons"), Some(40));
+    assert_eq!(line("Power Armour - requires Carapace Plating, Master Crafting"), Some(64));
+}
@@ -0,0 +1,89 @@
+use gero::frontend::effects::UnitVisuals;
+use gero::frontend::tiles::OverlayKind;
+use gero::frontend::{DrawCall, DrawLayer, Renderer, SIDE_MARKER};
+use gero::grid::GridMap;
+use gero::models::{Faction, Position, Unit, UnitType};
+use gero::state::GameState;
+
+fn renderer() -> Renderer<'static> {
+    let mut renderer = Renderer::new_headless(320, 320);
+    for sprite in ["tile_normal", SIDE_MARKER] {
+        renderer.load_sprite_from_bytes(sprite, vec![vec![0]]);
+    }
+    renderer.load_sprite_from_bytes("guard", vec![vec![0], vec![1]]);
+    renderer
+}
+
+fn squad() -> GameState {
+    let units = (0..3)
+        .map(|i| {
+            let mut unit = Unit::new(&format!("u{i}"), "U", UnitType::Guardsman, Faction::Imperial);
+            unit.sprite_id = "guard".into();
+            unit.grid_position = Position { x: i, y: i };
+            unit
+        })
+        .collect();
+    let mut state = GameState::new(units);
+    state.map = GridMap::new(5, 5);
+    state
+}
+
+/// What rendering `state` from scratch draws.
+fn from_scratch(state: &GameState) -> Vec<DrawCall> {
+    let mut renderer = renderer();
+    renderer.render_state(state);
+    renderer.draw_log
+}
+
+#[test]
+fn only_units_that_changed_are_re_recorded() {
+    let mut renderer = renderer();
+    let mut state = squad();
+    renderer.render_state_retained(&state);
+    assert_eq!(renderer.changed_instances(), None);
+    assert_eq!(renderer.draw_log, from_scratch(&state));
+
+    renderer.render_state_retained(&state);
+    assert_eq!(renderer.changed_instances(), Some(&[][..]));
+
+    // Stepping an animation and moving along a row touch only that unit.
+    state.units.by_name_mut("u1").unwrap().animation_state.frame_index = 1;
+    state.units.by_name_mut("u2").unwrap().grid_position = Position { x: 4, y: 2 };
+    renderer.render_state_retained(&state);
+    let changed = renderer.changed_instances().unwrap().to_vec();
+    assert_eq!(changed.len(), 4);
+    assert!(changed.iter().all(|&i| matches!(renderer.draw_log[i].position, (1, 1) | (4, 2))));
+    assert_eq!(renderer.draw_log, from_scratch(&state));
+
+    renderer.unit_visuals.insert("u0".into(), UnitVisuals { flash_remaining: 0.1, shake_remaining: 0.0 });
+    renderer.render_state_retained(&state);
+    let changed = renderer.changed_instances().unwrap();
+    assert!(changed.iter().all(|&i| renderer.draw_log[i].position == (0, 0)));
+    assert_eq!(renderer.draw_log.iter().find(|c| c.sprite_id == "guard").unwrap().tint, Some([1.0; 4]));
+}
+
+#[test]
+fn moving_rows_or_changing_the_scene_records_the_world_afresh() {
+    let mut renderer = renderer();
+    let mut state = squad();
+    renderer.render_state_retained(&state);
+
+    state.units.by_name_mut("u0").unwrap().grid_position = Position { x: 0, y: 3 };
+    renderer.render_state_retained(&state);
+    assert_eq!(renderer.changed_instances(), None);
+    assert_eq!(renderer.draw_log, from_scratch(&state));
+
+    renderer.set_fog(&state.map, &[Position { x: 1, y: 1 }].into_iter().collect());
+    renderer.render_state_retained(&state);
+    assert_eq!(renderer.changed_instances(), None);
+    assert!(!renderer.draw_log.iter().any(|c| c.sprite_id == "guard" && c.position != (1, 1)));
+
+    // Draws submitted after the world are dropped, not kept, next frame.
+    renderer.submit(DrawCall::new("cursor", (0, 0), DrawLayer::Ui));
+    renderer.render_state_retained(&state);
+    assert_eq!(renderer.changed_instances(), Some(&[][..]));
+    assert!(!renderer.draw_log.iter().any(|c| c.sprite_id == "cursor"));
+    renderer.clear_overlay(OverlayKind::Fog);
+    renderer.render_state_retained(&state);
+    assert_eq!(renderer.draw_log, from_scratch(&state));
+}
@@ -0,0 +1,47 @@
+use gero::flow::{GameFlow, ANIMATION_FRAME_DURATION};
+use gero::input::GameAction;
+use gero::models::{Unit, UnitType, Faction};
+use gero::runner::{FixedTimestep, RunConfig};
+use gero::state::GameState;
+use gero::ui::{UiManager, UiEvent};
+
+#[test]
+fn fixed_timestep_accumulates_partial_frames() {
+    let mut timestep = FixedTimestep::new(10);
+    assert_eq!(timestep.advance(0.05), 0);
+    assert_eq!(timestep.advance(0.06), 1);
+    assert_eq!(timestep.advance(0.25), 2);
+}
+
+#[test]
+fn fixed_timestep_caps_catch_up_after_a_stall() {
+    let mut timestep = FixedTimestep::new(60);
+    assert_eq!(timestep.advance(10.0), timestep.max_steps_per_frame);
+    assert!(timestep.advance(0.0) <= 1);
+}
+
+#[test]
+fn flow_update_advances_animation_frames() {
+    let unit = Unit::new("u", "U", UnitType::Guardsman, Faction::Imperial);
+    let mut flow = GameFlow::new(GameState::new(vec![unit]), UiManager::new(100, 100, vec![], vec![]));
+    flow.update(ANIMATION_FRAME_DURATION * 2.5);
+    assert_eq!(flow.state.units.by_name("u").unwrap().animation_state.frame_index, 2);
+}
+
+#[test]
+fn flow_routes_actions_to_ui() {
+    let config = RunConfig::default();
+    let ui = UiManager::new(config.width, config.height, vec!["fire".into()], vec![]);
+    let mut flow = GameFlow::new(config.initial_state, ui);
+    assert_eq!(flow.handle_action(GameAction::Activate), Some(UiEvent::AbilityPressed("fire".into())));
+}
+
+#[test]
+fn frame_limiter_waits_out_the_rest_of_a_capped_frame() {
+    use gero::runner::FrameLimiter;
+
+    let capped = FrameLimiter::new(Some(30));
+    assert!((capped.delay(0.01) - (1.0 / 30.0 - 0.01)).abs() < 1e-6);
+    assert_eq!(capped.delay(0.05), 0.0);
+    assert_eq!(FrameLimiter::new(None).delay(0.0), 0.0);
+}
@@ -0,0 +1,84 @@
+use gero::campaign::Campaign;
+use gero::combat::CombatEncounter;
+use gero::doctrine::Doctrine;
+use gero::grid::{GridMap, TerrainType};
+use gero::models::{Faction, Position, Trait, Unit, UnitType};
+use gero::saves::{self, SaveFormat};
+use gero::state::GameState;
+
+fn campaign() -> Campaign {
+    let mut campaign = Campaign::new();
+    for i in 0..20 {
+        let mut unit = Unit::new(&format!("u{i}"), "Trooper", UnitType::Guardsman, Faction::Imperial);
+        unit.traits.push(Trait::SteadyHands);
+        unit.experience = i * 10;
+        campaign.roster.push(unit);
+    }
+    campaign.set_flag("met_commissar");
+    campaign.adopt_doctrine(Doctrine::HoldTheLine);
+    campaign.currency = 120;
+    campaign
+}
+
+#[test]
+fn binary_saves_round_trip_and_are_smaller() {
+    let campaign = campaign();
+    let json = saves::encode(&campaign, SaveFormat::Json).unwrap();
+    let binary = saves::encode(&campaign, SaveFormat::Binary).unwrap();
+    assert!(binary.len() < json.len());
+    assert_eq!(SaveFormat::detect(&binary).unwrap(), SaveFormat::Binary);
+
+    let loaded: Campaign = saves::decode(&binary).unwrap();
+    assert_eq!(loaded.roster.len(), 20);
+    assert_eq!(loaded.roster[7].experience, 70);
+    assert_eq!(loaded.doctrines, vec![Doctrine::HoldTheLine]);
+    assert!(loaded.has_flag("met_commissar"));
+}
+
+#[test]
+fn format_is_detected_on_load() {
+    let mut map = GridMap::new(4, 4);
+    map.set_terrain(&Position { x: 1, y: 2 }, TerrainType::Hazardous);
+    let enc = CombatEncounter::new(
+        vec![Unit::new("g", "G", UnitType::Guardsman, Faction::Imperial)],
+        vec![Unit::new("o", "O", UnitType::OrkBoy, Faction::Ork)],
+        map,
+        None,
+    );
+    let state = GameState::from_encounter(&enc);
+    let json = state.save_to_string();
+
+    let from_json = GameState::load_from_bytes(json.as_bytes()).unwrap();
+    let from_binary = GameState::load_from_bytes(&state.save_to_bytes(SaveFormat::Binary).unwrap()).unwrap();
+    assert_eq!(from_json.checksum(), state.checksum());
+    assert_eq!(from_binary.checksum(), state.checksum());
+
+    assert!(GameState::load_from_bytes(b"GEROx").is_err());
+    assert!(GameState::load_from_bytes(b"not a save").is_err());
+}
+
+#[test]
+fn saves_go_through_files() {
+    let path = std::env::temp_dir().join(format!("gero_save_{}.bin", std::process::id()));
+    saves::save(&campaign(), &path, SaveFormat::Binary).unwrap();
+    let loaded: Campaign = saves::load(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(loaded.currency, 120);
+}
+
+#[cfg(feature = "compression")]
+#[test]
+fn compressed_saves_are_smallest() {
+    let campaign = campaign();
+    let binary = saves::encode(&campaign, SaveFormat::Binary).unwrap();
+    let compressed = saves::encode(&campaign, SaveFormat::Compressed).unwrap();
+    assert!(compressed.len() < binary.len());
+    let loaded: Campaign = saves::decode(&compressed).unwrap();
+    assert_eq!(loaded.roster.len(), 20);
+}
+
+#[cfg(not(feature = "compression"))]
+#[test]
+fn compression_needs_the_feature() {
+    assert!(saves::encode(&campaign(), SaveFormat::Compressed).is_err());
+}
@@ -0,0 +1,157 @@
+#![cfg(feature = "scripting")]
+
+use gero::actions::PresentationStep;
+use gero::combat::{CombatEncounter, CombatEvent, EnvironmentalEffect};
+use gero::content::ContentDatabase;
+use gero::flow::GameFlow;
+use gero::frontend::Renderer;
+use gero::grid::{GridMap, TerrainType};
+use gero::interrupts::InterruptEvent;
+use gero::models::{Faction, Position, Unit, UnitType};
+use gero::scripting::MissionScript;
+use gero::state::GameState;
+use gero::ui::UiManager;
+
+fn encounter() -> CombatEncounter {
+    let sergeant = Unit::new("sergeant", "Sergeant", UnitType::Guardsman, Faction::Imperial);
+    let mut ork = Unit::new("ork", "Ork", UnitType::OrkBoy, Faction::Ork);
+    ork.grid_position = Position { x: 5, y: 5 };
+    let mut enc = CombatEncounter::new(vec![sergeant], vec![ork], GridMap::new(6, 6), None);
+    enc.unit_templates = ContentDatabase::load("assets/content/items.json").unwrap().units;
+    enc
+}
+
+#[test]
+fn ambush_script_spawns_reinforcements_and_dialog() {
+    let mut enc = encounter();
+    let mut script = MissionScript::load("assets/scripts/ambush.rhai").unwrap();
+    enc.unit_by_id_mut("sergeant").unwrap().health_points = 6;
+    let event = CombatEvent::UnitDamaged { unit_id: "sergeant".into(), amount: 4, critical: false };
+    script.handle_event(&mut enc, &event).unwrap();
+
+    let ambusher = enc.unit_by_id("ork_ambusher").unwrap();
+    assert_eq!(ambusher.faction, Faction::Ork);
+    assert_eq!(ambusher.grid_position, Position { x: 4, y: 0 });
+    assert!(enc.turn_order.initiative.contains(&"ork_ambusher".to_string()));
+    assert!(matches!(enc.environmental_effects[0], EnvironmentalEffect::SmokeCloud { radius: 1, .. }));
+    assert_eq!(script.dialogs, vec![("Sergeant".to_string(), "Ambush! Get to cover!".to_string())]);
+}
+
+#[test]
+fn hooks_see_the_current_round() {
+    let mut enc = encounter();
+    let mut script = MissionScript::load("assets/scripts/ambush.rhai").unwrap();
+    enc.start_turn();
+    script.turn_started(&mut enc).unwrap();
+    assert!(enc.environmental_effects.is_empty());
+    enc.turn_order.round_number = 3;
+    script.turn_started(&mut enc).unwrap();
+    assert_eq!(enc.environmental_effects.len(), 1);
+}
+
+#[test]
+fn damage_requests_emit_events() {
+    let mut enc = encounter();
+    let mut script = MissionScript::from_source(r#"fn on_turn_start(id) { damage("ork", 3); }"#).unwrap();
+    enc.start_turn();
+    script.turn_started(&mut enc).unwrap();
+    assert_eq!(enc.unit_by_id("ork").unwrap().health_points, 7);
+    assert_eq!(enc.drain_events(), vec![CombatEvent::UnitDamaged { unit_id: "ork".into(), amount: 3, critical: false }]);
+    assert_eq!(enc.stats.unit("ork").damage_taken, 3);
+}
+
+#[test]
+fn damage_too_large_for_a_unit_is_refused() {
+    let mut enc = encounter();
+    let mut script = MissionScript::from_source(r#"fn on_turn_start(id) { damage("ork", 5_000_000_000); }"#).unwrap();
+    enc.start_turn();
+    assert!(script.turn_started(&mut enc).is_err());
+    assert_eq!(enc.unit_by_id("ork").unwrap().health_points, 10);
+}
+
+#[test]
+fn spawning_an_unknown_unit_fails_the_hook() {
+    let mut enc = encounter();
+    let mut script = MissionScript::from_source(r#"fn on_turn_start(id) { spawn_unit("titan", 3, 3, true); }"#).unwrap();
+    enc.start_turn();
+    assert!(script.turn_started(&mut enc).is_err());
+    assert!(enc.unit_by_id("titan").is_none());
+}
+
+#[test]
+fn the_flow_runs_the_battle_script() {
+    let source = r#"
+        fn on_turn_start(unit_id) { damage("ork", 3); }
+        fn on_unit_damaged(unit_id, amount) { show_dialog(unit_id, "hit"); }
+    "#;
+    let mut flow = GameFlow::new(GameState::new(Vec::new()), UiManager::new(1280, 720, Vec::new(), Vec::new()));
+    flow.script = Some(MissionScript::from_source(source).unwrap());
+    flow.start_battle(encounter());
+    assert_eq!(flow.battle.as_ref().unwrap().unit_by_id("ork").unwrap().health_points, 7);
+
+    flow.present_battle(0.1, &mut Renderer::new_headless(1280, 720));
+    assert_eq!(flow.script.unwrap().dialogs, vec![("ork".to_string(), "hit".to_string())]);
+}
+
+#[test]
+fn runaway_scripts_are_stopped() {
+    let mut enc = encounter();
+    let mut script = MissionScript::from_source("fn on_turn_start(id) { loop { } }").unwrap();
+    enc.start_turn();
+    assert!(script.turn_started(&mut enc).is_err());
+    assert!(MissionScript::from_source("fn broken( {").is_err());
+}
+
+#[test]
+fn scripts_can_interrupt_the_turn() {
+    let mut enc = encounter();
+    let source = r#"
+        fn on_turn_start(unit_id) {
+            pan_camera(4, 0);
+            reinforce("ork_ambusher", 4, 0, true);
+            pause_dialog("speaker.commissar", "dialogue.night_raid.ambush");
+        }
+    "#;
+    let mut script = MissionScript::from_source(source).unwrap();
+    enc.turn_order.current_unit_id = Some("sergeant".into());
+    script.turn_started(&mut enc).unwrap();
+    // The ambusher is on the field before the reveal is shown.
+    assert_eq!(enc.unit_by_id("ork_ambusher").unwrap().grid_position, Position { x: 4, y: 0 });
+
+    let started = enc.update_actions(10.0);
+    assert_eq!(started.len(), 3);
+    assert!(matches!(started[2], PresentationStep::Interrupt(InterruptEvent::Dialogue { .. })));
+    assert!(enc.resume_actions());
+    assert!(!enc.actions.is_busy());
+}
+
+#[test]
+fn scripts_react_to_units_entering_regions() {
+    let mut enc = encounter();
+    let source = r#"
+        fn on_region_entered(unit_id, region) {
+            if region == "extraction" { show_dialog(unit_id, "extracted"); }
+        }
+    "#;
+    let mut script = MissionScript::from_source(source).unwrap();
+    let event = CombatEvent::RegionEntered { unit_id: "sergeant".into(), region: "extraction".into() };
+    script.handle_event(&mut enc, &event).unwrap();
+    assert_eq!(script.dialogs, vec![("sergeant".to_string(), "extracted".to_string())]);
+}
+
+#[test]
+fn scripts_can_reshape_the_battlefield() {
+    let mut enc = encounter();
+    let source = r#"
+        fn on_turn_start(unit_id) {
+            raise_wall(2, 2, 1);
+            ice_over(3, 3, 2);
+            demolish(2, 2);
+        }
+    "#;
+    let mut script = MissionScript::from_source(source).unwrap();
+    enc.start_turn();
+    script.turn_started(&mut enc).unwrap();
+    assert_eq!(*enc.battlefield.terrain_at(&Position { x: 2, y: 2 }), TerrainType::Difficult);
+    assert_eq!(*enc.battlefield.terrain_at(&Position { x: 3, y: 3 }), TerrainType::Hazardous);
+}
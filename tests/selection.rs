@@ -0,0 +1,177 @@
+use gero::command::Command;
+use gero::damage::DamageType;
+use gero::flow::GameFlow;
+use gero::frontend::Renderer;
+use gero::localization::Localizer;
+use gero::models::{Ability, AbilityEffect, AbilityType, AnimationType, Faction, Position, Unit, UnitType};
+use gero::registry::UnitRegistry;
+use gero::selection::SelectionChanged;
+use gero::state::GameState;
+use gero::ui::UiManager;
+
+fn unit(id: &str, faction: Faction, x: usize, y: usize) -> Unit {
+    let mut unit = Unit::new(id, id, UnitType::Guardsman, faction);
+    unit.grid_position = Position { x, y };
+    unit
+}
+
+/// Three guardsmen below the top bar and an ork further down.
+fn state() -> GameState {
+    let players = vec![unit("a", Faction::Imperial, 1, 2), unit("b", Faction::Imperial, 2, 2), unit("c", Faction::Imperial, 3, 3)];
+    let mut state = GameState::new(Vec::new());
+    state.units = UnitRegistry::with_sides(players, vec![unit("ork", Faction::Ork, 5, 5)]);
+    state
+}
+
+fn flagged(state: &GameState) -> Vec<&str> {
+    state.units.iter().filter(|u| u.is_selected).map(|u| u.id.as_str()).collect()
+}
+
+fn heal(id: &str) -> Ability {
+    Ability {
+        id: id.into(),
+        name: id.into(),
+        ability_type: AbilityType::Healing,
+        description: String::new(),
+        action_point_cost: 1,
+        cooldown: 0,
+        current_cooldown: 0,
+        range: 1,
+        area_of_effect: None,
+        effect: AbilityEffect {
+            damage: None,
+            healing: Some(2),
+            buff: None,
+            debuff: None,
+            status_applied: None,
+            duration: None,
+            creates_hazard: None,
+            alters_terrain: None,
+            forced_move: None,
+            damage_type: DamageType::Kinetic,
+        },
+        animation: AnimationType::AbilityCast,
+        sound_effect_key: String::new(),
+        rank: 1,
+        ranks: Vec::new(),
+        channel: None,
+    }
+}
+
+fn cell(x: usize, y: usize) -> Option<Position> {
+    Some(Position { x, y })
+}
+
+#[test]
+fn clicks_select_one_unit_and_shift_clicks_build_a_squad() {
+    let mut state = state();
+    assert_eq!(state.select_at(cell(5, 5), false), Some(SelectionChanged { selected: vec!["ork".into()] }));
+    assert_eq!(state.select_at(cell(5, 5), false), None);
+
+    // Shift-clicking a player unit drops the enemy from the selection.
+    state.select_at(cell(2, 2), true);
+    state.select_at(cell(1, 2), true);
+    assert_eq!(state.selection.ids(), ["b", "a"]);
+    assert_eq!(flagged(&state), ["a", "b"]);
+    assert_eq!(state.select_at(cell(5, 5), true), None);
+    assert_eq!(state.select_at(cell(0, 0), true), None);
+
+    state.select_at(cell(2, 2), true);
+    assert_eq!(state.selection.primary(), Some("a"));
+    assert_eq!(flagged(&state), ["a"]);
+
+    state.select_at(cell(0, 0), false);
+    assert!(state.selection.is_empty() && flagged(&state).is_empty());
+}
+
+#[test]
+fn tab_cycles_living_player_units() {
+    let mut state = state();
+    state.units.by_name_mut("b").unwrap().health_points = 0;
+    state.select_at(cell(5, 5), false);
+    assert_eq!(state.cycle_selection().unwrap().selected, ["a"]);
+    assert_eq!(state.cycle_selection().unwrap().selected, ["c"]);
+    assert_eq!(state.cycle_selection().unwrap().selected, ["a"]);
+    assert_eq!(flagged(&state), ["a"]);
+    assert_eq!(GameState::new(Vec::new()).cycle_selection(), None);
+}
+
+#[test]
+fn set_selection_keeps_flags_in_step() {
+    let mut state = state();
+    state.units.by_name_mut("ork").unwrap().is_selected = true;
+    state.set_selection(vec!["c".into(), "ghost".into(), "c".into()]);
+    assert_eq!(state.selection.ids(), ["c"]);
+    assert_eq!(flagged(&state), ["c"]);
+
+    let loaded = GameState::load_from_str(&state.save_to_string());
+    assert_eq!(loaded.selection, state.selection);
+}
+
+#[test]
+fn selection_routes_formation_moves_and_abilities() {
+    let mut state = state();
+    assert!(state.formation_moves(&Position { x: 4, y: 4 }).is_empty());
+    state.set_selection(vec!["b".into(), "a".into(), "c".into()]);
+    let moves = |at: Position| -> Vec<(String, Position)> {
+        state
+            .formation_moves(&at)
+            .into_iter()
+            .map(|c| match c {
+                Command::Move { unit_id, destination } => (unit_id, destination),
+                other => panic!("not a move: {other:?}"),
+            })
+            .collect()
+    };
+    assert_eq!(moves(Position { x: 4, y: 4 }), [
+        ("b".into(), Position { x: 4, y: 4 }),
+        ("a".into(), Position { x: 3, y: 4 }),
+        ("c".into(), Position { x: 5, y: 5 })
+    ]);
+    // Spots off the 10x10 map are dropped.
+    assert_eq!(moves(Position { x: 9, y: 0 }), [("b".into(), Position { x: 9, y: 0 }), ("a".into(), Position { x: 8, y: 0 })]);
+    assert_eq!(moves(Position { x: 0, y: 0 }).len(), 2);
+
+    state.units.by_name_mut("b").unwrap().abilities.push(heal("mend"));
+    assert_eq!(
+        state.ability_command("mend", vec!["a".into()]),
+        Some(Command::UseAbility { user_id: "b".into(), ability_index: 0, target_ids: vec!["a".into()] })
+    );
+    assert_eq!(state.ability_command("fire", Vec::new()), None);
+}
+
+#[test]
+fn selection_changes_reach_the_info_panel() {
+    let mut state = state();
+    state.units.by_name_mut("b").unwrap().abilities.push(heal("mend"));
+    let mut flow = GameFlow::new(state, UiManager::new(1280, 720, vec!["fire".into()], Vec::new()));
+    let renderer = Renderer::new_headless(1280, 720);
+
+    let changed = flow.select_at((2 * 64 + 5, 2 * 64 + 5), false, &renderer);
+    assert_eq!(changed.unwrap().selected, ["b"]);
+    assert_eq!(flow.ui.selection, ["b"]);
+    let abilities: Vec<&str> = flow.ui.ability_buttons.iter().map(|b| b.id.as_str()).collect();
+    assert_eq!(abilities, ["mend"]);
+    flow.select_at((64 + 5, 2 * 64 + 5), true, &renderer);
+    // Clicks outside the battlefield leave the selection alone.
+    assert_eq!(flow.select_at((1279, 5), false, &renderer), None);
+
+    let loc = Localizer::new("en").unwrap();
+    let mut renderer = renderer;
+    flow.render(&mut renderer, &loc);
+    let texts: Vec<&str> = renderer.text_log.iter().map(|t| t.text.as_str()).collect();
+    assert!(texts.contains(&"b") && texts.contains(&"+1 more selected"));
+
+    flow.cycle_selection();
+    assert_eq!(flow.ui.selection, ["c"]);
+    assert!(flow.ui.ability_buttons.is_empty());
+}
+
+#[test]
+fn flags_set_before_the_flow_starts_are_picked_up() {
+    let mut marine = unit("m", Faction::Imperial, 0, 0);
+    marine.is_selected = true;
+    let flow = GameFlow::new(GameState::new(vec![marine]), UiManager::new(640, 480, Vec::new(), Vec::new()));
+    assert_eq!(flow.state.selection.ids(), ["m"]);
+    assert_eq!(flow.ui.selection, ["m"]);
+}
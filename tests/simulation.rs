@@ -0,0 +1,119 @@
+use gero::damage::DamageType;
+use gero::grid::GridMap;
+use gero::models::{Unit, UnitType, Faction, Weapon, WeaponTier, Position};
+use gero::rng::GameRng;
+use gero::simulation::simulate;
+
+fn rifle(damage: i32) -> Weapon {
+    Weapon {
+        id: "rifle".into(),
+        name: "Rifle".into(),
+        tier: WeaponTier::Basic,
+        damage,
+        accuracy: 0.7,
+        range: 6,
+        armor_piercing: None,
+        action_point_cost: 1,
+        critical_chance: 0.1,
+        abilities_granted: Vec::new(),
+        damage_type: DamageType::Kinetic,
+        forced_move: None,
+        indirect: None,
+    }
+}
+
+fn soldier(id: &str, unit_type: UnitType, faction: Faction, x: usize, damage: i32) -> Unit {
+    let mut unit = Unit::new(id, id, unit_type, faction);
+    unit.base_stats.agility = 4;
+    unit.recalculate_stats();
+    unit.equipment.weapon = Some(rifle(damage));
+    unit.grid_position = Position { x, y: 0 };
+    unit
+}
+
+#[test]
+fn rng_is_deterministic_per_seed() {
+    let mut a = GameRng::new(42);
+    let mut b = GameRng::new(42);
+    let rolls_a: Vec<u8> = (0..20).map(|_| a.roll_d100()).collect();
+    let rolls_b: Vec<u8> = (0..20).map(|_| b.roll_d100()).collect();
+    assert_eq!(rolls_a, rolls_b);
+    assert!(rolls_a.iter().all(|r| (1..=100).contains(r)));
+    assert_ne!(rolls_a, (0..20).map(|_| GameRng::new(7).roll_d100()).collect::<Vec<_>>());
+}
+
+#[test]
+fn simulate_reports_outcomes_and_damage() {
+    let players = vec![soldier("g", UnitType::Guardsman, Faction::Imperial, 0, 6)];
+    let enemies = vec![soldier("o", UnitType::OrkBoy, Faction::Ork, 4, 1)];
+    let report = simulate(&players, &enemies, &GridMap::new(8, 2), 20, 1);
+
+    assert_eq!(report.battles, 20);
+    assert_eq!(report.player_wins + report.enemy_wins + report.draws, 20);
+    assert!(report.win_rate() > 0.5);
+    assert!(report.average_rounds() >= 1.0);
+    assert!(report.average_damage(&UnitType::Guardsman) > report.average_damage(&UnitType::OrkBoy));
+}
+
+#[test]
+fn simulate_is_reproducible() {
+    let players = vec![soldier("g", UnitType::Guardsman, Faction::Imperial, 0, 3)];
+    let enemies = vec![soldier("o", UnitType::OrkBoy, Faction::Ork, 3, 3)];
+    let map = GridMap::new(6, 2);
+    let a = simulate(&players, &enemies, &map, 10, 99);
+    let b = simulate(&players, &enemies, &map, 10, 99);
+    assert_eq!(a.player_wins, b.player_wins);
+    assert_eq!(a.total_rounds, b.total_rounds);
+    assert_eq!(a.damage_by_unit_type, b.damage_by_unit_type);
+}
+
+#[test]
+fn auto_resolve_summarises_an_easy_fight() {
+    use gero::campaign::Campaign;
+    use gero::combat::CombatEncounter;
+    use gero::simulation::{BattleOutcome, XP_PER_KILL};
+
+    let players = vec![
+        soldier("g1", UnitType::Guardsman, Faction::Imperial, 0, 8),
+        soldier("g2", UnitType::Guardsman, Faction::Imperial, 1, 8),
+    ];
+    let enemies = vec![soldier("o", UnitType::OrkBoy, Faction::Ork, 4, 1)];
+    let encounter = CombatEncounter::new(players.clone(), enemies, GridMap::new(8, 2), None);
+
+    let report = encounter.auto_resolve();
+    assert_eq!(report.outcome, BattleOutcome::PlayerVictory);
+    assert!(report.confidence > 0.9);
+    assert!(report.casualties.is_empty());
+    assert_eq!(report.experience, XP_PER_KILL);
+    assert_eq!(report.loot, vec!["rifle".to_string()]);
+    assert_eq!(encounter.auto_resolve(), report);
+    assert!(encounter.unit_by_id("o").unwrap().health_points > 0);
+
+    let mut campaign = Campaign::new();
+    campaign.roster = players;
+    campaign.apply_auto_resolve(&["g1".to_string()], &report);
+    assert_eq!(campaign.roster[0].experience, XP_PER_KILL);
+    assert_eq!(campaign.roster[1].experience, 0);
+    assert_eq!(campaign.inventory, vec!["rifle".to_string()]);
+
+    // Enough experience levels the unit up, as after a fought battle.
+    let report = gero::simulation::AutoResolveReport { experience: 100, loot: Vec::new(), ..report };
+    campaign.apply_auto_resolve(&["g1".to_string()], &report);
+    assert_eq!(campaign.roster[0].level, 2);
+}
+
+#[test]
+fn auto_resolve_reports_expected_casualties() {
+    use gero::combat::CombatEncounter;
+    use gero::simulation::BattleOutcome;
+
+    let players = vec![soldier("g", UnitType::Guardsman, Faction::Imperial, 0, 1)];
+    let enemies = vec![
+        soldier("o1", UnitType::OrkBoy, Faction::Ork, 3, 8),
+        soldier("o2", UnitType::OrkBoy, Faction::Ork, 4, 8),
+    ];
+    let report = CombatEncounter::new(players, enemies, GridMap::new(8, 2), None).auto_resolve();
+    assert_eq!(report.outcome, BattleOutcome::EnemyVictory);
+    assert_eq!(report.casualties, vec!["g".to_string()]);
+    assert!(report.loot.is_empty());
+}
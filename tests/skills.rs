@@ -0,0 +1,65 @@
+use gero::campaign::Campaign;
+use gero::injuries::{Injury, InjuryKind};
+use gero::localization::Localizer;
+use gero::models::{Faction, Unit, UnitType};
+use gero::modifiers::Stat;
+use gero::rng::GameRng;
+use gero::skills::{skill_test, TestDifficulty};
+
+fn adept(intellect: i32) -> Unit {
+    let mut unit = Unit::new("adept", "Adept", UnitType::Guardsman, Faction::Imperial);
+    unit.base_stats.intellect = intellect;
+    unit.recalculate_stats();
+    unit
+}
+
+#[test]
+fn tests_roll_against_the_stat_and_difficulty() {
+    let unit = adept(5);
+    for seed in 0..50 {
+        let test = skill_test(&unit, Stat::Intellect, TestDifficulty::Hard, &mut GameRng::new(seed));
+        assert_eq!(test.target, 30);
+        assert_eq!(test.roll, GameRng::new(seed).roll_d100());
+        assert_eq!(test.success, test.roll <= 30);
+        let margin = if test.success { 30 - test.roll as i32 } else { test.roll as i32 - 31 };
+        assert_eq!(test.degrees, 1 + margin as u32 / 10);
+    }
+    // The same seed always gives the same result.
+    let a = skill_test(&unit, Stat::Fellowship, TestDifficulty::Easy, &mut GameRng::new(7));
+    assert_eq!(a, skill_test(&unit, Stat::Fellowship, TestDifficulty::Easy, &mut GameRng::new(7)));
+}
+
+#[test]
+fn extremes_always_pass_or_fail() {
+    let (hopeless, certain) = (adept(-10), adept(20));
+    let (mut low, mut high) = (false, false);
+    for seed in 0..2000 {
+        let test = skill_test(&hopeless, Stat::Intellect, TestDifficulty::VeryHard, &mut GameRng::new(seed));
+        assert_eq!(test.success, test.roll == 1);
+        let test = skill_test(&certain, Stat::Intellect, TestDifficulty::Easy, &mut GameRng::new(seed));
+        assert_eq!(test.success, test.roll != 100);
+        low |= test.roll == 1;
+        high |= test.roll == 100;
+    }
+    assert!(low && high);
+}
+
+#[test]
+fn campaigns_send_their_best_fit_unit() {
+    let mut campaign = Campaign::new();
+    assert!(campaign.skill_test(Stat::Intellect, TestDifficulty::Routine, &mut GameRng::new(0)).is_none());
+    let mut genius = adept(9);
+    genius.id = "genius".into();
+    genius.injuries.push(Injury { kind: InjuryKind::Concussion, missions_remaining: 1 });
+    let mut clever = adept(6);
+    clever.id = "clever".into();
+    campaign.roster = vec![adept(3), genius, clever, adept(6)];
+    let (unit, test) = campaign.skill_test(Stat::Intellect, TestDifficulty::Routine, &mut GameRng::new(0)).unwrap();
+    assert_eq!(unit.id, "clever");
+    assert_eq!(test.target, 80);
+
+    let loc = Localizer::new("en").unwrap();
+    let text = loc.plural(test.label_key(), test.degrees as i64, &[]);
+    assert!(text.starts_with(if test.success { "Success by" } else { "Failure by" }));
+    assert_eq!(loc.get(TestDifficulty::Routine.label_key()), "Routine");
+}
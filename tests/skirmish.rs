@@ -0,0 +1,125 @@
+use std::collections::HashSet;
+
+use gero::campaign::Campaign;
+use gero::content::{ContentDatabase, Item};
+use gero::grid::TerrainType;
+use gero::models::{Faction, Position, Unit, UnitType};
+use gero::profile::Difficulty;
+use gero::rng::GameRng;
+use gero::scenario::Scenario;
+use gero::skirmish::{threat_budget, EnemyTable, SkirmishMap, SkirmishMission, SkirmishSetup, DEFAULT_SQUAD_POINTS};
+
+fn table() -> EnemyTable {
+    EnemyTable::load("assets/content/enemies.json").unwrap()
+}
+
+fn threat(table: &EnemyTable, squad: &[Unit]) -> u32 {
+    squad
+        .iter()
+        .map(|u| table.archetypes.iter().find(|a| u.id.starts_with(&format!("{}_", a.id))).unwrap().threat)
+        .sum()
+}
+
+#[test]
+fn budgets_grow_with_progress_and_difficulty() {
+    assert_eq!(threat_budget(0, Difficulty::Normal), 6);
+    assert_eq!(threat_budget(3, Difficulty::Normal), 12);
+    assert_eq!(threat_budget(3, Difficulty::Easy), 9);
+    assert_eq!(threat_budget(3, Difficulty::Hard), 18);
+}
+
+#[test]
+fn squads_spend_the_budget_on_the_faction_with_variety() {
+    let table = table();
+    for seed in 0..20 {
+        let squad = table.compose(&Faction::Ork, 12, &mut GameRng::new(seed));
+        assert!(squad.iter().all(|u| u.faction == Faction::Ork && u.equipment.weapon.is_some()));
+        let spent = threat(&table, &squad);
+        assert!(spent <= 12 && spent > 12 - 2, "seed {} spent {}", seed, spent);
+        let ids: HashSet<&str> = squad.iter().map(|u| u.id.as_str()).collect();
+        assert_eq!(ids.len(), squad.len());
+        let weapons: HashSet<&str> = squad.iter().map(|u| u.equipment.weapon.as_ref().unwrap().id.as_str()).collect();
+        assert!(weapons.len() >= 3, "seed {} fielded {:?}", seed, weapons);
+    }
+    let a = table.compose(&Faction::Chaos, 10, &mut GameRng::new(4));
+    let b = table.compose(&Faction::Chaos, 10, &mut GameRng::new(4));
+    assert_eq!(a.iter().map(|u| &u.id).collect::<Vec<_>>(), b.iter().map(|u| &u.id).collect::<Vec<_>>());
+
+    // Too small a budget still fields the cheapest archetype.
+    let squad = table.compose(&Faction::Ork, 1, &mut GameRng::new(0));
+    assert_eq!(squad.len(), 1);
+    assert_eq!(squad[0].unit_type, UnitType::OrkBoy);
+    assert!(table.compose(&Faction::Imperial, 10, &mut GameRng::new(0)).is_empty());
+}
+
+#[test]
+fn skirmishes_deploy_the_generated_squad_on_free_cells() {
+    let scenario = Scenario::load("assets/scenarios/night_raid.json").unwrap();
+    let mission = SkirmishMission { scenario: scenario.clone(), faction: Faction::Chaos, difficulty: Difficulty::Hard };
+    let mut campaign = Campaign::new();
+    campaign.missions_completed = 2;
+    let squad = vec![Unit::new("p", "P", UnitType::Guardsman, Faction::Imperial)];
+    let enc = mission.encounter(&table(), &campaign, squad, &mut GameRng::new(9));
+
+    let enemies: Vec<&Unit> = enc.enemy_units().collect();
+    assert!(!enemies.is_empty());
+    let cells: HashSet<&Position> = enemies.iter().map(|u| &u.grid_position).collect();
+    assert_eq!(cells.len(), enemies.len());
+    for unit in &enemies {
+        assert!(unit.grid_position.x >= scenario.width * 2 / 3);
+        assert_ne!(*enc.battlefield.terrain_at(&unit.grid_position), TerrainType::Blocked);
+    }
+    assert_eq!(threat(&table(), &enemies.into_iter().cloned().collect::<Vec<_>>()), 15);
+}
+
+fn trooper(id: &str, faction: Faction) -> Unit {
+    let mut unit = Unit::new(id, id, UnitType::Guardsman, faction);
+    unit.base_stats.toughness = 3;
+    unit.base_stats.agility = 4;
+    unit.recalculate_stats();
+    unit
+}
+
+#[test]
+fn point_costs_follow_stats_and_equipment() {
+    let db = ContentDatabase::load("assets/content/items.json").unwrap();
+    let mut unit = trooper("g", Faction::Imperial);
+    // 7 stat points, 10 health and 2 action points.
+    assert_eq!(unit.point_cost(), 7 + 5 + 6);
+    let Some(Item::Weapon(bolter)) = db.get("bolter").map(|e| e.item.clone()) else { panic!() };
+    unit.equip_weapon(bolter);
+    assert_eq!(unit.point_cost(), 18 + 10 + 3 + 2);
+    let raine = db.spawn_hero("commissar_raine").unwrap();
+    assert!(raine.point_cost() > trooper("g", Faction::Imperial).point_cost());
+}
+
+#[test]
+fn squads_are_bought_within_the_points_and_launch_without_a_campaign() {
+    let map = SkirmishMap::Procedural { seed: 3, width: 10, height: 6 };
+    let mut setup = SkirmishSetup::new(map, Faction::Imperial, Faction::Ork);
+    setup.points = 40;
+    assert_eq!(setup.validate(), Err("squad is empty"));
+    setup.add_unit(trooper("g1", Faction::Imperial), false).unwrap();
+    setup.add_unit(trooper("g2", Faction::Imperial), false).unwrap();
+    assert_eq!(setup.remaining(false), 4);
+    assert_eq!(setup.add_unit(trooper("g3", Faction::Imperial), false), Err("squad over budget"));
+    assert_eq!(setup.add_unit(trooper("o1", Faction::Imperial), true), Err("unit from wrong faction"));
+    assert_eq!(setup.add_unit(trooper("g1", Faction::Ork), true), Err("duplicate unit id"));
+    setup.add_unit(trooper("o1", Faction::Ork), true).unwrap();
+    setup.validate().unwrap();
+    setup.points = 20;
+    assert_eq!(setup.validate(), Err("squad over budget"));
+    assert_eq!(setup.launch().err(), Some("squad over budget"));
+    setup.points = DEFAULT_SQUAD_POINTS;
+
+    let enc = setup.launch().unwrap();
+    let columns: Vec<usize> = enc.player_units().map(|u| u.grid_position.x).collect();
+    assert_eq!(columns, [0, 0]);
+    assert_eq!(enc.unit_by_id("o1").unwrap().grid_position.x, 9);
+    assert_eq!(enc.battlefield.width, 10);
+    // The same seed always lays out the same map, with clear deployment edges.
+    let terrain = Scenario::procedural(3, 10, 6).terrain;
+    assert_eq!(terrain, Scenario::procedural(3, 10, 6).terrain);
+    assert!(!terrain.is_empty());
+    assert!(terrain.iter().all(|(p, _)| (2..8).contains(&p.x)));
+}
@@ -0,0 +1,124 @@
+use gero::combat::{resolve_attack, CombatEncounter, CombatError, CombatEvent};
+use gero::command::Command;
+use gero::damage::DamageType;
+use gero::grid::GridMap;
+use gero::models::{Faction, Position, Unit, UnitType, Weapon, WeaponTier};
+use gero::rng::GameRng;
+use gero::stance::Stance;
+
+fn gun(range: u32, accuracy: f32) -> Weapon {
+    Weapon {
+        id: "gun".into(),
+        name: "Gun".into(),
+        tier: WeaponTier::Basic,
+        damage: 3,
+        accuracy,
+        range,
+        armor_piercing: None,
+        action_point_cost: 1,
+        critical_chance: 0.0,
+        abilities_granted: Vec::new(),
+        damage_type: DamageType::Kinetic,
+        forced_move: None,
+        indirect: None,
+    }
+}
+
+fn soldier(id: &str, faction: Faction, x: usize) -> Unit {
+    let mut unit = Unit::new(id, id, UnitType::Guardsman, faction);
+    unit.base_stats.agility = 6;
+    unit.base_stats.max_health = 30;
+    unit.equip_weapon(gun(3, 1.0));
+    unit.health_points = 30;
+    unit.grid_position = Position { x, y: 0 };
+    unit
+}
+
+fn stance(unit: &str, stance: Stance) -> Command {
+    Command::TakeStance { unit_id: unit.into(), stance }
+}
+
+#[test]
+fn stances_spend_the_rest_of_the_turn_and_last_until_the_next() {
+    let players = vec![soldier("guard", Faction::Imperial, 0)];
+    let enemies = vec![soldier("ork", Faction::Ork, 7)];
+    let mut encounter = CombatEncounter::new(players, enemies, GridMap::new(8, 1), None);
+    encounter.start_turn();
+    encounter.execute(stance("guard", Stance::Hunker)).unwrap();
+    let guard = encounter.unit_by_id("guard").unwrap();
+    assert_eq!((guard.action_points, guard.stance), (0, Some(Stance::Hunker)));
+    assert_eq!(encounter.execute(stance("guard", Stance::Brace)), Err(CombatError::InsufficientAp));
+
+    encounter.execute(Command::EndTurn).unwrap();
+    encounter.start_turn();
+    assert_eq!(encounter.unit_by_id("guard").unwrap().stance, Some(Stance::Hunker));
+    encounter.execute(Command::EndTurn).unwrap();
+    encounter.start_turn();
+    assert_eq!(encounter.unit_by_id("guard").unwrap().stance, None);
+}
+
+#[test]
+fn hunkering_down_counts_as_cover() {
+    let mut attacker = Unit::new("a", "A", UnitType::Guardsman, Faction::Ork);
+    let mut defender = Unit::new("d", "D", UnitType::Guardsman, Faction::Imperial);
+    let weapon = gun(3, 0.5);
+    assert!(resolve_attack(&mut attacker, &weapon, &mut defender.clone(), 40, 0).unwrap().hit);
+    defender.stance = Some(Stance::Hunker);
+    attacker.action_points = 2;
+    assert!(!resolve_attack(&mut attacker, &weapon, &mut defender, 40, 0).unwrap().hit);
+}
+
+#[test]
+fn overwatch_fires_once_on_a_unit_moving_into_range() {
+    let players = vec![soldier("guard", Faction::Imperial, 0)];
+    let enemies = vec![soldier("ork", Faction::Ork, 7)];
+    let mut encounter = CombatEncounter::new(players, enemies, GridMap::new(8, 1), None);
+    encounter.units.by_name_mut("ork").unwrap().action_points = 1;
+    encounter.take_stance("ork", Stance::Overwatch).unwrap();
+    // A hit, but not a critical one.
+    encounter.rng = GameRng::new((0..).find(|&s| (11..=30).contains(&GameRng::new(s).roll_d100())).unwrap());
+    encounter.start_turn();
+
+    let move_to = |x| Command::Move { unit_id: "guard".into(), destination: Position { x, y: 0 } };
+    encounter.execute(move_to(2)).unwrap();
+    assert!(encounter.can_undo());
+    encounter.execute(move_to(4)).unwrap();
+    assert!(!encounter.can_undo());
+    assert!(encounter.events.contains(&CombatEvent::OverwatchTriggered { unit_id: "ork".into(), target_id: "guard".into() }));
+    assert_eq!(encounter.unit_by_id("guard").unwrap().health_points, 27);
+    let ork = encounter.unit_by_id("ork").unwrap();
+    assert_eq!((ork.action_points, ork.stance), (0, None));
+
+    encounter.execute(move_to(5)).unwrap();
+    assert_eq!(encounter.unit_by_id("guard").unwrap().health_points, 27);
+}
+
+#[test]
+fn braced_units_blunt_charges_and_the_ai_avoids_them() {
+    let mut ork = soldier("ork", Faction::Ork, 0);
+    ork.equip_weapon(gun(1, 1.0));
+    let players = vec![soldier("guard", Faction::Imperial, 4)];
+    let mut encounter = CombatEncounter::new(players, vec![ork], GridMap::new(8, 1), None);
+    encounter.units.by_name_mut("ork").unwrap().action_points = 2;
+    assert!(encounter.ai_should_charge("ork", "guard"));
+    encounter.units.by_name_mut("guard").unwrap().stance = Some(Stance::Brace);
+    assert!(!encounter.ai_should_charge("ork", "guard"));
+}
+
+#[test]
+fn the_ai_looks_past_hunkered_units() {
+    let mut ork = soldier("ork", Faction::Ork, 3);
+    ork.base_stats.agility = 0;
+    ork.recalculate_stats();
+    let mut near = soldier("near", Faction::Imperial, 2);
+    near.stance = Some(Stance::Hunker);
+    let players = vec![near, soldier("far", Faction::Imperial, 6)];
+    let mut encounter = CombatEncounter::new(players, vec![ork], GridMap::new(8, 1), None);
+    encounter.turn_order.initiative.clear();
+    encounter.turn_order.add_unit("ork".into());
+    encounter.start_turn();
+    encounter.units.by_name_mut("ork").unwrap().action_points = 1;
+    encounter.enemy_ai_action(20);
+    assert_eq!(encounter.unit_by_id("near").unwrap().health_points, 30);
+    assert!(encounter.unit_by_id("far").unwrap().health_points < 30);
+}
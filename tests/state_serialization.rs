@@ -8,7 +8,7 @@ fn status_effects_persist_through_save() {
     let state = GameState::new(vec![unit.clone()]);
     let data = state.save_to_string();
     let loaded = GameState::load_from_str(&data);
-    assert_eq!(loaded.units[0].status_effects.len(), 1);
-    assert!(matches!(loaded.units[0].status_effects[0].effect_type, EffectType::Stun));
-    assert_eq!(loaded.units[0].status_effects[0].remaining_turns, 2);
+    assert_eq!(loaded.units.by_name("u").unwrap().status_effects.len(), 1);
+    assert!(matches!(loaded.units.by_name("u").unwrap().status_effects[0].effect_type, EffectType::Stun));
+    assert_eq!(loaded.units.by_name("u").unwrap().status_effects[0].remaining_turns, 2);
 }
@@ -0,0 +1,126 @@
+use gero::campaign::Campaign;
+use gero::combat::CombatEncounter;
+use gero::command::Command;
+use gero::damage::DamageType;
+use gero::flow::GameFlow;
+use gero::frontend::Renderer;
+use gero::grid::GridMap;
+use gero::input::GameAction;
+use gero::localization::Localizer;
+use gero::models::{Faction, Position, Unit, UnitType, Weapon, WeaponTier};
+use gero::saves::{self, SaveFormat};
+use gero::simulation::BattleOutcome;
+use gero::state::GameState;
+use gero::stats::{BattleStats, UnitStats};
+use gero::ui::UiManager;
+
+fn lasgun() -> Weapon {
+    Weapon {
+        id: "w".into(),
+        name: "Lasgun".into(),
+        tier: WeaponTier::Basic,
+        damage: 3,
+        accuracy: 1.0,
+        range: 8,
+        armor_piercing: None,
+        action_point_cost: 1,
+        critical_chance: 0.0,
+        abilities_granted: Vec::new(),
+        damage_type: DamageType::Kinetic,
+        forced_move: None,
+        indirect: None,
+    }
+}
+
+fn encounter() -> CombatEncounter {
+    let mut player = Unit::new("p", "Trooper", UnitType::Guardsman, Faction::Imperial);
+    player.base_stats.agility = 6;
+    player.recalculate_stats();
+    player.equipment.weapon = Some(lasgun());
+    let mut enemy = Unit::new("e", "Boy", UnitType::OrkBoy, Faction::Ork);
+    enemy.grid_position = Position { x: 5, y: 0 };
+    CombatEncounter::new(vec![player], vec![enemy], GridMap::new(8, 8), None)
+}
+
+#[test]
+fn commands_record_moves_attacks_and_turns() {
+    let mut enc = encounter();
+    enc.start_turn();
+    enc.execute(Command::Move { unit_id: "p".into(), destination: Position { x: 2, y: 1 } }).unwrap();
+    enc.execute(Command::Move { unit_id: "p".into(), destination: Position { x: 3, y: 1 } }).unwrap();
+    assert!(enc.undo());
+    assert_eq!(enc.stats.unit("p").tiles_moved, 2);
+
+    let hp = enc.unit_by_id("e").unwrap().health_points;
+    enc.execute(Command::Attack { attacker_id: "p".into(), target_id: "e".into() }).unwrap();
+    let dealt = (hp - enc.unit_by_id("e").unwrap().health_points) as u32;
+    enc.execute(Command::EndTurn).unwrap();
+
+    let trooper = enc.stats.unit("p");
+    assert_eq!(trooper.attacks, 1);
+    assert_eq!(trooper.accuracy(), Some(trooper.hits as f32));
+    assert_eq!(trooper.damage_dealt, dealt);
+    assert_eq!(trooper.turns_survived, 1);
+    assert_eq!(enc.stats.unit("e").damage_taken, dealt);
+    assert_eq!(enc.stats.rounds, 1);
+    assert_eq!(enc.stats.unit("nobody"), UnitStats::default());
+}
+
+#[test]
+fn battle_stats_are_saved_with_the_encounter() {
+    let mut enc = encounter();
+    enc.stats.record_attack("p", "e", true, 4);
+    enc.stats.record_ability("e");
+    for format in [SaveFormat::Json, SaveFormat::Binary] {
+        let loaded: CombatEncounter = saves::decode(&saves::encode(&enc, format).unwrap()).unwrap();
+        assert_eq!(loaded.stats, enc.stats);
+    }
+}
+
+#[test]
+fn campaign_aggregates_every_battle() {
+    let mut first = BattleStats::new();
+    first.record_attack("p", "e", true, 5);
+    first.record_attack("p", "e", false, 0);
+    first.rounds = 3;
+    let mut second = BattleStats::new();
+    second.record_attack("p", "e", true, 2);
+    second.record_damage(None, "p", 4);
+    second.rounds = 2;
+
+    let mut campaign = Campaign::new();
+    campaign.record_battle(&first, BattleOutcome::PlayerVictory);
+    campaign.record_battle(&second, BattleOutcome::EnemyVictory);
+    let stats = &campaign.stats;
+    assert_eq!((stats.battles, stats.victories, stats.defeats, stats.rounds), (2, 1, 1, 5));
+    let trooper = stats.units["p"];
+    assert_eq!((trooper.attacks, trooper.hits, trooper.damage_dealt, trooper.damage_taken), (3, 2, 7, 4));
+    assert_eq!(stats.totals().damage_taken, 11);
+
+    let loaded: Campaign = saves::decode(&saves::encode(&campaign, SaveFormat::Json).unwrap()).unwrap();
+    assert_eq!(loaded.stats, campaign.stats);
+}
+
+#[test]
+fn summary_screen_lists_units_until_dismissed() {
+    let mut enc = encounter();
+    enc.stats.record_attack("p", "e", true, 6);
+    enc.stats.rounds = 4;
+    let mut flow = GameFlow::new(GameState::new(Vec::new()), UiManager::new(1280, 720, Vec::new(), Vec::new()));
+    flow.finish_battle(&enc, BattleOutcome::PlayerVictory);
+    assert!(flow.is_paused());
+    assert_eq!(flow.campaign.stats.victories, 1);
+
+    let loc = Localizer::new("en").unwrap();
+    let mut renderer = Renderer::new_headless(1280, 720);
+    flow.render(&mut renderer, &loc);
+    let texts: Vec<&str> = renderer.text_log.iter().map(|t| t.text.as_str()).collect();
+    for expected in ["Victory", "4 rounds fought", "Trooper", "Boy", "Accuracy", "100%", "-"] {
+        assert!(texts.contains(&expected), "missing {expected:?} in {texts:?}");
+    }
+
+    flow.handle_action(GameAction::SelectDown);
+    assert!(flow.summary.is_some());
+    flow.handle_action(GameAction::Activate);
+    assert!(flow.summary.is_none());
+}
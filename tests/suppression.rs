@@ -0,0 +1,115 @@
+use gero::combat::{CombatEncounter, CombatError, CombatEvent};
+use gero::command::Command;
+use gero::damage::DamageType;
+use gero::durability::{MAX_DURABILITY, WEAPON_WEAR_PER_ATTACK};
+use gero::grid::GridMap;
+use gero::models::{Faction, Position, Unit, UnitType, Weapon, WeaponTier};
+use gero::rng::GameRng;
+use gero::suppression::{SUPPRESSION_ACCURACY_PENALTY, SUPPRESSION_BURST};
+
+fn weapon(range: u32) -> Weapon {
+    Weapon {
+        id: "stubber".into(),
+        name: "Heavy Stubber".into(),
+        tier: WeaponTier::Basic,
+        damage: 3,
+        accuracy: 0.5,
+        range,
+        armor_piercing: None,
+        action_point_cost: 1,
+        critical_chance: 0.0,
+        abilities_granted: Vec::new(),
+        damage_type: DamageType::Kinetic,
+        forced_move: None,
+        indirect: None,
+    }
+}
+
+fn unit(id: &str, faction: Faction, x: usize) -> Unit {
+    let mut unit = Unit::new(id, id, UnitType::Guardsman, faction);
+    unit.base_stats.agility = 4;
+    unit.equip_weapon(weapon(6));
+    unit.grid_position = Position { x, y: 0 };
+    unit
+}
+
+fn encounter() -> CombatEncounter {
+    let players = vec![unit("gunner", Faction::Imperial, 0)];
+    let enemies = vec![unit("o1", Faction::Ork, 4), unit("o2", Faction::Ork, 5), unit("o3", Faction::Ork, 6)];
+    CombatEncounter::new(players, enemies, GridMap::new(8, 2), None)
+}
+
+fn suppress(target: usize) -> Command {
+    Command::Suppress { attacker_id: "gunner".into(), target: Position { x: target, y: 0 } }
+}
+
+#[test]
+fn suppressing_fire_pays_extra_and_covers_an_area() {
+    let mut encounter = encounter();
+    encounter.start_turn();
+    assert_eq!(encounter.execute(suppress(7)), Err(CombatError::OutOfRange));
+    encounter.execute(suppress(5)).unwrap();
+
+    let gunner = encounter.unit_by_id("gunner").unwrap();
+    assert_eq!(gunner.action_points, 0);
+    assert_eq!(gunner.equipment.weapon_durability, MAX_DURABILITY - WEAPON_WEAR_PER_ATTACK * SUPPRESSION_BURST);
+    for id in ["o1", "o2", "o3"] {
+        let ork = encounter.unit_by_id(id).unwrap();
+        assert!(ork.is_suppressed());
+        assert_eq!(ork.current_stats.accuracy, SUPPRESSION_ACCURACY_PENALTY);
+        assert!(encounter.events.contains(&CombatEvent::UnitSuppressed { unit_id: id.into() }));
+    }
+    assert_eq!(encounter.execute(suppress(5)), Err(CombatError::InsufficientAp));
+
+    let mut melee = encounter.unit_by_id("gunner").unwrap().clone();
+    melee.action_points = 2;
+    melee.equip_weapon(weapon(1));
+    let mut encounter = CombatEncounter::new(vec![melee], vec![unit("o1", Faction::Ork, 1)], GridMap::new(4, 2), None);
+    assert_eq!(encounter.execute(suppress(1)), Err(CombatError::MeleeCantSuppress));
+}
+
+#[test]
+fn suppressed_units_that_lose_their_nerve_are_pinned() {
+    let mut encounter = encounter();
+    encounter.start_turn();
+    encounter.execute(suppress(5)).unwrap();
+    encounter.execute(Command::EndTurn).unwrap();
+
+    // With no willpower, anything but a roll of 1 fails the test.
+    let seed = (0..).find(|&s| GameRng::new(s).roll_d100() > 1).unwrap();
+    encounter.rng = GameRng::new(seed);
+    encounter.start_turn();
+    assert!(encounter.unit_by_id("o1").unwrap().is_pinned());
+    assert!(encounter.events.contains(&CombatEvent::UnitPinned { unit_id: "o1".into() }));
+    let attack = Command::Attack { attacker_id: "o1".into(), target_id: "gunner".into() };
+    assert_eq!(encounter.execute(attack.clone()), Err(CombatError::Pinned));
+    encounter.execute(Command::Move { unit_id: "o1".into(), destination: Position { x: 4, y: 1 } }).unwrap();
+
+    encounter.execute(Command::EndTurn).unwrap();
+    let ork = encounter.unit_by_id("o1").unwrap();
+    assert!(!ork.is_pinned() && !ork.is_suppressed());
+    assert_eq!(ork.current_stats.accuracy, 0);
+}
+
+#[test]
+fn the_ai_suppresses_groups_and_holds_under_fire() {
+    // With three targets bunched up, the ork lays down fire.
+    let players = vec![unit("a", Faction::Imperial, 4), unit("b", Faction::Imperial, 5), unit("c", Faction::Imperial, 6)];
+    let mut ork = unit("ork", Faction::Ork, 0);
+    ork.action_points = 2;
+    let mut ai = CombatEncounter::new(players, vec![ork], GridMap::new(8, 2), None);
+    ai.turn_order.current_unit_id = Some("ork".into());
+    ai.ai_take_action(50);
+    assert!(["a", "b", "c"].iter().all(|id| ai.unit_by_id(id).unwrap().is_suppressed()));
+    assert_eq!(ai.unit_by_id("ork").unwrap().action_points, 0);
+
+    // A suppressed unit with nothing in range stays put.
+    let mut encounter = encounter();
+    encounter.start_turn();
+    encounter.execute(suppress(5)).unwrap();
+    let o3 = encounter.unit_by_id_mut("o3").unwrap();
+    o3.equip_weapon(weapon(1));
+    encounter.turn_order.current_unit_id = Some("o3".into());
+    encounter.ai_take_action(50);
+    assert_eq!(encounter.unit_by_id("o3").unwrap().grid_position, Position { x: 6, y: 0 });
+}
@@ -0,0 +1,73 @@
+use gero::builders::{AbilityBuilder, UnitBuilder, WeaponBuilder};
+use gero::combat::CombatEncounter;
+use gero::command::Command;
+use gero::grid::GridMap;
+use gero::models::{AbilityType, EffectType, Faction, Trait, Unit, UnitType};
+use gero::modifiers::{Modifier, ModifierSource, Stat};
+
+fn guard(id: &str, x: usize) -> UnitBuilder {
+    UnitBuilder::new(id, UnitType::Guardsman, Faction::Imperial).max_health(20).at(x, 0)
+}
+
+/// An ork with a long gun at the left of the map, whose turn it is,
+/// facing `guards`.
+fn encounter(guards: Vec<Unit>) -> CombatEncounter {
+    let gun = WeaponBuilder::new("gun").damage(3).range(9).accuracy(1.0).critical_chance(0.0).build();
+    let ork = UnitBuilder::new("ork", UnitType::OrkBoy, Faction::Ork).weapon(gun).at(0, 0).build();
+    let mut encounter = CombatEncounter::new(guards, vec![ork], GridMap::new(10, 1), None);
+    encounter.balance.critical_multiplier = 1;
+    encounter.turn_order.current_unit_id = Some("ork".into());
+    encounter
+}
+
+fn shot(encounter: &mut CombatEncounter) -> String {
+    let before: Vec<(String, i32)> = encounter.units.iter().map(|u| (u.id.clone(), u.health_points)).collect();
+    encounter.unit_by_id_mut("ork").unwrap().action_points = 1;
+    encounter.ai_take_action(50);
+    let hurt = before.into_iter().find(|(id, hp)| encounter.unit_by_id(id).unwrap().health_points < *hp);
+    hurt.expect("the ork hit nobody").0
+}
+
+#[test]
+fn the_ai_shoots_the_nearest_by_default() {
+    let mut encounter = encounter(vec![guard("near", 2).build(), guard("far", 4).build()]);
+    assert_eq!(shot(&mut encounter), "near");
+}
+
+#[test]
+fn concealed_units_are_passed_over() {
+    let mut stealthy = encounter(vec![guard("near", 2).with_trait(Trait::Stealthy).build(), guard("far", 4).build()]);
+    assert_eq!(shot(&mut stealthy), "far");
+
+    let mut camo = guard("near", 2).build();
+    camo.add_modifier(Modifier::timed(ModifierSource::Equipment, Stat::Concealment, 1, 2));
+    let mut camouflaged = encounter(vec![camo, guard("far", 4).build()]);
+    assert_eq!(shot(&mut camouflaged), "near");
+}
+
+#[test]
+fn taunted_units_go_after_the_taunter() {
+    let shout = AbilityBuilder::new("shout", AbilityType::Special).range(9).status(EffectType::Taunted, 1).build();
+    let far = guard("far", 6).ability(shout).build();
+    let mut encounter = encounter(vec![guard("near", 2).build(), far]);
+    encounter.turn_order.current_unit_id = Some("far".into());
+    let cmd = Command::UseAbility { user_id: "far".into(), ability_index: 0, target_ids: vec!["ork".into()] };
+    encounter.execute(cmd).unwrap();
+    assert_eq!(encounter.unit_by_id("ork").unwrap().taunter(), Some("far"));
+    encounter.turn_order.current_unit_id = Some("ork".into());
+    assert_eq!(shot(&mut encounter), "far");
+
+    // The taunt wears off at the end of the ork's turn.
+    encounter.end_turn();
+    encounter.turn_order.current_unit_id = Some("ork".into());
+    assert_eq!(encounter.unit_by_id("ork").unwrap().taunter(), None);
+    assert_eq!(shot(&mut encounter), "near");
+}
+
+#[test]
+fn taunts_from_the_fallen_are_ignored() {
+    let mut encounter = encounter(vec![guard("near", 2).build(), guard("far", 6).build()]);
+    encounter.taunt("far", "ork", 2);
+    encounter.unit_by_id_mut("far").unwrap().health_points = 0;
+    assert_eq!(shot(&mut encounter), "near");
+}
@@ -0,0 +1,78 @@
+use gero::frontend::palette::{Palette, PaletteRole};
+use gero::frontend::terminal::{unit_glyph, TerminalFrame};
+use gero::grid::{GridMap, TerrainType};
+use gero::localization::Localizer;
+use gero::models::{Faction, Position, Unit, UnitType};
+use gero::registry::UnitRegistry;
+use gero::state::GameState;
+
+fn unit(id: &str, name: &str, unit_type: UnitType, faction: Faction, x: usize, y: usize) -> Unit {
+    let mut unit = Unit::new(id, name, unit_type, faction);
+    unit.grid_position = Position { x, y };
+    unit
+}
+
+fn state() -> GameState {
+    let mut state = GameState::new(Vec::new());
+    let mut marine = unit("m", "Brother Tiber", UnitType::SpaceMarine, Faction::Imperial, 0, 0);
+    marine.is_selected = true;
+    let ork = unit("o", "Grukk", UnitType::OrkBoy, Faction::Ork, 3, 1);
+    let mut dead = unit("d", "Snikrot", UnitType::OrkNob, Faction::Ork, 1, 1);
+    dead.health_points = 0;
+    state.units = UnitRegistry::with_sides(vec![marine], vec![ork, dead]);
+    state.map = GridMap::new(4, 2);
+    state.map.set_terrain(&Position { x: 1, y: 0 }, TerrainType::Blocked);
+    state.map.set_terrain(&Position { x: 2, y: 1 }, TerrainType::Hazardous);
+    for id in ["m", "o"] {
+        state.turn_queue.add_unit(id.into());
+    }
+    state
+}
+
+#[test]
+fn battlefield_shows_terrain_and_living_units() {
+    let loc = Localizer::new("en").unwrap();
+    let palette = Palette::default();
+    let frame = TerminalFrame::new(&state(), &palette, &loc, &[]);
+    assert_eq!(frame.battlefield_text(), "M#..\n..~o");
+
+    let marine = &frame.battlefield[0][0];
+    assert_eq!(marine.color, Some(palette.color(PaletteRole::Ally)));
+    assert!(marine.highlight);
+    let ork = &frame.battlefield[1][3];
+    assert_eq!(ork.color, Some(palette.color(PaletteRole::Enemy)));
+    assert!(!ork.highlight);
+    assert_eq!(frame.battlefield[0][1].color, None);
+    assert_ne!(unit_glyph(&UnitType::Cultist), unit_glyph(&UnitType::Commissar));
+}
+
+#[test]
+fn turn_order_marks_the_acting_unit_first() {
+    let loc = Localizer::new("en").unwrap();
+    let mut state = state();
+    let frame = TerminalFrame::new(&state, &Palette::default(), &loc, &[]);
+    assert_eq!(frame.turn_order, ["Round 1", "  Brother Tiber", "  Grukk"]);
+
+    state.turn_queue.next_turn();
+    state.turn_queue.next_turn();
+    let log = vec!["Grukk attacks".to_string()];
+    let frame = TerminalFrame::new(&state, &Palette::default(), &loc, &log);
+    assert_eq!(frame.turn_order, ["Round 1", "> Grukk", "  Brother Tiber"]);
+    assert_eq!(frame.log, log);
+}
+
+#[cfg(feature = "tui")]
+#[test]
+fn draws_panels_into_a_terminal() {
+    use ratatui::backend::TestBackend;
+    use ratatui::Terminal;
+
+    let loc = Localizer::new("en").unwrap();
+    let frame = TerminalFrame::new(&state(), &Palette::default(), &loc, &["Grukk attacks".to_string()]);
+    let mut terminal = Terminal::new(TestBackend::new(80, 10)).unwrap();
+    terminal.draw(|f| frame.draw(f, &loc)).unwrap();
+    let screen: String = terminal.backend().buffer().content().iter().map(|c| c.symbol()).collect();
+    for text in ["Turn order", "Battlefield", "Log", "Brother Tiber", "M#..", "Grukk attacks"] {
+        assert!(screen.contains(text), "missing {text:?}");
+    }
+}
@@ -0,0 +1,98 @@
+use gero::builders::{AbilityBuilder, UnitBuilder};
+use gero::combat::{CombatEncounter, CombatError};
+use gero::command::Command;
+use gero::grid::{GridMap, TerrainType};
+use gero::models::{Ability, AbilityType, AreaOfEffect, Faction, Position, UnitType};
+use gero::terraform::{area_cells, TerrainShift};
+
+fn at(x: usize, y: usize) -> Position {
+    Position { x, y }
+}
+
+fn ability(id: &str, shift: TerrainShift, area: AreaOfEffect) -> Ability {
+    AbilityBuilder::new(id, AbilityType::Special).range(4).area(area).alters_terrain(shift).build()
+}
+
+/// A psyker at the left of a 7x5 map who knows `abilities`, facing an ork
+/// at the right.
+fn encounter(abilities: Vec<Ability>) -> CombatEncounter {
+    let mut psyker = UnitBuilder::new("psyker", UnitType::Guardsman, Faction::Imperial).max_action(4).at(0, 2);
+    for a in abilities {
+        psyker = psyker.ability(a);
+    }
+    let ork = UnitBuilder::new("ork", UnitType::OrkBoy, Faction::Ork).at(6, 2).build();
+    CombatEncounter::new(vec![psyker.build()], vec![ork], GridMap::new(7, 5), None)
+}
+
+fn use_at(index: usize, target: Position) -> Command {
+    Command::UseAbilityAt { user_id: "psyker".into(), ability_index: index, target }
+}
+
+#[test]
+fn line_areas_run_across_the_line_of_fire() {
+    let map = GridMap::new(7, 5);
+    let line = AreaOfEffect::Line { length: 3 };
+    assert_eq!(area_cells(&map, &at(0, 2), &at(3, 2), Some(&line)), [at(3, 1), at(3, 2), at(3, 3)]);
+    assert_eq!(area_cells(&map, &at(3, 0), &at(3, 2), Some(&line)), [at(2, 2), at(3, 2), at(4, 2)]);
+    assert_eq!(area_cells(&map, &at(0, 0), &at(1, 0), None), [at(1, 0)]);
+}
+
+#[test]
+fn psychic_walls_block_sight_until_they_fade() {
+    let wall = ability("wall", TerrainShift::Wall { turns: 1 }, AreaOfEffect::Line { length: 5 });
+    let mut encounter = encounter(vec![wall]);
+    let revision = encounter.battlefield.revision;
+    encounter.execute(use_at(0, at(3, 2))).unwrap();
+    assert_eq!(*encounter.battlefield.terrain_at(&at(3, 0)), TerrainType::Blocked);
+    assert!(!encounter.battlefield.has_line_of_sight(&at(0, 2), &at(6, 2)));
+    assert!(encounter.battlefield.revision > revision);
+
+    encounter.tick_environment();
+    assert_eq!(*encounter.battlefield.terrain_at(&at(3, 0)), TerrainType::Normal);
+    assert!(encounter.battlefield.has_line_of_sight(&at(0, 2), &at(6, 2)));
+    assert!(encounter.terrain_changes.is_empty());
+}
+
+#[test]
+fn walls_are_not_raised_on_units() {
+    let mut encounter = encounter(Vec::new());
+    let wall = encounter.alter_terrain(TerrainShift::Wall { turns: 2 }, &[at(6, 2), at(5, 2)]);
+    assert_eq!(wall, [at(5, 2)]);
+    assert_eq!(*encounter.battlefield.terrain_at(&at(6, 2)), TerrainType::Normal);
+}
+
+#[test]
+fn demolition_leaves_rubble_for_good() {
+    let demolish = ability("demolish", TerrainShift::Demolish, AreaOfEffect::Circle { radius: 1 });
+    let mut encounter = encounter(vec![demolish]);
+    encounter.battlefield.place_structure(&at(2, 2), 10);
+    encounter.battlefield.set_terrain(&at(2, 1), TerrainType::Blocked);
+    encounter.execute(use_at(0, at(2, 2))).unwrap();
+    assert_eq!(*encounter.battlefield.terrain_at(&at(2, 2)), TerrainType::Difficult);
+    assert_eq!(*encounter.battlefield.terrain_at(&at(2, 1)), TerrainType::Difficult);
+    assert!(encounter.battlefield.structures.is_empty());
+    encounter.tick_environment();
+    assert_eq!(*encounter.battlefield.terrain_at(&at(2, 2)), TerrainType::Difficult);
+}
+
+#[test]
+fn ice_slicks_turn_ground_hazardous() {
+    let ice = ability("ice", TerrainShift::Ice { turns: 2 }, AreaOfEffect::Circle { radius: 0 });
+    let mut encounter = encounter(vec![ice]);
+    encounter.execute(use_at(0, at(4, 2))).unwrap();
+    assert_eq!(*encounter.battlefield.terrain_at(&at(4, 2)), TerrainType::Hazardous);
+    encounter.tick_environment();
+    assert_eq!(*encounter.battlefield.terrain_at(&at(4, 2)), TerrainType::Hazardous);
+    encounter.tick_environment();
+    assert_eq!(*encounter.battlefield.terrain_at(&at(4, 2)), TerrainType::Normal);
+}
+
+#[test]
+fn cell_targets_are_checked() {
+    let ice = ability("ice", TerrainShift::Ice { turns: 2 }, AreaOfEffect::Circle { radius: 0 });
+    let bolt = AbilityBuilder::new("bolt", AbilityType::PsychicBlast).range(6).build();
+    let mut encounter = encounter(vec![ice, bolt]);
+    assert_eq!(encounter.execute(use_at(0, at(6, 4))), Err(CombatError::OutOfRange));
+    assert_eq!(encounter.execute(use_at(1, at(2, 2))), Err(CombatError::NeedsUnitTarget));
+    assert_eq!(encounter.execute(use_at(0, at(9, 9))), Err(CombatError::InvalidTarget));
+}
@@ -0,0 +1,89 @@
+use gero::frontend::Renderer;
+use gero::frontend::text::WHITE;
+use gero::ui::UiManager;
+use gero::ui::options::AccessibilitySettings;
+use gero::localization::Localizer;
+
+#[test]
+fn draw_text_records_scaled_size() {
+    let mut renderer = Renderer::new_headless(100, 100);
+    let settings = AccessibilitySettings { font_scale: 1.5, ..Default::default() };
+    renderer.apply_accessibility(&settings);
+    renderer.draw_text("Ready", (4, 8), 16.0, WHITE);
+    assert_eq!(renderer.text_log.len(), 1);
+    assert_eq!(renderer.text_log[0].text, "Ready");
+    assert_eq!(renderer.text_log[0].position, (4, 8));
+    assert_eq!(renderer.text_log[0].size, 24.0);
+}
+
+#[test]
+fn measure_text_grows_with_font_scale() {
+    let mut renderer = Renderer::new_headless(100, 100);
+    let (w1, h1) = renderer.measure_text("Abilities", 10.0);
+    renderer.font_scale = 2.0;
+    let (w2, h2) = renderer.measure_text("Abilities", 10.0);
+    assert_eq!(w2, w1 * 2);
+    assert_eq!(h2, h1 * 2);
+}
+
+#[test]
+fn floating_heal_text_is_green() {
+    let mut ui = UiManager::new(50, 50, vec![], vec![]);
+    ui.spawn_floating_text(4, (2, 2));
+    let mut renderer = Renderer::new_headless(50, 50);
+    let loc = Localizer::new("en").unwrap();
+    ui.render(&mut renderer, &loc);
+    let heal = renderer.text_log.iter().find(|t| t.text == "+4").unwrap();
+    assert_eq!(heal.color, gero::frontend::text::HEAL_GREEN);
+}
+
+#[test]
+fn right_to_left_text_is_reordered_for_display() {
+    use gero::frontend::text::visual_order;
+    use gero::localization::TextDirection;
+
+    assert_eq!(visual_order("שלום", TextDirection::RightToLeft), "םולש");
+    assert_eq!(visual_order("HP שלום", TextDirection::LeftToRight), "HP םולש");
+    assert_eq!(visual_order("HP 12", TextDirection::RightToLeft), "HP 12");
+    assert_eq!(Localizer::from_json("ar", "{}").unwrap().direction(), TextDirection::RightToLeft);
+    assert_eq!(TextDirection::of("he-IL"), TextDirection::RightToLeft);
+    assert_eq!(Localizer::new("en").unwrap().direction(), TextDirection::LeftToRight);
+}
+
+#[test]
+fn wrapping_breaks_between_words_and_cjk_characters() {
+    let renderer = Renderer::new_headless(100, 100);
+    // Headless estimate: 0.6em per Latin character, 1em per CJK character.
+    let lines = renderer.wrap_text("Hold the line until relieved", 16.0, 120);
+    assert!(lines.len() > 1);
+    assert!(lines.iter().all(|l| renderer.measure_text(l, 16.0).0 <= 120));
+    assert_eq!(lines.join(" "), "Hold the line until relieved");
+
+    let lines = renderer.wrap_text("製油所を奪還せよ。今日中に。", 16.0, 128);
+    assert_eq!(lines.concat(), "製油所を奪還せよ。今日中に。");
+    assert!(lines.len() > 1);
+    assert!(lines.iter().all(|l| !l.starts_with('。') && !l.starts_with('に')));
+    assert_eq!(renderer.wrap_text("one\ntwo", 16.0, 500), vec!["one", "two"]);
+}
+
+#[test]
+fn right_to_left_paragraphs_align_to_the_right_edge() {
+    use gero::localization::TextDirection;
+
+    let mut renderer = Renderer::new_headless(400, 100);
+    let height = renderer.draw_paragraph("שלום עולם", (10, 20), 200, 16.0, WHITE, TextDirection::RightToLeft);
+    assert_eq!(height, 20);
+    let line = &renderer.text_log[0];
+    assert_eq!(line.text, "םלוע םולש");
+    assert_eq!(line.position.0 + renderer.measure_text(&line.text, 16.0).0, 210);
+}
+
+#[test]
+fn font_manifest_selects_a_font_per_language() {
+    use gero::frontend::text::FontManifest;
+
+    let manifest = FontManifest::load("assets/fonts/manifest.json").unwrap();
+    assert_eq!(manifest.font_for("ja"), Some("assets/fonts/NotoSansJP-Regular.otf"));
+    assert_eq!(manifest.font_for("zh-TW"), manifest.font_for("zh"));
+    assert_eq!(manifest.font_for("en"), None);
+}
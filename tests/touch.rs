@@ -0,0 +1,102 @@
+use gero::flow::GameFlow;
+use gero::frontend::Renderer;
+use gero::input::{Gesture, TouchGestures, LONG_PRESS_SECONDS};
+use gero::localization::Localizer;
+use gero::models::{Faction, Position, Unit, UnitType};
+use gero::state::GameState;
+use gero::ui::layout::LayoutContext;
+use gero::ui::tooltip::TooltipSubject;
+use gero::ui::{UiEvent, UiManager, TOUCH_BUTTON_SIZE};
+use winit::dpi::PhysicalPosition;
+use winit::event::{DeviceId, Touch, TouchPhase};
+
+fn touch(id: u64, phase: TouchPhase, x: f64, y: f64) -> Touch {
+    Touch { device_id: unsafe { DeviceId::dummy() }, phase, location: PhysicalPosition { x, y }, force: None, id }
+}
+
+fn flow() -> GameFlow {
+    let mut unit = Unit::new("g", "Trooper", UnitType::Guardsman, Faction::Imperial);
+    unit.grid_position = Position { x: 2, y: 3 };
+    let ui = UiManager::with_layout(&LayoutContext::new(1280, 720).with_touch(true), vec!["fire".into()], vec![]);
+    GameFlow::new(GameState::new(vec![unit]), ui)
+}
+
+#[test]
+fn drags_pan_instead_of_tapping() {
+    let mut gestures = TouchGestures::new();
+    assert!(gestures.touch(&touch(1, TouchPhase::Started, 100.0, 100.0)).is_empty());
+    assert!(gestures.touch(&touch(1, TouchPhase::Moved, 105.0, 100.0)).is_empty());
+    assert_eq!(gestures.touch(&touch(1, TouchPhase::Moved, 130.0, 90.0)), [Gesture::Pan { dx: 25.0, dy: -10.0 }]);
+    assert_eq!(gestures.touch(&touch(1, TouchPhase::Ended, 130.0, 90.0)), [Gesture::Released]);
+}
+
+#[test]
+fn two_fingers_pinch_and_never_tap() {
+    let mut gestures = TouchGestures::new();
+    gestures.touch(&touch(1, TouchPhase::Started, 100.0, 100.0));
+    gestures.touch(&touch(2, TouchPhase::Started, 200.0, 100.0));
+    assert_eq!(
+        gestures.touch(&touch(2, TouchPhase::Moved, 300.0, 100.0)),
+        [Gesture::Pinch { x: 200.0, y: 100.0, scale: 2.0 }]
+    );
+    assert!(gestures.touch(&touch(2, TouchPhase::Ended, 300.0, 100.0)).is_empty());
+    assert_eq!(gestures.touch(&touch(1, TouchPhase::Ended, 100.0, 100.0)), [Gesture::Released]);
+}
+
+#[test]
+fn holding_still_long_presses_once() {
+    let mut gestures = TouchGestures::new();
+    gestures.touch(&touch(1, TouchPhase::Started, 40.0, 50.0));
+    assert_eq!(gestures.update(LONG_PRESS_SECONDS / 2.0), None);
+    assert_eq!(gestures.update(LONG_PRESS_SECONDS / 2.0), Some(Gesture::LongPress { x: 40.0, y: 50.0 }));
+    assert_eq!(gestures.update(1.0), None);
+    assert_eq!(gestures.touch(&touch(1, TouchPhase::Ended, 40.0, 50.0)), [Gesture::Released]);
+}
+
+#[test]
+fn taps_select_units_and_press_touch_sized_buttons() {
+    let mut flow = flow();
+    let mut renderer = Renderer::new_headless(1280, 720);
+    let button = flow.ui.ability_buttons[0].bounds.clone();
+    assert_eq!(button.height, TOUCH_BUTTON_SIZE);
+
+    let event = flow.handle_gesture(Gesture::Tap { x: button.x as f32 + 1.0, y: button.y as f32 + 1.0 }, &mut renderer);
+    assert_eq!(event, Some(UiEvent::AbilityPressed("fire".into())));
+
+    flow.handle_gesture(Gesture::Tap { x: 2.0 * 64.0 + 5.0, y: 3.0 * 64.0 + 5.0 }, &mut renderer);
+    assert!(flow.state.units.by_name("g").unwrap().is_selected);
+    flow.handle_gesture(Gesture::Tap { x: 5.0, y: 100.0 }, &mut renderer);
+    assert!(!flow.state.units.by_name("g").unwrap().is_selected);
+}
+
+#[test]
+fn pans_and_pinches_move_the_camera() {
+    let mut flow = flow();
+    let mut renderer = Renderer::new_headless(1280, 720);
+    renderer.camera.x_offset = 100.0;
+    flow.handle_gesture(Gesture::Pan { dx: 40.0, dy: -20.0 }, &mut renderer);
+    assert_eq!((renderer.camera.x_offset, renderer.camera.y_offset), (60.0, 20.0));
+
+    flow.handle_gesture(Gesture::Pinch { x: 0.0, y: 0.0, scale: 2.0 }, &mut renderer);
+    assert_eq!(renderer.camera.zoom_level, 2.0);
+    assert_eq!((renderer.camera.x_offset, renderer.camera.y_offset), (120.0, 40.0));
+    flow.handle_gesture(Gesture::Pinch { x: 0.0, y: 0.0, scale: 10.0 }, &mut renderer);
+    assert_eq!(renderer.camera.zoom_level, 3.0);
+}
+
+#[test]
+fn long_press_shows_a_tooltip_until_release() {
+    let mut flow = flow();
+    let mut renderer = Renderer::new_headless(1280, 720);
+    flow.handle_gesture(Gesture::LongPress { x: 2.0 * 64.0 + 5.0, y: 3.0 * 64.0 + 5.0 }, &mut renderer);
+    assert_eq!(flow.ui.tooltip.as_ref().unwrap().subject, TooltipSubject::Unit("g".into()));
+
+    let loc = Localizer::new("en").unwrap();
+    flow.render(&mut renderer, &loc);
+    assert!(renderer.draw_log.iter().any(|d| d.sprite_id == "panel.tooltip"));
+    let texts: Vec<&str> = renderer.text_log.iter().map(|t| t.text.as_str()).collect();
+    assert!(texts.contains(&"Trooper"));
+
+    flow.handle_gesture(Gesture::Released, &mut renderer);
+    assert!(flow.ui.tooltip.is_none());
+}
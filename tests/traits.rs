@@ -0,0 +1,92 @@
+use gero::combat::resolve_attack;
+use gero::damage::DamageType;
+use gero::flow::GameFlow;
+use gero::frontend::Renderer;
+use gero::grid::{try_move, GridMap};
+use gero::localization::Localizer;
+use gero::models::{Faction, Position, Trait, Unit, UnitType, Weapon, WeaponTier};
+use gero::recruitment::QuestionPool;
+use gero::state::GameState;
+use gero::ui::UiManager;
+
+fn weapon(range: u32) -> Weapon {
+    Weapon {
+        id: "w".into(),
+        name: "W".into(),
+        tier: WeaponTier::Basic,
+        damage: 3,
+        accuracy: 0.5,
+        range,
+        armor_piercing: None,
+        action_point_cost: 1,
+        critical_chance: 0.0,
+        abilities_granted: Vec::new(),
+        damage_type: DamageType::Kinetic,
+        forced_move: None,
+        indirect: None,
+    }
+}
+
+#[test]
+fn traits_modify_recalculated_stats() {
+    let mut unit = Unit::new("u", "U", UnitType::Guardsman, Faction::Imperial);
+    unit.base_stats.agility = 4;
+    unit.traits = vec![Trait::SteadyHands, Trait::Brute, Trait::Fleet];
+    unit.recalculate_stats();
+    assert_eq!(unit.current_stats.accuracy, 10);
+    assert_eq!(unit.current_stats.melee_damage, 2);
+    assert_eq!(unit.current_stats.movement_points(), 3);
+}
+
+#[test]
+fn fleet_extends_movement() {
+    let map = GridMap::new(8, 1);
+    let mut unit = Unit::new("u", "U", UnitType::Guardsman, Faction::Imperial);
+    unit.base_stats.agility = 4;
+    unit.recalculate_stats();
+    unit.action_points = 1;
+    assert!(!try_move(&mut unit, Position { x: 3, y: 0 }, &map));
+    unit.level_up(Some(Trait::Fleet));
+    assert_eq!(unit.level, 2);
+    assert!(try_move(&mut unit, Position { x: 3, y: 0 }, &map));
+}
+
+#[test]
+fn steady_hands_and_brute_apply_in_combat() {
+    let mut attacker = Unit::new("a", "A", UnitType::OrkBoy, Faction::Ork);
+    let mut defender = Unit::new("d", "D", UnitType::Guardsman, Faction::Imperial);
+    // Base hit chance is 50; a roll of 55 only hits with Steady Hands.
+    assert!(!resolve_attack(&mut attacker, &weapon(1), &mut defender, 55, 0).unwrap().hit);
+    attacker.action_points = 2;
+    attacker.traits = vec![Trait::SteadyHands, Trait::Brute];
+    attacker.recalculate_stats();
+    let melee = resolve_attack(&mut attacker, &weapon(1), &mut defender, 55, 0).unwrap();
+    assert!(melee.hit);
+    assert_eq!(melee.damage, 5);
+    let ranged = resolve_attack(&mut attacker, &weapon(6), &mut defender, 55, 0).unwrap();
+    assert_eq!(ranged.damage, 3);
+}
+
+#[test]
+fn traits_come_from_unit_type_and_survive_saves() {
+    let pool = QuestionPool::load_for("assets/recruitment", &UnitType::SpaceMarine).unwrap();
+    let unit = pool.recruit("m", "M");
+    assert_eq!(unit.traits, vec![Trait::SteadyHands]);
+    assert_eq!(unit.current_stats.accuracy, 10);
+
+    let loaded = GameState::load_from_str(&GameState::new(vec![unit]).save_to_string());
+    assert_eq!(loaded.units.by_name("m").unwrap().traits, vec![Trait::SteadyHands]);
+}
+
+#[test]
+fn selected_unit_traits_show_in_info_panel() {
+    let loc = Localizer::new("en").unwrap();
+    let mut renderer = Renderer::new_headless(640, 480);
+    let mut unit = Unit::new("u", "Sergeant", UnitType::Guardsman, Faction::Imperial);
+    unit.traits = vec![Trait::Fleet];
+    unit.is_selected = true;
+    let mut flow = GameFlow::new(GameState::new(vec![unit]), UiManager::new(640, 480, vec![], vec![]));
+    flow.render(&mut renderer, &loc);
+    assert!(renderer.text_log.iter().any(|t| t.text == "Sergeant"));
+    assert!(renderer.text_log.iter().any(|t| t.text == "Fleet"));
+}
@@ -0,0 +1,61 @@
+use gero::combat::{CombatError, CombatEvent};
+use gero::command::Command;
+use gero::models::Position;
+use gero::tutorial::{Highlight, Tutorial, TutorialStep};
+
+fn tutorial() -> Tutorial {
+    Tutorial::load("assets/tutorials/basics.json").unwrap()
+}
+
+fn attack() -> Command {
+    Command::Attack { attacker_id: "recruit".into(), target_id: "target".into() }
+}
+
+#[test]
+fn steps_only_let_their_own_commands_through() {
+    let mut run = tutorial().start();
+    let step = run.step().unwrap();
+    assert_eq!(step.text, "tutorial.basics.move");
+    assert_eq!(step.highlight, Some(Highlight::Cell { position: Position { x: 3, y: 1 } }));
+
+    let not_now = Err(CombatError::TutorialStep);
+    assert_eq!(run.execute(attack()), not_now);
+    assert_eq!(run.execute(Command::EndTurn), not_now);
+    let elsewhere = Command::Move { unit_id: "recruit".into(), destination: Position { x: 2, y: 1 } };
+    assert_eq!(run.execute(elsewhere), not_now);
+    assert_eq!(run.step_index(), 0);
+    assert_eq!(run.encounter().unit_by_id("recruit").unwrap().grid_position, Position { x: 1, y: 1 });
+}
+
+#[test]
+fn steps_advance_on_their_event() {
+    let mut run = tutorial().start();
+    run.execute(Command::Move { unit_id: "recruit".into(), destination: Position { x: 3, y: 1 } }).unwrap();
+    assert_eq!(run.step().unwrap().text, "tutorial.basics.attack");
+    run.execute(attack()).unwrap();
+    assert!(run.encounter().unit_by_id("target").unwrap().health_points < 10);
+    assert_eq!(run.step().unwrap().highlight, Some(Highlight::EndTurnButton));
+
+    run.execute(Command::EndTurn).unwrap();
+    assert!(run.is_complete());
+    assert!(run.step().is_none());
+    // With the lesson over the player is free to play on.
+    assert!(run.execute(Command::EndTurn).is_ok());
+}
+
+#[test]
+fn event_patterns_match_on_the_fields_given() {
+    let step: TutorialStep = serde_json::from_str(
+        r#"{ "text": "t", "advance_on": { "UnitDamaged": { "unit_id": "target" } } }"#,
+    )
+    .unwrap();
+    assert!(step.completed_by(&CombatEvent::UnitDamaged { unit_id: "target".into(), amount: 3, critical: true }));
+    assert!(!step.completed_by(&CombatEvent::UnitDamaged { unit_id: "recruit".into(), amount: 3, critical: false }));
+    assert!(!step.completed_by(&CombatEvent::UnitHealed { unit_id: "target".into(), amount: 3 }));
+
+    let data = std::fs::read_to_string("assets/tutorials/basics.json").unwrap();
+    let mut raw: serde_json::Value = serde_json::from_str(&data).unwrap();
+    raw["steps"] = serde_json::json!([]);
+    let err = Tutorial::from_json(&raw.to_string()).unwrap_err();
+    assert!(err.to_string().contains("has no steps"));
+}
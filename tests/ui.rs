@@ -1,18 +1,59 @@
 use gero::ui::{UiManager, UiTab, UiEvent};
 use gero::localization::Localizer;
 use gero::frontend::Renderer;
-use gero::input::{InputHandler, GameAction};
-use winit::event::{Event, DeviceEvent, WindowEvent, ElementState, MouseButton};
+use gero::input::InputHandler;
+use winit::event::{Event, WindowEvent, ElementState, MouseButton};
 use winit::event::DeviceId;
 use winit::window::WindowId;
 
 #[test]
 fn layout_panels_from_gdd() {
-    let ui = UiManager::new(100, 100, vec![], vec![]);
-    assert_eq!(ui.top_bar.height, 10);
-    assert_eq!(ui.bottom_bar.height, 10);
-    assert_eq!(ui.info_panel.width, 15);
-    assert_eq!(ui.battlefield.width, 70);
+    let ui = UiManager::new(1000, 1000, vec![], vec![]);
+    assert_eq!(ui.top_bar.height, 100);
+    assert_eq!(ui.bottom_bar.height, 100);
+    assert_eq!(ui.info_panel.width, 150);
+    assert_eq!(ui.battlefield.width, 700);
+}
+
+#[test]
+fn small_screens_keep_minimum_panel_sizes() {
+    use gero::ui::layout::LayoutContext;
+
+    let ui = UiManager::new(200, 100, vec![], vec![]);
+    assert_eq!(ui.top_bar.height, 24);
+    assert_eq!(ui.info_panel.width, 96);
+    assert_eq!(ui.battlefield.width, 104);
+
+    let hidpi = UiManager::with_layout(&LayoutContext::new(200, 200).with_scale_factor(2.0).with_font_scale(1.5), vec![], vec![]);
+    assert_eq!(hidpi.top_bar.height, 72);
+    assert_eq!(hidpi.bottom_bar.height, 80);
+    assert_eq!(hidpi.info_panel.width, 200);
+    assert_eq!(hidpi.battlefield.width, 0);
+}
+
+#[test]
+fn inventory_buttons_wrap_instead_of_clipping() {
+    let items: Vec<String> = (0..12).map(|i| format!("item{}", i)).collect();
+    let ui = UiManager::new(400, 300, vec!["fire".into()], items);
+    let bar = &ui.bottom_bar;
+    assert!(ui.inventory_buttons.iter().all(|b| b.bounds.x + b.bounds.width <= bar.x + bar.width));
+    assert!(ui.inventory_buttons.iter().all(|b| b.bounds.y + b.bounds.height <= bar.y + bar.height));
+    let rows: std::collections::BTreeSet<u32> = ui.inventory_buttons.iter().map(|b| b.bounds.y).collect();
+    assert_eq!(rows.len(), 2);
+    assert_eq!(bar.y + bar.height, 300);
+    assert_eq!(ui.battlefield.y + ui.battlefield.height, bar.y);
+    assert_eq!(ui.ability_buttons[0].bounds.width, ui.info_panel.width - 8);
+}
+
+#[test]
+fn safe_area_insets_shrink_the_layout() {
+    use gero::ui::layout::{Insets, LayoutContext};
+
+    let ctx = LayoutContext::new(1000, 1000).with_safe_area(Insets { top: 40, right: 0, bottom: 20, left: 60 });
+    let ui = UiManager::with_layout(&ctx, vec![], vec![]);
+    assert_eq!((ui.top_bar.x, ui.top_bar.y), (60, 40));
+    assert_eq!(ui.bottom_bar.y + ui.bottom_bar.height, 980);
+    assert_eq!(ui.battlefield.x, 60);
 }
 
 #[test]
@@ -41,7 +82,92 @@ fn floating_text_draws_using_renderer() {
     let loc = Localizer::new("en").unwrap();
     ui.render(&mut renderer, &loc);
     assert!(renderer
-        .draw_log
+        .text_log
         .iter()
-        .any(|c| c.sprite_id == "float:damage:5" && c.position == (10, 10)));
+        .any(|t| t.text == "-5" && t.position == (10, 10)));
+}
+
+#[test]
+fn resizing_relays_out_the_ui_and_recentres_the_camera() {
+    use gero::ui::layout::LayoutContext;
+
+    let mut ui = UiManager::new(1000, 1000, vec!["fire".into()], vec!["medkit".into()]);
+    ui.selected_index = 0;
+    ui.current_tab = UiTab::Inventory;
+    let mut renderer = Renderer::new_headless(1000, 1000);
+    renderer.camera.x_offset = 500.0;
+
+    renderer.resize(1600, 900);
+    ui.relayout(&LayoutContext::new(renderer.width, renderer.height));
+    assert_eq!((renderer.width, renderer.height), (1600, 900));
+    assert_eq!(renderer.camera.x_offset, 200.0);
+    assert_eq!(renderer.camera.y_offset, 50.0);
+    assert!((renderer.camera.aspect_ratio - 16.0 / 9.0).abs() < 1e-6);
+    assert_eq!(ui.battlefield.width, 1120);
+    assert_eq!(ui.bottom_bar.y + ui.bottom_bar.height, 900);
+    assert_eq!(ui.inventory_buttons[0].id, "medkit");
+    assert_eq!(ui.current_tab, UiTab::Inventory);
+
+    renderer.resize(0, 0);
+    assert_eq!((renderer.width, renderer.height), (1600, 900));
+}
+
+#[test]
+fn fullscreen_choice_persists_in_the_config_file() {
+    use gero::ui::options::{ColorBlindPalette, OptionsMenu};
+
+    let path = std::env::temp_dir().join(format!("gero_config_{}.json", std::process::id()));
+    let mut options = OptionsMenu::new();
+    assert!(options.toggle_fullscreen());
+    options.accessibility.palette = ColorBlindPalette::Tritanopia;
+    options.save_config(&path).unwrap();
+
+    let loaded = OptionsMenu::load_config(&path).unwrap();
+    assert!(loaded.display.borderless_fullscreen);
+    assert_eq!(loaded.accessibility.palette, ColorBlindPalette::Tritanopia);
+    assert_eq!(loaded.language, "en");
+
+    std::fs::write(&path, "{}").unwrap();
+    assert!(!OptionsMenu::load_config(&path).unwrap().display.borderless_fullscreen);
+    let _ = std::fs::remove_file(path);
+}
+
+#[test]
+fn fps_counter_averages_recent_frames_when_shown() {
+    let mut ui = UiManager::new(800, 600, vec![], vec![]);
+    for _ in 0..100 {
+        ui.fps_counter.record(0.02);
+    }
+    assert!((ui.fps_counter.fps() - 50.0).abs() < 0.01);
+    assert!((ui.fps_counter.frame_time_ms() - 20.0).abs() < 0.01);
+
+    let mut renderer = Renderer::new_headless(800, 600);
+    let loc = Localizer::new("en").unwrap();
+    ui.render(&mut renderer, &loc);
+    assert!(!renderer.text_log.iter().any(|t| t.text.contains("FPS")));
+    ui.fps_counter.visible = true;
+    ui.render(&mut renderer, &loc);
+    assert!(renderer.text_log.iter().any(|t| t.text == "50 FPS  20.0 ms"));
+}
+
+#[test]
+fn render_options_switch_present_mode_and_persist() {
+    use gero::ui::options::OptionsMenu;
+    use wgpu::PresentMode;
+
+    let mut renderer = Renderer::new_headless(100, 100);
+    assert_eq!(renderer.present_mode(), PresentMode::Fifo);
+    renderer.set_vsync(false);
+    assert_eq!(renderer.present_mode(), PresentMode::Mailbox);
+    renderer.set_vsync(true);
+    assert_eq!(renderer.present_mode(), PresentMode::Fifo);
+
+    let path = std::env::temp_dir().join(format!("gero_render_config_{}.json", std::process::id()));
+    let mut options = OptionsMenu::new();
+    options.render.vsync = false;
+    options.render.fps_cap = Some(30);
+    options.render.show_fps = true;
+    options.save_config(&path).unwrap();
+    assert_eq!(OptionsMenu::load_config(&path).unwrap().render, options.render);
+    let _ = std::fs::remove_file(path);
 }
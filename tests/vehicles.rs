@@ -0,0 +1,218 @@
+use gero::builders::{UnitBuilder, WeaponBuilder};
+use gero::combat::{CombatEncounter, CombatError, CombatEvent, EnvironmentalEffect};
+use gero::command::Command;
+use gero::facing::{Facing, FacingArc};
+use gero::grid::{find_path, GridMap};
+use gero::models::{Faction, Position, UnitType};
+use gero::registry::Side;
+use gero::rng::GameRng;
+use gero::vehicle::{FacingArmor, Mount, Vehicle};
+use gero::weather::Weather;
+
+fn at(x: usize, y: usize) -> Position {
+    Position { x, y }
+}
+
+/// A north-facing Chimera with room for one, a hull lascannon and a
+/// turret, a guardsman beside it and an ork to its north-east, on an open
+/// 8x8 field.
+fn convoy() -> CombatEncounter {
+    let armor = FacingArmor { front: 6, side: 3, rear: 1 };
+    let lascannon = WeaponBuilder::new("lascannon").damage(8).accuracy(1.0).range(8).build();
+    let heavy_bolter = WeaponBuilder::new("heavy_bolter").damage(3).accuracy(1.0).range(8).build();
+    let vehicle = Vehicle::new(armor, 1).hardpoint(Mount::Hull, lascannon).hardpoint(Mount::Turret, heavy_bolter);
+    let chimera = UnitBuilder::new("chimera", UnitType::Chimera, Faction::Imperial).max_health(30).agility(8).vehicle(vehicle).at(2, 5).build();
+    let guard = UnitBuilder::new("guard", UnitType::Guardsman, Faction::Imperial).agility(6).at(2, 6).build();
+    let gun = WeaponBuilder::new("gun").damage(8).accuracy(1.0).range(10).build();
+    let ork = UnitBuilder::new("ork", UnitType::OrkBoy, Faction::Ork).max_health(40).weapon(gun).at(6, 1).build();
+    let mut encounter = CombatEncounter::new(vec![chimera, guard], vec![ork], GridMap::new(8, 8), None);
+    encounter.balance.critical_multiplier = 1;
+    encounter.turn_order.initiative = ["chimera", "guard", "ork"].map(String::from).into();
+    encounter.start_turn();
+    encounter
+}
+
+/// Hand the turn to `id`, so it may act.
+fn turn_of(encounter: &mut CombatEncounter, id: &str) {
+    encounter.turn_order.current_unit_id = Some(id.into());
+}
+
+/// Seed the dice so the next roll is a 1, a sure hit.
+fn sure_hit(encounter: &mut CombatEncounter) {
+    encounter.rng = GameRng::new((0..).find(|&s| GameRng::new(s).roll_d100() == 1).unwrap());
+}
+
+#[test]
+fn facings_split_the_compass_into_arcs() {
+    assert_eq!(Facing::toward(&at(2, 2), &at(2, 0)), Some(Facing::North));
+    assert_eq!(Facing::toward(&at(2, 2), &at(5, 5)), Some(Facing::SouthEast));
+    assert_eq!(Facing::toward(&at(2, 2), &at(2, 2)), None);
+    assert_eq!(Facing::North.turns_to(Facing::West), 2);
+    assert_eq!(Facing::NorthWest.turns_to(Facing::NorthEast), 2);
+    let arcs = [at(2, 0), at(4, 1), at(5, 2), at(3, 5)].map(|from| Facing::North.arc(&at(2, 2), &from));
+    assert_eq!(arcs, [FacingArc::Front, FacingArc::Front, FacingArc::Side, FacingArc::Rear]);
+}
+
+#[test]
+fn vehicles_pay_for_turning_and_end_facing_the_way_they_drove() {
+    let mut encounter = convoy();
+    let map = &encounter.battlefield;
+    let (chimera, guard) = (encounter.unit_by_id("chimera").unwrap(), encounter.unit_by_id("guard").unwrap());
+    // Straight ahead costs only the steps; a quarter turn adds two points.
+    assert_eq!(find_path(chimera, &at(2, 3), map, 0, Weather::Clear).cost, Some(2));
+    assert_eq!(find_path(chimera, &at(4, 5), map, 0, Weather::Clear).cost, Some(4));
+    // Reversing the long way round costs a full about-turn.
+    assert_eq!(find_path(chimera, &at(2, 7), map, 0, Weather::Clear).cost, Some(6));
+    // Infantry turn for free.
+    assert_eq!(find_path(guard, &at(4, 6), map, 0, Weather::Clear).cost, Some(2));
+
+    encounter.execute(Command::Move { unit_id: "chimera".into(), destination: at(4, 5) }).unwrap();
+    assert_eq!(encounter.unit_by_id("chimera").unwrap().facing, Facing::East);
+    assert!(encounter.undo());
+    assert_eq!(encounter.unit_by_id("chimera").unwrap().facing, Facing::North);
+
+    // Turning in place is paid for out of movement like a move.
+    encounter.execute(Command::Turn { unit_id: "chimera".into(), facing: Facing::South }).unwrap();
+    let chimera = encounter.unit_by_id("chimera").unwrap();
+    assert_eq!((chimera.facing, chimera.action_points, chimera.movement_left), (Facing::South, 1, 0));
+}
+
+#[test]
+fn hull_armor_depends_on_the_arc_hit_and_piercing_cuts_through_it() {
+    let mut encounter = convoy();
+    let chimera = encounter.unit_by_id("chimera").unwrap();
+    let gun = WeaponBuilder::new("gun").damage(8).build();
+    let melta = WeaponBuilder::new("melta").damage(8).armor_piercing(0.5).build();
+    assert_eq!(chimera.hull_armor(&at(6, 1), &gun), 6);
+    assert_eq!(chimera.hull_armor(&at(2, 7), &gun), 1);
+    assert_eq!(chimera.hull_armor(&at(6, 1), &melta), 3);
+
+    let shoot = |encounter: &mut CombatEncounter| {
+        sure_hit(encounter);
+        turn_of(encounter, "ork");
+        encounter.unit_by_id_mut("ork").unwrap().action_points = 2;
+        let before = encounter.unit_by_id("chimera").unwrap().health_points;
+        encounter.execute(Command::Attack { attacker_id: "ork".into(), target_id: "chimera".into() }).unwrap();
+        before - encounter.unit_by_id("chimera").unwrap().health_points
+    };
+    let from_front = shoot(&mut encounter);
+    encounter.unit_by_id_mut("chimera").unwrap().facing = Facing::South;
+    let from_rear = shoot(&mut encounter);
+    assert_eq!(from_rear - from_front, 5);
+}
+
+#[test]
+fn passengers_ride_along_out_of_reach_and_get_out_beside_the_vehicle() {
+    let mut encounter = convoy();
+    let board = Command::Embark { unit_id: "guard".into(), vehicle_id: "chimera".into() };
+    turn_of(&mut encounter, "guard");
+    // The dead don't climb aboard.
+    let health = std::mem::replace(&mut encounter.unit_by_id_mut("guard").unwrap().health_points, 0);
+    assert_eq!(encounter.execute(board.clone()), Err(CombatError::UnknownUnit));
+    encounter.unit_by_id_mut("guard").unwrap().health_points = health;
+    encounter.execute(board.clone()).unwrap();
+    let guard = encounter.unit_by_id("guard").unwrap();
+    assert_eq!((guard.embarked_in.as_deref(), guard.grid_position.clone(), guard.action_points), (Some("chimera"), at(2, 5), 1));
+
+    // Passengers can't act or be hit, and take no room the vehicle lacks.
+    let walk = Command::Move { unit_id: "guard".into(), destination: at(0, 0) };
+    assert_eq!(encounter.execute(walk), Err(CombatError::Embarked));
+    let shot = Command::Attack { attacker_id: "ork".into(), target_id: "guard".into() };
+    turn_of(&mut encounter, "ork");
+    assert_eq!(encounter.execute(shot), Err(CombatError::InvalidTarget));
+    let other = UnitBuilder::new("other", UnitType::Guardsman, Faction::Imperial).at(2, 5).build();
+    encounter.units.insert(other, Side::Player);
+    let full = Command::Embark { unit_id: "other".into(), vehicle_id: "chimera".into() };
+    turn_of(&mut encounter, "other");
+    assert_eq!(encounter.execute(full), Err(CombatError::VehicleFull));
+
+    turn_of(&mut encounter, "chimera");
+    encounter.execute(Command::Move { unit_id: "chimera".into(), destination: at(2, 3) }).unwrap();
+    assert_eq!(encounter.unit_by_id("guard").unwrap().grid_position, at(2, 3));
+
+    turn_of(&mut encounter, "guard");
+    let too_far = Command::Disembark { unit_id: "guard".into(), destination: at(0, 3) };
+    assert_eq!(encounter.execute(too_far), Err(CombatError::OutOfRange));
+    // Nor onto the vehicle's own cell or another unit's.
+    let onto = |x, y| Command::Disembark { unit_id: "guard".into(), destination: at(x, y) };
+    assert_eq!(encounter.execute(onto(2, 3)), Err(CombatError::InvalidTarget));
+    encounter.unit_by_id_mut("other").unwrap().grid_position = at(3, 3);
+    assert_eq!(encounter.execute(onto(3, 3)), Err(CombatError::InvalidTarget));
+    encounter.execute(Command::Disembark { unit_id: "guard".into(), destination: at(1, 3) }).unwrap();
+    let guard = encounter.unit_by_id("guard").unwrap();
+    assert_eq!((guard.embarked_in.clone(), guard.grid_position.clone()), (None, at(1, 3)));
+    assert!(encounter.unit_by_id("chimera").unwrap().vehicle.as_ref().unwrap().passengers.is_empty());
+
+    // A wreck turns its passengers out where it stands.
+    encounter.unit_by_id_mut("guard").unwrap().action_points = 2;
+    encounter.unit_by_id_mut("guard").unwrap().grid_position = at(2, 4);
+    encounter.execute(board).unwrap();
+    encounter.unit_by_id_mut("chimera").unwrap().health_points = 1;
+    sure_hit(&mut encounter);
+    turn_of(&mut encounter, "ork");
+    encounter.execute(Command::Attack { attacker_id: "ork".into(), target_id: "chimera".into() }).unwrap();
+    assert!(encounter.unit_by_id("chimera").unwrap().health_points <= 0);
+    assert_eq!(encounter.unit_by_id("guard").unwrap().embarked_in, None);
+}
+
+#[test]
+fn passengers_are_sheltered_from_fire_and_acid_under_the_vehicle() {
+    let mut encounter = convoy();
+    turn_of(&mut encounter, "guard");
+    encounter.execute(Command::Embark { unit_id: "guard".into(), vehicle_id: "chimera".into() }).unwrap();
+    let cells = vec![at(2, 5)];
+    encounter.environmental_effects.push(EnvironmentalEffect::FirePatch { grid_cells: cells.clone(), damage_per_turn: 5 });
+    encounter.environmental_effects.push(EnvironmentalEffect::AcidPool { grid_cells: cells, movement_penalty: 0.5 });
+    let agility = encounter.unit_by_id("guard").unwrap().current_stats.agility;
+    encounter.execute(Command::EndTurn).unwrap();
+    encounter.start_turn();
+    assert_eq!(encounter.turn_order.current_unit_id.as_deref(), Some("guard"));
+    let guard = encounter.unit_by_id("guard").unwrap();
+    assert_eq!((guard.health_points, guard.current_stats.agility), (guard.current_stats.max_health, agility));
+}
+
+#[test]
+fn hardpoints_fire_once_a_turn_within_their_arc() {
+    let mut encounter = convoy();
+    encounter.unit_by_id_mut("chimera").unwrap().action_points = 4;
+    let fire = |hardpoint| Command::FireHardpoint { vehicle_id: "chimera".into(), hardpoint, target_id: "ork".into() };
+    encounter.unit_by_id_mut("chimera").unwrap().facing = Facing::West;
+    assert_eq!(encounter.execute(fire(0)), Err(CombatError::OutsideArc));
+    encounter.execute(fire(1)).unwrap();
+    assert_eq!(encounter.execute(fire(1)), Err(CombatError::HardpointFired));
+    encounter.unit_by_id_mut("chimera").unwrap().facing = Facing::NorthEast;
+    encounter.execute(fire(0)).unwrap();
+    assert_eq!(encounter.execute(fire(2)), Err(CombatError::NoSuchHardpoint));
+
+    // They are ready again on the vehicle's next turn.
+    for _ in 0..3 {
+        encounter.execute(Command::EndTurn).unwrap();
+        encounter.start_turn();
+    }
+    assert_eq!(encounter.turn_order.current_unit_id.as_deref(), Some("chimera"));
+    encounter.execute(fire(1)).unwrap();
+}
+
+#[test]
+fn ai_vehicles_roll_for_each_hardpoint() {
+    let shoota = || WeaponBuilder::new("big_shoota").damage(3).accuracy(0.5).range(8).build();
+    let vehicle = Vehicle::new(FacingArmor { front: 6, side: 3, rear: 1 }, 0).hardpoint(Mount::Turret, shoota()).hardpoint(Mount::Turret, shoota());
+    let trukk = UnitBuilder::new("trukk", UnitType::Trukk, Faction::Ork).max_health(30).vehicle(vehicle).at(2, 2).build();
+    let guard = UnitBuilder::new("guard", UnitType::Guardsman, Faction::Imperial).max_health(40).at(2, 5).build();
+    let mut encounter = CombatEncounter::new(vec![guard], vec![trukk], GridMap::new(8, 8), None);
+    encounter.balance.critical_multiplier = 1;
+    encounter.turn_order.current_unit_id = Some("trukk".into());
+    encounter.unit_by_id_mut("trukk").unwrap().action_points = 4;
+    // A sure hit for the first hardpoint and a sure miss for the second.
+    encounter.rng = GameRng::new(
+        (0..)
+            .find(|&s| {
+                let mut rng = GameRng::new(s);
+                rng.roll_d100() == 1 && rng.roll_d100() == 100
+            })
+            .unwrap(),
+    );
+    encounter.ai_take_action(1);
+    let hits = encounter.events.iter().filter(|e| matches!(e, CombatEvent::UnitAttacked { .. })).count();
+    assert_eq!(hits, 1);
+}
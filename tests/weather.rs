@@ -0,0 +1,90 @@
+use gero::audio::AudioSystem;
+use gero::balance::BalanceConfig;
+use gero::combat::{resolve_attack_with, AttackModifiers, CombatEncounter};
+use gero::damage::DamageType;
+use gero::facing::Facing;
+use gero::frontend::Renderer;
+use gero::grid::{try_move_in, GridMap, TerrainType};
+use gero::models::{Faction, Position, Unit, UnitType, Weapon, WeaponTier};
+use gero::scenario::Scenario;
+use gero::state::GameState;
+use gero::weather::Weather;
+
+fn walker() -> Unit {
+    let mut unit = Unit::new("u", "U", UnitType::Guardsman, Faction::Imperial);
+    unit.base_stats.agility = 4;
+    unit.recalculate_stats();
+    unit
+}
+
+#[test]
+fn weather_raises_step_costs() {
+    // One AP's worth of movement each.
+    let walker = || Unit { action_points: 1, ..walker() };
+    let mut map = GridMap::new(4, 1);
+    map.set_terrain(&Position { x: 1, y: 0 }, TerrainType::Difficult);
+    assert!(try_move_in(&mut walker(), Position { x: 1, y: 0 }, &map, 0, Weather::Clear));
+    assert!(!try_move_in(&mut walker(), Position { x: 1, y: 0 }, &map, 0, Weather::Rain));
+    assert!(try_move_in(&mut walker(), Position { x: 1, y: 0 }, &map, 0, Weather::Night));
+
+    let open = GridMap::new(4, 1);
+    assert!(try_move_in(&mut walker(), Position { x: 2, y: 0 }, &open, 0, Weather::Rain));
+    assert!(!try_move_in(&mut walker(), Position { x: 2, y: 0 }, &open, 0, Weather::DustStorm));
+}
+
+#[test]
+fn weather_penalises_ranged_attacks_and_sight() {
+    let rifle = Weapon {
+        id: "r".into(),
+        name: "R".into(),
+        tier: WeaponTier::Basic,
+        damage: 3,
+        accuracy: 0.6,
+        range: 8,
+        armor_piercing: None,
+        action_point_cost: 1,
+        critical_chance: 0.0,
+        abilities_granted: Vec::new(),
+        damage_type: DamageType::Kinetic,
+        forced_move: None,
+        indirect: None,
+    };
+    let mut shooter = walker();
+    shooter.base_stats.agility = 0;
+    shooter.recalculate_stats();
+    shooter.action_points = 2;
+    let mut target = Unit::new("o", "O", UnitType::OrkBoy, Faction::Ork);
+    target.grid_position = Position { x: 5, y: 0 };
+    target.facing = Facing::West;
+
+    let clear = AttackModifiers::default().in_weather(Weather::Clear, &rifle);
+    let dust = AttackModifiers::default().in_weather(Weather::DustStorm, &rifle);
+    // Hit chance drops from 60 to 40.
+    assert!(resolve_attack_with(&mut shooter, &rifle, &mut target, 50, 0, clear, &BalanceConfig::DEFAULT).unwrap().hit);
+    assert!(!resolve_attack_with(&mut shooter, &rifle, &mut target, 50, 0, dust, &BalanceConfig::DEFAULT).unwrap().hit);
+
+    let mut enc = CombatEncounter::new(vec![shooter], vec![target], GridMap::new(20, 1), None);
+    assert_eq!(enc.visible_cells(0).len(), 7);
+    enc.set_weather(Weather::DustStorm, None);
+    assert_eq!(enc.visible_cells(0).len(), 4);
+}
+
+#[test]
+fn scenario_declares_weather_music_and_tint() {
+    let scenario = Scenario::load("assets/scenarios/night_raid.json").unwrap();
+    assert_eq!(scenario.weather, Weather::Night);
+    let mut audio = AudioSystem::new();
+    let enc = scenario.encounter(vec![walker()], vec![], Some(&mut audio));
+    assert_eq!(audio.current_music.as_deref(), Some("combat_night"));
+    assert_eq!(*enc.battlefield.terrain_at(&Position { x: 4, y: 3 }), TerrainType::Blocked);
+
+    let mut renderer = Renderer::new_headless(640, 480);
+    renderer.load_sprite_from_bytes("tile_normal", vec![vec![0]]);
+    renderer.apply_weather(enc.weather);
+    renderer.render_state(&GameState::from_encounter(&enc));
+    assert!(renderer.draw_log.iter().all(|c| c.tint == Weather::Night.tint()));
+    assert!(!renderer.draw_log.is_empty());
+
+    let bad = r#"{ "name": "x", "width": 2, "height": 2, "terrain": [[{ "x": 2, "y": 0 }, "Blocked"]] }"#;
+    assert!(Scenario::from_json(bad).is_err());
+}
@@ -0,0 +1,114 @@
+use gero::combat::CombatEvent;
+use gero::flow::{GameFlow, ANIMATION_FRAME_DURATION};
+use gero::frontend::wounds::{WoundState, BLOOD_DECAL, CORPSE_SPRITE};
+use gero::frontend::{DrawCall, DrawLayer, Renderer, SIDE_MARKER};
+use gero::grid::GridMap;
+use gero::loot::Loot;
+use gero::models::{AnimationType, Faction, Position, Unit, UnitType};
+use gero::state::GameState;
+use gero::ui::UiManager;
+
+fn renderer() -> Renderer<'static> {
+    let mut renderer = Renderer::new_headless(320, 320);
+    renderer.load_sprite_from_bytes(SIDE_MARKER, vec![vec![0]]);
+    renderer.load_sprite_from_bytes("guard", vec![vec![0]]);
+    renderer.load_sprite_from_bytes("guard:wounded", vec![vec![0]]);
+    renderer
+}
+
+/// A guardsman with 10 health at (1, 1) on a 4x4 field, drawn with the
+/// "guard" sprite.
+fn state(health: i32) -> GameState {
+    let mut guard = Unit::new("g", "G", UnitType::Guardsman, Faction::Imperial);
+    guard.sprite_id = "guard".into();
+    guard.grid_position = Position { x: 1, y: 1 };
+    guard.health_points = health;
+    let mut state = GameState::new(vec![guard]);
+    state.map = GridMap::new(4, 4);
+    state
+}
+
+fn drawn(renderer: &mut Renderer, state: &GameState) -> Vec<(String, DrawLayer)> {
+    renderer.render_state(state);
+    renderer.draw_log.iter().map(|c| (c.sprite_id.clone(), c.layer)).collect()
+}
+
+#[test]
+fn hurt_units_swap_to_their_wounded_sprites() {
+    use WoundState::*;
+    let states = [10, 5, 4, 2, 0].map(|hp| WoundState::of(state(hp).units.by_name("g").unwrap()));
+    assert_eq!(states, [Healthy, Healthy, Wounded, Critical, Dead]);
+
+    let mut renderer = renderer();
+    assert_eq!(drawn(&mut renderer, &state(10))[1].0, "guard");
+    assert_eq!(drawn(&mut renderer, &state(4))[1].0, "guard:wounded");
+    // Without frames of its own a badly wounded unit looks merely wounded.
+    assert_eq!(drawn(&mut renderer, &state(2))[1].0, "guard:wounded");
+    renderer.load_sprite_from_bytes("guard:critical", vec![vec![0]]);
+    assert_eq!(drawn(&mut renderer, &state(2))[1].0, "guard:critical");
+}
+
+#[test]
+fn the_dead_lie_among_the_props_and_play_their_death_once() {
+    let mut renderer = renderer();
+    // Corpses draw under the living, without markers.
+    let mut dead = state(0);
+    assert_eq!(drawn(&mut renderer, &dead), [("guard".to_string(), DrawLayer::Objects)]);
+    renderer.load_sprite_from_bytes(CORPSE_SPRITE, vec![vec![0]]);
+    assert_eq!(drawn(&mut renderer, &dead)[0].0, CORPSE_SPRITE);
+    renderer.load_sprite_from_bytes("guard:corpse", vec![vec![0], vec![1], vec![2]]);
+    assert_eq!(drawn(&mut renderer, &dead)[0].0, "guard:corpse");
+
+    dead.units.by_name_mut("g").unwrap().animation_state.frame_index = 7;
+    let mut flow = GameFlow::new(dead, UiManager::new(100, 100, vec![], vec![]));
+    flow.update(ANIMATION_FRAME_DURATION * 1.5);
+    let animation = &flow.state.units.by_name("g").unwrap().animation_state;
+    assert_eq!((&animation.current_animation, animation.frame_index), (&AnimationType::Death, 1));
+    flow.update(ANIMATION_FRAME_DURATION * 10.0);
+    renderer.render_state(&flow.state);
+    assert_eq!(renderer.draw_log[0].frame_index, 2);
+}
+
+#[test]
+fn blood_pools_where_units_are_hit_and_stays() {
+    let mut renderer = renderer();
+    renderer.load_sprite_from_bytes(BLOOD_DECAL, vec![vec![0], vec![1]]);
+    let mut state = state(10);
+    state.map.drop_loot(&Position { x: 1, y: 1 }, vec![Loot::Requisition(5)]);
+    let hit = CombatEvent::UnitDamaged { unit_id: "g".into(), amount: 3, critical: false };
+    renderer.handle_combat_event(&hit);
+    assert!(renderer.blood_decals.is_empty(), "the unit hasn't been drawn yet");
+
+    renderer.render_state(&state);
+    for _ in 0..3 {
+        renderer.handle_combat_event(&hit);
+    }
+    renderer.update(60.0);
+    renderer.render_state(&state);
+    let blood: Vec<&DrawCall> = renderer.draw_log.iter().filter(|c| c.sprite_id == BLOOD_DECAL).collect();
+    assert_eq!(blood.len(), 1);
+    assert_eq!((blood[0].position, blood[0].frame_index), ((1, 1), 1));
+    // Under the loot lying in the pool.
+    let order: Vec<&str> = renderer.draw_log.iter().filter(|c| c.layer == DrawLayer::Objects).map(|c| c.sprite_id.as_str()).collect();
+    assert_eq!(order.first(), Some(&BLOOD_DECAL));
+
+    renderer.render_state_retained(&state);
+    renderer.clear_blood();
+    renderer.render_state_retained(&state);
+    assert!(renderer.draw_log.iter().all(|c| c.sprite_id != BLOOD_DECAL));
+}
+
+#[test]
+fn retained_frames_redraw_units_that_are_wounded_or_killed() {
+    let mut renderer = renderer();
+    let mut state = state(10);
+    renderer.render_state_retained(&state);
+    for health in [4, 0] {
+        state.units.by_name_mut("g").unwrap().health_points = health;
+        renderer.render_state_retained(&state);
+        assert_eq!(renderer.changed_instances(), None);
+        let mut fresh = self::renderer();
+        fresh.render_state(&state);
+        assert_eq!(renderer.draw_log, fresh.draw_log);
+    }
+}